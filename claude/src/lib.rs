@@ -2,20 +2,50 @@
 //!
 //! This crate provides a focused client for Claude's Messages API with:
 //! - Non-streaming and streaming completions
-//! - Tool use support
+//! - Tool use support, with a [`ToolRouter`] for dispatching calls by name
 //! - Proper SSE parsing for streaming responses
-
+//! - Optional retries with exponential backoff for transient errors
+//! - Prompt caching for large, stable system prompts
+//! - Extended thinking with a token budget
+//! - Cancellation of in-flight requests
+//! - Record-and-replay cassettes for offline tests
+//! - An [`LlmProvider`] trait for swapping in other backends
+
+use base64::Engine;
 use futures::StreamExt;
+use rand::Rng;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::pin::Pin;
+use std::sync::Mutex;
+use std::time::Duration;
 use thiserror::Error;
 use tokio_stream::Stream;
 
-const API_BASE: &str = "https://api.anthropic.com/v1";
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+
+pub use tokio_util::sync::CancellationToken;
+
+const API_BASE: &str = "https://api.anthropic.com";
 const API_VERSION: &str = "2023-06-01";
 const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
 
+/// Smallest thinking budget the API accepts.
+const MIN_THINKING_BUDGET: usize = 1024;
+
+/// Image media types the API accepts.
+const SUPPORTED_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Content block types this client models; others become [`ContentBlock::Unknown`].
+const KNOWN_BLOCK_TYPES: &[&str] = &["text", "tool_use", "thinking"];
+
+/// HTTP statuses worth retrying: rate limits, server errors, and overload.
+const RETRYABLE_STATUSES: &[u16] = &[429, 500, 502, 503, 529];
+
 /// Errors that can occur when using the Claude client.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -33,6 +63,15 @@ pub enum Error {
 
     #[error("Invalid configuration: {0}")]
     Config(String),
+
+    #[error("Image error: {0}")]
+    Image(String),
+
+    #[error("Request cancelled")]
+    Cancelled,
+
+    #[error("Cassette error: {0}")]
+    Cassette(String),
 }
 
 /// Claude API client.
@@ -41,6 +80,9 @@ pub struct Claude {
     client: reqwest::Client,
     api_key: String,
     model: String,
+    api_base: String,
+    max_retries: u32,
+    base_delay: Duration,
 }
 
 impl Claude {
@@ -54,6 +96,9 @@ impl Claude {
                 .expect("Failed to build HTTP client"),
             api_key: api_key.into(),
             model: DEFAULT_MODEL.to_string(),
+            api_base: API_BASE.to_string(),
+            max_retries: 0,
+            base_delay: Duration::from_secs(1),
         }
     }
 
@@ -69,62 +114,54 @@ impl Claude {
         self
     }
 
+    /// Send requests to a different host, such as a proxy or compatible gateway.
+    ///
+    /// The URL is the part before `/v1/messages`, e.g. `https://gateway.example.com`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.api_base = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Retry transient failures (429, 500, 502, 503, 529) up to `max_retries` times.
+    ///
+    /// Waits for the server's `retry-after` header when present, and otherwise
+    /// for a jittered exponential backoff starting at `base_delay`.
+    pub fn with_retry(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.base_delay = base_delay;
+        self
+    }
+
     /// Send a completion request and return the full response.
+    ///
+    /// Returns [`Error::Cancelled`] if the request's cancellation token fires first.
     pub async fn complete(&self, request: Request) -> Result<Response, Error> {
+        request.validate()?;
         let api_request = self.build_api_request(&request, false);
-        let headers = self.build_headers()?;
-
-        let response = self
-            .client
-            .post(format!("{API_BASE}/messages"))
-            .headers(headers)
-            .json(&api_request)
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::Api {
-                status,
-                message: body,
-            });
-        }
-
-        let api_response: ApiResponse = response
-            .json()
-            .await
-            .map_err(|e| Error::Parse(e.to_string()))?;
 
-        Ok(self.parse_response(api_response))
+        cancellable(request.cancel.as_ref(), async {
+            let response = self.send("messages", &api_request).await?;
+            let api_response: ApiResponse = response
+                .json()
+                .await
+                .map_err(|e| Error::Parse(e.to_string()))?;
+            Ok(self.parse_response(api_response))
+        })
+        .await
     }
 
     /// Send a completion request and stream the response.
+    ///
+    /// If the request's cancellation token fires, the connection is dropped
+    /// and the stream ends with [`Error::Cancelled`].
     pub async fn stream(
         &self,
         request: Request,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<StreamEvent, Error>> + Send>>, Error> {
+        request.validate()?;
         let api_request = self.build_api_request(&request, true);
-        let headers = self.build_headers()?;
-
-        let response = self
-            .client
-            .post(format!("{API_BASE}/messages"))
-            .headers(headers)
-            .json(&api_request)
-            .send()
-            .await
-            .map_err(|e| Error::Network(e.to_string()))?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(Error::Api {
-                status,
-                message: body,
-            });
-        }
+        let response =
+            cancellable(request.cancel.as_ref(), self.send("messages", &api_request)).await?;
 
         // Use scan to maintain a buffer for incomplete SSE events across chunks
         let stream = response
@@ -140,9 +177,26 @@ impl Claude {
                 // Return Some to continue, the events vector
                 futures::future::ready(Some(events))
             })
-            .flat_map(futures::stream::iter);
+            .flat_map(futures::stream::iter)
+            // Nothing useful follows an error frame, so stop polling after one
+            .scan(false, |errored, event| {
+                if *errored {
+                    return futures::future::ready(None);
+                }
+                *errored = matches!(event, Ok(StreamEvent::Error { .. }));
+                futures::future::ready(Some(event))
+            });
 
-        Ok(Box::pin(stream))
+        match request.cancel {
+            Some(token) => {
+                let cancelled = token.clone().cancelled_owned();
+                let ending = futures::stream::once(async move { token.is_cancelled() }).filter_map(
+                    |cancelled| futures::future::ready(cancelled.then_some(Err(Error::Cancelled))),
+                );
+                Ok(Box::pin(stream.take_until(cancelled).chain(ending)))
+            }
+            None => Ok(Box::pin(stream)),
+        }
     }
 
     /// Run a tool use loop until completion.
@@ -213,6 +267,81 @@ impl Claude {
         }
     }
 
+    /// Run a tool use loop, dispatching each call to the router's handler
+    /// for that tool name.
+    ///
+    /// Calls to tools the router doesn't know come back to Claude as errors.
+    pub async fn complete_with_router(
+        &self,
+        request: Request,
+        router: &ToolRouter,
+    ) -> Result<Response, Error> {
+        self.complete_with_tools(request, |tool_use| router.execute(tool_use))
+            .await
+    }
+
+    /// Count the input tokens a request would use, without sending it.
+    ///
+    /// Uses the same model, system prompt, messages, and tools as `complete`.
+    pub async fn count_tokens(&self, request: &Request) -> Result<usize, Error> {
+        let api_request = self.build_api_request(request, false);
+        let count_request = ApiCountTokensRequest {
+            model: &api_request.model,
+            system: api_request.system.as_ref(),
+            messages: &api_request.messages,
+            tools: api_request.tools.as_ref(),
+            tool_choice: api_request.tool_choice.as_ref(),
+            thinking: api_request.thinking.as_ref(),
+        };
+        let response = self.send("messages/count_tokens", &count_request).await?;
+
+        let count: ApiTokenCount = response
+            .json()
+            .await
+            .map_err(|e| Error::Parse(e.to_string()))?;
+        Ok(count.input_tokens)
+    }
+
+    /// POST a request to a Messages API endpoint, retrying transient failures.
+    async fn send(
+        &self,
+        endpoint: &str,
+        body: &impl Serialize,
+    ) -> Result<reqwest::Response, Error> {
+        let headers = self.build_headers()?;
+        let mut attempt = 0;
+
+        loop {
+            let response = self
+                .client
+                .post(format!("{}/v1/{endpoint}", self.api_base))
+                .headers(headers.clone())
+                .json(body)
+                .send()
+                .await
+                .map_err(|e| Error::Network(e.to_string()))?;
+
+            if response.status().is_success() {
+                return Ok(response);
+            }
+
+            let status = response.status().as_u16();
+            if attempt < self.max_retries && RETRYABLE_STATUSES.contains(&status) {
+                let delay = retry_after(response.headers())
+                    .unwrap_or_else(|| backoff_delay(self.base_delay, attempt));
+                attempt += 1;
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::Api {
+                status,
+                message: body,
+            });
+        }
+    }
+
     fn build_headers(&self) -> Result<HeaderMap, Error> {
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
@@ -252,9 +381,23 @@ impl Claude {
         ApiRequest {
             model: request.model.clone().unwrap_or_else(|| self.model.clone()),
             max_tokens: request.max_tokens,
-            system: request.system.clone(),
+            system: request.system.clone().map(|text| {
+                if request.cache_system {
+                    ApiSystem::Blocks(vec![ApiSystemBlock {
+                        r#type: "text".to_string(),
+                        text,
+                        cache_control: Some(ApiCacheControl {
+                            r#type: "ephemeral".to_string(),
+                        }),
+                    }])
+                } else {
+                    ApiSystem::Text(text)
+                }
+            }),
             messages,
             temperature: request.temperature,
+            top_p: request.top_p,
+            top_k: request.top_k,
             tools,
             tool_choice: request.tool_choice.as_ref().map(|tc| match tc {
                 ToolChoice::Auto => ApiToolChoice {
@@ -270,6 +413,11 @@ impl Claude {
                     name: Some(name.clone()),
                 },
             }),
+            stop_sequences: request.stop_sequences.clone(),
+            thinking: request.thinking_budget.map(|budget_tokens| ApiThinking {
+                r#type: "enabled".to_string(),
+                budget_tokens,
+            }),
             stream,
         }
     }
@@ -283,27 +431,24 @@ impl Claude {
                 ApiContent::ToolUse { id, name, input } => {
                     ContentBlock::ToolUse { id, name, input }
                 }
-                ApiContent::Thinking { thinking } => ContentBlock::Thinking { thinking },
+                ApiContent::Thinking {
+                    thinking,
+                    signature,
+                } => ContentBlock::Thinking {
+                    thinking,
+                    signature,
+                },
+                ApiContent::Unknown(raw) => unknown_block(raw, Vec::new()),
             })
             .collect();
 
-        let stop_reason = match api_response.stop_reason.as_str() {
-            "end_turn" => StopReason::EndTurn,
-            "max_tokens" => StopReason::MaxTokens,
-            "stop_sequence" => StopReason::StopSequence,
-            "tool_use" => StopReason::ToolUse,
-            _ => StopReason::EndTurn,
-        };
-
         Response {
             id: api_response.id,
             model: api_response.model,
             content,
-            stop_reason,
-            usage: Usage {
-                input_tokens: api_response.usage.input_tokens,
-                output_tokens: api_response.usage.output_tokens,
-            },
+            stop_reason: parse_stop_reason(&api_response.stop_reason),
+            stop_sequence: api_response.stop_sequence,
+            usage: api_response.usage.into(),
         }
     }
 }
@@ -320,8 +465,18 @@ pub struct Request {
     pub system: Option<String>,
     pub messages: Vec<Message>,
     pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub top_k: Option<u32>,
     pub tools: Option<Vec<Tool>>,
     pub tool_choice: Option<ToolChoice>,
+    /// Mark the system prompt as cacheable (`cache_control: ephemeral`).
+    pub cache_system: bool,
+    /// Token budget for extended thinking, if enabled.
+    pub thinking_budget: Option<usize>,
+    /// Strings that stop generation when produced.
+    pub stop_sequences: Option<Vec<String>>,
+    /// Aborts the request when cancelled.
+    pub cancel: Option<CancellationToken>,
 }
 
 impl Request {
@@ -333,8 +488,14 @@ impl Request {
             system: None,
             messages,
             temperature: None,
+            top_p: None,
+            top_k: None,
             tools: None,
             tool_choice: None,
+            cache_system: false,
+            thinking_budget: None,
+            stop_sequences: None,
+            cancel: None,
         }
     }
 
@@ -358,6 +519,18 @@ impl Request {
         self
     }
 
+    /// Nucleus sampling threshold, clamped to `0.0..=1.0`.
+    pub fn with_top_p(mut self, top_p: f32) -> Self {
+        self.top_p = Some(top_p.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Only sample from the `top_k` most likely tokens.
+    pub fn with_top_k(mut self, top_k: u32) -> Self {
+        self.top_k = Some(top_k);
+        self
+    }
+
     pub fn with_tools(mut self, tools: Vec<Tool>) -> Self {
         self.tools = Some(tools);
         self
@@ -367,6 +540,54 @@ impl Request {
         self.tool_choice = Some(tool_choice);
         self
     }
+
+    /// Abort the request (or stop its stream) when `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Stop generating when any of these strings is produced.
+    pub fn with_stop_sequences(mut self, stop_sequences: Vec<String>) -> Self {
+        self.stop_sequences = Some(stop_sequences);
+        self
+    }
+
+    /// Cache the system prompt (and the tools before it) across requests.
+    ///
+    /// Cached prefixes are billed at a fraction of the input token price on
+    /// later requests that resend them unchanged.
+    pub fn with_cache_control(mut self) -> Self {
+        self.cache_system = true;
+        self
+    }
+
+    /// Let Claude think before answering, using up to `budget_tokens` tokens.
+    ///
+    /// The budget counts toward `max_tokens`, so it must be smaller than it
+    /// (and at least 1024).
+    pub fn with_thinking(mut self, budget_tokens: usize) -> Self {
+        self.thinking_budget = Some(budget_tokens);
+        self
+    }
+
+    /// Check settings the API would reject.
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(budget) = self.thinking_budget {
+            if budget < MIN_THINKING_BUDGET {
+                return Err(Error::Config(format!(
+                    "Thinking budget must be at least {MIN_THINKING_BUDGET} tokens, got {budget}"
+                )));
+            }
+            if budget >= self.max_tokens {
+                return Err(Error::Config(format!(
+                    "Thinking budget ({budget}) must be less than max_tokens ({})",
+                    self.max_tokens
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// A message in the conversation.
@@ -423,10 +644,56 @@ pub enum ContentBlock {
     },
     Thinking {
         thinking: String,
+        /// Signature the API needs to accept the block back in later turns.
+        signature: String,
+    },
+    /// A block type this client doesn't model (such as `redacted_thinking`),
+    /// kept exactly as the API sent it so it can be passed back.
+    Unknown {
+        type_name: String,
+        raw: serde_json::Value,
+        /// Unmodeled deltas the block received while streaming.
+        deltas: Vec<serde_json::Value>,
     },
 }
 
 impl ContentBlock {
+    /// Create an image block from raw bytes of the given media type.
+    ///
+    /// Supported types are PNG, JPEG, GIF, and WebP.
+    pub fn image_from_bytes(bytes: &[u8], media_type: impl Into<String>) -> Result<Self, Error> {
+        let media_type = media_type.into();
+        if !SUPPORTED_IMAGE_TYPES.contains(&media_type.as_str()) {
+            return Err(Error::Image(format!(
+                "Unsupported image type '{media_type}' (expected PNG, JPEG, GIF, or WebP)"
+            )));
+        }
+        Ok(ContentBlock::Image {
+            media_type,
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        })
+    }
+
+    /// Create an image block from a file, detecting its type from the
+    /// file's contents or, failing that, its extension.
+    pub fn image_from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)
+            .map_err(|e| Error::Image(format!("Failed to read {}: {e}", path.display())))?;
+        let media_type = sniff_image_type(&bytes)
+            .or_else(|| {
+                let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+                image_type_for_extension(&extension)
+            })
+            .ok_or_else(|| {
+                Error::Image(format!(
+                    "Unsupported image file {} (expected PNG, JPEG, GIF, or WebP)",
+                    path.display()
+                ))
+            })?;
+        Self::image_from_bytes(&bytes, media_type)
+    }
+
     /// Extract text from a Text content block.
     pub fn as_text(&self) -> Option<&str> {
         if let ContentBlock::Text { text } = self {
@@ -460,6 +727,8 @@ pub struct Response {
     pub model: String,
     pub content: Vec<ContentBlock>,
     pub stop_reason: StopReason,
+    /// The stop sequence that ended generation, if any.
+    pub stop_sequence: Option<String>,
     pub usage: Usage,
 }
 
@@ -475,19 +744,44 @@ impl Response {
 }
 
 /// Why the model stopped generating.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StopReason {
     EndTurn,
     MaxTokens,
     StopSequence,
     ToolUse,
+    /// A stop reason this client doesn't know, as the API named it.
+    Other(String),
 }
 
 /// Token usage information.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Usage {
     pub input_tokens: usize,
     pub output_tokens: usize,
+    /// Input tokens read from the prompt cache.
+    pub cache_read_tokens: usize,
+    /// Input tokens written to the prompt cache.
+    pub cache_write_tokens: usize,
+}
+
+impl Usage {
+    /// Fold in the cumulative usage a `message_delta` reports.
+    ///
+    /// The output count is replaced; input and cache counts are only replaced
+    /// when the delta reports them, since most deltas carry output tokens alone.
+    pub fn update(&mut self, delta: &Usage) {
+        self.output_tokens = delta.output_tokens;
+        if delta.input_tokens > 0 {
+            self.input_tokens = delta.input_tokens;
+        }
+        if delta.cache_read_tokens > 0 {
+            self.cache_read_tokens = delta.cache_read_tokens;
+        }
+        if delta.cache_write_tokens > 0 {
+            self.cache_write_tokens = delta.cache_write_tokens;
+        }
+    }
 }
 
 /// A tool use request from Claude.
@@ -521,6 +815,72 @@ impl ToolResult {
     }
 }
 
+/// Something that can carry out a tool call.
+///
+/// Closures taking a [`ToolUse`] and returning a future of [`ToolResult`]
+/// implement this, so they can be registered with a [`ToolRouter`] directly.
+pub trait ToolExecutor: Send + Sync {
+    /// Execute the tool call and report its result.
+    fn execute(&self, tool_use: ToolUse) -> BoxFuture<'_, ToolResult>;
+}
+
+impl<F, Fut> ToolExecutor for F
+where
+    F: Fn(ToolUse) -> Fut + Send + Sync,
+    Fut: Future<Output = ToolResult> + Send + 'static,
+{
+    fn execute(&self, tool_use: ToolUse) -> BoxFuture<'_, ToolResult> {
+        Box::pin(self(tool_use))
+    }
+}
+
+/// Dispatches tool calls to handlers registered by tool name.
+///
+/// Pass it to [`Claude::complete_with_router`] to run a tool use loop.
+#[derive(Default)]
+pub struct ToolRouter {
+    handlers: BTreeMap<String, Box<dyn ToolExecutor>>,
+}
+
+impl ToolRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route calls to the tool `name` to `handler`, replacing any earlier one.
+    pub fn with_tool(
+        mut self,
+        name: impl Into<String>,
+        handler: impl ToolExecutor + 'static,
+    ) -> Self {
+        self.register(name, handler);
+        self
+    }
+
+    /// Route calls to the tool `name` to `handler`, replacing any earlier one.
+    pub fn register(&mut self, name: impl Into<String>, handler: impl ToolExecutor + 'static) {
+        self.handlers.insert(name.into(), Box::new(handler));
+    }
+
+    /// Whether a handler is registered for the tool `name`.
+    pub fn handles(&self, name: &str) -> bool {
+        self.handlers.contains_key(name)
+    }
+}
+
+impl ToolExecutor for ToolRouter {
+    fn execute(&self, tool_use: ToolUse) -> BoxFuture<'_, ToolResult> {
+        match self.handlers.get(&tool_use.name) {
+            Some(handler) => handler.execute(tool_use),
+            None => {
+                Box::pin(
+                    async move { ToolResult::error(format!("Unknown tool: {}", tool_use.name)) },
+                )
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Streaming types
 // ============================================================================
@@ -528,7 +888,12 @@ impl ToolResult {
 /// Events from a streaming response.
 #[derive(Debug, Clone)]
 pub enum StreamEvent {
-    MessageStart { id: String, model: String },
+    MessageStart {
+        id: String,
+        model: String,
+        /// Usage so far: the prompt's input and cache tokens.
+        usage: Option<Usage>,
+    },
     ContentBlockStart {
         index: usize,
         content_type: String,
@@ -537,104 +902,683 @@ pub enum StreamEvent {
         /// Tool name (only present for tool_use blocks)
         tool_name: Option<String>,
     },
-    TextDelta { index: usize, text: String },
-    InputJsonDelta { index: usize, partial_json: String },
-    ContentBlockStop { index: usize },
-    MessageDelta { stop_reason: Option<StopReason> },
+    TextDelta {
+        index: usize,
+        text: String,
+    },
+    ThinkingDelta {
+        index: usize,
+        thinking: String,
+    },
+    SignatureDelta {
+        index: usize,
+        signature: String,
+    },
+    InputJsonDelta {
+        index: usize,
+        partial_json: String,
+    },
+    /// Start of a block type this client doesn't model, as the API sent it.
+    UnknownBlockStart {
+        index: usize,
+        content_block: serde_json::Value,
+    },
+    /// A delta this client doesn't model, as the API sent it.
+    UnknownDelta {
+        index: usize,
+        delta: serde_json::Value,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        stop_reason: Option<StopReason>,
+        /// The custom stop sequence that ended the message, if one did.
+        stop_sequence: Option<String>,
+        /// Cumulative usage for the message, sent with the final delta.
+        usage: Option<Usage>,
+    },
     MessageStop,
     Ping,
-    Error { message: String },
+    /// An error the API reported mid-stream; the stream ends after it.
+    Error {
+        /// The API's error type, such as `overloaded_error`.
+        error_type: String,
+        message: String,
+    },
 }
 
-// ============================================================================
-// Internal API types
-// ============================================================================
-
-#[derive(Debug, Serialize)]
-struct ApiRequest {
+/// Folds streaming events back into complete content blocks.
+///
+/// Feed every [`StreamEvent`] to [`push`](Self::push); tool arguments arrive
+/// as JSON fragments and are parsed once their block stops. After the stream
+/// ends, [`into_response`](Self::into_response) gives the same [`Response`]
+/// a non-streaming call would have returned.
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    id: String,
     model: String,
-    max_tokens: usize,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
-    messages: Vec<ApiMessage>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    temperature: Option<f32>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    tools: Option<Vec<ApiTool>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    tool_choice: Option<ApiToolChoice>,
-    stream: bool,
-}
-
-#[derive(Debug, Serialize)]
-struct ApiMessage {
-    role: String,
-    content: Vec<ApiContentBlock>,
+    blocks: BTreeMap<usize, PartialBlock>,
+    stop_reason: Option<StopReason>,
+    stop_sequence: Option<String>,
+    usage: Usage,
+    finished: bool,
 }
 
-#[derive(Debug, Serialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-enum ApiContentBlock {
-    Text {
-        text: String,
-    },
-    Image {
-        source: ApiImageSource,
+/// A content block still being streamed, or finished.
+#[derive(Debug)]
+enum PartialBlock {
+    Text(String),
+    Thinking {
+        thinking: String,
+        signature: String,
     },
     ToolUse {
         id: String,
         name: String,
-        input: serde_json::Value,
+        json: String,
     },
-    ToolResult {
-        tool_use_id: String,
-        content: String,
-        is_error: bool,
+    /// An unmodeled block, with any unmodeled deltas it received.
+    Unknown {
+        raw: serde_json::Value,
+        deltas: Vec<serde_json::Value>,
     },
+    Done(ContentBlock),
 }
 
-impl From<&ContentBlock> for ApiContentBlock {
-    fn from(block: &ContentBlock) -> Self {
-        match block {
-            ContentBlock::Text { text } => ApiContentBlock::Text { text: text.clone() },
-            ContentBlock::Image { data, media_type } => ApiContentBlock::Image {
-                source: ApiImageSource {
-                    r#type: "base64".to_string(),
-                    media_type: media_type.clone(),
-                    data: data.clone(),
-                },
-            },
-            ContentBlock::ToolUse { id, name, input } => ApiContentBlock::ToolUse {
-                id: id.clone(),
-                name: name.clone(),
-                input: input.clone(),
-            },
-            ContentBlock::ToolResult {
-                tool_use_id,
-                content,
-                is_error,
-            } => ApiContentBlock::ToolResult {
-                tool_use_id: tool_use_id.clone(),
-                content: content.clone(),
-                is_error: *is_error,
-            },
-            ContentBlock::Thinking { thinking } => ApiContentBlock::Text {
-                text: thinking.clone(),
-            },
-        }
+impl StreamAccumulator {
+    pub fn new() -> Self {
+        Self::default()
     }
-}
-
-#[derive(Debug, Serialize)]
-struct ApiImageSource {
-    r#type: String,
-    media_type: String,
-    data: String,
-}
 
-#[derive(Debug, Serialize)]
-struct ApiTool {
-    name: String,
+    /// Apply one event. Returns the block it finished, if any.
+    pub fn push(&mut self, event: &StreamEvent) -> Result<Option<ContentBlock>, Error> {
+        match event {
+            StreamEvent::MessageStart { id, model, usage } => {
+                self.id = id.clone();
+                self.model = model.clone();
+                self.usage = usage.clone().unwrap_or_default();
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_type,
+                tool_use_id,
+                tool_name,
+            } => {
+                let block = match content_type.as_str() {
+                    "tool_use" => PartialBlock::ToolUse {
+                        id: tool_use_id.clone().unwrap_or_default(),
+                        name: tool_name.clone().unwrap_or_default(),
+                        json: String::new(),
+                    },
+                    "thinking" => PartialBlock::Thinking {
+                        thinking: String::new(),
+                        signature: String::new(),
+                    },
+                    _ => PartialBlock::Text(String::new()),
+                };
+                self.blocks.insert(*index, block);
+            }
+            StreamEvent::TextDelta { index, text } => {
+                if let PartialBlock::Text(buffer) = self.block(*index) {
+                    buffer.push_str(text);
+                }
+            }
+            StreamEvent::ThinkingDelta { index, thinking } => {
+                if let PartialBlock::Thinking {
+                    thinking: buffer, ..
+                } = self.block(*index)
+                {
+                    buffer.push_str(thinking);
+                }
+            }
+            StreamEvent::SignatureDelta { index, signature } => {
+                if let PartialBlock::Thinking {
+                    signature: buffer, ..
+                } = self.block(*index)
+                {
+                    buffer.push_str(signature);
+                }
+            }
+            StreamEvent::InputJsonDelta {
+                index,
+                partial_json,
+            } => {
+                if let PartialBlock::ToolUse { json, .. } = self.block(*index) {
+                    json.push_str(partial_json);
+                }
+            }
+            StreamEvent::UnknownBlockStart {
+                index,
+                content_block,
+            } => {
+                let block = PartialBlock::Unknown {
+                    raw: content_block.clone(),
+                    deltas: Vec::new(),
+                };
+                self.blocks.insert(*index, block);
+            }
+            StreamEvent::UnknownDelta { index, delta } => match self.block(*index) {
+                PartialBlock::Unknown { deltas, .. } => deltas.push(delta.clone()),
+                _ => log::debug!("Ignoring unknown delta for content block {index}: {delta}"),
+            },
+            StreamEvent::ContentBlockStop { index } => {
+                let block = match self.blocks.remove(index) {
+                    Some(partial) => Self::finish(partial)?,
+                    None => return Ok(None),
+                };
+                self.blocks
+                    .insert(*index, PartialBlock::Done(block.clone()));
+                return Ok(Some(block));
+            }
+            StreamEvent::MessageDelta {
+                stop_reason,
+                stop_sequence,
+                usage,
+            } => {
+                if stop_reason.is_some() {
+                    self.stop_reason = stop_reason.clone();
+                }
+                if stop_sequence.is_some() {
+                    self.stop_sequence = stop_sequence.clone();
+                }
+                if let Some(usage) = usage {
+                    self.usage.update(usage);
+                }
+            }
+            StreamEvent::MessageStop => self.finished = true,
+            StreamEvent::Ping => {}
+            StreamEvent::Error {
+                error_type,
+                message,
+            } => {
+                return Err(Error::Api {
+                    status: error_status(error_type),
+                    message: message.clone(),
+                })
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether the `message_stop` event has been seen.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Assemble the response from every block received so far.
+    pub fn into_response(self) -> Result<Response, Error> {
+        let content = self
+            .blocks
+            .into_values()
+            .map(Self::finish)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Response {
+            id: self.id,
+            model: self.model,
+            content,
+            stop_reason: self.stop_reason.unwrap_or(StopReason::EndTurn),
+            stop_sequence: self.stop_sequence,
+            usage: self.usage,
+        })
+    }
+
+    /// The block at `index`, starting a text block if none was announced.
+    fn block(&mut self, index: usize) -> &mut PartialBlock {
+        self.blocks
+            .entry(index)
+            .or_insert_with(|| PartialBlock::Text(String::new()))
+    }
+
+    fn finish(partial: PartialBlock) -> Result<ContentBlock, Error> {
+        Ok(match partial {
+            PartialBlock::Text(text) => ContentBlock::Text { text },
+            PartialBlock::Thinking {
+                thinking,
+                signature,
+            } => ContentBlock::Thinking {
+                thinking,
+                signature,
+            },
+            PartialBlock::ToolUse { id, name, json } => {
+                // Tools without arguments stream no JSON at all
+                let input = if json.trim().is_empty() {
+                    serde_json::json!({})
+                } else {
+                    serde_json::from_str(&json)
+                        .map_err(|e| Error::Parse(format!("Invalid JSON for tool '{name}': {e}")))?
+                };
+                ContentBlock::ToolUse { id, name, input }
+            }
+            PartialBlock::Unknown { raw, deltas } => unknown_block(raw, deltas),
+            PartialBlock::Done(block) => block,
+        })
+    }
+}
+
+// ============================================================================
+// Record and replay
+// ============================================================================
+
+/// Whether a [`RecordingClient`] talks to the API or plays back a cassette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send requests to the API and save each exchange to the cassette.
+    Record,
+    /// Answer requests from the cassette without touching the network.
+    Replay,
+}
+
+/// A [`Claude`] wrapper that records exchanges to a JSON cassette file, or
+/// replays them, so tests can run a real conversation offline.
+///
+/// Requests are matched on their API body (model, system prompt, messages,
+/// tools and settings) with the `stream` flag ignored. Identical requests
+/// replay in the order they were recorded.
+pub struct RecordingClient {
+    client: Claude,
+    mode: CassetteMode,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cassette {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Interaction {
+    request: serde_json::Value,
+    response: serde_json::Value,
+    /// Whether replay has already used this interaction.
+    #[serde(skip)]
+    played: bool,
+}
+
+impl RecordingClient {
+    /// Record every exchange into a new cassette at `path`.
+    ///
+    /// Any existing cassette there is overwritten on the first request.
+    pub fn record(client: Claude, path: impl Into<PathBuf>) -> Self {
+        Self {
+            client,
+            mode: CassetteMode::Record,
+            path: path.into(),
+            cassette: Mutex::new(Cassette::default()),
+        }
+    }
+
+    /// Replay the cassette at `path`.
+    pub fn replay(client: Claude, path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| Error::Cassette(format!("{}: {e}", path.display())))?;
+        let cassette =
+            serde_json::from_str(&content).map_err(|e| Error::Cassette(e.to_string()))?;
+        Ok(Self {
+            client,
+            mode: CassetteMode::Replay,
+            path,
+            cassette: Mutex::new(cassette),
+        })
+    }
+
+    /// Whether this client is recording or replaying.
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Number of exchanges in the cassette.
+    pub fn interaction_count(&self) -> usize {
+        self.lock().interactions.len()
+    }
+
+    /// Send a completion request, or answer it from the cassette.
+    ///
+    /// In replay mode a request with no recorded match is an [`Error::Cassette`].
+    pub async fn complete(&self, request: Request) -> Result<Response, Error> {
+        request.validate()?;
+        let api_request = self.client.build_api_request(&request, false);
+        let key = cassette_key(&api_request)?;
+
+        let raw = match self.mode {
+            CassetteMode::Replay => self.lookup(&key)?,
+            CassetteMode::Record => {
+                let raw: serde_json::Value = cancellable(request.cancel.as_ref(), async {
+                    let response = self.client.send("messages", &api_request).await?;
+                    response
+                        .json()
+                        .await
+                        .map_err(|e| Error::Parse(e.to_string()))
+                })
+                .await?;
+                self.store(key, raw.clone())?;
+                raw
+            }
+        };
+
+        let api_response: ApiResponse =
+            serde_json::from_value(raw).map_err(|e| Error::Parse(e.to_string()))?;
+        Ok(self.client.parse_response(api_response))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cassette> {
+        self.cassette.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Take the first unplayed response recorded for `key`.
+    fn lookup(&self, key: &serde_json::Value) -> Result<serde_json::Value, Error> {
+        let mut cassette = self.lock();
+        let interaction = cassette
+            .interactions
+            .iter_mut()
+            .find(|i| !i.played && i.request == *key)
+            .ok_or_else(|| {
+                Error::Cassette(format!(
+                    "no recorded response for request in {}",
+                    self.path.display()
+                ))
+            })?;
+        interaction.played = true;
+        Ok(interaction.response.clone())
+    }
+
+    /// Append an exchange and rewrite the cassette file.
+    fn store(&self, request: serde_json::Value, response: serde_json::Value) -> Result<(), Error> {
+        let mut cassette = self.lock();
+        cassette.interactions.push(Interaction {
+            request,
+            response,
+            played: false,
+        });
+        let content =
+            serde_json::to_string_pretty(&*cassette).map_err(|e| Error::Cassette(e.to_string()))?;
+        std::fs::write(&self.path, content)
+            .map_err(|e| Error::Cassette(format!("{}: {e}", self.path.display())))
+    }
+}
+
+/// The part of a request that identifies it in a cassette.
+fn cassette_key(api_request: &ApiRequest) -> Result<serde_json::Value, Error> {
+    let mut key = serde_json::to_value(api_request).map_err(|e| Error::Parse(e.to_string()))?;
+    if let Some(fields) = key.as_object_mut() {
+        fields.remove("stream");
+    }
+    Ok(key)
+}
+
+// ============================================================================
+// Providers
+// ============================================================================
+
+/// A boxed, sendable future, as returned by [`LlmProvider`] methods.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A boxed stream of events, as returned by [`Claude::stream`].
+pub type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent, Error>> + Send>>;
+
+/// Anything that can answer a [`Request`] the way the Messages API would.
+///
+/// [`Claude`] talks to the real API; tests and local models can implement
+/// this to stand in for it. Only [`complete`](Self::complete) is required:
+/// the default [`stream`](Self::stream) replays the finished response as
+/// events, one delta per content block.
+pub trait LlmProvider: Send + Sync {
+    /// Send a request and wait for the whole response.
+    fn complete(&self, request: Request) -> BoxFuture<'_, Result<Response, Error>>;
+
+    /// Send a request and stream the response as it is generated.
+    fn stream(&self, request: Request) -> BoxFuture<'_, Result<EventStream, Error>> {
+        Box::pin(async move {
+            let response = self.complete(request).await?;
+            let events = response_events(response).into_iter().map(Ok);
+            Ok(Box::pin(futures::stream::iter(events)) as EventStream)
+        })
+    }
+}
+
+impl LlmProvider for Claude {
+    fn complete(&self, request: Request) -> BoxFuture<'_, Result<Response, Error>> {
+        Box::pin(Claude::complete(self, request))
+    }
+
+    fn stream(&self, request: Request) -> BoxFuture<'_, Result<EventStream, Error>> {
+        Box::pin(Claude::stream(self, request))
+    }
+}
+
+impl LlmProvider for RecordingClient {
+    fn complete(&self, request: Request) -> BoxFuture<'_, Result<Response, Error>> {
+        Box::pin(RecordingClient::complete(self, request))
+    }
+}
+
+/// The events a stream would have sent for a finished response.
+fn response_events(response: Response) -> Vec<StreamEvent> {
+    let mut events = vec![StreamEvent::MessageStart {
+        id: response.id,
+        model: response.model,
+        usage: None,
+    }];
+    for (index, block) in response.content.into_iter().enumerate() {
+        let (content_type, tool_use_id, tool_name, deltas) = match block {
+            ContentBlock::Text { text } => (
+                "text",
+                None,
+                None,
+                vec![StreamEvent::TextDelta { index, text }],
+            ),
+            ContentBlock::ToolUse { id, name, input } => (
+                "tool_use",
+                Some(id),
+                Some(name),
+                vec![StreamEvent::InputJsonDelta {
+                    index,
+                    partial_json: input.to_string(),
+                }],
+            ),
+            ContentBlock::Thinking {
+                thinking,
+                signature,
+            } => (
+                "thinking",
+                None,
+                None,
+                vec![
+                    StreamEvent::ThinkingDelta { index, thinking },
+                    StreamEvent::SignatureDelta { index, signature },
+                ],
+            ),
+            ContentBlock::Unknown { raw, deltas, .. } => {
+                events.push(StreamEvent::UnknownBlockStart {
+                    index,
+                    content_block: raw,
+                });
+                events.extend(
+                    deltas
+                        .into_iter()
+                        .map(|delta| StreamEvent::UnknownDelta { index, delta }),
+                );
+                events.push(StreamEvent::ContentBlockStop { index });
+                continue;
+            }
+            // Responses never contain images or tool results
+            ContentBlock::Image { .. } | ContentBlock::ToolResult { .. } => continue,
+        };
+        events.push(StreamEvent::ContentBlockStart {
+            index,
+            content_type: content_type.to_string(),
+            tool_use_id,
+            tool_name,
+        });
+        events.extend(deltas);
+        events.push(StreamEvent::ContentBlockStop { index });
+    }
+    events.push(StreamEvent::MessageDelta {
+        stop_reason: Some(response.stop_reason),
+        stop_sequence: response.stop_sequence,
+        usage: Some(response.usage),
+    });
+    events.push(StreamEvent::MessageStop);
+    events
+}
+
+// ============================================================================
+// Internal API types
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+struct ApiRequest {
+    model: String,
+    max_tokens: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<ApiSystem>,
+    messages: Vec<ApiMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<ApiTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<ApiToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ApiThinking>,
+    stream: bool,
+}
+
+/// Body for the count_tokens endpoint, which rejects generation settings.
+#[derive(Debug, Serialize)]
+struct ApiCountTokensRequest<'a> {
+    model: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<&'a ApiSystem>,
+    messages: &'a [ApiMessage],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<&'a Vec<ApiTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<&'a ApiToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<&'a ApiThinking>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTokenCount {
+    input_tokens: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiThinking {
+    r#type: String,
+    budget_tokens: usize,
+}
+
+/// The system prompt: plain text, or blocks when it carries cache control.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum ApiSystem {
+    Text(String),
+    Blocks(Vec<ApiSystemBlock>),
+}
+
+#[derive(Debug, Serialize)]
+struct ApiSystemBlock {
+    r#type: String,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<ApiCacheControl>,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiCacheControl {
+    r#type: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiMessage {
+    role: String,
+    content: Vec<ApiContentBlock>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ApiContentBlock {
+    Text {
+        text: String,
+    },
+    Image {
+        source: ApiImageSource,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        is_error: bool,
+    },
+    Thinking {
+        thinking: String,
+        signature: String,
+    },
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
+}
+
+impl From<&ContentBlock> for ApiContentBlock {
+    fn from(block: &ContentBlock) -> Self {
+        match block {
+            ContentBlock::Text { text } => ApiContentBlock::Text { text: text.clone() },
+            ContentBlock::Image { data, media_type } => ApiContentBlock::Image {
+                source: ApiImageSource {
+                    r#type: "base64".to_string(),
+                    media_type: media_type.clone(),
+                    data: data.clone(),
+                },
+            },
+            ContentBlock::ToolUse { id, name, input } => ApiContentBlock::ToolUse {
+                id: id.clone(),
+                name: name.clone(),
+                input: input.clone(),
+            },
+            ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } => ApiContentBlock::ToolResult {
+                tool_use_id: tool_use_id.clone(),
+                content: content.clone(),
+                is_error: *is_error,
+            },
+            ContentBlock::Thinking {
+                thinking,
+                signature,
+            } => ApiContentBlock::Thinking {
+                thinking: thinking.clone(),
+                signature: signature.clone(),
+            },
+            ContentBlock::Unknown { raw, .. } => ApiContentBlock::Unknown(raw.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ApiImageSource {
+    r#type: String,
+    media_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ApiTool {
+    name: String,
     description: String,
     input_schema: serde_json::Value,
 }
@@ -652,6 +1596,8 @@ struct ApiResponse {
     model: String,
     content: Vec<ApiContent>,
     stop_reason: String,
+    #[serde(default)]
+    stop_sequence: Option<String>,
     usage: ApiUsage,
 }
 
@@ -668,13 +1614,34 @@ enum ApiContent {
     },
     Thinking {
         thinking: String,
+        #[serde(default)]
+        signature: String,
     },
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
 #[derive(Debug, Deserialize)]
 struct ApiUsage {
+    /// Absent from `message_delta` usage, which only reports output tokens.
+    #[serde(default)]
     input_tokens: usize,
     output_tokens: usize,
+    #[serde(default)]
+    cache_read_input_tokens: Option<usize>,
+    #[serde(default)]
+    cache_creation_input_tokens: Option<usize>,
+}
+
+impl From<ApiUsage> for Usage {
+    fn from(usage: ApiUsage) -> Self {
+        Usage {
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+            cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+        }
+    }
 }
 
 // Streaming types
@@ -686,7 +1653,7 @@ enum ApiStreamEvent {
     },
     ContentBlockStart {
         index: usize,
-        content_block: ApiContentBlockStart,
+        content_block: serde_json::Value,
     },
     ContentBlockDelta {
         index: usize,
@@ -697,6 +1664,8 @@ enum ApiStreamEvent {
     },
     MessageDelta {
         delta: ApiMessageDelta,
+        #[serde(default)]
+        usage: Option<ApiUsage>,
     },
     MessageStop,
     Ping,
@@ -709,38 +1678,103 @@ enum ApiStreamEvent {
 struct ApiMessageStart {
     id: String,
     model: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct ApiContentBlockStart {
-    r#type: String,
-    /// Tool use ID (present for tool_use blocks)
     #[serde(default)]
-    id: Option<String>,
-    /// Tool name (present for tool_use blocks)
-    #[serde(default)]
-    name: Option<String>,
+    usage: Option<ApiUsage>,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[allow(clippy::enum_variant_names)]
 enum ApiDelta {
-    TextDelta { text: String },
-    InputJsonDelta { partial_json: String },
-    ThinkingDelta { thinking: String },
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    SignatureDelta {
+        signature: String,
+    },
+    #[serde(untagged)]
+    Unknown(serde_json::Value),
 }
 
 #[derive(Debug, Deserialize)]
 struct ApiMessageDelta {
     stop_reason: Option<String>,
+    #[serde(default)]
+    stop_sequence: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ApiError {
+    #[serde(default, rename = "type")]
+    error_type: String,
     message: String,
 }
 
+/// Run `future`, giving up with [`Error::Cancelled`] if `token` fires first.
+async fn cancellable<T>(
+    token: Option<&CancellationToken>,
+    future: impl std::future::Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    match token {
+        Some(token) => tokio::select! {
+            _ = token.cancelled() => Err(Error::Cancelled),
+            result = future => result,
+        },
+        None => future.await,
+    }
+}
+
+/// Detect an image's media type from its magic bytes.
+fn sniff_image_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Guess an image's media type from a lowercase file extension.
+fn image_type_for_extension(extension: &str) -> Option<&'static str> {
+    match extension {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Read the `retry-after` header (in seconds) from a response.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds: f64 = headers
+        .get("retry-after")?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Exponential backoff with jitter: a random delay between half and all of
+/// `base * 2^attempt`.
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    let full = base.saturating_mul(2u32.saturating_pow(attempt));
+    full.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+}
+
 /// Parse SSE events from a buffer, consuming complete events and leaving incomplete data.
 ///
 /// SSE events are separated by double newlines. This function finds complete events,
@@ -749,14 +1783,9 @@ struct ApiError {
 fn parse_sse_events_buffered(buffer: &mut String) -> Vec<Result<StreamEvent, Error>> {
     let mut events = Vec::new();
 
-    // Process complete SSE events (terminated by \n\n or at end of valid data lines)
-    loop {
-        // Find the next complete line (ending with \n)
-        let Some(newline_pos) = buffer.find('\n') else {
-            // No complete line yet, wait for more data
-            break;
-        };
-
+    // Process complete SSE events (terminated by \n\n or at end of valid data lines).
+    // Stops when there is no complete line (ending with \n) yet, waiting for more data.
+    while let Some(newline_pos) = buffer.find('\n') {
         let line = &buffer[..newline_pos];
 
         // Check if this is a data line
@@ -787,45 +1816,97 @@ fn parse_sse_events_buffered(buffer: &mut String) -> Vec<Result<StreamEvent, Err
     events
 }
 
+/// The HTTP status the API uses for an error type, so a mid-stream error
+/// surfaces as the same [`Error::Api`] a failed request would. Unknown types
+/// map to 0.
+fn error_status(error_type: &str) -> u16 {
+    match error_type {
+        "invalid_request_error" => 400,
+        "authentication_error" => 401,
+        "permission_error" => 403,
+        "not_found_error" => 404,
+        "request_too_large" => 413,
+        "rate_limit_error" => 429,
+        "api_error" => 500,
+        "overloaded_error" => 529,
+        _ => 0,
+    }
+}
+
+/// Map an API stop reason, keeping ones this client doesn't know by name.
+fn parse_stop_reason(stop_reason: &str) -> StopReason {
+    match stop_reason {
+        "end_turn" => StopReason::EndTurn,
+        "max_tokens" => StopReason::MaxTokens,
+        "stop_sequence" => StopReason::StopSequence,
+        "tool_use" => StopReason::ToolUse,
+        other => StopReason::Other(other.to_string()),
+    }
+}
+
+/// Wrap a block of a type this client doesn't model.
+fn unknown_block(raw: serde_json::Value, deltas: Vec<serde_json::Value>) -> ContentBlock {
+    let type_name = raw["type"].as_str().unwrap_or_default().to_string();
+    log::debug!("Keeping unknown content block type '{type_name}'");
+    ContentBlock::Unknown {
+        type_name,
+        raw,
+        deltas,
+    }
+}
+
 fn convert_stream_event(event: ApiStreamEvent) -> StreamEvent {
     match event {
         ApiStreamEvent::MessageStart { message } => StreamEvent::MessageStart {
             id: message.id,
             model: message.model,
+            usage: message.usage.map(Usage::from),
         },
         ApiStreamEvent::ContentBlockStart {
             index,
             content_block,
-        } => StreamEvent::ContentBlockStart {
-            index,
-            content_type: content_block.r#type,
-            tool_use_id: content_block.id,
-            tool_name: content_block.name,
-        },
+        } => {
+            let content_type = content_block["type"].as_str().unwrap_or_default();
+            if !KNOWN_BLOCK_TYPES.contains(&content_type) {
+                log::debug!("Keeping unknown content block type '{content_type}'");
+                return StreamEvent::UnknownBlockStart {
+                    index,
+                    content_block,
+                };
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_type: content_type.to_string(),
+                // Only tool_use blocks carry an ID and name
+                tool_use_id: content_block["id"].as_str().map(String::from),
+                tool_name: content_block["name"].as_str().map(String::from),
+            }
+        }
         ApiStreamEvent::ContentBlockDelta { index, delta } => match delta {
             ApiDelta::TextDelta { text } => StreamEvent::TextDelta { index, text },
             ApiDelta::InputJsonDelta { partial_json } => StreamEvent::InputJsonDelta {
                 index,
                 partial_json,
             },
-            ApiDelta::ThinkingDelta { thinking } => StreamEvent::TextDelta {
-                index,
-                text: thinking,
-            },
+            ApiDelta::ThinkingDelta { thinking } => StreamEvent::ThinkingDelta { index, thinking },
+            ApiDelta::SignatureDelta { signature } => {
+                StreamEvent::SignatureDelta { index, signature }
+            }
+            ApiDelta::Unknown(delta) => {
+                log::debug!("Keeping unknown delta for content block {index}: {delta}");
+                StreamEvent::UnknownDelta { index, delta }
+            }
         },
         ApiStreamEvent::ContentBlockStop { index } => StreamEvent::ContentBlockStop { index },
-        ApiStreamEvent::MessageDelta { delta } => StreamEvent::MessageDelta {
-            stop_reason: delta.stop_reason.map(|s| match s.as_str() {
-                "end_turn" => StopReason::EndTurn,
-                "max_tokens" => StopReason::MaxTokens,
-                "stop_sequence" => StopReason::StopSequence,
-                "tool_use" => StopReason::ToolUse,
-                _ => StopReason::EndTurn,
-            }),
+        ApiStreamEvent::MessageDelta { delta, usage } => StreamEvent::MessageDelta {
+            stop_reason: delta.stop_reason.as_deref().map(parse_stop_reason),
+            stop_sequence: delta.stop_sequence,
+            usage: usage.map(Usage::from),
         },
         ApiStreamEvent::MessageStop => StreamEvent::MessageStop,
         ApiStreamEvent::Ping => StreamEvent::Ping,
         ApiStreamEvent::Error { error } => StreamEvent::Error {
+            error_type: error.error_type,
             message: error.message,
         },
     }
@@ -834,6 +1915,16 @@ fn convert_stream_event(event: ApiStreamEvent) -> StreamEvent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{mock_server, MockResponse, RecordedRequest};
+    use std::sync::Arc;
+
+    fn mock_client(url: String) -> Claude {
+        Claude::new("test-key")
+            .with_base_url(url)
+            .with_retry(2, Duration::from_millis(1))
+    }
+
+    const OK_BODY: &str = r#"{"id":"msg_1","model":"claude-test","content":[{"type":"text","text":"Hello"}],"stop_reason":"end_turn","usage":{"input_tokens":3,"output_tokens":1}}"#;
 
     #[test]
     fn test_client_creation() {
@@ -847,6 +1938,140 @@ mod tests {
         assert_eq!(client.model, "claude-3-opus");
     }
 
+    #[test]
+    fn test_client_with_base_url() {
+        assert_eq!(Claude::new("test-key").api_base, API_BASE);
+        let client = Claude::new("test-key").with_base_url("https://gateway.example.com//");
+        assert_eq!(client.api_base, "https://gateway.example.com");
+    }
+
+    #[tokio::test]
+    async fn test_base_url_requests_hit_server() {
+        let (url, requests) = mock_server(vec![MockResponse::new(200, OK_BODY)]).await;
+        let client = Claude::new("test-key").with_base_url(format!("{url}/"));
+        let response = client
+            .complete(Request::new(vec![Message::user("Hi")]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.id, "msg_1");
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_count_tokens() {
+        let (url, requests) =
+            mock_server(vec![MockResponse::new(200, r#"{"input_tokens":1234}"#)]).await;
+        let request = Request::new(vec![Message::user("Describe the tavern")])
+            .with_system("You are the DM")
+            .with_temperature(0.5);
+        let count = Claude::new("test-key")
+            .with_base_url(url)
+            .count_tokens(&request)
+            .await
+            .unwrap();
+        assert_eq!(count, 1234);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests[0].path, "/v1/messages/count_tokens");
+        assert_eq!(requests[0].body["system"], "You are the DM");
+        assert_eq!(requests[0].body["messages"][0]["role"], "user");
+        assert!(requests[0].body.get("max_tokens").is_none());
+        assert!(requests[0].body.get("temperature").is_none());
+        assert!(requests[0].body.get("stream").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stream() {
+        let (url, _) = mock_server(vec![MockResponse::new(
+            200,
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"model\":\"claude-test\"}}\n\n",
+        )
+        .with_header("content-type", "text/event-stream")
+        .hanging()])
+        .await;
+        let token = CancellationToken::new();
+        let request = Request::new(vec![Message::user("Hi")]).with_cancellation(token.clone());
+        let mut stream = Claude::new("test-key")
+            .with_base_url(url)
+            .stream(request)
+            .await
+            .unwrap();
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert!(matches!(first, StreamEvent::MessageStart { .. }));
+
+        token.cancel();
+        let next = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("stream should end promptly");
+        assert!(matches!(next, Some(Err(Error::Cancelled))));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stream_ends_after_error_event() {
+        let (url, _) = mock_server(vec![MockResponse::new(
+            200,
+            concat!(
+                "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"model\":\"claude-test\"}}\n\n",
+                "event: error\n",
+                "data: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n",
+                "data: {\"type\":\"ping\"}\n\n",
+            ),
+        )
+        .with_header("content-type", "text/event-stream")
+        .hanging()])
+        .await;
+        let mut stream = Claude::new("test-key")
+            .with_base_url(url)
+            .stream(Request::new(vec![Message::user("Hi")]))
+            .await
+            .unwrap();
+
+        let mut accumulator = StreamAccumulator::new();
+        accumulator
+            .push(&stream.next().await.unwrap().unwrap())
+            .unwrap();
+        let error = stream.next().await.unwrap().unwrap();
+        assert!(matches!(
+            &error,
+            StreamEvent::Error { error_type, message }
+                if error_type == "overloaded_error" && message == "Overloaded"
+        ));
+        assert!(matches!(
+            accumulator.push(&error),
+            Err(Error::Api { status: 529, .. })
+        ));
+
+        // The connection stays open, but the stream is over
+        let next = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("stream should end after the error");
+        assert!(next.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_complete() {
+        let (url, _) = mock_server(vec![MockResponse::new(200, "{").hanging()]).await;
+        let token = CancellationToken::new();
+        let request = Request::new(vec![Message::user("Hi")]).with_cancellation(token.clone());
+
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            token.cancel();
+        });
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            Claude::new("test-key").with_base_url(url).complete(request),
+        )
+        .await
+        .expect("complete should return promptly");
+        canceller.await.unwrap();
+
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
+
     #[test]
     fn test_request_builder() {
         let request = Request::new(vec![Message::user("Hello")])
@@ -859,6 +2084,32 @@ mod tests {
         assert_eq!(request.temperature, Some(0.7));
     }
 
+    #[test]
+    fn test_sampling_builder() {
+        let request = Request::new(vec![Message::user("Hello")])
+            .with_top_p(0.9)
+            .with_top_k(40);
+        assert_eq!(request.top_p, Some(0.9));
+        assert_eq!(request.top_k, Some(40));
+
+        assert_eq!(Request::new(vec![]).with_top_p(1.5).top_p, Some(1.0));
+        assert_eq!(Request::new(vec![]).with_top_p(-0.2).top_p, Some(0.0));
+    }
+
+    #[test]
+    fn test_sampling_serialization() {
+        let client = Claude::new("test-key");
+        let plain = Request::new(vec![Message::user("Hi")]);
+        let body = serde_json::to_value(client.build_api_request(&plain, false)).unwrap();
+        assert!(body.get("top_p").is_none());
+        assert!(body.get("top_k").is_none());
+
+        let tuned = plain.with_top_p(0.5).with_top_k(10);
+        let body = serde_json::to_value(client.build_api_request(&tuned, false)).unwrap();
+        assert_eq!(body["top_p"], 0.5);
+        assert_eq!(body["top_k"], 10);
+    }
+
     #[test]
     fn test_message_creation() {
         let user_msg = Message::user("Hello");
@@ -869,6 +2120,616 @@ mod tests {
         assert!(matches!(assistant_msg.role, Role::Assistant));
     }
 
+    #[tokio::test]
+    async fn test_cassette_record_and_replay() {
+        const SECOND_BODY: &str = r#"{"id":"msg_2","model":"claude-test","content":[{"type":"text","text":"Goodbye"}],"stop_reason":"end_turn","usage":{"input_tokens":3,"output_tokens":1}}"#;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("cassette.json");
+
+        let (url, requests) = mock_server(vec![
+            MockResponse::new(200, OK_BODY),
+            MockResponse::new(200, SECOND_BODY),
+        ])
+        .await;
+        let recorder = RecordingClient::record(mock_client(url), &path);
+        let hello = Request::new(vec![Message::user("Hi")]);
+        let bye = Request::new(vec![Message::user("Bye")]).with_system("Be brief");
+        assert_eq!(
+            recorder.complete(hello.clone()).await.unwrap().text(),
+            "Hello"
+        );
+        assert_eq!(
+            recorder.complete(bye.clone()).await.unwrap().text(),
+            "Goodbye"
+        );
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        // Nothing listens here, so any network access would fail
+        let offline = Claude::new("test-key").with_base_url("http://127.0.0.1:1");
+        let player = RecordingClient::replay(offline, &path).unwrap();
+        assert_eq!(player.mode(), CassetteMode::Replay);
+        assert_eq!(player.interaction_count(), 2);
+
+        // Matching is by request body, not by order
+        assert_eq!(player.complete(bye).await.unwrap().text(), "Goodbye");
+        assert_eq!(
+            player.complete(hello.clone()).await.unwrap().text(),
+            "Hello"
+        );
+        assert!(matches!(
+            player.complete(hello).await,
+            Err(Error::Cassette(_))
+        ));
+        assert!(matches!(
+            player
+                .complete(Request::new(vec![Message::user("Unrecorded")]))
+                .await,
+            Err(Error::Cassette(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_exhausted() {
+        let (url, requests) = mock_server(vec![MockResponse::new(529, "overloaded")]).await;
+        let result = mock_client(url)
+            .complete(Request::new(vec![Message::user("Hi")]))
+            .await;
+
+        assert!(matches!(result, Err(Error::Api { status: 529, .. })));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_then_success() {
+        let (url, requests) = mock_server(vec![
+            MockResponse::new(429, "rate limited").with_header("retry-after", "0"),
+            MockResponse::new(200, OK_BODY),
+        ])
+        .await;
+        let response = mock_client(url)
+            .complete(Request::new(vec![Message::user("Hi")]))
+            .await
+            .unwrap();
+
+        assert_eq!(response.text(), "Hello");
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_client_error() {
+        let (url, requests) = mock_server(vec![MockResponse::new(400, "bad request")]).await;
+        let result = mock_client(url)
+            .stream(Request::new(vec![Message::user("Hi")]))
+            .await;
+
+        assert!(matches!(result, Err(Error::Api { status: 400, .. })));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_retry_delays() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+        headers.insert("retry-after", HeaderValue::from_static("2"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+
+        let base = Duration::from_millis(100);
+        for attempt in 0..4 {
+            let delay = backoff_delay(base, attempt);
+            let full = base * 2u32.pow(attempt);
+            assert!(delay >= full / 2 && delay <= full);
+        }
+    }
+
+    #[test]
+    fn test_cache_control_serialization() {
+        let client = Claude::new("test-key");
+        let request = Request::new(vec![Message::user("Hi")]).with_system("You are the DM");
+
+        let plain = serde_json::to_value(client.build_api_request(&request, false)).unwrap();
+        assert_eq!(plain["system"], "You are the DM");
+
+        let cached = client.build_api_request(&request.with_cache_control(), false);
+        let cached = serde_json::to_value(cached).unwrap();
+        assert_eq!(cached["system"][0]["type"], "text");
+        assert_eq!(cached["system"][0]["text"], "You are the DM");
+        assert_eq!(cached["system"][0]["cache_control"]["type"], "ephemeral");
+    }
+
+    #[test]
+    fn test_cache_usage_parsing() {
+        let client = Claude::new("test-key");
+        let api_response: ApiResponse = serde_json::from_str(
+            r#"{"id":"msg_1","model":"claude-test","content":[],"stop_reason":"end_turn",
+                "usage":{"input_tokens":10,"output_tokens":5,
+                         "cache_read_input_tokens":2000,"cache_creation_input_tokens":300}}"#,
+        )
+        .unwrap();
+        let usage = client.parse_response(api_response).usage;
+        assert_eq!(usage.cache_read_tokens, 2000);
+        assert_eq!(usage.cache_write_tokens, 300);
+
+        let api_response: ApiResponse = serde_json::from_str(OK_BODY).unwrap();
+        let usage = client.parse_response(api_response).usage;
+        assert_eq!(usage.cache_read_tokens, 0);
+        assert_eq!(usage.cache_write_tokens, 0);
+    }
+
+    #[test]
+    fn test_thinking_request() {
+        let client = Claude::new("test-key");
+        let request = Request::new(vec![Message::user("Is this a legal grapple?")])
+            .with_max_tokens(8000)
+            .with_thinking(2048);
+        assert!(request.validate().is_ok());
+
+        let body = serde_json::to_value(client.build_api_request(&request, false)).unwrap();
+        assert_eq!(body["thinking"]["type"], "enabled");
+        assert_eq!(body["thinking"]["budget_tokens"], 2048);
+
+        let plain = Request::new(vec![Message::user("Hi")]);
+        let body = serde_json::to_value(client.build_api_request(&plain, false)).unwrap();
+        assert!(body.get("thinking").is_none());
+    }
+
+    #[test]
+    fn test_thinking_budget_validation() {
+        let too_large = Request::new(vec![])
+            .with_max_tokens(2000)
+            .with_thinking(2000);
+        assert!(matches!(too_large.validate(), Err(Error::Config(_))));
+
+        let too_small = Request::new(vec![])
+            .with_max_tokens(8000)
+            .with_thinking(100);
+        assert!(matches!(too_small.validate(), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_thinking_stream_events() {
+        let mut buffer = concat!(
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"The DC is 15\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"signature_delta\",\"signature\":\"sig\"}}\n\n",
+        )
+        .to_string();
+        let events = parse_sse_events_buffered(&mut buffer);
+
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            Ok(StreamEvent::ThinkingDelta { index: 0, thinking }) if thinking == "The DC is 15"
+        ));
+        assert!(matches!(
+            &events[1],
+            Ok(StreamEvent::SignatureDelta { index: 0, signature }) if signature == "sig"
+        ));
+    }
+
+    #[test]
+    fn test_thinking_block_round_trip() {
+        let client = Claude::new("test-key");
+        let api_response: ApiResponse = serde_json::from_str(
+            r#"{"id":"msg_1","model":"claude-test","stop_reason":"end_turn",
+                "content":[{"type":"thinking","thinking":"Hmm","signature":"abc"},
+                           {"type":"text","text":"Yes"}],
+                "usage":{"input_tokens":1,"output_tokens":1}}"#,
+        )
+        .unwrap();
+        let response = client.parse_response(api_response);
+        assert!(matches!(
+            &response.content[0],
+            ContentBlock::Thinking { thinking, signature } if thinking == "Hmm" && signature == "abc"
+        ));
+
+        let block = serde_json::to_value(ApiContentBlock::from(&response.content[0])).unwrap();
+        assert_eq!(
+            block,
+            serde_json::json!({"type": "thinking", "thinking": "Hmm", "signature": "abc"})
+        );
+    }
+
+    #[test]
+    fn test_unknown_block_round_trip() {
+        let client = Claude::new("test-key");
+        let api_response: ApiResponse = serde_json::from_str(
+            r#"{"id":"msg_1","model":"claude-test","stop_reason":"pause_turn",
+                "content":[{"type":"redacted_thinking","data":"opaque"},
+                           {"type":"text","text":"Yes"}],
+                "usage":{"input_tokens":1,"output_tokens":1}}"#,
+        )
+        .unwrap();
+        let response = client.parse_response(api_response);
+        assert_eq!(
+            response.stop_reason,
+            StopReason::Other("pause_turn".to_string())
+        );
+        assert!(matches!(
+            &response.content[0],
+            ContentBlock::Unknown { type_name, .. } if type_name == "redacted_thinking"
+        ));
+        assert_eq!(response.text(), "Yes");
+
+        let block = serde_json::to_value(ApiContentBlock::from(&response.content[0])).unwrap();
+        assert_eq!(
+            block,
+            serde_json::json!({"type": "redacted_thinking", "data": "opaque"})
+        );
+    }
+
+    #[test]
+    fn test_stream_keeps_unknown_blocks() {
+        let mut buffer = concat!(
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"server_tool_use\",\"id\":\"srv_1\",\"name\":\"web_search\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"query_delta\",\"query\":\"goblins\"}}\n\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+            "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"Found them\"}}\n\n",
+            "data: {\"type\":\"content_block_stop\",\"index\":1}\n\n",
+        )
+        .to_string();
+        let events = parse_sse_events_buffered(&mut buffer)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(matches!(
+            &events[0],
+            StreamEvent::UnknownBlockStart { index: 0, content_block }
+                if content_block["type"] == "server_tool_use"
+        ));
+        assert!(matches!(
+            &events[1],
+            StreamEvent::UnknownDelta { index: 0, delta } if delta["query"] == "goblins"
+        ));
+
+        let mut accumulator = StreamAccumulator::new();
+        for event in &events {
+            accumulator.push(event).unwrap();
+        }
+        let response = accumulator.into_response().unwrap();
+        assert_eq!(response.content.len(), 2);
+        match &response.content[0] {
+            ContentBlock::Unknown {
+                type_name,
+                raw,
+                deltas,
+            } => {
+                assert_eq!(type_name, "server_tool_use");
+                assert_eq!(raw["name"], "web_search");
+                assert!(raw.get("deltas").is_none());
+                assert_eq!(deltas[0]["query"], "goblins");
+            }
+            other => panic!("expected an unknown block, got {other:?}"),
+        }
+        assert_eq!(response.text(), "Found them");
+    }
+
+    #[test]
+    fn test_stop_sequences() {
+        let client = Claude::new("test-key");
+        let request = Request::new(vec![Message::user("Extract the intent")])
+            .with_stop_sequences(vec!["</intent>".to_string()]);
+        assert_eq!(request.stop_sequences, Some(vec!["</intent>".to_string()]));
+
+        let body = serde_json::to_value(client.build_api_request(&request, false)).unwrap();
+        assert_eq!(body["stop_sequences"], serde_json::json!(["</intent>"]));
+
+        let plain = Request::new(vec![Message::user("Hi")]);
+        let body = serde_json::to_value(client.build_api_request(&plain, false)).unwrap();
+        assert!(body.get("stop_sequences").is_none());
+    }
+
+    #[test]
+    fn test_stop_sequence_response() {
+        let client = Claude::new("test-key");
+        let api_response: ApiResponse = serde_json::from_str(
+            r#"{"id":"msg_1","model":"claude-test","content":[{"type":"text","text":"{}"}],
+                "stop_reason":"stop_sequence","stop_sequence":"</intent>",
+                "usage":{"input_tokens":1,"output_tokens":1}}"#,
+        )
+        .unwrap();
+        let response = client.parse_response(api_response);
+        assert_eq!(response.stop_reason, StopReason::StopSequence);
+        assert_eq!(response.stop_sequence.as_deref(), Some("</intent>"));
+    }
+
+    /// Signature and IHDR chunk of a 1x1 PNG.
+    const TINY_PNG: &[u8] = &[
+        0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44,
+        0x52, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1F,
+        0x15, 0xC4, 0x89,
+    ];
+
+    #[test]
+    fn test_image_from_bytes() {
+        let block = ContentBlock::image_from_bytes(TINY_PNG, "image/png").unwrap();
+        let ContentBlock::Image { media_type, data } = block else {
+            panic!("expected an image block");
+        };
+        assert_eq!(media_type, "image/png");
+        assert!(data.starts_with("iVBORw0KGgo"));
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .unwrap();
+        assert_eq!(decoded, TINY_PNG);
+
+        assert!(matches!(
+            ContentBlock::image_from_bytes(TINY_PNG, "image/bmp"),
+            Err(Error::Image(_))
+        ));
+    }
+
+    #[test]
+    fn test_image_from_path() {
+        let dir = std::env::temp_dir().join(format!("claude-image-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Magic bytes win over a misleading extension
+        let png = dir.join("portrait.jpg");
+        std::fs::write(&png, TINY_PNG).unwrap();
+        let block = ContentBlock::image_from_path(&png).unwrap();
+        assert!(
+            matches!(block, ContentBlock::Image { ref media_type, .. } if media_type == "image/png")
+        );
+
+        // Unknown contents fall back to the extension
+        let gif = dir.join("map.GIF");
+        std::fs::write(&gif, b"not really a gif").unwrap();
+        let block = ContentBlock::image_from_path(&gif).unwrap();
+        assert!(
+            matches!(block, ContentBlock::Image { ref media_type, .. } if media_type == "image/gif")
+        );
+
+        let text = dir.join("notes.txt");
+        std::fs::write(&text, b"hello").unwrap();
+        assert!(matches!(
+            ContentBlock::image_from_path(&text),
+            Err(Error::Image(_))
+        ));
+        assert!(ContentBlock::image_from_path(dir.join("missing.png")).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_stream_accumulator_tool_use() {
+        let events = vec![
+            StreamEvent::MessageStart {
+                id: "msg_1".to_string(),
+                model: "claude-test".to_string(),
+                usage: Some(Usage {
+                    input_tokens: 120,
+                    output_tokens: 1,
+                    cache_read_tokens: 900,
+                    cache_write_tokens: 0,
+                }),
+            },
+            StreamEvent::ContentBlockStart {
+                index: 0,
+                content_type: "text".to_string(),
+                tool_use_id: None,
+                tool_name: None,
+            },
+            StreamEvent::TextDelta {
+                index: 0,
+                text: "Roll for ".to_string(),
+            },
+            StreamEvent::TextDelta {
+                index: 0,
+                text: "initiative.".to_string(),
+            },
+            StreamEvent::ContentBlockStop { index: 0 },
+            StreamEvent::ContentBlockStart {
+                index: 1,
+                content_type: "tool_use".to_string(),
+                tool_use_id: Some("toolu_1".to_string()),
+                tool_name: Some("roll_dice".to_string()),
+            },
+            StreamEvent::InputJsonDelta {
+                index: 1,
+                partial_json: r#"{"notat"#.to_string(),
+            },
+            StreamEvent::InputJsonDelta {
+                index: 1,
+                partial_json: r#"ion": "1d2"#.to_string(),
+            },
+            StreamEvent::InputJsonDelta {
+                index: 1,
+                partial_json: r#"0+2"}"#.to_string(),
+            },
+        ];
+
+        let mut accumulator = StreamAccumulator::new();
+        let finished: Vec<ContentBlock> = events
+            .iter()
+            .filter_map(|event| accumulator.push(event).unwrap())
+            .collect();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].as_text(), Some("Roll for initiative."));
+
+        let finished = accumulator
+            .push(&StreamEvent::ContentBlockStop { index: 1 })
+            .unwrap();
+        assert!(matches!(
+            finished,
+            Some(ContentBlock::ToolUse { ref id, ref name, ref input })
+                if id == "toolu_1" && name == "roll_dice" && input["notation"] == "1d20+2"
+        ));
+
+        accumulator
+            .push(&StreamEvent::MessageDelta {
+                stop_reason: Some(StopReason::ToolUse),
+                stop_sequence: None,
+                usage: Some(Usage {
+                    output_tokens: 42,
+                    ..Usage::default()
+                }),
+            })
+            .unwrap();
+        accumulator.push(&StreamEvent::MessageStop).unwrap();
+        assert!(accumulator.is_finished());
+
+        let response = accumulator.into_response().unwrap();
+        assert_eq!(response.id, "msg_1");
+        assert_eq!(response.stop_reason, StopReason::ToolUse);
+        assert_eq!(response.text(), "Roll for initiative.");
+        assert_eq!(response.content.len(), 2);
+        // Input and cache counts come from message_start, output from the delta
+        assert_eq!(response.usage.output_tokens, 42);
+        assert_eq!(response.usage.input_tokens, 120);
+        assert_eq!(response.usage.cache_read_tokens, 900);
+    }
+
+    #[tokio::test]
+    async fn test_provider_default_stream_replays_response() {
+        struct Canned;
+
+        impl LlmProvider for Canned {
+            fn complete(&self, _request: Request) -> BoxFuture<'_, Result<Response, Error>> {
+                Box::pin(async {
+                    Ok(Response {
+                        id: "msg_1".to_string(),
+                        model: "local".to_string(),
+                        content: vec![
+                            ContentBlock::Text {
+                                text: "Roll for initiative.".to_string(),
+                            },
+                            ContentBlock::ToolUse {
+                                id: "toolu_1".to_string(),
+                                name: "roll_dice".to_string(),
+                                input: serde_json::json!({"notation": "1d20+2"}),
+                            },
+                        ],
+                        stop_reason: StopReason::ToolUse,
+                        stop_sequence: None,
+                        usage: Usage {
+                            output_tokens: 42,
+                            ..Usage::default()
+                        },
+                    })
+                })
+            }
+        }
+
+        let provider: Box<dyn LlmProvider> = Box::new(Canned);
+        let mut stream = provider
+            .stream(Request::new(vec![Message::user("Hi")]))
+            .await
+            .unwrap();
+        let mut accumulator = StreamAccumulator::new();
+        while let Some(event) = stream.next().await {
+            accumulator.push(&event.unwrap()).unwrap();
+        }
+        assert!(accumulator.is_finished());
+
+        let response = accumulator.into_response().unwrap();
+        assert_eq!(response.id, "msg_1");
+        assert_eq!(response.stop_reason, StopReason::ToolUse);
+        assert_eq!(response.text(), "Roll for initiative.");
+        assert!(matches!(
+            &response.content[1],
+            ContentBlock::ToolUse { name, input, .. }
+                if name == "roll_dice" && input["notation"] == "1d20+2"
+        ));
+        assert_eq!(response.usage.output_tokens, 42);
+    }
+
+    #[test]
+    fn test_message_start_usage() {
+        let mut buffer = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"model\":\"claude-test\",\"usage\":{\"input_tokens\":25,\"output_tokens\":1,\"cache_read_input_tokens\":400,\"cache_creation_input_tokens\":60}}}\n\n",
+        )
+        .to_string();
+        let events = parse_sse_events_buffered(&mut buffer);
+
+        assert!(matches!(
+            &events[0],
+            Ok(StreamEvent::MessageStart {
+                usage: Some(Usage {
+                    input_tokens: 25,
+                    output_tokens: 1,
+                    cache_read_tokens: 400,
+                    cache_write_tokens: 60,
+                }),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_message_delta_usage() {
+        let mut buffer = concat!(
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"end_turn\",\"stop_sequence\":null},\"usage\":{\"output_tokens\":15}}\n\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":null}}\n\n",
+        )
+        .to_string();
+        let events = parse_sse_events_buffered(&mut buffer);
+
+        assert!(matches!(
+            &events[0],
+            Ok(StreamEvent::MessageDelta {
+                stop_reason: Some(StopReason::EndTurn),
+                stop_sequence: None,
+                usage: Some(Usage {
+                    input_tokens: 0,
+                    output_tokens: 15,
+                    ..
+                }),
+            })
+        ));
+        assert!(matches!(
+            &events[1],
+            Ok(StreamEvent::MessageDelta {
+                stop_reason: None,
+                stop_sequence: None,
+                usage: None
+            })
+        ));
+    }
+
+    #[test]
+    fn test_stream_accumulator_keeps_stop_sequence() {
+        let mut buffer = concat!(
+            "data: {\"type\":\"message_start\",\"message\":{\"id\":\"msg_1\",\"model\":\"claude-test\"}}\n\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"stop_sequence\",\"stop_sequence\":\"</intent>\"}}\n\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        )
+        .to_string();
+        let mut accumulator = StreamAccumulator::new();
+        for event in parse_sse_events_buffered(&mut buffer) {
+            accumulator.push(&event.unwrap()).unwrap();
+        }
+
+        let response = accumulator.into_response().unwrap();
+        assert_eq!(response.stop_reason, StopReason::StopSequence);
+        assert_eq!(response.stop_sequence.as_deref(), Some("</intent>"));
+    }
+
+    #[test]
+    fn test_stream_accumulator_invalid_json() {
+        let mut accumulator = StreamAccumulator::new();
+        accumulator
+            .push(&StreamEvent::ContentBlockStart {
+                index: 0,
+                content_type: "tool_use".to_string(),
+                tool_use_id: Some("toolu_1".to_string()),
+                tool_name: Some("roll_dice".to_string()),
+            })
+            .unwrap();
+        accumulator
+            .push(&StreamEvent::InputJsonDelta {
+                index: 0,
+                partial_json: r#"{"notation": "#.to_string(),
+            })
+            .unwrap();
+        assert!(matches!(
+            accumulator.push(&StreamEvent::ContentBlockStop { index: 0 }),
+            Err(Error::Parse(_))
+        ));
+    }
+
     #[test]
     fn test_tool_result() {
         let success = ToolResult::success("worked");
@@ -879,4 +2740,77 @@ mod tests {
         assert!(error.is_error);
         assert_eq!(error.content, "failed");
     }
+
+    /// A response calling one tool.
+    fn tool_call_body(id: &str, name: &str, input: &str) -> String {
+        format!(
+            r#"{{"id":"msg_{id}","model":"claude-test","content":[{{"type":"tool_use","id":"{id}","name":"{name}","input":{input}}}],"stop_reason":"tool_use","usage":{{"input_tokens":3,"output_tokens":1}}}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_router_dispatches_by_tool_name() {
+        let (url, requests) = mock_server(vec![
+            MockResponse::new(200, tool_call_body("call_1", "roll", r#"{"dice":"1d20"}"#)),
+            MockResponse::new(
+                200,
+                tool_call_body("call_2", "lookup", r#"{"name":"Goblin"}"#),
+            ),
+            MockResponse::new(200, OK_BODY),
+        ])
+        .await;
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let (rolls, lookups) = (calls.clone(), calls.clone());
+        let router = ToolRouter::new()
+            .with_tool("roll", move |tool_use: ToolUse| {
+                rolls
+                    .lock()
+                    .unwrap()
+                    .push(format!("roll {}", tool_use.input["dice"]));
+                async { ToolResult::success("17") }
+            })
+            .with_tool("lookup", move |tool_use: ToolUse| {
+                lookups
+                    .lock()
+                    .unwrap()
+                    .push(format!("lookup {}", tool_use.input["name"]));
+                async { ToolResult::success("AC 15") }
+            });
+        assert!(router.handles("roll"));
+        assert!(!router.handles("cast"));
+
+        let response = mock_client(url)
+            .complete_with_router(Request::new(vec![Message::user("Attack")]), &router)
+            .await
+            .unwrap();
+
+        assert_eq!(response.text(), "Hello");
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![r#"roll "1d20""#, r#"lookup "Goblin""#]
+        );
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        let result_of = |request: &RecordedRequest| {
+            let messages = request.body["messages"].as_array().unwrap();
+            messages.last().unwrap()["content"][0]["content"].clone()
+        };
+        assert_eq!(result_of(&requests[1]), "17");
+        assert_eq!(result_of(&requests[2]), "AC 15");
+    }
+
+    #[tokio::test]
+    async fn test_router_rejects_unknown_tools() {
+        let result = ToolRouter::new()
+            .execute(ToolUse {
+                id: "call_1".to_string(),
+                name: "cast".to_string(),
+                input: serde_json::json!({}),
+            })
+            .await;
+
+        assert!(result.is_error);
+        assert_eq!(result.content, "Unknown tool: cast");
+    }
 }
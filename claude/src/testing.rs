@@ -0,0 +1,156 @@
+//! A local HTTP server that stands in for the Messages API in tests.
+//!
+//! Enabled for this crate's tests and, for other crates, by the `test-util`
+//! feature. Point a client at the returned URL with
+//! [`Claude::with_base_url`](crate::Claude::with_base_url).
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::oneshot;
+
+/// A canned HTTP response from the mock server.
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(&'static str, &'static str)>,
+    body: String,
+    /// Keep the connection open after the body, as a slow stream would.
+    hang: bool,
+    /// More body to send once the receiver fires.
+    rest: Mutex<Option<(oneshot::Receiver<()>, String)>>,
+}
+
+impl MockResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            hang: false,
+            rest: Mutex::new(None),
+        }
+    }
+
+    /// A 200 response carrying a JSON body.
+    pub fn json(body: serde_json::Value) -> Self {
+        Self::new(200, body.to_string()).with_header("content-type", "application/json")
+    }
+
+    /// A 200 response streaming server-sent events, one per value.
+    pub fn events(events: &[serde_json::Value]) -> Self {
+        Self::new(200, sse(events)).with_header("content-type", "text/event-stream")
+    }
+
+    pub fn hanging(mut self) -> Self {
+        self.hang = true;
+        self
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: &'static str) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    /// Hold back `rest` of the body until `release` fires, so a test can
+    /// watch what a client does with the first part.
+    pub fn then_after(self, release: oneshot::Receiver<()>, rest: impl Into<String>) -> Self {
+        *self.rest.lock().unwrap() = Some((release, rest.into()));
+        self
+    }
+}
+
+/// Format events as a server-sent event stream.
+pub fn sse(events: &[serde_json::Value]) -> String {
+    events
+        .iter()
+        .map(|event| {
+            format!(
+                "event: {}\ndata: {event}\n\n",
+                event["type"].as_str().unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+/// A request received by the mock server.
+pub struct RecordedRequest {
+    pub path: String,
+    pub body: serde_json::Value,
+}
+
+/// Every request the mock server has received, oldest first.
+pub type Recorded = Arc<Mutex<Vec<RecordedRequest>>>;
+
+/// Serve the responses in order (repeating the last one), recording requests.
+///
+/// Returns the server's base URL.
+pub async fn mock_server(responses: Vec<MockResponse>) -> (String, Recorded) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let recorded = Recorded::default();
+    let requests = recorded.clone();
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let n = requests.lock().unwrap().len();
+            let response = &responses[n.min(responses.len() - 1)];
+
+            // Read the request head, then the body it announces
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            let body_start = loop {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+                if let Some(pos) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+            };
+            let head = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+            let length: usize = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .map(|v| v.trim().parse().unwrap())
+                .unwrap_or(0);
+            while request.len() < body_start + length {
+                let read = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..read]);
+            }
+            requests.lock().unwrap().push(RecordedRequest {
+                path: head
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .to_string(),
+                body: serde_json::from_slice(&request[body_start..]).unwrap_or_default(),
+            });
+
+            let rest = response.rest.lock().unwrap().take();
+            let mut reply = format!("HTTP/1.1 {} Mock\r\nconnection: close\r\n", response.status);
+            if !response.hang && rest.is_none() {
+                reply.push_str(&format!("content-length: {}\r\n", response.body.len()));
+            }
+            for (name, value) in &response.headers {
+                reply.push_str(&format!("{name}: {value}\r\n"));
+            }
+            reply.push_str("\r\n");
+            reply.push_str(&response.body);
+            socket.write_all(reply.as_bytes()).await.unwrap();
+            if let Some((release, rest)) = rest {
+                socket.flush().await.unwrap();
+                release.await.unwrap();
+                socket.write_all(rest.as_bytes()).await.unwrap();
+            }
+            if response.hang {
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    drop(socket);
+                });
+            } else {
+                socket.shutdown().await.ok();
+            }
+        }
+    });
+
+    (url, recorded)
+}
@@ -7,9 +7,10 @@ use bevy_egui::egui;
 use dnd_core::spells::{spells_by_level, SpellClass};
 use dnd_core::world::{
     Ability, AbilityScores, Background, Character, CharacterClass, ClassLevel, HitPoints,
-    ProficiencyLevel, Race, RaceType, Skill, SlotInfo, Speed, SpellSlots, SpellcastingData,
+    PactMagic, ProficiencyLevel, Race, RaceType, Senses, Skill, SlotInfo, Speed, SpellSlots,
+    SpellcastingData,
 };
-use dnd_core::{AbilityMethod, CharacterBuilder};
+use dnd_core::{AbilityMethod, CharacterBuilder, EquipmentChoices};
 use std::collections::HashSet;
 
 use crate::state::{AppState, GamePhase};
@@ -169,7 +170,8 @@ impl CharacterCreation {
             .class(self.class.ok_or("No class selected")?)
             .background(self.background.ok_or("No background selected")?)
             .ability_scores(self.ability_scores.clone())
-            .skills(self.selected_skills.clone());
+            .skills(self.selected_skills.clone())
+            .with_starting_equipment(EquipmentChoices::default());
 
         // Handle Half-Elf bonus abilities
         if self.race == Some(RaceType::HalfElf) {
@@ -193,6 +195,9 @@ impl CharacterCreation {
                         spells_prepared: self.selected_spells.clone(), // For simplicity, prepared = known at level 1
                         cantrips_known: self.selected_cantrips.clone(),
                         spell_slots: create_level_1_spell_slots(class),
+                        pact_slots: (class == CharacterClass::Warlock)
+                            .then(|| PactMagic::for_level(1)),
+                        prepared_since_rest: false,
                     });
                 }
             }
@@ -220,6 +225,8 @@ impl CharacterCreation {
             };
             character.race_type = race;
             character.speed = Speed::new(race.base_speed());
+            character.senses = Senses::for_race(race);
+            character.languages = race.languages().into_iter().collect();
         }
 
         // Apply class
@@ -282,6 +289,9 @@ impl CharacterCreation {
                         spells_prepared: self.selected_spells.clone(),
                         cantrips_known: self.selected_cantrips.clone(),
                         spell_slots: create_level_1_spell_slots(class),
+                        pact_slots: (class == CharacterClass::Warlock)
+                            .then(|| PactMagic::for_level(1)),
+                        prepared_since_rest: false,
                     });
                 }
             }
@@ -299,7 +309,6 @@ fn create_level_1_spell_slots(class: CharacterClass) -> SpellSlots {
         | CharacterClass::Druid
         | CharacterClass::Sorcerer
         | CharacterClass::Wizard => 2,
-        CharacterClass::Warlock => 1, // Pact Magic
         _ => 0,
     };
 
@@ -33,9 +33,8 @@ pub fn process_effect(
                 Vec2::new(400.0, 300.0), // Center-ish position
             );
 
-            // Add to narrative
-            let result_text = format!("{}: {} = {}", purpose, roll.expression, roll.total);
-            app_state.add_narrative(result_text, NarrativeType::System, time);
+            // Add to narrative, keeping the dice for the log
+            app_state.add_roll_narrative(roll.clone(), purpose, time);
         }
 
         Effect::AttackHit {
@@ -44,6 +43,7 @@ pub fn process_effect(
             attack_roll,
             target_ac,
             is_critical,
+            ..
         } => {
             // Screen shake on hit
             let intensity = if *is_critical { 1.0 } else { 0.5 };
@@ -155,6 +155,68 @@ pub fn process_effect(
             );
         }
 
+        Effect::TempHpGranted { amount, .. } => {
+            animations::spawn_combat_effect(commands, EffectType::Heal, Vec2::ZERO, 0.3);
+            app_state.add_narrative(
+                format!("Gains {amount} temporary HP."),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::ExhaustionChanged {
+            old_level,
+            new_level,
+            source,
+            ..
+        } => {
+            let text = if new_level > old_level {
+                format!("Exhaustion increases to level {new_level} ({source}).")
+            } else if *new_level == 0 {
+                "No longer exhausted.".to_string()
+            } else {
+                format!("Exhaustion decreases to level {new_level}.")
+            };
+            app_state.add_narrative(text, NarrativeType::System, time);
+        }
+
+        Effect::AbilityModifierAdded { modifier, .. } => {
+            app_state.add_narrative(
+                format!(
+                    "{} {:+} ({}).",
+                    modifier.ability.abbreviation(),
+                    modifier.delta,
+                    modifier.source
+                ),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::AbilityModifierRemoved { source, .. } => {
+            app_state.add_narrative(format!("{source} wears off."), NarrativeType::System, time);
+        }
+
+        Effect::InspirationGranted { .. } => {
+            app_state.add_narrative(
+                "Gains Inspiration!".to_string(),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::InspirationUsed { .. } => {
+            app_state.add_narrative(
+                "Inspiration spent: advantage on the next roll.".to_string(),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::InspirationApplied { .. } => {
+            // The advantage already shows in the roll itself
+        }
+
         Effect::CombatStarted => {
             animations::spawn_combat_effect(commands, EffectType::ScreenShake, Vec2::ZERO, 0.3);
             app_state.add_narrative("Combat begins!".to_string(), NarrativeType::Combat, time);
@@ -204,6 +266,19 @@ pub fn process_effect(
             );
         }
 
+        Effect::CombatantMoved { .. }
+        | Effect::Disengaged { .. }
+        | Effect::ActionUsed { .. }
+        | Effect::ActionRestored { .. }
+        | Effect::MovementUsed { .. }
+        | Effect::Mounted { .. }
+        | Effect::Dismounted { .. }
+        | Effect::ReactionReadied { .. }
+        | Effect::ReactionUsed { .. }
+        | Effect::HitSpent { .. } => {
+            // Tracked in combat state; the resolution narrative covers it
+        }
+
         Effect::TimeAdvanced { minutes } => {
             if *minutes >= 60 {
                 let hours = minutes / 60;
@@ -230,7 +305,9 @@ pub fn process_effect(
             }
         }
 
-        Effect::ExperienceGained { amount, new_total } => {
+        Effect::ExperienceGained {
+            amount, new_total, ..
+        } => {
             app_state.add_narrative(
                 format!("Gained {amount} XP! (Total: {new_total} XP)"),
                 NarrativeType::System,
@@ -238,7 +315,38 @@ pub fn process_effect(
             );
         }
 
-        Effect::LevelUp { new_level } => {
+        Effect::QuestAdded { quest } => {
+            app_state.add_narrative(
+                format!("New quest: {}", quest.name),
+                NarrativeType::System,
+                time,
+            );
+            app_state.set_status(format!("Quest added: {}", quest.name), time);
+        }
+
+        Effect::QuestStatusChanged {
+            quest_name, status, ..
+        } => {
+            app_state.add_narrative(
+                format!("Quest {}: {quest_name}", status.name()),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::ObjectiveCompleted {
+            quest_name,
+            objective,
+            ..
+        } => {
+            app_state.add_narrative(
+                format!("Objective complete ({quest_name}): {objective}"),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::LevelUp { new_level, .. } => {
             animations::spawn_combat_effect(commands, EffectType::LevelUp, Vec2::ZERO, 1.0);
             app_state.add_narrative(
                 format!("LEVEL UP! You are now level {new_level}!"),
@@ -248,9 +356,18 @@ pub fn process_effect(
             app_state.set_status(format!("Level up! Now level {new_level}!"), time);
         }
 
+        Effect::FeatTaken { feat_name, .. } => {
+            app_state.add_narrative(
+                format!("Feat gained: {feat_name}"),
+                NarrativeType::System,
+                time,
+            );
+        }
+
         Effect::FeatureUsed {
             feature_name,
             uses_remaining,
+            ..
         } => {
             app_state.add_narrative(
                 format!("Used {feature_name}. ({uses_remaining} uses remaining)"),
@@ -259,7 +376,9 @@ pub fn process_effect(
             );
         }
 
-        Effect::SpellSlotUsed { level, remaining } => {
+        Effect::SpellSlotUsed {
+            level, remaining, ..
+        } => {
             animations::spawn_combat_effect(commands, EffectType::SpellCast, Vec2::ZERO, 0.5);
             app_state.add_narrative(
                 format!("Used a level {level} spell slot. ({remaining} remaining)"),
@@ -268,6 +387,35 @@ pub fn process_effect(
             );
         }
 
+        Effect::PactSlotUsed {
+            level, remaining, ..
+        } => {
+            animations::spawn_combat_effect(commands, EffectType::SpellCast, Vec2::ZERO, 0.5);
+            app_state.add_narrative(
+                format!("Used a level {level} pact slot. ({remaining} remaining)"),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::SpellsPrepared { spells, .. } => {
+            app_state.add_narrative(
+                format!("Prepared spells: {}", spells.join(", ")),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::HitDiceSpent {
+            die_type, count, ..
+        } => {
+            app_state.add_narrative(
+                format!("Spent {count} {die_type:?} hit dice."),
+                NarrativeType::System,
+                time,
+            );
+        }
+
         Effect::RestCompleted { rest_type } => {
             let rest_name = match rest_type {
                 dnd_core::rules::RestType::Short => "short",
@@ -330,6 +478,7 @@ pub fn process_effect(
             item_name,
             quantity,
             new_total,
+            ..
         } => {
             let qty_str = if *quantity > 1 {
                 format!("{quantity} x ")
@@ -347,6 +496,7 @@ pub fn process_effect(
             item_name,
             quantity,
             remaining,
+            ..
         } => {
             let qty_str = if *quantity > 1 {
                 format!("{quantity} x ")
@@ -368,7 +518,9 @@ pub fn process_effect(
             }
         }
 
-        Effect::ItemEquipped { item_name, slot } => {
+        Effect::ItemEquipped {
+            item_name, slot, ..
+        } => {
             app_state.add_narrative(
                 format!("Equipped {item_name} in {slot} slot."),
                 NarrativeType::System,
@@ -376,7 +528,9 @@ pub fn process_effect(
             );
         }
 
-        Effect::ItemUnequipped { item_name, slot } => {
+        Effect::ItemUnequipped {
+            item_name, slot, ..
+        } => {
             app_state.add_narrative(
                 format!("Unequipped {item_name} from {slot} slot."),
                 NarrativeType::System,
@@ -392,10 +546,27 @@ pub fn process_effect(
             );
         }
 
+        Effect::ItemAttuned { item_name, .. } => {
+            app_state.add_narrative(
+                format!("Attuned to {item_name}"),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::AttunementEnded { item_name, .. } => {
+            app_state.add_narrative(
+                format!("Attunement to {item_name} ended"),
+                NarrativeType::System,
+                time,
+            );
+        }
+
         Effect::GoldChanged {
             amount,
             new_total,
             reason,
+            ..
         } => {
             let action = if *amount >= 0.0 { "Gained" } else { "Spent" };
             app_state.add_narrative(
@@ -411,7 +582,7 @@ pub fn process_effect(
             );
         }
 
-        Effect::AcChanged { new_ac, source } => {
+        Effect::AcChanged { new_ac, source, .. } => {
             app_state.add_narrative(
                 format!("AC changed to {new_ac} ({source})"),
                 NarrativeType::System,
@@ -527,6 +698,7 @@ pub fn process_effect(
         Effect::LocationChanged {
             previous_location,
             new_location,
+            ..
         } => {
             app_state.add_narrative(
                 format!("You travel from {previous_location} to {new_location}."),
@@ -536,6 +708,18 @@ pub fn process_effect(
             app_state.set_status(format!("Now at: {new_location}"), time);
         }
 
+        Effect::LocationDiscovered { location } => {
+            app_state.add_narrative(
+                format!("Discovered: {}", location.name),
+                NarrativeType::System,
+                time,
+            );
+        }
+
+        Effect::LocationsConnected { .. } => {
+            // The map changed; nothing to show
+        }
+
         Effect::ClassResourceUsed {
             character_name,
             resource_name,
@@ -5,6 +5,7 @@
 //! processing player actions.
 
 use bevy::prelude::*;
+use dnd_core::dice::RollResult;
 use dnd_core::rules::Effect;
 use dnd_core::world::{
     AbilityScores, CombatState, Condition, DeathSaves, GameMode, GameTime, HitPoints, Item, Quest,
@@ -37,8 +38,8 @@ pub enum WorkerRequest {
     PlayerAction(String),
     /// Cancel the current processing.
     Cancel,
-    /// Save the game to a file.
-    Save(std::path::PathBuf),
+    /// Autosave the game into a directory, rotating older autosaves.
+    AutoSave(std::path::PathBuf),
     /// Load a game from a file.
     Load(std::path::PathBuf),
     /// Shutdown the worker.
@@ -193,7 +194,7 @@ impl WorldUpdate {
             campaign_name: world.campaign_name.clone(),
             conditions: character.conditions.iter().map(|c| c.condition).collect(),
             death_saves: character.death_saves.clone(),
-            gold: character.inventory.gold,
+            gold: character.inventory.purse.total_gp() as f32,
             equipped_weapon: character
                 .equipment
                 .main_hand
@@ -217,11 +218,19 @@ impl WorldUpdate {
                 .spellcasting
                 .as_ref()
                 .map(|sc| {
-                    sc.spell_slots
+                    let mut slots: Vec<(u8, u8)> = sc
+                        .spell_slots
                         .slots
                         .iter()
                         .map(|slot| (slot.available(), slot.total))
-                        .collect()
+                        .collect();
+                    // Pact Magic slots show alongside standard slots of their level
+                    if let Some(pact) = sc.pact_slots {
+                        let slot = &mut slots[pact.level as usize - 1];
+                        slot.0 += pact.available();
+                        slot.1 += pact.count;
+                    }
+                    slots
                 })
                 .unwrap_or_default(),
             known_spells: character
@@ -257,6 +266,8 @@ pub struct NarrativeEntry {
     pub text: String,
     pub entry_type: NarrativeType,
     pub timestamp: f64,
+    /// The dice behind a `DiceRoll` entry.
+    pub roll: Option<RollResult>,
 }
 
 /// Active overlay screen.
@@ -411,11 +422,26 @@ impl Default for AppState {
 impl AppState {
     /// Add a narrative entry.
     pub fn add_narrative(&mut self, text: String, entry_type: NarrativeType, time: f64) {
-        self.narrative.push(NarrativeEntry {
+        self.push_narrative(NarrativeEntry {
             text,
             entry_type,
             timestamp: time,
+            roll: None,
         });
+    }
+
+    /// Add a dice roll entry that keeps the individual dice.
+    pub fn add_roll_narrative(&mut self, roll: RollResult, purpose: &str, time: f64) {
+        self.push_narrative(NarrativeEntry {
+            text: format!("{}: {} = {}", purpose, roll.expression, roll.total),
+            entry_type: NarrativeType::DiceRoll,
+            timestamp: time,
+            roll: Some(roll),
+        });
+    }
+
+    fn push_narrative(&mut self, entry: NarrativeEntry) {
+        self.narrative.push(entry);
         // Keep narrative history bounded
         if self.narrative.len() > 500 {
             self.narrative.remove(0);
@@ -796,10 +822,10 @@ async fn worker_loop(
             Some(WorkerRequest::Cancel) => {
                 let _ = response_tx.send(WorkerResponse::Cancelled).await;
             }
-            Some(WorkerRequest::Save(path)) => {
-                let result = session.save(&path).await;
+            Some(WorkerRequest::AutoSave(dir)) => {
+                let result = session.autosave(&dir).await;
                 let response = match result {
-                    Ok(()) => WorkerResponse::SaveComplete(Ok(path)),
+                    Ok(path) => WorkerResponse::SaveComplete(Ok(path)),
                     Err(e) => WorkerResponse::SaveComplete(Err(e.to_string())),
                 };
                 let _ = response_tx.send(response).await;
@@ -260,8 +260,7 @@ pub fn handle_keyboard_input(
     if ctrl_pressed && keys.just_pressed(KeyCode::KeyS)
         && !app_state.is_saving && !app_state.is_processing && app_state.has_session() {
             if let Some(tx) = &app_state.request_tx {
-                let path = dnd_core::persist::auto_save_path("saves", &app_state.world.campaign_name);
-                let _ = tx.try_send(crate::state::WorkerRequest::Save(path));
+                let _ = tx.try_send(crate::state::WorkerRequest::AutoSave("saves".into()));
                 app_state.is_saving = true;
                 app_state.set_status_persistent("Saving...");
             }
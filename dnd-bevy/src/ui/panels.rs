@@ -181,8 +181,7 @@ pub fn render_top_bar(ctx: &egui::Context, app_state: &mut AppState) {
                     .clicked()
                 {
                     if let Some(tx) = &app_state.request_tx {
-                        let path = dnd_core::persist::auto_save_path("saves", &app_state.world.campaign_name);
-                        let _ = tx.try_send(WorkerRequest::Save(path));
+                        let _ = tx.try_send(WorkerRequest::AutoSave("saves".into()));
                         app_state.is_saving = true;
                         app_state.set_status_persistent("Saving...");
                     }
@@ -238,6 +237,7 @@ pub fn render_narrative_panel(ctx: &egui::Context, app_state: &AppState, _curren
                         NarrativeType::NpcDialogue => egui::Color32::from_rgb(200, 200, 150), // Tan
                         NarrativeType::Combat => egui::Color32::from_rgb(255, 100, 100), // Red
                         NarrativeType::System => egui::Color32::from_rgb(180, 180, 180), // Gray
+                        NarrativeType::DiceRoll => egui::Color32::from_rgb(230, 190, 90), // Gold
                     };
 
                     let prefix = match entry.entry_type {
@@ -246,11 +246,15 @@ pub fn render_narrative_panel(ctx: &egui::Context, app_state: &AppState, _curren
                         NarrativeType::NpcDialogue => "\"",
                         NarrativeType::Combat => "[Combat] ",
                         NarrativeType::System => "[System] ",
+                        NarrativeType::DiceRoll => "[Roll] ",
                     };
 
                     // Split by paragraph breaks (double newlines first, then single)
                     // and render each paragraph with proper visual spacing
-                    let text_with_prefix = format!("{}{}", prefix, entry.text);
+                    let text_with_prefix = match &entry.roll {
+                        Some(roll) => format!("{}{} {}", prefix, entry.text, roll.dice_display()),
+                        None => format!("{}{}", prefix, entry.text),
+                    };
 
                     // Check if there are double newlines (proper paragraphs)
                     if text_with_prefix.contains("\n\n") {
@@ -197,10 +197,10 @@ async fn run_scenario(scenario: &Scenario, output_dir: &Path) -> Result<(), Box<
     }
 
     // Footer
-    transcript.push_str(&format!(
+    transcript.push_str(
         "*This transcript was generated by [dnd-ai](https://github.com/yourusername/dnd-ai), \
-        an AI Dungeon Master powered by Claude.*\n"
-    ));
+        an AI Dungeon Master powered by Claude.*\n",
+    );
 
     // Write the file
     let output_path = output_dir.join(scenario.filename);
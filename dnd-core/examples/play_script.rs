@@ -0,0 +1,46 @@
+//! Replay a file of player actions through a headless game.
+//!
+//! Run with: `cargo run -p dnd-core --example play_script -- actions.txt [--seed 42] [--json]`
+//!
+//! Each non-empty line of the file is sent as one player action. With a seed
+//! the dice are deterministic, so the same script reproduces the same rolls.
+
+use dnd_core::headless::{HeadlessConfig, HeadlessGame};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let _ = dotenvy::dotenv();
+
+    let mut args = std::env::args().skip(1);
+    let mut script = None;
+    let mut seed = None;
+    let mut json = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = Some(args.next().ok_or("--seed needs a value")?.parse()?),
+            "--json" => json = true,
+            _ => script = Some(arg),
+        }
+    }
+    let script = script.ok_or("usage: play_script <actions file> [--seed N] [--json]")?;
+
+    let mut config = HeadlessConfig::quick_start("Scripted Hero")
+        .script(script)
+        .json_output(json);
+    if let Some(seed) = seed {
+        config = config.with_seed(seed);
+    }
+
+    let mut game = HeadlessGame::new(config).await?;
+    let responses = game.run_script().await?;
+
+    if !json {
+        for (entry, response) in game.transcript().iter().zip(&responses) {
+            println!("> {}\n", entry.player_input);
+            println!("{}\n", response.narrative);
+        }
+        println!("HP: {}/{}", game.current_hp(), game.max_hp());
+    }
+
+    Ok(())
+}
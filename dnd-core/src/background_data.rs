@@ -0,0 +1,127 @@
+//! D&D 5e background data for character creation.
+//!
+//! Contains the skill and tool proficiencies, free language picks, and
+//! feature granted by each PHB background. Where a background offers a choice
+//! of tool (a gaming set, an instrument), the most common pick is used.
+
+use crate::world::{Background, Feature, Skill};
+
+/// Background-specific data for character creation.
+pub struct BackgroundData {
+    /// Skill proficiencies granted by the background.
+    pub skill_proficiencies: [Skill; 2],
+    /// Tool (and vehicle) proficiencies granted by the background.
+    pub tool_proficiencies: &'static [&'static str],
+    /// Number of extra languages of the player's choice.
+    pub bonus_languages: usize,
+    /// The background feature.
+    pub feature: Feature,
+}
+
+impl Background {
+    /// Get background data for character creation.
+    pub fn data(&self) -> BackgroundData {
+        let (tool_proficiencies, feature_name, feature_description): (&[&str], &str, &str) =
+            match self {
+                Background::Acolyte => (
+                    &[],
+                    "Shelter of the Faithful",
+                    "You and your companions can expect free healing and care at temples of your faith, and support from its priests.",
+                ),
+                Background::Charlatan => (
+                    &["Disguise Kit", "Forgery Kit"],
+                    "False Identity",
+                    "You have a second identity with documentation, acquaintances, and disguises, and can forge documents you have seen.",
+                ),
+                Background::Criminal => (
+                    &["Dice Set", "Thieves' Tools"],
+                    "Criminal Contact",
+                    "You have a reliable contact who acts as your liaison to a network of other criminals.",
+                ),
+                Background::Entertainer => (
+                    &["Disguise Kit", "Lute"],
+                    "By Popular Demand",
+                    "You can always find a place to perform, earning free lodging and food of a modest or comfortable standard.",
+                ),
+                Background::FolkHero => (
+                    &["Carpenter's Tools", "Vehicles (Land)"],
+                    "Rustic Hospitality",
+                    "Common folk will shelter you from the law or anyone searching for you, though they won't risk their lives.",
+                ),
+                Background::GuildArtisan => (
+                    &["Smith's Tools"],
+                    "Guild Membership",
+                    "Your guild offers lodging and food if needed, and will support you in legal trouble or with political connections.",
+                ),
+                Background::Hermit => (
+                    &["Herbalism Kit"],
+                    "Discovery",
+                    "Your seclusion gave you access to a unique and powerful discovery, such as a great truth or a hidden site.",
+                ),
+                Background::Noble => (
+                    &["Playing Card Set"],
+                    "Position of Privilege",
+                    "People assume the best of you; you are welcome in high society and can secure an audience with local nobles.",
+                ),
+                Background::Outlander => (
+                    &["Flute"],
+                    "Wanderer",
+                    "You have an excellent memory for maps and geography, and can find food and fresh water for up to five people each day.",
+                ),
+                Background::Sage => (
+                    &[],
+                    "Researcher",
+                    "When you don't know a piece of lore, you often know where and from whom you can obtain it.",
+                ),
+                Background::Sailor => (
+                    &["Navigator's Tools", "Vehicles (Water)"],
+                    "Ship's Passage",
+                    "You can secure free passage on a sailing ship for yourself and your companions, in exchange for help with the crew.",
+                ),
+                Background::Soldier => (
+                    &["Dice Set", "Vehicles (Land)"],
+                    "Military Rank",
+                    "Soldiers loyal to your former organization recognize your authority, and you can requisition simple equipment.",
+                ),
+                Background::Urchin => (
+                    &["Disguise Kit", "Thieves' Tools"],
+                    "City Secrets",
+                    "You know the secret patterns of cities and can travel through them at twice your normal speed out of combat.",
+                ),
+            };
+
+        BackgroundData {
+            skill_proficiencies: self.skill_proficiencies(),
+            tool_proficiencies,
+            bonus_languages: self.bonus_languages(),
+            feature: Feature {
+                name: feature_name.to_string(),
+                description: feature_description.to_string(),
+                source: self.name().to_string(),
+                uses: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_backgrounds_have_data() {
+        for background in Background::all() {
+            let data = background.data();
+            assert!(!data.feature.name.is_empty());
+            assert_eq!(data.feature.source, background.name());
+        }
+    }
+
+    #[test]
+    fn test_criminal_data() {
+        let data = Background::Criminal.data();
+        assert_eq!(data.skill_proficiencies, [Skill::Deception, Skill::Stealth]);
+        assert!(data.tool_proficiencies.contains(&"Thieves' Tools"));
+        assert_eq!(data.feature.name, "Criminal Contact");
+    }
+}
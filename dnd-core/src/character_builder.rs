@@ -3,12 +3,15 @@
 //! Provides a step-by-step builder for creating new characters with
 //! proper D&D 5e rules for ability scores, class features, and proficiencies.
 
+use crate::feats::{get_feat, FeatError};
+use crate::items::{find_item, get_adventuring_gear, get_armor, get_weapon};
+use crate::spells::{spells_for_class, SpellClass};
 use crate::world::{
     Ability, AbilityScores, Background, Character, CharacterClass, ClassLevel, ClassResources,
-    HitDice, HitPoints, ProficiencyLevel, Race, RaceType, Skill, SlotInfo, Speed, SpellSlots,
-    SpellcastingData,
+    HitDice, HitPoints, Language, PactMagic, ProficiencyLevel, Purse, Race, RaceType, Senses,
+    Skill, SlotInfo, Speed, SpellSlots, SpellcastingData,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 /// Method for determining ability scores.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -69,6 +72,120 @@ pub fn point_buy_cost(score: u8) -> Option<u8> {
 /// Total points available for point buy.
 pub const POINT_BUY_TOTAL: u8 = 27;
 
+/// Choices offered by a class's starting equipment.
+#[derive(Debug, Clone, Default)]
+pub struct EquipmentChoices {
+    /// Wield this weapon instead of the class's usual one, e.g. a greatsword
+    /// for a fighter. A two-handed pick leaves the shield in the pack.
+    pub weapon: Option<String>,
+    /// Wear this armor instead of the class's usual armor.
+    pub armor: Option<String>,
+}
+
+/// What a class starts with: worn, wielded, and carried.
+struct StartingPack {
+    armor: Option<&'static str>,
+    weapon: &'static str,
+    shield: bool,
+    gear: &'static [(&'static str, u32)],
+}
+
+/// An explorer's pack, which every class starts with.
+const EXPLORERS_PACK: &[(&str, u32)] = &[
+    ("Backpack", 1),
+    ("Bedroll", 1),
+    ("Tinderbox", 1),
+    ("Torch", 10),
+    ("Rations (1 day)", 10),
+    ("Waterskin", 1),
+    ("Rope (50 feet)", 1),
+];
+
+/// Starting equipment by class, following the PHB's default picks.
+fn starting_pack(class: CharacterClass) -> StartingPack {
+    let (armor, weapon, shield, gear): (_, _, _, &[(&str, u32)]) = match class {
+        CharacterClass::Barbarian => (None, "Greataxe", false, &[("Handaxe", 2), ("Javelin", 4)]),
+        CharacterClass::Bard => (Some("Leather Armor"), "Rapier", false, &[("Dagger", 1)]),
+        CharacterClass::Cleric => (
+            Some("Scale Mail"),
+            "Mace",
+            true,
+            &[("Light Crossbow", 1), ("Bolts (20)", 1), ("Holy Symbol", 1)],
+        ),
+        CharacterClass::Druid => (
+            Some("Leather Armor"),
+            "Scimitar",
+            true,
+            &[("Druidic Focus", 1)],
+        ),
+        CharacterClass::Fighter => (
+            Some("Chain Mail"),
+            "Longsword",
+            true,
+            &[("Light Crossbow", 1), ("Bolts (20)", 1)],
+        ),
+        CharacterClass::Monk => (None, "Shortsword", false, &[]),
+        CharacterClass::Paladin => (
+            Some("Chain Mail"),
+            "Longsword",
+            true,
+            &[("Javelin", 5), ("Holy Symbol", 1)],
+        ),
+        CharacterClass::Ranger => (
+            Some("Scale Mail"),
+            "Shortsword",
+            false,
+            &[("Shortsword", 1), ("Longbow", 1), ("Arrows (20)", 1)],
+        ),
+        CharacterClass::Rogue => (
+            Some("Leather Armor"),
+            "Rapier",
+            false,
+            &[
+                ("Shortbow", 1),
+                ("Arrows (20)", 1),
+                ("Dagger", 2),
+                ("Thieves' Tools", 1),
+            ],
+        ),
+        CharacterClass::Sorcerer => (
+            None,
+            "Light Crossbow",
+            false,
+            &[("Bolts (20)", 1), ("Dagger", 2), ("Component Pouch", 1)],
+        ),
+        CharacterClass::Warlock => (
+            Some("Leather Armor"),
+            "Light Crossbow",
+            false,
+            &[("Bolts (20)", 1), ("Dagger", 2), ("Component Pouch", 1)],
+        ),
+        CharacterClass::Wizard => (
+            None,
+            "Quarterstaff",
+            false,
+            &[("Component Pouch", 1), ("Spellbook", 1)],
+        ),
+    };
+    StartingPack {
+        armor,
+        weapon,
+        shield,
+        gear,
+    }
+}
+
+/// Gear a background adds on top of the class pack.
+fn background_gear(background: Background) -> &'static [(&'static str, u32)] {
+    match background {
+        Background::Acolyte => &[("Holy Symbol", 1)],
+        Background::Criminal => &[("Crowbar", 1)],
+        Background::Sailor => &[("Rope (50 feet)", 1)],
+        Background::Urchin => &[("Rations (1 day)", 2)],
+        _ => &[],
+    }
+}
+
 /// Builder for creating D&D 5e characters.
 #[derive(Debug, Clone, Default)]
 pub struct CharacterBuilder {
@@ -83,6 +200,12 @@ pub struct CharacterBuilder {
     half_elf_bonus_abilities: Option<[Ability; 2]>,
     /// Optional character backstory
     backstory: Option<String>,
+    /// Languages picked for the race's and background's free choices
+    extra_languages: Vec<Language>,
+    /// Class and background gear to start with, if requested
+    starting_equipment: Option<EquipmentChoices>,
+    /// Feat taken at creation, with its ability choice
+    feat: Option<(String, Option<Ability>)>,
 }
 
 /// Error from character building.
@@ -96,6 +219,11 @@ pub enum BuilderError {
     InvalidSkillCount { expected: usize, got: usize },
     SkillNotAvailable(Skill),
     HalfElfNeedsBonusAbilities,
+    InvalidHalfElfBonuses,
+    PointBuyScoreOutOfRange { ability: Ability, score: u8 },
+    PointBuyOverBudget { cost: u8 },
+    UnknownItem(String),
+    Feat(FeatError),
 }
 
 impl std::fmt::Display for BuilderError {
@@ -115,12 +243,35 @@ impl std::fmt::Display for BuilderError {
             BuilderError::HalfElfNeedsBonusAbilities => {
                 write!(f, "Half-Elf requires two additional ability bonuses")
             }
+            BuilderError::InvalidHalfElfBonuses => write!(
+                f,
+                "Half-Elf bonuses must go to two different abilities other than Charisma"
+            ),
+            BuilderError::PointBuyScoreOutOfRange { ability, score } => write!(
+                f,
+                "{} score {score} is out of range (8-15)",
+                ability.abbreviation()
+            ),
+            BuilderError::UnknownItem(name) => write!(f, "Unknown item: {name}"),
+            BuilderError::Feat(e) => write!(f, "{e}"),
+            BuilderError::PointBuyOverBudget { cost } => {
+                write!(
+                    f,
+                    "Total point cost {cost} exceeds maximum {POINT_BUY_TOTAL}"
+                )
+            }
         }
     }
 }
 
 impl std::error::Error for BuilderError {}
 
+impl From<FeatError> for BuilderError {
+    fn from(e: FeatError) -> Self {
+        BuilderError::Feat(e)
+    }
+}
+
 impl CharacterBuilder {
     /// Create a new character builder.
     pub fn new() -> Self {
@@ -177,12 +328,24 @@ impl CharacterBuilder {
     }
 
     /// Set ability scores from point buy values.
+    ///
+    /// The scores are checked against the point buy rules when the character
+    /// is built; use [`with_point_buy`](Self::with_point_buy) to check now.
     pub fn point_buy(mut self, scores: AbilityScores) -> Self {
         self.ability_scores = Some(scores);
         self.ability_method = AbilityMethod::PointBuy;
         self
     }
 
+    /// Set ability scores from point buy values, rejecting any score outside
+    /// 8-15 or a spread costing more than 27 points.
+    ///
+    /// Scores are before racial bonuses, which are applied when building.
+    pub fn with_point_buy(self, scores: AbilityScores) -> Result<Self, BuilderError> {
+        validate_point_buy(&scores)?;
+        Ok(self.point_buy(scores))
+    }
+
     /// Set ability scores from rolled values.
     pub fn rolled(mut self, scores: AbilityScores) -> Self {
         self.ability_scores = Some(scores);
@@ -208,30 +371,45 @@ impl CharacterBuilder {
         self
     }
 
+    /// Choose languages for the free picks granted by race and background.
+    /// Picks beyond the number granted are ignored.
+    pub fn languages(mut self, languages: Vec<Language>) -> Self {
+        self.extra_languages = languages;
+        self
+    }
+
+    /// Start with the class's and background's equipment, wearing the armor
+    /// and holding the weapon (and shield, if the class has one).
+    pub fn with_starting_equipment(mut self, choices: EquipmentChoices) -> Self {
+        self.starting_equipment = Some(choices);
+        self
+    }
+
+    /// Take a feat at creation, such as Lucky as an origin feat. `ability` is
+    /// the choice for feats like Resilient; prerequisites are checked on build.
+    pub fn with_feat(mut self, feat_name: impl Into<String>, ability: Option<Ability>) -> Self {
+        self.feat = Some((feat_name.into(), ability));
+        self
+    }
+
     /// Build the character, returning an error if any required field is missing.
     pub fn build(self) -> Result<Character, BuilderError> {
         let name = self.name.ok_or(BuilderError::MissingName)?;
         let race = self.race.ok_or(BuilderError::MissingRace)?;
         let class = self.class.ok_or(BuilderError::MissingClass)?;
         let background = self.background.ok_or(BuilderError::MissingBackground)?;
-        let mut ability_scores = self
+        let ability_scores = self
             .ability_scores
             .ok_or(BuilderError::MissingAbilityScores)?;
-
-        // Apply racial ability bonuses
-        race.apply_ability_bonuses(&mut ability_scores);
-
-        // Handle Half-Elf's extra +1 to two abilities
-        if race == RaceType::HalfElf {
-            let bonuses = self
-                .half_elf_bonus_abilities
-                .ok_or(BuilderError::HalfElfNeedsBonusAbilities)?;
-            for ability in bonuses {
-                let current = ability_scores.get(ability);
-                ability_scores.set(ability, current + 1);
-            }
+        if self.ability_method == AbilityMethod::PointBuy {
+            validate_point_buy(&ability_scores)?;
         }
 
+        // Start from the chosen scores and layer the race on top
+        let mut character = Character::new(&name);
+        character.ability_scores = ability_scores;
+        Self::apply_racial_traits(&mut character, race, self.half_elf_bonus_abilities)?;
+
         // Get class data
         let class_data = class.data();
 
@@ -252,12 +430,10 @@ impl CharacterBuilder {
         }
 
         // Calculate HP
-        let con_mod = ability_scores.modifier(Ability::Constitution);
+        let con_mod = character.ability_scores.modifier(Ability::Constitution);
         let hp = (class_data.base_hp + con_mod as i32).max(1);
 
         // Build the character
-        let mut character = Character::new(&name);
-        character.ability_scores = ability_scores;
         character.level = 1;
         character.hit_points = HitPoints::new(hp);
 
@@ -278,53 +454,72 @@ impl CharacterBuilder {
             character.saving_throw_proficiencies.insert(ability);
         }
 
-        // Set skill proficiencies (class skills)
-        character.skill_proficiencies = HashMap::new();
+        character.grant_class_proficiencies(class);
+
+        // Add class skill proficiencies
         for skill in &self.selected_skills {
             character
                 .skill_proficiencies
                 .insert(*skill, ProficiencyLevel::Proficient);
         }
 
-        // Add background skill proficiencies
-        for skill in background.skill_proficiencies() {
+        // Add background skill and tool proficiencies
+        let background_data = background.data();
+        for skill in background_data.skill_proficiencies {
             character
                 .skill_proficiencies
                 .entry(skill)
                 .or_insert(ProficiencyLevel::Proficient);
         }
+        character.tool_proficiencies = background_data
+            .tool_proficiencies
+            .iter()
+            .map(|tool| tool.to_string())
+            .collect();
 
         // Set features
         character.features = class_data.level_1_features;
-
-        // Set race
-        character.race = Race {
-            name: race.name().to_string(),
-            subrace: None,
-            race_type: Some(race),
-        };
-        character.race_type = race;
+        character.features.push(background_data.feature);
 
         // Set background
         character.background = background;
         character.background_name = background.name().to_string();
 
-        // Set speed
-        character.speed = Speed::new(race.base_speed());
+        // Add any free language picks
+        let mut picks = race.bonus_languages() + background_data.bonus_languages;
+        for language in self.extra_languages {
+            if picks == 0 {
+                break;
+            }
+            if character.languages.insert(language) {
+                picks -= 1;
+            }
+        }
 
         // Set backstory
         character.backstory = self.backstory;
 
+        if let Some(choices) = self.starting_equipment {
+            Self::grant_starting_equipment(&mut character, class, background, choices)?;
+        }
+
+        if let Some((feat_name, ability)) = self.feat {
+            let feat = get_feat(&feat_name).ok_or(FeatError::UnknownFeat(feat_name))?;
+            feat.check(&character, ability)?;
+            feat.apply(&mut character, ability);
+        }
+
         // Initialize spellcasting for spellcasting classes
         if class.is_spellcaster() {
             if let Some(ability) = class.spellcasting_ability() {
                 let mut spell_slots = SpellSlots::new();
+                let mut pact_slots = None;
 
                 // Set up level 1 spell slots based on class
                 match class {
                     CharacterClass::Warlock => {
                         // Pact Magic: 1 first-level slot at level 1
-                        spell_slots.slots[0] = SlotInfo { total: 1, used: 0 };
+                        pact_slots = Some(PactMagic::for_level(1));
                     }
                     CharacterClass::Bard
                     | CharacterClass::Cleric
@@ -407,17 +602,39 @@ impl CharacterBuilder {
                     _ => vec![],
                 };
 
-                // For prepared casters, spells_prepared starts empty
-                // (player chooses after long rest)
-                let spells_prepared = vec![];
+                // Prepared casters start with a default preparation, which the
+                // player can replace until their first long rest
+                let mut spells_prepared = match class {
+                    CharacterClass::Wizard => spells_known.clone(),
+                    CharacterClass::Cleric | CharacterClass::Druid => {
+                        let list = if class == CharacterClass::Cleric {
+                            SpellClass::Cleric
+                        } else {
+                            SpellClass::Druid
+                        };
+                        let mut names: Vec<String> = spells_for_class(list)
+                            .filter(|spell| spell.level == 1)
+                            .map(|spell| spell.name.clone())
+                            .collect();
+                        names.sort();
+                        names
+                    }
+                    _ => vec![],
+                };
 
                 character.spellcasting = Some(SpellcastingData {
                     ability,
                     spells_known,
-                    spells_prepared,
+                    spells_prepared: Vec::new(),
                     cantrips_known,
                     spell_slots,
+                    pact_slots,
+                    prepared_since_rest: false,
                 });
+                spells_prepared.truncate(character.prepared_spell_limit().unwrap_or(0));
+                if let Some(spellcasting) = character.spellcasting.as_mut() {
+                    spellcasting.spells_prepared = spells_prepared;
+                }
             }
         }
 
@@ -437,13 +654,114 @@ impl CharacterBuilder {
 
         Ok(character)
     }
+
+    /// Apply everything the race grants: ability score increases (with a
+    /// Half-Elf's two +1s of choice), speed, senses, innate skill
+    /// proficiencies and languages.
+    ///
+    /// `build` calls this once, on scores that have no racial bonuses yet.
+    fn apply_racial_traits(
+        character: &mut Character,
+        race: RaceType,
+        half_elf_bonuses: Option<[Ability; 2]>,
+    ) -> Result<(), BuilderError> {
+        race.apply_ability_bonuses(&mut character.ability_scores);
+
+        // Half-Elves add +1 to two different abilities other than Charisma
+        if race == RaceType::HalfElf {
+            let bonuses = half_elf_bonuses.ok_or(BuilderError::HalfElfNeedsBonusAbilities)?;
+            if bonuses[0] == bonuses[1] || bonuses.contains(&Ability::Charisma) {
+                return Err(BuilderError::InvalidHalfElfBonuses);
+            }
+            for ability in bonuses {
+                let current = character.ability_scores.get(ability);
+                character.ability_scores.set(ability, current + 1);
+            }
+        }
+
+        character.race = Race {
+            name: race.name().to_string(),
+            subrace: None,
+            race_type: Some(race),
+        };
+        character.race_type = race;
+        character.speed = Speed::new(race.base_speed());
+        character.senses = Senses::for_race(race);
+        character.languages = race.languages().into_iter().collect();
+        for skill in race.skill_proficiencies() {
+            character
+                .skill_proficiencies
+                .insert(skill, ProficiencyLevel::Proficient);
+        }
+        Ok(())
+    }
+
+    /// Fill the inventory from the class pack and background, then equip the
+    /// armor, weapon and shield.
+    fn grant_starting_equipment(
+        character: &mut Character,
+        class: CharacterClass,
+        background: Background,
+        choices: EquipmentChoices,
+    ) -> Result<(), BuilderError> {
+        let pack = starting_pack(class);
+
+        let armor_name = choices.armor.as_deref().or(pack.armor);
+        let armor = armor_name
+            .map(|name| get_armor(name).ok_or_else(|| BuilderError::UnknownItem(name.to_string())))
+            .transpose()?;
+        let weapon_name = choices.weapon.as_deref().unwrap_or(pack.weapon);
+        let weapon = get_weapon(weapon_name)
+            .ok_or_else(|| BuilderError::UnknownItem(weapon_name.to_string()))?;
+
+        let inventory = &mut character.inventory;
+        inventory.items.clear();
+        inventory.purse = Purse {
+            gp: background.starting_gold(),
+            ..Default::default()
+        };
+        for &(name, count) in EXPLORERS_PACK
+            .iter()
+            .chain(pack.gear)
+            .chain(background_gear(background))
+        {
+            let mut item = find_item(name)
+                .ok_or_else(|| BuilderError::UnknownItem(name.to_string()))?
+                .as_item();
+            if item.is_stackable() {
+                item.quantity = count;
+                inventory.add_item(item);
+            } else {
+                for _ in 0..count {
+                    inventory.add_item(item.clone());
+                }
+            }
+        }
+
+        let equipment = &mut character.equipment;
+        equipment.armor = armor;
+        equipment.main_hand = Some(weapon);
+        if pack.shield {
+            let shield = get_adventuring_gear("Shield")
+                .ok_or_else(|| BuilderError::UnknownItem("Shield".to_string()))?;
+            if equipment
+                .can_equip("shield", &shield.name, &Default::default())
+                .is_ok()
+            {
+                equipment.shield = Some(shield);
+            } else {
+                character.inventory.add_item(shield);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Roll 4d6, drop lowest, for ability score generation.
 pub fn roll_4d6_drop_lowest() -> u8 {
     use rand::Rng;
-    let mut rng = rand::thread_rng();
-    let mut rolls: Vec<u8> = (0..4).map(|_| rng.gen_range(1..=6)).collect();
+    let mut rolls: Vec<u8> =
+        crate::dice::with_rng(|rng| (0..4).map(|_| rng.gen_range(1..=6)).collect());
     rolls.sort();
     rolls[1..].iter().sum()
 }
@@ -458,25 +776,18 @@ pub fn roll_ability_scores() -> [u8; 6] {
 }
 
 /// Validate point buy scores.
-pub fn validate_point_buy(scores: &AbilityScores) -> Result<(), String> {
+pub fn validate_point_buy(scores: &AbilityScores) -> Result<(), BuilderError> {
     let mut total_cost = 0u8;
 
     for ability in Ability::all() {
         let score = scores.get(ability);
-        if !(8..=15).contains(&score) {
-            return Err(format!(
-                "{} score {} is out of range (8-15)",
-                ability.abbreviation(),
-                score
-            ));
-        }
-        total_cost += point_buy_cost(score).unwrap();
+        let cost = point_buy_cost(score)
+            .ok_or(BuilderError::PointBuyScoreOutOfRange { ability, score })?;
+        total_cost += cost;
     }
 
     if total_cost > POINT_BUY_TOTAL {
-        return Err(format!(
-            "Total point cost {total_cost} exceeds maximum {POINT_BUY_TOTAL}"
-        ));
+        return Err(BuilderError::PointBuyOverBudget { cost: total_cost });
     }
 
     Ok(())
@@ -604,6 +915,66 @@ mod tests {
         assert!(validate_point_buy(&out_of_range).is_err());
     }
 
+    fn point_buy_fighter(scores: AbilityScores) -> Result<Character, BuilderError> {
+        CharacterBuilder::new()
+            .name("Test")
+            .race(RaceType::Human)
+            .class(CharacterClass::Fighter)
+            .background(Background::Soldier)
+            .skills(vec![Skill::Athletics, Skill::Perception])
+            .with_point_buy(scores)?
+            .build()
+    }
+
+    #[test]
+    fn test_point_buy_full_budget() {
+        // 9 + 9 + 9 + 0 + 0 + 0 = 27 points
+        let character = point_buy_fighter(AbilityScores::new(15, 15, 15, 8, 8, 8)).unwrap();
+        // Racial bonuses land on top of the bought scores
+        assert_eq!(character.ability_scores.strength, 16);
+        assert_eq!(character.ability_scores.charisma, 9);
+    }
+
+    #[test]
+    fn test_point_buy_over_budget() {
+        // 9 + 9 + 9 + 1 = 28 points
+        let result = point_buy_fighter(AbilityScores::new(15, 15, 15, 9, 8, 8));
+        assert!(matches!(
+            result,
+            Err(BuilderError::PointBuyOverBudget { cost: 28 })
+        ));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Total point cost 28 exceeds maximum 27"
+        );
+    }
+
+    #[test]
+    fn test_point_buy_score_out_of_range() {
+        let result = point_buy_fighter(AbilityScores::new(12, 12, 12, 12, 12, 7));
+        assert!(matches!(
+            result,
+            Err(BuilderError::PointBuyScoreOutOfRange {
+                ability: Ability::Charisma,
+                score: 7
+            })
+        ));
+
+        // Scores set without validation are still checked when building
+        let result = CharacterBuilder::new()
+            .name("Test")
+            .race(RaceType::Human)
+            .class(CharacterClass::Fighter)
+            .background(Background::Soldier)
+            .skills(vec![Skill::Athletics, Skill::Perception])
+            .point_buy(AbilityScores::new(16, 8, 8, 8, 8, 8))
+            .build();
+        assert!(matches!(
+            result,
+            Err(BuilderError::PointBuyScoreOutOfRange { score: 16, .. })
+        ));
+    }
+
     #[test]
     fn test_roll_4d6_drop_lowest() {
         for _ in 0..100 {
@@ -699,6 +1070,42 @@ mod tests {
         // Wizard gets 2 first-level slots at level 1
         assert_eq!(spellcasting.spell_slots.slots[0].total, 2);
         assert_eq!(spellcasting.spell_slots.slots[0].used, 0);
+
+        // Intelligence 16 + level 1 prepares four spells from the spellbook
+        assert_eq!(spellcasting.spells_prepared.len(), 4);
+        assert!(spellcasting
+            .spells_prepared
+            .iter()
+            .all(|s| spellcasting.spells_known.contains(s)));
+    }
+
+    #[test]
+    fn test_cleric_starts_with_spells_prepared() {
+        let character = CharacterBuilder::new()
+            .name("Brother Aldric")
+            .race(RaceType::Human)
+            .class(CharacterClass::Cleric)
+            .background(Background::Acolyte)
+            .standard_array([
+                (15, Ability::Wisdom),
+                (14, Ability::Constitution),
+                (13, Ability::Strength),
+                (12, Ability::Charisma),
+                (10, Ability::Dexterity),
+                (8, Ability::Intelligence),
+            ])
+            .skills(vec![Skill::Medicine, Skill::Religion])
+            .build()
+            .expect("Should build successfully");
+
+        let spellcasting = character.spellcasting.as_ref().unwrap();
+        assert!(!spellcasting.spells_prepared.is_empty());
+        assert!(spellcasting.spells_prepared.len() <= character.prepared_spell_limit().unwrap());
+        assert!(spellcasting
+            .spells_prepared
+            .contains(&"Cure Wounds".to_string()));
+        // The default can still be swapped out before the first long rest
+        assert!(!spellcasting.prepared_since_rest);
     }
 
     #[test]
@@ -734,8 +1141,12 @@ mod tests {
         // Warlock knows 2 spells at level 1
         assert_eq!(spellcasting.spells_known.len(), 2);
 
-        // Warlock gets only 1 first-level slot (Pact Magic)
-        assert_eq!(spellcasting.spell_slots.slots[0].total, 1);
+        // Warlock gets only 1 first-level slot, through Pact Magic
+        assert_eq!(spellcasting.spell_slots.slots[0].total, 0);
+        let pact = spellcasting
+            .pact_slots
+            .expect("Warlock should have Pact Magic");
+        assert_eq!((pact.count, pact.level), (1, 1));
     }
 
     #[test]
@@ -760,4 +1171,233 @@ mod tests {
         // Fighter is not a spellcaster at level 1
         assert!(character.spellcasting.is_none());
     }
+
+    fn build_with_languages(
+        race: RaceType,
+        background: Background,
+        languages: Vec<Language>,
+    ) -> Character {
+        CharacterBuilder::new()
+            .name("Linguist")
+            .race(race)
+            .class(CharacterClass::Fighter)
+            .background(background)
+            .standard_array([
+                (15, Ability::Strength),
+                (14, Ability::Constitution),
+                (13, Ability::Dexterity),
+                (12, Ability::Wisdom),
+                (10, Ability::Intelligence),
+                (8, Ability::Charisma),
+            ])
+            .half_elf_bonuses([Ability::Strength, Ability::Constitution])
+            .skills(vec![Skill::Athletics, Skill::Perception])
+            .languages(languages)
+            .build()
+            .expect("Should build successfully")
+    }
+
+    #[test]
+    fn test_elf_understands_elvish_but_not_draconic() {
+        let elf = build_with_languages(RaceType::Elf, Background::Soldier, vec![]);
+        assert_eq!(
+            elf.languages,
+            HashSet::from([Language::Common, Language::Elvish])
+        );
+        assert!(elf.understands(Language::Elvish));
+        assert!(!elf.understands(Language::Draconic));
+    }
+
+    #[test]
+    fn test_language_picks_limited_by_race_and_background() {
+        // Human (1 pick) + Sage (2 picks); Dwarvish is requested twice
+        let sage = build_with_languages(
+            RaceType::Human,
+            Background::Sage,
+            vec![
+                Language::Dwarvish,
+                Language::Dwarvish,
+                Language::Elvish,
+                Language::Draconic,
+                Language::Infernal,
+            ],
+        );
+        assert_eq!(sage.languages.len(), 4);
+        assert!(sage.understands(Language::Draconic));
+        assert!(!sage.understands(Language::Infernal));
+
+        // Soldier grants no picks, and dwarves have none either
+        let dwarf =
+            build_with_languages(RaceType::Dwarf, Background::Soldier, vec![Language::Giant]);
+        assert!(dwarf.understands(Language::Dwarvish));
+        assert!(!dwarf.understands(Language::Giant));
+    }
+
+    #[test]
+    fn test_background_proficiencies_and_feature() {
+        // Human fighter: INT 11 (+0), DEX 14 (+2)
+        let sage = build_with_languages(RaceType::Human, Background::Sage, vec![]);
+        assert!(sage.skill_proficiencies.contains_key(&Skill::Arcana));
+        assert!(sage.skill_proficiencies.contains_key(&Skill::History));
+        assert_eq!(sage.skill_modifier(Skill::Arcana), 2);
+        assert!(sage.features.iter().any(|f| f.name == "Researcher"));
+
+        let criminal = build_with_languages(RaceType::Human, Background::Criminal, vec![]);
+        assert!(criminal.skill_proficiencies.contains_key(&Skill::Deception));
+        assert_eq!(criminal.skill_modifier(Skill::Stealth), 4);
+        assert!(criminal
+            .tool_proficiencies
+            .contains(&"Thieves' Tools".to_string()));
+        assert!(criminal
+            .features
+            .iter()
+            .any(|f| f.name == "Criminal Contact"));
+    }
+
+    #[test]
+    fn test_dwarf_racial_traits() {
+        let dwarf = build_with_languages(RaceType::Dwarf, Background::Soldier, vec![]);
+        assert_eq!(dwarf.ability_scores.constitution, 16);
+        assert_eq!(dwarf.ability_scores.strength, 15);
+        assert_eq!(dwarf.senses.darkvision_ft, 60);
+        assert_eq!(dwarf.speed.walk, 25);
+        // HP uses the boosted CON: 10 + 3
+        assert_eq!(dwarf.hit_points.maximum, 13);
+
+        let elf = build_with_languages(RaceType::Elf, Background::Sage, vec![]);
+        assert_eq!(elf.ability_scores.dexterity, 15);
+        assert!(elf.skill_proficiencies.contains_key(&Skill::Perception));
+    }
+
+    #[test]
+    fn test_half_elf_chooses_two_bonuses() {
+        let half_elf = build_with_languages(RaceType::HalfElf, Background::Soldier, vec![]);
+        assert_eq!(half_elf.ability_scores.charisma, 10);
+        assert_eq!(half_elf.ability_scores.strength, 16);
+        assert_eq!(half_elf.ability_scores.constitution, 15);
+        assert_eq!(half_elf.ability_scores.dexterity, 13);
+
+        let builder = CharacterBuilder::new()
+            .name("Test")
+            .race(RaceType::HalfElf)
+            .class(CharacterClass::Fighter)
+            .background(Background::Soldier)
+            .ability_scores(AbilityScores::new(15, 14, 13, 12, 10, 8))
+            .skills(vec![Skill::Athletics, Skill::Perception]);
+        assert!(matches!(
+            builder.clone().build(),
+            Err(BuilderError::HalfElfNeedsBonusAbilities)
+        ));
+        assert!(matches!(
+            builder
+                .clone()
+                .half_elf_bonuses([Ability::Strength, Ability::Strength])
+                .build(),
+            Err(BuilderError::InvalidHalfElfBonuses)
+        ));
+        assert!(matches!(
+            builder
+                .half_elf_bonuses([Ability::Charisma, Ability::Strength])
+                .build(),
+            Err(BuilderError::InvalidHalfElfBonuses)
+        ));
+    }
+
+    fn equipped(class: CharacterClass, skills: Vec<Skill>, choices: EquipmentChoices) -> Character {
+        CharacterBuilder::new()
+            .name("Recruit")
+            .race(RaceType::Human)
+            .class(class)
+            .background(Background::Soldier)
+            .ability_scores(AbilityScores::new(15, 14, 13, 12, 10, 8))
+            .skills(skills)
+            .with_starting_equipment(choices)
+            .build()
+            .expect("Should build successfully")
+    }
+
+    #[test]
+    fn test_fighter_starts_armored() {
+        let fighter = equipped(
+            CharacterClass::Fighter,
+            vec![Skill::Athletics, Skill::Perception],
+            EquipmentChoices::default(),
+        );
+        let equipment = &fighter.equipment;
+        assert_eq!(equipment.armor.as_ref().unwrap().base.name, "Chain Mail");
+        assert_eq!(equipment.main_hand.as_ref().unwrap().base.name, "Longsword");
+        assert!(equipment.shield.is_some());
+        // Chain mail 16 + shield 2
+        assert_eq!(fighter.current_ac(), 18);
+        assert_eq!(fighter.inventory.find_item("Torch").unwrap().quantity, 10);
+        assert_eq!(fighter.inventory.purse.gp, 10);
+
+        // A two-handed pick leaves the shield in the pack
+        let fighter = equipped(
+            CharacterClass::Fighter,
+            vec![Skill::Athletics, Skill::Perception],
+            EquipmentChoices {
+                weapon: Some("Greatsword".to_string()),
+                armor: None,
+            },
+        );
+        assert_eq!(
+            fighter.equipment.main_hand.as_ref().unwrap().base.name,
+            "Greatsword"
+        );
+        assert!(fighter.equipment.shield.is_none());
+        assert!(fighter.inventory.has_item("Shield"));
+    }
+
+    #[test]
+    fn test_wizard_starts_with_spellbook() {
+        let wizard = equipped(
+            CharacterClass::Wizard,
+            vec![Skill::Arcana, Skill::History],
+            EquipmentChoices::default(),
+        );
+        assert!(wizard.inventory.has_item("Spellbook"));
+        assert!(wizard.inventory.has_item("Component Pouch"));
+        assert!(wizard.equipment.armor.is_none());
+        assert_eq!(
+            wizard.equipment.main_hand.as_ref().unwrap().base.name,
+            "Quarterstaff"
+        );
+
+        let result = CharacterBuilder::new()
+            .name("Recruit")
+            .race(RaceType::Human)
+            .class(CharacterClass::Wizard)
+            .background(Background::Sage)
+            .ability_scores(AbilityScores::new(8, 14, 13, 15, 12, 10))
+            .skills(vec![Skill::Arcana, Skill::History])
+            .with_starting_equipment(EquipmentChoices {
+                weapon: Some("Vorpal Spoon".to_string()),
+                armor: None,
+            })
+            .build();
+        assert!(matches!(result, Err(BuilderError::UnknownItem(name)) if name == "Vorpal Spoon"));
+    }
+
+    #[test]
+    fn test_feat_at_creation() {
+        let builder = CharacterBuilder::new()
+            .name("Recruit")
+            .race(RaceType::Human)
+            .class(CharacterClass::Wizard)
+            .background(Background::Sage)
+            .ability_scores(AbilityScores::new(8, 14, 13, 15, 12, 10))
+            .skills(vec![Skill::Arcana, Skill::History]);
+
+        let lucky = builder.clone().with_feat("Lucky", None).build().unwrap();
+        assert!(lucky.has_feat("Lucky"));
+
+        let result = builder
+            .with_feat("Resilient", Some(Ability::Constitution))
+            .build();
+        assert!(matches!(
+            result,
+            Err(BuilderError::Feat(FeatError::UnmetPrerequisite { .. }))
+        ));
+    }
 }
@@ -1,9 +1,13 @@
 //! D&D 5e class data for character creation.
 //!
-//! Contains saving throw proficiencies, skill options, and level 1 features
-//! for all 12 PHB classes.
+//! Contains saving throw, weapon, and armor proficiencies, skill options, and
+//! level 1 features for all 12 PHB classes, plus the features gained on
+//! leveling up.
 
-use crate::world::{Ability, CharacterClass, Feature, FeatureUses, RechargeType, Skill};
+use crate::world::{
+    Ability, ArmorProficiency, CharacterClass, Feature, FeatureUses, RechargeType, Skill,
+    WeaponProficiency,
+};
 
 /// Class-specific data for character creation.
 pub struct ClassData {
@@ -15,6 +19,10 @@ pub struct ClassData {
     pub skill_options: &'static [Skill],
     /// Starting HP at level 1 (hit die max, before CON modifier).
     pub base_hp: i32,
+    /// Weapons the class is proficient with.
+    pub weapon_proficiencies: &'static [WeaponProficiency],
+    /// Armor training granted by the class.
+    pub armor_proficiencies: &'static [ArmorProficiency],
     /// Features gained at level 1.
     pub level_1_features: Vec<Feature>,
 }
@@ -35,6 +43,8 @@ impl CharacterClass {
                     Skill::Survival,
                 ],
                 base_hp: 12,
+                weapon_proficiencies: &[WeaponProficiency::Simple, WeaponProficiency::Martial],
+                armor_proficiencies: &[ArmorProficiency::Light, ArmorProficiency::Medium, ArmorProficiency::Shields],
                 level_1_features: vec![
                     Feature {
                         name: "Rage".to_string(),
@@ -71,6 +81,8 @@ impl CharacterClass {
                     Skill::Stealth, Skill::Survival,
                 ],
                 base_hp: 8,
+                weapon_proficiencies: &[WeaponProficiency::Simple],
+                armor_proficiencies: &[ArmorProficiency::Light],
                 level_1_features: vec![
                     Feature {
                         name: "Bardic Inspiration".to_string(),
@@ -101,6 +113,8 @@ impl CharacterClass {
                     Skill::Religion,
                 ],
                 base_hp: 8,
+                weapon_proficiencies: &[WeaponProficiency::Simple],
+                armor_proficiencies: &[ArmorProficiency::Light, ArmorProficiency::Medium, ArmorProficiency::Shields],
                 level_1_features: vec![
                     Feature {
                         name: "Spellcasting".to_string(),
@@ -130,6 +144,8 @@ impl CharacterClass {
                     Skill::Survival,
                 ],
                 base_hp: 8,
+                weapon_proficiencies: &[WeaponProficiency::Simple],
+                armor_proficiencies: &[ArmorProficiency::Light, ArmorProficiency::Shields],
                 level_1_features: vec![
                     Feature {
                         name: "Druidic".to_string(),
@@ -159,6 +175,8 @@ impl CharacterClass {
                     Skill::Survival,
                 ],
                 base_hp: 10,
+                weapon_proficiencies: &[WeaponProficiency::Simple, WeaponProficiency::Martial],
+                armor_proficiencies: &[ArmorProficiency::Light, ArmorProficiency::Medium, ArmorProficiency::Heavy, ArmorProficiency::Shields],
                 level_1_features: vec![
                     Feature {
                         name: "Fighting Style".to_string(),
@@ -196,6 +214,8 @@ impl CharacterClass {
                     Skill::Stealth,
                 ],
                 base_hp: 8,
+                weapon_proficiencies: &[WeaponProficiency::Simple, WeaponProficiency::MartialLight],
+                armor_proficiencies: &[],
                 level_1_features: vec![
                     Feature {
                         name: "Unarmored Defense".to_string(),
@@ -223,6 +243,8 @@ impl CharacterClass {
                     Skill::Religion,
                 ],
                 base_hp: 10,
+                weapon_proficiencies: &[WeaponProficiency::Simple, WeaponProficiency::Martial],
+                armor_proficiencies: &[ArmorProficiency::Light, ArmorProficiency::Medium, ArmorProficiency::Heavy, ArmorProficiency::Shields],
                 level_1_features: vec![
                     Feature {
                         name: "Lay on Hands".to_string(),
@@ -262,6 +284,8 @@ impl CharacterClass {
                     Skill::Survival,
                 ],
                 base_hp: 10,
+                weapon_proficiencies: &[WeaponProficiency::Simple, WeaponProficiency::Martial],
+                armor_proficiencies: &[ArmorProficiency::Light, ArmorProficiency::Medium, ArmorProficiency::Shields],
                 level_1_features: vec![
                     Feature {
                         name: "Spellcasting".to_string(),
@@ -305,6 +329,8 @@ impl CharacterClass {
                     Skill::Athletics,
                 ],
                 base_hp: 8,
+                weapon_proficiencies: &[WeaponProficiency::Simple, WeaponProficiency::MartialFinesseOrLight],
+                armor_proficiencies: &[ArmorProficiency::Light],
                 level_1_features: vec![
                     Feature {
                         name: "Expertise".to_string(),
@@ -344,6 +370,8 @@ impl CharacterClass {
                     Skill::Religion,
                 ],
                 base_hp: 6,
+                weapon_proficiencies: &[WeaponProficiency::Simple],
+                armor_proficiencies: &[],
                 level_1_features: vec![
                     Feature {
                         name: "Spellcasting".to_string(),
@@ -372,6 +400,8 @@ impl CharacterClass {
                     Skill::Religion,
                 ],
                 base_hp: 8,
+                weapon_proficiencies: &[WeaponProficiency::Simple],
+                armor_proficiencies: &[ArmorProficiency::Light],
                 level_1_features: vec![
                     Feature {
                         name: "Otherworldly Patron".to_string(),
@@ -399,6 +429,8 @@ impl CharacterClass {
                     Skill::Religion,
                 ],
                 base_hp: 6,
+                weapon_proficiencies: &[WeaponProficiency::Simple],
+                armor_proficiencies: &[],
                 level_1_features: vec![
                     Feature {
                         name: "Spellcasting".to_string(),
@@ -421,6 +453,100 @@ impl CharacterClass {
         }
     }
 
+    /// Features gained on reaching `level` (levels 2-5; subclass features are
+    /// chosen separately).
+    pub fn features_at_level(&self, level: u8) -> Vec<Feature> {
+        let table: &[(u8, &str, &str)] = match self {
+            CharacterClass::Barbarian => &[
+                (2, "Danger Sense", "Advantage on DEX saving throws against effects you can see."),
+                (2, "Reckless Attack", "Gain advantage on STR attack rolls this turn; attacks against you have advantage until your next turn."),
+                (3, "Primal Knowledge", "Gain proficiency in another Barbarian skill and use STR for certain checks while raging."),
+                (5, "Extra Attack", "Attack twice whenever you take the Attack action."),
+                (5, "Fast Movement", "Your speed increases by 10 feet while you aren't wearing heavy armor."),
+            ],
+            CharacterClass::Bard => &[
+                (2, "Expertise", "Double your proficiency bonus for two skills you are proficient in."),
+                (2, "Jack of All Trades", "Add half your proficiency bonus to ability checks you aren't proficient in."),
+                (5, "Font of Inspiration", "Regain all Bardic Inspiration uses on a short or long rest."),
+            ],
+            CharacterClass::Cleric => &[
+                (2, "Channel Divinity", "Channel divine energy once per rest: Turn Undead or Divine Spark."),
+                (5, "Sear Undead", "Undead that fail the save against Turn Undead also take radiant damage."),
+            ],
+            CharacterClass::Druid => &[
+                (2, "Wild Shape", "Magically assume the shape of a beast twice per short rest."),
+                (2, "Wild Companion", "Expend a Wild Shape use to cast Find Familiar."),
+                (5, "Wild Resurgence", "Trade spell slots and Wild Shape uses for one another."),
+            ],
+            CharacterClass::Fighter => &[
+                (2, "Action Surge", "Take one additional action on your turn, once per short rest."),
+                (2, "Tactical Mind", "Expend a use of Second Wind to add 1d10 to a failed ability check."),
+                (5, "Extra Attack", "Attack twice whenever you take the Attack action."),
+                (5, "Tactical Shift", "When you use Second Wind, move up to half your speed without provoking opportunity attacks."),
+            ],
+            CharacterClass::Monk => &[
+                (2, "Monk's Focus", "Gain focus points equal to your monk level to fuel Flurry of Blows, Patient Defense, and Step of the Wind."),
+                (2, "Unarmored Movement", "Your speed increases by 10 feet while you aren't wearing armor or wielding a shield."),
+                (2, "Uncanny Metabolism", "When you roll initiative, regain all focus points and heal once per long rest."),
+                (3, "Deflect Attacks", "Use your reaction to reduce the damage of an attack that hits you."),
+                (4, "Slow Fall", "Use your reaction to reduce falling damage by five times your monk level."),
+                (5, "Extra Attack", "Attack twice whenever you take the Attack action."),
+                (5, "Stunning Strike", "Spend a focus point when you hit to force a CON save or stun the target."),
+            ],
+            CharacterClass::Paladin => &[
+                (2, "Fighting Style", "Adopt a fighting style that suits your combat approach."),
+                (2, "Paladin's Smite", "Expend a spell slot when you hit with a melee weapon to deal extra radiant damage."),
+                (3, "Channel Divinity", "Channel divine energy once per rest."),
+                (5, "Extra Attack", "Attack twice whenever you take the Attack action."),
+                (5, "Faithful Steed", "Always have Find Steed prepared and cast it once per long rest without a slot."),
+            ],
+            CharacterClass::Ranger => &[
+                (2, "Deft Explorer", "Gain expertise in one skill and learn two languages."),
+                (2, "Fighting Style", "Adopt a fighting style that suits your combat approach."),
+                (5, "Extra Attack", "Attack twice whenever you take the Attack action."),
+            ],
+            CharacterClass::Rogue => &[
+                (2, "Cunning Action", "Take the Dash, Disengage, or Hide action as a bonus action."),
+                (3, "Steady Aim", "If you haven't moved this turn, use a bonus action to gain advantage on your next attack."),
+                (5, "Cunning Strike", "Trade Sneak Attack dice for effects such as poisoning or tripping the target."),
+                (5, "Uncanny Dodge", "Use your reaction to halve the damage of an attack you can see."),
+            ],
+            CharacterClass::Sorcerer => &[
+                (2, "Font of Magic", "Gain sorcery points equal to your sorcerer level and convert them to and from spell slots."),
+                (2, "Metamagic", "Learn two Metamagic options to twist your spells."),
+                (5, "Sorcerous Restoration", "Regain sorcery points on a short rest once per long rest."),
+            ],
+            CharacterClass::Warlock => &[
+                (2, "Magical Cunning", "Perform a rite to regain half your Pact Magic slots once per long rest."),
+                (5, "Additional Invocation", "Learn another Eldritch Invocation."),
+            ],
+            CharacterClass::Wizard => &[
+                (2, "Scholar", "Gain expertise in one of Arcana, History, Investigation, Medicine, Nature, or Religion."),
+                (5, "Memorize Spell", "On a short rest, swap one prepared wizard spell for another in your spellbook."),
+            ],
+        };
+        table
+            .iter()
+            .filter(|(gained_at, _, _)| *gained_at == level)
+            .map(|(_, name, description)| Feature {
+                name: name.to_string(),
+                description: description.to_string(),
+                source: self.name().to_string(),
+                uses: None,
+            })
+            .collect()
+    }
+
+    /// Whether reaching `level` grants an Ability Score Improvement (or feat).
+    pub fn grants_asi(&self, level: u8) -> bool {
+        matches!(
+            (self, level),
+            (_, 4 | 8 | 12 | 16 | 19)
+                | (CharacterClass::Fighter, 6 | 14)
+                | (CharacterClass::Rogue, 10)
+        )
+    }
+
     /// Get a short description of the class.
     pub fn description(&self) -> &'static str {
         match self {
@@ -497,6 +623,18 @@ mod tests {
         assert_eq!(data.base_hp, 6);
     }
 
+    #[test]
+    fn test_fighter_gains_extra_attack_at_5() {
+        let features = CharacterClass::Fighter.features_at_level(5);
+        assert!(features.iter().any(|f| f.name == "Extra Attack"));
+        assert!(CharacterClass::Wizard
+            .features_at_level(5)
+            .iter()
+            .all(|f| f.name != "Extra Attack"));
+        assert!(CharacterClass::Fighter.grants_asi(6));
+        assert!(!CharacterClass::Wizard.grants_asi(6));
+    }
+
     #[test]
     fn test_rogue_gets_4_skills() {
         let data = CharacterClass::Rogue.data();
@@ -3,12 +3,37 @@
 //! Supports standard dice notation: XdY+Z, advantage/disadvantage,
 //! keep highest/lowest, and more.
 
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
+use std::sync::Mutex;
 use thiserror::Error;
 
+/// Process-wide seeded RNG; when unset, rolls use the thread RNG.
+static SEEDED_RNG: Mutex<Option<StdRng>> = Mutex::new(None);
+
+/// Make every subsequent roll deterministic, or go back to true randomness
+/// with `None`.
+///
+/// Together with a fixed list of player actions this makes a run reproducible.
+pub fn set_seed(seed: Option<u64>) {
+    *SEEDED_RNG.lock().unwrap_or_else(|e| e.into_inner()) = seed.map(StdRng::seed_from_u64);
+}
+
+/// Run `f` with the seeded RNG if one is set, otherwise the thread RNG.
+pub(crate) fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    let mut seeded = SEEDED_RNG.lock().unwrap_or_else(|e| e.into_inner());
+    match seeded.as_mut() {
+        Some(rng) => f(rng),
+        None => {
+            drop(seeded);
+            f(&mut rand::thread_rng())
+        }
+    }
+}
+
 /// Error type for dice parsing and rolling.
 #[derive(Debug, Error)]
 pub enum DiceError {
@@ -221,7 +246,7 @@ impl DiceExpression {
 
     /// Roll the dice expression and return the result.
     pub fn roll(&self) -> RollResult {
-        self.roll_with_rng(&mut rand::thread_rng())
+        with_rng(|mut rng| self.roll_with_rng(&mut rng))
     }
 
     /// Roll with a specific RNG (useful for testing).
@@ -278,7 +303,7 @@ impl DiceExpression {
 
     /// Roll with advantage/disadvantage (only applies to single d20 rolls).
     pub fn roll_with_advantage(&self, advantage: Advantage) -> RollResult {
-        self.roll_with_advantage_rng(advantage, &mut rand::thread_rng())
+        with_rng(|mut rng| self.roll_with_advantage_rng(advantage, &mut rng))
     }
 
     pub fn roll_with_advantage_rng<R: Rng>(&self, advantage: Advantage, rng: &mut R) -> RollResult {
@@ -433,6 +458,25 @@ impl RollResult {
         self.natural_20
     }
 
+    /// The kept face of a single d20, if this roll contained one.
+    pub fn natural_d20(&self) -> Option<u32> {
+        self.component_results
+            .iter()
+            .find(|c| c.die_type == DieType::D20 && c.kept.len() == 1)
+            .map(|c| c.kept[0])
+    }
+
+    /// Check if this was a critical hit given the lowest natural roll that crits.
+    ///
+    /// A `crit_range` of 20 behaves like [`RollResult::is_critical`]; 19 means
+    /// a natural 19 or 20 crits, and so on.
+    pub fn is_critical_with_range(&self, crit_range: u8) -> bool {
+        self.natural_20
+            || self
+                .natural_d20()
+                .is_some_and(|face| face >= crit_range as u32)
+    }
+
     /// Check if this was a critical failure (natural 1 on attack).
     pub fn is_fumble(&self) -> bool {
         self.natural_1
@@ -528,6 +572,32 @@ mod tests {
         }
     }
 
+    fn d20_result(face: u32) -> RollResult {
+        RollResult {
+            expression: DiceExpression::parse("1d20").unwrap(),
+            component_results: vec![ComponentResult {
+                die_type: DieType::D20,
+                rolls: vec![face],
+                kept: vec![face],
+                subtotal: face,
+            }],
+            modifier: 0,
+            total: face as i32,
+            natural_20: face == 20,
+            natural_1: face == 1,
+        }
+    }
+
+    #[test]
+    fn test_critical_with_range() {
+        assert!(!d20_result(19).is_critical_with_range(20));
+        assert!(d20_result(19).is_critical_with_range(19));
+        assert!(d20_result(20).is_critical_with_range(19));
+        assert!(!d20_result(18).is_critical_with_range(19));
+        assert!(d20_result(18).is_critical_with_range(18));
+        assert_eq!(d20_result(7).natural_d20(), Some(7));
+    }
+
     #[test]
     fn test_advantage_combine() {
         assert_eq!(
@@ -11,9 +11,12 @@ use super::story_memory::{
 };
 use super::tools::{execute_info_tool, parse_tool_call, DmTools};
 use crate::rules::{apply_effects, Effect, Intent, Resolution, RulesEngine};
-use crate::world::{GameMode, GameWorld, NarrativeType};
-use claude::{Claude, ContentBlock, Message, Request, StopReason, StreamEvent, ToolResult};
+use crate::world::{GameMode, GameWorld, NarrativeType, QuestStatus};
+use claude::{
+    Claude, ContentBlock, LlmProvider, Message, Request, StopReason, StreamEvent, ToolResult, Usage,
+};
 use futures::StreamExt;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Errors from the DM agent.
@@ -70,11 +73,28 @@ pub struct DmResponse {
 
     /// Resolution details for each intent.
     pub resolutions: Vec<Resolution>,
+
+    /// Tokens used across every API call that produced this response.
+    pub usage: Usage,
+}
+
+/// Add one API call's token usage to a running total.
+fn add_usage(total: &mut Usage, usage: &Usage) {
+    total.input_tokens += usage.input_tokens;
+    total.output_tokens += usage.output_tokens;
+    total.cache_read_tokens += usage.cache_read_tokens;
+    total.cache_write_tokens += usage.cache_write_tokens;
 }
 
+/// How many times to retry overloaded or rate-limited API calls.
+const API_RETRIES: u32 = 3;
+
+/// Starting delay for API retry backoff.
+const API_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
 /// The AI Dungeon Master.
 pub struct DungeonMaster {
-    client: Claude,
+    client: Arc<dyn LlmProvider>,
     config: DmConfig,
     memory: DmMemory,
     story_memory: StoryMemory,
@@ -84,25 +104,33 @@ pub struct DungeonMaster {
 impl DungeonMaster {
     /// Create a new DungeonMaster with an API key.
     pub fn new(api_key: impl Into<String>) -> Self {
-        Self {
-            client: Claude::new(api_key),
-            config: DmConfig::default(),
-            memory: DmMemory::new(),
-            story_memory: StoryMemory::new(),
-            rules: RulesEngine::new(),
-        }
+        Self::with_provider(Arc::new(
+            Claude::new(api_key).with_retry(API_RETRIES, API_RETRY_DELAY),
+        ))
     }
 
     /// Create a DungeonMaster from the ANTHROPIC_API_KEY environment variable.
     pub fn from_env() -> Result<Self, DmError> {
-        let client = Claude::from_env()?;
-        Ok(Self {
+        let client = Claude::from_env()?.with_retry(API_RETRIES, API_RETRY_DELAY);
+        Ok(Self::with_provider(Arc::new(client)))
+    }
+
+    /// Create a DungeonMaster backed by any model provider, e.g. a local
+    /// model or a scripted one in tests.
+    pub fn with_provider(client: Arc<dyn LlmProvider>) -> Self {
+        Self {
             client,
             config: DmConfig::default(),
             memory: DmMemory::new(),
             story_memory: StoryMemory::new(),
             rules: RulesEngine::new(),
-        })
+        }
+    }
+
+    /// Use a preconfigured client, e.g. one pointed at a different base URL.
+    pub fn with_client(mut self, client: Claude) -> Self {
+        self.client = Arc::new(client);
+        self
     }
 
     /// Get the story memory.
@@ -147,11 +175,14 @@ impl DungeonMaster {
         world.add_narrative(player_input.to_string(), NarrativeType::PlayerAction);
 
         // Check for relevant consequences using fast model (Haiku)
-        let relevance_result = self.check_relevance(player_input, world).await?;
+        let mut relevance_result = self.check_relevance(player_input, world).await?;
 
         // Mark triggered consequences
         self.apply_relevance_results(&relevance_result);
 
+        // Catch triggers the model missed with a keyword match on the action and narration
+        self.apply_keyword_triggers(player_input, world, &mut relevance_result);
+
         // Build system prompt with story context for this input
         let mut system_prompt = self.build_system_prompt(world, player_input);
 
@@ -163,9 +194,10 @@ impl DungeonMaster {
 
         // Track intents, effects, and resolutions
         let mut all_intents = Vec::new();
-        let mut all_effects = Vec::new();
+        let mut all_effects = self.triggered_consequence_effects(&relevance_result);
         let mut all_resolutions = Vec::new();
         let mut narrative = String::new();
+        let mut usage = Usage::default();
 
         // Build initial messages
         let mut messages = self.memory.get_messages();
@@ -176,6 +208,7 @@ impl DungeonMaster {
 
             let mut request = Request::new(messages.clone())
                 .with_system(&system_prompt)
+                .with_cache_control()
                 .with_max_tokens(self.config.max_tokens)
                 .with_tools(tools);
 
@@ -189,6 +222,7 @@ impl DungeonMaster {
 
             // Make API call
             let response = self.client.complete(request).await?;
+            add_usage(&mut usage, &response.usage);
 
             // Collect tool uses
             let mut tool_uses = Vec::new();
@@ -304,6 +338,7 @@ impl DungeonMaster {
             intents: all_intents,
             effects: all_effects,
             resolutions: all_resolutions,
+            usage,
         })
     }
 
@@ -330,11 +365,14 @@ impl DungeonMaster {
         world.add_narrative(player_input.to_string(), NarrativeType::PlayerAction);
 
         // Check for relevant consequences using fast model (Haiku)
-        let relevance_result = self.check_relevance(player_input, world).await?;
+        let mut relevance_result = self.check_relevance(player_input, world).await?;
 
         // Mark triggered consequences
         self.apply_relevance_results(&relevance_result);
 
+        // Catch triggers the model missed with a keyword match on the action and narration
+        self.apply_keyword_triggers(player_input, world, &mut relevance_result);
+
         // Build system prompt with story context for this input
         let mut system_prompt = self.build_system_prompt(world, player_input);
 
@@ -346,9 +384,10 @@ impl DungeonMaster {
 
         // Track intents, effects, and resolutions
         let mut all_intents = Vec::new();
-        let mut all_effects = Vec::new();
+        let mut all_effects = self.triggered_consequence_effects(&relevance_result);
         let mut all_resolutions = Vec::new();
         let mut narrative = String::new();
+        let mut usage = Usage::default();
 
         // Build initial messages
         let mut messages = self.memory.get_messages();
@@ -368,6 +407,7 @@ impl DungeonMaster {
 
             let mut request = Request::new(messages.clone())
                 .with_system(&system_prompt)
+                .with_cache_control()
                 .with_max_tokens(self.config.max_tokens)
                 .with_tools(tools);
 
@@ -386,6 +426,7 @@ impl DungeonMaster {
             let mut tool_uses: Vec<PartialToolUse> = Vec::new();
             let mut current_tool_index: Option<usize> = None;
             let mut stop_reason = StopReason::EndTurn;
+            let mut message_usage = Usage::default();
 
             while let Some(event_result) = stream.next().await {
                 let event = event_result?;
@@ -400,16 +441,14 @@ impl DungeonMaster {
                         content_type,
                         tool_use_id,
                         tool_name,
-                    } => {
-                        if content_type == "tool_use" {
-                            // Start accumulating a new tool use
-                            current_tool_index = Some(index);
-                            tool_uses.push(PartialToolUse {
-                                id: tool_use_id.unwrap_or_default(),
-                                name: tool_name.unwrap_or_default(),
-                                json_buffer: String::new(),
-                            });
-                        }
+                    } if content_type == "tool_use" => {
+                        // Start accumulating a new tool use
+                        current_tool_index = Some(index);
+                        tool_uses.push(PartialToolUse {
+                            id: tool_use_id.unwrap_or_default(),
+                            name: tool_name.unwrap_or_default(),
+                            json_buffer: String::new(),
+                        });
                     }
                     StreamEvent::InputJsonDelta { index, partial_json } => {
                         // Accumulate JSON for the current tool use
@@ -421,25 +460,44 @@ impl DungeonMaster {
                             }
                         }
                     }
-                    StreamEvent::ContentBlockStop { index } => {
-                        // Reset current tool index if this was a tool block
-                        if Some(index) == current_tool_index {
-                            current_tool_index = None;
-                        }
+                    // Reset current tool index if this was a tool block
+                    StreamEvent::ContentBlockStop { index } if Some(index) == current_tool_index => {
+                        current_tool_index = None;
+                    }
+                    // Input and cache tokens arrive with message_start
+                    StreamEvent::MessageStart {
+                        usage: Some(start_usage),
+                        ..
+                    } => {
+                        message_usage = start_usage;
                     }
                     StreamEvent::MessageDelta {
-                        stop_reason: Some(sr),
+                        stop_reason: delta_stop_reason,
+                        usage: delta_usage,
+                        ..
                     } => {
-                        stop_reason = sr;
+                        if let Some(sr) = delta_stop_reason {
+                            stop_reason = sr;
+                        }
+                        // Output tokens on the delta are the message's running total
+                        if let Some(ref delta_usage) = delta_usage {
+                            message_usage.update(delta_usage);
+                        }
                     }
-                    StreamEvent::Error { message } => {
-                        return Err(DmError::ToolError(format!("Stream error: {message}")));
+                    StreamEvent::Error {
+                        error_type,
+                        message,
+                    } => {
+                        return Err(DmError::ToolError(format!(
+                            "Stream error ({error_type}): {message}"
+                        )));
                     }
                     _ => {
-                        // Ignore other events (MessageStart, MessageStop, Ping, etc.)
+                        // Ignore other events (MessageStop, Ping, etc.)
                     }
                 }
             }
+            add_usage(&mut usage, &message_usage);
 
             // If no tool calls or stop reason isn't ToolUse, we're done
             if stop_reason != StopReason::ToolUse || tool_uses.is_empty() {
@@ -569,6 +627,7 @@ impl DungeonMaster {
             intents: all_intents,
             effects: all_effects,
             resolutions: all_resolutions,
+            usage,
         })
     }
 
@@ -639,6 +698,12 @@ impl DungeonMaster {
             prompt.push_str(&format!(" ({})", class_info.join("/")));
         }
         prompt.push('\n');
+        if pc.pending_asi > 0 {
+            prompt.push_str(&format!(
+                "**Pending Ability Score Improvements:** {} (offer an ASI, or a feat via take_feat)\n",
+                pc.pending_asi
+            ));
+        }
         prompt.push_str(&format!("**Race:** {}\n", pc.race.name));
         prompt.push_str(&format!(
             "**Background:** {} - {}\n",
@@ -650,6 +715,19 @@ impl DungeonMaster {
             pc.hit_points.current, pc.hit_points.maximum
         ));
         prompt.push_str(&format!("**AC:** {}\n", pc.current_ac()));
+        let mut languages: Vec<_> = pc.languages.iter().collect();
+        languages.sort();
+        let languages: Vec<_> = languages.iter().map(|l| l.name()).collect();
+        prompt.push_str(&format!("**Languages:** {}\n", languages.join(", ")));
+        if !pc.tool_proficiencies.is_empty() {
+            prompt.push_str(&format!(
+                "**Tool Proficiencies:** {}\n",
+                pc.tool_proficiencies.join(", ")
+            ));
+        }
+        if pc.has_inspiration {
+            prompt.push_str("**Inspiration:** available\n");
+        }
 
         // Add backstory if present
         if let Some(ref backstory) = pc.backstory {
@@ -662,8 +740,8 @@ impl DungeonMaster {
             prompt.push_str(&format!(
                 "- Ability: {} (DC {}, +{} to hit)\n",
                 spellcasting.ability.name(),
-                spellcasting.spell_save_dc(&pc.ability_scores, pc.proficiency_bonus()),
-                spellcasting.spell_attack_bonus(&pc.ability_scores, pc.proficiency_bonus())
+                spellcasting.spell_save_dc(&pc.effective_scores(), pc.proficiency_bonus()),
+                spellcasting.spell_attack_bonus(&pc.effective_scores(), pc.proficiency_bonus())
             ));
             if !spellcasting.cantrips_known.is_empty() {
                 prompt.push_str(&format!(
@@ -676,11 +754,19 @@ impl DungeonMaster {
                     "- Prepared Spells: {}\n",
                     spellcasting.spells_prepared.join(", ")
                 ));
-            } else if !spellcasting.spells_known.is_empty() {
-                prompt.push_str(&format!(
-                    "- Known Spells: {}\n",
-                    spellcasting.spells_known.join(", ")
-                ));
+            } else {
+                // Casters that prepare can't cast leveled spells until they do
+                if let Some(limit) = pc.prepared_spell_limit() {
+                    prompt.push_str(&format!(
+                        "- Prepared Spells: none yet; ask the player which spells (up to {limit}) to prepare and record them with prepare_spells\n"
+                    ));
+                }
+                if !spellcasting.spells_known.is_empty() {
+                    prompt.push_str(&format!(
+                        "- Known Spells: {}\n",
+                        spellcasting.spells_known.join(", ")
+                    ));
+                }
             }
             // Show available spell slots
             let slots: Vec<String> = spellcasting
@@ -694,6 +780,14 @@ impl DungeonMaster {
             if !slots.is_empty() {
                 prompt.push_str(&format!("- Spell Slots: {}\n", slots.join(", ")));
             }
+            if let Some(pact) = spellcasting.pact_slots {
+                prompt.push_str(&format!(
+                    "- Pact Slots (level {}): {}/{}\n",
+                    pact.level,
+                    pact.available(),
+                    pact.count
+                ));
+            }
         }
 
         // Add ability scores
@@ -707,9 +801,42 @@ impl DungeonMaster {
             pc.ability_scores.charisma
         ));
 
+        // Add party companions
+        if !world.party.is_empty() {
+            prompt.push_str("\n## Party Members\n");
+            for member in &world.party {
+                prompt.push_str(&format!(
+                    "- **{}** (level {}) - HP {}/{}, AC {}",
+                    member.name,
+                    member.level,
+                    member.hit_points.current,
+                    member.hit_points.maximum,
+                    member.current_ac()
+                ));
+                if !member.conditions.is_empty() {
+                    let conditions: Vec<_> = member
+                        .conditions
+                        .iter()
+                        .map(|c| c.condition.to_string())
+                        .collect();
+                    prompt.push_str(&format!(" [{}]", conditions.join(", ")));
+                }
+                prompt.push('\n');
+            }
+        }
+
         // Add current situation
         prompt.push_str("\n## Current Situation\n");
         prompt.push_str(&format!("Location: {}\n", world.current_location.name));
+        if !world.current_location.connections.is_empty() {
+            let exits: Vec<_> = world
+                .current_location
+                .connections
+                .iter()
+                .map(|c| c.destination_name.as_str())
+                .collect();
+            prompt.push_str(&format!("Known routes to: {}\n", exits.join(", ")));
+        }
         prompt.push_str(&format!(
             "Time: {} ({})\n",
             world.game_time.time_of_day(),
@@ -721,6 +848,23 @@ impl DungeonMaster {
         ));
         prompt.push_str(&format!("Mode: {:?}\n", world.mode));
 
+        // Open quests, so quest tools can name them
+        let active_quests: Vec<_> = world
+            .quests
+            .iter()
+            .filter(|q| q.status == QuestStatus::Active)
+            .collect();
+        if !active_quests.is_empty() {
+            prompt.push_str("\n## Quest Log\n");
+            for quest in active_quests {
+                prompt.push_str(&format!("- **{}**: {}\n", quest.name, quest.description));
+                for objective in &quest.objectives {
+                    let mark = if objective.completed { "x" } else { " " };
+                    prompt.push_str(&format!("  - [{mark}] {}\n", objective.description));
+                }
+            }
+        }
+
         // Combat info if in combat
         if world.mode == GameMode::Combat {
             // Include combat-specific narration guidelines
@@ -736,13 +880,24 @@ impl DungeonMaster {
                 for (i, c) in combat.combatants.iter().enumerate() {
                     let marker = if i == combat.turn_index { ">" } else { " " };
                     let hp_status = Self::describe_hp_status(c.current_hp, c.max_hp);
+                    let conditions = if c.conditions.is_empty() {
+                        String::new()
+                    } else {
+                        let names: Vec<String> = c
+                            .conditions
+                            .iter()
+                            .map(|a| a.condition.to_string())
+                            .collect();
+                        format!(" [{}]", names.join(", "))
+                    };
                     prompt.push_str(&format!(
-                        "{} {}. {} (init {}) - {}\n",
+                        "{} {}. {} (init {}) - {}{}\n",
                         marker,
                         i + 1,
                         c.name,
                         c.initiative,
-                        hp_status
+                        hp_status,
+                        conditions
                     ));
                 }
             }
@@ -770,6 +925,13 @@ impl DungeonMaster {
             prompt.push_str(&story_context);
         }
 
+        // Flag contradictory facts so the DM can reconcile them
+        let conflicts_context = self.story_memory.build_conflicts_context();
+        if !conflicts_context.is_empty() {
+            prompt.push('\n');
+            prompt.push_str(&conflicts_context);
+        }
+
         prompt
     }
 
@@ -898,7 +1060,7 @@ impl DungeonMaster {
             return Ok(RelevanceResult::default());
         }
 
-        let checker = RelevanceChecker::new(self.client.clone());
+        let checker = RelevanceChecker::with_provider(self.client.clone());
         let result = checker
             .check_relevance(player_input, &world.current_location.name, &self.story_memory)
             .await
@@ -914,6 +1076,44 @@ impl DungeonMaster {
         }
     }
 
+    /// Trigger pending consequences whose trigger keywords match the player's
+    /// input or the narration it answers, adding them to the relevance results.
+    ///
+    /// The location name is left out: it would fire "enters X" triggers on
+    /// anything the player does while in X.
+    pub fn apply_keyword_triggers(
+        &mut self,
+        player_input: &str,
+        world: &GameWorld,
+        results: &mut RelevanceResult,
+    ) {
+        let scene = world
+            .narrative_history
+            .iter()
+            .rev()
+            .find(|e| matches!(e.entry_type, NarrativeType::DmNarration))
+            .map_or("", |e| e.content.as_str());
+        let context = format!("{player_input} {scene}");
+        for consequence in self.story_memory.check_triggers(&context) {
+            if !results.triggered_consequences.contains(&consequence.id) {
+                results.triggered_consequences.push(consequence.id);
+            }
+        }
+    }
+
+    /// Effects announcing each triggered consequence, for the UI.
+    fn triggered_consequence_effects(&self, results: &RelevanceResult) -> Vec<Effect> {
+        results
+            .triggered_consequences
+            .iter()
+            .filter_map(|id| self.story_memory.get_consequence(*id))
+            .map(|consequence| Effect::ConsequenceTriggered {
+                consequence_id: consequence.id.to_string(),
+                consequence_description: consequence.consequence_description.clone(),
+            })
+            .collect()
+    }
+
     /// Build additional context for triggered consequences.
     fn build_triggered_consequences_context(&self, results: &RelevanceResult) -> String {
         if results.triggered_consequences.is_empty() {
@@ -953,7 +1153,7 @@ struct PartialToolUse {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::dm::story_memory::ConsequenceId;
+    use crate::dm::story_memory::{ConsequenceId, ConsequenceSeverity};
     use crate::world::{Character, CharacterClass, Location, LocationType};
 
     fn create_test_world() -> GameWorld {
@@ -985,6 +1185,7 @@ mod tests {
             intents: vec![],
             effects: vec![],
             resolutions: vec![],
+            usage: Usage::default(),
         };
         assert_eq!(response.narrative, "You enter the dark cave.");
         assert!(response.intents.is_empty());
@@ -1028,6 +1229,41 @@ mod tests {
         assert!(prompt.contains("Test Location"));
     }
 
+    #[test]
+    fn test_system_prompt_asks_unprepared_caster_to_prepare() {
+        use crate::character_builder::CharacterBuilder;
+        use crate::world::{Ability, Background, RaceType, Skill};
+
+        let mut cleric = CharacterBuilder::new()
+            .name("Brother Aldric")
+            .race(RaceType::Human)
+            .class(CharacterClass::Cleric)
+            .background(Background::Acolyte)
+            .standard_array([
+                (15, Ability::Wisdom),
+                (14, Ability::Constitution),
+                (13, Ability::Strength),
+                (12, Ability::Charisma),
+                (10, Ability::Dexterity),
+                (8, Ability::Intelligence),
+            ])
+            .skills(vec![Skill::Medicine, Skill::Religion])
+            .build()
+            .unwrap();
+        // As in a save from before characters started with spells prepared
+        cleric
+            .spellcasting
+            .as_mut()
+            .unwrap()
+            .spells_prepared
+            .clear();
+
+        let dm = DungeonMaster::new("test-key");
+        let world = GameWorld::new("Test Campaign", cleric);
+        let prompt = dm.build_system_prompt(&world, "I pray");
+        assert!(prompt.contains("prepare_spells"));
+    }
+
     #[test]
     fn test_relevance_result_triggers() {
         let result = RelevanceResult {
@@ -1064,6 +1300,36 @@ mod tests {
         assert!(context.is_empty());
     }
 
+    #[test]
+    fn test_keyword_triggers_join_relevance_results() {
+        let mut dm = DungeonMaster::new("test-key");
+        let mut world = create_test_world();
+        dm.story_memory.create_consequence(
+            "The player returns to the Test Location",
+            "The mayor demands payment",
+            ConsequenceSeverity::Moderate,
+        );
+
+        // Standing in the location isn't enough
+        let mut result = RelevanceResult::default();
+        dm.apply_keyword_triggers("I look around", &world, &mut result);
+        assert!(result.triggered_consequences.is_empty());
+
+        world.add_narrative(
+            "The road winds back to the Test Location.".to_string(),
+            NarrativeType::DmNarration,
+        );
+        dm.apply_keyword_triggers("I look around", &world, &mut result);
+
+        assert_eq!(result.triggered_consequences.len(), 1);
+        let effects = dm.triggered_consequence_effects(&result);
+        assert!(matches!(
+            &effects[..],
+            [Effect::ConsequenceTriggered { consequence_description, .. }]
+                if consequence_description == "The mayor demands payment"
+        ));
+    }
+
     #[test]
     fn test_partial_tool_use_struct() {
         let partial = PartialToolUse {
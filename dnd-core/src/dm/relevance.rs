@@ -5,8 +5,9 @@
 //! matching instead of just keyword matching.
 
 use super::story_memory::{ConsequenceId, EntityId, FactId, StoryMemory};
-use claude::{Claude, Message, Request};
+use claude::{Claude, LlmProvider, Message, Request};
 use serde::Deserialize;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Default model for relevance checking (fast and cheap).
@@ -73,13 +74,18 @@ struct RelevanceResponse {
 
 /// Checks relevance of stored consequences and facts against player input.
 pub struct RelevanceChecker {
-    client: Claude,
+    client: Arc<dyn LlmProvider>,
     model: String,
 }
 
 impl RelevanceChecker {
     /// Create a new relevance checker with the given API client.
     pub fn new(client: Claude) -> Self {
+        Self::with_provider(Arc::new(client))
+    }
+
+    /// Create a relevance checker backed by any model provider.
+    pub fn with_provider(client: Arc<dyn LlmProvider>) -> Self {
         Self {
             client,
             model: RELEVANCE_MODEL.to_string(),
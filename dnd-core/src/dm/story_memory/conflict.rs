@@ -0,0 +1,215 @@
+//! Contradiction detection between story facts.
+//!
+//! Over a long campaign the DM may record "The innkeeper is dead" and later
+//! "The innkeeper greets you warmly". Flagging such pairs lets the DM notice
+//! and reconcile them (for example by superseding the stale fact).
+
+use super::entity::EntityId;
+use super::fact::{FactId, StoryFact};
+use serde::{Deserialize, Serialize};
+
+/// Two current facts about the same entity that appear to contradict each other.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactConflict {
+    /// The entity both facts are about.
+    pub entity: EntityId,
+    /// The earlier of the two facts.
+    pub first: FactId,
+    /// The later of the two facts.
+    pub second: FactId,
+    /// Why the checker thinks these contradict.
+    pub reason: String,
+}
+
+/// Decides whether two facts about the same entity contradict each other.
+pub trait ConflictChecker {
+    /// Return a reason if the facts conflict, or `None` if they're compatible.
+    fn check(&self, first: &StoryFact, second: &StoryFact) -> Option<String>;
+}
+
+/// Words that flip the meaning of a statement.
+const NEGATIONS: &[&str] = &[
+    "not", "no", "never", "isnt", "wasnt", "arent", "werent", "doesnt", "dont", "didnt", "cannot",
+    "cant", "wont", "longer",
+];
+
+/// Words ignored when comparing what two facts are about.
+const STOPWORDS: &[&str] = &[
+    "the", "and", "you", "your", "was", "are", "were", "has", "have", "had", "his", "her", "its",
+    "their", "with", "that", "this", "now", "still", "any", "anymore",
+];
+
+/// Pairs of opposing word groups. A fact using a word from one side conflicts
+/// with a fact about the same entity using a word from the other. Entries may
+/// be phrases; the "dead" side holds only words describing the subject's state,
+/// since "X killed Y" says nothing about whether X is alive.
+const ANTONYMS: &[(&[&str], &[&str])] = &[
+    (
+        &["dead", "died", "deceased", "was killed", "was slain"],
+        &[
+            "alive", "living", "greets", "greeted", "speaks", "spoke", "says", "said", "smiles",
+            "laughs", "waves", "walks", "talks",
+        ],
+    ),
+    (
+        &["friendly", "ally", "allied", "trusts", "trusted"],
+        &["hostile", "enemy", "distrusts", "hates"],
+    ),
+    (
+        &["loyal", "faithful"],
+        &["traitor", "betrayed", "treacherous"],
+    ),
+    (&["open", "unlocked"], &["closed", "locked", "sealed"]),
+    (&["married"], &["unmarried", "single", "widowed"]),
+];
+
+/// Default checker using negation and antonym heuristics.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicConflictChecker;
+
+impl HeuristicConflictChecker {
+    /// Lowercased words with apostrophes removed ("isn't" becomes "isnt").
+    fn words(text: &str) -> Vec<String> {
+        text.to_lowercase()
+            .replace('\'', "")
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn is_negated(words: &[String]) -> bool {
+        words.iter().any(|w| NEGATIONS.contains(&w.as_str()))
+    }
+
+    /// Words that carry the meaning of the statement.
+    fn content_words(words: &[String]) -> Vec<&str> {
+        words
+            .iter()
+            .map(String::as_str)
+            .filter(|w| w.len() >= 3 && !NEGATIONS.contains(w) && !STOPWORDS.contains(w))
+            .collect()
+    }
+
+    /// The first entry of `group` appearing as contiguous whole words.
+    fn uses_any<'a>(words: &[String], group: &[&'a str]) -> Option<&'a str> {
+        group
+            .iter()
+            .find(|candidate| {
+                let phrase: Vec<&str> = candidate.split(' ').collect();
+                words
+                    .windows(phrase.len())
+                    .any(|window| window.iter().zip(&phrase).all(|(w, p)| w == p))
+            })
+            .copied()
+    }
+}
+
+impl ConflictChecker for HeuristicConflictChecker {
+    fn check(&self, first: &StoryFact, second: &StoryFact) -> Option<String> {
+        let a = Self::words(&first.content);
+        let b = Self::words(&second.content);
+        let a_negated = Self::is_negated(&a);
+        let b_negated = Self::is_negated(&b);
+
+        // "X is dead" vs "X is not dead": same claim, opposite polarity
+        if a_negated != b_negated {
+            let a_content = Self::content_words(&a);
+            let b_content = Self::content_words(&b);
+            let (shorter, longer) = if a_content.len() <= b_content.len() {
+                (&a_content, &b_content)
+            } else {
+                (&b_content, &a_content)
+            };
+            if !shorter.is_empty() && shorter.iter().all(|w| longer.contains(w)) {
+                return Some("one fact negates the other".to_string());
+            }
+        }
+
+        // "X is dead" vs "X greets you": opposing words
+        if !a_negated && !b_negated {
+            for (left, right) in ANTONYMS {
+                let pairs = [
+                    (Self::uses_any(&a, left), Self::uses_any(&b, right)),
+                    (Self::uses_any(&b, left), Self::uses_any(&a, right)),
+                ];
+                for pair in pairs {
+                    if let (Some(x), Some(y)) = pair {
+                        return Some(format!("\"{x}\" contradicts \"{y}\""));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dm::story_memory::{FactCategory, FactSource};
+
+    fn fact(content: &str) -> StoryFact {
+        StoryFact::new(
+            EntityId::new(),
+            content,
+            FactCategory::Status,
+            FactSource::DmNarration,
+            0,
+        )
+    }
+
+    #[test]
+    fn test_negation_conflict() {
+        let checker = HeuristicConflictChecker;
+        assert!(checker
+            .check(
+                &fact("The bridge is intact"),
+                &fact("The bridge isn't intact")
+            )
+            .is_some());
+        assert!(checker
+            .check(
+                &fact("The bridge is intact"),
+                &fact("The river is not deep")
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_antonym_conflict() {
+        let checker = HeuristicConflictChecker;
+        let reason = checker
+            .check(
+                &fact("The innkeeper is dead"),
+                &fact("The innkeeper greets you"),
+            )
+            .unwrap();
+        assert!(reason.contains("dead"));
+        let reason = checker
+            .check(
+                &fact("The innkeeper was killed by bandits"),
+                &fact("The innkeeper smiles"),
+            )
+            .unwrap();
+        assert!(reason.contains("was killed"));
+        assert!(checker
+            .check(
+                &fact("The door is locked"),
+                &fact("The door is made of oak")
+            )
+            .is_none());
+    }
+
+    #[test]
+    fn test_killer_speaking_is_not_a_conflict() {
+        let checker = HeuristicConflictChecker;
+        assert!(checker
+            .check(
+                &fact("Garrick killed the troll"),
+                &fact("Garrick speaks of the battle")
+            )
+            .is_none());
+    }
+}
@@ -0,0 +1,177 @@
+//! Embeddings for semantic fact search.
+//!
+//! Entity names only get you so far: "who betrayed the party" should find
+//! "Garrick sold the party's location to the cult" without knowing Garrick's
+//! name. A [`FactEmbedder`] turns fact text into vectors so [`StoryMemory`]
+//! can rank facts by cosine similarity to a query.
+//!
+//! [`StoryMemory`]: super::StoryMemory
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Voyage AI embeddings endpoint.
+const VOYAGE_API_URL: &str = "https://api.voyageai.com/v1/embeddings";
+
+/// Default Voyage model for fact embeddings.
+const VOYAGE_DEFAULT_MODEL: &str = "voyage-3";
+
+/// Errors from embedding text.
+#[derive(Debug, Error)]
+pub enum EmbedError {
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Embedding API error ({status}): {message}")]
+    Api { status: u16, message: String },
+
+    #[error("Expected {expected} embeddings, got {actual}")]
+    CountMismatch { expected: usize, actual: usize },
+}
+
+/// Turns text into embedding vectors.
+pub trait FactEmbedder: Send + Sync {
+    /// Embed each text, returning one vector per input in the same order.
+    fn embed<'a>(&'a self, texts: &'a [String])
+        -> BoxFuture<'a, Result<Vec<Vec<f32>>, EmbedError>>;
+}
+
+/// Embedder that produces no vectors, leaving semantic search empty.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopEmbedder;
+
+impl FactEmbedder for NoopEmbedder {
+    fn embed<'a>(
+        &'a self,
+        texts: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Vec<f32>>, EmbedError>> {
+        Box::pin(async move { Ok(vec![Vec::new(); texts.len()]) })
+    }
+}
+
+/// Embedder backed by the Voyage AI API (Anthropic's recommended embeddings provider).
+#[derive(Debug, Clone)]
+pub struct VoyageEmbedder {
+    http: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+impl VoyageEmbedder {
+    /// Create an embedder with the given API key and the default model.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            api_key: api_key.into(),
+            model: VOYAGE_DEFAULT_MODEL.to_string(),
+        }
+    }
+
+    /// Create from the `VOYAGE_API_KEY` environment variable.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("VOYAGE_API_KEY").ok().map(Self::new)
+    }
+
+    /// Use a different Voyage model.
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct VoyageRequest<'a> {
+    input: &'a [String],
+    model: &'a str,
+}
+
+#[derive(Deserialize)]
+struct VoyageResponse {
+    data: Vec<VoyageEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct VoyageEmbedding {
+    embedding: Vec<f32>,
+    index: usize,
+}
+
+impl FactEmbedder for VoyageEmbedder {
+    fn embed<'a>(
+        &'a self,
+        texts: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<Vec<f32>>, EmbedError>> {
+        Box::pin(async move {
+            if texts.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let response = self
+                .http
+                .post(VOYAGE_API_URL)
+                .bearer_auth(&self.api_key)
+                .json(&VoyageRequest {
+                    input: texts,
+                    model: &self.model,
+                })
+                .send()
+                .await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let message = response.text().await.unwrap_or_default();
+                return Err(EmbedError::Api {
+                    status: status.as_u16(),
+                    message,
+                });
+            }
+
+            let mut data = response.json::<VoyageResponse>().await?.data;
+            if data.len() != texts.len() {
+                return Err(EmbedError::CountMismatch {
+                    expected: texts.len(),
+                    actual: data.len(),
+                });
+            }
+            data.sort_by_key(|e| e.index);
+            Ok(data.into_iter().map(|e| e.embedding).collect())
+        })
+    }
+}
+
+/// Cosine similarity between two vectors (0.0 if either is empty or zero).
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 2.0]), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_noop_embedder() {
+        let texts = vec!["a".to_string(), "b".to_string()];
+        let vectors = NoopEmbedder.embed(&texts).await.unwrap();
+        assert_eq!(vectors.len(), 2);
+        assert!(vectors.iter().all(|v| v.is_empty()));
+    }
+}
@@ -21,14 +21,20 @@
 //! └─────────────────────────────────────────────────────────────────┘
 //! ```
 
+mod conflict;
 mod consequence;
+mod embedding;
 mod entity;
 mod fact;
 mod relationship;
 mod store;
+mod summary;
 
+pub use conflict::{ConflictChecker, FactConflict, HeuristicConflictChecker};
 pub use consequence::{Consequence, ConsequenceId, ConsequenceSeverity, ConsequenceStatus};
+pub use embedding::{cosine_similarity, EmbedError, FactEmbedder, NoopEmbedder, VoyageEmbedder};
 pub use entity::{Entity, EntityId, EntityType, StoryMoment};
 pub use fact::{FactCategory, FactId, FactSource, StoryFact};
 pub use relationship::{Relationship, RelationshipType};
 pub use store::StoryMemory;
+pub use summary::{ConcatSummarizer, FactMerge, FactSummarizer, PRESERVE_IMPORTANCE};
@@ -1,11 +1,12 @@
 //! Story memory store for entity and fact management.
 
-use super::consequence::{Consequence, ConsequenceId, ConsequenceSeverity};
-#[cfg(test)]
-use super::consequence::ConsequenceStatus;
+use super::conflict::{ConflictChecker, FactConflict, HeuristicConflictChecker};
+use super::consequence::{Consequence, ConsequenceId, ConsequenceSeverity, ConsequenceStatus};
+use super::embedding::{cosine_similarity, EmbedError, FactEmbedder};
 use super::entity::{Entity, EntityId, EntityType};
-use super::fact::{FactCategory, FactSource, StoryFact};
+use super::fact::{FactCategory, FactId, FactSource, StoryFact};
 use super::relationship::{Relationship, RelationshipType};
+use super::summary::{FactMerge, FactSummarizer, PRESERVE_IMPORTANCE};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashMap;
@@ -16,6 +17,9 @@ const MAX_CONTEXT_FACTS: usize = 30;
 /// Maximum consequences to include in relevance checking.
 const MAX_CONTEXT_CONSEQUENCES: usize = 20;
 
+/// Maximum contradictions to surface in context.
+const MAX_CONTEXT_CONFLICTS: usize = 5;
+
 /// Importance decay rate per turn.
 const IMPORTANCE_DECAY_PER_TURN: f32 = 0.02;
 
@@ -36,6 +40,12 @@ pub struct StoryMemory {
     /// All pending consequences.
     #[serde(default)]
     consequences: Vec<Consequence>,
+    /// Embedding vectors for semantic search, keyed by fact.
+    #[serde(default)]
+    embeddings: HashMap<FactId, Vec<f32>>,
+    /// Audit trail of facts folded into summaries.
+    #[serde(default)]
+    merge_log: Vec<FactMerge>,
     /// Current turn number.
     current_turn: u32,
 }
@@ -268,6 +278,251 @@ impl StoryMemory {
             .collect()
     }
 
+    // =========================================================================
+    // Compaction
+    // =========================================================================
+
+    /// Shrink the store to at most `max_facts` facts by folding old,
+    /// low-importance facts about each entity into a single summary fact.
+    ///
+    /// Facts at or above [`PRESERVE_IMPORTANCE`] and superseded facts are never
+    /// merged, so the store can stay above the cap if nothing else is left to
+    /// fold. Entities with the oldest facts are compacted first. Returns the
+    /// merges performed; they are also kept in [`merge_log`](Self::merge_log).
+    pub fn summarize(
+        &mut self,
+        max_facts: usize,
+        summarizer: &dyn FactSummarizer,
+    ) -> Vec<FactMerge> {
+        let mut merges = Vec::new();
+        if self.facts.len() <= max_facts {
+            return merges;
+        }
+
+        // Mergeable facts per subject, oldest first
+        let mut groups: HashMap<EntityId, Vec<&StoryFact>> = HashMap::new();
+        for fact in &self.facts {
+            if fact.is_current && fact.importance < PRESERVE_IMPORTANCE {
+                groups.entry(fact.subject).or_default().push(fact);
+            }
+        }
+        let mut groups: Vec<(EntityId, Vec<&StoryFact>)> = groups
+            .into_iter()
+            .filter(|(_, facts)| facts.len() >= 2)
+            .collect();
+        for (_, facts) in &mut groups {
+            facts.sort_by_key(|f| f.established.turn);
+        }
+        groups
+            .sort_by_key(|(_, facts)| (facts[0].established.turn, std::cmp::Reverse(facts.len())));
+
+        let mut remaining = self.facts.len();
+        let mut summaries = Vec::new();
+        for (entity, facts) in groups {
+            if remaining <= max_facts {
+                break;
+            }
+            let name = self
+                .get_entity(entity)
+                .map(|e| e.name.as_str())
+                .unwrap_or("Unknown");
+            let mut summary = StoryFact::new(
+                entity,
+                summarizer.summarize(name, &facts),
+                FactCategory::Backstory,
+                FactSource::DmNarration,
+                facts[0].established.turn,
+            )
+            .with_importance(facts.iter().map(|f| f.importance).fold(0.0, f32::max));
+            for mentioned in facts.iter().flat_map(|f| &f.mentioned_entities) {
+                summary = summary.with_mentioned(*mentioned);
+            }
+
+            remaining -= facts.len() - 1;
+            merges.push(FactMerge {
+                summary: summary.id,
+                merged: facts.iter().map(|f| f.id).collect(),
+                turn: self.current_turn,
+            });
+            summaries.push(summary);
+        }
+
+        let merged: Vec<FactId> = merges
+            .iter()
+            .flat_map(|m| m.merged.iter().copied())
+            .collect();
+        self.facts.retain(|f| !merged.contains(&f.id));
+        for id in &merged {
+            self.embeddings.remove(id);
+        }
+        self.facts.extend(summaries);
+        self.merge_log.extend(merges.iter().cloned());
+        merges
+    }
+
+    /// All compactions performed so far, oldest first.
+    pub fn merge_log(&self) -> &[FactMerge] {
+        &self.merge_log
+    }
+
+    // =========================================================================
+    // Consistency Checking
+    // =========================================================================
+
+    /// Find pairs of current facts about the same entity that contradict each
+    /// other, using negation and antonym heuristics.
+    pub fn detect_conflicts(&self) -> Vec<FactConflict> {
+        self.detect_conflicts_with(&HeuristicConflictChecker)
+    }
+
+    /// Find contradictory facts using a custom checker.
+    ///
+    /// Conflicts come out in the order the facts were recorded, grouped by
+    /// entity, so the prompt section built from them is stable between runs.
+    pub fn detect_conflicts_with(&self, checker: &dyn ConflictChecker) -> Vec<FactConflict> {
+        let mut by_subject: Vec<(EntityId, Vec<&StoryFact>)> = Vec::new();
+        let mut slot: HashMap<EntityId, usize> = HashMap::new();
+        for fact in self.facts.iter().filter(|f| f.is_current) {
+            let index = *slot.entry(fact.subject).or_insert_with(|| {
+                by_subject.push((fact.subject, Vec::new()));
+                by_subject.len() - 1
+            });
+            by_subject[index].1.push(fact);
+        }
+
+        let mut conflicts = Vec::new();
+        for (entity, facts) in by_subject {
+            for (i, first) in facts.iter().enumerate() {
+                for second in &facts[i + 1..] {
+                    if let Some(reason) = checker.check(first, second) {
+                        conflicts.push(FactConflict {
+                            entity,
+                            first: first.id,
+                            second: second.id,
+                            reason,
+                        });
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Build a context section listing contradictory facts for the DM to reconcile.
+    pub fn build_conflicts_context(&self) -> String {
+        let conflicts = self.detect_conflicts();
+        if conflicts.is_empty() {
+            return String::new();
+        }
+
+        let fact_content = |id: FactId| {
+            self.facts
+                .iter()
+                .find(|f| f.id == id)
+                .map(|f| f.content.as_str())
+                .unwrap_or("?")
+        };
+
+        let mut context = String::from("## Possible Contradictions\n\n");
+        context.push_str(
+            "These recorded facts seem to conflict. Reconcile them in the story, \
+             or treat the later one as what is true now.\n",
+        );
+        for conflict in conflicts.iter().take(MAX_CONTEXT_CONFLICTS) {
+            let name = self
+                .get_entity(conflict.entity)
+                .map(|e| e.name.as_str())
+                .unwrap_or("Unknown");
+            context.push_str(&format!(
+                "- {}: \"{}\" vs \"{}\" ({})\n",
+                name,
+                fact_content(conflict.first),
+                fact_content(conflict.second),
+                conflict.reason
+            ));
+        }
+        context
+    }
+
+    // =========================================================================
+    // Semantic Search
+    // =========================================================================
+
+    /// Embed any current facts that don't have a vector yet.
+    /// Returns the number of facts embedded.
+    pub async fn embed_facts(&mut self, embedder: &dyn FactEmbedder) -> Result<usize, EmbedError> {
+        let pending: Vec<&StoryFact> = self
+            .facts
+            .iter()
+            .filter(|f| f.is_current && !self.embeddings.contains_key(&f.id))
+            .collect();
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let ids: Vec<FactId> = pending.iter().map(|f| f.id).collect();
+        let texts: Vec<String> = pending.iter().map(|f| f.content.clone()).collect();
+        let vectors = embedder.embed(&texts).await?;
+        if vectors.len() != ids.len() {
+            return Err(EmbedError::CountMismatch {
+                expected: ids.len(),
+                actual: vectors.len(),
+            });
+        }
+
+        let mut embedded = 0;
+        for (id, vector) in ids.into_iter().zip(vectors) {
+            // Empty vectors (e.g. from NoopEmbedder) aren't worth persisting
+            if !vector.is_empty() {
+                self.embeddings.insert(id, vector);
+                embedded += 1;
+            }
+        }
+        Ok(embedded)
+    }
+
+    /// Find the `top_k` current facts most similar to `query`.
+    ///
+    /// Only facts embedded with [`embed_facts`](Self::embed_facts) are searched.
+    pub async fn search_semantic(
+        &self,
+        embedder: &dyn FactEmbedder,
+        query: &str,
+        top_k: usize,
+    ) -> Result<Vec<StoryFact>, EmbedError> {
+        let query_vector = embedder
+            .embed(&[query.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        if query_vector.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut scored: Vec<(&StoryFact, f32)> = self
+            .facts
+            .iter()
+            .filter(|f| f.is_current)
+            .filter_map(|f| {
+                let vector = self.embeddings.get(&f.id)?;
+                Some((f, cosine_similarity(&query_vector, vector)))
+            })
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+
+        Ok(scored
+            .into_iter()
+            .take(top_k)
+            .map(|(f, _)| f.clone())
+            .collect())
+    }
+
+    /// Number of facts with a stored embedding.
+    pub fn embedded_fact_count(&self) -> usize {
+        self.embeddings.len()
+    }
+
     // =========================================================================
     // Relationship Management
     // =========================================================================
@@ -429,6 +684,46 @@ impl StoryMemory {
             .count()
     }
 
+    /// Fire pending consequences whose trigger matches the given context
+    /// (typically the player's action and the narration it answers).
+    ///
+    /// A trigger matches when the context mentions one of the consequence's
+    /// entities by name, or contains at least half of the trigger's keywords.
+    /// Expired consequences are dropped first since they can never fire.
+    /// Returns the consequences that fired, already marked as triggered.
+    pub fn check_triggers(&mut self, context: &str) -> Vec<Consequence> {
+        self.prune_expired_consequences();
+
+        let context_words = keywords(context);
+        let mentioned = self.extract_mentioned_entities(context);
+        let mut fired = Vec::new();
+
+        for consequence in &mut self.consequences {
+            if !consequence.status.is_active() {
+                continue;
+            }
+            let entity_match = mentioned.iter().any(|id| consequence.involves(*id));
+            if entity_match || trigger_matches(&consequence.trigger_description, &context_words) {
+                consequence.trigger();
+                fired.push(consequence.clone());
+            }
+        }
+
+        fired
+    }
+
+    /// Drop consequences that expired without firing. Returns how many were removed.
+    pub fn prune_expired_consequences(&mut self) -> usize {
+        let before = self.consequences.len();
+        let current_turn = self.current_turn;
+        for consequence in &mut self.consequences {
+            consequence.check_expiry(current_turn);
+        }
+        self.consequences
+            .retain(|c| c.status != ConsequenceStatus::Expired);
+        before - self.consequences.len()
+    }
+
     /// Build context string for pending consequences.
     /// This is used by the relevance checker.
     pub fn build_consequences_for_relevance(&self) -> String {
@@ -516,6 +811,87 @@ fn is_alphanumeric(b: u8) -> bool {
     b.is_ascii_alphanumeric()
 }
 
+/// Words too common in trigger descriptions to say anything about a match.
+const TRIGGER_STOPWORDS: &[&str] = &[
+    "the",
+    "and",
+    "for",
+    "with",
+    "into",
+    "onto",
+    "from",
+    "that",
+    "this",
+    "their",
+    "them",
+    "they",
+    "when",
+    "while",
+    "any",
+    "his",
+    "her",
+    "its",
+    "are",
+    "was",
+    "has",
+    "have",
+    "near",
+    "player",
+    "party",
+    "character",
+    "enter",
+    "visit",
+    "return",
+    "again",
+    "there",
+];
+
+/// Lowercased, lightly stemmed content words of `text`.
+fn keywords(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .map(|word| stem(&word.to_lowercase()))
+        .filter(|word| word.len() >= 3 && !TRIGGER_STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Strip common English suffixes so "sleeps" matches "sleeping".
+fn stem(word: &str) -> String {
+    for suffix in ["ing", "ed"] {
+        if let Some(root) = word.strip_suffix(suffix) {
+            if root.len() >= 3 {
+                return root.to_string();
+            }
+        }
+    }
+    // "-es" is only a suffix after a sibilant or "o" ("watches", "goes");
+    // elsewhere just the "s" goes ("arrives" becomes "arrive")
+    if let Some(root) = word.strip_suffix("es") {
+        if ["ch", "sh", "ss", "x", "z", "o"]
+            .iter()
+            .any(|end| root.ends_with(end))
+        {
+            return root.to_string();
+        }
+    }
+    match word.strip_suffix('s') {
+        Some(root) if root.len() >= 3 && !root.ends_with('s') => root.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+/// Check whether at least half of a trigger's keywords appear in the context.
+fn trigger_matches(trigger_description: &str, context_words: &[String]) -> bool {
+    let trigger_words = keywords(trigger_description);
+    if trigger_words.is_empty() {
+        return false;
+    }
+    let matched = trigger_words
+        .iter()
+        .filter(|word| context_words.contains(word))
+        .count();
+    matched * 2 >= trigger_words.len()
+}
+
 impl StoryMemory {
     /// Build context string for entities mentioned in input.
     pub fn build_context_for_input(&self, input: &str) -> String {
@@ -722,6 +1098,8 @@ impl StoryMemory {
 
 #[cfg(test)]
 mod tests {
+    use super::super::embedding::NoopEmbedder;
+    use super::super::summary::ConcatSummarizer;
     use super::*;
 
     #[test]
@@ -1007,4 +1385,350 @@ mod tests {
         let involving = store.consequences_involving(npc_id);
         assert_eq!(involving.len(), 1);
     }
+
+    #[test]
+    fn test_check_triggers_fires_on_keywords() {
+        let mut store = StoryMemory::new();
+        let id = store.create_consequence(
+            "The player enters Riverside village",
+            "Guards attempt to arrest the player",
+            ConsequenceSeverity::Major,
+        );
+
+        assert!(store
+            .check_triggers("I sharpen my sword in the forest")
+            .is_empty());
+
+        let fired = store.check_triggers("I walk down the road into Riverside");
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].id, id);
+        assert_eq!(
+            store.get_consequence(id).unwrap().status,
+            ConsequenceStatus::Triggered
+        );
+
+        // A triggered consequence doesn't fire twice
+        assert!(store.check_triggers("I return to Riverside").is_empty());
+    }
+
+    #[test]
+    fn test_stem_keeps_short_words_whole() {
+        assert_eq!(stem("goes"), "go");
+        assert_eq!(stem("watches"), "watch");
+        assert_eq!(stem("arrives"), "arrive");
+        assert_eq!(stem("arrive"), "arrive");
+        assert_eq!(stem("bosses"), "boss");
+        assert_eq!(stem("boss"), "boss");
+        assert_eq!(stem("sleeping"), stem("sleeps"));
+    }
+
+    #[test]
+    fn test_check_triggers_matches_entity_names() {
+        let mut store = StoryMemory::new();
+        let baron = store.create_entity(EntityType::Npc, "Baron Aldric");
+        let consequence = Consequence::new(
+            "The baron learns the player is alive",
+            "Assassins are dispatched",
+            ConsequenceSeverity::Critical,
+            store.current_turn(),
+        )
+        .with_subject(baron);
+        store.add_consequence(consequence);
+
+        assert_eq!(store.check_triggers("I ask about Baron Aldric").len(), 1);
+    }
+
+    #[test]
+    fn test_expired_consequence_does_not_fire() {
+        let mut store = StoryMemory::new();
+        store.create_consequence_with_expiry(
+            "The player sleeps in the haunted inn",
+            "A ghost visits in the night",
+            ConsequenceSeverity::Moderate,
+            2,
+        );
+
+        store.advance_turn();
+        store.advance_turn();
+        store.advance_turn();
+
+        assert!(store
+            .check_triggers("I go to sleep at the haunted inn")
+            .is_empty());
+        // Expired consequences are dropped from the store
+        assert_eq!(store.consequence_count(), 0);
+    }
+
+    /// Embeds text as a vector of keyword hits along fixed axes.
+    struct KeywordEmbedder;
+
+    impl FactEmbedder for KeywordEmbedder {
+        fn embed<'a>(
+            &'a self,
+            texts: &'a [String],
+        ) -> futures::future::BoxFuture<'a, Result<Vec<Vec<f32>>, EmbedError>> {
+            const AXES: [&[&str]; 3] = [
+                &["betray", "betrayed", "sold", "traitor"],
+                &["dragon", "wyrm", "scales"],
+                &["tavern", "ale", "inn"],
+            ];
+            Box::pin(async move {
+                Ok(texts
+                    .iter()
+                    .map(|text| {
+                        let text = text.to_lowercase();
+                        AXES.iter()
+                            .map(|words| words.iter().filter(|w| text.contains(*w)).count() as f32)
+                            .collect()
+                    })
+                    .collect())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_ranks_by_similarity() {
+        let mut store = StoryMemory::new();
+        let garrick = store.create_entity(EntityType::Npc, "Garrick");
+        let mountain = store.create_entity(EntityType::Location, "Grey Peak");
+        let inn = store.create_entity(EntityType::Location, "Prancing Pony");
+
+        store.record_fact(
+            inn,
+            "The inn serves watered-down ale",
+            FactCategory::Status,
+            FactSource::DmNarration,
+        );
+        store.record_fact(
+            garrick,
+            "Garrick sold the party's route to the cult, a traitor to the end",
+            FactCategory::Event,
+            FactSource::DmNarration,
+        );
+        store.record_fact(
+            mountain,
+            "A red dragon with scales like embers nests on the peak",
+            FactCategory::Status,
+            FactSource::DmNarration,
+        );
+
+        assert_eq!(store.embed_facts(&KeywordEmbedder).await.unwrap(), 3);
+        // Already-embedded facts aren't sent again
+        assert_eq!(store.embed_facts(&KeywordEmbedder).await.unwrap(), 0);
+
+        let results = store
+            .search_semantic(&KeywordEmbedder, "who betrayed the party?", 2)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].content.starts_with("Garrick"));
+
+        let results = store
+            .search_semantic(&KeywordEmbedder, "dragon scales", 3)
+            .await
+            .unwrap();
+        assert!(results[0].content.contains("red dragon"));
+
+        // Vectors survive a save/load round trip
+        let json = serde_json::to_string(&store).unwrap();
+        let loaded: StoryMemory = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.embedded_fact_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_with_noop_embedder() {
+        let mut store = StoryMemory::new();
+        let npc = store.create_entity(EntityType::Npc, "Garrick");
+        store.record_fact(
+            npc,
+            "Garrick is a traitor",
+            FactCategory::Event,
+            FactSource::DmNarration,
+        );
+
+        assert_eq!(store.embed_facts(&NoopEmbedder).await.unwrap(), 0);
+        let results = store
+            .search_semantic(&NoopEmbedder, "traitor", 5)
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_detect_conflicts() {
+        let mut store = StoryMemory::new();
+        let innkeeper = store.create_entity(EntityType::Npc, "Old Tom");
+        let guard = store.create_entity(EntityType::Npc, "Sergeant Hale");
+
+        store.record_fact(
+            innkeeper,
+            "Old Tom is dead, killed by bandits",
+            FactCategory::Status,
+            FactSource::DmNarration,
+        );
+        store.record_fact(
+            innkeeper,
+            "Old Tom greets you from behind the bar",
+            FactCategory::Event,
+            FactSource::DmNarration,
+        );
+        store.record_fact(
+            innkeeper,
+            "Old Tom has a grey beard",
+            FactCategory::Appearance,
+            FactSource::DmNarration,
+        );
+        // Opposed facts about different entities don't conflict
+        store.record_fact(
+            guard,
+            "Sergeant Hale is alive and well",
+            FactCategory::Status,
+            FactSource::DmNarration,
+        );
+
+        let conflicts = store.detect_conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].entity, innkeeper);
+
+        let context = store.build_conflicts_context();
+        assert!(context.contains("Old Tom"));
+        assert!(context.contains("greets you"));
+    }
+
+    #[test]
+    fn test_conflicts_context_is_stable() {
+        let mut store = StoryMemory::new();
+        let names = ["Ada", "Bram", "Cora", "Dain", "Edda", "Finn", "Gale"];
+        for name in names {
+            let npc = store.create_entity(EntityType::Npc, name);
+            for status in ["is dead", "is alive"] {
+                store.record_fact(
+                    npc,
+                    format!("{name} {status}"),
+                    FactCategory::Status,
+                    FactSource::DmNarration,
+                );
+            }
+        }
+
+        let context = store.build_conflicts_context();
+        let listed: Vec<_> = context
+            .lines()
+            .filter_map(|line| line.strip_prefix("- "))
+            .map(|line| line.split(':').next().unwrap())
+            .collect();
+        assert_eq!(listed, names[..MAX_CONTEXT_CONFLICTS]);
+        for _ in 0..10 {
+            assert_eq!(store.build_conflicts_context(), context);
+        }
+    }
+
+    #[test]
+    fn test_compatible_facts_not_flagged() {
+        let mut store = StoryMemory::new();
+        let npc = store.create_entity(EntityType::Npc, "Mira");
+        store.record_fact(
+            npc,
+            "Mira is a skilled archer",
+            FactCategory::Capability,
+            FactSource::DmNarration,
+        );
+        store.record_fact(
+            npc,
+            "Mira smiles at the party",
+            FactCategory::Event,
+            FactSource::DmNarration,
+        );
+        store.record_fact(
+            npc,
+            "Mira is loyal to the queen",
+            FactCategory::Motivation,
+            FactSource::DmNarration,
+        );
+
+        assert!(store.detect_conflicts().is_empty());
+        assert!(store.build_conflicts_context().is_empty());
+    }
+
+    #[test]
+    fn test_summarize_shrinks_below_cap() {
+        let mut store = StoryMemory::new();
+        let tom = store.create_entity(EntityType::Npc, "Old Tom");
+        let mira = store.create_entity(EntityType::Npc, "Mira");
+        for i in 0..6 {
+            store.record_fact_full(
+                tom,
+                format!("Old Tom tells story {i}"),
+                FactCategory::Event,
+                FactSource::NpcDialogue,
+                &[],
+                0.3,
+            );
+            store.record_fact_full(
+                mira,
+                format!("Mira practices archery {i}"),
+                FactCategory::Event,
+                FactSource::DmNarration,
+                &[],
+                0.2,
+            );
+        }
+        assert_eq!(store.fact_count(), 12);
+
+        let merges = store.summarize(8, &ConcatSummarizer);
+        assert!(store.fact_count() <= 8);
+        assert_eq!(merges.len(), 1);
+        assert_eq!(merges[0].merged.len(), 6);
+        assert_eq!(store.merge_log(), &merges[..]);
+
+        let summary = store
+            .facts
+            .iter()
+            .find(|f| f.id == merges[0].summary)
+            .unwrap();
+        assert!(summary.content.starts_with("Earlier, about"));
+        assert!(summary.content.ends_with("5."));
+        // Merged facts are gone from the store
+        assert!(!store.facts.iter().any(|f| merges[0].merged.contains(&f.id)));
+
+        // Already under the cap: nothing to do
+        assert!(store.summarize(8, &ConcatSummarizer).is_empty());
+    }
+
+    #[test]
+    fn test_summarize_preserves_important_facts() {
+        let mut store = StoryMemory::new();
+        let baron = store.create_entity(EntityType::Npc, "Baron Aldric");
+        store.record_fact_full(
+            baron,
+            "Baron Aldric murdered the king",
+            FactCategory::Secret,
+            FactSource::DmNarration,
+            &[],
+            0.95,
+        );
+        for i in 0..5 {
+            store.record_fact_full(
+                baron,
+                format!("Baron Aldric eats breakfast {i}"),
+                FactCategory::Event,
+                FactSource::DmNarration,
+                &[],
+                0.1,
+            );
+        }
+
+        store.summarize(1, &ConcatSummarizer);
+
+        // The important fact survives verbatim; the trivia is folded into one summary
+        assert_eq!(store.fact_count(), 2);
+        let facts = store.facts_about(baron);
+        assert!(facts
+            .iter()
+            .any(|f| f.content == "Baron Aldric murdered the king"));
+        assert!(facts
+            .iter()
+            .all(|f| f.content == "Baron Aldric murdered the king"
+                || f.content.starts_with("Earlier")));
+    }
 }
@@ -0,0 +1,72 @@
+//! Fact summarization for keeping story memory within a size budget.
+//!
+//! Long campaigns accumulate thousands of minor facts. Compaction folds the
+//! old, low-importance facts about an entity into a single summary fact so
+//! the store stays small while important facts survive untouched.
+
+use super::fact::{FactId, StoryFact};
+use serde::{Deserialize, Serialize};
+
+/// Facts at or above this importance are never merged into a summary.
+pub const PRESERVE_IMPORTANCE: f32 = 0.7;
+
+/// Condenses several facts about one entity into a single sentence or paragraph.
+pub trait FactSummarizer {
+    /// Summarize `facts` (oldest first) about the entity named `entity_name`.
+    fn summarize(&self, entity_name: &str, facts: &[&StoryFact]) -> String;
+}
+
+/// Summarizer that joins fact contents in order, without any model call.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConcatSummarizer;
+
+impl FactSummarizer for ConcatSummarizer {
+    fn summarize(&self, entity_name: &str, facts: &[&StoryFact]) -> String {
+        let contents: Vec<&str> = facts
+            .iter()
+            .map(|f| f.content.trim_end_matches('.'))
+            .collect();
+        format!("Earlier, about {}: {}.", entity_name, contents.join("; "))
+    }
+}
+
+/// Record of one compaction: which facts were folded into which summary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FactMerge {
+    /// The summary fact that replaced the merged facts.
+    pub summary: FactId,
+    /// The facts that were removed.
+    pub merged: Vec<FactId>,
+    /// Story turn when the merge happened.
+    pub turn: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dm::story_memory::{EntityId, FactCategory, FactSource};
+
+    #[test]
+    fn test_concat_summarizer() {
+        let entity = EntityId::new();
+        let a = StoryFact::new(
+            entity,
+            "Tom pours ale.",
+            FactCategory::Event,
+            FactSource::DmNarration,
+            1,
+        );
+        let b = StoryFact::new(
+            entity,
+            "Tom hums a tune",
+            FactCategory::Event,
+            FactSource::DmNarration,
+            2,
+        );
+
+        assert_eq!(
+            ConcatSummarizer.summarize("Tom", &[&a, &b]),
+            "Earlier, about Tom: Tom pours ale; Tom hums a tune."
+        );
+    }
+}
@@ -4,8 +4,12 @@
 //! by generating Intents that the RulesEngine resolves.
 
 use crate::dice::Advantage;
-use crate::rules::{CombatantInit, DamageType, Intent};
-use crate::world::{Ability, CharacterId, Condition, GameWorld, Skill};
+use crate::monsters;
+use crate::rules::{CombatantInit, DamageTraits, DamageType, Intent};
+use crate::world::{
+    Ability, CharacterId, Coin, CombatantAttack, Condition, GameWorld, Lighting, QuestStatus,
+    Senses, Size, Skill,
+};
 use claude::Tool;
 use serde_json::{json, Value};
 
@@ -21,13 +25,28 @@ impl DmTools {
             Self::ability_check(),
             Self::saving_throw(),
             Self::apply_damage(),
+            Self::fall(),
+            Self::suffocate(),
             Self::apply_healing(),
+            Self::grant_temp_hp(),
             Self::apply_condition(),
             Self::remove_condition(),
+            Self::modify_ability(),
+            Self::remove_ability_modifier(),
+            Self::add_exhaustion(),
+            Self::remove_exhaustion(),
+            Self::grant_inspiration(),
+            Self::use_inspiration(),
+            Self::take_feat(),
+            Self::grapple(),
+            Self::shove(),
             Self::start_combat(),
             Self::end_combat(),
             Self::next_turn(),
+            Self::mount(),
+            Self::dismount(),
             Self::short_rest(),
+            Self::spend_hit_dice(),
             Self::long_rest(),
             Self::remember_fact(),
             Self::register_consequence(),
@@ -37,11 +56,19 @@ impl DmTools {
             Self::use_item(),
             Self::equip_item(),
             Self::unequip_item(),
+            Self::attune_item(),
+            Self::end_attunement(),
             Self::adjust_gold(),
+            Self::adjust_currency(),
             Self::show_inventory(),
             Self::death_save(),
             Self::concentration_check(),
             Self::change_location(),
+            Self::travel(),
+            // Quest tools
+            Self::add_quest(),
+            Self::update_quest_status(),
+            Self::complete_objective(),
             // Class feature tools
             Self::use_rage(),
             Self::end_rage(),
@@ -57,6 +84,7 @@ impl DmTools {
             Self::use_sorcery_points(),
             // Spellcasting
             Self::cast_spell(),
+            Self::prepare_spells(),
         ]
     }
 
@@ -73,12 +101,16 @@ impl DmTools {
                     },
                     "location_type": {
                         "type": "string",
-                        "enum": ["city", "town", "village", "dungeon", "wilderness", "building", "room", "other"],
+                        "enum": ["city", "town", "village", "dungeon", "wilderness", "building", "room", "road", "cave", "other"],
                         "description": "Type of location"
                     },
                     "description": {
                         "type": "string",
                         "description": "Brief description of the location for future reference"
+                    },
+                    "travel_time_minutes": {
+                        "type": "integer",
+                        "description": "How many minutes the journey takes. Game time advances by this much, and later trips along the same route take as long."
                     }
                 },
                 "required": ["new_location"]
@@ -86,6 +118,105 @@ impl DmTools {
         }
     }
 
+    fn travel() -> Tool {
+        Tool {
+            name: "travel".to_string(),
+            description: "Travel to a place already on the map by a known route. Use this when the player heads back to somewhere they have been; use change_location for new places.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "destination": {
+                        "type": "string",
+                        "description": "Name of a known location connected to the current one"
+                    }
+                },
+                "required": ["destination"]
+            }),
+        }
+    }
+
+    // ========================================================================
+    // Quest Tools
+    // ========================================================================
+
+    fn add_quest() -> Tool {
+        Tool {
+            name: "add_quest".to_string(),
+            description: "Add a quest to the player's quest log when they accept a job, task, or goal. Track its steps as objectives so progress can be ticked off with complete_objective.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Short quest name (e.g., 'The Missing Miller')"
+                    },
+                    "description": {
+                        "type": "string",
+                        "description": "What the quest is about"
+                    },
+                    "objectives": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Steps needed to finish the quest (e.g., ['Find the mill', 'Rescue the miller'])"
+                    },
+                    "giver": {
+                        "type": "string",
+                        "description": "Who gave the quest, if anyone"
+                    },
+                    "xp_reward": {
+                        "type": "integer",
+                        "description": "Experience awarded automatically when the quest is completed"
+                    }
+                },
+                "required": ["name", "description"]
+            }),
+        }
+    }
+
+    fn update_quest_status() -> Tool {
+        Tool {
+            name: "update_quest_status".to_string(),
+            description: "Mark a quest in the quest log as completed, failed, or abandoned (or active again, unless it was completed). Completing a quest awards its experience.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "quest_name": {
+                        "type": "string",
+                        "description": "Name of the quest"
+                    },
+                    "status": {
+                        "type": "string",
+                        "enum": ["active", "completed", "failed", "abandoned"],
+                        "description": "The quest's new status"
+                    }
+                },
+                "required": ["quest_name", "status"]
+            }),
+        }
+    }
+
+    fn complete_objective() -> Tool {
+        Tool {
+            name: "complete_objective".to_string(),
+            description: "Tick off one of a quest's objectives when the player achieves it."
+                .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "quest_name": {
+                        "type": "string",
+                        "description": "Name of the quest"
+                    },
+                    "objective": {
+                        "type": "string",
+                        "description": "The objective's description, or enough of it to identify it"
+                    }
+                },
+                "required": ["quest_name", "objective"]
+            }),
+        }
+    }
+
     // ========================================================================
     // Class Feature Tools
     // ========================================================================
@@ -180,7 +311,7 @@ impl DmTools {
     fn use_divine_smite() -> Tool {
         Tool {
             name: "use_divine_smite".to_string(),
-            description: "Paladin expends a spell slot to deal extra radiant damage when hitting with a melee weapon attack. Damage: 2d8 + 1d8 per slot level above 1st. Extra 1d8 vs undead or fiends. Maximum 5d8 (or 6d8 vs undead/fiends using 4th level slot).".to_string(),
+            description: "Paladin expends a spell slot to deal extra radiant damage to the target of the attack that just hit. Use it right after a hitting attack; dice double on a critical hit. Damage: 2d8 + 1d8 per slot level above 1st. Extra 1d8 vs undead or fiends. Maximum 5d8 (or 6d8 vs undead/fiends using 4th level slot).".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -376,6 +507,10 @@ impl DmTools {
                         "type": "array",
                         "items": { "type": "string" },
                         "description": "Names of targets for the spell (for targeted spells)"
+                    },
+                    "ritual": {
+                        "type": "boolean",
+                        "description": "Cast as a ritual (spells with the ritual tag only, outside combat): takes 10 extra minutes and uses no spell slot"
                     }
                 },
                 "required": ["spell_name"]
@@ -383,6 +518,24 @@ impl DmTools {
         }
     }
 
+    fn prepare_spells() -> Tool {
+        Tool {
+            name: "prepare_spells".to_string(),
+            description: "Choose the leveled spells a cleric, druid, or wizard has prepared for the day. They can prepare spellcasting modifier + class level spells (minimum 1), once per long rest. Wizards prepare from their spellbook. Only prepared spells can be cast; cantrips are always ready.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "spells": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Names of the spells to prepare, replacing the current list"
+                    }
+                },
+                "required": ["spells"]
+            }),
+        }
+    }
+
     fn death_save() -> Tool {
         Tool {
             name: "death_save".to_string(),
@@ -549,6 +702,11 @@ impl DmTools {
                         "type": "string",
                         "enum": ["normal", "advantage", "disadvantage"],
                         "description": "Advantage state for the roll"
+                    },
+                    "lighting": {
+                        "type": "string",
+                        "enum": ["bright", "dim", "darkness"],
+                        "description": "Lighting where the check happens (affects Perception; darkvision is applied automatically)"
                     }
                 },
                 "required": ["skill", "dc", "description"]
@@ -643,8 +801,7 @@ impl DmTools {
                     },
                     "target": {
                         "type": "string",
-                        "enum": ["player", "npc"],
-                        "description": "Who receives the damage"
+                        "description": "Who receives the damage: 'player' (default) or the name of a party member"
                     }
                 },
                 "required": ["amount", "damage_type", "source"]
@@ -652,6 +809,48 @@ impl DmTools {
         }
     }
 
+    fn fall() -> Tool {
+        Tool {
+            name: "fall".to_string(),
+            description: "Resolve a fall: 1d6 bludgeoning damage per 10 feet fallen (max 20d6), and the character lands prone.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "distance_feet": {
+                        "type": "integer",
+                        "description": "How far the character fell, in feet"
+                    },
+                    "target": {
+                        "type": "string",
+                        "description": "Who falls: 'player' (default) or the name of a party member"
+                    }
+                },
+                "required": ["distance_feet"]
+            }),
+        }
+    }
+
+    fn suffocate() -> Tool {
+        Tool {
+            name: "suffocate".to_string(),
+            description: "Track a character going without air (underwater, smothered, in a vacuum). Call with the total rounds since their last breath; the rules work out held breath from Constitution and drop them to 0 HP when it runs out.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "rounds_without_air": {
+                        "type": "integer",
+                        "description": "Total rounds (6 seconds each) since the character last breathed"
+                    },
+                    "target": {
+                        "type": "string",
+                        "description": "Who is suffocating: 'player' (default) or the name of a party member"
+                    }
+                },
+                "required": ["rounds_without_air"]
+            }),
+        }
+    }
+
     fn apply_healing() -> Tool {
         Tool {
             name: "apply_healing".to_string(),
@@ -666,6 +865,31 @@ impl DmTools {
                     "source": {
                         "type": "string",
                         "description": "Source of the healing"
+                    },
+                    "target": {
+                        "type": "string",
+                        "description": "Who is healed: 'player' (default) or the name of a party member"
+                    }
+                },
+                "required": ["amount", "source"]
+            }),
+        }
+    }
+
+    fn grant_temp_hp() -> Tool {
+        Tool {
+            name: "grant_temp_hp".to_string(),
+            description: "Grant temporary hit points to a character. Temporary HP doesn't stack: the character keeps whichever value is higher. Damage depletes temporary HP first.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "amount": {
+                        "type": "integer",
+                        "description": "Number of temporary hit points"
+                    },
+                    "source": {
+                        "type": "string",
+                        "description": "Source of the temporary hit points (spell, feature, etc.)"
                     }
                 },
                 "required": ["amount", "source"]
@@ -676,7 +900,7 @@ impl DmTools {
     fn apply_condition() -> Tool {
         Tool {
             name: "apply_condition".to_string(),
-            description: "Apply a condition to a character.".to_string(),
+            description: "Apply a condition to a character or combatant.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -689,11 +913,19 @@ impl DmTools {
                     },
                     "source": {
                         "type": "string",
-                        "description": "What caused the condition"
+                        "description": "What caused the condition; for Charmed and Frightened, name the creature responsible"
                     },
                     "duration_rounds": {
                         "type": "integer",
                         "description": "How many rounds the condition lasts (omit for indefinite)"
+                    },
+                    "duration_minutes": {
+                        "type": "integer",
+                        "description": "How many minutes of game time the condition lasts, for effects outside combat such as an hour of poison"
+                    },
+                    "target": {
+                        "type": "string",
+                        "description": "Who gains the condition: 'player' (default), a party member, or a combatant by name"
                     }
                 },
                 "required": ["condition", "source"]
@@ -704,7 +936,7 @@ impl DmTools {
     fn remove_condition() -> Tool {
         Tool {
             name: "remove_condition".to_string(),
-            description: "Remove a condition from a character.".to_string(),
+            description: "Remove a condition from a character or combatant.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -714,6 +946,10 @@ impl DmTools {
                                 "incapacitated", "invisible", "paralyzed", "petrified", "poisoned",
                                 "prone", "restrained", "stunned", "unconscious"],
                         "description": "The condition to remove"
+                    },
+                    "target": {
+                        "type": "string",
+                        "description": "Who loses the condition: 'player' (default), a party member, or a combatant by name"
                     }
                 },
                 "required": ["condition"]
@@ -721,10 +957,192 @@ impl DmTools {
         }
     }
 
+    fn modify_ability() -> Tool {
+        Tool {
+            name: "modify_ability".to_string(),
+            description: "Temporarily raise or lower one of the character's ability scores, e.g. a Strength-draining poison (-2 STR) or a Potion of Giant Strength. Checks, saves and attacks use the modified score until it expires or is removed.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "ability": {
+                        "type": "string",
+                        "enum": ["strength", "dexterity", "constitution", "intelligence", "wisdom", "charisma"],
+                        "description": "The ability score to change"
+                    },
+                    "delta": {
+                        "type": "integer",
+                        "description": "Amount to add to the score (negative to reduce it)"
+                    },
+                    "source": {
+                        "type": "string",
+                        "description": "What caused the change; used to remove it later"
+                    },
+                    "duration_rounds": {
+                        "type": "integer",
+                        "description": "Combat turns until it wears off; omit to last until removed"
+                    }
+                },
+                "required": ["ability", "delta", "source"]
+            }),
+        }
+    }
+
+    fn remove_ability_modifier() -> Tool {
+        Tool {
+            name: "remove_ability_modifier".to_string(),
+            description: "End a temporary ability score change early (the poison is cured, the effect is dispelled).".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "description": "The source given when the modifier was applied"
+                    }
+                },
+                "required": ["source"]
+            }),
+        }
+    }
+
+    fn add_exhaustion() -> Tool {
+        Tool {
+            name: "add_exhaustion".to_string(),
+            description: "Add levels of exhaustion to the character (forced march, starvation, extreme cold, etc.). Level 1: disadvantage on ability checks. 2: speed halved. 3: disadvantage on attacks and saves. 4: HP maximum halved. 5: speed 0. 6: death.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "levels": {
+                        "type": "integer",
+                        "description": "Number of exhaustion levels to add (usually 1)"
+                    },
+                    "source": {
+                        "type": "string",
+                        "description": "What caused the exhaustion"
+                    }
+                },
+                "required": ["levels", "source"]
+            }),
+        }
+    }
+
+    fn remove_exhaustion() -> Tool {
+        Tool {
+            name: "remove_exhaustion".to_string(),
+            description: "Remove levels of exhaustion from the character (e.g., from magic). Long rests already remove one level automatically.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "levels": {
+                        "type": "integer",
+                        "description": "Number of exhaustion levels to remove"
+                    }
+                },
+                "required": ["levels"]
+            }),
+        }
+    }
+
+    fn grant_inspiration() -> Tool {
+        Tool {
+            name: "grant_inspiration".to_string(),
+            description: "Award the player Inspiration for good roleplay, clever ideas, or acting true to their character. They can hold only one at a time.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        }
+    }
+
+    fn use_inspiration() -> Tool {
+        Tool {
+            name: "use_inspiration".to_string(),
+            description: "Spend the player's Inspiration when they ask to. Their next attack roll, ability check, or saving throw is made with advantage.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {},
+                "required": []
+            }),
+        }
+    }
+
+    fn take_feat() -> Tool {
+        Tool {
+            name: "take_feat".to_string(),
+            description: "Spend one of a character's pending Ability Score Improvements on a feat once they've chosen it. Prerequisites are checked, and the feat's effects (ability increase, save proficiency, luck points) are applied.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Who takes the feat: 'player' (default) or the name of a party member"
+                    },
+                    "feat_name": {
+                        "type": "string",
+                        "enum": ["Lucky", "Resilient"],
+                        "description": "The feat to take"
+                    },
+                    "ability": {
+                        "type": "string",
+                        "enum": ["strength", "dexterity", "constitution", "intelligence", "wisdom", "charisma"],
+                        "description": "Ability chosen for the feat (required for Resilient)"
+                    }
+                },
+                "required": ["feat_name"]
+            }),
+        }
+    }
+
+    fn grapple() -> Tool {
+        Tool {
+            name: "grapple".to_string(),
+            description: "Resolve a grapple attempt as an opposed check: the grappler's Athletics vs the target's Athletics or Acrobatics. Ties go to the target. On success the target is Grappled.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "grappler": {
+                        "type": "string",
+                        "description": "Name of the creature attempting the grapple (use 'player' for the player character)"
+                    },
+                    "target": {
+                        "type": "string",
+                        "description": "Name of the creature being grappled (use 'player' for the player character)"
+                    }
+                },
+                "required": ["grappler", "target"]
+            }),
+        }
+    }
+
+    fn shove() -> Tool {
+        Tool {
+            name: "shove".to_string(),
+            description: "Resolve a shove as an opposed check: the shover's Athletics vs the target's Athletics or Acrobatics. Ties go to the target. On success the target is knocked Prone or pushed 5 feet away.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "shover": {
+                        "type": "string",
+                        "description": "Name of the creature doing the shoving (use 'player' for the player character)"
+                    },
+                    "target": {
+                        "type": "string",
+                        "description": "Name of the creature being shoved (use 'player' for the player character)"
+                    },
+                    "knock_prone": {
+                        "type": "boolean",
+                        "description": "True to knock the target prone, false to push it 5 feet away"
+                    }
+                },
+                "required": ["shover", "target", "knock_prone"]
+            }),
+        }
+    }
+
     fn start_combat() -> Tool {
         Tool {
             name: "start_combat".to_string(),
-            description: "Start a combat encounter. Initiative will be rolled for all combatants. Provide enemy stats based on D&D 5e SRD creatures."
+            description: "Start a combat encounter. Initiative will be rolled for all combatants. Provide enemy stats based on D&D 5e SRD creatures. Enemies given an attack_bonus and damage_dice take their turns automatically when next_turn reaches them."
                 .to_string(),
             input_schema: json!({
                 "type": "object",
@@ -749,6 +1167,58 @@ impl DmTools {
                                 "initiative_modifier": {
                                     "type": "integer",
                                     "description": "Initiative modifier based on DEX (e.g., Goblin: +2, Orc: +1, Wolf: +2)"
+                                },
+                                "attack_name": {
+                                    "type": "string",
+                                    "description": "The enemy's main attack (e.g., Goblin: 'Scimitar', Wolf: 'Bite')"
+                                },
+                                "attack_bonus": {
+                                    "type": "integer",
+                                    "description": "To-hit bonus for that attack (e.g., Goblin: +4, Orc: +5, Wolf: +4)"
+                                },
+                                "damage_dice": {
+                                    "type": "string",
+                                    "description": "Damage including modifier (e.g., Goblin: '1d6+2', Orc: '1d12+3', Wolf: '2d4+2')"
+                                },
+                                "damage_type": {
+                                    "type": "string",
+                                    "description": "Damage type of the attack (e.g., 'slashing', 'piercing')"
+                                },
+                                "size": {
+                                    "type": "string",
+                                    "enum": ["tiny", "small", "medium", "large", "huge", "gargantuan"],
+                                    "description": "Size category (default medium; e.g., Goblin: small, Warhorse: large)"
+                                },
+                                "speed": {
+                                    "type": "integer",
+                                    "description": "Walking speed in feet (default 30; e.g., Wolf: 40, Warhorse: 60)"
+                                },
+                                "challenge_rating": {
+                                    "type": "string",
+                                    "description": "Challenge rating such as '1/4', '1' or '5'. Fills in DMG-balanced HP, AC, attack bonus and damage for any of those left out"
+                                },
+                                "resistances": {
+                                    "type": "array",
+                                    "items": { "type": "string" },
+                                    "description": "Damage types that deal half damage (e.g., Skeleton: none, Ghost: ['fire', 'lightning'])"
+                                },
+                                "immunities": {
+                                    "type": "array",
+                                    "items": { "type": "string" },
+                                    "description": "Damage types that deal no damage (e.g., Zombie: ['poison'])"
+                                },
+                                "vulnerabilities": {
+                                    "type": "array",
+                                    "items": { "type": "string" },
+                                    "description": "Damage types that deal double damage (e.g., Skeleton: ['bludgeoning'])"
+                                },
+                                "blindsight_ft": {
+                                    "type": "integer",
+                                    "description": "Blindsight range in feet; lets the enemy perceive invisible creatures (e.g., Ochre Jelly: 60)"
+                                },
+                                "tremorsense_ft": {
+                                    "type": "integer",
+                                    "description": "Tremorsense range in feet; lets the enemy perceive invisible creatures on the ground (e.g., Bulette: 60)"
                                 }
                             },
                             "required": ["name"]
@@ -773,10 +1243,48 @@ impl DmTools {
         }
     }
 
+    fn mount() -> Tool {
+        Tool {
+            name: "mount".to_string(),
+            description: "Have a combatant climb onto a willing creature at least one size larger. While mounted they move at the mount's speed and have advantage on melee attacks against unmounted creatures smaller than the mount. If the mount is knocked prone the rider makes a DC 10 DEX save or falls off.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "rider": {
+                        "type": "string",
+                        "description": "Who mounts: 'player' or a combatant's name"
+                    },
+                    "mount": {
+                        "type": "string",
+                        "description": "Name of the creature to ride"
+                    }
+                },
+                "required": ["rider", "mount"]
+            }),
+        }
+    }
+
+    fn dismount() -> Tool {
+        Tool {
+            name: "dismount".to_string(),
+            description: "Have a mounted combatant get off their mount.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "rider": {
+                        "type": "string",
+                        "description": "Who dismounts: 'player' or a combatant's name"
+                    }
+                },
+                "required": ["rider"]
+            }),
+        }
+    }
+
     fn next_turn() -> Tool {
         Tool {
             name: "next_turn".to_string(),
-            description: "Advance to the next turn in combat.".to_string(),
+            description: "Advance to the next turn in combat. If the next combatant is an enemy with attack stats, its attack is rolled and applied automatically.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {},
@@ -797,6 +1305,23 @@ impl DmTools {
         }
     }
 
+    fn spend_hit_dice() -> Tool {
+        Tool {
+            name: "spend_hit_dice".to_string(),
+            description: "Spend hit dice to heal during a short rest. Each die rolls the class hit die plus CON modifier.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "count": {
+                        "type": "integer",
+                        "description": "Number of hit dice to spend"
+                    }
+                },
+                "required": ["count"]
+            }),
+        }
+    }
+
     fn long_rest() -> Tool {
         Tool {
             name: "long_rest".to_string(),
@@ -814,10 +1339,14 @@ impl DmTools {
     fn give_item() -> Tool {
         Tool {
             name: "give_item".to_string(),
-            description: "Give an item to the player. Use this when they find loot, receive rewards, or purchase items.".to_string(),
+            description: "Give an item to the player or a party member. Use this when they find loot, receive rewards, or purchase items.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Who receives the item: 'player' (default) or the name of a party member"
+                    },
                     "item_name": {
                         "type": "string",
                         "description": "Name of the item (e.g., 'Longsword', 'Healing Potion', 'Rope')"
@@ -857,10 +1386,14 @@ impl DmTools {
     fn remove_item() -> Tool {
         Tool {
             name: "remove_item".to_string(),
-            description: "Remove an item from the player's inventory. Use when items are consumed, lost, sold, or given away.".to_string(),
+            description: "Remove an item from a character's inventory. Use when items are consumed, lost, sold, or given away.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Whose inventory the item leaves: 'player' (default) or the name of a party member"
+                    },
                     "item_name": {
                         "type": "string",
                         "description": "Name of the item to remove"
@@ -883,6 +1416,10 @@ impl DmTools {
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Who uses the item: 'player' (default) or the name of a party member"
+                    },
                     "item_name": {
                         "type": "string",
                         "description": "Name of the item to use"
@@ -905,6 +1442,10 @@ impl DmTools {
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Who equips the item: 'player' (default) or the name of a party member"
+                    },
                     "item_name": {
                         "type": "string",
                         "description": "Name of the item to equip"
@@ -922,6 +1463,10 @@ impl DmTools {
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Who unequips the item: 'player' (default) or the name of a party member"
+                    },
                     "slot": {
                         "type": "string",
                         "enum": ["armor", "shield", "main_hand", "off_hand"],
@@ -933,13 +1478,61 @@ impl DmTools {
         }
     }
 
+    fn attune_item() -> Tool {
+        Tool {
+            name: "attune_item".to_string(),
+            description: "Attune a character to a magic item they own that requires attunement (takes a short rest). A character can be attuned to at most three items; an unattuned item's magic doesn't work.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Who attunes to the item: 'player' (default) or the name of a party member"
+                    },
+                    "item_name": {
+                        "type": "string",
+                        "description": "Name of the magic item to attune to"
+                    }
+                },
+                "required": ["item_name"]
+            }),
+        }
+    }
+
+    fn end_attunement() -> Tool {
+        Tool {
+            name: "end_attunement".to_string(),
+            description:
+                "End a character's attunement to a magic item, freeing an attunement slot."
+                    .to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Who ends the attunement: 'player' (default) or the name of a party member"
+                    },
+                    "item_name": {
+                        "type": "string",
+                        "description": "Name of the item to stop being attuned to"
+                    }
+                },
+                "required": ["item_name"]
+            }),
+        }
+    }
+
     fn adjust_gold() -> Tool {
         Tool {
             name: "adjust_gold".to_string(),
-            description: "Add or remove gold from the player. Positive values add gold, negative values remove it.".to_string(),
+            description: "Add or remove a character's gold. Positive values add gold, negative values remove it.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Whose purse changes: 'player' (default) or the name of a party member"
+                    },
                     "amount": {
                         "type": "number",
                         "description": "Amount of gold to add (positive) or remove (negative)"
@@ -954,6 +1547,36 @@ impl DmTools {
         }
     }
 
+    fn adjust_currency() -> Tool {
+        Tool {
+            name: "adjust_currency".to_string(),
+            description: "Add or spend coins of a specific denomination. Use this for prices and loot not in whole gold pieces (e.g., 4 sp for a meal, 30 cp in a pouch). Spending makes change automatically.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "character": {
+                        "type": "string",
+                        "description": "Whose purse changes: 'player' (default) or the name of a party member"
+                    },
+                    "amount": {
+                        "type": "integer",
+                        "description": "Number of coins to add (positive) or spend (negative)"
+                    },
+                    "denomination": {
+                        "type": "string",
+                        "enum": ["cp", "sp", "ep", "gp", "pp"],
+                        "description": "Coin type: copper, silver, electrum, gold, or platinum"
+                    },
+                    "reason": {
+                        "type": "string",
+                        "description": "Reason for the change (e.g., 'paying for a room', 'looting a pouch')"
+                    }
+                },
+                "required": ["amount", "denomination"]
+            }),
+        }
+    }
+
     fn show_inventory() -> Tool {
         Tool {
             name: "show_inventory".to_string(),
@@ -983,12 +1606,14 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
             let dc = input["dc"].as_i64()? as i32;
             let description = input["description"].as_str().unwrap_or("").to_string();
             let advantage = parse_advantage(input["advantage"].as_str());
+            let lighting = parse_lighting(input["lighting"].as_str());
             Some(Intent::SkillCheck {
                 character_id: world.player_character.id,
                 skill,
                 dc,
                 advantage,
                 description,
+                lighting,
             })
         }
         "ability_check" => {
@@ -1026,12 +1651,20 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
             let damage_type = parse_damage_type(input["damage_type"].as_str()?)?;
             let source = input["source"].as_str().unwrap_or("unknown").to_string();
             Some(Intent::Damage {
-                target_id: world.player_character.id,
+                target_id: parse_target(world, input),
                 amount,
                 damage_type,
                 source,
             })
         }
+        "fall" => Some(Intent::Fall {
+            character_id: parse_target(world, input),
+            distance_feet: input["distance_feet"].as_u64()? as u32,
+        }),
+        "suffocate" => Some(Intent::Suffocate {
+            character_id: parse_target(world, input),
+            rounds_without_air: input["rounds_without_air"].as_u64()? as u32,
+        }),
         "apply_healing" => {
             let amount = input["amount"].as_i64()? as i32;
             // Validate healing is positive
@@ -1040,6 +1673,18 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
             }
             let source = input["source"].as_str().unwrap_or("healing").to_string();
             Some(Intent::Heal {
+                target_id: parse_target(world, input),
+                amount,
+                source,
+            })
+        }
+        "grant_temp_hp" => {
+            let amount = input["amount"].as_i64()? as i32;
+            if amount <= 0 {
+                return None;
+            }
+            let source = input["source"].as_str().unwrap_or("unknown").to_string();
+            Some(Intent::GrantTempHp {
                 target_id: world.player_character.id,
                 amount,
                 source,
@@ -1049,20 +1694,69 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
             let condition = parse_condition(input["condition"].as_str()?)?;
             let source = input["source"].as_str().unwrap_or("unknown").to_string();
             let duration_rounds = input["duration_rounds"].as_i64().map(|d| d as u32);
+            let duration_minutes = input["duration_minutes"].as_i64().map(|d| d as u32);
             Some(Intent::ApplyCondition {
-                target_id: world.player_character.id,
+                target_id: parse_creature_target(world, input),
                 condition,
                 source,
                 duration_rounds,
+                duration_minutes,
             })
         }
         "remove_condition" => {
             let condition = parse_condition(input["condition"].as_str()?)?;
             Some(Intent::RemoveCondition {
-                target_id: world.player_character.id,
+                target_id: parse_creature_target(world, input),
                 condition,
             })
         }
+        "modify_ability" => Some(Intent::ModifyAbility {
+            character_id: world.player_character.id,
+            ability: parse_ability(input["ability"].as_str()?)?,
+            delta: input["delta"].as_i64()?.clamp(-30, 30) as i8,
+            source: input["source"].as_str().unwrap_or("unknown").to_string(),
+            duration_rounds: input["duration_rounds"].as_u64().map(|d| d as u32),
+        }),
+        "remove_ability_modifier" => Some(Intent::RemoveAbilityModifier {
+            character_id: world.player_character.id,
+            source: input["source"].as_str()?.to_string(),
+        }),
+        "add_exhaustion" => {
+            let levels = input["levels"].as_u64().unwrap_or(1).min(6) as u8;
+            let source = input["source"].as_str().unwrap_or("unknown").to_string();
+            Some(Intent::AddExhaustion {
+                character_id: world.player_character.id,
+                levels,
+                source,
+            })
+        }
+        "remove_exhaustion" => {
+            let levels = input["levels"].as_u64().unwrap_or(1).min(6) as u8;
+            Some(Intent::RemoveExhaustion {
+                character_id: world.player_character.id,
+                levels,
+            })
+        }
+        "grant_inspiration" => Some(Intent::GrantInspiration {
+            character_id: world.player_character.id,
+        }),
+        "use_inspiration" => Some(Intent::UseInspiration {
+            character_id: world.player_character.id,
+        }),
+        "take_feat" => Some(Intent::TakeFeat {
+            character_id: parse_character(world, input),
+            feat_name: input["feat_name"].as_str()?.to_string(),
+            ability: input["ability"].as_str().and_then(parse_ability),
+        }),
+        "grapple" => Some(Intent::Grapple {
+            grappler_id: find_creature_id(world, input["grappler"].as_str()?),
+            target_id: find_creature_id(world, input["target"].as_str()?),
+        }),
+        "shove" => Some(Intent::Shove {
+            shover_id: find_creature_id(world, input["shover"].as_str()?),
+            target_id: find_creature_id(world, input["target"].as_str()?),
+            knock_prone: input["knock_prone"].as_bool().unwrap_or(true),
+        }),
         "start_combat" => {
             let enemies = input["enemies"].as_array()?;
             let player_hp = &world.player_character.hit_points;
@@ -1075,17 +1769,94 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
                 max_hp: player_hp.maximum,
                 armor_class: world.player_character.current_ac(),
                 initiative_modifier: world.player_character.initiative_modifier(),
+                attack: None,
+                position: None,
+                size: world.player_character.race_type.size(),
+                speed: world.player_character.speed.walk,
+                damage_traits: DamageTraits::default(),
+                senses: world.player_character.senses.clone(),
             }];
 
             for enemy in enemies {
                 let name = enemy["name"].as_str().unwrap_or("Enemy").to_string();
+                // A challenge rating fills in balanced defaults for anything not given
+                let challenge_rating = enemy["challenge_rating"]
+                    .as_f64()
+                    .map(|cr| cr as f32)
+                    .or_else(|| {
+                        enemy["challenge_rating"]
+                            .as_str()
+                            .and_then(monsters::parse_challenge_rating)
+                    });
+                let defaults =
+                    challenge_rating.map(|cr| monsters::stats_for_cr(cr).combatant(&name));
                 // Parse enemy HP if provided, default to 10/10 for basic enemies
-                let max_hp = enemy["max_hp"].as_i64().unwrap_or(10) as i32;
+                let max_hp = enemy["max_hp"]
+                    .as_i64()
+                    .unwrap_or(defaults.as_ref().map_or(10, |d| d.max_hp as i64))
+                    as i32;
                 let current_hp = enemy["current_hp"].as_i64().unwrap_or(max_hp as i64) as i32;
                 // Parse enemy AC if provided, default to 10 (unarmored)
-                let armor_class = enemy["armor_class"].as_u64().unwrap_or(10) as u8;
+                let armor_class = enemy["armor_class"]
+                    .as_u64()
+                    .unwrap_or(defaults.as_ref().map_or(10, |d| d.armor_class as u64))
+                    as u8;
                 // Parse initiative modifier if provided, default to 0
-                let initiative_modifier = enemy["initiative_modifier"].as_i64().unwrap_or(0) as i8;
+                let initiative_modifier = enemy["initiative_modifier"].as_i64().unwrap_or(
+                    defaults
+                        .as_ref()
+                        .map_or(0, |d| d.initiative_modifier as i64),
+                ) as i8;
+                let default_attack = defaults.and_then(|d| d.attack);
+                let size = enemy["size"]
+                    .as_str()
+                    .and_then(Size::from_name)
+                    .unwrap_or_default();
+                let speed = enemy["speed"].as_u64().unwrap_or(30) as u32;
+                let damage_types = |key: &str| -> Vec<DamageType> {
+                    enemy[key]
+                        .as_array()
+                        .map(|types| {
+                            types
+                                .iter()
+                                .filter_map(|t| t.as_str().and_then(parse_damage_type))
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                };
+                let damage_traits = DamageTraits {
+                    resistances: damage_types("resistances"),
+                    immunities: damage_types("immunities"),
+                    vulnerabilities: damage_types("vulnerabilities"),
+                };
+                // Blindsight and tremorsense let an enemy find invisible foes
+                let senses = Senses {
+                    blindsight_ft: enemy["blindsight_ft"].as_u64().unwrap_or(0) as u32,
+                    tremorsense_ft: enemy["tremorsense_ft"].as_u64().unwrap_or(0) as u32,
+                    ..Senses::default()
+                };
+                // An attack bonus and damage let the engine run this enemy's turns
+                let attack = enemy["damage_dice"]
+                    .as_str()
+                    .or(default_attack.as_ref().map(|a| a.damage_dice.as_str()))
+                    .and_then(|damage_dice| {
+                        Some(CombatantAttack {
+                            name: enemy["attack_name"]
+                                .as_str()
+                                .unwrap_or("Attack")
+                                .to_string(),
+                            attack_bonus: enemy["attack_bonus"]
+                                .as_i64()
+                                .map(|bonus| bonus as i8)
+                                .or(default_attack.as_ref().map(|a| a.attack_bonus))?,
+                            damage_dice: damage_dice.to_string(),
+                            damage_type: enemy["damage_type"]
+                                .as_str()
+                                .and_then(parse_damage_type)
+                                .unwrap_or(DamageType::Bludgeoning),
+                            reach_feet: 5,
+                        })
+                    });
                 combatants.push(CombatantInit {
                     id: CharacterId::new(),
                     name,
@@ -1095,6 +1866,12 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
                     max_hp,
                     armor_class,
                     initiative_modifier,
+                    attack,
+                    position: None,
+                    size,
+                    speed,
+                    damage_traits,
+                    senses,
                 });
             }
 
@@ -1102,7 +1879,24 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
         }
         "end_combat" => Some(Intent::EndCombat),
         "next_turn" => Some(Intent::NextTurn),
+        "mount" => Some(Intent::Mount {
+            rider_id: find_creature_id(world, input["rider"].as_str()?),
+            mount_id: find_creature_id(world, input["mount"].as_str()?),
+        }),
+        "dismount" => Some(Intent::Dismount {
+            rider_id: find_creature_id(world, input["rider"].as_str()?),
+        }),
         "short_rest" => Some(Intent::ShortRest),
+        "spend_hit_dice" => {
+            let count = input["count"].as_u64()?.min(u8::MAX as u64) as u8;
+            if count == 0 {
+                return None;
+            }
+            Some(Intent::SpendHitDice {
+                character_id: world.player_character.id,
+                count,
+            })
+        }
         "long_rest" => Some(Intent::LongRest),
         "remember_fact" => {
             let subject_name = input["subject_name"].as_str()?.to_string();
@@ -1171,6 +1965,7 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
             let value_gp = input["value_gp"].as_f64().map(|v| v as f32);
 
             Some(Intent::AddItem {
+                character_id: parse_character(world, input),
                 item_name,
                 quantity,
                 item_type,
@@ -1184,7 +1979,11 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
             let item_name = input["item_name"].as_str()?.to_string();
             let quantity = input["quantity"].as_u64().unwrap_or(1) as u32;
 
-            Some(Intent::RemoveItem { item_name, quantity })
+            Some(Intent::RemoveItem {
+                character_id: parse_character(world, input),
+                item_name,
+                quantity,
+            })
         }
         "use_item" => {
             let item_name = input["item_name"].as_str()?.to_string();
@@ -1192,17 +1991,52 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
             let target_id = None;
 
             Some(Intent::UseItem {
+                character_id: parse_character(world, input),
                 item_name,
                 target_id,
             })
         }
         "equip_item" => {
             let item_name = input["item_name"].as_str()?.to_string();
-            Some(Intent::EquipItem { item_name })
+            Some(Intent::EquipItem {
+                character_id: parse_character(world, input),
+                item_name,
+            })
         }
         "unequip_item" => {
             let slot = input["slot"].as_str()?.to_string();
-            Some(Intent::UnequipItem { slot })
+            Some(Intent::UnequipItem {
+                character_id: parse_character(world, input),
+                slot,
+            })
+        }
+        "attune_item" => {
+            let item_name = input["item_name"].as_str()?.to_string();
+            Some(Intent::Attune {
+                character_id: parse_character(world, input),
+                item_name,
+            })
+        }
+        "end_attunement" => {
+            let item_name = input["item_name"].as_str()?.to_string();
+            Some(Intent::EndAttunement {
+                character_id: parse_character(world, input),
+                item_name,
+            })
+        }
+        "adjust_currency" => {
+            let amount = input["amount"].as_i64()? as i32;
+            let coin = Coin::parse(input["denomination"].as_str()?)?;
+            let reason = input["reason"]
+                .as_str()
+                .unwrap_or("currency adjustment")
+                .to_string();
+            Some(Intent::AdjustCurrency {
+                character_id: parse_character(world, input),
+                amount,
+                coin,
+                reason,
+            })
         }
         "adjust_gold" => {
             let amount = input["amount"].as_f64()? as f32;
@@ -1210,7 +2044,11 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
                 .as_str()
                 .unwrap_or("gold adjustment")
                 .to_string();
-            Some(Intent::AdjustGold { amount, reason })
+            Some(Intent::AdjustGold {
+                character_id: parse_character(world, input),
+                amount,
+                reason,
+            })
         }
         "death_save" => Some(Intent::DeathSave {
             character_id: world.player_character.id,
@@ -1228,12 +2066,43 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
             let new_location = input["new_location"].as_str()?.to_string();
             let location_type = input["location_type"].as_str().map(|s| s.to_string());
             let description = input["description"].as_str().map(|s| s.to_string());
+            let travel_time_minutes = input["travel_time_minutes"].as_u64().map(|m| m as u32);
             Some(Intent::ChangeLocation {
                 new_location,
                 location_type,
                 description,
+                travel_time_minutes,
             })
         }
+        "travel" => {
+            let destination = world.location_named(input["destination"].as_str()?)?;
+            Some(Intent::Travel { to: destination.id })
+        }
+
+        // Quest tools
+        "add_quest" => Some(Intent::AddQuest {
+            name: input["name"].as_str()?.to_string(),
+            description: input["description"].as_str().unwrap_or("").to_string(),
+            objectives: input["objectives"]
+                .as_array()
+                .map(|objectives| {
+                    objectives
+                        .iter()
+                        .filter_map(|o| o.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            giver: input["giver"].as_str().map(str::to_string),
+            xp_reward: input["xp_reward"].as_u64().unwrap_or(0) as u32,
+        }),
+        "update_quest_status" => Some(Intent::UpdateQuestStatus {
+            quest_name: input["quest_name"].as_str()?.to_string(),
+            status: parse_quest_status(input["status"].as_str()?)?,
+        }),
+        "complete_objective" => Some(Intent::CompleteObjective {
+            quest_name: input["quest_name"].as_str()?.to_string(),
+            objective: input["objective"].as_str()?.to_string(),
+        }),
 
         // Class feature tools
         "use_rage" => Some(Intent::UseRage {
@@ -1366,6 +2235,19 @@ pub fn parse_tool_call(name: &str, input: &Value, world: &GameWorld) -> Option<I
                 targets: vec![], // We pass target names separately
                 spell_level: slot_level,
                 target_names: targets,
+                ritual: input["ritual"].as_bool().unwrap_or(false),
+            })
+        }
+
+        "prepare_spells" => {
+            let spells = input["spells"]
+                .as_array()?
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+            Some(Intent::PrepareSpells {
+                character_id: world.player_character.id,
+                spells,
             })
         }
 
@@ -1391,7 +2273,7 @@ fn format_inventory(world: &GameWorld) -> String {
     output.push_str(&format!("=== {}'s Inventory ===\n\n", character.name));
 
     // Gold
-    output.push_str(&format!("Gold: {:.0} gp\n\n", character.inventory.gold));
+    output.push_str(&format!("Gold: {}\n\n", character.inventory.purse));
 
     // Current AC
     output.push_str(&format!("Current AC: {}\n\n", character.current_ac()));
@@ -1510,6 +2392,24 @@ fn parse_advantage(s: Option<&str>) -> Advantage {
     }
 }
 
+fn parse_quest_status(s: &str) -> Option<QuestStatus> {
+    match s.to_lowercase().as_str() {
+        "active" => Some(QuestStatus::Active),
+        "completed" | "complete" => Some(QuestStatus::Completed),
+        "failed" => Some(QuestStatus::Failed),
+        "abandoned" => Some(QuestStatus::Abandoned),
+        _ => None,
+    }
+}
+
+fn parse_lighting(s: Option<&str>) -> Lighting {
+    match s {
+        Some("dim") => Lighting::Dim,
+        Some("darkness") => Lighting::Darkness,
+        _ => Lighting::Bright,
+    }
+}
+
 fn parse_damage_type(s: &str) -> Option<DamageType> {
     match s.to_lowercase().as_str() {
         "slashing" => Some(DamageType::Slashing),
@@ -1529,6 +2429,59 @@ fn parse_damage_type(s: &str) -> Option<DamageType> {
     }
 }
 
+/// Resolve the optional `target` field to a tracked character, defaulting to the player.
+fn parse_target(world: &GameWorld, input: &Value) -> CharacterId {
+    input["target"]
+        .as_str()
+        .and_then(|name| world.character_by_name(name))
+        .map(|c| c.id)
+        .unwrap_or(world.player_character.id)
+}
+
+/// Resolve the optional `character` field to the player or a party member.
+/// A name outside the party gets a fresh id, which the rules engine refuses.
+fn parse_character(world: &GameWorld, input: &Value) -> CharacterId {
+    match input["character"].as_str() {
+        None => world.player_character.id,
+        Some(name) if name.eq_ignore_ascii_case("player") => world.player_character.id,
+        Some(name) => world
+            .character_by_name(name)
+            .map(|c| c.id)
+            .unwrap_or_default(),
+    }
+}
+
+/// Like `parse_target`, but a named target may also be a combatant.
+fn parse_creature_target(world: &GameWorld, input: &Value) -> CharacterId {
+    input["target"]
+        .as_str()
+        .map_or(world.player_character.id, |name| {
+            find_creature_id(world, name)
+        })
+}
+
+/// Look up a creature's id by name: the player, a party member, a combatant, or
+/// a fresh id for a creature the rules engine doesn't track.
+fn find_creature_id(world: &GameWorld, name: &str) -> CharacterId {
+    if name.eq_ignore_ascii_case("player") {
+        return world.player_character.id;
+    }
+    if let Some(character) = world.character_by_name(name) {
+        return character.id;
+    }
+    world
+        .combat
+        .as_ref()
+        .and_then(|combat| {
+            combat
+                .combatants
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(name))
+        })
+        .map(|c| c.id)
+        .unwrap_or_default()
+}
+
 fn parse_condition(s: &str) -> Option<Condition> {
     match s.to_lowercase().as_str() {
         "blinded" => Some(Condition::Blinded),
@@ -1552,7 +2505,7 @@ fn parse_condition(s: &str) -> Option<Condition> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::world::{Character, CharacterClass, ClassLevel, GameWorld};
+    use crate::world::{Character, CharacterClass, ClassLevel, GameWorld, Location, LocationType};
 
     fn create_test_world() -> GameWorld {
         let mut character = Character::new("Test Hero");
@@ -1761,6 +2714,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_tool_call_grapple() {
+        let world = create_test_world();
+        let input = json!({
+            "grappler": "Test Hero",
+            "target": "Goblin"
+        });
+
+        let intent = parse_tool_call("grapple", &input, &world);
+
+        if let Some(Intent::Grapple {
+            grappler_id,
+            target_id,
+        }) = intent
+        {
+            assert_eq!(grappler_id, world.player_character.id);
+            assert_ne!(target_id, world.player_character.id);
+        } else {
+            panic!("Expected Grapple intent");
+        }
+    }
+
+    #[test]
+    fn test_parse_tool_call_damage_targets_party_member() {
+        let mut world = create_test_world();
+        let companion_id = world.add_party_member(Character::new("Mira"));
+        let input = json!({
+            "amount": 4,
+            "damage_type": "piercing",
+            "source": "arrow",
+            "target": "Mira"
+        });
+
+        match parse_tool_call("apply_damage", &input, &world) {
+            Some(Intent::Damage { target_id, .. }) => assert_eq!(target_id, companion_id),
+            _ => panic!("Expected Damage intent"),
+        }
+    }
+
     #[test]
     fn test_parse_tool_call_unknown_tool() {
         let world = create_test_world();
@@ -1770,6 +2762,18 @@ mod tests {
         assert!(intent.is_none());
     }
 
+    #[test]
+    fn test_parse_travel_only_to_known_places() {
+        let mut world = create_test_world();
+        let road = Location::new("King's Road", LocationType::Road);
+        let road_id = road.id;
+        world.known_locations.insert(road_id, road);
+
+        let intent = parse_tool_call("travel", &json!({"destination": "king's road"}), &world);
+        assert!(matches!(intent, Some(Intent::Travel { to }) if to == road_id));
+        assert!(parse_tool_call("travel", &json!({"destination": "Atlantis"}), &world).is_none());
+    }
+
     #[test]
     fn test_parse_ability() {
         assert_eq!(parse_ability("strength"), Some(Ability::Strength));
@@ -0,0 +1,242 @@
+//! Feats a character can take in place of an Ability Score Improvement.
+//!
+//! Each feat lists its prerequisites and mechanical effects. Taking a feat
+//! applies those effects to the character and records the feat as a
+//! feature, so the rules engine and the DM both see it.
+
+use crate::world::{Ability, Character, Feature, FeatureUses, RechargeType};
+use std::fmt;
+use thiserror::Error;
+
+/// Source recorded on the features that feats add to a character.
+pub const FEAT_SOURCE: &str = "Feat";
+
+/// A requirement a character must meet before taking a feat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prerequisite {
+    /// Minimum character level.
+    Level(u8),
+    /// Minimum score in an ability.
+    Ability(Ability, u8),
+}
+
+impl fmt::Display for Prerequisite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Prerequisite::Level(level) => write!(f, "level {level}+"),
+            Prerequisite::Ability(ability, score) => write!(f, "{} {score}+", ability.name()),
+        }
+    }
+}
+
+/// A mechanical change a feat makes to the character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatEffect {
+    /// +1 to a fixed ability score, to a maximum of 20.
+    IncreaseAbility(Ability),
+    /// +1 to the ability chosen when taking the feat, to a maximum of 20.
+    IncreaseChosenAbility,
+    /// Saving throw proficiency in the chosen ability.
+    ChosenSaveProficiency,
+    /// Luck points equal to the proficiency bonus, regained on a long rest.
+    LuckPoints,
+}
+
+/// A feat from the registry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feat {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub prerequisites: &'static [Prerequisite],
+    pub effects: &'static [FeatEffect],
+}
+
+/// Why a character can't take a feat.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum FeatError {
+    #[error("Unknown feat: {0}")]
+    UnknownFeat(String),
+
+    #[error("{feat} requires {prerequisite}")]
+    UnmetPrerequisite {
+        feat: &'static str,
+        prerequisite: Prerequisite,
+    },
+
+    #[error("{0} requires choosing an ability")]
+    AbilityChoiceRequired(&'static str),
+
+    #[error("Already proficient in {} saving throws", .0.name())]
+    AlreadyProficient(Ability),
+
+    #[error("{0} has already been taken")]
+    AlreadyTaken(&'static str),
+}
+
+/// Common feats, looked up by name with [`get_feat`].
+pub const FEATS: &[Feat] = &[
+    Feat {
+        name: "Lucky",
+        description: "Spend a luck point to give yourself advantage on a d20 test, or to impose disadvantage on an attack roll against you.",
+        prerequisites: &[],
+        effects: &[FeatEffect::LuckPoints],
+    },
+    Feat {
+        name: "Resilient",
+        description: "+1 to the chosen ability and proficiency in its saving throws.",
+        prerequisites: &[Prerequisite::Level(4)],
+        effects: &[
+            FeatEffect::IncreaseChosenAbility,
+            FeatEffect::ChosenSaveProficiency,
+        ],
+    },
+];
+
+/// Look up a feat by name (case-insensitive).
+pub fn get_feat(name: &str) -> Option<&'static Feat> {
+    FEATS
+        .iter()
+        .find(|feat| feat.name.eq_ignore_ascii_case(name))
+}
+
+impl Feat {
+    /// Whether taking this feat needs an ability choice (e.g. Resilient).
+    pub fn needs_ability_choice(&self) -> bool {
+        self.effects.iter().any(|effect| {
+            matches!(
+                effect,
+                FeatEffect::IncreaseChosenAbility | FeatEffect::ChosenSaveProficiency
+            )
+        })
+    }
+
+    /// Check that `character` can take this feat with the given ability choice.
+    pub fn check(&self, character: &Character, ability: Option<Ability>) -> Result<(), FeatError> {
+        if character.has_feat(self.name) {
+            return Err(FeatError::AlreadyTaken(self.name));
+        }
+        for prerequisite in self.prerequisites {
+            let met = match *prerequisite {
+                Prerequisite::Level(level) => character.level >= level,
+                Prerequisite::Ability(ability, score) => {
+                    character.ability_scores.get(ability) >= score
+                }
+            };
+            if !met {
+                return Err(FeatError::UnmetPrerequisite {
+                    feat: self.name,
+                    prerequisite: *prerequisite,
+                });
+            }
+        }
+        if self.needs_ability_choice() {
+            let ability = ability.ok_or(FeatError::AbilityChoiceRequired(self.name))?;
+            if self.effects.contains(&FeatEffect::ChosenSaveProficiency)
+                && character.saving_throw_proficiencies.contains(&ability)
+            {
+                return Err(FeatError::AlreadyProficient(ability));
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply this feat's effects to `character` and record it as a feature.
+    ///
+    /// Call [`Feat::check`] first; this doesn't validate prerequisites.
+    pub fn apply(&self, character: &mut Character, ability: Option<Ability>) {
+        let mut uses = None;
+        for effect in self.effects {
+            match (*effect, ability) {
+                (FeatEffect::IncreaseAbility(ability), _)
+                | (FeatEffect::IncreaseChosenAbility, Some(ability)) => {
+                    let score = character.ability_scores.get(ability);
+                    character.ability_scores.set(ability, (score + 1).min(20));
+                }
+                (FeatEffect::ChosenSaveProficiency, Some(ability)) => {
+                    character.saving_throw_proficiencies.insert(ability);
+                }
+                (FeatEffect::LuckPoints, _) => {
+                    let points = character.proficiency_bonus() as u8;
+                    uses = Some(FeatureUses {
+                        current: points,
+                        maximum: points,
+                        recharge: RechargeType::LongRest,
+                    });
+                }
+                _ => {}
+            }
+        }
+        let name = match ability {
+            Some(ability) if self.needs_ability_choice() => {
+                format!("{} ({})", self.name, ability.abbreviation())
+            }
+            _ => self.name.to_string(),
+        };
+        character.features.push(Feature {
+            name,
+            description: self.description.to_string(),
+            source: FEAT_SOURCE.to_string(),
+            uses,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::create_sample_fighter;
+
+    #[test]
+    fn test_prerequisites_are_checked() {
+        let mut character = create_sample_fighter("Roland");
+        let resilient = get_feat("resilient").unwrap();
+        assert_eq!(
+            resilient.check(&character, Some(Ability::Wisdom)),
+            Err(FeatError::UnmetPrerequisite {
+                feat: "Resilient",
+                prerequisite: Prerequisite::Level(4),
+            })
+        );
+
+        character.level_up(4);
+        assert_eq!(resilient.check(&character, Some(Ability::Wisdom)), Ok(()));
+    }
+
+    #[test]
+    fn test_lucky_grants_luck_points_once() {
+        let mut character = create_sample_fighter("Roland");
+        let lucky = get_feat("Lucky").unwrap();
+        lucky.apply(&mut character, None);
+
+        let feature = character.features.last().unwrap();
+        assert_eq!(feature.source, FEAT_SOURCE);
+        assert_eq!(feature.uses.as_ref().unwrap().maximum, 2);
+        assert_eq!(
+            lucky.check(&character, None),
+            Err(FeatError::AlreadyTaken("Lucky"))
+        );
+    }
+
+    #[test]
+    fn test_resilient_needs_an_unproficient_ability() {
+        let mut character = create_sample_fighter("Roland");
+        character.level_up(4);
+        let resilient = get_feat("Resilient").unwrap();
+        assert_eq!(
+            resilient.check(&character, None),
+            Err(FeatError::AbilityChoiceRequired("Resilient"))
+        );
+        // Fighters already have CON save proficiency
+        assert_eq!(
+            resilient.check(&character, Some(Ability::Constitution)),
+            Err(FeatError::AlreadyProficient(Ability::Constitution))
+        );
+
+        resilient.apply(&mut character, Some(Ability::Wisdom));
+        assert!(character
+            .saving_throw_proficiencies
+            .contains(&Ability::Wisdom));
+        assert_eq!(character.ability_scores.get(Ability::Wisdom), 13);
+        assert!(character.has_feat("Resilient"));
+    }
+}
@@ -34,11 +34,12 @@
 use crate::character_builder::{
     roll_ability_scores, AbilityMethod, CharacterBuilder, STANDARD_ARRAY,
 };
-use crate::session::{GameSession, SessionConfig, SessionError};
+use crate::session::{GameSession, Response, SessionConfig, SessionError};
 use crate::world::{
     Ability, AbilityScores, Background, Character, CharacterClass, Condition, RaceType,
 };
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 /// Configuration for a headless game session.
 #[derive(Debug, Clone)]
@@ -57,6 +58,12 @@ pub struct HeadlessConfig {
     pub campaign_name: String,
     /// Starting location.
     pub starting_location: String,
+    /// Whether to print one JSON line per turn.
+    pub json_output: bool,
+    /// File of newline-separated player actions for [`HeadlessGame::run_script`].
+    pub script: Option<PathBuf>,
+    /// Seed for deterministic dice rolls.
+    pub seed: Option<u64>,
 }
 
 impl HeadlessConfig {
@@ -72,6 +79,9 @@ impl HeadlessConfig {
             ability_method: AbilityMethod::StandardArray,
             campaign_name: "Headless Adventure".to_string(),
             starting_location: "The Crossroads Inn".to_string(),
+            json_output: false,
+            script: None,
+            seed: None,
         }
     }
 
@@ -90,6 +100,9 @@ impl HeadlessConfig {
             ability_method: AbilityMethod::StandardArray,
             campaign_name: "Headless Adventure".to_string(),
             starting_location: "The Crossroads Inn".to_string(),
+            json_output: false,
+            script: None,
+            seed: None,
         }
     }
 
@@ -111,8 +124,35 @@ impl HeadlessConfig {
         self
     }
 
+    /// Emit each turn as a single JSON line for scripts and CI.
+    ///
+    /// Each line holds the turn number, player input, narrative, applied
+    /// effects, and the player's HP and level.
+    pub fn json_output(mut self, enabled: bool) -> Self {
+        self.json_output = enabled;
+        self
+    }
+
+    /// Read player actions from a file, one per line.
+    pub fn script(mut self, path: impl Into<PathBuf>) -> Self {
+        self.script = Some(path.into());
+        self
+    }
+
+    /// Seed the dice so a scripted run is reproducible.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     /// Build the character from this configuration.
-    fn build_character(&self) -> Result<Character, SessionError> {
+    ///
+    /// With a seed set, the dice are seeded first, so rolled ability scores
+    /// and every roll after them repeat from run to run.
+    pub fn build_character(&self) -> Result<Character, SessionError> {
+        if let Some(seed) = self.seed {
+            crate::dice::set_seed(Some(seed));
+        }
         let class_data = self.class.data();
 
         // Auto-select skills based on class
@@ -313,6 +353,12 @@ pub struct HeadlessGame {
     session: GameSession,
     /// Transcript of all exchanges.
     transcript: Vec<TranscriptEntry>,
+    /// Whether to write a JSON line per turn to `output`.
+    json_output: bool,
+    /// Where JSON turn lines go (stdout unless overridden).
+    output: Box<dyn Write + Send>,
+    /// Actions file played by [`HeadlessGame::run_script`].
+    script: Option<PathBuf>,
 }
 
 /// An entry in the game transcript.
@@ -339,23 +385,68 @@ impl HeadlessGame {
         let session = GameSession::new_with_character(session_config, character).await?;
 
         Ok(Self {
-            session,
-            transcript: Vec::new(),
+            json_output: config.json_output,
+            script: config.script,
+            ..Self::from_session(session)
         })
     }
 
     /// Load a saved game from a file.
     pub async fn load(path: impl AsRef<Path>) -> Result<Self, SessionError> {
         let session = GameSession::load(path).await?;
-        Ok(Self {
+        Ok(Self::from_session(session))
+    }
+
+    fn from_session(session: GameSession) -> Self {
+        Self {
             session,
             transcript: Vec::new(),
-        })
+            json_output: false,
+            output: Box::new(std::io::stdout()),
+            script: None,
+        }
+    }
+
+    /// Turn per-turn JSON lines on or off.
+    pub fn set_json_output(&mut self, enabled: bool) {
+        self.json_output = enabled;
+    }
+
+    /// Write JSON turn lines to `output` instead of stdout.
+    pub fn with_output(mut self, output: impl Write + Send + 'static) -> Self {
+        self.output = Box::new(output);
+        self
     }
 
     /// Send player input to the game and get a response.
     pub async fn send(&mut self, input: &str) -> Result<GameResponse, SessionError> {
         let response = self.session.player_action(input).await?;
+        self.record_turn(input, response)
+    }
+
+    /// Play every action in the configured script, in order, until EOF.
+    ///
+    /// Blank lines are skipped. Returns one response per action, or an empty
+    /// list when no script was configured.
+    pub async fn run_script(&mut self) -> Result<Vec<GameResponse>, SessionError> {
+        let Some(path) = self.script.clone() else {
+            return Ok(Vec::new());
+        };
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let mut responses = Vec::new();
+        for action in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            responses.push(self.send(action).await?);
+        }
+        Ok(responses)
+    }
+
+    /// Record a resolved turn in the transcript and emit it if JSON output is on.
+    fn record_turn(
+        &mut self,
+        input: &str,
+        response: Response,
+    ) -> Result<GameResponse, SessionError> {
         let (current_hp, max_hp) = self.session.hp_status();
 
         // Record in transcript
@@ -365,6 +456,20 @@ impl HeadlessGame {
             turn: self.transcript.len() + 1,
         });
 
+        if self.json_output {
+            let line = serde_json::json!({
+                "turn": self.transcript.len(),
+                "input": input,
+                "narrative": response.narrative,
+                "effects": response.effects,
+                "current_hp": current_hp,
+                "max_hp": max_hp,
+                "level": self.session.world().player_character.level,
+            });
+            writeln!(self.output, "{line}")?;
+            self.output.flush()?;
+        }
+
         Ok(GameResponse {
             narrative: response.narrative,
             in_combat: response.in_combat,
@@ -508,4 +613,141 @@ mod tests {
         let character = config.build_character().unwrap();
         assert_eq!(character.name, "Test Hero");
     }
+
+    #[test]
+    fn test_json_output_turn() {
+        use crate::dm::DungeonMaster;
+        use crate::rules::Effect;
+        use crate::world::{create_sample_fighter, GameWorld};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+        impl Write for SharedBuf {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let world = GameWorld::new("JSON Test", create_sample_fighter("Roland"));
+        let session = GameSession::with_world(DungeonMaster::new("test-key"), world);
+        let buf = SharedBuf::default();
+        let mut game = HeadlessGame::from_session(session).with_output(buf.clone());
+        game.set_json_output(true);
+
+        let response = Response {
+            narrative: "You find a coin.".to_string(),
+            effects: vec![Effect::ExperienceGained {
+                character_id: crate::world::CharacterId::new(),
+                amount: 10,
+                new_total: 910,
+            }],
+            in_combat: false,
+            is_player_turn: false,
+        };
+        game.record_turn("I search the floor", response).unwrap();
+
+        let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let json: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(json["turn"], 1);
+        assert_eq!(json["input"], "I search the floor");
+        assert_eq!(json["narrative"], "You find a coin.");
+        assert_eq!(json["effects"].as_array().unwrap().len(), 1);
+        assert_eq!(json["current_hp"], 28);
+        assert_eq!(json["max_hp"], 28);
+        assert_eq!(json["level"], 3);
+    }
+
+    #[test]
+    fn test_json_output_config() {
+        assert!(!HeadlessConfig::quick_start("Thorin").json_output);
+        assert!(
+            HeadlessConfig::quick_start("Thorin")
+                .json_output(true)
+                .json_output
+        );
+    }
+
+    /// A mock API answering "Narrative 1", then "Narrative 2".
+    async fn numbered_dm_server() -> String {
+        use claude::testing::{mock_server, MockResponse};
+
+        let reply = |n: u32| {
+            MockResponse::json(serde_json::json!({
+                "id": format!("msg_{n}"),
+                "model": "claude-test",
+                "content": [{"type": "text", "text": format!("Narrative {n}")}],
+                "stop_reason": "end_turn",
+                "usage": {"input_tokens": 100, "output_tokens": 20},
+            }))
+        };
+        mock_server(vec![reply(1), reply(2)]).await.0
+    }
+
+    #[tokio::test]
+    async fn test_run_script() {
+        use crate::dm::DungeonMaster;
+        use crate::world::{create_sample_fighter, GameWorld};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let script = temp_dir.path().join("actions.txt");
+        std::fs::write(&script, "I look around the tavern\n\nI order an ale\n").unwrap();
+
+        let url = numbered_dm_server().await;
+        let dm = DungeonMaster::new("test-key")
+            .with_client(claude::Claude::new("test-key").with_base_url(url));
+        let world = GameWorld::new("Script Test", create_sample_fighter("Roland"));
+        let mut game = HeadlessGame::from_session(GameSession::with_world(dm, world));
+        game.script = HeadlessConfig::quick_start("Roland").script(&script).script;
+
+        let responses = game.run_script().await.unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].narrative, "Narrative 1");
+        assert_eq!(responses[1].narrative, "Narrative 2");
+
+        let inputs: Vec<&str> = game
+            .transcript()
+            .iter()
+            .map(|e| e.player_input.as_str())
+            .collect();
+        assert_eq!(inputs, vec!["I look around the tavern", "I order an ale"]);
+    }
+
+    #[tokio::test]
+    async fn test_session_stats_accumulate_and_persist() {
+        use crate::dm::DungeonMaster;
+        use crate::world::{create_sample_fighter, GameWorld};
+
+        let url = numbered_dm_server().await;
+        let dm = DungeonMaster::new("test-key")
+            .with_client(claude::Claude::new("test-key").with_base_url(url));
+        let world = GameWorld::new("Stats Test", create_sample_fighter("Roland"));
+        let mut game = HeadlessGame::from_session(GameSession::with_world(dm, world));
+
+        game.send("I look around").await.unwrap();
+        game.send("I order an ale").await.unwrap();
+        let stats = game.session.stats();
+        assert_eq!(stats.total_input_tokens, 200);
+        assert_eq!(stats.total_output_tokens, 40);
+        assert_eq!(stats.turns, 2);
+        // 200 input at $3/M plus 40 output at $15/M
+        assert!((game.session.estimated_cost() - 0.0012).abs() < 1e-9);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("stats.json");
+        game.session.save(&path).await.unwrap();
+        let loaded = GameSession::load_with_dm(&path, DungeonMaster::new("test-key"))
+            .await
+            .unwrap();
+        assert_eq!(loaded.stats(), game.session.stats());
+    }
 }
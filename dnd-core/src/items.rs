@@ -7,6 +7,19 @@ use crate::world::{
     ArmorItem, ArmorType, ConsumableEffect, ConsumableItem, Item, ItemType, WeaponDamageType,
     WeaponItem, WeaponProperty,
 };
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+/// Errors from loading custom item definitions.
+#[derive(Debug, Error)]
+pub enum ItemError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
 
 /// Get a standard weapon by name.
 pub fn get_weapon(name: &str) -> Option<WeaponItem> {
@@ -32,6 +45,80 @@ pub fn get_adventuring_gear(name: &str) -> Option<Item> {
     ADVENTURING_GEAR.iter().find(|i| i.name.to_lowercase() == name_lower).cloned()
 }
 
+/// Homebrew items a campaign has registered, saved with its world.
+///
+/// Lookups through the registry prefer registered items over the standard
+/// ones, so equipping and attacking use the homebrew stats.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ItemRegistry {
+    items: Vec<StandardItem>,
+}
+
+impl ItemRegistry {
+    /// Register a homebrew item, replacing any earlier one with the same name.
+    pub fn register(&mut self, item: StandardItem) {
+        let name = item.as_item().name.to_lowercase();
+        self.items
+            .retain(|existing| existing.as_item().name.to_lowercase() != name);
+        self.items.push(item);
+    }
+
+    /// Register every item in a JSON file holding an array of [`StandardItem`]s.
+    ///
+    /// Returns how many items were registered.
+    pub fn load_from_json(&mut self, path: impl AsRef<Path>) -> Result<usize, ItemError> {
+        let content = std::fs::read_to_string(path)?;
+        let items: Vec<StandardItem> = serde_json::from_str(&content)?;
+        let count = items.len();
+        for item in items {
+            self.register(item);
+        }
+        Ok(count)
+    }
+
+    /// The registered items, in registration order.
+    pub fn items(&self) -> &[StandardItem] {
+        &self.items
+    }
+
+    fn registered(&self, name: &str) -> Option<&StandardItem> {
+        let name_lower = name.to_lowercase();
+        self.items
+            .iter()
+            .find(|item| item.as_item().name.to_lowercase() == name_lower)
+    }
+
+    /// Get a weapon by name, preferring registered weapons.
+    pub fn get_weapon(&self, name: &str) -> Option<WeaponItem> {
+        match self.registered(name) {
+            Some(StandardItem::Weapon(weapon)) => Some(weapon.clone()),
+            _ => get_weapon(name),
+        }
+    }
+
+    /// Get an armor piece by name, preferring registered armor.
+    pub fn get_armor(&self, name: &str) -> Option<ArmorItem> {
+        match self.registered(name) {
+            Some(StandardItem::Armor(armor)) => Some(armor.clone()),
+            _ => get_armor(name),
+        }
+    }
+
+    /// Get a potion by name, preferring registered consumables.
+    pub fn get_potion(&self, name: &str) -> Option<ConsumableItem> {
+        match self.registered(name) {
+            Some(StandardItem::Consumable(potion)) => Some(potion.clone()),
+            _ => get_potion(name),
+        }
+    }
+
+    /// Find any item by name, preferring registered items.
+    pub fn find_item(&self, name: &str) -> Option<StandardItem> {
+        self.registered(name).cloned().or_else(|| find_item(name))
+    }
+}
+
 /// Try to find any standard item by name.
 pub fn find_item(name: &str) -> Option<StandardItem> {
     if let Some(weapon) = get_weapon(name) {
@@ -50,7 +137,8 @@ pub fn find_item(name: &str) -> Option<StandardItem> {
 }
 
 /// A standard item from the database.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum StandardItem {
     Weapon(WeaponItem),
     Armor(ArmorItem),
@@ -125,72 +213,90 @@ lazy_static::lazy_static! {
 
         // Martial Melee Weapons
         WeaponItem::new("Battleaxe", "1d8", WeaponDamageType::Slashing)
+            .martial()
             .with_weight(4.0)
             .with_value(10.0)
             .with_properties(vec![WeaponProperty::Versatile("1d10".to_string())]),
         WeaponItem::new("Flail", "1d8", WeaponDamageType::Bludgeoning)
+            .martial()
             .with_weight(2.0)
             .with_value(10.0),
         WeaponItem::new("Glaive", "1d10", WeaponDamageType::Slashing)
+            .martial()
             .with_weight(6.0)
             .with_value(20.0)
             .with_properties(vec![WeaponProperty::Heavy, WeaponProperty::Reach, WeaponProperty::TwoHanded]),
         WeaponItem::new("Greataxe", "1d12", WeaponDamageType::Slashing)
+            .martial()
             .with_weight(7.0)
             .with_value(30.0)
             .with_properties(vec![WeaponProperty::Heavy, WeaponProperty::TwoHanded]),
         WeaponItem::new("Greatsword", "2d6", WeaponDamageType::Slashing)
+            .martial()
             .with_weight(6.0)
             .with_value(50.0)
             .with_properties(vec![WeaponProperty::Heavy, WeaponProperty::TwoHanded]),
         WeaponItem::new("Halberd", "1d10", WeaponDamageType::Slashing)
+            .martial()
             .with_weight(6.0)
             .with_value(20.0)
             .with_properties(vec![WeaponProperty::Heavy, WeaponProperty::Reach, WeaponProperty::TwoHanded]),
         WeaponItem::new("Lance", "1d12", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(6.0)
             .with_value(10.0)
             .with_properties(vec![WeaponProperty::Reach]),
         WeaponItem::new("Longsword", "1d8", WeaponDamageType::Slashing)
+            .martial()
             .with_weight(3.0)
             .with_value(15.0)
             .with_properties(vec![WeaponProperty::Versatile("1d10".to_string())]),
         WeaponItem::new("Maul", "2d6", WeaponDamageType::Bludgeoning)
+            .martial()
             .with_weight(10.0)
             .with_value(10.0)
             .with_properties(vec![WeaponProperty::Heavy, WeaponProperty::TwoHanded]),
         WeaponItem::new("Morningstar", "1d8", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(4.0)
             .with_value(15.0),
         WeaponItem::new("Pike", "1d10", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(18.0)
             .with_value(5.0)
             .with_properties(vec![WeaponProperty::Heavy, WeaponProperty::Reach, WeaponProperty::TwoHanded]),
         WeaponItem::new("Rapier", "1d8", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(2.0)
             .with_value(25.0)
             .with_properties(vec![WeaponProperty::Finesse]),
         WeaponItem::new("Scimitar", "1d6", WeaponDamageType::Slashing)
+            .martial()
             .with_weight(3.0)
             .with_value(25.0)
             .with_properties(vec![WeaponProperty::Finesse, WeaponProperty::Light]),
         WeaponItem::new("Shortsword", "1d6", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(2.0)
             .with_value(10.0)
             .with_properties(vec![WeaponProperty::Finesse, WeaponProperty::Light]),
         WeaponItem::new("Trident", "1d6", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(4.0)
             .with_value(5.0)
             .with_properties(vec![WeaponProperty::Thrown, WeaponProperty::Versatile("1d8".to_string())])
             .with_range(20, 60),
         WeaponItem::new("War Pick", "1d8", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(2.0)
             .with_value(5.0),
         WeaponItem::new("Warhammer", "1d8", WeaponDamageType::Bludgeoning)
+            .martial()
             .with_weight(2.0)
             .with_value(15.0)
             .with_properties(vec![WeaponProperty::Versatile("1d10".to_string())]),
         WeaponItem::new("Whip", "1d4", WeaponDamageType::Slashing)
+            .martial()
             .with_weight(3.0)
             .with_value(2.0)
             .with_properties(vec![WeaponProperty::Finesse, WeaponProperty::Reach]),
@@ -209,16 +315,19 @@ lazy_static::lazy_static! {
 
         // Martial Ranged Weapons
         WeaponItem::new("Hand Crossbow", "1d6", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(3.0)
             .with_value(75.0)
             .with_properties(vec![WeaponProperty::Ammunition, WeaponProperty::Light, WeaponProperty::Loading])
             .with_range(30, 120),
         WeaponItem::new("Heavy Crossbow", "1d10", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(18.0)
             .with_value(50.0)
             .with_properties(vec![WeaponProperty::Ammunition, WeaponProperty::Heavy, WeaponProperty::Loading, WeaponProperty::TwoHanded])
             .with_range(100, 400),
         WeaponItem::new("Longbow", "1d8", WeaponDamageType::Piercing)
+            .martial()
             .with_weight(2.0)
             .with_value(50.0)
             .with_properties(vec![WeaponProperty::Ammunition, WeaponProperty::Heavy, WeaponProperty::TwoHanded])
@@ -295,6 +404,7 @@ lazy_static::lazy_static! {
                 description: Some("Grants advantage on saving throws against poison for 1 hour.".to_string()),
                 item_type: ItemType::Potion,
                 magical: false,
+                requires_attunement: false,
             },
             effect: ConsumableEffect::GrantAdvantage {
                 roll_type: "poison saves".to_string(),
@@ -313,6 +423,7 @@ lazy_static::lazy_static! {
             description: Some("A leather pack for carrying gear.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Bedroll".to_string(),
@@ -322,6 +433,7 @@ lazy_static::lazy_static! {
             description: None,
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Rope (50 feet)".to_string(),
@@ -331,6 +443,7 @@ lazy_static::lazy_static! {
             description: Some("Hemp rope, 50 feet.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Torch".to_string(),
@@ -340,6 +453,7 @@ lazy_static::lazy_static! {
             description: Some("Provides bright light in 20-foot radius, dim light for 20 feet beyond. Burns for 1 hour.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Rations (1 day)".to_string(),
@@ -349,6 +463,7 @@ lazy_static::lazy_static! {
             description: Some("Trail rations for one day.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Waterskin".to_string(),
@@ -358,6 +473,7 @@ lazy_static::lazy_static! {
             description: Some("Holds 4 pints of liquid.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Tinderbox".to_string(),
@@ -367,6 +483,7 @@ lazy_static::lazy_static! {
             description: Some("Used to light fires.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Lantern".to_string(),
@@ -376,6 +493,7 @@ lazy_static::lazy_static! {
             description: Some("A hooded lantern casts bright light in 30-foot radius.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Oil Flask".to_string(),
@@ -385,6 +503,7 @@ lazy_static::lazy_static! {
             description: Some("Flask of oil for lanterns or as improvised weapon.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Grappling Hook".to_string(),
@@ -394,6 +513,7 @@ lazy_static::lazy_static! {
             description: None,
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Crowbar".to_string(),
@@ -403,6 +523,7 @@ lazy_static::lazy_static! {
             description: Some("Grants advantage on Strength checks to pry things open.".to_string()),
             item_type: ItemType::Tool,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Thieves' Tools".to_string(),
@@ -412,6 +533,7 @@ lazy_static::lazy_static! {
             description: Some("Required for picking locks and disarming traps.".to_string()),
             item_type: ItemType::Tool,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Holy Symbol".to_string(),
@@ -421,6 +543,7 @@ lazy_static::lazy_static! {
             description: Some("A religious focus for spellcasting.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Arcane Focus".to_string(),
@@ -430,6 +553,17 @@ lazy_static::lazy_static! {
             description: Some("A crystal, orb, or similar item used as a spellcasting focus.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
+        },
+        Item {
+            name: "Druidic Focus".to_string(),
+            quantity: 1,
+            weight: 1.0,
+            value_gp: 1.0,
+            description: Some("A sprig of mistletoe, totem, or wooden staff used as a spellcasting focus.".to_string()),
+            item_type: ItemType::Adventuring,
+            magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Component Pouch".to_string(),
@@ -439,6 +573,17 @@ lazy_static::lazy_static! {
             description: Some("A small pouch containing spell components.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
+        },
+        Item {
+            name: "Spellbook".to_string(),
+            quantity: 1,
+            weight: 3.0,
+            value_gp: 50.0,
+            description: Some("A leather-bound tome of vellum pages for recording wizard spells.".to_string()),
+            item_type: ItemType::Adventuring,
+            magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Arrows (20)".to_string(),
@@ -448,6 +593,7 @@ lazy_static::lazy_static! {
             description: Some("A quiver of 20 arrows.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Bolts (20)".to_string(),
@@ -457,6 +603,7 @@ lazy_static::lazy_static! {
             description: Some("A case of 20 crossbow bolts.".to_string()),
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         },
         Item {
             name: "Shield".to_string(),
@@ -466,6 +613,7 @@ lazy_static::lazy_static! {
             description: Some("A wooden or metal shield. +2 AC when equipped.".to_string()),
             item_type: ItemType::Shield,
             magical: false,
+            requires_attunement: false,
         },
     ];
 }
@@ -506,4 +654,29 @@ mod tests {
         assert!(matches!(find_item("Rope (50 feet)"), Some(StandardItem::Item(_))));
         assert!(find_item("Nonexistent Item").is_none());
     }
+
+    #[test]
+    fn test_registered_items_take_precedence() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("homebrew.json");
+        let homebrew = vec![StandardItem::Weapon(
+            WeaponItem::new("Frostbrand Dagger", "1d4", WeaponDamageType::Piercing)
+                .with_properties(vec![WeaponProperty::Finesse, WeaponProperty::Light])
+                .with_magic_bonus(2),
+        )];
+        std::fs::write(&path, serde_json::to_string(&homebrew).unwrap()).unwrap();
+
+        let mut registry = ItemRegistry::default();
+        assert_eq!(registry.load_from_json(&path).unwrap(), 1);
+        let dagger = registry.get_weapon("frostbrand dagger").unwrap();
+        assert_eq!(dagger.magic_bonus, 2);
+        assert!(dagger.is_finesse());
+        assert!(matches!(
+            registry.find_item("Frostbrand Dagger"),
+            Some(StandardItem::Weapon(_))
+        ));
+        // Standard items are still there, and other registries are untouched
+        assert!(registry.get_weapon("Longsword").is_some());
+        assert!(get_weapon("Frostbrand Dagger").is_none());
+    }
 }
@@ -26,12 +26,15 @@
 //! }
 //! ```
 
+pub mod background_data;
 pub mod character_builder;
 pub mod class_data;
 pub mod dice;
 pub mod dm;
+pub mod feats;
 pub mod headless;
 pub mod items;
+pub mod monsters;
 pub mod persist;
 pub mod rules;
 pub mod session;
@@ -39,15 +42,35 @@ pub mod spells;
 pub mod testing;
 pub mod world;
 
+// Lets `#[derive(Tool)]` name `::dnd_core` from inside this crate too
+extern crate self as dnd_core;
+
 // Re-export for convenience
 pub use dnd_macros::Tool;
 
+/// A type whose JSON schema can be inlined into a tool's input schema.
+///
+/// Implemented by `#[derive(Tool)]`; field types that aren't primitives,
+/// `String`, `Option`, or `Vec` need it.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` has no tool input schema",
+    label = "this field's type must derive `Tool`",
+    note = "add `#[derive(Tool)]` to `{Self}`, or use a primitive, `String`, `Option`, or `Vec` field"
+)]
+pub trait ToolSchema {
+    /// The JSON schema describing this type's input.
+    fn input_schema() -> serde_json::Value;
+}
+
 // Primary public API
-pub use character_builder::{AbilityMethod, CharacterBuilder};
+pub use character_builder::{AbilityMethod, CharacterBuilder, EquipmentChoices};
 pub use headless::{HeadlessConfig, HeadlessGame};
 pub use persist::{CharacterMetadata, CharacterSaveInfo, SavedCharacter};
-pub use session::{GameSession, Response, SessionConfig, SessionError};
-pub use testing::{MockDm, MockResponse, TestHarness};
+pub use session::{
+    Checkpoint, GameSession, NarrativeChunk, Response, SessionConfig, SessionError, SessionStats,
+    TokenPricing,
+};
+pub use testing::{MockDm, MockExhausted, MockResponse, TestHarness, WhenExhausted};
 pub use world::{Background, CharacterClass, RaceType};
 
 #[cfg(test)]
@@ -66,6 +89,126 @@ mod tests {
         purpose: Option<String>,
     }
 
+    /// How to roll a d20
+    #[derive(Tool, Deserialize)]
+    #[allow(dead_code)]
+    enum RollMode {
+        Normal,
+        Advantage,
+        #[tool(rename = "disadvantage")]
+        #[serde(rename = "disadvantage")]
+        Disadvantage,
+    }
+
+    /// How a combatant holds their ground
+    #[derive(Tool, Deserialize, Debug, PartialEq)]
+    #[tool(rename_all = "kebab-case")]
+    #[serde(rename_all = "kebab-case")]
+    enum Stance {
+        HoldGround,
+        FullDefense,
+    }
+
+    /// Make an ability check
+    #[derive(Tool, Deserialize)]
+    #[allow(dead_code)]
+    struct AbilityCheck {
+        /// How to roll
+        mode: RollMode,
+        /// Optional bonus roll mode for a reroll
+        reroll_mode: Option<RollMode>,
+        /// Ability used for the check
+        #[tool(enum("strength", "dexterity", "constitution"))]
+        ability: String,
+    }
+
+    /// A monster joining the fight
+    #[derive(Tool, Deserialize)]
+    #[allow(dead_code)]
+    struct CombatantEntry {
+        /// Display name
+        name: String,
+        /// Initiative bonus
+        initiative_bonus: Option<i32>,
+    }
+
+    /// Start combat
+    #[derive(Tool, Deserialize)]
+    #[allow(dead_code)]
+    struct StartCombat {
+        /// The creature that started the fight
+        leader: CombatantEntry,
+        /// Everyone else in the fight
+        combatants: Vec<CombatantEntry>,
+    }
+
+    /// Cast a spell
+    #[derive(Tool, Deserialize)]
+    #[allow(dead_code)]
+    struct CastSpell {
+        /// Name of the spell
+        #[tool(min_length = 1, max_length = 64)]
+        spell_name: String,
+        /// Slot level to cast at
+        #[tool(min = 1, max = 9)]
+        slot_level: u8,
+        /// Importance of the moment
+        #[tool(optional, min = 0.0, max = 1.0)]
+        importance: Option<f32>,
+    }
+
+    /// Update an NPC's free-form attributes
+    #[derive(Tool, Deserialize)]
+    #[allow(dead_code)]
+    struct UpdateNpc {
+        /// Numeric attributes like "favor"
+        scores: std::collections::HashMap<String, i32>,
+        /// Notes keyed by topic
+        notes: Option<std::collections::BTreeMap<String, Vec<String>>>,
+    }
+
+    /// Spend a spell slot
+    #[derive(Tool, Deserialize)]
+    #[tool(name = "spend_slot", rename_all = "camelCase")]
+    #[serde(rename_all = "camelCase")]
+    #[allow(dead_code)]
+    struct SpendSlot {
+        /// Level of the slot
+        spell_slot_level: u8,
+        /// Who is casting
+        #[tool(rename = "caster")]
+        #[serde(rename = "caster")]
+        caster_name: Option<String>,
+    }
+
+    /// Spend a spell slot
+    #[derive(Tool, Deserialize)]
+    #[tool(rename_all = "kebab-case")]
+    #[serde(rename_all = "kebab-case")]
+    #[allow(dead_code)]
+    struct SpendSlotKebab {
+        spell_slot_level: u8,
+    }
+
+    /// Spend a spell slot
+    #[derive(Tool, Deserialize)]
+    #[tool(rename_all = "PascalCase")]
+    #[serde(rename_all = "PascalCase")]
+    #[allow(dead_code)]
+    struct SpendSlotPascal {
+        spell_slot_level: u8,
+    }
+
+    /// Spend a spell slot, named only through serde
+    #[derive(Tool, Deserialize)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    #[allow(dead_code)]
+    struct SpendSlotScreaming {
+        spell_slot_level: u8,
+        #[serde(rename(deserialize = "who"))]
+        caster_name: Option<String>,
+    }
+
     #[test]
     fn test_tool_derive() {
         assert_eq!(RollDice::tool_name(), "roll_dice");
@@ -88,6 +231,222 @@ mod tests {
         assert!(!required.iter().any(|v| v == "purpose"));
     }
 
+    #[test]
+    fn test_enum_field_schema() {
+        assert_eq!(
+            RollMode::variant_names(),
+            &["Normal", "Advantage", "disadvantage"]
+        );
+
+        let schema = AbilityCheck::input_schema();
+        let mode = &schema["properties"]["mode"];
+        assert_eq!(mode["type"], "string");
+        assert_eq!(
+            mode["enum"],
+            serde_json::json!(["Normal", "Advantage", "disadvantage"])
+        );
+        assert_eq!(mode["description"], "How to roll");
+        assert_eq!(schema["properties"]["reroll_mode"]["enum"], mode["enum"]);
+
+        let ability = &schema["properties"]["ability"];
+        assert_eq!(ability["type"], "string");
+        assert_eq!(
+            ability["enum"],
+            serde_json::json!(["strength", "dexterity", "constitution"])
+        );
+
+        // Enum fields are required unless they're Option
+        let required = schema["required"].as_array().unwrap();
+        assert!(required.iter().any(|v| v == "mode"));
+        assert!(required.iter().any(|v| v == "ability"));
+        assert!(!required.iter().any(|v| v == "reroll_mode"));
+    }
+
+    #[test]
+    fn test_nested_struct_schema() {
+        let schema = StartCombat::input_schema();
+
+        let leader = &schema["properties"]["leader"];
+        assert_eq!(leader["type"], "object");
+        assert_eq!(leader["description"], "The creature that started the fight");
+        assert_eq!(leader["properties"]["name"]["type"], "string");
+        assert_eq!(leader["properties"]["initiative_bonus"]["type"], "integer");
+        assert_eq!(leader["required"], serde_json::json!(["name"]));
+
+        let combatants = &schema["properties"]["combatants"];
+        assert_eq!(combatants["type"], "array");
+        assert_eq!(combatants["items"]["type"], "object");
+        assert_eq!(combatants["items"]["properties"]["name"]["type"], "string");
+        assert_eq!(combatants["items"]["required"], serde_json::json!(["name"]));
+    }
+
+    #[test]
+    fn test_constraint_schema() {
+        let schema = CastSpell::input_schema();
+
+        let name = &schema["properties"]["spell_name"];
+        assert_eq!(name["type"], "string");
+        assert_eq!(name["minLength"], 1);
+        assert_eq!(name["maxLength"], 64);
+
+        let level = &schema["properties"]["slot_level"];
+        assert_eq!(level["type"], "integer");
+        assert_eq!(level["minimum"], 1);
+        assert_eq!(level["maximum"], 9);
+        assert!(level.get("minLength").is_none());
+
+        let importance = &schema["properties"]["importance"];
+        assert_eq!(importance["minimum"], 0.0);
+        assert_eq!(importance["maximum"], 1.0);
+        assert_eq!(
+            schema["required"],
+            serde_json::json!(["spell_name", "slot_level"])
+        );
+    }
+
+    #[test]
+    fn test_from_input() {
+        let input = serde_json::json!({
+            "notation": "2d6+3",
+            "purpose": "damage"
+        });
+        let roll = RollDice::from_input(input).unwrap();
+        assert_eq!(roll.notation, "2d6+3");
+        assert_eq!(roll.purpose.as_deref(), Some("damage"));
+
+        let check = AbilityCheck::from_input(serde_json::json!({
+            "mode": "Advantage",
+            "ability": "strength"
+        }))
+        .unwrap();
+        assert!(matches!(check.mode, RollMode::Advantage));
+        assert!(check.reroll_mode.is_none());
+
+        assert!(RollDice::from_input(serde_json::json!({"purpose": "oops"})).is_err());
+    }
+
+    #[test]
+    fn test_map_field_schema() {
+        let schema = UpdateNpc::input_schema();
+
+        let scores = &schema["properties"]["scores"];
+        assert_eq!(scores["type"], "object");
+        assert_eq!(scores["additionalProperties"]["type"], "integer");
+        assert_eq!(scores["description"], "Numeric attributes like \"favor\"");
+
+        let notes = &schema["properties"]["notes"];
+        assert_eq!(notes["type"], "object");
+        assert_eq!(notes["additionalProperties"]["type"], "array");
+        assert_eq!(notes["additionalProperties"]["items"]["type"], "string");
+        assert_eq!(schema["required"], serde_json::json!(["scores"]));
+    }
+
+    #[test]
+    fn test_rename_all_schema() {
+        assert_eq!(SpendSlot::tool_name(), "spend_slot");
+        let schema = SpendSlot::input_schema();
+        assert_eq!(schema["properties"]["spellSlotLevel"]["type"], "integer");
+        assert!(schema["properties"].get("spell_slot_level").is_none());
+        assert_eq!(schema["properties"]["caster"]["type"], "string");
+        assert_eq!(schema["required"], serde_json::json!(["spellSlotLevel"]));
+
+        let kebab = SpendSlotKebab::input_schema();
+        assert_eq!(kebab["required"], serde_json::json!(["spell-slot-level"]));
+        let pascal = SpendSlotPascal::input_schema();
+        assert_eq!(pascal["required"], serde_json::json!(["SpellSlotLevel"]));
+
+        let screaming = SpendSlotScreaming::input_schema();
+        assert_eq!(
+            screaming["required"],
+            serde_json::json!(["SPELL_SLOT_LEVEL"])
+        );
+        assert_eq!(screaming["properties"]["who"]["type"], "string");
+    }
+
+    #[test]
+    fn test_renamed_fields_round_trip() {
+        let slot = SpendSlot::from_input(serde_json::json!({
+            "spellSlotLevel": 3,
+            "caster": "Mira"
+        }))
+        .unwrap();
+        assert_eq!(slot.spell_slot_level, 3);
+        assert_eq!(slot.caster_name.as_deref(), Some("Mira"));
+        assert!(SpendSlot::from_input(serde_json::json!({"spell_slot_level": 3})).is_err());
+
+        let kebab = SpendSlotKebab::from_input(serde_json::json!({"spell-slot-level": 2}));
+        assert_eq!(kebab.unwrap().spell_slot_level, 2);
+
+        let pascal = SpendSlotPascal::from_input(serde_json::json!({"SpellSlotLevel": 4}));
+        assert_eq!(pascal.unwrap().spell_slot_level, 4);
+
+        let screaming = SpendSlotScreaming::from_input(serde_json::json!({
+            "SPELL_SLOT_LEVEL": 5,
+            "who": "Roland"
+        }))
+        .unwrap();
+        assert_eq!(screaming.spell_slot_level, 5);
+        assert_eq!(screaming.caster_name.as_deref(), Some("Roland"));
+
+        let mode = RollMode::from_input(serde_json::json!("disadvantage"));
+        assert!(matches!(mode.unwrap(), RollMode::Disadvantage));
+
+        assert_eq!(Stance::variant_names(), &["hold-ground", "full-defense"]);
+        for name in Stance::variant_names() {
+            assert!(Stance::from_input(serde_json::json!(name)).is_ok());
+        }
+        assert_eq!(
+            Stance::from_input(serde_json::json!("full-defense")).unwrap(),
+            Stance::FullDefense
+        );
+    }
+
+    /// Build an input from a schema alone, filling every listed property.
+    fn sample_input(schema: &serde_json::Value) -> serde_json::Value {
+        use serde_json::json;
+        if let Some(values) = schema["enum"].as_array() {
+            return values[0].clone();
+        }
+        match schema["type"].as_str() {
+            Some("string") => json!("x"),
+            Some("integer") => schema.get("minimum").cloned().unwrap_or(json!(1)),
+            Some("number") => schema.get("minimum").cloned().unwrap_or(json!(0.5)),
+            Some("boolean") => json!(true),
+            Some("array") => json!([sample_input(&schema["items"])]),
+            Some("object") => {
+                let mut object = serde_json::Map::new();
+                if let Some(properties) = schema["properties"].as_object() {
+                    for (name, property) in properties {
+                        object.insert(name.clone(), sample_input(property));
+                    }
+                }
+                serde_json::Value::Object(object)
+            }
+            other => panic!("unexpected schema type {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_schema_names_parse() {
+        // Whatever the schema advertises, from_input must accept
+        assert!(RollDice::from_input(sample_input(&RollDice::input_schema())).is_ok());
+        assert!(RollMode::from_input(sample_input(&RollMode::input_schema())).is_ok());
+        assert!(Stance::from_input(sample_input(&Stance::input_schema())).is_ok());
+        assert!(AbilityCheck::from_input(sample_input(&AbilityCheck::input_schema())).is_ok());
+        assert!(StartCombat::from_input(sample_input(&StartCombat::input_schema())).is_ok());
+        assert!(CastSpell::from_input(sample_input(&CastSpell::input_schema())).is_ok());
+        assert!(UpdateNpc::from_input(sample_input(&UpdateNpc::input_schema())).is_ok());
+        assert!(SpendSlot::from_input(sample_input(&SpendSlot::input_schema())).is_ok());
+        assert!(SpendSlotKebab::from_input(sample_input(&SpendSlotKebab::input_schema())).is_ok());
+        assert!(
+            SpendSlotPascal::from_input(sample_input(&SpendSlotPascal::input_schema())).is_ok()
+        );
+        assert!(
+            SpendSlotScreaming::from_input(sample_input(&SpendSlotScreaming::input_schema()))
+                .is_ok()
+        );
+    }
+
     #[test]
     fn test_tool_as_tool() {
         let tool = RollDice::as_tool();
@@ -0,0 +1,200 @@
+//! Monster statistics by challenge rating.
+//!
+//! Follows the DMG's "Monster Statistics by Challenge Rating" table, so an
+//! improvised enemy can be statted with balanced numbers instead of guesses.
+
+use crate::rules::{CombatantInit, DamageTraits, DamageType};
+use crate::world::{CharacterId, CombatantAttack, Senses, Size};
+use std::ops::RangeInclusive;
+
+/// Expected statistics for a monster of a given challenge rating.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonsterStats {
+    pub challenge_rating: f32,
+    pub proficiency_bonus: i8,
+    pub armor_class: u8,
+    /// Expected hit point band.
+    pub hit_points: RangeInclusive<i32>,
+    pub attack_bonus: i8,
+    /// Expected damage dealt per round.
+    pub damage_per_round: RangeInclusive<i32>,
+    pub save_dc: u8,
+}
+
+/// (CR, proficiency, AC, HP min, HP max, attack bonus, damage min, damage max, save DC)
+type Row = (f32, i8, u8, i32, i32, i8, i32, i32, u8);
+
+const STATS_BY_CR: [Row; 34] = [
+    (0.0, 2, 13, 1, 6, 3, 0, 1, 13),
+    (0.125, 2, 13, 7, 35, 3, 2, 3, 13),
+    (0.25, 2, 13, 36, 49, 3, 4, 5, 13),
+    (0.5, 2, 13, 50, 70, 3, 6, 8, 13),
+    (1.0, 2, 13, 71, 85, 3, 9, 14, 13),
+    (2.0, 2, 13, 86, 100, 3, 15, 20, 13),
+    (3.0, 2, 13, 101, 115, 4, 21, 26, 13),
+    (4.0, 2, 14, 116, 130, 5, 27, 32, 14),
+    (5.0, 3, 15, 131, 145, 6, 33, 38, 15),
+    (6.0, 3, 15, 146, 160, 6, 39, 44, 15),
+    (7.0, 3, 15, 161, 175, 6, 45, 50, 15),
+    (8.0, 3, 16, 176, 190, 7, 51, 56, 16),
+    (9.0, 4, 16, 191, 205, 7, 57, 62, 16),
+    (10.0, 4, 17, 206, 220, 7, 63, 68, 16),
+    (11.0, 4, 17, 221, 235, 8, 69, 74, 17),
+    (12.0, 4, 17, 236, 250, 8, 75, 80, 17),
+    (13.0, 5, 18, 251, 265, 8, 81, 86, 18),
+    (14.0, 5, 18, 266, 280, 8, 87, 92, 18),
+    (15.0, 5, 18, 281, 295, 8, 93, 98, 18),
+    (16.0, 5, 18, 296, 310, 9, 99, 104, 18),
+    (17.0, 6, 19, 311, 325, 10, 105, 110, 19),
+    (18.0, 6, 19, 326, 340, 10, 111, 116, 19),
+    (19.0, 6, 19, 341, 355, 10, 117, 122, 19),
+    (20.0, 6, 19, 356, 400, 10, 123, 140, 19),
+    (21.0, 7, 19, 401, 445, 11, 141, 158, 20),
+    (22.0, 7, 19, 446, 490, 11, 159, 176, 20),
+    (23.0, 7, 19, 491, 535, 11, 177, 194, 20),
+    (24.0, 7, 19, 536, 580, 12, 195, 212, 21),
+    (25.0, 8, 19, 581, 625, 12, 213, 230, 21),
+    (26.0, 8, 19, 626, 670, 12, 231, 248, 21),
+    (27.0, 8, 19, 671, 715, 13, 249, 266, 22),
+    (28.0, 8, 19, 716, 760, 13, 267, 284, 22),
+    (29.0, 9, 19, 761, 805, 13, 285, 302, 22),
+    (30.0, 9, 19, 806, 850, 14, 303, 320, 23),
+];
+
+/// Look up the expected statistics for a challenge rating.
+///
+/// CRs between table rows round down, so CR 1.5 uses the CR 1 row; anything
+/// past 30 uses the CR 30 row.
+pub fn stats_for_cr(cr: f32) -> MonsterStats {
+    let row = STATS_BY_CR
+        .iter()
+        .rev()
+        .find(|row| row.0 <= cr)
+        .unwrap_or(&STATS_BY_CR[0]);
+    MonsterStats {
+        challenge_rating: row.0,
+        proficiency_bonus: row.1,
+        armor_class: row.2,
+        hit_points: row.3..=row.4,
+        attack_bonus: row.5,
+        damage_per_round: row.6..=row.7,
+        save_dc: row.8,
+    }
+}
+
+/// Parse a challenge rating written as a number or a fraction ("1/4").
+pub fn parse_challenge_rating(s: &str) -> Option<f32> {
+    match s.trim().split_once('/') {
+        Some((num, den)) => {
+            let den: f32 = den.trim().parse().ok()?;
+            (den != 0.0).then_some(num.trim().parse::<f32>().ok()? / den)
+        }
+        None => s.trim().parse().ok(),
+    }
+}
+
+impl MonsterStats {
+    /// Hit points in the middle of the expected band.
+    pub fn typical_hp(&self) -> i32 {
+        (self.hit_points.start() + self.hit_points.end()) / 2
+    }
+
+    /// Damage per round in the middle of the expected band.
+    pub fn typical_damage(&self) -> i32 {
+        (self.damage_per_round.start() + self.damage_per_round.end()) / 2
+    }
+
+    /// Damage expression for a single attack per round that averages about
+    /// the typical damage, e.g. "3d6+1".
+    pub fn damage_dice(&self) -> String {
+        let damage = self.typical_damage();
+        if damage < 4 {
+            return "1d4".to_string();
+        }
+        // The attack bonus is proficiency plus the attacking ability's modifier
+        let modifier = (self.attack_bonus - self.proficiency_bonus).max(0) as i32;
+        let dice = (((damage - modifier) as f32 / 3.5).round() as i32).max(1);
+        format!("{dice}d6+{modifier}")
+    }
+
+    /// An enemy combatant with these statistics, ready for `Intent::StartCombat`.
+    pub fn combatant(&self, name: impl Into<String>) -> CombatantInit {
+        CombatantInit {
+            id: CharacterId::new(),
+            name: name.into(),
+            is_player: false,
+            is_ally: false,
+            current_hp: self.typical_hp(),
+            max_hp: self.typical_hp(),
+            armor_class: self.armor_class,
+            initiative_modifier: self.attack_bonus - self.proficiency_bonus,
+            attack: Some(CombatantAttack {
+                name: "Attack".to_string(),
+                attack_bonus: self.attack_bonus,
+                damage_dice: self.damage_dice(),
+                damage_type: DamageType::Bludgeoning,
+                reach_feet: 5,
+            }),
+            position: None,
+            size: Size::Medium,
+            speed: 30,
+            damage_traits: DamageTraits::default(),
+            senses: Senses::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dice::DiceExpression;
+
+    fn average(expression: &str) -> f64 {
+        let expression = DiceExpression::parse(expression).unwrap();
+        let rolls = 2000;
+        let total: i32 = (0..rolls).map(|_| expression.roll().total).sum();
+        total as f64 / rolls as f64
+    }
+
+    #[test]
+    fn test_cr_1_matches_the_dmg() {
+        let stats = stats_for_cr(1.0);
+        assert_eq!(stats.proficiency_bonus, 2);
+        assert_eq!(stats.armor_class, 13);
+        assert_eq!(stats.attack_bonus, 3);
+        assert_eq!(stats.save_dc, 13);
+        assert!(stats.hit_points.contains(&stats.typical_hp()));
+        assert_eq!(stats.hit_points, 71..=85);
+
+        let damage = average(&stats.damage_dice());
+        assert!((9.0..=14.0).contains(&damage), "average {damage}");
+    }
+
+    #[test]
+    fn test_cr_5_matches_the_dmg() {
+        let stats = stats_for_cr(5.0);
+        assert_eq!(stats.proficiency_bonus, 3);
+        assert_eq!(stats.armor_class, 15);
+        assert_eq!(stats.attack_bonus, 6);
+        assert_eq!(stats.save_dc, 15);
+        assert_eq!(stats.hit_points, 131..=145);
+
+        let damage = average(&stats.damage_dice());
+        assert!((33.0..=38.0).contains(&damage), "average {damage}");
+
+        let ogre = stats.combatant("Ogre Chief");
+        assert!(stats.hit_points.contains(&ogre.max_hp));
+        assert_eq!(ogre.armor_class, 15);
+        assert_eq!(ogre.attack.unwrap().attack_bonus, 6);
+    }
+
+    #[test]
+    fn test_fractional_and_in_between_crs() {
+        assert_eq!(parse_challenge_rating("1/4"), Some(0.25));
+        assert_eq!(parse_challenge_rating("3"), Some(3.0));
+        assert_eq!(parse_challenge_rating("1/0"), None);
+        assert_eq!(stats_for_cr(0.25).hit_points, 36..=49);
+        assert_eq!(stats_for_cr(1.5).challenge_rating, 1.0);
+        assert_eq!(stats_for_cr(45.0).challenge_rating, 30.0);
+    }
+}
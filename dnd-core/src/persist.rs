@@ -4,8 +4,14 @@
 //! supporting both JSON (human-readable) and bincode (compact) formats.
 
 use crate::dm::memory::{CampaignFact, FactCategory};
-use crate::world::{Character, GameWorld};
+use crate::dm::StoryMemory;
+use crate::world::{
+    ArmorProficiency, Character, CharacterClass, GameWorld, NarrativeType, PactMagic, SpellSlots,
+    WeaponProficiency,
+};
+use crate::GameSession;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
 use thiserror::Error;
 use tokio::fs;
@@ -27,7 +33,9 @@ pub enum PersistError {
 }
 
 /// Current save file version.
-const SAVE_VERSION: u32 = 1;
+///
+/// Bump this when the save format changes and add a step to [`upgrade`].
+pub const CURRENT_VERSION: u32 = 4;
 
 /// A saved campaign with all state needed to resume play.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +55,10 @@ pub struct SavedCampaign {
     /// Summary of the conversation for context restoration.
     pub conversation_summary: Option<String>,
 
+    /// Entities, facts, relationships and consequences the DM has recorded.
+    #[serde(default)]
+    pub story_memory: StoryMemory,
+
     /// Metadata about the save.
     pub metadata: SaveMetadata,
 }
@@ -77,6 +89,21 @@ pub struct SaveMetadata {
     pub saved_at: String,
 }
 
+impl SaveMetadata {
+    /// Describe a world being saved at `saved_at`.
+    pub fn from_world(world: &GameWorld, saved_at: String) -> Self {
+        Self {
+            character_name: world.player_character.name.clone(),
+            campaign_name: world.campaign_name.clone(),
+            level: world.player_character.level,
+            location: world.current_location.name.clone(),
+            play_time_minutes: 0, // TODO: Track actual play time
+            days_elapsed: world.game_time.day as u32,
+            saved_at,
+        }
+    }
+}
+
 impl SavedCampaign {
     /// Create a new saved campaign from game state.
     pub fn new(
@@ -85,51 +112,44 @@ impl SavedCampaign {
         conversation_summary: Option<String>,
     ) -> Self {
         let saved_at = chrono_now();
-        let metadata = SaveMetadata {
-            character_name: world.player_character.name.clone(),
-            campaign_name: world.campaign_name.clone(),
-            level: world.player_character.level,
-            location: world.current_location.name.clone(),
-            play_time_minutes: 0, // TODO: Track actual play time
-            days_elapsed: world.game_time.day as u32,
-            saved_at: saved_at.clone(),
-        };
+        let metadata = SaveMetadata::from_world(&world, saved_at.clone());
 
         Self {
-            version: SAVE_VERSION,
+            version: CURRENT_VERSION,
             saved_at,
             world,
             campaign_facts,
             conversation_summary,
+            story_memory: StoryMemory::new(),
             metadata,
         }
     }
 
+    /// Include the DM's story memory in the save.
+    pub fn with_story_memory(mut self, story_memory: StoryMemory) -> Self {
+        self.story_memory = story_memory;
+        self
+    }
+
     /// Save to a JSON file.
+    ///
+    /// The file is gzip-compressed when `path` ends in `.gz`.
     pub async fn save_json(&self, path: impl AsRef<Path>) -> Result<(), PersistError> {
         let content = serde_json::to_string_pretty(self)?;
-        fs::write(path, content).await?;
-        Ok(())
+        write_save_file(path, content).await
     }
 
-    /// Load from a JSON file.
+    /// Load from a JSON file, upgrading older save versions.
+    ///
+    /// Gzip-compressed files are detected and decompressed automatically.
     pub async fn load_json(path: impl AsRef<Path>) -> Result<Self, PersistError> {
-        let content = fs::read_to_string(path).await?;
-        let saved: Self = serde_json::from_str(&content)?;
-
-        if saved.version != SAVE_VERSION {
-            return Err(PersistError::VersionMismatch {
-                expected: SAVE_VERSION,
-                found: saved.version,
-            });
-        }
-
-        Ok(saved)
+        let content = read_save_file(path).await?;
+        migrate(serde_json::from_str(&content)?)
     }
 
     /// Check if a save file exists and get its metadata without loading the full state.
     pub async fn peek_metadata(path: impl AsRef<Path>) -> Result<SaveMetadata, PersistError> {
-        let content = fs::read_to_string(path).await?;
+        let content = read_save_file(path).await?;
 
         // Parse just enough to get metadata
         #[derive(Deserialize)]
@@ -140,9 +160,10 @@ impl SavedCampaign {
 
         let partial: Partial = serde_json::from_str(&content)?;
 
-        if partial.version != SAVE_VERSION {
+        // Metadata hasn't changed shape, so older versions can be peeked as-is
+        if partial.version > CURRENT_VERSION {
             return Err(PersistError::VersionMismatch {
-                expected: SAVE_VERSION,
+                expected: CURRENT_VERSION,
                 found: partial.version,
             });
         }
@@ -151,6 +172,211 @@ impl SavedCampaign {
     }
 }
 
+/// The first two bytes of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Read a save file, decompressing it if it starts with the gzip magic bytes.
+///
+/// Detection ignores the extension, so renamed files still load.
+pub(crate) async fn read_save_file(path: impl AsRef<Path>) -> Result<String, PersistError> {
+    use std::io::Read;
+
+    let bytes = fs::read(path).await?;
+    if !bytes.starts_with(&GZIP_MAGIC) {
+        return String::from_utf8(bytes).map_err(|_| PersistError::InvalidFormat);
+    }
+
+    let mut content = String::new();
+    flate2::read::GzDecoder::new(bytes.as_slice()).read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Write a save file, gzip-compressing it when `path` ends in `.gz`.
+pub(crate) async fn write_save_file(
+    path: impl AsRef<Path>,
+    content: String,
+) -> Result<(), PersistError> {
+    use std::io::Write;
+
+    let path = path.as_ref();
+    if path.extension().is_some_and(|e| e == "gz") {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content.as_bytes())?;
+        fs::write(path, encoder.finish()?).await?;
+    } else {
+        fs::write(path, content).await?;
+    }
+    Ok(())
+}
+
+/// Whether `path` looks like a campaign save (`.json` or `.json.gz`).
+fn is_save_file(path: &Path) -> bool {
+    let name = path.to_string_lossy();
+    name.ends_with(".json") || name.ends_with(".json.gz")
+}
+
+/// Upgrade a save payload of any older version to a current [`SavedCampaign`].
+pub fn migrate(mut value: serde_json::Value) -> Result<SavedCampaign, PersistError> {
+    upgrade(&mut value)?;
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Upgrade a save payload in place, one version at a time, to [`CURRENT_VERSION`].
+///
+/// Works on any save with a top-level `world` (campaign and session saves),
+/// and upgrades the world in each session checkpoint too. Payloads without a
+/// `version` are treated as version 1.
+pub fn upgrade(value: &mut serde_json::Value) -> Result<(), PersistError> {
+    if !value.is_object() {
+        return Err(PersistError::InvalidFormat);
+    }
+
+    let mut version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(1) as u32;
+    if version > CURRENT_VERSION {
+        return Err(PersistError::VersionMismatch {
+            expected: CURRENT_VERSION,
+            found: version,
+        });
+    }
+
+    while version < CURRENT_VERSION {
+        let migrate_world: fn(&mut serde_json::Value) = match version {
+            1 => migrate_v1_to_v2,
+            2 => migrate_v2_to_v3,
+            3 => migrate_v3_to_v4,
+            _ => return Err(PersistError::InvalidFormat),
+        };
+        migrate_world(&mut value["world"]);
+        if let Some(checkpoints) = value.get_mut("checkpoints").and_then(|c| c.as_array_mut()) {
+            checkpoints
+                .iter_mut()
+                .filter_map(|checkpoint| checkpoint.get_mut("world"))
+                .for_each(migrate_world);
+        }
+        version += 1;
+    }
+    value["version"] = serde_json::json!(CURRENT_VERSION);
+    Ok(())
+}
+
+/// Version 2 replaced `inventory.gold` with a coin purse.
+fn migrate_v1_to_v2(world: &mut serde_json::Value) {
+    gold_to_purse(&mut world["player_character"]);
+    if let Some(party) = world.get_mut("party").and_then(|p| p.as_array_mut()) {
+        party.iter_mut().for_each(gold_to_purse);
+    }
+}
+
+fn gold_to_purse(character: &mut serde_json::Value) {
+    let Some(inventory) = character
+        .get_mut("inventory")
+        .and_then(|i| i.as_object_mut())
+    else {
+        return;
+    };
+    if inventory.contains_key("purse") {
+        return;
+    }
+    if let Some(gold) = inventory.remove("gold").and_then(|g| g.as_f64()) {
+        let purse = crate::world::Purse::from_gp(gold);
+        inventory.insert(
+            "purse".to_string(),
+            serde_json::to_value(purse).unwrap_or_default(),
+        );
+    }
+}
+
+/// Version 3 tracks weapon and armor proficiencies; older characters get
+/// their classes' proficiencies instead of none.
+fn migrate_v2_to_v3(world: &mut serde_json::Value) {
+    seed_class_proficiencies(&mut world["player_character"]);
+    if let Some(party) = world.get_mut("party").and_then(|p| p.as_array_mut()) {
+        party.iter_mut().for_each(seed_class_proficiencies);
+    }
+}
+
+fn seed_class_proficiencies(character: &mut serde_json::Value) {
+    let Some(object) = character.as_object_mut() else {
+        return;
+    };
+    if object.contains_key("weapon_proficiencies") {
+        return;
+    }
+    let mut weapons: HashSet<WeaponProficiency> = HashSet::new();
+    let mut armor: HashSet<ArmorProficiency> = HashSet::new();
+    let classes = object.get("classes").and_then(|c| c.as_array());
+    for class in classes.into_iter().flatten() {
+        if let Ok(class) = serde_json::from_value::<CharacterClass>(class["class"].clone()) {
+            let data = class.data();
+            weapons.extend(data.weapon_proficiencies);
+            armor.extend(data.armor_proficiencies);
+        }
+    }
+    object.insert(
+        "weapon_proficiencies".to_string(),
+        serde_json::to_value(weapons).unwrap_or_default(),
+    );
+    object.insert(
+        "armor_proficiencies".to_string(),
+        serde_json::to_value(armor).unwrap_or_default(),
+    );
+}
+
+/// Version 4 keeps warlock slots as Pact Magic; older warlocks had them in
+/// the standard slot table.
+fn migrate_v3_to_v4(world: &mut serde_json::Value) {
+    slots_to_pact_magic(&mut world["player_character"]);
+    if let Some(party) = world.get_mut("party").and_then(|p| p.as_array_mut()) {
+        party.iter_mut().for_each(slots_to_pact_magic);
+    }
+}
+
+fn slots_to_pact_magic(character: &mut serde_json::Value) {
+    let classes: Vec<(CharacterClass, u8)> = character
+        .get("classes")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|class| {
+            let level = class["level"].as_u64()? as u8;
+            Some((serde_json::from_value(class["class"].clone()).ok()?, level))
+        })
+        .collect();
+    let Some(&(_, warlock_level)) = classes
+        .iter()
+        .find(|(class, _)| *class == CharacterClass::Warlock)
+    else {
+        return;
+    };
+    let Some(spellcasting) = character
+        .get_mut("spellcasting")
+        .and_then(|s| s.as_object_mut())
+    else {
+        return;
+    };
+    if spellcasting.get("pact_slots").is_some_and(|p| !p.is_null()) {
+        return;
+    }
+
+    // Carry spent slots over; a pure warlock's standard slots were all pact slots
+    let slots: Option<SpellSlots> = spellcasting
+        .get("spell_slots")
+        .and_then(|s| serde_json::from_value(s.clone()).ok());
+    let used: u8 = slots.iter().flat_map(|s| &s.slots).map(|s| s.used).sum();
+    let mut pact = PactMagic::for_level(warlock_level);
+    pact.used = used.min(pact.count);
+    spellcasting.insert(
+        "pact_slots".to_string(),
+        serde_json::to_value(pact).unwrap_or_default(),
+    );
+    if classes.len() == 1 {
+        spellcasting.insert(
+            "spell_slots".to_string(),
+            serde_json::to_value(SpellSlots::new()).unwrap_or_default(),
+        );
+    }
+}
+
 /// List all save files in a directory.
 pub async fn list_saves(dir: impl AsRef<Path>) -> Result<Vec<SaveInfo>, PersistError> {
     let mut saves = Vec::new();
@@ -158,7 +384,7 @@ pub async fn list_saves(dir: impl AsRef<Path>) -> Result<Vec<SaveInfo>, PersistE
 
     while let Some(entry) = entries.next_entry().await? {
         let path = entry.path();
-        if path.extension().map(|e| e == "json").unwrap_or(false) {
+        if is_save_file(&path) {
             if let Ok(metadata) = SavedCampaign::peek_metadata(&path).await {
                 saves.push(SaveInfo {
                     path: path.to_string_lossy().to_string(),
@@ -183,15 +409,70 @@ pub struct SaveInfo {
     pub metadata: SaveMetadata,
 }
 
+/// How many autosaves (the latest plus archived ones) to keep per campaign.
+pub const AUTOSAVES_TO_KEEP: usize = 5;
+
+/// Replace characters that aren't safe in file names.
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
 /// Create an auto-save file name.
 pub fn auto_save_path(base_dir: impl AsRef<Path>, campaign_name: &str) -> std::path::PathBuf {
-    let sanitized = campaign_name
-        .chars()
-        .map(|c| if c.is_alphanumeric() { c } else { '_' })
-        .collect::<String>();
+    let sanitized = sanitize_file_name(campaign_name);
     base_dir.as_ref().join(format!("{sanitized}_autosave.json"))
 }
 
+/// File name to move the previous autosave to before writing a new one.
+pub fn archived_auto_save_path(
+    base_dir: impl AsRef<Path>,
+    campaign_name: &str,
+) -> std::path::PathBuf {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let sanitized = sanitize_file_name(campaign_name);
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    base_dir
+        .as_ref()
+        .join(format!("{sanitized}_autosave_{millis}.json"))
+}
+
+/// Delete the oldest autosaves for a campaign beyond the newest `keep`.
+///
+/// Autosaves, plain or compressed, are matched by the campaign's file name
+/// prefix and ordered by modification time. Returns the paths that were removed.
+pub async fn rotate_saves(
+    dir: impl AsRef<Path>,
+    campaign_name: &str,
+    keep: usize,
+) -> Result<Vec<std::path::PathBuf>, PersistError> {
+    let prefix = format!("{}_autosave", sanitize_file_name(campaign_name));
+    let mut autosaves = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(&prefix) && is_save_file(&entry.path()) {
+            let modified = entry.metadata().await?.modified()?;
+            autosaves.push((modified, entry.path()));
+        }
+    }
+
+    // Newest first
+    autosaves.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    let mut removed = Vec::new();
+    for (_, path) in autosaves.into_iter().skip(keep) {
+        fs::remove_file(&path).await?;
+        removed.push(path);
+    }
+    Ok(removed)
+}
+
 /// Create a manual save file name with timestamp.
 pub fn manual_save_path(base_dir: impl AsRef<Path>, campaign_name: &str) -> std::path::PathBuf {
     let sanitized = campaign_name
@@ -204,8 +485,93 @@ pub fn manual_save_path(base_dir: impl AsRef<Path>, campaign_name: &str) -> std:
         .join(format!("{sanitized}_{timestamp}.json"))
 }
 
+/// Render a campaign's narrative history as a Markdown transcript.
+///
+/// A new heading starts whenever the location or the day changes. Player
+/// actions are blockquotes, NPC dialogue is italic, combat is labelled, and
+/// system messages (dice rolls, rule results) appear inline in brackets.
+/// The transcript ends with a summary of the player character.
+pub fn transcript_markdown(world: &GameWorld) -> String {
+    let character = &world.player_character;
+    let mut out = format!("# {}\n", world.campaign_name);
+
+    let mut scene: Option<(&str, u8)> = None;
+    for entry in &world.narrative_history {
+        let location = if entry.location.is_empty() {
+            "Unknown location"
+        } else {
+            entry.location.as_str()
+        };
+        if scene != Some((location, entry.game_time.day)) {
+            scene = Some((location, entry.game_time.day));
+            out.push_str(&format!(
+                "\n## {} — Day {}, {}\n",
+                location,
+                entry.game_time.day,
+                entry.game_time.time_of_day()
+            ));
+        }
+
+        let content = entry.content.trim();
+        let line = match entry.entry_type {
+            NarrativeType::DmNarration => content.to_string(),
+            NarrativeType::PlayerAction => format!("> **{}:** {}", character.name, content),
+            NarrativeType::NpcDialogue => format!("*{content}*"),
+            NarrativeType::Combat => format!("**Combat:** {content}"),
+            NarrativeType::System | NarrativeType::DiceRoll => format!("`[{content}]`"),
+        };
+        out.push('\n');
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    let classes = character
+        .classes
+        .iter()
+        .map(|c| format!("{} {}", c.class.name(), c.level))
+        .collect::<Vec<_>>()
+        .join(" / ");
+    out.push_str(&format!("\n## {}\n\n", character.name));
+    out.push_str(&format!(
+        "- Level {} {} {}\n",
+        character.level, character.race.name, classes
+    ));
+    out.push_str(&format!(
+        "- HP {}/{}, AC {}\n",
+        character.hit_points.current,
+        character.hit_points.maximum,
+        character.current_ac()
+    ));
+    if !character.conditions.is_empty() {
+        let conditions = character
+            .conditions
+            .iter()
+            .map(|c| c.condition.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!("- Conditions: {conditions}\n"));
+    }
+    out.push_str(&format!("- Purse: {}\n", character.inventory.purse));
+    out
+}
+
+/// Write a session's narrative history to `path` as a Markdown transcript.
+pub async fn export_transcript(
+    session: &GameSession,
+    path: impl AsRef<Path>,
+) -> Result<(), PersistError> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent).await?;
+        }
+    }
+    fs::write(path, transcript_markdown(session.world())).await?;
+    Ok(())
+}
+
 /// Get current timestamp as ISO 8601 string.
-fn chrono_now() -> String {
+pub(crate) fn chrono_now() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     let now = SystemTime::now()
@@ -292,6 +658,7 @@ impl CampaignExport {
             .world
             .narrative_history
             .iter()
+            .filter(|n| !matches!(n.entry_type, NarrativeType::DiceRoll))
             .take(20)
             .map(|n| {
                 // Use character count for unicode-safe truncation
@@ -390,7 +757,12 @@ impl SavedCharacter {
     /// Load from a JSON file.
     pub async fn load_json(path: impl AsRef<Path>) -> Result<Self, PersistError> {
         let content = fs::read_to_string(path).await?;
-        let saved: Self = serde_json::from_str(&content)?;
+        let mut value: serde_json::Value = serde_json::from_str(&content)?;
+        // Characters saved before proficiency tracking get their class's
+        if let Some(character) = value.get_mut("character") {
+            seed_class_proficiencies(character);
+        }
+        let saved: Self = serde_json::from_value(value)?;
 
         if saved.version != CHARACTER_SAVE_VERSION {
             return Err(PersistError::VersionMismatch {
@@ -486,17 +858,314 @@ mod tests {
 
         let saved = SavedCampaign::new(world, vec![], None);
 
-        assert_eq!(saved.version, SAVE_VERSION);
+        assert_eq!(saved.version, CURRENT_VERSION);
         assert_eq!(saved.metadata.character_name, "Test Hero");
         assert_eq!(saved.metadata.campaign_name, "Test Campaign");
     }
 
+    /// A version 1 save: no party, and gold as a single number.
+    fn v1_save() -> serde_json::Value {
+        let world = GameWorld::new("Old Campaign", create_sample_fighter("Veteran"));
+        let mut value = serde_json::to_value(SavedCampaign::new(world, vec![], None)).unwrap();
+        value["version"] = serde_json::json!(1);
+        value["world"].as_object_mut().unwrap().remove("party");
+        let inventory = value["world"]["player_character"]["inventory"]
+            .as_object_mut()
+            .unwrap();
+        inventory.remove("purse");
+        inventory.insert("gold".to_string(), serde_json::json!(25.5));
+        let character = value["world"]["player_character"].as_object_mut().unwrap();
+        character.remove("weapon_proficiencies");
+        character.remove("armor_proficiencies");
+        value
+    }
+
+    #[test]
+    fn test_migrate_v1_save() {
+        let saved = migrate(v1_save()).expect("v1 saves should migrate");
+
+        assert_eq!(saved.version, CURRENT_VERSION);
+        assert_eq!(saved.world.player_character.name, "Veteran");
+        let purse = &saved.world.player_character.inventory.purse;
+        assert_eq!((purse.gp, purse.sp), (25, 5));
+        assert!(saved.world.party.is_empty());
+        let veteran = &saved.world.player_character;
+        assert!(veteran
+            .weapon_proficiencies
+            .contains(&WeaponProficiency::Martial));
+        assert!(veteran
+            .armor_proficiencies
+            .contains(&ArmorProficiency::Heavy));
+    }
+
+    #[test]
+    fn test_migrate_moves_warlock_slots_to_pact_magic() {
+        use crate::character_builder::CharacterBuilder;
+        use crate::world::{AbilityScores, Background, RaceType, Skill, SlotInfo};
+
+        let mut warlock = CharacterBuilder::new()
+            .name("Morwen")
+            .race(RaceType::Human)
+            .class(CharacterClass::Warlock)
+            .background(Background::Charlatan)
+            .ability_scores(AbilityScores::new(8, 14, 13, 10, 12, 15))
+            .skills(vec![Skill::Arcana, Skill::Deception])
+            .build()
+            .unwrap();
+        warlock.level_up(3);
+        let spellcasting = warlock.spellcasting.as_mut().unwrap();
+        spellcasting.pact_slots = None;
+        spellcasting.spell_slots.slots[1] = SlotInfo { total: 2, used: 1 };
+        let world = GameWorld::new("Old Pact", warlock);
+        let mut value = serde_json::to_value(SavedCampaign::new(world, vec![], None)).unwrap();
+        value["version"] = serde_json::json!(3);
+
+        let saved = migrate(value).unwrap();
+        let spellcasting = saved.world.player_character.spellcasting.unwrap();
+        assert_eq!(
+            spellcasting.pact_slots,
+            Some(PactMagic {
+                count: 2,
+                level: 2,
+                used: 1
+            })
+        );
+        assert!(spellcasting.spell_slots.slots.iter().all(|s| s.total == 0));
+    }
+
+    #[test]
+    fn test_migrate_upgrades_checkpoint_worlds() {
+        let mut value = v1_save();
+        let old_world = value["world"].clone();
+        value["checkpoints"] = serde_json::json!([{ "label": "tavern", "world": old_world }]);
+
+        upgrade(&mut value).unwrap();
+        let checkpoint: GameWorld =
+            serde_json::from_value(value["checkpoints"][0]["world"].clone())
+                .expect("checkpoint worlds should migrate");
+        let veteran = &checkpoint.player_character;
+        assert_eq!(veteran.inventory.purse.gp, 25);
+        assert!(veteran
+            .weapon_proficiencies
+            .contains(&WeaponProficiency::Martial));
+    }
+
+    #[test]
+    fn test_migrate_rejects_newer_versions() {
+        let mut value = v1_save();
+        value["version"] = serde_json::json!(CURRENT_VERSION + 1);
+        assert!(matches!(
+            migrate(value),
+            Err(PersistError::VersionMismatch { found, .. }) if found == CURRENT_VERSION + 1
+        ));
+        assert!(matches!(
+            migrate(serde_json::json!([])),
+            Err(PersistError::InvalidFormat)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_load_json_migrates() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("old.json");
+        std::fs::write(&path, serde_json::to_string(&v1_save()).unwrap()).unwrap();
+
+        let loaded = SavedCampaign::load_json(&path).await.unwrap();
+        assert_eq!(loaded.version, CURRENT_VERSION);
+        let metadata = SavedCampaign::peek_metadata(&path).await.unwrap();
+        assert_eq!(metadata.campaign_name, "Old Campaign");
+    }
+
+    #[tokio::test]
+    async fn test_story_memory_round_trip() {
+        use crate::dm::{EntityType, FactSource, RelationshipType, StoryFactCategory};
+
+        let mut memory = StoryMemory::new();
+        let mira = memory.create_entity(EntityType::Npc, "Mira");
+        let anchor = memory.create_entity(EntityType::Location, "Rusty Anchor");
+        memory.record_fact(
+            mira,
+            "Mira owes the guild money",
+            StoryFactCategory::Secret,
+            FactSource::NpcDialogue,
+        );
+        memory.create_relationship(mira, anchor, RelationshipType::Owns);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("memory.json");
+        let world = GameWorld::new("Memory Quest", create_sample_fighter("Roland"));
+        SavedCampaign::new(world, vec![], None)
+            .with_story_memory(memory)
+            .save_json(&path)
+            .await
+            .unwrap();
+
+        let loaded = SavedCampaign::load_json(&path).await.unwrap().story_memory;
+        assert_eq!(loaded.find_entity_id("Mira"), Some(mira));
+        assert_eq!(
+            loaded.facts_about(mira)[0].content,
+            "Mira owes the guild money"
+        );
+        assert_eq!(
+            loaded
+                .find_relationship(mira, anchor)
+                .unwrap()
+                .relationship_type,
+            RelationshipType::Owns
+        );
+    }
+
+    #[tokio::test]
+    async fn test_compressed_save_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("quest.json.gz");
+        let mut world = GameWorld::new("Gzip Quest", create_sample_fighter("Roland"));
+        world.add_narrative("The road is long.".to_string(), NarrativeType::DmNarration);
+        let saved = SavedCampaign::new(world, vec![], Some("A summary".to_string()));
+
+        saved.save_json(&path).await.unwrap();
+        assert!(std::fs::read(&path).unwrap().starts_with(&GZIP_MAGIC));
+
+        assert_eq!(
+            read_save_file(&path).await.unwrap(),
+            serde_json::to_string_pretty(&saved).unwrap()
+        );
+        let loaded = SavedCampaign::load_json(&path).await.unwrap();
+        assert_eq!(loaded.saved_at, saved.saved_at);
+        assert_eq!(loaded.conversation_summary.as_deref(), Some("A summary"));
+        assert_eq!(
+            loaded.world.narrative_history[0].content,
+            "The road is long."
+        );
+
+        let metadata = SavedCampaign::peek_metadata(&path).await.unwrap();
+        assert_eq!(metadata.campaign_name, "Gzip Quest");
+        assert_eq!(metadata.character_name, "Roland");
+
+        // Detection uses the magic bytes, not the extension
+        let renamed = temp_dir.path().join("quest.json");
+        std::fs::rename(&path, &renamed).unwrap();
+        assert!(SavedCampaign::load_json(&renamed).await.is_ok());
+        assert_eq!(list_saves(temp_dir.path()).await.unwrap().len(), 1);
+    }
+
     #[test]
     fn test_auto_save_path() {
         let path = auto_save_path("/saves", "My Campaign!");
         assert!(path.to_string_lossy().contains("My_Campaign__autosave"));
     }
 
+    #[tokio::test]
+    async fn test_rotate_saves() {
+        use std::time::{Duration, SystemTime};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let base = SystemTime::now() - Duration::from_secs(3600);
+
+        let names = [
+            "My_Quest_autosave_1.json",
+            "My_Quest_autosave_2.json",
+            "My_Quest_autosave_3.json",
+            "My_Quest_autosave.json",
+        ];
+        for (i, name) in names.iter().enumerate() {
+            let file = std::fs::File::create(dir.join(name)).unwrap();
+            file.set_modified(base + Duration::from_secs(i as u64 * 60))
+                .unwrap();
+        }
+        // Other campaigns and manual saves are left alone
+        std::fs::write(dir.join("Other_autosave.json"), "{}").unwrap();
+        std::fs::write(dir.join("My_Quest_12345.json"), "{}").unwrap();
+
+        let removed = rotate_saves(dir, "My Quest", 2).await.unwrap();
+        assert_eq!(removed.len(), 2);
+
+        let mut remaining: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                "My_Quest_12345.json",
+                "My_Quest_autosave.json",
+                "My_Quest_autosave_3.json",
+                "Other_autosave.json",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rotate_saves_includes_compressed_autosaves() {
+        use std::time::{Duration, SystemTime};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path();
+        let base = SystemTime::now() - Duration::from_secs(3600);
+
+        let names = [
+            "My_Quest_autosave_1.json.gz",
+            "My_Quest_autosave_2.json",
+            "My_Quest_autosave_3.json.gz",
+        ];
+        for (i, name) in names.iter().enumerate() {
+            let file = std::fs::File::create(dir.join(name)).unwrap();
+            file.set_modified(base + Duration::from_secs(i as u64 * 60))
+                .unwrap();
+        }
+
+        let removed = rotate_saves(dir, "My Quest", 1).await.unwrap();
+        assert_eq!(removed.len(), 2);
+        assert!(dir.join("My_Quest_autosave_3.json.gz").exists());
+        assert!(!dir.join("My_Quest_autosave_1.json.gz").exists());
+    }
+
+    #[tokio::test]
+    async fn test_export_transcript() {
+        use crate::dm::DungeonMaster;
+
+        let mut world = GameWorld::new("Epic Quest", create_sample_fighter("Roland"));
+        world.add_narrative(
+            "The tavern is warm and loud.".to_string(),
+            NarrativeType::DmNarration,
+        );
+        world.add_narrative("I order an ale.".to_string(), NarrativeType::PlayerAction);
+        world.add_narrative(
+            "\"Two copper, friend.\"".to_string(),
+            NarrativeType::NpcDialogue,
+        );
+        world.add_narrative("Persuasion: 14".to_string(), NarrativeType::System);
+        world.current_location.name = "Dark Alley".to_string();
+        world.add_narrative(
+            "A thug swings at Roland.".to_string(),
+            NarrativeType::Combat,
+        );
+        let tavern = world.narrative_history[0].location.clone();
+        let ac = world.player_character.current_ac();
+
+        let session = GameSession::with_world(DungeonMaster::new("test-key"), world);
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("exports/transcript.md");
+        export_transcript(&session, &path).await.unwrap();
+
+        let markdown = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines[0], "# Epic Quest");
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with(&format!("## {tavern} — Day"))));
+        assert!(lines.iter().any(|l| l.starts_with("## Dark Alley — Day")));
+        assert!(lines.contains(&"The tavern is warm and loud."));
+        assert!(lines.contains(&"> **Roland:** I order an ale."));
+        assert!(lines.contains(&"*\"Two copper, friend.\"*"));
+        assert!(lines.contains(&"`[Persuasion: 14]`"));
+        assert!(lines.contains(&"**Combat:** A thug swings at Roland."));
+        assert!(lines.contains(&"## Roland"));
+        assert!(lines.contains(&format!("- HP 28/28, AC {ac}").as_str()));
+    }
+
     #[test]
     fn test_campaign_export() {
         let character = create_sample_fighter("Hero");
@@ -10,15 +10,29 @@
 //! independent of AI decision-making.
 
 use crate::dice::{self, Advantage, ComponentResult, DiceExpression, DieType, RollResult};
-use crate::world::{Ability, CharacterId, Combatant, Condition, GameWorld, Item, ItemType, Skill};
+use crate::feats::{get_feat, FeatError};
+use crate::world::{
+    Ability, AbilityMod, ActiveCondition, Character, CharacterId, Coin, Combatant, CombatantAttack,
+    Condition, Encumbrance, GameWorld, GridPosition, Item, ItemType, LandedHit, Lighting, Location,
+    LocationId, LocationType, PactMagic, Purse, Quest, QuestStatus, Senses, Size, Skill,
+};
 use serde::{Deserialize, Serialize};
 
 /// Roll dice with a fallback expression. If both fail, returns a minimal result.
 ///
 /// This avoids nested unwraps which could panic in edge cases.
 fn roll_with_fallback(notation: &str, fallback: &str) -> RollResult {
-    dice::roll(notation)
-        .or_else(|_| dice::roll(fallback))
+    roll_with_fallback_advantage(notation, fallback, Advantage::Normal)
+}
+
+/// Like [`roll_with_fallback`], rolling a single d20 twice for advantage or disadvantage.
+fn roll_with_fallback_advantage(
+    notation: &str,
+    fallback: &str,
+    advantage: Advantage,
+) -> RollResult {
+    dice::roll_with_advantage(notation, advantage)
+        .or_else(|_| dice::roll_with_advantage(fallback, advantage))
         .unwrap_or_else(|_| {
             // Create a minimal fallback result (1d4 = 1)
             let expr = DiceExpression {
@@ -42,6 +56,443 @@ fn roll_with_fallback(notation: &str, fallback: &str) -> RollResult {
         })
 }
 
+/// Impose disadvantage if the character's exhaustion is at or above `min_level`.
+fn with_exhaustion(character: &Character, min_level: u8, advantage: Advantage) -> Advantage {
+    if character.exhaustion_level() >= min_level {
+        advantage.combine(Advantage::Disadvantage)
+    } else {
+        advantage
+    }
+}
+
+/// Wearing armor without training imposes disadvantage on d20 tests that use
+/// Strength or Dexterity.
+fn with_armor_training(character: &Character, ability: Ability, advantage: Advantage) -> Advantage {
+    if matches!(ability, Ability::Strength | Ability::Dexterity)
+        && character.wearing_untrained_armor()
+    {
+        advantage.combine(Advantage::Disadvantage)
+    } else {
+        advantage
+    }
+}
+
+/// Whether a mounted attacker has the high ground: their target is on foot and
+/// smaller than the mount.
+fn has_mounted_advantage(
+    world: &GameWorld,
+    attacker_id: CharacterId,
+    target_id: CharacterId,
+) -> bool {
+    let Some(combat) = world.combat.as_ref() else {
+        return false;
+    };
+    let mount = combat
+        .combatant(attacker_id)
+        .and_then(|a| a.mounted_on)
+        .and_then(|id| combat.combatant(id));
+    match (mount, combat.combatant(target_id)) {
+        (Some(mount), Some(target)) => {
+            target.id != mount.id && target.mounted_on.is_none() && target.size < mount.size
+        }
+        _ => false,
+    }
+}
+
+/// Under the optional flanking rule, a melee attacker has advantage when a
+/// conscious ally stands on the opposite side of the target, both adjacent to it.
+fn has_flanking_advantage(
+    world: &GameWorld,
+    attacker_id: CharacterId,
+    target_id: CharacterId,
+) -> bool {
+    if !world.combat_rules.flanking {
+        return false;
+    }
+    let Some(combat) = world.combat.as_ref() else {
+        return false;
+    };
+    let (Some(attacker), Some(target)) =
+        (combat.combatant(attacker_id), combat.combatant(target_id))
+    else {
+        return false;
+    };
+    let (Some(from), Some(at)) = (attacker.position, target.position) else {
+        return false;
+    };
+    if from.distance_feet(at) != 5 {
+        return false;
+    }
+    let opposite = GridPosition::new(2 * at.x - from.x, 2 * at.y - from.y);
+    combat.combatants.iter().any(|ally| {
+        let incapacitated = match world.character(ally.id) {
+            Some(character) => character
+                .conditions
+                .iter()
+                .any(|c| c.condition.is_incapacitating()),
+            None => ally
+                .conditions
+                .iter()
+                .any(|c| c.condition.is_incapacitating()),
+        };
+        ally.id != attacker.id
+            && ally.is_ally == attacker.is_ally
+            && ally.position == Some(opposite)
+            && ally.current_hp > 0
+            && !incapacitated
+    })
+}
+
+/// Apply advantage from spent Inspiration, returning the effect that uses it up.
+fn with_inspiration(character: &Character, advantage: Advantage) -> (Advantage, Option<Effect>) {
+    if character.inspiration_pending {
+        let effect = Effect::InspirationApplied {
+            character_id: character.id,
+        };
+        (advantage.combine(Advantage::Advantage), Some(effect))
+    } else {
+        (advantage, None)
+    }
+}
+
+/// Describe the cumulative penalties of an exhaustion level.
+fn exhaustion_description(level: u8) -> &'static str {
+    match level {
+        0 => "no exhaustion",
+        1 => "disadvantage on ability checks",
+        2 => "speed halved",
+        3 => "disadvantage on attack rolls and saving throws",
+        4 => "hit point maximum halved",
+        5 => "speed reduced to 0",
+        _ => "death",
+    }
+}
+
+/// Outcome of an opposed Athletics contest (grapple or shove).
+struct Contest {
+    attacker_name: String,
+    defender_name: String,
+    attacker_total: i32,
+    defender_total: i32,
+    defender_skill: Skill,
+    rolls: Vec<Effect>,
+}
+
+impl Contest {
+    /// Ties go to the defender, who keeps the status quo.
+    fn attacker_wins(&self) -> bool {
+        self.attacker_total > self.defender_total
+    }
+
+    fn summary(&self) -> String {
+        let winner = if self.attacker_wins() {
+            &self.attacker_name
+        } else {
+            &self.defender_name
+        };
+        format!(
+            "{} rolls Athletics {} vs {} {} {}: {} wins.",
+            self.attacker_name,
+            self.attacker_total,
+            self.defender_name,
+            self.defender_skill.name(),
+            self.defender_total,
+            winner
+        )
+    }
+}
+
+/// The refusal for an id that is neither the player nor a party member.
+fn unknown_character(id: CharacterId) -> Resolution {
+    Resolution::new(format!("No character with id {id} is in the party."))
+}
+
+/// Damage dice for a critical hit: "XdY..." becomes "2XdY...".
+fn critical_damage_dice(damage_dice: &str) -> String {
+    if let Some(d_pos) = damage_dice.find('d') {
+        let num_dice: i32 = damage_dice[..d_pos].parse().unwrap_or(1);
+        let die_type = &damage_dice[d_pos..];
+        format!("{}{}", num_dice * 2, die_type)
+    } else {
+        // Not a dice expression, just double the flat value
+        let flat: i32 = damage_dice.parse().unwrap_or(1);
+        format!("{}", flat * 2)
+    }
+}
+
+/// Name of a creature by id: a tracked character, a combatant, or a generic fallback.
+fn creature_name(world: &GameWorld, id: CharacterId) -> String {
+    if let Some(character) = world.character(id) {
+        return character.name.clone();
+    }
+    world
+        .combat
+        .as_ref()
+        .and_then(|combat| combat.combatants.iter().find(|c| c.id == id))
+        .map(|c| c.name.clone())
+        .unwrap_or_else(|| "the creature".to_string())
+}
+
+/// Whether a creature has a condition: on its character sheet if it has one,
+/// otherwise on its combat entry.
+fn creature_has_condition(world: &GameWorld, id: CharacterId, condition: Condition) -> bool {
+    if let Some(character) = world.character(id) {
+        return character.has_condition(condition);
+    }
+    world
+        .combat
+        .as_ref()
+        .and_then(|combat| combat.combatant(id))
+        .is_some_and(|c| c.has_condition(condition))
+}
+
+/// A creature's senses: from its character sheet if it has one, otherwise
+/// from its combat entry.
+fn creature_senses(world: &GameWorld, id: CharacterId) -> Senses {
+    if let Some(character) = world.character(id) {
+        return character.senses.clone();
+    }
+    world
+        .combat
+        .as_ref()
+        .and_then(|combat| combat.combatant(id))
+        .map(|c| c.senses.clone())
+        .unwrap_or_default()
+}
+
+/// Advantage from invisibility: an unseen attacker has advantage, and
+/// attacks against an unseen target have disadvantage. Blindsight or
+/// tremorsense on the other side cancels the benefit.
+fn invisibility_advantage(
+    world: &GameWorld,
+    attacker_id: CharacterId,
+    target_id: CharacterId,
+) -> Advantage {
+    let mut advantage = Advantage::Normal;
+    if creature_has_condition(world, attacker_id, Condition::Invisible)
+        && !creature_senses(world, target_id).perceives_unseen()
+    {
+        advantage = advantage.combine(Advantage::Advantage);
+    }
+    if creature_has_condition(world, target_id, Condition::Invisible)
+        && !creature_senses(world, attacker_id).perceives_unseen()
+    {
+        advantage = advantage.combine(Advantage::Disadvantage);
+    }
+    advantage
+}
+
+/// Conditions that drop a creature's speed to 0.
+const IMMOBILIZING_CONDITIONS: [Condition; 6] = [
+    Condition::Grappled,
+    Condition::Restrained,
+    Condition::Paralyzed,
+    Condition::Petrified,
+    Condition::Stunned,
+    Condition::Unconscious,
+];
+
+/// Damage a tracked combatant, after its resistances, immunities and
+/// vulnerabilities. Returns the damage taken and the HP change, if any.
+fn damage_combatant(
+    combatant: &Combatant,
+    amount: i32,
+    damage_type: DamageType,
+) -> (i32, Option<Effect>) {
+    let damage = combatant.damage_traits.adjust(amount, damage_type);
+    if damage <= 0 {
+        return (0, None);
+    }
+    let new_current = (combatant.current_hp - damage).max(0);
+    (
+        damage,
+        Some(Effect::HpChanged {
+            target_id: combatant.id,
+            amount: -damage,
+            new_current,
+            new_max: combatant.max_hp,
+            dropped_to_zero: combatant.current_hp > 0 && new_current == 0,
+        }),
+    )
+}
+
+/// The AC change an equipment effect would cause the character, if any.
+fn ac_change(
+    world: &GameWorld,
+    character: &Character,
+    effect: &Effect,
+    source: String,
+) -> Option<Effect> {
+    let mut preview = GameWorld::new(world.campaign_name.clone(), character.clone());
+    preview.custom_items = world.custom_items.clone();
+    apply_effect(&mut preview, effect);
+    let new_ac = preview.player_character.current_ac();
+    (new_ac != character.current_ac()).then_some(Effect::AcChanged {
+        character_id: character.id,
+        new_ac,
+        source,
+    })
+}
+
+fn ac_text(ac_changed: &Option<Effect>) -> String {
+    match ac_changed {
+        Some(Effect::AcChanged { new_ac, .. }) => format!(" (AC now {new_ac})"),
+        _ => String::new(),
+    }
+}
+
+/// The effect spending the slot a leveled spell is cast with: a Pact Magic
+/// slot for warlocks, a standard slot otherwise.
+fn slot_used(caster_id: CharacterId, pact: Option<PactMagic>, level: u8) -> Effect {
+    match pact {
+        Some(pact) => Effect::PactSlotUsed {
+            caster_id,
+            level,
+            remaining: pact.available().saturating_sub(1),
+        },
+        None => Effect::SpellSlotUsed {
+            caster_id,
+            level,
+            remaining: 0, // Will be calculated by effect application
+        },
+    }
+}
+
+/// Roll an opposed check: the attacker uses Athletics, the defender uses the
+/// better of Athletics or Acrobatics.
+///
+/// Creatures without a character sheet roll with no modifier.
+fn roll_contest(
+    world: &GameWorld,
+    attacker_id: CharacterId,
+    defender_id: CharacterId,
+    purpose: &str,
+) -> Contest {
+    let (attacker_mod, attacker_advantage) = if let Some(attacker) = world.character(attacker_id) {
+        (
+            attacker.skill_modifier(Skill::Athletics),
+            with_armor_training(
+                attacker,
+                Ability::Strength,
+                with_exhaustion(attacker, 1, Advantage::Normal),
+            ),
+        )
+    } else {
+        (0, Advantage::Normal)
+    };
+    let (defender_skill, defender_mod, defender_advantage) =
+        if let Some(defender) = world.character(defender_id) {
+            let athletics = defender.skill_modifier(Skill::Athletics);
+            let acrobatics = defender.skill_modifier(Skill::Acrobatics);
+            let (skill, modifier) = if acrobatics > athletics {
+                (Skill::Acrobatics, acrobatics)
+            } else {
+                (Skill::Athletics, athletics)
+            };
+            (
+                skill,
+                modifier,
+                with_armor_training(
+                    defender,
+                    skill.ability(),
+                    with_exhaustion(defender, 1, Advantage::Normal),
+                ),
+            )
+        } else {
+            (Skill::Athletics, 0, Advantage::Normal)
+        };
+
+    let attacker_roll = DiceExpression::parse(&format!("1d20{attacker_mod:+}"))
+        .unwrap()
+        .roll_with_advantage(attacker_advantage);
+    let defender_roll = DiceExpression::parse(&format!("1d20{defender_mod:+}"))
+        .unwrap()
+        .roll_with_advantage(defender_advantage);
+
+    let attacker_name = creature_name(world, attacker_id);
+    let defender_name = creature_name(world, defender_id);
+
+    Contest {
+        attacker_total: attacker_roll.total,
+        defender_total: defender_roll.total,
+        rolls: vec![
+            Effect::DiceRolled {
+                roll: attacker_roll,
+                purpose: format!("{purpose} - {attacker_name} Athletics"),
+            },
+            Effect::DiceRolled {
+                roll: defender_roll,
+                purpose: format!("{purpose} - {defender_name} {}", defender_skill.name()),
+            },
+        ],
+        attacker_name,
+        defender_name,
+        defender_skill,
+    }
+}
+
+/// A reaction a combatant can ready to interrupt another creature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reaction {
+    /// Negate the next hostile spell; spells above `slot_level` need an
+    /// ability check against 10 + the spell's level
+    Counterspell { slot_level: u8 },
+    /// +5 AC against an attack that would otherwise hit
+    Shield,
+    /// A melee attack against a creature leaving reach; never readied, since
+    /// moving away provokes it on its own
+    OpportunityAttack,
+}
+
+impl Reaction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reaction::Counterspell { .. } => "Counterspell",
+            Reaction::Shield => "Shield",
+            Reaction::OpportunityAttack => "Opportunity Attack",
+        }
+    }
+
+    /// Spell slot the reaction expends, if any.
+    pub fn slot_level(&self) -> Option<u8> {
+        match self {
+            Reaction::Counterspell { slot_level } => Some(*slot_level),
+            Reaction::Shield => Some(1),
+            Reaction::OpportunityAttack => None,
+        }
+    }
+}
+
+/// The part of a combatant's turn an intent spends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActionCost {
+    /// The action.
+    #[default]
+    Action,
+    /// One attack of the Attack action; Extra Attack allows more than one.
+    Attack,
+    /// The bonus action.
+    BonusAction,
+}
+
+impl ActionCost {
+    /// The cost a spell's casting time puts on a combat turn, if any.
+    pub fn for_casting_time(casting_time: &crate::spells::CastingTime) -> Option<Self> {
+        match casting_time {
+            crate::spells::CastingTime::Action => Some(ActionCost::Action),
+            crate::spells::CastingTime::BonusAction => Some(ActionCost::BonusAction),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActionCost::Action | ActionCost::Attack => "action",
+            ActionCost::BonusAction => "bonus action",
+        }
+    }
+}
+
 /// An intent represents what a character wants to do.
 /// The AI generates intents, the RulesEngine resolves them.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,6 +503,28 @@ pub enum Intent {
         target_id: CharacterId,
         weapon_name: String,
         advantage: Advantage,
+        /// Light around the target, as seen by the attacker
+        #[serde(default)]
+        lighting: Lighting,
+    },
+
+    /// Grapple a target (Athletics vs the target's Athletics or Acrobatics)
+    Grapple {
+        grappler_id: CharacterId,
+        target_id: CharacterId,
+    },
+
+    /// Shove a target prone or 5 feet away (same contest as grappling)
+    Shove {
+        shover_id: CharacterId,
+        target_id: CharacterId,
+        knock_prone: bool,
+    },
+
+    /// Choose a prepared caster's spells for the day (after a long rest)
+    PrepareSpells {
+        character_id: CharacterId,
+        spells: Vec<String>,
     },
 
     /// Cast a spell
@@ -62,6 +535,9 @@ pub enum Intent {
         spell_level: u8,
         /// Target names (for when we don't have CharacterIds)
         target_names: Vec<String>,
+        /// Cast as a ritual: takes 10 minutes longer but spends no slot
+        #[serde(default)]
+        ritual: bool,
     },
 
     /// Make a skill check
@@ -71,6 +547,9 @@ pub enum Intent {
         dc: i32,
         advantage: Advantage,
         description: String,
+        /// Light the character is working in
+        #[serde(default)]
+        lighting: Lighting,
     },
 
     /// Make an ability check (raw ability, not skill)
@@ -106,12 +585,22 @@ pub enum Intent {
         source: String,
     },
 
+    /// Grant temporary hit points (these don't stack; the higher value is kept)
+    GrantTempHp {
+        target_id: CharacterId,
+        amount: i32,
+        source: String,
+    },
+
     /// Apply a condition to a target
     ApplyCondition {
         target_id: CharacterId,
         condition: Condition,
         source: String,
         duration_rounds: Option<u32>,
+        /// Game minutes it lasts, for effects that outlive a fight
+        #[serde(default)]
+        duration_minutes: Option<u32>,
     },
 
     /// Remove a condition from a target
@@ -120,11 +609,92 @@ pub enum Intent {
         condition: Condition,
     },
 
+    /// Add levels of exhaustion to a character
+    AddExhaustion {
+        character_id: CharacterId,
+        levels: u8,
+        source: String,
+    },
+
+    /// Remove levels of exhaustion from a character
+    RemoveExhaustion {
+        character_id: CharacterId,
+        levels: u8,
+    },
+
+    /// Temporarily raise or lower an ability score
+    ModifyAbility {
+        character_id: CharacterId,
+        ability: Ability,
+        delta: i8,
+        source: String,
+        /// Turns until it wears off; lasts until removed if not given
+        duration_rounds: Option<u32>,
+    },
+
+    /// End a temporary ability modifier early
+    RemoveAbilityModifier {
+        character_id: CharacterId,
+        source: String,
+    },
+
+    /// Award Inspiration for good roleplay
+    GrantInspiration { character_id: CharacterId },
+
+    /// Spend Inspiration for advantage on the next attack, check or save
+    UseInspiration { character_id: CharacterId },
+
+    /// Fall a distance: 1d6 bludgeoning per 10 feet (max 20d6), landing prone
+    Fall {
+        character_id: CharacterId,
+        distance_feet: u32,
+    },
+
+    /// Go without air; after held breath and CON-based grace rounds, drop to 0 HP
+    Suffocate {
+        character_id: CharacterId,
+        rounds_without_air: u32,
+    },
+
     /// Move to a different location or position
     Move {
         character_id: CharacterId,
         destination: String,
         distance_feet: u32,
+        /// Square moved to on the combat grid; leaving a hostile's reach
+        /// provokes an opportunity attack
+        #[serde(default)]
+        to_position: Option<GridPosition>,
+    },
+
+    /// Take the Disengage action, so movement this turn provokes no
+    /// opportunity attacks
+    Disengage {
+        combatant_id: CharacterId,
+        /// A bonus action with Cunning Action or Step of the Wind
+        #[serde(default)]
+        cost: ActionCost,
+    },
+
+    /// Climb onto a willing creature at least one size larger
+    Mount {
+        rider_id: CharacterId,
+        mount_id: CharacterId,
+    },
+
+    /// Get off a mount
+    Dismount { rider_id: CharacterId },
+
+    /// Ready a reaction that fires on its trigger (once per round)
+    Reaction {
+        reactor_id: CharacterId,
+        reaction: Reaction,
+    },
+
+    /// Spend hit dice to heal (during a short rest)
+    SpendHitDice {
+        character_id: CharacterId,
+        count: u8,
     },
 
     /// Take a short rest
@@ -142,6 +712,10 @@ pub enum Intent {
     /// Advance to next turn in combat
     NextTurn,
 
+    /// Run a hostile combatant's turn: it attacks the weakest opposing
+    /// character using its stored attack
+    EnemyTurn { combatant_id: CharacterId },
+
     /// Roll initiative for a character
     RollInitiative {
         character_id: CharacterId,
@@ -157,7 +731,41 @@ pub enum Intent {
     AdvanceTime { minutes: u32 },
 
     /// Add experience points
-    GainExperience { amount: u32 },
+    GainExperience {
+        character_id: CharacterId,
+        amount: u32,
+    },
+
+    /// Add a quest to the quest log
+    AddQuest {
+        name: String,
+        description: String,
+        objectives: Vec<String>,
+        giver: Option<String>,
+        /// Experience awarded when the quest is completed
+        xp_reward: u32,
+    },
+
+    /// Mark a quest completed, failed, abandoned, or active again
+    UpdateQuestStatus {
+        quest_name: String,
+        status: QuestStatus,
+    },
+
+    /// Complete one of a quest's objectives
+    CompleteObjective {
+        quest_name: String,
+        /// The objective's description, or enough of it to pick it out
+        objective: String,
+    },
+
+    /// Spend a pending Ability Score Improvement on a feat
+    TakeFeat {
+        character_id: CharacterId,
+        feat_name: String,
+        /// Ability chosen for feats like Resilient
+        ability: Option<Ability>,
+    },
 
     /// Use a class feature
     UseFeature {
@@ -176,8 +784,9 @@ pub enum Intent {
     },
 
     // Inventory management
-    /// Add an item to the player's inventory
+    /// Add an item to a character's inventory
     AddItem {
+        character_id: CharacterId,
         item_name: String,
         quantity: u32,
         item_type: Option<String>,
@@ -187,23 +796,58 @@ pub enum Intent {
         value_gp: Option<f32>,
     },
 
-    /// Remove an item from the player's inventory
-    RemoveItem { item_name: String, quantity: u32 },
+    /// Remove an item from a character's inventory
+    RemoveItem {
+        character_id: CharacterId,
+        item_name: String,
+        quantity: u32,
+    },
 
     /// Equip an item from inventory
-    EquipItem { item_name: String },
+    EquipItem {
+        character_id: CharacterId,
+        item_name: String,
+    },
 
     /// Unequip an item from a slot
-    UnequipItem { slot: String },
+    UnequipItem {
+        character_id: CharacterId,
+        slot: String,
+    },
 
     /// Use a consumable item
     UseItem {
+        character_id: CharacterId,
         item_name: String,
         target_id: Option<CharacterId>,
     },
 
-    /// Adjust the player's gold
-    AdjustGold { amount: f32, reason: String },
+    /// Attune to a magic item the character owns
+    Attune {
+        character_id: CharacterId,
+        item_name: String,
+    },
+
+    /// End attunement to a magic item, freeing the slot
+    EndAttunement {
+        character_id: CharacterId,
+        item_name: String,
+    },
+
+    /// Adjust a character's gold
+    AdjustGold {
+        character_id: CharacterId,
+        amount: f32,
+        reason: String,
+    },
+
+    /// Add (positive) or spend (negative) coins of a specific denomination
+    AdjustCurrency {
+        character_id: CharacterId,
+        amount: i32,
+        coin: Coin,
+        reason: String,
+    },
 
     /// Make a death saving throw (when at 0 HP)
     DeathSave { character_id: CharacterId },
@@ -220,8 +864,14 @@ pub enum Intent {
         new_location: String,
         location_type: Option<String>,
         description: Option<String>,
+        /// How long the journey takes; a new route remembers it
+        #[serde(default)]
+        travel_time_minutes: Option<u32>,
     },
 
+    /// Travel to a known location along a known connection
+    Travel { to: LocationId },
+
     /// Register a consequence for future triggering
     RegisterConsequence {
         /// Natural language description of when this triggers
@@ -338,6 +988,21 @@ pub struct CombatantInit {
     pub armor_class: u8,
     /// Initiative modifier (DEX mod for most creatures)
     pub initiative_modifier: i8,
+    /// Basic attack for enemies whose turns the engine runs
+    #[serde(default)]
+    pub attack: Option<CombatantAttack>,
+    /// Starting square on the combat grid
+    #[serde(default)]
+    pub position: Option<GridPosition>,
+    #[serde(default)]
+    pub size: Size,
+    /// Walking speed in feet
+    #[serde(default = "crate::world::default_speed_feet")]
+    pub speed: u32,
+    #[serde(default)]
+    pub damage_traits: DamageTraits,
+    #[serde(default)]
+    pub senses: Senses,
 }
 
 /// Common D&D damage types.
@@ -378,6 +1043,33 @@ impl DamageType {
     }
 }
 
+/// Damage types a creature shrugs off, ignores, or is especially hurt by.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DamageTraits {
+    #[serde(default)]
+    pub resistances: Vec<DamageType>,
+    #[serde(default)]
+    pub immunities: Vec<DamageType>,
+    #[serde(default)]
+    pub vulnerabilities: Vec<DamageType>,
+}
+
+impl DamageTraits {
+    /// Damage actually taken: none if immune, halved (rounded down) if
+    /// resistant, doubled if vulnerable.
+    pub fn adjust(&self, amount: i32, damage_type: DamageType) -> i32 {
+        if self.immunities.contains(&damage_type) {
+            0
+        } else if self.resistances.contains(&damage_type) {
+            amount / 2
+        } else if self.vulnerabilities.contains(&damage_type) {
+            amount * 2
+        } else {
+            amount
+        }
+    }
+}
+
 /// The result of resolving an intent.
 #[derive(Debug, Clone)]
 pub struct Resolution {
@@ -420,12 +1112,17 @@ pub enum Effect {
         dropped_to_zero: bool,
     },
 
+    /// Temporary hit points were granted
+    TempHpGranted { target_id: CharacterId, amount: i32 },
+
     /// A condition was applied
     ConditionApplied {
         target_id: CharacterId,
         condition: Condition,
         source: String,
         duration_rounds: Option<u32>,
+        #[serde(default)]
+        duration_minutes: Option<u32>,
     },
 
     /// A condition was removed
@@ -434,6 +1131,35 @@ pub enum Effect {
         condition: Condition,
     },
 
+    /// A character's exhaustion level changed
+    ExhaustionChanged {
+        target_id: CharacterId,
+        old_level: u8,
+        new_level: u8,
+        source: String,
+    },
+
+    /// A temporary ability modifier took hold
+    AbilityModifierAdded {
+        character_id: CharacterId,
+        modifier: AbilityMod,
+    },
+
+    /// Temporary ability modifiers from a source ended
+    AbilityModifierRemoved {
+        character_id: CharacterId,
+        source: String,
+    },
+
+    /// A character was awarded Inspiration
+    InspirationGranted { character_id: CharacterId },
+
+    /// A character spent Inspiration; their next d20 roll has advantage
+    InspirationUsed { character_id: CharacterId },
+
+    /// Spent Inspiration granted advantage on a roll and is gone
+    InspirationApplied { character_id: CharacterId },
+
     /// Combat started
     CombatStarted,
 
@@ -463,27 +1189,142 @@ pub enum Effect {
         current_hp: i32,
         max_hp: i32,
         armor_class: u8,
+        #[serde(default)]
+        attack: Option<CombatantAttack>,
+        #[serde(default)]
+        position: Option<GridPosition>,
+        #[serde(default)]
+        size: Size,
+        #[serde(default = "crate::world::default_speed_feet")]
+        speed: u32,
+        #[serde(default)]
+        damage_traits: DamageTraits,
+        #[serde(default)]
+        senses: Senses,
+    },
+
+    /// A combatant moved on the combat grid
+    CombatantMoved {
+        id: CharacterId,
+        position: GridPosition,
+    },
+
+    /// A combatant took the Disengage action
+    Disengaged { id: CharacterId },
+
+    /// A combatant spent part of their turn
+    ActionUsed {
+        combatant_id: CharacterId,
+        cost: ActionCost,
+    },
+
+    /// A combatant gained a fresh action this turn (Action Surge)
+    ActionRestored { combatant_id: CharacterId },
+
+    /// A combatant spent some of their movement for the turn
+    MovementUsed { id: CharacterId, feet: u32 },
+
+    /// A combatant climbed onto a mount
+    Mounted {
+        rider_id: CharacterId,
+        mount_id: CharacterId,
+    },
+
+    /// A combatant got off (or fell off) their mount
+    Dismounted { rider_id: CharacterId },
+
+    /// A combatant readied a reaction
+    ReactionReadied {
+        reactor_id: CharacterId,
+        reaction: Reaction,
     },
 
+    /// A readied reaction fired, spending the combatant's reaction for the round
+    ReactionUsed {
+        reactor_id: CharacterId,
+        reaction: Reaction,
+    },
+
+    /// Something that rides on a hit, like Divine Smite, used up the
+    /// attacker's last landed hit
+    HitSpent { attacker_id: CharacterId },
+
     /// Time advanced
     TimeAdvanced { minutes: u32 },
 
     /// Experience gained
-    ExperienceGained { amount: u32, new_total: u32 },
+    ExperienceGained {
+        character_id: CharacterId,
+        amount: u32,
+        new_total: u32,
+    },
+
+    /// A quest was added to the quest log
+    QuestAdded { quest: Quest },
+
+    /// A quest's status changed
+    QuestStatusChanged {
+        quest_id: uuid::Uuid,
+        quest_name: String,
+        status: QuestStatus,
+    },
+
+    /// One of a quest's objectives was completed
+    ObjectiveCompleted {
+        quest_id: uuid::Uuid,
+        quest_name: String,
+        objective_index: usize,
+        objective: String,
+    },
 
     /// Level up occurred
-    LevelUp { new_level: u8 },
+    LevelUp {
+        character_id: CharacterId,
+        new_level: u8,
+    },
+
+    /// A feat was taken in place of an Ability Score Improvement
+    FeatTaken {
+        character_id: CharacterId,
+        feat_name: String,
+        ability: Option<Ability>,
+    },
 
     /// Feature use consumed
     FeatureUsed {
+        character_id: CharacterId,
         feature_name: String,
         uses_remaining: u8,
     },
 
     /// Spell slot consumed
-    SpellSlotUsed { level: u8, remaining: u8 },
+    SpellSlotUsed {
+        caster_id: CharacterId,
+        level: u8,
+        remaining: u8,
+    },
 
-    /// Rest completed
+    /// Warlock Pact Magic slot consumed
+    PactSlotUsed {
+        caster_id: CharacterId,
+        level: u8,
+        remaining: u8,
+    },
+
+    /// A prepared caster chose their spells for the day
+    SpellsPrepared {
+        character_id: CharacterId,
+        spells: Vec<String>,
+    },
+
+    /// Hit dice were spent
+    HitDiceSpent {
+        target_id: CharacterId,
+        die_type: DieType,
+        count: u8,
+    },
+
+    /// Rest completed
     RestCompleted { rest_type: RestType },
 
     /// A check succeeded
@@ -502,6 +1343,9 @@ pub enum Effect {
 
     /// Attack hit
     AttackHit {
+        attacker_id: CharacterId,
+        /// Who was hit, when the engine knows
+        target_id: Option<CharacterId>,
         attacker_name: String,
         target_name: String,
         attack_roll: i32,
@@ -528,38 +1372,78 @@ pub enum Effect {
     },
 
     // Inventory effects
-    /// An item was added to inventory
+    /// An item was added to inventory. The item's stats are used when the
+    /// item isn't in the catalog.
     ItemAdded {
+        character_id: CharacterId,
         item_name: String,
         quantity: u32,
         new_total: u32,
+        #[serde(default)]
+        item_type: ItemType,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        magical: bool,
+        #[serde(default)]
+        weight: f32,
+        #[serde(default)]
+        value_gp: f32,
     },
 
     /// An item was removed from inventory
     ItemRemoved {
+        character_id: CharacterId,
         item_name: String,
         quantity: u32,
         remaining: u32,
     },
 
     /// An item was equipped
-    ItemEquipped { item_name: String, slot: String },
+    ItemEquipped {
+        character_id: CharacterId,
+        item_name: String,
+        slot: String,
+    },
 
     /// An item was unequipped
-    ItemUnequipped { item_name: String, slot: String },
+    ItemUnequipped {
+        character_id: CharacterId,
+        item_name: String,
+        slot: String,
+    },
 
     /// An item was used (consumable)
     ItemUsed { item_name: String, result: String },
 
+    /// A character attuned to a magic item
+    ItemAttuned {
+        character_id: CharacterId,
+        item_name: String,
+    },
+
+    /// A character's attunement to a magic item ended
+    AttunementEnded {
+        character_id: CharacterId,
+        item_name: String,
+    },
+
     /// Gold was added or removed
     GoldChanged {
+        character_id: CharacterId,
         amount: f32,
         new_total: f32,
         reason: String,
+        /// Coins held after the change
+        purse: Purse,
     },
 
     /// AC was recalculated due to equipment change
-    AcChanged { new_ac: u8, source: String },
+    AcChanged {
+        character_id: CharacterId,
+        new_ac: u8,
+        source: String,
+    },
 
     /// Death save failure (damage while at 0 HP)
     DeathSaveFailure {
@@ -609,6 +1493,20 @@ pub enum Effect {
     LocationChanged {
         previous_location: String,
         new_location: String,
+        /// The known location moved to; absent in effects from older saves
+        #[serde(default)]
+        location_id: Option<LocationId>,
+    },
+
+    /// A new location was added to the map
+    LocationDiscovered { location: Location },
+
+    /// A route between two known locations was found
+    LocationsConnected {
+        from: LocationId,
+        to: LocationId,
+        #[serde(default)]
+        travel_time_minutes: u32,
     },
 
     /// A consequence was registered for future triggering
@@ -669,21 +1567,73 @@ impl RulesEngine {
                 target_id,
                 weapon_name,
                 advantage,
-            } => self.resolve_attack(world, attacker_id, target_id, &weapon_name, advantage),
+                lighting,
+            } => self.with_action_cost(world, attacker_id, ActionCost::Attack, || {
+                self.resolve_attack(
+                    world,
+                    attacker_id,
+                    target_id,
+                    &weapon_name,
+                    advantage,
+                    lighting,
+                )
+            }),
+            Intent::Grapple {
+                grappler_id,
+                target_id,
+            } => self.with_action_cost(world, grappler_id, ActionCost::Attack, || {
+                self.resolve_grapple(world, grappler_id, target_id)
+            }),
+            Intent::Shove {
+                shover_id,
+                target_id,
+                knock_prone,
+            } => self.with_action_cost(world, shover_id, ActionCost::Attack, || {
+                self.resolve_shove(world, shover_id, target_id, knock_prone)
+            }),
+            Intent::PrepareSpells {
+                character_id,
+                spells,
+            } => self.resolve_prepare_spells(world, character_id, &spells),
             Intent::CastSpell {
                 caster_id,
                 spell_name,
-                targets: _,
+                targets,
                 spell_level,
-                target_names,
-            } => self.resolve_cast_spell(world, caster_id, &spell_name, spell_level, &target_names),
+                mut target_names,
+                ritual,
+            } => {
+                for id in targets {
+                    let name = creature_name(world, id);
+                    if !target_names.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+                        target_names.push(name);
+                    }
+                }
+                self.resolve_cast_spell(
+                    world,
+                    caster_id,
+                    &spell_name,
+                    spell_level,
+                    &target_names,
+                    ritual,
+                )
+            }
             Intent::SkillCheck {
                 character_id,
                 skill,
                 dc,
                 advantage,
                 description,
-            } => self.resolve_skill_check(world, character_id, skill, dc, advantage, &description),
+                lighting,
+            } => self.resolve_skill_check(
+                world,
+                character_id,
+                skill,
+                dc,
+                advantage,
+                &description,
+                lighting,
+            ),
             Intent::AbilityCheck {
                 character_id,
                 ability,
@@ -716,23 +1666,103 @@ impl RulesEngine {
                 amount,
                 source,
             } => self.resolve_heal(world, target_id, amount, &source),
+            Intent::GrantTempHp {
+                target_id,
+                amount,
+                source,
+            } => self.resolve_grant_temp_hp(world, target_id, amount, &source),
             Intent::ApplyCondition {
                 target_id,
                 condition,
                 source,
                 duration_rounds,
-            } => {
-                self.resolve_apply_condition(world, target_id, condition, &source, duration_rounds)
-            }
+                duration_minutes,
+            } => self.resolve_apply_condition(
+                world,
+                target_id,
+                condition,
+                &source,
+                duration_rounds,
+                duration_minutes,
+            ),
             Intent::RemoveCondition {
                 target_id,
                 condition,
             } => self.resolve_remove_condition(world, target_id, condition),
+            Intent::AddExhaustion {
+                character_id,
+                levels,
+                source,
+            } => self.resolve_add_exhaustion(world, character_id, levels, &source),
+            Intent::RemoveExhaustion {
+                character_id,
+                levels,
+            } => self.resolve_remove_exhaustion(world, character_id, levels),
+            Intent::ModifyAbility {
+                character_id,
+                ability,
+                delta,
+                source,
+                duration_rounds,
+            } => self.resolve_modify_ability(
+                world,
+                character_id,
+                ability,
+                delta,
+                &source,
+                duration_rounds,
+            ),
+            Intent::RemoveAbilityModifier {
+                character_id,
+                source,
+            } => self.resolve_remove_ability_modifier(world, character_id, &source),
+            Intent::GrantInspiration { character_id } => {
+                self.resolve_grant_inspiration(world, character_id)
+            }
+            Intent::UseInspiration { character_id } => {
+                self.resolve_use_inspiration(world, character_id)
+            }
+            Intent::Fall {
+                character_id,
+                distance_feet,
+            } => self.resolve_fall(world, character_id, distance_feet),
+            Intent::Suffocate {
+                character_id,
+                rounds_without_air,
+            } => self.resolve_suffocate(world, character_id, rounds_without_air),
+            Intent::SpendHitDice {
+                character_id,
+                count,
+            } => self.resolve_spend_hit_dice(world, character_id, count),
             Intent::ShortRest => self.resolve_short_rest(world),
             Intent::LongRest => self.resolve_long_rest(world),
             Intent::StartCombat { combatants } => self.resolve_start_combat(world, combatants),
             Intent::EndCombat => self.resolve_end_combat(world),
             Intent::NextTurn => self.resolve_next_turn(world),
+            Intent::EnemyTurn { combatant_id } => self.resolve_enemy_turn(world, combatant_id),
+            Intent::Move {
+                character_id,
+                destination,
+                distance_feet,
+                to_position,
+            } => self.resolve_move(
+                world,
+                character_id,
+                &destination,
+                distance_feet,
+                to_position,
+            ),
+            Intent::Disengage { combatant_id, cost } => {
+                self.with_action_cost(world, combatant_id, cost, || {
+                    self.resolve_disengage(world, combatant_id, cost)
+                })
+            }
+            Intent::Mount { rider_id, mount_id } => self.resolve_mount(world, rider_id, mount_id),
+            Intent::Dismount { rider_id } => self.resolve_dismount(world, rider_id),
+            Intent::Reaction {
+                reactor_id,
+                reaction,
+            } => self.resolve_ready_reaction(world, reactor_id, reaction),
             Intent::RollInitiative {
                 character_id,
                 name,
@@ -741,7 +1771,29 @@ impl RulesEngine {
             } => self.resolve_roll_initiative(character_id, &name, modifier, is_player),
             Intent::RollDice { notation, purpose } => self.resolve_roll_dice(&notation, &purpose),
             Intent::AdvanceTime { minutes } => self.resolve_advance_time(minutes),
-            Intent::GainExperience { amount } => self.resolve_gain_experience(world, amount),
+            Intent::GainExperience {
+                character_id,
+                amount,
+            } => self.resolve_gain_experience(world, character_id, amount),
+            Intent::AddQuest {
+                name,
+                description,
+                objectives,
+                giver,
+                xp_reward,
+            } => self.resolve_add_quest(world, name, description, objectives, giver, xp_reward),
+            Intent::UpdateQuestStatus { quest_name, status } => {
+                self.resolve_update_quest_status(world, &quest_name, status)
+            }
+            Intent::CompleteObjective {
+                quest_name,
+                objective,
+            } => self.resolve_complete_objective(world, &quest_name, &objective),
+            Intent::TakeFeat {
+                character_id,
+                feat_name,
+                ability,
+            } => self.resolve_take_feat(world, character_id, &feat_name, ability),
             Intent::UseFeature {
                 character_id,
                 feature_name,
@@ -763,6 +1815,7 @@ impl RulesEngine {
             ),
             // Inventory intents
             Intent::AddItem {
+                character_id,
                 item_name,
                 quantity,
                 item_type,
@@ -772,6 +1825,7 @@ impl RulesEngine {
                 value_gp,
             } => self.resolve_add_item(
                 world,
+                character_id,
                 &item_name,
                 quantity,
                 item_type.as_deref(),
@@ -781,18 +1835,41 @@ impl RulesEngine {
                 value_gp,
             ),
             Intent::RemoveItem {
+                character_id,
                 item_name,
                 quantity,
-            } => self.resolve_remove_item(world, &item_name, quantity),
-            Intent::EquipItem { item_name } => self.resolve_equip_item(world, &item_name),
-            Intent::UnequipItem { slot } => self.resolve_unequip_item(world, &slot),
+            } => self.resolve_remove_item(world, character_id, &item_name, quantity),
+            Intent::EquipItem {
+                character_id,
+                item_name,
+            } => self.resolve_equip_item(world, character_id, &item_name),
+            Intent::UnequipItem { character_id, slot } => {
+                self.resolve_unequip_item(world, character_id, &slot)
+            }
             Intent::UseItem {
+                character_id,
                 item_name,
                 target_id,
-            } => self.resolve_use_item(world, &item_name, target_id),
-            Intent::AdjustGold { amount, reason } => {
-                self.resolve_adjust_gold(world, amount, &reason)
-            }
+            } => self.resolve_use_item(world, character_id, &item_name, target_id),
+            Intent::Attune {
+                character_id,
+                item_name,
+            } => self.resolve_attune(world, character_id, &item_name),
+            Intent::EndAttunement {
+                character_id,
+                item_name,
+            } => self.resolve_end_attunement(world, character_id, &item_name),
+            Intent::AdjustGold {
+                character_id,
+                amount,
+                reason,
+            } => self.resolve_adjust_gold(world, character_id, amount, &reason),
+            Intent::AdjustCurrency {
+                character_id,
+                amount,
+                coin,
+                reason,
+            } => self.resolve_adjust_currency(world, character_id, amount, coin, &reason),
             Intent::DeathSave { character_id } => self.resolve_death_save(world, character_id),
             Intent::ConcentrationCheck {
                 character_id,
@@ -803,7 +1880,15 @@ impl RulesEngine {
                 new_location,
                 location_type,
                 description,
-            } => self.resolve_change_location(world, &new_location, location_type, description),
+                travel_time_minutes,
+            } => self.resolve_change_location(
+                world,
+                &new_location,
+                location_type,
+                description,
+                travel_time_minutes,
+            ),
+            Intent::Travel { to } => self.resolve_travel(world, to),
             Intent::RegisterConsequence {
                 trigger_description,
                 consequence_description,
@@ -821,7 +1906,11 @@ impl RulesEngine {
             ),
 
             // Class feature intents
-            Intent::UseRage { character_id } => self.resolve_use_rage(world, character_id),
+            Intent::UseRage { character_id } => {
+                self.with_action_cost(world, character_id, ActionCost::BonusAction, || {
+                    self.resolve_use_rage(world, character_id)
+                })
+            }
             Intent::EndRage {
                 character_id,
                 reason,
@@ -881,7 +1970,9 @@ impl RulesEngine {
                 action_taken,
             } => self.resolve_use_action_surge(world, character_id, &action_taken),
             Intent::UseSecondWind { character_id } => {
-                self.resolve_use_second_wind(world, character_id)
+                self.with_action_cost(world, character_id, ActionCost::BonusAction, || {
+                    self.resolve_use_second_wind(world, character_id)
+                })
             }
             Intent::UseSorceryPoints {
                 character_id,
@@ -906,24 +1997,49 @@ impl RulesEngine {
     fn resolve_attack(
         &self,
         world: &GameWorld,
-        _attacker_id: CharacterId,
+        attacker_id: CharacterId,
         target_id: CharacterId,
         weapon_name: &str,
         advantage: Advantage,
+        lighting: Lighting,
     ) -> Resolution {
-        let attacker = &world.player_character;
+        let Some(attacker) = world.character(attacker_id) else {
+            return unknown_character(attacker_id);
+        };
+        let crit_range = attacker.crit_range();
+
+        // Incapacitated characters (stunned, paralyzed, unconscious...) cannot attack
+        if let Some(condition) = attacker.incapacitating_condition() {
+            return Resolution::new(format!(
+                "{} is {} and cannot attack!",
+                attacker.name,
+                condition.name().to_lowercase()
+            ));
+        }
+
+        let target_name = world
+            .character(target_id)
+            .map(|c| c.name.clone())
+            .or_else(|| {
+                world
+                    .combat
+                    .as_ref()
+                    .and_then(|combat| combat.combatant(target_id))
+                    .map(|c| c.name.clone())
+            })
+            .unwrap_or_default();
 
-        // Unconscious characters cannot attack
-        if attacker.has_condition(Condition::Unconscious) {
+        // A charmed creature can't attack the one who charmed it
+        if attacker.has_condition_from(Condition::Charmed, &target_name) {
             return Resolution::new(format!(
-                "{} is unconscious and cannot attack!",
-                attacker.name
+                "{} is charmed by {} and cannot attack them!",
+                attacker.name, target_name
             ));
         }
 
-        // Get target AC from combat state, or use player AC if targeting self
-        let target_ac = if target_id == world.player_character.id {
-            world.player_character.current_ac()
+        // Get target AC from a tracked character, then combat state
+        let target_ac = if let Some(target) = world.character(target_id) {
+            target.current_ac()
         } else if let Some(ref combat) = world.combat {
             combat
                 .combatants
@@ -936,9 +2052,20 @@ impl RulesEngine {
         };
 
         // Look up weapon from database or equipped weapon
-        let weapon = crate::items::get_weapon(weapon_name);
+        let weapon = world.custom_items.get_weapon(weapon_name);
         let equipped_weapon = attacker.equipment.main_hand.as_ref();
 
+        // Versatile weapons use their larger die when no shield or off-hand
+        // item keeps the second hand busy
+        let hands_free =
+            attacker.equipment.shield.is_none() && attacker.equipment.off_hand.is_none();
+        let two_handed_dice = weapon
+            .as_ref()
+            .or(equipped_weapon)
+            .and_then(|w| w.versatile_damage())
+            .filter(|_| hands_free)
+            .map(str::to_string);
+
         // Determine the weapon properties
         let (damage_dice, is_finesse, is_ranged) = if let Some(w) = &weapon {
             (w.damage_dice.clone(), w.is_finesse(), w.is_ranged())
@@ -948,13 +2075,14 @@ impl RulesEngine {
             // Default to unarmed strike
             ("1".to_string(), false, false)
         };
+        let damage_dice = two_handed_dice.clone().unwrap_or(damage_dice);
 
         // Determine which ability modifier to use
         // Ranged: DEX only
         // Finesse: higher of STR or DEX
         // Melee: STR only
-        let str_mod = attacker.ability_scores.modifier(Ability::Strength);
-        let dex_mod = attacker.ability_scores.modifier(Ability::Dexterity);
+        let str_mod = attacker.effective_scores().modifier(Ability::Strength);
+        let dex_mod = attacker.effective_scores().modifier(Ability::Dexterity);
 
         // Track if this is a strength-based melee attack (for rage bonus)
         let is_strength_melee = if is_ranged {
@@ -973,31 +2101,98 @@ impl RulesEngine {
             str_mod
         };
 
-        let attack_mod = ability_mod + attacker.proficiency_bonus();
-        let attack_expr = DiceExpression::parse(&format!("1d20+{attack_mod}")).unwrap();
-        let attack_roll = attack_expr.roll_with_advantage(advantage);
+        // Enchantment bonus from the equipped weapon, if its magic works for the attacker
+        let magic_bonus = equipped_weapon
+            .filter(|w| w.base.name.eq_ignore_ascii_case(weapon_name))
+            .filter(|w| attacker.can_use_magic(&w.base))
+            .map_or(0, |w| w.magic_bonus);
+
+        // Proficiency only applies to weapons the attacker is trained with;
+        // everyone is proficient with unarmed strikes
+        let proficient = weapon
+            .as_ref()
+            .or(equipped_weapon)
+            .is_none_or(|w| attacker.is_proficient_with(w));
+        let proficiency = if proficient {
+            attacker.proficiency_bonus()
+        } else {
+            0
+        };
+
+        let attack_mod = ability_mod + proficiency + magic_bonus;
+        let attack_expr = DiceExpression::parse(&format!("1d20{attack_mod:+}")).unwrap();
+        // Attacking a target you can't see has disadvantage
+        let advantage = if attacker.senses.perceived_lighting(lighting) == Lighting::Darkness {
+            advantage.combine(Advantage::Disadvantage)
+        } else {
+            advantage
+        };
+        let advantage = if !is_ranged
+            && (has_mounted_advantage(world, attacker_id, target_id)
+                || has_flanking_advantage(world, attacker_id, target_id))
+        {
+            advantage.combine(Advantage::Advantage)
+        } else {
+            advantage
+        };
+        let attack_ability = if is_strength_melee {
+            Ability::Strength
+        } else {
+            Ability::Dexterity
+        };
+        let advantage = with_armor_training(attacker, attack_ability, advantage);
+        // Attacking the source of your fear has disadvantage
+        let advantage = if attacker.has_condition_from(Condition::Frightened, &target_name) {
+            advantage.combine(Advantage::Disadvantage)
+        } else {
+            advantage
+        };
+        let advantage = advantage.combine(invisibility_advantage(world, attacker_id, target_id));
+        let (advantage, inspiration) = with_inspiration(attacker, advantage);
+        let attack_roll = attack_expr.roll_with_advantage(with_exhaustion(attacker, 3, advantage));
 
+        let grip = if two_handed_dice.is_some() {
+            " two-handed"
+        } else {
+            ""
+        };
         let mut resolution = Resolution::new(format!(
-            "{} attacks with {} (roll: {} vs AC {})",
-            attacker.name, weapon_name, attack_roll.total, target_ac
+            "{} attacks with {}{} (roll: {} vs AC {})",
+            attacker.name, weapon_name, grip, attack_roll.total, target_ac
         ));
 
-        resolution = resolution.with_effect(Effect::DiceRolled {
-            roll: attack_roll.clone(),
-            purpose: format!("Attack with {weapon_name}"),
-        });
+        resolution = resolution
+            .with_effect(Effect::DiceRolled {
+                roll: attack_roll.clone(),
+                purpose: format!("Attack with {weapon_name}"),
+            })
+            .with_effects(inspiration);
 
-        // Natural 1 always misses, natural 20 always hits (and crits)
-        let hits = !attack_roll.is_fumble()
-            && (attack_roll.total >= target_ac as i32 || attack_roll.is_critical());
+        // Natural 1 always misses; a roll in the crit range always hits (and crits)
+        let is_critical = attack_roll.is_critical_with_range(crit_range);
+        let mut hits =
+            !attack_roll.is_fumble() && (attack_roll.total >= target_ac as i32 || is_critical);
+        let mut target_ac = target_ac;
+        if hits {
+            if let Some(shield) =
+                self.shield_reaction(world, target_id, attack_roll.total, target_ac, is_critical)
+            {
+                resolution.narrative = format!("{} - {}", resolution.narrative, shield.narrative);
+                resolution = resolution.with_effects(shield.effects);
+                target_ac += 5;
+                hits = false;
+            }
+        }
 
         if hits {
             resolution = resolution.with_effect(Effect::AttackHit {
+                attacker_id: attacker.id,
+                target_id: Some(target_id),
                 attacker_name: attacker.name.clone(),
                 target_name: "target".to_string(),
                 attack_roll: attack_roll.total,
                 target_ac,
-                is_critical: attack_roll.is_critical(),
+                is_critical,
             });
 
             // Roll damage with ability modifier and rage bonus (if applicable)
@@ -1006,21 +2201,10 @@ impl RulesEngine {
             } else {
                 0
             };
-            let total_mod = ability_mod as i32 + rage_bonus;
-
-            let damage_expr = if attack_roll.is_critical() {
-                // Critical hit: double the number of dice
-                // Parse "XdY" and produce "2XdY"
-                let doubled_dice = if let Some(d_pos) = damage_dice.find('d') {
-                    let num_dice: i32 = damage_dice[..d_pos].parse().unwrap_or(1);
-                    let die_type = &damage_dice[d_pos..];
-                    format!("{}{}", num_dice * 2, die_type)
-                } else {
-                    // Not a dice expression, just double the flat value
-                    let flat: i32 = damage_dice.parse().unwrap_or(1);
-                    format!("{}", flat * 2)
-                };
-                format!("{doubled_dice}+{total_mod}")
+            let total_mod = ability_mod as i32 + rage_bonus + magic_bonus as i32;
+
+            let damage_expr = if is_critical {
+                format!("{}+{total_mod}", critical_damage_dice(&damage_dice))
             } else {
                 format!("{damage_dice}+{total_mod}")
             };
@@ -1041,17 +2225,109 @@ impl RulesEngine {
         resolution
     }
 
+    fn resolve_grapple(
+        &self,
+        world: &GameWorld,
+        grappler_id: CharacterId,
+        target_id: CharacterId,
+    ) -> Resolution {
+        let contest = roll_contest(world, grappler_id, target_id, "Grapple");
+        let mut resolution = Resolution::new(format!(
+            "{} tries to grapple {}. {}",
+            contest.attacker_name,
+            contest.defender_name,
+            contest.summary()
+        ))
+        .with_effects(contest.rolls.clone());
+
+        if contest.attacker_wins() {
+            resolution
+                .narrative
+                .push_str(&format!(" {} is grappled!", contest.defender_name));
+            resolution = resolution.with_effect(Effect::ConditionApplied {
+                target_id,
+                condition: Condition::Grappled,
+                source: format!("Grappled by {}", contest.attacker_name),
+                duration_rounds: None,
+                duration_minutes: None,
+            });
+        } else {
+            resolution.narrative.push_str(&format!(
+                " {} breaks free of the attempt.",
+                contest.defender_name
+            ));
+        }
+
+        resolution
+    }
+
+    fn resolve_shove(
+        &self,
+        world: &GameWorld,
+        shover_id: CharacterId,
+        target_id: CharacterId,
+        knock_prone: bool,
+    ) -> Resolution {
+        let contest = roll_contest(world, shover_id, target_id, "Shove");
+        let mut resolution = Resolution::new(format!(
+            "{} tries to shove {}{}. {}",
+            contest.attacker_name,
+            contest.defender_name,
+            if knock_prone { " prone" } else { " away" },
+            contest.summary()
+        ))
+        .with_effects(contest.rolls.clone());
+
+        if !contest.attacker_wins() {
+            resolution
+                .narrative
+                .push_str(&format!(" {} holds their ground.", contest.defender_name));
+        } else if knock_prone {
+            resolution
+                .narrative
+                .push_str(&format!(" {} is knocked prone!", contest.defender_name));
+            resolution = resolution.with_effect(Effect::ConditionApplied {
+                target_id,
+                condition: Condition::Prone,
+                source: format!("Shoved by {}", contest.attacker_name),
+                duration_rounds: None,
+                duration_minutes: None,
+            });
+            let riders = self.resolve_mount_knocked_prone(world, target_id);
+            resolution.narrative.push_str(&riders.narrative);
+            resolution = resolution.with_effects(riders.effects);
+        } else {
+            resolution.narrative.push_str(&format!(
+                " {} is pushed 5 feet away.",
+                contest.defender_name
+            ));
+        }
+
+        resolution
+    }
+
     fn resolve_cast_spell(
         &self,
         world: &GameWorld,
-        _caster_id: CharacterId,
+        caster_id: CharacterId,
         spell_name: &str,
         slot_level: u8,
         target_names: &[String],
+        ritual: bool,
     ) -> Resolution {
         use crate::spells::{get_spell, SpellAttackType};
 
-        let caster = &world.player_character;
+        let Some(caster) = world.character(caster_id) else {
+            return unknown_character(caster_id);
+        };
+
+        if let Some(condition) = caster.incapacitating_condition() {
+            return Resolution::new(format!(
+                "{} is {} and cannot cast spells!",
+                caster.name,
+                condition.name().to_lowercase()
+            ));
+        }
 
         // Look up the spell
         let spell = match get_spell(spell_name) {
@@ -1064,11 +2340,104 @@ impl RulesEngine {
             }
         };
 
+        // Rituals take 10 minutes, so only action and bonus action spells
+        // fit in a combat turn
+        let cost = ActionCost::for_casting_time(&spell.casting_time).filter(|_| !ritual);
+        if let Some(refusal) = cost.and_then(|cost| self.action_unavailable(world, caster_id, cost))
+        {
+            return refusal;
+        }
+        let spent = cost.and_then(|cost| self.action_used(world, caster_id, cost));
+
+        if ritual {
+            if !spell.ritual {
+                return Resolution::new(format!("{} can't be cast as a ritual.", spell.name));
+            }
+            if world.combat.is_some() {
+                return Resolution::new(format!(
+                    "{} can't spend 10 minutes on a ritual in the middle of combat.",
+                    caster.name
+                ));
+            }
+            if !caster.can_ritual_cast(&spell.name) {
+                return Resolution::new(format!(
+                    "{} can't cast {} as a ritual.",
+                    caster.name, spell.name
+                ));
+            }
+        }
+
+        // Prepared casters can only cast the leveled spells they prepared
+        // (wizards may ritual-cast straight from their spellbook)
+        if spell.level > 0 && !ritual && caster.prepared_spell_limit().is_some() {
+            let prepared = caster.spellcasting.as_ref().is_some_and(|sc| {
+                sc.spells_prepared
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&spell.name))
+            });
+            if !prepared {
+                return Resolution::new(format!("{} hasn't prepared {}.", caster.name, spell.name));
+            }
+        }
+
+        // Somatic components need a free hand, which a focus provides
+        if spell.components.somatic
+            && caster.equipment.hands_full()
+            && !caster.has_spellcasting_focus()
+        {
+            return Resolution::new(format!(
+                "{} needs a free hand to cast {}.",
+                caster.name, spell.name
+            ));
+        }
+
+        // Costly material components must be carried; a component pouch or
+        // focus stands in for the rest
+        let mut component_used = None;
+        if let Some(material) = &spell.components.material {
+            if let (Some(item_name), Some(cost_gp)) = (&material.item, material.cost_gp) {
+                let Some(item) = caster.inventory.items.iter().find(|i| {
+                    i.name.eq_ignore_ascii_case(item_name) && i.value_gp >= cost_gp as f32
+                }) else {
+                    return Resolution::new(format!(
+                        "{} needs a {} worth at least {} gp to cast {}.",
+                        caster.name,
+                        item_name.to_lowercase(),
+                        cost_gp,
+                        spell.name
+                    ));
+                };
+                if material.consumed {
+                    component_used = Some(Effect::ItemRemoved {
+                        character_id: caster.id,
+                        item_name: item.name.clone(),
+                        quantity: 1,
+                        remaining: item.quantity.saturating_sub(1),
+                    });
+                }
+            }
+        }
+
+        // Warlocks cast leveled spells through Pact Magic, always at its slot level
+        let pact = caster
+            .spellcasting
+            .as_ref()
+            .and_then(|sc| sc.pact_slots)
+            .filter(|_| spell.level > 0 && !ritual);
+
         // Determine the effective slot level
         let effective_slot = if spell.level == 0 {
             0 // Cantrips don't use slots
-        } else if slot_level == 0 {
-            spell.level // Use base spell level if not specified
+        } else if let Some(pact) = pact {
+            if spell.level > pact.level {
+                return Resolution::new(format!(
+                    "Cannot cast {} using a level {} pact slot - requires at least level {}.",
+                    spell.name, pact.level, spell.level
+                ));
+            }
+            pact.level
+        } else if slot_level == 0 || ritual {
+            spell.level // Use base spell level if not specified; rituals can't upcast
         } else if slot_level < spell.level {
             return Resolution::new(format!(
                 "Cannot cast {} using a level {} slot - requires at least level {}.",
@@ -1078,8 +2447,12 @@ impl RulesEngine {
             slot_level
         };
 
-        // Check and consume spell slot (if not a cantrip)
-        if spell.level > 0 {
+        // Check and consume spell slot (if not a cantrip or ritual)
+        if let Some(pact) = pact {
+            if pact.available() == 0 {
+                return Resolution::new(format!("{} has no pact slots remaining!", caster.name));
+            }
+        } else if spell.level > 0 && !ritual {
             if let Some(ref spellcasting) = caster.spellcasting {
                 let slot_idx = (effective_slot - 1) as usize;
                 if slot_idx >= 9 {
@@ -1100,11 +2473,27 @@ impl RulesEngine {
             }
         }
 
+        // A hostile Counterspell resolves before the spell takes effect
+        let counterspell = self.resolve_counterspell(world, caster_id, &spell.name, effective_slot);
+        if let Some((counter, true)) = counterspell {
+            let mut resolution = Resolution::new(format!(
+                "{} casts {}! {}",
+                caster.name, spell.name, counter.narrative
+            ))
+            .with_effects(counter.effects)
+            .with_effects(spent)
+            .with_effects(component_used);
+            if spell.level > 0 && !ritual {
+                resolution = resolution.with_effect(slot_used(caster_id, pact, effective_slot));
+            }
+            return resolution;
+        }
+
         // Get spellcasting ability modifier
         let spell_mod = caster
             .spellcasting
             .as_ref()
-            .map(|sc| caster.ability_scores.modifier(sc.ability))
+            .map(|sc| caster.effective_scores().modifier(sc.ability))
             .unwrap_or(0);
         let spell_attack_bonus = spell_mod + caster.proficiency_bonus();
         // Minimum DC of 8 as a sanity floor (though in practice, no valid build would go lower)
@@ -1113,10 +2502,16 @@ impl RulesEngine {
         // Build the resolution
         let mut resolution = Resolution::new(String::new());
         let mut narrative_parts = Vec::new();
+        if let Some((counter, _)) = counterspell {
+            narrative_parts.push(format!("{}.", counter.narrative));
+            resolution = resolution.with_effects(counter.effects);
+        }
 
         // Casting announcement
         let slot_text = if spell.level == 0 {
             String::new()
+        } else if ritual {
+            " as a ritual".to_string()
         } else if effective_slot > spell.level {
             format!(" (upcast at level {})", effective_slot)
         } else {
@@ -1141,26 +2536,30 @@ impl RulesEngine {
             };
 
             // Roll spell attack
-            let attack_roll = roll_with_fallback(&format!("1d20+{}", spell_attack_bonus), "1d20");
-
-            resolution = resolution.with_effect(Effect::DiceRolled {
-                roll: attack_roll.clone(),
-                purpose: format!("{} spell attack", attack_type_name),
-            });
+            let (advantage, inspiration) = with_inspiration(caster, Advantage::Normal);
+            let attack_roll = roll_with_fallback_advantage(
+                &format!("1d20+{}", spell_attack_bonus),
+                "1d20",
+                with_exhaustion(caster, 3, advantage),
+            );
+
+            resolution = resolution
+                .with_effect(Effect::DiceRolled {
+                    roll: attack_roll.clone(),
+                    purpose: format!("{} spell attack", attack_type_name),
+                })
+                .with_effects(inspiration);
 
             let target_name = target_names.first().map(|s| s.as_str()).unwrap_or("target");
 
-            // Look up target AC from combat state by name
-            let target_ac = if let Some(ref combat) = world.combat {
+            // Look up the target in combat state by name; outside combat it has AC 10
+            let target = world.combat.as_ref().and_then(|combat| {
                 combat
                     .combatants
                     .iter()
                     .find(|c| c.name.eq_ignore_ascii_case(target_name))
-                    .map(|c| c.armor_class)
-                    .unwrap_or(10)
-            } else {
-                10 // Default AC outside combat
-            };
+            });
+            let target_ac = target.map_or(10, |c| c.armor_class);
 
             narrative_parts.push(format!(
                 "Makes a {} spell attack against {}: {} vs AC {}.",
@@ -1173,6 +2572,8 @@ impl RulesEngine {
             if hits {
                 narrative_parts.push("Hit!".to_string());
                 resolution = resolution.with_effect(Effect::AttackHit {
+                    attacker_id: caster.id,
+                    target_id: target.map(|c| c.id),
                     attacker_name: caster.name.clone(),
                     target_name: target_name.to_string(),
                     attack_roll: attack_roll.total,
@@ -1199,16 +2600,22 @@ impl RulesEngine {
                             .damage_type
                             .map(|dt| dt.name())
                             .unwrap_or("magical");
+                        let (damage, hp_change) = match (target, spell.damage_type) {
+                            (Some(target), Some(damage_type)) => {
+                                damage_combatant(target, damage_roll.total, damage_type)
+                            }
+                            _ => (damage_roll.total, None),
+                        };
 
-                        narrative_parts.push(format!(
-                            "Deals {} {} damage.",
-                            damage_roll.total, damage_type_name
-                        ));
+                        narrative_parts
+                            .push(format!("Deals {} {} damage.", damage, damage_type_name));
 
-                        resolution = resolution.with_effect(Effect::DiceRolled {
-                            roll: damage_roll,
-                            purpose: format!("{} damage", spell.name),
-                        });
+                        resolution = resolution
+                            .with_effect(Effect::DiceRolled {
+                                roll: damage_roll,
+                                purpose: format!("{} damage", spell.name),
+                            })
+                            .with_effects(hp_change);
                     }
                 }
             } else {
@@ -1225,8 +2632,7 @@ impl RulesEngine {
         else if let Some(save_ability) = spell.save_type {
             let save_effect = spell
                 .save_effect
-                .as_ref()
-                .map(|s| s.as_str())
+                .as_deref()
                 .unwrap_or("negates effect");
 
             narrative_parts.push(format!(
@@ -1236,7 +2642,7 @@ impl RulesEngine {
                 save_effect
             ));
 
-            // Roll damage (before save resolution)
+            // Roll damage once; every target saves against the same roll
             if let Some(ref dice_str) = damage_dice {
                 if let Ok(damage_roll) = dice::roll(dice_str) {
                     let damage_type_name = spell
@@ -1249,10 +2655,27 @@ impl RulesEngine {
                         damage_roll.total, damage_type_name
                     ));
 
+                    let full_damage = damage_roll.total;
                     resolution = resolution.with_effect(Effect::DiceRolled {
                         roll: damage_roll,
                         purpose: format!("{} damage", spell.name),
                     });
+
+                    let half_on_save = save_effect.contains("half");
+                    for target_name in target_names {
+                        let target = self.resolve_spell_save(
+                            world,
+                            target_name,
+                            save_ability,
+                            spell_save_dc as i32,
+                            full_damage,
+                            half_on_save,
+                            spell.damage_type.unwrap_or(DamageType::Force),
+                            &spell.name,
+                        );
+                        narrative_parts.push(target.narrative);
+                        resolution = resolution.with_effects(target.effects);
+                    }
                 }
             }
         }
@@ -1277,36 +2700,221 @@ impl RulesEngine {
             narrative_parts.push(spell.description.clone());
         }
 
-        // Add spell slot consumption effect (for leveled spells)
-        if spell.level > 0 {
-            resolution = resolution.with_effect(Effect::SpellSlotUsed {
-                level: effective_slot,
-                remaining: 0, // Will be calculated by effect application
-            });
+        // Add spell slot consumption effect (for leveled spells); rituals
+        // take 10 minutes longer instead
+        if ritual {
+            resolution = resolution.with_effect(Effect::TimeAdvanced { minutes: 10 });
+        } else if spell.level > 0 {
+            resolution = resolution.with_effect(slot_used(caster_id, pact, effective_slot));
         }
 
         resolution.narrative = narrative_parts.join(" ");
-        resolution
+        resolution.with_effects(spent).with_effects(component_used)
+    }
+
+    fn resolve_prepare_spells(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        spell_names: &[String],
+    ) -> Resolution {
+        use crate::spells::{get_spell, SpellClass};
+
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let (Some(spellcasting), Some(limit)) = (
+            character.spellcasting.as_ref(),
+            character.prepared_spell_limit(),
+        ) else {
+            return Resolution::new(format!("{} doesn't prepare spells.", character.name));
+        };
+        if spellcasting.prepared_since_rest {
+            return Resolution::new(format!(
+                "{} has already prepared spells today; they can change them after a long rest.",
+                character.name
+            ));
+        }
+
+        let mut spells: Vec<String> = Vec::new();
+        for name in spell_names {
+            let Some(spell) = get_spell(name) else {
+                return Resolution::new(format!("Unknown spell: '{name}'."));
+            };
+            if spells.iter().any(|s| s == &spell.name) {
+                continue;
+            }
+            if spell.level == 0 {
+                return Resolution::new(format!(
+                    "{} is a cantrip and is always ready.",
+                    spell.name
+                ));
+            }
+            let slot_total = spellcasting.spell_slots.slots[spell.level as usize - 1].total;
+            if slot_total == 0 {
+                return Resolution::new(format!(
+                    "{} has no level {} spell slots to prepare {} with.",
+                    character.name, spell.level, spell.name
+                ));
+            }
+
+            // Wizards prepare from their spellbook; clerics and druids from the class list
+            let available = character.classes.iter().any(|c| match c.class {
+                crate::world::CharacterClass::Wizard => spellcasting
+                    .spells_known
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&spell.name)),
+                crate::world::CharacterClass::Cleric => spell.classes.contains(&SpellClass::Cleric),
+                crate::world::CharacterClass::Druid => spell.classes.contains(&SpellClass::Druid),
+                _ => false,
+            });
+            if !available {
+                return Resolution::new(format!(
+                    "{} can't prepare {}.",
+                    character.name, spell.name
+                ));
+            }
+            spells.push(spell.name.clone());
+        }
+
+        if spells.len() > limit {
+            return Resolution::new(format!(
+                "{} can prepare at most {} spells, not {}.",
+                character.name,
+                limit,
+                spells.len()
+            ));
+        }
+
+        Resolution::new(format!(
+            "{} prepares {}.",
+            character.name,
+            spells.join(", ")
+        ))
+        .with_effect(Effect::SpellsPrepared {
+            character_id: character.id,
+            spells,
+        })
+    }
+
+    /// One target's saving throw against an area spell, taking full damage on
+    /// a failure and half (or none) on a success.
+    #[allow(clippy::too_many_arguments)]
+    fn resolve_spell_save(
+        &self,
+        world: &GameWorld,
+        target_name: &str,
+        ability: Ability,
+        dc: i32,
+        full_damage: i32,
+        half_on_save: bool,
+        damage_type: DamageType,
+        spell_name: &str,
+    ) -> Resolution {
+        let character = std::iter::once(&world.player_character)
+            .chain(world.party.iter())
+            .find(|c| c.name.eq_ignore_ascii_case(target_name));
+        let combatant = world.combat.as_ref().and_then(|combat| {
+            combat
+                .combatants
+                .iter()
+                .find(|c| c.name.eq_ignore_ascii_case(target_name))
+        });
+
+        let damage_for = |saved: bool| match (saved, half_on_save) {
+            (false, _) => full_damage,
+            (true, true) => full_damage / 2,
+            (true, false) => 0,
+        };
+
+        if let Some(character) = character {
+            let save = self.resolve_saving_throw(
+                world,
+                character.id,
+                ability,
+                dc,
+                Advantage::Normal,
+                spell_name,
+            );
+            let saved = save
+                .effects
+                .iter()
+                .any(|e| matches!(e, Effect::CheckSucceeded { .. }));
+            let damage = damage_for(saved);
+            let mut resolution = Resolution::new(save.narrative).with_effects(save.effects);
+            if damage > 0 {
+                let hurt =
+                    self.resolve_damage(world, character.id, damage, damage_type, spell_name);
+                resolution.narrative = format!("{}. {}", resolution.narrative, hurt.narrative);
+                resolution = resolution.with_effects(hurt.effects);
+            }
+            return resolution;
+        }
+
+        let Some(combatant) = combatant else {
+            return Resolution::new(format!("{target_name} isn't in the area."));
+        };
+
+        // Creatures without a character sheet save with no modifier
+        let roll = DiceExpression::parse("1d20").unwrap().roll();
+        let saved = roll.total >= dc;
+        let (damage, hp_change) = damage_combatant(combatant, damage_for(saved), damage_type);
+        let check_type = format!("{} save", ability.abbreviation());
+
+        let resolution = Resolution::new(format!(
+            "{} {} on {} saving throw ({} vs DC {}) and takes {} {} damage",
+            combatant.name,
+            if saved { "succeeds" } else { "fails" },
+            ability.abbreviation(),
+            roll.total,
+            dc,
+            damage,
+            damage_type.name()
+        ))
+        .with_effect(Effect::DiceRolled {
+            roll: roll.clone(),
+            purpose: format!("{} save vs {}", ability.abbreviation(), spell_name),
+        })
+        .with_effect(if saved {
+            Effect::CheckSucceeded {
+                check_type,
+                roll: roll.total,
+                dc,
+            }
+        } else {
+            Effect::CheckFailed {
+                check_type,
+                roll: roll.total,
+                dc,
+            }
+        });
+
+        resolution.with_effects(hp_change)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn resolve_skill_check(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
+        character_id: CharacterId,
         skill: Skill,
         dc: i32,
         advantage: Advantage,
         description: &str,
+        lighting: Lighting,
     ) -> Resolution {
-        let character = &world.player_character;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        // Unconscious characters automatically fail Strength and Dexterity checks
-        if character.has_condition(Condition::Unconscious) {
+        // Helpless characters automatically fail Strength and Dexterity checks
+        if let Some(condition) = character.helpless_condition() {
             let ability = skill.ability();
             if matches!(ability, Ability::Strength | Ability::Dexterity) {
                 return Resolution::new(format!(
-                    "{} is unconscious and automatically fails the {} check!",
+                    "{} is {} and automatically fails the {} check!",
                     character.name,
+                    condition.name().to_lowercase(),
                     skill.name()
                 ))
                 .with_effect(Effect::CheckFailed {
@@ -1317,6 +2925,19 @@ impl RulesEngine {
             }
         }
 
+        // An invisible creature hides without needing to roll
+        if skill == Skill::Stealth && character.has_condition(Condition::Invisible) {
+            return Resolution::new(format!(
+                "{} is invisible and hides automatically.",
+                character.name
+            ))
+            .with_effect(Effect::CheckSucceeded {
+                check_type: skill.name().to_string(),
+                roll: 0,
+                dc,
+            });
+        }
+
         let modifier = character.skill_modifier(skill);
 
         // Check for armor-imposed stealth disadvantage
@@ -1335,8 +2956,21 @@ impl RulesEngine {
             advantage
         };
 
+        // Perception relies on sight, so anything short of bright light hinders it
+        let effective_advantage = if skill == Skill::Perception
+            && character.senses.perceived_lighting(lighting) != Lighting::Bright
+        {
+            effective_advantage.combine(Advantage::Disadvantage)
+        } else {
+            effective_advantage
+        };
+
+        let effective_advantage =
+            with_armor_training(character, skill.ability(), effective_advantage);
+
         let expr = DiceExpression::parse(&format!("1d20+{modifier}")).unwrap();
-        let roll = expr.roll_with_advantage(effective_advantage);
+        let (rolled_advantage, inspiration) = with_inspiration(character, effective_advantage);
+        let roll = expr.roll_with_advantage(with_exhaustion(character, 1, rolled_advantage));
 
         let success = roll.total >= dc;
         let result_str = if success { "succeeds" } else { "fails" };
@@ -1361,10 +2995,12 @@ impl RulesEngine {
             disadvantage_note
         ));
 
-        resolution = resolution.with_effect(Effect::DiceRolled {
-            roll: roll.clone(),
-            purpose: format!("{} check - {}", skill.name(), description),
-        });
+        resolution = resolution
+            .with_effect(Effect::DiceRolled {
+                roll: roll.clone(),
+                purpose: format!("{} check - {}", skill.name(), description),
+            })
+            .with_effects(inspiration);
 
         if success {
             resolution = resolution.with_effect(Effect::CheckSucceeded {
@@ -1386,33 +3022,40 @@ impl RulesEngine {
     fn resolve_ability_check(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
+        character_id: CharacterId,
         ability: Ability,
         dc: i32,
         advantage: Advantage,
         description: &str,
     ) -> Resolution {
-        let character = &world.player_character;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        // Unconscious characters automatically fail Strength and Dexterity checks
-        if character.has_condition(Condition::Unconscious)
-            && matches!(ability, Ability::Strength | Ability::Dexterity) {
-                return Resolution::new(format!(
-                    "{} is unconscious and automatically fails the {} check!",
-                    character.name,
-                    ability.abbreviation()
-                ))
-                .with_effect(Effect::CheckFailed {
-                    check_type: format!("{} check", ability.abbreviation()),
-                    roll: 0,
-                    dc,
-                });
-            }
+        // Helpless characters automatically fail Strength and Dexterity checks
+        if let Some(condition) = character
+            .helpless_condition()
+            .filter(|_| matches!(ability, Ability::Strength | Ability::Dexterity))
+        {
+            return Resolution::new(format!(
+                "{} is {} and automatically fails the {} check!",
+                character.name,
+                condition.name().to_lowercase(),
+                ability.abbreviation()
+            ))
+            .with_effect(Effect::CheckFailed {
+                check_type: format!("{} check", ability.abbreviation()),
+                roll: 0,
+                dc,
+            });
+        }
 
-        let modifier = character.ability_scores.modifier(ability);
+        let modifier = character.effective_scores().modifier(ability);
 
         let expr = DiceExpression::parse(&format!("1d20+{modifier}")).unwrap();
-        let roll = expr.roll_with_advantage(advantage);
+        let advantage = with_armor_training(character, ability, advantage);
+        let (advantage, inspiration) = with_inspiration(character, advantage);
+        let roll = expr.roll_with_advantage(with_exhaustion(character, 1, advantage));
 
         let success = roll.total >= dc;
         let result_str = if success { "succeeds" } else { "fails" };
@@ -1426,10 +3069,12 @@ impl RulesEngine {
             dc
         ));
 
-        resolution = resolution.with_effect(Effect::DiceRolled {
-            roll: roll.clone(),
-            purpose: format!("{} check - {}", ability.abbreviation(), description),
-        });
+        resolution = resolution
+            .with_effect(Effect::DiceRolled {
+                roll: roll.clone(),
+                purpose: format!("{} check - {}", ability.abbreviation(), description),
+            })
+            .with_effects(inspiration);
 
         if success {
             resolution.with_effect(Effect::CheckSucceeded {
@@ -1449,33 +3094,41 @@ impl RulesEngine {
     fn resolve_saving_throw(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
+        character_id: CharacterId,
         ability: Ability,
         dc: i32,
         advantage: Advantage,
         source: &str,
     ) -> Resolution {
-        let character = &world.player_character;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        // Unconscious characters automatically fail Strength and Dexterity saving throws
-        if character.has_condition(Condition::Unconscious)
-            && matches!(ability, Ability::Strength | Ability::Dexterity) {
-                return Resolution::new(format!(
-                    "{} is unconscious and automatically fails the {} saving throw!",
-                    character.name,
-                    ability.abbreviation()
-                ))
-                .with_effect(Effect::CheckFailed {
-                    check_type: format!("{} save", ability.abbreviation()),
-                    roll: 0,
-                    dc,
-                });
-            }
+        // Paralyzed, petrified, stunned, and unconscious characters automatically
+        // fail Strength and Dexterity saving throws
+        if let Some(condition) = character
+            .helpless_condition()
+            .filter(|_| matches!(ability, Ability::Strength | Ability::Dexterity))
+        {
+            return Resolution::new(format!(
+                "{} is {} and automatically fails the {} saving throw!",
+                character.name,
+                condition.name().to_lowercase(),
+                ability.abbreviation()
+            ))
+            .with_effect(Effect::CheckFailed {
+                check_type: format!("{} save", ability.abbreviation()),
+                roll: 0,
+                dc,
+            });
+        }
 
         let modifier = character.saving_throw_modifier(ability);
 
         let expr = DiceExpression::parse(&format!("1d20+{modifier}")).unwrap();
-        let roll = expr.roll_with_advantage(advantage);
+        let advantage = with_armor_training(character, ability, advantage);
+        let (advantage, inspiration) = with_inspiration(character, advantage);
+        let roll = expr.roll_with_advantage(with_exhaustion(character, 3, advantage));
 
         let success = roll.total >= dc;
         let result_str = if success { "succeeds" } else { "fails" };
@@ -1489,10 +3142,12 @@ impl RulesEngine {
             dc
         ));
 
-        resolution = resolution.with_effect(Effect::DiceRolled {
-            roll: roll.clone(),
-            purpose: format!("{} save vs {}", ability.abbreviation(), source),
-        });
+        resolution = resolution
+            .with_effect(Effect::DiceRolled {
+                roll: roll.clone(),
+                purpose: format!("{} save vs {}", ability.abbreviation(), source),
+            })
+            .with_effects(inspiration);
 
         if success {
             resolution.with_effect(Effect::CheckSucceeded {
@@ -1517,7 +3172,9 @@ impl RulesEngine {
         damage_type: DamageType,
         source: &str,
     ) -> Resolution {
-        let target = &world.player_character;
+        let Some(target) = world.character(target_id) else {
+            return unknown_character(target_id);
+        };
 
         // Special handling for damage while already at 0 HP
         if target.hit_points.current <= 0 {
@@ -1598,12 +3255,24 @@ impl RulesEngine {
             format!(" (HP: {}/{})", hp.current, hp.maximum)
         };
 
+        // Temporary HP soaks damage before real HP
+        let absorbed = amount.min(target.hit_points.temporary).max(0);
+        let temp_status = if absorbed > 0 {
+            format!(
+                " [{} absorbed by temporary HP, {} temp HP left]",
+                absorbed, hp.temporary
+            )
+        } else {
+            String::new()
+        };
+
         let mut resolution = Resolution::new(format!(
-            "{} takes {} {} damage from {}{}",
+            "{} takes {} {} damage from {}{}{}",
             target.name,
             amount,
             damage_type.name(),
             source,
+            temp_status,
             hp_status
         ));
 
@@ -1622,6 +3291,13 @@ impl RulesEngine {
             });
         }
 
+        // Falling unconscious ends a rage
+        if result.dropped_to_zero && target.class_resources.rage_active {
+            let ended = self.resolve_end_rage(world, target_id, "unconscious");
+            resolution.narrative = format!("{} {}", resolution.narrative, ended.narrative);
+            resolution = resolution.with_effects(ended.effects);
+        }
+
         resolution
     }
 
@@ -1632,8 +3308,12 @@ impl RulesEngine {
         amount: i32,
         source: &str,
     ) -> Resolution {
-        let target = &world.player_character;
+        let Some(target) = world.character(target_id) else {
+            return unknown_character(target_id);
+        };
         let mut hp = target.hit_points.clone();
+        // Exhaustion can reduce the hit point maximum
+        hp.maximum = target.effective_max_hp();
         let was_unconscious = hp.current <= 0;
         let healed = hp.heal(amount);
 
@@ -1663,6 +3343,33 @@ impl RulesEngine {
         })
     }
 
+    fn resolve_grant_temp_hp(
+        &self,
+        world: &GameWorld,
+        target_id: CharacterId,
+        amount: i32,
+        source: &str,
+    ) -> Resolution {
+        let Some(target) = world.character(target_id) else {
+            return unknown_character(target_id);
+        };
+        let current_temp = target.hit_points.temporary;
+
+        let narrative = if amount > current_temp {
+            format!(
+                "{} gains {} temporary hit points from {}",
+                target.name, amount, source
+            )
+        } else {
+            format!(
+                "{} already has {} temporary hit points; the {} from {} don't stack",
+                target.name, current_temp, amount, source
+            )
+        };
+
+        Resolution::new(narrative).with_effect(Effect::TempHpGranted { target_id, amount })
+    }
+
     fn resolve_apply_condition(
         &self,
         world: &GameWorld,
@@ -1670,27 +3377,37 @@ impl RulesEngine {
         condition: Condition,
         source: &str,
         duration_rounds: Option<u32>,
+        duration_minutes: Option<u32>,
     ) -> Resolution {
-        let target = &world.player_character;
+        let target_name = creature_name(world, target_id);
 
-        let duration_text = duration_rounds
-            .map(|d| format!(" for {} rounds", d))
-            .unwrap_or_default();
+        let duration_text = match (duration_rounds, duration_minutes) {
+            (Some(d), _) => format!(" for {} rounds", d),
+            (None, Some(m)) => format!(" for {} minutes", m),
+            (None, None) => String::new(),
+        };
 
-        let resolution = Resolution::new(format!(
+        let mut resolution = Resolution::new(format!(
             "{} is now {} ({}){}",
-            target.name,
+            target_name,
             condition.name(),
             source,
             duration_text
-        ));
-
-        resolution.with_effect(Effect::ConditionApplied {
+        ))
+        .with_effect(Effect::ConditionApplied {
             target_id,
             condition,
             source: source.to_string(),
             duration_rounds,
-        })
+            duration_minutes,
+        });
+
+        if condition == Condition::Prone {
+            let riders = self.resolve_mount_knocked_prone(world, target_id);
+            resolution.narrative.push_str(&riders.narrative);
+            resolution = resolution.with_effects(riders.effects);
+        }
+        resolution
     }
 
     fn resolve_remove_condition(
@@ -1699,10 +3416,10 @@ impl RulesEngine {
         target_id: CharacterId,
         condition: Condition,
     ) -> Resolution {
-        let target = &world.player_character;
+        let target_name = creature_name(world, target_id);
 
         let resolution =
-            Resolution::new(format!("{} is no longer {}", target.name, condition.name()));
+            Resolution::new(format!("{} is no longer {}", target_name, condition.name()));
 
         resolution.with_effect(Effect::ConditionRemoved {
             target_id,
@@ -1710,57 +3427,410 @@ impl RulesEngine {
         })
     }
 
-    fn resolve_short_rest(&self, world: &GameWorld) -> Resolution {
-        // Can't rest during combat
-        if world.combat.is_some() {
-            return Resolution::new("Cannot take a short rest while in combat!");
-        }
+    fn resolve_add_exhaustion(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        levels: u8,
+        source: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let old_level = character.exhaustion_level();
+        let new_level = old_level.saturating_add(levels).min(6);
 
-        Resolution::new("The party takes a short rest, spending 1 hour resting.")
-            .with_effect(Effect::TimeAdvanced { minutes: 60 })
-            .with_effect(Effect::RestCompleted {
-                rest_type: RestType::Short,
-            })
-    }
+        let mut resolution = Resolution::new(format!(
+            "{} gains exhaustion from {} (level {} -> {}): {}",
+            character.name,
+            source,
+            old_level,
+            new_level,
+            exhaustion_description(new_level)
+        ))
+        .with_effect(Effect::ExhaustionChanged {
+            target_id: character_id,
+            old_level,
+            new_level,
+            source: source.to_string(),
+        });
 
-    fn resolve_long_rest(&self, world: &GameWorld) -> Resolution {
-        // Can't rest during combat
-        if world.combat.is_some() {
-            return Resolution::new("Cannot take a long rest while in combat!");
+        if new_level >= 6 {
+            resolution
+                .narrative
+                .push_str(&format!(" {} DIES!", character.name));
+            resolution = resolution.with_effect(Effect::CharacterDied {
+                target_id: character_id,
+                cause: "Exhaustion".to_string(),
+            });
         }
 
-        Resolution::new("The party takes a long rest, spending 8 hours resting.")
-            .with_effect(Effect::TimeAdvanced { minutes: 480 })
-            .with_effect(Effect::RestCompleted {
-                rest_type: RestType::Long,
-            })
+        resolution
     }
 
-    fn resolve_start_combat(
+    fn resolve_modify_ability(
         &self,
         world: &GameWorld,
-        combatants: Vec<CombatantInit>,
+        character_id: CharacterId,
+        ability: Ability,
+        delta: i8,
+        source: &str,
+        duration_rounds: Option<u32>,
     ) -> Resolution {
-        let mut resolution = Resolution::new("Combat begins! Roll for initiative.")
-            .with_effect(Effect::CombatStarted);
-
-        // Roll initiative for each combatant
-        for init in combatants {
-            let modifier = if init.is_player {
-                world.player_character.initiative_modifier()
-            } else {
-                init.initiative_modifier
-            };
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let before = character.effective_score(ability);
+        let modifier = AbilityMod {
+            ability,
+            delta,
+            source: source.to_string(),
+            expires_in_rounds: duration_rounds,
+        };
 
-            let roll = dice::roll("1d20").unwrap();
-            let total = roll.total + modifier as i32;
+        let mut after = character.clone();
+        after.ability_modifiers.push(modifier.clone());
+        let duration_text = duration_rounds
+            .map(|d| format!(" for {d} rounds"))
+            .unwrap_or_default();
 
-            resolution = resolution.with_effect(Effect::InitiativeRolled {
-                character_id: init.id,
-                name: init.name.clone(),
-                roll: roll.total,
-                total,
-            });
+        Resolution::new(format!(
+            "{}'s {} goes from {} to {} ({}){}",
+            character.name,
+            ability.name(),
+            before,
+            after.effective_score(ability),
+            source,
+            duration_text
+        ))
+        .with_effect(Effect::AbilityModifierAdded {
+            character_id,
+            modifier,
+        })
+    }
+
+    fn resolve_remove_ability_modifier(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        source: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        if !character
+            .ability_modifiers
+            .iter()
+            .any(|m| m.source.eq_ignore_ascii_case(source))
+        {
+            return Resolution::new(format!(
+                "{} has no ability modifier from {}.",
+                character.name, source
+            ));
+        }
+        Resolution::new(format!("{}'s {} wears off.", character.name, source)).with_effect(
+            Effect::AbilityModifierRemoved {
+                character_id,
+                source: source.to_string(),
+            },
+        )
+    }
+
+    fn resolve_grant_inspiration(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        if character.has_inspiration {
+            return Resolution::new(format!("{} already has Inspiration.", character.name));
+        }
+        Resolution::new(format!("{} gains Inspiration!", character.name))
+            .with_effect(Effect::InspirationGranted { character_id })
+    }
+
+    fn resolve_use_inspiration(&self, world: &GameWorld, character_id: CharacterId) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        if !character.has_inspiration {
+            return Resolution::new(format!("{} has no Inspiration to spend.", character.name));
+        }
+        Resolution::new(format!(
+            "{} spends Inspiration: their next attack, check or save has advantage.",
+            character.name
+        ))
+        .with_effect(Effect::InspirationUsed { character_id })
+    }
+
+    fn resolve_fall(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        distance_feet: u32,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let dice_count = (distance_feet / 10).min(20);
+        if dice_count == 0 {
+            return Resolution::new(format!(
+                "{} drops {} feet and lands unharmed.",
+                character.name, distance_feet
+            ));
+        }
+
+        let roll = dice::roll(&format!("{dice_count}d6")).unwrap();
+        let source = format!("a {distance_feet}-foot fall");
+        let damage = self.resolve_damage(
+            world,
+            character_id,
+            roll.total,
+            DamageType::Bludgeoning,
+            &source,
+        );
+
+        Resolution::new(format!(
+            "{} falls {} feet. {}",
+            character.name, distance_feet, damage.narrative
+        ))
+        .with_effect(Effect::DiceRolled {
+            roll,
+            purpose: "Falling damage".to_string(),
+        })
+        .with_effects(damage.effects)
+        .with_effect(Effect::ConditionApplied {
+            target_id: character_id,
+            condition: Condition::Prone,
+            source,
+            duration_rounds: None,
+            duration_minutes: None,
+        })
+    }
+
+    fn resolve_suffocate(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        rounds_without_air: u32,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let breath = character.breath_rounds();
+        let grace = character.suffocation_rounds();
+
+        if rounds_without_air <= breath {
+            return Resolution::new(format!(
+                "{} holds their breath ({} of {} rounds).",
+                character.name, rounds_without_air, breath
+            ));
+        }
+        if rounds_without_air <= breath + grace {
+            return Resolution::new(format!(
+                "{} is out of breath and choking! {} round(s) until they fall unconscious.",
+                character.name,
+                breath + grace - rounds_without_air
+            ));
+        }
+        if character.hit_points.current <= 0 {
+            return Resolution::new(format!(
+                "{} is suffocating and can't be stabilized until they can breathe.",
+                character.name
+            ));
+        }
+
+        Resolution::new(format!(
+            "{} suffocates and drops to 0 HP, unconscious and dying!",
+            character.name
+        ))
+        .with_effect(Effect::HpChanged {
+            target_id: character_id,
+            amount: -character.hit_points.current,
+            new_current: 0,
+            new_max: character.hit_points.maximum,
+            dropped_to_zero: true,
+        })
+    }
+
+    fn resolve_remove_exhaustion(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        levels: u8,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let old_level = character.exhaustion_level();
+        if old_level == 0 {
+            return Resolution::new(format!("{} is not exhausted.", character.name));
+        }
+        let new_level = old_level.saturating_sub(levels);
+
+        Resolution::new(format!(
+            "{} recovers from exhaustion (level {} -> {})",
+            character.name, old_level, new_level
+        ))
+        .with_effect(Effect::ExhaustionChanged {
+            target_id: character_id,
+            old_level,
+            new_level,
+            source: "Recovery".to_string(),
+        })
+    }
+
+    fn resolve_spend_hit_dice(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        count: u8,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        if world.combat.is_some() {
+            return Resolution::new("Cannot spend hit dice while in combat!");
+        }
+        if character.hit_dice.total_remaining() == 0 {
+            return Resolution::new(format!("{} has no hit dice remaining!", character.name));
+        }
+
+        let con_mod = character.effective_scores().modifier(Ability::Constitution) as i32;
+        let mut hit_dice = character.hit_dice.clone();
+        let mut hp = character.hit_points.clone();
+        hp.maximum = character.effective_max_hp();
+
+        let mut resolution = Resolution::new("");
+        let mut spent: Vec<(DieType, u8)> = Vec::new();
+        let mut total_healing = 0;
+
+        for _ in 0..count {
+            let Some(die_type) = hit_dice.largest_remaining() else {
+                break;
+            };
+            hit_dice.spend(die_type);
+            match spent.iter_mut().find(|(d, _)| *d == die_type) {
+                Some((_, n)) => *n += 1,
+                None => spent.push((die_type, 1)),
+            }
+
+            let roll = roll_with_fallback(&format!("1d{}{con_mod:+}", die_type.sides()), "1d4");
+            // Each die heals at least 0, even with a negative CON modifier
+            total_healing += roll.total.max(0);
+            resolution = resolution.with_effect(Effect::DiceRolled {
+                roll,
+                purpose: "Hit die".to_string(),
+            });
+        }
+
+        let dice_spent: u8 = spent.iter().map(|(_, n)| n).sum();
+        let healed = hp.heal(total_healing);
+
+        for (die_type, n) in spent {
+            resolution = resolution.with_effect(Effect::HitDiceSpent {
+                target_id: character_id,
+                die_type,
+                count: n,
+            });
+        }
+
+        let shortfall = if dice_spent < count {
+            format!(" (only {dice_spent} hit dice were available)")
+        } else {
+            String::new()
+        };
+        resolution.narrative = format!(
+            "{} spends {} hit dice and regains {} hit points{} (HP: {}/{}, hit dice left: {})",
+            character.name,
+            dice_spent,
+            healed,
+            shortfall,
+            hp.current,
+            hp.maximum,
+            hit_dice.total_remaining()
+        );
+
+        resolution.with_effect(Effect::HpChanged {
+            target_id: character_id,
+            amount: healed,
+            new_current: hp.current,
+            new_max: hp.maximum,
+            dropped_to_zero: false,
+        })
+    }
+
+    fn resolve_short_rest(&self, world: &GameWorld) -> Resolution {
+        // Can't rest during combat
+        if world.combat.is_some() {
+            return Resolution::new("Cannot take a short rest while in combat!");
+        }
+
+        // The rest itself moves the clock on
+        Resolution::new("The party takes a short rest, spending 1 hour resting.").with_effect(
+            Effect::RestCompleted {
+                rest_type: RestType::Short,
+            },
+        )
+    }
+
+    fn resolve_long_rest(&self, world: &GameWorld) -> Resolution {
+        // Can't rest during combat
+        if world.combat.is_some() {
+            return Resolution::new("Cannot take a long rest while in combat!");
+        }
+
+        Resolution::new("The party takes a long rest, spending 8 hours resting.").with_effect(
+            Effect::RestCompleted {
+                rest_type: RestType::Long,
+            },
+        )
+    }
+
+    fn resolve_start_combat(
+        &self,
+        world: &GameWorld,
+        combatants: Vec<CombatantInit>,
+    ) -> Resolution {
+        let mut resolution = Resolution::new("Combat begins! Roll for initiative.")
+            .with_effect(Effect::CombatStarted);
+
+        // Roll initiative for each combatant, numbering repeated names so each
+        // can be targeted ("Goblin", "Goblin 2")
+        let mut names: Vec<String> = Vec::new();
+        for mut init in combatants {
+            init.name = crate::world::numbered_name(&init.name, |name| {
+                names.iter().any(|n| n.eq_ignore_ascii_case(name))
+            });
+            names.push(init.name.clone());
+            let (modifier, advantage) = if init.is_player {
+                let player = &world.player_character;
+                (
+                    player.initiative_modifier(),
+                    with_armor_training(
+                        player,
+                        Ability::Dexterity,
+                        with_exhaustion(player, 1, Advantage::Normal),
+                    ),
+                )
+            } else {
+                (init.initiative_modifier, Advantage::Normal)
+            };
+
+            let roll = DiceExpression::parse("1d20")
+                .unwrap()
+                .roll_with_advantage(advantage);
+            let total = roll.total + modifier as i32;
+
+            resolution = resolution.with_effect(Effect::InitiativeRolled {
+                character_id: init.id,
+                name: init.name.clone(),
+                roll: roll.total,
+                total,
+            });
 
             resolution = resolution.with_effect(Effect::CombatantAdded {
                 id: init.id,
@@ -1770,6 +3840,12 @@ impl RulesEngine {
                 current_hp: init.current_hp,
                 max_hp: init.max_hp,
                 armor_class: init.armor_class,
+                attack: init.attack,
+                position: init.position,
+                size: init.size,
+                speed: init.speed,
+                damage_traits: init.damage_traits,
+                senses: init.senses,
             });
         }
 
@@ -1782,6 +3858,7 @@ impl RulesEngine {
 
     fn resolve_next_turn(&self, world: &GameWorld) -> Resolution {
         if let Some(ref combat) = world.combat {
+            let rage_end = self.rage_end_at_turn_end(world, combat);
             let mut combat_clone = combat.clone();
             combat_clone.next_turn();
 
@@ -1790,2228 +3867,7481 @@ impl RulesEngine {
                 .map(|c| c.name.clone())
                 .unwrap_or_else(|| "Unknown".to_string());
 
-            Resolution::new(format!(
+            let mut resolution = Resolution::new(format!(
                 "Next turn: {} (Round {})",
                 current, combat_clone.round
             ))
             .with_effect(Effect::TurnAdvanced {
                 round: combat_clone.round,
                 current_combatant: current,
-            })
+            });
+            if let Some(ended) = rage_end {
+                resolution.narrative = format!("{}. {}", ended.narrative, resolution.narrative);
+                resolution = resolution.with_effects(ended.effects);
+            }
+
+            // A dying character rolls a death save at the start of their turn
+            if let Some(dying) = combat_clone
+                .current_combatant()
+                .and_then(|c| world.character(c.id))
+                .filter(|c| {
+                    c.hit_points.current == 0 && !c.death_saves.stable && !c.death_saves.is_dead()
+                })
+            {
+                let save = self.resolve_death_save(world, dying.id);
+                resolution.narrative = format!("{}. {}", resolution.narrative, save.narrative);
+                resolution = resolution.with_effects(save.effects);
+            }
+
+            // Enemies with a stored attack take their turn right away
+            if let Some(enemy) = combat_clone
+                .current_combatant()
+                .filter(|c| !c.is_ally && c.attack.is_some())
+            {
+                let turn = self.resolve_enemy_turn(world, enemy.id);
+                resolution.narrative = format!("{}. {}", resolution.narrative, turn.narrative);
+                resolution = resolution.with_effects(turn.effects);
+            }
+
+            resolution
         } else {
             Resolution::new("No combat in progress")
         }
     }
 
-    fn resolve_roll_initiative(
+    /// A rage that lapses as the barbarian's turn ends: when they neither
+    /// attacked nor took damage since their last turn, or the minute runs out.
+    fn rage_end_at_turn_end(
         &self,
-        character_id: CharacterId,
-        name: &str,
-        modifier: i8,
-        _is_player: bool,
-    ) -> Resolution {
-        let roll = dice::roll("1d20").unwrap();
-        let total = roll.total + modifier as i32;
-
-        Resolution::new(format!(
-            "{} rolls initiative: {} + {} = {}",
-            name, roll.total, modifier, total
-        ))
-        .with_effect(Effect::DiceRolled {
-            roll: roll.clone(),
-            purpose: "Initiative".to_string(),
-        })
-        .with_effect(Effect::InitiativeRolled {
-            character_id,
-            name: name.to_string(),
-            roll: roll.total,
-            total,
-        })
-    }
-
-    fn resolve_roll_dice(&self, notation: &str, purpose: &str) -> Resolution {
-        match dice::roll(notation) {
-            Ok(roll) => Resolution::new(format!("Rolling {notation} for {purpose}: {roll}"))
-                .with_effect(Effect::DiceRolled {
-                    roll,
-                    purpose: purpose.to_string(),
-                }),
-            Err(e) => Resolution::new(format!("Failed to roll {notation}: {e}")),
+        world: &GameWorld,
+        combat: &crate::world::CombatState,
+    ) -> Option<Resolution> {
+        let ending = combat.current_combatant()?;
+        let barbarian = world.character(ending.id)?;
+        let resources = &barbarian.class_resources;
+        if !resources.rage_active {
+            return None;
         }
+        let reason = if ending.attacks_made == 0 && !resources.rage_damage_taken {
+            "no_combat_action"
+        } else if resources
+            .rage_rounds_remaining
+            .is_some_and(|rounds| rounds <= 1)
+        {
+            "duration_expired"
+        } else {
+            return None;
+        };
+        Some(self.resolve_end_rage(world, barbarian.id, reason))
     }
 
-    fn resolve_advance_time(&self, minutes: u32) -> Resolution {
-        let hours = minutes / 60;
-        let mins = minutes % 60;
+    fn resolve_enemy_turn(&self, world: &GameWorld, combatant_id: CharacterId) -> Resolution {
+        let Some(combat) = world.combat.as_ref() else {
+            return Resolution::new("No combat in progress");
+        };
+        let Some(enemy) = combat.combatants.iter().find(|c| c.id == combatant_id) else {
+            return Resolution::new("That creature is not part of this combat");
+        };
+        if enemy.is_ally {
+            return Resolution::new(format!(
+                "{} fights alongside the party; their turn is played by hand.",
+                enemy.name
+            ));
+        }
+        if enemy.current_hp <= 0 {
+            return Resolution::new(format!("{} is down and loses its turn.", enemy.name));
+        }
+        if let Some(active) = enemy
+            .conditions
+            .iter()
+            .find(|c| c.condition.is_incapacitating())
+        {
+            return Resolution::new(format!(
+                "{} is {} and loses its turn.",
+                enemy.name,
+                active.condition.name().to_lowercase()
+            ));
+        }
+        let Some(attack) = enemy.attack.as_ref() else {
+            return Resolution::new(format!(
+                "{} has no attack on record; describe its turn.",
+                enemy.name
+            ));
+        };
 
-        let time_str = if hours > 0 && mins > 0 {
-            format!("{hours} hours and {mins} minutes")
-        } else if hours > 0 {
-            format!("{hours} hours")
-        } else {
-            format!("{mins} minutes")
+        // Focus on the most wounded character still standing
+        let Some(target) = combat
+            .combatants
+            .iter()
+            .filter(|c| c.is_ally)
+            .filter_map(|c| world.character(c.id))
+            .filter(|c| c.hit_points.current > 0)
+            .min_by_key(|c| c.hit_points.current)
+        else {
+            return Resolution::new(format!("{} has no one left to attack.", enemy.name));
         };
 
-        Resolution::new(format!("{time_str} pass.")).with_effect(Effect::TimeAdvanced { minutes })
+        self.resolve_combatant_attack(world, enemy, attack, target)
     }
 
-    fn resolve_gain_experience(&self, world: &GameWorld, amount: u32) -> Resolution {
-        let new_total = world.player_character.experience + amount;
-        let current_level = world.player_character.level;
-
-        // XP thresholds for levels 1-20
-        let xp_thresholds = [
-            0, 300, 900, 2700, 6500, 14000, 23000, 34000, 48000, 64000, 85000, 100000, 120000,
-            140000, 165000, 195000, 225000, 265000, 305000, 355000,
-        ];
-
-        let new_level = xp_thresholds
-            .iter()
-            .rposition(|&threshold| new_total >= threshold)
-            .map(|idx| (idx + 1) as u8)
-            .unwrap_or(1);
+    /// Roll a combatant's stored attack against a character and apply damage on a hit.
+    fn resolve_combatant_attack(
+        &self,
+        world: &GameWorld,
+        attacker: &Combatant,
+        attack: &CombatantAttack,
+        target: &Character,
+    ) -> Resolution {
+        let target_ac = target.current_ac();
+        let mut advantage = if has_mounted_advantage(world, attacker.id, target.id)
+            || has_flanking_advantage(world, attacker.id, target.id)
+        {
+            Advantage::Advantage
+        } else {
+            Advantage::Normal
+        };
+        // The attacker's own conditions hamper it; a helpless target is easy to hit
+        if [
+            Condition::Blinded,
+            Condition::Poisoned,
+            Condition::Prone,
+            Condition::Restrained,
+        ]
+        .into_iter()
+        .any(|c| attacker.has_condition(c))
+        {
+            advantage = advantage.combine(Advantage::Disadvantage);
+        }
+        if [
+            Condition::Blinded,
+            Condition::Paralyzed,
+            Condition::Prone,
+            Condition::Restrained,
+            Condition::Stunned,
+            Condition::Unconscious,
+        ]
+        .into_iter()
+        .any(|c| target.has_condition(c))
+        {
+            advantage = advantage.combine(Advantage::Advantage);
+        }
+        advantage = advantage.combine(invisibility_advantage(world, attacker.id, target.id));
+        let attack_roll = DiceExpression::parse(&format!("1d20{:+}", attack.attack_bonus))
+            .unwrap()
+            .roll_with_advantage(advantage);
 
         let mut resolution = Resolution::new(format!(
-            "Gained {amount} experience points (Total: {new_total})"
-        ));
-
-        resolution = resolution.with_effect(Effect::ExperienceGained { amount, new_total });
+            "{} attacks {} with {} (roll: {} vs AC {})",
+            attacker.name, target.name, attack.name, attack_roll.total, target_ac
+        ))
+        .with_effect(Effect::DiceRolled {
+            roll: attack_roll.clone(),
+            purpose: format!("{} attack with {}", attacker.name, attack.name),
+        });
 
-        if new_level > current_level {
-            resolution = resolution.with_effect(Effect::LevelUp { new_level });
+        let is_critical = attack_roll.is_critical();
+        let hits =
+            !attack_roll.is_fumble() && (attack_roll.total >= target_ac as i32 || is_critical);
+        let shield = hits
+            .then(|| {
+                self.shield_reaction(world, target.id, attack_roll.total, target_ac, is_critical)
+            })
+            .flatten();
+        if let Some(shield) = shield {
+            resolution.narrative = format!("{} - {}", resolution.narrative, shield.narrative);
+            return resolution
+                .with_effects(shield.effects)
+                .with_effect(Effect::AttackMissed {
+                    attacker_name: attacker.name.clone(),
+                    target_name: target.name.clone(),
+                    attack_roll: attack_roll.total,
+                    target_ac: target_ac + 5,
+                });
+        }
+        if !hits {
+            resolution.narrative.push_str(" - miss!");
+            return resolution.with_effect(Effect::AttackMissed {
+                attacker_name: attacker.name.clone(),
+                target_name: target.name.clone(),
+                attack_roll: attack_roll.total,
+                target_ac,
+            });
         }
 
+        resolution = resolution.with_effect(Effect::AttackHit {
+            attacker_id: attacker.id,
+            target_id: Some(target.id),
+            attacker_name: attacker.name.clone(),
+            target_name: target.name.clone(),
+            attack_roll: attack_roll.total,
+            target_ac,
+            is_critical,
+        });
+
+        let damage_expr = if is_critical {
+            critical_damage_dice(&attack.damage_dice)
+        } else {
+            attack.damage_dice.clone()
+        };
+        let damage_roll = roll_with_fallback(&damage_expr, "1d4");
+        let damage = self.resolve_damage(
+            world,
+            target.id,
+            damage_roll.total.max(1),
+            attack.damage_type,
+            &attacker.name,
+        );
+
+        resolution.narrative = format!(
+            "{} - {}! {}",
+            resolution.narrative,
+            if is_critical { "critical hit" } else { "hit" },
+            damage.narrative
+        );
         resolution
+            .with_effect(Effect::DiceRolled {
+                roll: damage_roll,
+                purpose: "Damage".to_string(),
+            })
+            .with_effects(damage.effects)
     }
 
-    fn resolve_use_feature(
+    fn resolve_move(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        feature_name: &str,
+        character_id: CharacterId,
+        destination: &str,
+        distance_feet: u32,
+        to_position: Option<GridPosition>,
     ) -> Resolution {
-        let character = &world.player_character;
+        let mover_name = creature_name(world, character_id);
+        let mount_id = world
+            .combat
+            .as_ref()
+            .and_then(|combat| combat.combatant(character_id))
+            .and_then(|mover| mover.mounted_on);
 
-        if let Some(feature) = character.features.iter().find(|f| f.name == feature_name) {
-            if let Some(ref uses) = feature.uses {
-                if uses.current > 0 {
-                    Resolution::new(format!(
-                        "{} uses {} ({} uses remaining)",
-                        character.name,
-                        feature_name,
-                        uses.current - 1
-                    ))
-                    .with_effect(Effect::FeatureUsed {
-                        feature_name: feature_name.to_string(),
-                        uses_remaining: uses.current - 1,
-                    })
-                } else {
-                    Resolution::new(format!(
-                        "{} has no uses of {} remaining",
-                        character.name, feature_name
-                    ))
-                }
-            } else {
-                Resolution::new(format!("{} uses {}", character.name, feature_name))
-            }
+        // Grappled, restrained, paralyzed (and similar) creatures have a speed of 0
+        let immobilized = IMMOBILIZING_CONDITIONS.into_iter().find(|c| {
+            creature_has_condition(world, character_id, *c)
+                || mount_id.is_some_and(|id| creature_has_condition(world, id, *c))
+        });
+        if let Some(condition) = immobilized {
+            return Resolution::new(format!(
+                "{mover_name} can't move: {} leaves them with a speed of 0.",
+                condition.name().to_lowercase()
+            ));
+        }
+
+        // Standing up from prone costs half the mover's speed
+        let standing =
+            mount_id.is_none() && creature_has_condition(world, character_id, Condition::Prone);
+        let mut resolution = if standing {
+            Resolution::new(format!(
+                "{mover_name} stands up, using half their movement, and moves {distance_feet} feet to {destination}."
+            ))
+            .with_effect(Effect::ConditionRemoved {
+                target_id: character_id,
+                condition: Condition::Prone,
+            })
         } else {
             Resolution::new(format!(
-                "{} does not have the feature {}",
-                character.name, feature_name
+                "{mover_name} moves {distance_feet} feet to {destination}."
             ))
+        };
+
+        let Some(combat) = world.combat.as_ref() else {
+            return resolution;
+        };
+        let Some(mover) = combat.combatant(character_id) else {
+            return resolution;
+        };
+
+        // A rider moves at their mount's speed, and the two move together
+        let mount = mover.mounted_on.and_then(|id| combat.combatant(id));
+        let speed = mount.map_or(mover.speed, |m| m.speed);
+        let stand_cost = if standing { speed / 2 } else { 0 };
+        let remaining = mount.map_or(mover.movement_remaining, |m| m.movement_remaining);
+        let budget = remaining.min(speed).saturating_sub(stand_cost);
+        if distance_feet > budget {
+            return Resolution::new(format!(
+                "{} can only move {} feet this turn{}.",
+                mover.name,
+                budget,
+                if standing {
+                    " after standing up from prone".to_string()
+                } else {
+                    mount.map_or(String::new(), |m| format!(" riding {}", m.name))
+                }
+            ));
+        }
+        let unit_id = mount.map_or(mover.id, |m| m.id);
+        resolution = resolution.with_effect(Effect::MovementUsed {
+            id: unit_id,
+            feet: distance_feet + stand_cost,
+        });
+        let Some(to) = to_position else {
+            return resolution;
+        };
+
+        if let Some(from) = mover.position {
+            // Hostiles with a reaction left whose reach the mover starts in
+            // and ends outside of
+            let provokers = combat.combatants.iter().filter(|c| {
+                c.is_ally != mover.is_ally
+                    && c.current_hp > 0
+                    && !c.reaction_used
+                    && c.position.is_some_and(|p| {
+                        p.distance_feet(from) <= c.reach_feet()
+                            && p.distance_feet(to) > c.reach_feet()
+                    })
+            });
+
+            for provoker in provokers {
+                if mover.disengaged {
+                    resolution.narrative.push_str(&format!(
+                        " {} disengaged, so {} gets no opportunity attack.",
+                        mover.name, provoker.name
+                    ));
+                    continue;
+                }
+                let attack = self.resolve_opportunity_attack(world, provoker, mover);
+                resolution.narrative.push_str(&format!(
+                    " {} leaves {}'s reach and provokes an opportunity attack! {}",
+                    mover.name, provoker.name, attack.narrative
+                ));
+                resolution = resolution.with_effects(attack.effects).with_effects(
+                    self.reaction_used_effects(world, provoker, Reaction::OpportunityAttack),
+                );
+            }
         }
+
+        let moved = combat
+            .combatants
+            .iter()
+            .filter(|c| c.id == unit_id || c.mounted_on == Some(unit_id))
+            .map(|c| Effect::CombatantMoved {
+                id: c.id,
+                position: to,
+            });
+        resolution.with_effects(moved)
     }
 
-    fn resolve_remember_fact(
+    fn resolve_mount(
         &self,
-        subject_name: &str,
-        subject_type: &str,
-        fact: &str,
-        category: &str,
-        related_entities: &[String],
-        importance: f32,
+        world: &GameWorld,
+        rider_id: CharacterId,
+        mount_id: CharacterId,
     ) -> Resolution {
-        // The actual storage is handled by the DM agent, not the rules engine.
-        // We return a confirmation message and an effect that signals what to store.
-        let related_str = if related_entities.is_empty() {
-            String::new()
-        } else {
-            format!(" (related: {})", related_entities.join(", "))
+        let Some(combat) = world.combat.as_ref() else {
+            return Resolution::new(format!(
+                "{} climbs onto {}.",
+                creature_name(world, rider_id),
+                creature_name(world, mount_id)
+            ));
+        };
+        let (Some(rider), Some(mount)) = (combat.combatant(rider_id), combat.combatant(mount_id))
+        else {
+            return Resolution::new("Both rider and mount must be in the combat.");
         };
+        if rider.mounted_on.is_some() {
+            return Resolution::new(format!("{} is already mounted.", rider.name));
+        }
+        if mount.size <= rider.size {
+            return Resolution::new(format!(
+                "{} ({}) is too small to carry {} ({}).",
+                mount.name,
+                mount.size.name(),
+                rider.name,
+                rider.size.name()
+            ));
+        }
+        if combat.riders_of(mount_id).next().is_some() {
+            return Resolution::new(format!("{} already has a rider.", mount.name));
+        }
 
         Resolution::new(format!(
-            "Noted: {subject_name} ({subject_type}) - {fact}{related_str}"
+            "{} mounts {}, using half their movement.",
+            rider.name, mount.name
         ))
-        .with_effect(Effect::FactRemembered {
-            subject_name: subject_name.to_string(),
-            subject_type: subject_type.to_string(),
-            fact: fact.to_string(),
-            category: category.to_string(),
-            related_entities: related_entities.to_vec(),
-            importance,
-        })
+        .with_effect(Effect::Mounted { rider_id, mount_id })
     }
 
-    // Inventory resolution methods
-
-    #[allow(clippy::too_many_arguments)]
-    fn resolve_add_item(
-        &self,
-        world: &GameWorld,
-        item_name: &str,
-        quantity: u32,
-        _item_type: Option<&str>,
-        _description: Option<&str>,
-        _magical: bool,
-        _weight: Option<f32>,
-        _value_gp: Option<f32>,
-    ) -> Resolution {
-        let character = &world.player_character;
-
-        // Check if item already exists
-        let existing_qty = character
-            .inventory
-            .find_item(item_name)
-            .map(|i| i.quantity)
-            .unwrap_or(0);
-        let new_total = existing_qty + quantity;
-
-        // Note: item_type, description, magical, weight, value_gp are passed through
-        // but the actual item creation happens in apply_effect or could be enhanced
-        // to look up standard items from the items database.
-
-        let qty_str = if quantity > 1 {
-            format!("{quantity} x ")
-        } else {
-            String::new()
+    fn resolve_dismount(&self, world: &GameWorld, rider_id: CharacterId) -> Resolution {
+        let rider = world
+            .combat
+            .as_ref()
+            .and_then(|c| c.combatant(rider_id))
+            .filter(|c| c.mounted_on.is_some());
+        let Some(rider) = rider else {
+            return Resolution::new(format!("{} isn't mounted.", creature_name(world, rider_id)));
         };
-
         Resolution::new(format!(
-            "{} receives {}{} (now has {} total)",
-            character.name, qty_str, item_name, new_total
+            "{} dismounts, using half their movement.",
+            rider.name
         ))
-        .with_effect(Effect::ItemAdded {
-            item_name: item_name.to_string(),
-            quantity,
-            new_total,
-        })
+        .with_effect(Effect::Dismounted { rider_id })
     }
 
-    fn resolve_remove_item(
-        &self,
-        world: &GameWorld,
-        item_name: &str,
-        quantity: u32,
-    ) -> Resolution {
-        let character = &world.player_character;
+    /// When a mount is knocked prone, each rider makes a DC 10 DEX save or falls off prone.
+    fn resolve_mount_knocked_prone(&self, world: &GameWorld, mount_id: CharacterId) -> Resolution {
+        let mut resolution = Resolution::new("");
+        let Some(combat) = world.combat.as_ref() else {
+            return resolution;
+        };
 
-        if let Some(item) = character.inventory.find_item(item_name) {
-            if item.quantity >= quantity {
-                let remaining = item.quantity - quantity;
-                let qty_str = if quantity > 1 {
-                    format!("{quantity} x ")
-                } else {
-                    String::new()
+        for rider in combat.riders_of(mount_id) {
+            let (narrative, rolls, saved) = if world.character(rider.id).is_some() {
+                let save = self.resolve_saving_throw(
+                    world,
+                    rider.id,
+                    Ability::Dexterity,
+                    10,
+                    Advantage::Normal,
+                    "falling from a mount",
+                );
+                let saved = save
+                    .effects
+                    .iter()
+                    .any(|e| matches!(e, Effect::CheckSucceeded { .. }));
+                (save.narrative, save.effects, saved)
+            } else {
+                // Creatures without a character sheet save with no modifier
+                let roll = DiceExpression::parse("1d20").unwrap().roll();
+                let saved = roll.total >= 10;
+                let narrative = format!(
+                    "{} {} on DEX saving throw ({} vs DC 10)",
+                    rider.name,
+                    if saved { "succeeds" } else { "fails" },
+                    roll.total
+                );
+                let rolled = Effect::DiceRolled {
+                    roll,
+                    purpose: "DEX save vs falling from a mount".to_string(),
                 };
-                Resolution::new(format!(
-                    "{} loses {}{} ({} remaining)",
-                    character.name, qty_str, item_name, remaining
-                ))
-                .with_effect(Effect::ItemRemoved {
-                    item_name: item_name.to_string(),
-                    quantity,
-                    remaining,
-                })
+                (narrative, vec![rolled], saved)
+            };
+
+            resolution.narrative.push_str(&format!(" {narrative}"));
+            resolution = resolution.with_effects(rolls);
+            if saved {
+                resolution.narrative.push_str(" and stays in the saddle.");
             } else {
-                Resolution::new(format!(
-                    "{} doesn't have enough {} (has {}, needs {})",
-                    character.name, item_name, item.quantity, quantity
-                ))
+                resolution
+                    .narrative
+                    .push_str(" and falls off, landing prone!");
+                resolution = resolution
+                    .with_effect(Effect::Dismounted { rider_id: rider.id })
+                    .with_effect(Effect::ConditionApplied {
+                        target_id: rider.id,
+                        condition: Condition::Prone,
+                        source: "Fell from mount".to_string(),
+                        duration_rounds: None,
+                        duration_minutes: None,
+                    });
             }
-        } else {
-            Resolution::new(format!(
-                "{} doesn't have any {}",
-                character.name, item_name
-            ))
         }
-    }
-
-    fn resolve_equip_item(&self, world: &GameWorld, item_name: &str) -> Resolution {
-        let character = &world.player_character;
 
-        if let Some(item) = character.inventory.find_item(item_name) {
-            let slot = match item.item_type {
-                ItemType::Weapon => "main_hand",
-                ItemType::Armor => "armor",
-                ItemType::Shield => "shield",
-                _ => {
-                    return Resolution::new(format!(
-                        "{item_name} cannot be equipped (not a weapon, armor, or shield)"
-                    ));
-                }
-            };
+        resolution
+    }
 
-            // Check for two-handed weapon + shield conflict
-            if slot == "shield" {
-                if let Some(ref weapon) = character.equipment.main_hand {
-                    if weapon.is_two_handed() {
-                        return Resolution::new(format!(
-                            "Cannot equip {} - {} requires two hands",
-                            item_name, weapon.base.name
-                        ));
-                    }
-                }
-            }
+    fn resolve_opportunity_attack(
+        &self,
+        world: &GameWorld,
+        attacker: &Combatant,
+        target: &Combatant,
+    ) -> Resolution {
+        if let Some(character) = world.character(attacker.id) {
+            let weapon = character
+                .equipment
+                .main_hand
+                .as_ref()
+                .map_or("Unarmed Strike".to_string(), |w| w.base.name.clone());
+            return self.resolve_attack(
+                world,
+                attacker.id,
+                target.id,
+                &weapon,
+                Advantage::Normal,
+                Lighting::default(),
+            );
+        }
 
-            // Check for shield + two-handed weapon conflict
-            if slot == "main_hand" {
-                if let Some(db_weapon) = crate::items::get_weapon(item_name) {
-                    if db_weapon.is_two_handed() && character.equipment.shield.is_some() {
-                        return Resolution::new(format!(
-                            "Cannot equip {item_name} - it requires two hands but a shield is equipped. Unequip the shield first."
-                        ));
-                    }
-                }
+        match (attacker.attack.as_ref(), world.character(target.id)) {
+            (Some(attack), Some(target)) => {
+                self.resolve_combatant_attack(world, attacker, attack, target)
             }
+            _ => Resolution::new(format!(
+                "{} swings as {} passes; describe the result.",
+                attacker.name, target.name
+            )),
+        }
+    }
 
-            // Check strength requirement for heavy armor
-            if slot == "armor" {
-                if let Some(db_armor) = crate::items::get_armor(item_name) {
-                    if let Some(str_req) = db_armor.strength_requirement {
-                        let char_str = character.ability_scores.strength;
-                        if char_str < str_req {
-                            return Resolution::new(format!(
-                                "{} equips {} but doesn't meet the Strength {} requirement (has {}). Movement speed reduced by 10 feet.",
-                                character.name, item_name, str_req, char_str
-                            ))
-                            .with_effect(Effect::ItemEquipped {
-                                item_name: item_name.to_string(),
-                                slot: slot.to_string(),
-                            });
-                        }
-                    }
-                }
+    /// Refuse an intent whose cost the combatant has already spent this turn.
+    fn action_unavailable(
+        &self,
+        world: &GameWorld,
+        combatant_id: CharacterId,
+        cost: ActionCost,
+    ) -> Option<Resolution> {
+        let combatant = world.combat.as_ref()?.combatant(combatant_id)?;
+        let spent = match cost {
+            ActionCost::Action => combatant.action_used,
+            // Extra Attack lets the Attack action make more than one attack
+            ActionCost::Attack => {
+                let attacks = world
+                    .character(combatant_id)
+                    .map_or(1, |c| c.attacks_per_action());
+                combatant.action_used
+                    && (combatant.attacks_made == 0 || combatant.attacks_made >= attacks)
             }
-
-            Resolution::new(format!(
-                "{} equips {} in {} slot",
-                character.name, item_name, slot
-            ))
-            .with_effect(Effect::ItemEquipped {
-                item_name: item_name.to_string(),
-                slot: slot.to_string(),
-            })
-        } else {
+            ActionCost::BonusAction => combatant.bonus_action_used,
+        };
+        spent.then(|| {
             Resolution::new(format!(
-                "{} doesn't have {} in their inventory",
-                character.name, item_name
+                "{} has already used their {} this turn.",
+                combatant.name,
+                cost.name()
             ))
-        }
+        })
     }
 
-    fn resolve_unequip_item(&self, world: &GameWorld, slot: &str) -> Resolution {
-        let character = &world.player_character;
+    /// The effect spending `cost` from a combatant's turn, if they're in combat.
+    fn action_used(
+        &self,
+        world: &GameWorld,
+        combatant_id: CharacterId,
+        cost: ActionCost,
+    ) -> Option<Effect> {
+        world
+            .combat
+            .as_ref()?
+            .combatant(combatant_id)
+            .map(|_| Effect::ActionUsed { combatant_id, cost })
+    }
 
-        let item_name = match slot.to_lowercase().as_str() {
-            "armor" => character.equipment.armor.as_ref().map(|a| a.base.name.clone()),
-            "shield" => character.equipment.shield.as_ref().map(|s| s.name.clone()),
-            "main_hand" | "weapon" => character.equipment.main_hand.as_ref().map(|w| w.base.name.clone()),
-            "off_hand" => character.equipment.off_hand.as_ref().map(|i| i.name.clone()),
-            _ => {
-                return Resolution::new(format!(
-                    "Unknown equipment slot: {slot}. Valid slots: armor, shield, main_hand, off_hand"
-                ));
-            }
-        };
-
-        if let Some(name) = item_name {
-            Resolution::new(format!("{} unequips {}", character.name, name))
-                .with_effect(Effect::ItemUnequipped {
-                    item_name: name,
-                    slot: slot.to_string(),
-                })
-        } else {
-            Resolution::new(format!("Nothing equipped in {slot} slot"))
+    /// Resolve an intent that spends part of the combatant's turn. Refused
+    /// intents (those without effects) cost nothing.
+    fn with_action_cost(
+        &self,
+        world: &GameWorld,
+        combatant_id: CharacterId,
+        cost: ActionCost,
+        resolve: impl FnOnce() -> Resolution,
+    ) -> Resolution {
+        if let Some(refusal) = self.action_unavailable(world, combatant_id, cost) {
+            return refusal;
+        }
+        let resolution = resolve();
+        if resolution.effects.is_empty() {
+            return resolution;
         }
+        let spent = self.action_used(world, combatant_id, cost);
+        resolution.with_effects(spent)
     }
 
-    fn resolve_use_item(
+    fn resolve_disengage(
         &self,
         world: &GameWorld,
-        item_name: &str,
-        _target_id: Option<CharacterId>,
+        combatant_id: CharacterId,
+        cost: ActionCost,
     ) -> Resolution {
-        let character = &world.player_character;
-
-        // Unconscious characters cannot use items themselves
-        if character.has_condition(Condition::Unconscious) {
+        let name = creature_name(world, combatant_id);
+        if world.combat.is_none() {
+            return Resolution::new(format!("{name} can only disengage in combat."));
+        }
+        // Rogues (Cunning Action) and monks (Step of the Wind) disengage as a
+        // bonus action; untracked creatures are trusted to have a way to
+        let quick = world.character(combatant_id).is_none_or(|c| {
+            c.features
+                .iter()
+                .any(|f| f.name == "Cunning Action" || f.name == "Monk's Focus")
+        });
+        if cost != ActionCost::Action && !quick {
             return Resolution::new(format!(
-                "{} is unconscious and cannot use items!",
-                character.name
+                "{name} can't disengage as a bonus action; it takes their action."
             ));
         }
-
-        if let Some(item) = character.inventory.find_item(item_name) {
-            // Check if it's a consumable type
-            match item.item_type {
-                ItemType::Potion => {
-                    // Look up proper healing amount from database, fall back to basic potion
-                    let (dice_expr, bonus) = if let Some(potion) = crate::items::get_potion(item_name) {
-                        match potion.effect {
-                            crate::world::ConsumableEffect::Healing { ref dice, bonus } => {
-                                (dice.clone(), bonus)
-                            }
-                            _ => ("2d4".to_string(), 2),
-                        }
-                    } else {
-                        ("2d4".to_string(), 2) // Default healing potion
-                    };
-
-                    let heal_expr = if bonus != 0 {
-                        format!("{dice_expr}+{bonus}")
-                    } else {
-                        dice_expr
-                    };
-                    let heal_roll = roll_with_fallback(&heal_expr, "1d4");
-
-                    Resolution::new(format!(
-                        "{} drinks {} and heals for {} HP",
-                        character.name, item_name, heal_roll.total
-                    ))
-                    .with_effect(Effect::ItemUsed {
-                        item_name: item_name.to_string(),
-                        result: format!("Healed {} HP", heal_roll.total),
-                    })
-                    .with_effect(Effect::HpChanged {
-                        target_id: character.id,
-                        amount: heal_roll.total,
-                        new_current: (character.hit_points.current + heal_roll.total)
-                            .min(character.hit_points.maximum),
-                        new_max: character.hit_points.maximum,
-                        dropped_to_zero: false,
-                    })
-                    .with_effect(Effect::ItemRemoved {
-                        item_name: item_name.to_string(),
-                        quantity: 1,
-                        remaining: item.quantity.saturating_sub(1),
-                    })
-                }
-                ItemType::Scroll => {
-                    Resolution::new(format!(
-                        "{} reads {} and it crumbles to dust",
-                        character.name, item_name
-                    ))
-                    .with_effect(Effect::ItemUsed {
-                        item_name: item_name.to_string(),
-                        result: "Scroll consumed".to_string(),
-                    })
-                    .with_effect(Effect::ItemRemoved {
-                        item_name: item_name.to_string(),
-                        quantity: 1,
-                        remaining: item.quantity.saturating_sub(1),
-                    })
-                }
-                _ => Resolution::new(format!(
-                    "{item_name} is not a consumable item"
-                )),
-            }
-        } else {
-            Resolution::new(format!(
-                "{} doesn't have {} in their inventory",
-                character.name, item_name
-            ))
-        }
-    }
-
-    fn resolve_adjust_gold(&self, world: &GameWorld, amount: f32, reason: &str) -> Resolution {
-        let character = &world.player_character;
-        let new_total = character.inventory.gold + amount;
-
-        if new_total < 0.0 {
-            Resolution::new(format!(
-                "{} doesn't have enough gold (has {:.0} gp, needs {:.0} gp)",
-                character.name, character.inventory.gold, -amount
-            ))
-        } else {
-            let action = if amount >= 0.0 { "gains" } else { "spends" };
-            Resolution::new(format!(
-                "{} {} {:.0} gp {} (now has {:.0} gp)",
-                character.name, action, amount.abs(), reason, new_total
-            ))
-            .with_effect(Effect::GoldChanged {
-                amount,
-                new_total,
-                reason: reason.to_string(),
-            })
-        }
+        Resolution::new(format!(
+            "{name} disengages; their movement doesn't provoke opportunity attacks this turn."
+        ))
+        .with_effect(Effect::Disengaged { id: combatant_id })
     }
 
-    /// Resolve a death saving throw (D&D 5e rules)
-    /// - Roll d20 (no modifiers by default)
-    /// - 10+ = success, <10 = failure
-    /// - Natural 20 = regain 1 HP and become conscious
-    /// - Natural 1 = counts as 2 failures
-    /// - 3 successes = stable
-    /// - 3 failures = death
-    fn resolve_death_save(&self, world: &GameWorld, character_id: CharacterId) -> Resolution {
-        let character = &world.player_character;
-
-        // Must be at 0 HP to make death saves
-        if character.hit_points.current > 0 {
+    fn resolve_ready_reaction(
+        &self,
+        world: &GameWorld,
+        reactor_id: CharacterId,
+        reaction: Reaction,
+    ) -> Resolution {
+        let name = creature_name(world, reactor_id);
+        let Some(reactor) = world.combat.as_ref().and_then(|c| c.combatant(reactor_id)) else {
+            return Resolution::new(format!("{name} can only react in combat."));
+        };
+        if reactor.reaction_used {
             return Resolution::new(format!(
-                "{} is not dying and doesn't need to make a death save.",
-                character.name
+                "{name} has already used their reaction this round."
             ));
         }
-
-        // Roll d20
-        let roll = dice::roll("1d20").unwrap();
-        let roll_value = roll.total;
-
-        // Check for natural 20 - regain 1 HP
-        if roll.is_critical() {
+        if reaction == Reaction::OpportunityAttack {
             return Resolution::new(format!(
-                "{} rolls a NATURAL 20 on their death save! They regain 1 HP and become conscious!",
-                character.name
-            ))
-            .with_effect(Effect::DeathSavesReset { target_id: character_id })
-            .with_effect(Effect::HpChanged {
-                target_id: character_id,
-                amount: 1,
-                new_current: 1,
-                new_max: character.hit_points.maximum,
-                dropped_to_zero: false,
-            })
-            .with_effect(Effect::ConditionRemoved {
-                target_id: character_id,
-                condition: Condition::Unconscious,
-            });
+                "{name} needs no readying for an opportunity attack; leaving their reach provokes one."
+            ));
         }
 
-        // Check for natural 1 - counts as 2 failures
-        if roll.is_fumble() {
-            let new_failures = character.death_saves.failures + 2;
-            if new_failures >= 3 {
-                return Resolution::new(format!(
-                    "{} rolls a NATURAL 1 on their death save! Two failures! {} has died!",
-                    character.name, character.name
-                ))
-                .with_effect(Effect::DeathSaveFailure {
-                    target_id: character_id,
-                    failures: 2,
-                    total_failures: new_failures.min(3),
-                    source: "Natural 1 on death save".to_string(),
-                })
-                .with_effect(Effect::CharacterDied {
-                    target_id: character_id,
-                    cause: "Failed death saves".to_string(),
-                });
-            } else {
+        // Tracked casters need the slot the reaction will spend
+        if let (Some(character), Some(level)) = (world.character(reactor_id), reaction.slot_level())
+        {
+            let available = character
+                .spellcasting
+                .as_ref()
+                .and_then(|sc| sc.spell_slots.slots.get(level.saturating_sub(1) as usize))
+                .map_or(0, |slot| slot.available());
+            if level == 0 || available == 0 {
                 return Resolution::new(format!(
-                    "{} rolls a NATURAL 1 on their death save! That counts as TWO failures! ({}/3)",
-                    character.name, new_failures
-                ))
-                .with_effect(Effect::DeathSaveFailure {
-                    target_id: character_id,
-                    failures: 2,
-                    total_failures: new_failures,
-                    source: "Natural 1 on death save".to_string(),
-                });
+                    "{name} has no level {level} spell slot for {}.",
+                    reaction.name()
+                ));
             }
         }
 
-        // Normal roll - 10+ is success, <10 is failure
-        if roll_value >= 10 {
-            let new_successes = character.death_saves.successes + 1;
-            if new_successes >= 3 {
-                Resolution::new(format!(
-                    "{} rolls {} on their death save - SUCCESS! With 3 successes, {} is now STABLE!",
-                    character.name, roll_value, character.name
-                ))
-                .with_effect(Effect::DeathSaveSuccess {
-                    target_id: character_id,
-                    roll: roll_value,
-                    total_successes: 3,
-                })
-                .with_effect(Effect::Stabilized { target_id: character_id })
-            } else {
-                Resolution::new(format!(
-                    "{} rolls {} on their death save - SUCCESS! ({}/3 successes)",
-                    character.name, roll_value, new_successes
-                ))
-                .with_effect(Effect::DeathSaveSuccess {
-                    target_id: character_id,
-                    roll: roll_value,
-                    total_successes: new_successes,
-                })
-            }
-        } else {
-            let new_failures = character.death_saves.failures + 1;
-            if new_failures >= 3 {
-                Resolution::new(format!(
-                    "{} rolls {} on their death save - FAILURE! With 3 failures, {} has DIED!",
-                    character.name, roll_value, character.name
-                ))
-                .with_effect(Effect::DeathSaveFailure {
-                    target_id: character_id,
-                    failures: 1,
-                    total_failures: 3,
-                    source: "Death save".to_string(),
-                })
-                .with_effect(Effect::CharacterDied {
-                    target_id: character_id,
-                    cause: "Failed death saves".to_string(),
-                })
-            } else {
-                Resolution::new(format!(
-                    "{} rolls {} on their death save - FAILURE! ({}/3 failures)",
-                    character.name, roll_value, new_failures
-                ))
-                .with_effect(Effect::DeathSaveFailure {
-                    target_id: character_id,
-                    failures: 1,
-                    total_failures: new_failures,
-                    source: "Death save".to_string(),
-                })
+        Resolution::new(format!("{name} readies {}.", reaction.name())).with_effect(
+            Effect::ReactionReadied {
+                reactor_id,
+                reaction,
+            },
+        )
+    }
+
+    /// A readied reaction from someone other than `actor_id`, if it's still available.
+    fn readied_reaction<'w>(
+        &self,
+        world: &'w GameWorld,
+        actor_id: CharacterId,
+        matches: impl Fn(&Combatant, Reaction) -> bool,
+    ) -> Option<(&'w Combatant, Reaction)> {
+        let combat = world.combat.as_ref()?;
+        combat.combatants.iter().find_map(|c| {
+            let reaction = c.readied_reaction?;
+            (c.id != actor_id && !c.reaction_used && matches(c, reaction)).then_some((c, reaction))
+        })
+    }
+
+    /// Effects for spending a reaction, including the reactor's slot when tracked.
+    fn reaction_used_effects(
+        &self,
+        world: &GameWorld,
+        reactor: &Combatant,
+        reaction: Reaction,
+    ) -> Vec<Effect> {
+        let mut effects = vec![Effect::ReactionUsed {
+            reactor_id: reactor.id,
+            reaction,
+        }];
+        if world.character(reactor.id).is_some() {
+            if let Some(level) = reaction.slot_level() {
+                effects.push(Effect::SpellSlotUsed {
+                    caster_id: reactor.id,
+                    level,
+                    remaining: 0,
+                });
             }
         }
+        effects
     }
 
-    /// Resolve a concentration check (D&D 5e rules)
-    /// - CON saving throw
-    /// - DC = max(10, damage / 2)
-    fn resolve_concentration_check(
+    /// Let a hostile Counterspell try to stop a spell. Returns the reaction's
+    /// resolution and whether the spell was countered.
+    fn resolve_counterspell(
         &self,
         world: &GameWorld,
-        character_id: CharacterId,
-        damage_taken: i32,
+        caster_id: CharacterId,
         spell_name: &str,
-    ) -> Resolution {
-        let character = &world.player_character;
+        spell_level: u8,
+    ) -> Option<(Resolution, bool)> {
+        let caster_is_ally = world
+            .combat
+            .as_ref()
+            .and_then(|c| c.combatant(caster_id))
+            .is_none_or(|c| c.is_ally);
+        let (reactor, reaction) = self.readied_reaction(world, caster_id, |c, r| {
+            c.is_ally != caster_is_ally && matches!(r, Reaction::Counterspell { .. })
+        })?;
+        let Reaction::Counterspell { slot_level } = reaction else {
+            return None;
+        };
 
-        // Calculate DC: max(10, damage / 2)
-        let dc = (damage_taken / 2).max(10);
-
-        // Get CON modifier
-        let con_mod = character.ability_scores.modifier(Ability::Constitution);
-        let proficiency = character.proficiency_bonus();
-
-        // Check if proficient in CON saves (some classes like Sorcerer, Wizard with War Caster)
-        // For now, assume base CON save without proficiency unless they have the save proficiency
-        let save_mod = if character
-            .saving_throw_proficiencies
-            .contains(&Ability::Constitution)
-        {
-            con_mod + proficiency
-        } else {
-            con_mod
-        };
+        let mut resolution = Resolution::new(format!(
+            "{} casts Counterspell at level {slot_level}",
+            reactor.name
+        ))
+        .with_effects(self.reaction_used_effects(world, reactor, reaction));
 
-        // Roll the save
-        let roll = dice::roll(&format!("1d20+{save_mod}")).unwrap();
-        let roll_total = roll.total;
+        if slot_level >= spell_level {
+            resolution
+                .narrative
+                .push_str(&format!(" - {spell_name} is countered!"));
+            return Some((resolution, true));
+        }
 
-        if roll_total >= dc {
-            Resolution::new(format!(
-                "{} makes a DC {} Constitution save to maintain concentration on {}. Rolls {} - SUCCESS! Concentration maintained.",
-                character.name, dc, spell_name, roll_total
-            ))
-            .with_effect(Effect::ConcentrationMaintained {
-                character_id,
-                spell_name: spell_name.to_string(),
-                roll: roll_total,
-                dc,
+        // Higher-level spells need a spellcasting ability check
+        let dc = 10 + spell_level as i32;
+        let modifier = world
+            .character(reactor.id)
+            .and_then(|c| {
+                c.spellcasting
+                    .as_ref()
+                    .map(|sc| c.effective_scores().modifier(sc.ability))
             })
+            .unwrap_or(0);
+        let roll = DiceExpression::parse(&format!("1d20{modifier:+}"))
+            .unwrap()
+            .roll();
+        let countered = roll.total >= dc;
+        resolution.narrative.push_str(&format!(
+            " (check: {} vs DC {dc}) - {}",
+            roll.total,
+            if countered {
+                format!("{spell_name} is countered!")
+            } else {
+                format!("{spell_name} pushes through!")
+            }
+        ));
+        resolution = resolution.with_effect(Effect::DiceRolled {
+            roll: roll.clone(),
+            purpose: "Counterspell check".to_string(),
+        });
+        resolution = resolution.with_effect(if countered {
+            Effect::CheckSucceeded {
+                check_type: "Counterspell".to_string(),
+                roll: roll.total,
+                dc,
+            }
         } else {
-            Resolution::new(format!(
-                "{} makes a DC {} Constitution save to maintain concentration on {}. Rolls {} - FAILED! Concentration is broken!",
-                character.name, dc, spell_name, roll_total
-            ))
-            .with_effect(Effect::ConcentrationBroken {
-                character_id,
-                spell_name: spell_name.to_string(),
-                damage_taken,
-                roll: roll_total,
+            Effect::CheckFailed {
+                check_type: "Counterspell".to_string(),
+                roll: roll.total,
                 dc,
-            })
-        }
+            }
+        });
+        Some((resolution, countered))
     }
 
-    fn resolve_change_location(
+    /// A readied Shield on the target, if it turns this hit into a miss.
+    fn shield_reaction(
         &self,
         world: &GameWorld,
-        new_location: &str,
-        _location_type: Option<String>,
-        _description: Option<String>,
+        target_id: CharacterId,
+        attack_total: i32,
+        target_ac: u8,
+        is_critical: bool,
+    ) -> Option<Resolution> {
+        if is_critical || attack_total >= target_ac as i32 + 5 {
+            return None;
+        }
+        let target = world.combat.as_ref()?.combatant(target_id)?;
+        if target.reaction_used || target.readied_reaction != Some(Reaction::Shield) {
+            return None;
+        }
+        Some(
+            Resolution::new(format!(
+                "{} raises a Shield (AC {}) - miss!",
+                target.name,
+                target_ac + 5
+            ))
+            .with_effects(self.reaction_used_effects(world, target, Reaction::Shield)),
+        )
+    }
+
+    fn resolve_roll_initiative(
+        &self,
+        character_id: CharacterId,
+        name: &str,
+        modifier: i8,
+        _is_player: bool,
     ) -> Resolution {
-        let previous_location = world.current_location.name.clone();
+        let roll = dice::roll("1d20").unwrap();
+        let total = roll.total + modifier as i32;
 
         Resolution::new(format!(
-            "You travel from {previous_location} to {new_location}."
+            "{} rolls initiative: {} + {} = {}",
+            name, roll.total, modifier, total
         ))
-        .with_effect(Effect::LocationChanged {
-            previous_location,
-            new_location: new_location.to_string(),
+        .with_effect(Effect::DiceRolled {
+            roll: roll.clone(),
+            purpose: "Initiative".to_string(),
+        })
+        .with_effect(Effect::InitiativeRolled {
+            character_id,
+            name: name.to_string(),
+            roll: roll.total,
+            total,
         })
     }
 
-    fn resolve_register_consequence(
-        &self,
-        trigger_description: &str,
-        consequence_description: &str,
-        severity: &str,
-        _related_entities: &[String],
-        importance: f32,
-        expires_in_turns: Option<u32>,
-    ) -> Resolution {
-        // Generate a unique ID for this consequence
-        let consequence_id = uuid::Uuid::new_v4().to_string();
+    fn resolve_roll_dice(&self, notation: &str, purpose: &str) -> Resolution {
+        match dice::roll(notation) {
+            Ok(roll) => Resolution::new(format!("Rolling {notation} for {purpose}: {roll}"))
+                .with_effect(Effect::DiceRolled {
+                    roll,
+                    purpose: purpose.to_string(),
+                }),
+            Err(e) => Resolution::new(format!("Failed to roll {notation}: {e}")),
+        }
+    }
 
-        let severity_display = match severity.to_lowercase().as_str() {
-            "minor" => "minor",
-            "moderate" => "moderate",
-            "major" => "major",
-            "critical" => "critical",
-            _ => "moderate",
-        };
+    fn resolve_advance_time(&self, minutes: u32) -> Resolution {
+        let hours = minutes / 60;
+        let mins = minutes % 60;
 
-        let expiry_note = match expires_in_turns {
-            Some(turns) => format!(" (expires in {turns} turns)"),
-            None => String::new(),
+        let time_str = if hours > 0 && mins > 0 {
+            format!("{hours} hours and {mins} minutes")
+        } else if hours > 0 {
+            format!("{hours} hours")
+        } else {
+            format!("{mins} minutes")
         };
 
-        Resolution::new(format!(
-            "Consequence registered: If {trigger_description}, then {consequence_description} ({severity_display} severity, importance {importance:.1}){expiry_note}"
-        ))
-        .with_effect(Effect::ConsequenceRegistered {
-            consequence_id,
-            trigger_description: trigger_description.to_string(),
-            consequence_description: consequence_description.to_string(),
-            severity: severity_display.to_string(),
-        })
+        Resolution::new(format!("{time_str} pass.")).with_effect(Effect::TimeAdvanced { minutes })
     }
 
-    // ========================================================================
-    // Class Feature Resolution Functions
-    // ========================================================================
-
-    fn resolve_use_rage(
+    fn resolve_gain_experience(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
+        character_id: CharacterId,
+        amount: u32,
     ) -> Resolution {
-        let character = &world.player_character;
-
-        // Check if already raging
-        if world.player_character.class_resources.rage_active {
-            return Resolution::new(format!("{} is already raging!", character.name));
-        }
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let new_total = character.experience + amount;
+        let current_level = character.level;
 
-        // Check for rage uses remaining
-        let rage_feature = character.features.iter().find(|f| f.name == "Rage");
-        if let Some(feature) = rage_feature {
-            if let Some(ref uses) = feature.uses {
-                if uses.current == 0 {
-                    return Resolution::new(format!(
-                        "{} has no rage uses remaining! (Recovers on long rest)",
-                        character.name
-                    ));
-                }
-            }
-        }
+        // XP thresholds for levels 1-20
+        let xp_thresholds = [
+            0, 300, 900, 2700, 6500, 14000, 23000, 34000, 48000, 64000, 85000, 100000, 120000,
+            140000, 165000, 195000, 225000, 265000, 305000, 355000,
+        ];
 
-        // Determine rage damage bonus based on level
-        let barbarian_level = character
-            .classes
+        let new_level = xp_thresholds
             .iter()
-            .find(|c| c.class == crate::world::CharacterClass::Barbarian)
-            .map(|c| c.level)
+            .rposition(|&threshold| new_total >= threshold)
+            .map(|idx| (idx + 1) as u8)
             .unwrap_or(1);
 
-        let rage_damage = match barbarian_level {
-            1..=8 => 2,
-            9..=15 => 3,
-            _ => 4,
-        };
+        let mut resolution = Resolution::new(format!(
+            "{} gained {amount} experience points (Total: {new_total})",
+            character.name
+        ));
 
-        Resolution::new(format!(
-            "{} enters a RAGE! Gains: advantage on STR checks/saves, +{} rage damage to melee attacks, resistance to bludgeoning/piercing/slashing damage. Cannot cast spells or concentrate while raging.",
-            character.name, rage_damage
-        ))
-        .with_effect(Effect::RageStarted {
-            character_id: world.player_character.id,
-            damage_bonus: rage_damage,
-        })
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Rage".to_string(),
-            description: format!("Entered rage (1 minute, +{rage_damage} damage)"),
-        })
-        .with_effect(Effect::FeatureUsed {
-            feature_name: "Rage".to_string(),
-            uses_remaining: 0,
-        })
+        resolution = resolution.with_effect(Effect::ExperienceGained {
+            character_id,
+            amount,
+            new_total,
+        });
+
+        if new_level > current_level {
+            resolution = resolution.with_effect(Effect::LevelUp {
+                character_id,
+                new_level,
+            });
+        }
+
+        resolution
     }
 
-    fn resolve_end_rage(
+    fn resolve_add_quest(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        reason: &str,
+        name: String,
+        description: String,
+        objectives: Vec<String>,
+        giver: Option<String>,
+        xp_reward: u32,
     ) -> Resolution {
-        let character = &world.player_character;
-
-        if !world.player_character.class_resources.rage_active {
-            return Resolution::new(format!("{} is not currently raging.", character.name));
+        if world.quest(&name).is_some() {
+            return Resolution::new(format!("{name} is already in the quest log."));
         }
 
-        let reason_text = match reason {
-            "duration_expired" => "Rage ended (1 minute duration expired).",
-            "unconscious" => "Rage ended (knocked unconscious).",
-            "no_combat_action" => "Rage ended (turn ended without attacking or taking damage).",
-            "voluntary" => "Rage ended voluntarily.",
-            _ => "Rage ended.",
-        };
+        let mut quest = objectives
+            .into_iter()
+            .fold(Quest::new(name, description), Quest::with_objective);
+        quest.giver = giver;
+        quest.xp_reward = xp_reward;
 
-        Resolution::new(format!("{}'s rage ends. {}", character.name, reason_text))
-            .with_effect(Effect::RageEnded {
-                character_id: world.player_character.id,
-                reason: reason_text.to_string(),
-            })
-            .with_effect(Effect::ClassResourceUsed {
-                character_name: character.name.clone(),
-                resource_name: "Rage".to_string(),
-                description: reason_text.to_string(),
-            })
+        let mut narrative = format!("New quest: {}", quest.name);
+        if let Some(giver) = &quest.giver {
+            narrative.push_str(&format!(" (from {giver})"));
+        }
+        if !quest.objectives.is_empty() {
+            let objectives: Vec<_> = quest
+                .objectives
+                .iter()
+                .map(|o| o.description.as_str())
+                .collect();
+            narrative.push_str(&format!(" - objectives: {}", objectives.join("; ")));
+        }
+        Resolution::new(narrative).with_effect(Effect::QuestAdded { quest })
     }
 
-    fn resolve_use_ki(
+    fn resolve_update_quest_status(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        points: u8,
-        ability: &str,
+        quest_name: &str,
+        status: QuestStatus,
     ) -> Resolution {
-        let character = &world.player_character;
-        let resources = &world.player_character.class_resources;
-
-        if resources.ki_points < points {
+        let Some(quest) = world.quest(quest_name) else {
+            return Resolution::new(format!("No quest named {quest_name} is in the quest log."));
+        };
+        if quest.status == status {
+            return Resolution::new(format!("{} is already {}.", quest.name, status.name()));
+        }
+        // Its reward is paid, so a completed quest stays completed
+        if quest.status == QuestStatus::Completed {
             return Resolution::new(format!(
-                "{} doesn't have enough ki points! Has {} but needs {}.",
-                character.name, resources.ki_points, points
+                "{} is completed and can't be reopened.",
+                quest.name
             ));
         }
 
-        let ability_description = match ability {
-            "flurry_of_blows" => "Flurry of Blows: Make two unarmed strikes as a bonus action.",
-            "patient_defense" => "Patient Defense: Take the Dodge action as a bonus action.",
-            "step_of_the_wind" => "Step of the Wind: Disengage or Dash as a bonus action, jump distance doubled.",
-            "stunning_strike" => "Stunning Strike: Target must make a CON save or be Stunned until the end of your next turn.",
-            _ => ability,
-        };
+        let mut resolution = Resolution::new(format!("Quest {}: {}", status.name(), quest.name))
+            .with_effect(Effect::QuestStatusChanged {
+                quest_id: quest.id,
+                quest_name: quest.name.clone(),
+                status,
+            });
 
-        Resolution::new(format!(
-            "{} spends {} ki point{}. {}",
-            character.name,
-            points,
-            if points == 1 { "" } else { "s" },
-            ability_description
-        ))
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Ki Points".to_string(),
-            description: format!("Spent {points} ki for {ability}"),
-        })
+        // Completing a quest pays out its experience to everyone in the party
+        if status == QuestStatus::Completed && quest.xp_reward > 0 {
+            for character in world.characters() {
+                let reward = self.resolve_gain_experience(world, character.id, quest.xp_reward);
+                resolution.narrative = format!("{}. {}", resolution.narrative, reward.narrative);
+                resolution = resolution.with_effects(reward.effects);
+            }
+        }
+        resolution
     }
 
-    fn resolve_use_lay_on_hands(
+    fn resolve_complete_objective(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        target_name: &str,
-        hp_amount: u32,
-        cure_disease: bool,
-        neutralize_poison: bool,
+        quest_name: &str,
+        objective: &str,
     ) -> Resolution {
-        let character = &world.player_character;
-        let pool = world.player_character.class_resources.lay_on_hands_pool;
-
-        let total_cost = hp_amount
-            + if cure_disease { 5 } else { 0 }
-            + if neutralize_poison { 5 } else { 0 };
-
-        if pool < total_cost {
+        let Some(quest) = world.quest(quest_name) else {
+            return Resolution::new(format!("No quest named {quest_name} is in the quest log."));
+        };
+        let Some(index) = quest.objective_index(objective) else {
+            let objectives: Vec<_> = quest
+                .objectives
+                .iter()
+                .map(|o| o.description.as_str())
+                .collect();
             return Resolution::new(format!(
-                "{} doesn't have enough in their Lay on Hands pool! Has {} HP but needs {}.",
-                character.name, pool, total_cost
+                "{} has no objective matching '{}' (objectives: {}).",
+                quest.name,
+                objective,
+                objectives.join("; ")
+            ));
+        };
+        let target = &quest.objectives[index];
+        if target.completed {
+            return Resolution::new(format!(
+                "{} is already done for {}.",
+                target.description, quest.name
             ));
         }
 
-        let mut effects_text = Vec::new();
-        if hp_amount > 0 {
-            effects_text.push(format!("restores {hp_amount} HP"));
-        }
-        if cure_disease {
-            effects_text.push("cures one disease".to_string());
-        }
-        if neutralize_poison {
-            effects_text.push("neutralizes one poison".to_string());
-        }
+        let remaining = quest
+            .objectives
+            .iter()
+            .enumerate()
+            .filter(|(i, o)| *i != index && !o.completed && !o.optional)
+            .count();
+        let progress = if remaining == 0 {
+            " All required objectives are done.".to_string()
+        } else {
+            format!(" {remaining} objective(s) remain.")
+        };
 
         Resolution::new(format!(
-            "{} uses Lay on Hands on {}: {}. ({} HP remaining in pool)",
-            character.name,
-            target_name,
-            effects_text.join(", "),
-            pool - total_cost
+            "Objective complete for {}: {}.{}",
+            quest.name, target.description, progress
         ))
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Lay on Hands".to_string(),
-            description: format!("Used {total_cost} points on {target_name}"),
+        .with_effect(Effect::ObjectiveCompleted {
+            quest_id: quest.id,
+            quest_name: quest.name.clone(),
+            objective_index: index,
+            objective: target.description.clone(),
         })
     }
 
-    fn resolve_use_divine_smite(
+    fn resolve_take_feat(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        spell_slot_level: u8,
-        target_is_undead_or_fiend: bool,
+        character_id: CharacterId,
+        feat_name: &str,
+        ability: Option<Ability>,
     ) -> Resolution {
-        let character = &world.player_character;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        // Check if they have spell slots available
-        if let Some(ref spellcasting) = character.spellcasting {
-            let slot_idx = spell_slot_level.saturating_sub(1) as usize;
-            if slot_idx < 9 {
-                let slot = &spellcasting.spell_slots.slots[slot_idx];
-                if slot.available() == 0 {
-                    return Resolution::new(format!(
-                        "{} has no level {} spell slots remaining!",
-                        character.name, spell_slot_level
-                    ));
-                }
-            }
+        if character.pending_asi == 0 {
+            return Resolution::new(format!(
+                "{} has no Ability Score Improvement to spend on a feat",
+                character.name
+            ));
+        }
+        let Some(feat) = get_feat(feat_name) else {
+            return Resolution::new(FeatError::UnknownFeat(feat_name.to_string()).to_string());
+        };
+        if let Err(e) = feat.check(character, ability) {
+            return Resolution::new(format!("{} can't take {}: {e}", character.name, feat.name));
         }
 
-        // Calculate damage dice
-        // Base: 2d8, +1d8 per slot level above 1st, max 5d8
-        // Extra 1d8 vs undead/fiends
-        let base_dice = 2 + (spell_slot_level.saturating_sub(1)).min(3);
-        let total_dice = if target_is_undead_or_fiend {
-            (base_dice + 1).min(6)
-        } else {
-            base_dice.min(5)
+        Resolution::new(format!("{} gains the {} feat", character.name, feat.name)).with_effect(
+            Effect::FeatTaken {
+                character_id,
+                feat_name: feat.name.to_string(),
+                ability,
+            },
+        )
+    }
+
+    fn resolve_use_feature(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        feature_name: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
         };
 
-        let damage_roll = roll_with_fallback(&format!("{total_dice}d8"), "2d8");
+        if let Some(feature) = character.features.iter().find(|f| f.name == feature_name) {
+            if let Some(ref uses) = feature.uses {
+                if uses.current > 0 {
+                    Resolution::new(format!(
+                        "{} uses {} ({} uses remaining)",
+                        character.name,
+                        feature_name,
+                        uses.current - 1
+                    ))
+                    .with_effect(Effect::FeatureUsed {
+                        character_id,
+                        feature_name: feature_name.to_string(),
+                        uses_remaining: uses.current - 1,
+                    })
+                } else {
+                    Resolution::new(format!(
+                        "{} has no uses of {} remaining",
+                        character.name, feature_name
+                    ))
+                }
+            } else {
+                Resolution::new(format!("{} uses {}", character.name, feature_name))
+            }
+        } else {
+            Resolution::new(format!(
+                "{} does not have the feature {}",
+                character.name, feature_name
+            ))
+        }
+    }
 
-        let extra_text = if target_is_undead_or_fiend {
-            " (extra damage vs undead/fiend)"
+    fn resolve_remember_fact(
+        &self,
+        subject_name: &str,
+        subject_type: &str,
+        fact: &str,
+        category: &str,
+        related_entities: &[String],
+        importance: f32,
+    ) -> Resolution {
+        // The actual storage is handled by the DM agent, not the rules engine.
+        // We return a confirmation message and an effect that signals what to store.
+        let related_str = if related_entities.is_empty() {
+            String::new()
         } else {
-            ""
+            format!(" (related: {})", related_entities.join(", "))
         };
 
         Resolution::new(format!(
-            "{} channels divine power into their strike! Divine Smite deals {}d8 = {} radiant damage{}. (Level {} slot expended)",
-            character.name, total_dice, damage_roll.total, extra_text, spell_slot_level
+            "Noted: {subject_name} ({subject_type}) - {fact}{related_str}"
         ))
-        .with_effect(Effect::DiceRolled {
-            roll: damage_roll,
-            purpose: "Divine Smite damage".to_string(),
-        })
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Divine Smite".to_string(),
-            description: format!("Used level {spell_slot_level} slot for smite"),
+        .with_effect(Effect::FactRemembered {
+            subject_name: subject_name.to_string(),
+            subject_type: subject_type.to_string(),
+            fact: fact.to_string(),
+            category: category.to_string(),
+            related_entities: related_entities.to_vec(),
+            importance,
         })
     }
 
-    fn resolve_use_wild_shape(
+    // Inventory resolution methods
+
+    #[allow(clippy::too_many_arguments)]
+    fn resolve_add_item(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        beast_form: &str,
-        beast_hp: i32,
-        _beast_ac: Option<u8>,
+        character_id: CharacterId,
+        item_name: &str,
+        quantity: u32,
+        item_type: Option<&str>,
+        description: Option<&str>,
+        magical: bool,
+        weight: Option<f32>,
+        value_gp: Option<f32>,
     ) -> Resolution {
-        let character = &world.player_character;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        // Check if already in Wild Shape
-        if world.player_character.class_resources.wild_shape_form.is_some() {
-            return Resolution::new(format!(
-                "{} is already in Wild Shape form!",
-                character.name
-            ));
-        }
+        // Check if item already exists
+        let existing_qty = character
+            .inventory
+            .find_item(item_name)
+            .map(|i| i.quantity)
+            .unwrap_or(0);
+        let new_total = existing_qty + quantity;
 
-        // Find Wild Shape feature uses
-        let wild_shape_feature = character.features.iter().find(|f| f.name == "Wild Shape");
-        if let Some(feature) = wild_shape_feature {
-            if let Some(ref uses) = feature.uses {
-                if uses.current == 0 {
-                    return Resolution::new(format!(
-                        "{} has no Wild Shape uses remaining! (Recovers on short/long rest)",
-                        character.name
-                    ));
-                }
-            }
-        }
+        let qty_str = if quantity > 1 {
+            format!("{quantity} x ")
+        } else {
+            String::new()
+        };
 
-        // Calculate duration based on Druid level
-        let druid_level = character
-            .classes
-            .iter()
-            .find(|c| c.class == crate::world::CharacterClass::Druid)
-            .map(|c| c.level)
-            .unwrap_or(2);
-        let duration_hours = druid_level / 2;
+        // Warn, but don't block, when the pickup makes the character slower
+        let unit_weight = world
+            .custom_items
+            .find_item(item_name)
+            .map(|item| item.as_item().weight)
+            .or(weight)
+            .unwrap_or(0.0);
+        let new_weight = character.carried_weight() + unit_weight * quantity as f32;
+        let before = character.encumbrance_level();
+        let after = Encumbrance::for_load(new_weight, character.effective_score(Ability::Strength));
+        let warning = if after > before {
+            format!(
+                " {} is now {} ({:.0}/{:.0} lb).",
+                character.name,
+                after,
+                new_weight,
+                character.carrying_capacity()
+            )
+        } else {
+            String::new()
+        };
 
         Resolution::new(format!(
-            "{} transforms into a {}! Beast form has {} HP. Duration: {} hour{}. Mental stats, proficiencies, and features retained. Cannot cast spells but can maintain concentration.",
-            character.name, beast_form, beast_hp, duration_hours,
-            if duration_hours == 1 { "" } else { "s" }
+            "{} receives {}{} (now has {} total).{}",
+            character.name, qty_str, item_name, new_total, warning
         ))
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Wild Shape".to_string(),
-            description: format!("Transformed into {beast_form} ({beast_hp} HP)"),
-        })
-        .with_effect(Effect::FeatureUsed {
-            feature_name: "Wild Shape".to_string(),
-            uses_remaining: 0,
+        .with_effect(Effect::ItemAdded {
+            character_id,
+            item_name: item_name.to_string(),
+            quantity,
+            new_total,
+            item_type: item_type.map(parse_item_type).unwrap_or_default(),
+            description: description.map(str::to_string),
+            magical,
+            weight: weight.unwrap_or(0.0),
+            value_gp: value_gp.unwrap_or(0.0),
         })
     }
 
-    fn resolve_end_wild_shape(
+    fn resolve_remove_item(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        reason: &str,
-        excess_damage: i32,
+        character_id: CharacterId,
+        item_name: &str,
+        quantity: u32,
     ) -> Resolution {
-        let character = &world.player_character;
-
-        if world.player_character.class_resources.wild_shape_form.is_none() {
-            return Resolution::new(format!(
-                "{} is not currently in Wild Shape form.",
-                character.name
-            ));
-        }
-
-        let reason_text = match reason {
-            "duration_expired" => "Wild Shape ended (duration expired).",
-            "hp_zero" => {
-                if excess_damage > 0 {
-                    &format!(
-                        "Wild Shape ended (beast HP dropped to 0). {} excess damage carries over to normal form!",
-                        excess_damage
-                    )
-                } else {
-                    "Wild Shape ended (beast HP dropped to 0)."
-                }
-            }
-            "voluntary" => "Wild Shape ended voluntarily as a bonus action.",
-            "incapacitated" => "Wild Shape ended (druid became incapacitated).",
-            _ => "Wild Shape ended.",
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
         };
 
-        let mut resolution = Resolution::new(format!(
-            "{} reverts to their normal form. {}",
-            character.name, reason_text
-        ))
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Wild Shape".to_string(),
-            description: reason_text.to_string(),
-        });
-
-        // Apply excess damage if any
-        if excess_damage > 0 {
-            resolution = resolution.with_effect(Effect::HpChanged {
-                target_id: world.player_character.id,
-                amount: -excess_damage,
-                new_current: (character.hit_points.current - excess_damage).max(0),
-                new_max: character.hit_points.maximum,
-                dropped_to_zero: character.hit_points.current - excess_damage <= 0,
-            });
+        if let Some(item) = character.inventory.find_item(item_name) {
+            if item.quantity >= quantity {
+                let remaining = item.quantity - quantity;
+                let qty_str = if quantity > 1 {
+                    format!("{quantity} x ")
+                } else {
+                    String::new()
+                };
+                Resolution::new(format!(
+                    "{} loses {}{} ({} remaining)",
+                    character.name, qty_str, item_name, remaining
+                ))
+                .with_effect(Effect::ItemRemoved {
+                    character_id,
+                    item_name: item_name.to_string(),
+                    quantity,
+                    remaining,
+                })
+            } else {
+                Resolution::new(format!(
+                    "{} doesn't have enough {} (has {}, needs {})",
+                    character.name, item_name, item.quantity, quantity
+                ))
+            }
+        } else {
+            Resolution::new(format!(
+                "{} doesn't have any {}",
+                character.name, item_name
+            ))
         }
-
-        resolution
     }
 
-    fn resolve_use_channel_divinity(
+    fn resolve_equip_item(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        option: &str,
-        targets: &[String],
+        character_id: CharacterId,
+        item_name: &str,
     ) -> Resolution {
-        let character = &world.player_character;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        // Check for Channel Divinity uses
-        let cd_feature = character
-            .features
-            .iter()
-            .find(|f| f.name == "Channel Divinity");
-        if let Some(feature) = cd_feature {
-            if let Some(ref uses) = feature.uses {
-                if uses.current == 0 {
+        if let Some(item) = character.inventory.find_item(item_name) {
+            let slot = match item.item_type {
+                ItemType::Weapon => "main_hand",
+                ItemType::Armor => "armor",
+                ItemType::Shield => "shield",
+                _ => {
                     return Resolution::new(format!(
-                        "{} has no Channel Divinity uses remaining! (Recovers on short/long rest)",
-                        character.name
+                        "{item_name} cannot be equipped (not a weapon, armor, or shield)"
                     ));
                 }
-            }
-        }
+            };
 
-        let option_description = match option.to_lowercase().as_str() {
-            "turn undead" => {
-                "Turn Undead: Each undead within 30 feet must make a WIS save. On failure, they must spend their turns moving away and cannot take reactions for 1 minute."
-            }
-            "divine spark" => {
-                "Divine Spark: Either deal 1d8 radiant damage to one creature within 30 feet (DEX save for half), or restore 1d8 HP to one creature within 30 feet."
-            }
-            "sacred weapon" => {
-                "Sacred Weapon: Your weapon becomes magical for 1 minute, +CHA to attack rolls, and sheds bright light."
+            // A two-handed weapon and a shield can't share the character's hands
+            if let Err(conflict) =
+                character
+                    .equipment
+                    .can_equip(slot, item_name, &world.custom_items)
+            {
+                return Resolution::new(conflict.to_string());
             }
-            _ => option,
-        };
-
-        let targets_text = if targets.is_empty() {
-            String::new()
-        } else {
-            format!(" Targets: {}.", targets.join(", "))
-        };
-
-        Resolution::new(format!(
-            "{} uses Channel Divinity: {}.{}",
-            character.name, option_description, targets_text
-        ))
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Channel Divinity".to_string(),
-            description: option.to_string(),
-        })
-        .with_effect(Effect::FeatureUsed {
-            feature_name: "Channel Divinity".to_string(),
-            uses_remaining: 0,
-        })
-    }
 
-    fn resolve_use_bardic_inspiration(
-        &self,
-        world: &GameWorld,
-        _character_id: CharacterId,
-        target_name: &str,
-        die_size: &str,
-    ) -> Resolution {
-        let character = &world.player_character;
+            let equipped = Effect::ItemEquipped {
+                character_id,
+                item_name: item_name.to_string(),
+                slot: slot.to_string(),
+            };
+            let ac_changed =
+                ac_change(world, character, &equipped, format!("Equipped {item_name}"));
 
-        // Check for Bardic Inspiration uses
-        let bi_feature = character
-            .features
-            .iter()
-            .find(|f| f.name == "Bardic Inspiration");
-        if let Some(feature) = bi_feature {
-            if let Some(ref uses) = feature.uses {
-                if uses.current == 0 {
-                    return Resolution::new(format!(
-                        "{} has no Bardic Inspiration uses remaining! (Recovers on long rest, or short rest at level 5+)",
-                        character.name
-                    ));
+            // Check strength requirement for heavy armor
+            if slot == "armor" {
+                if let Some(db_armor) = world.custom_items.get_armor(item_name) {
+                    if let Some(str_req) = db_armor.strength_requirement {
+                        let char_str = character.effective_score(Ability::Strength);
+                        if char_str < str_req {
+                            return Resolution::new(format!(
+                                "{} equips {} but doesn't meet the Strength {} requirement (has {}). Movement speed reduced by 10 feet.{}",
+                                character.name, item_name, str_req, char_str, ac_text(&ac_changed)
+                            ))
+                            .with_effect(equipped)
+                            .with_effects(ac_changed);
+                        }
+                    }
                 }
             }
-        }
 
-        Resolution::new(format!(
-            "{} inspires {} with a rousing performance! {} gains a {} Bardic Inspiration die they can add to one ability check, attack roll, or saving throw within the next 10 minutes.",
-            character.name, target_name, target_name, die_size
-        ))
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Bardic Inspiration".to_string(),
-            description: format!("Inspired {target_name} with a {die_size}"),
-        })
-        .with_effect(Effect::FeatureUsed {
-            feature_name: "Bardic Inspiration".to_string(),
-            uses_remaining: 0,
-        })
+            Resolution::new(format!(
+                "{} equips {} in {} slot{}",
+                character.name,
+                item_name,
+                slot,
+                ac_text(&ac_changed)
+            ))
+            .with_effect(equipped)
+            .with_effects(ac_changed)
+        } else {
+            Resolution::new(format!(
+                "{} doesn't have {} in their inventory",
+                character.name, item_name
+            ))
+        }
     }
 
-    fn resolve_use_action_surge(
+    fn resolve_unequip_item(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        action_taken: &str,
+        character_id: CharacterId,
+        slot: &str,
     ) -> Resolution {
-        let character = &world.player_character;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        if world.player_character.class_resources.action_surge_used {
-            return Resolution::new(format!(
-                "{} has already used Action Surge! (Recovers on short/long rest)",
-                character.name
-            ));
-        }
+        let item_name = match slot.to_lowercase().as_str() {
+            "armor" => character.equipment.armor.as_ref().map(|a| a.base.name.clone()),
+            "shield" => character.equipment.shield.as_ref().map(|s| s.name.clone()),
+            "main_hand" | "weapon" => character.equipment.main_hand.as_ref().map(|w| w.base.name.clone()),
+            "off_hand" => character.equipment.off_hand.as_ref().map(|i| i.name.clone()),
+            _ => {
+                return Resolution::new(format!(
+                    "Unknown equipment slot: {slot}. Valid slots: armor, shield, main_hand, off_hand"
+                ));
+            }
+        };
 
-        Resolution::new(format!(
-            "{} surges with renewed vigor! Takes an additional action this turn: {}",
-            character.name, action_taken
-        ))
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Action Surge".to_string(),
-            description: action_taken.to_string(),
-        })
-        .with_effect(Effect::FeatureUsed {
-            feature_name: "Action Surge".to_string(),
-            uses_remaining: 0,
-        })
+        if let Some(name) = item_name {
+            let unequipped = Effect::ItemUnequipped {
+                character_id,
+                item_name: name.clone(),
+                slot: slot.to_string(),
+            };
+            let ac_changed = ac_change(world, character, &unequipped, format!("Unequipped {name}"));
+            Resolution::new(format!(
+                "{} unequips {}{}",
+                character.name,
+                name,
+                ac_text(&ac_changed)
+            ))
+            .with_effect(unequipped)
+            .with_effects(ac_changed)
+        } else {
+            Resolution::new(format!("Nothing equipped in {slot} slot"))
+        }
     }
 
-    fn resolve_use_second_wind(
+    fn resolve_use_item(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
+        character_id: CharacterId,
+        item_name: &str,
+        _target_id: Option<CharacterId>,
     ) -> Resolution {
-        let character = &world.player_character;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        if world.player_character.class_resources.second_wind_used {
+        // Unconscious characters cannot use items themselves
+        if character.has_condition(Condition::Unconscious) {
             return Resolution::new(format!(
-                "{} has already used Second Wind! (Recovers on short/long rest)",
+                "{} is unconscious and cannot use items!",
                 character.name
             ));
         }
 
-        // Calculate healing: 1d10 + fighter level
-        let fighter_level = character
-            .classes
-            .iter()
-            .find(|c| c.class == crate::world::CharacterClass::Fighter)
-            .map(|c| c.level)
-            .unwrap_or(1);
-
-        let healing_roll = roll_with_fallback(&format!("1d10+{fighter_level}"), "1d10+1");
-        let healing = healing_roll.total;
+        if let Some(item) = character.inventory.find_item(item_name) {
+            // Check if it's a consumable type
+            match item.item_type {
+                ItemType::Potion => {
+                    // Look up proper healing amount from database, fall back to basic potion
+                    let (dice_expr, bonus) = if let Some(potion) = world.custom_items.get_potion(item_name) {
+                        match potion.effect {
+                            crate::world::ConsumableEffect::Healing { ref dice, bonus } => {
+                                (dice.clone(), bonus)
+                            }
+                            _ => ("2d4".to_string(), 2),
+                        }
+                    } else {
+                        ("2d4".to_string(), 2) // Default healing potion
+                    };
 
-        let new_hp = (character.hit_points.current + healing).min(character.hit_points.maximum);
+                    let heal_expr = if bonus != 0 {
+                        format!("{dice_expr}+{bonus}")
+                    } else {
+                        dice_expr
+                    };
+                    let heal_roll = roll_with_fallback(&heal_expr, "1d4");
 
-        Resolution::new(format!(
-            "{} catches their breath with Second Wind! Regains 1d10+{} = {} HP. (Now at {}/{})",
-            character.name, fighter_level, healing, new_hp, character.hit_points.maximum
-        ))
-        .with_effect(Effect::DiceRolled {
-            roll: healing_roll,
-            purpose: "Second Wind healing".to_string(),
-        })
-        .with_effect(Effect::HpChanged {
-            target_id: world.player_character.id,
-            amount: healing,
-            new_current: new_hp,
-            new_max: character.hit_points.maximum,
-            dropped_to_zero: false,
-        })
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Second Wind".to_string(),
-            description: format!("Healed {healing} HP"),
-        })
-        .with_effect(Effect::FeatureUsed {
-            feature_name: "Second Wind".to_string(),
-            uses_remaining: 0,
-        })
+                    Resolution::new(format!(
+                        "{} drinks {} and heals for {} HP",
+                        character.name, item_name, heal_roll.total
+                    ))
+                    .with_effect(Effect::ItemUsed {
+                        item_name: item_name.to_string(),
+                        result: format!("Healed {} HP", heal_roll.total),
+                    })
+                    .with_effect(Effect::HpChanged {
+                        target_id: character.id,
+                        amount: heal_roll.total,
+                        new_current: (character.hit_points.current + heal_roll.total)
+                            .min(character.hit_points.maximum),
+                        new_max: character.hit_points.maximum,
+                        dropped_to_zero: false,
+                    })
+                    .with_effect(Effect::ItemRemoved {
+                        character_id,
+                        item_name: item_name.to_string(),
+                        quantity: 1,
+                        remaining: item.quantity.saturating_sub(1),
+                    })
+                }
+                ItemType::Scroll => {
+                    Resolution::new(format!(
+                        "{} reads {} and it crumbles to dust",
+                        character.name, item_name
+                    ))
+                    .with_effect(Effect::ItemUsed {
+                        item_name: item_name.to_string(),
+                        result: "Scroll consumed".to_string(),
+                    })
+                    .with_effect(Effect::ItemRemoved {
+                        character_id,
+                        item_name: item_name.to_string(),
+                        quantity: 1,
+                        remaining: item.quantity.saturating_sub(1),
+                    })
+                }
+                _ => Resolution::new(format!(
+                    "{item_name} is not a consumable item"
+                )),
+            }
+        } else {
+            Resolution::new(format!(
+                "{} doesn't have {} in their inventory",
+                character.name, item_name
+            ))
+        }
     }
 
-    fn resolve_use_sorcery_points(
+    fn resolve_attune(
         &self,
         world: &GameWorld,
-        _character_id: CharacterId,
-        points: u8,
-        metamagic: &str,
-        spell_name: Option<&str>,
-        slot_level: Option<u8>,
+        character_id: CharacterId,
+        item_name: &str,
     ) -> Resolution {
-        let character = &world.player_character;
-        let resources = &world.player_character.class_resources;
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-        // Handle slot conversion separately
-        if metamagic == "convert_to_slot" {
-            if let Some(level) = slot_level {
-                let cost = level; // Costs spell level points to create a slot
-                if resources.sorcery_points < cost {
-                    return Resolution::new(format!(
-                        "{} doesn't have enough sorcery points! Has {} but needs {} to create a level {} slot.",
-                        character.name, resources.sorcery_points, cost, level
-                    ));
-                }
-                return Resolution::new(format!(
-                    "{} converts {} sorcery points into a level {} spell slot.",
-                    character.name, cost, level
-                ))
-                .with_effect(Effect::ClassResourceUsed {
-                    character_name: character.name.clone(),
-                    resource_name: "Sorcery Points".to_string(),
-                    description: format!("Created level {level} spell slot"),
-                });
-            }
+        let Some(item) = character.find_owned_item(item_name) else {
+            return Resolution::new(format!(
+                "{} doesn't have {} to attune to",
+                character.name, item_name
+            ));
+        };
+        if !item.magical {
+            return Resolution::new(format!(
+                "{} is not magical and can't be attuned to",
+                item.name
+            ));
         }
-
-        if metamagic == "convert_from_slot" {
-            if let Some(level) = slot_level {
-                return Resolution::new(format!(
-                    "{} converts a level {} spell slot into {} sorcery points.",
-                    character.name, level, level
-                ))
-                .with_effect(Effect::ClassResourceUsed {
-                    character_name: character.name.clone(),
-                    resource_name: "Sorcery Points".to_string(),
-                    description: format!("Gained {level} points from slot"),
-                });
-            }
+        if !item.requires_attunement {
+            return Resolution::new(format!(
+                "{} doesn't require attunement; its magic already works",
+                item.name
+            ));
         }
-
-        // Regular Metamagic usage
-        if resources.sorcery_points < points {
+        if character.is_attuned_to(&item.name) {
             return Resolution::new(format!(
-                "{} doesn't have enough sorcery points! Has {} but needs {}.",
-                character.name, resources.sorcery_points, points
+                "{} is already attuned to {}",
+                character.name, item.name
+            ));
+        }
+        if character.attuned.len() >= Character::MAX_ATTUNED_ITEMS {
+            return Resolution::new(format!(
+                "{} is already attuned to {} items ({}) and must end an attunement first",
+                character.name,
+                Character::MAX_ATTUNED_ITEMS,
+                character.attuned.join(", ")
             ));
         }
-
-        let metamagic_description = match metamagic.to_lowercase().as_str() {
-            "careful" => "Careful Spell: Protect allies from your spell's area effect.",
-            "distant" => "Distant Spell: Double the spell's range (or 30 ft if touch).",
-            "empowered" => "Empowered Spell: Reroll up to CHA mod damage dice.",
-            "extended" => "Extended Spell: Double the spell's duration (max 24 hours).",
-            "heightened" => "Heightened Spell: Target has disadvantage on first save.",
-            "quickened" => "Quickened Spell: Cast as a bonus action instead of an action.",
-            "subtle" => "Subtle Spell: Cast without verbal or somatic components.",
-            "twinned" => "Twinned Spell: Target a second creature with a single-target spell.",
-            _ => metamagic,
-        };
-
-        let spell_text = spell_name.map_or(String::new(), |s| format!(" on {}", s));
 
         Resolution::new(format!(
-            "{} uses {}{} ({} sorcery point{}).",
-            character.name,
-            metamagic_description,
-            spell_text,
-            points,
-            if points == 1 { "" } else { "s" }
+            "{} spends a short rest focused on {} and becomes attuned to it",
+            character.name, item.name
         ))
-        .with_effect(Effect::ClassResourceUsed {
-            character_name: character.name.clone(),
-            resource_name: "Sorcery Points".to_string(),
-            description: format!("Used {points} for {metamagic}"),
+        .with_effect(Effect::ItemAttuned {
+            character_id,
+            item_name: item.name.clone(),
         })
     }
-}
 
-#[allow(dead_code)]
-fn parse_item_type(s: &str) -> ItemType {
-    match s.to_lowercase().as_str() {
-        "weapon" => ItemType::Weapon,
-        "armor" => ItemType::Armor,
-        "shield" => ItemType::Shield,
-        "potion" => ItemType::Potion,
-        "scroll" => ItemType::Scroll,
-        "wand" => ItemType::Wand,
-        "ring" => ItemType::Ring,
-        "wondrous" => ItemType::Wondrous,
-        "adventuring" => ItemType::Adventuring,
-        "tool" => ItemType::Tool,
-        _ => ItemType::Other,
-    }
-}
+    fn resolve_end_attunement(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        item_name: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-impl Default for RulesEngine {
-    fn default() -> Self {
-        Self::new()
+        match character
+            .attuned
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(item_name))
+        {
+            Some(name) => Resolution::new(format!(
+                "{} ends their attunement to {}",
+                character.name, name
+            ))
+            .with_effect(Effect::AttunementEnded {
+                character_id,
+                item_name: name.clone(),
+            }),
+            None => Resolution::new(format!(
+                "{} is not attuned to {}",
+                character.name, item_name
+            )),
+        }
     }
-}
 
-/// Apply effects to the game world.
-pub fn apply_effects(world: &mut GameWorld, effects: &[Effect]) {
-    for effect in effects {
-        apply_effect(world, effect);
+    fn resolve_adjust_gold(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        amount: f32,
+        reason: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let mut inventory = character.inventory.clone();
+
+        match inventory.adjust_gold(amount) {
+            Err(_) => Resolution::new(format!(
+                "{} doesn't have enough gold (has {:.0} gp, needs {:.0} gp)",
+                character.name,
+                character.inventory.purse.total_gp(),
+                -amount
+            )),
+            Ok(new_total) => {
+                let action = if amount >= 0.0 { "gains" } else { "spends" };
+                Resolution::new(format!(
+                    "{} {} {:.0} gp {} (now has {:.0} gp)",
+                    character.name,
+                    action,
+                    amount.abs(),
+                    reason,
+                    new_total
+                ))
+                .with_effect(Effect::GoldChanged {
+                    character_id,
+                    amount,
+                    new_total,
+                    reason: reason.to_string(),
+                    purse: inventory.purse,
+                })
+            }
+        }
     }
-}
 
-/// Apply a single effect to the game world.
-pub fn apply_effect(world: &mut GameWorld, effect: &Effect) {
-    match effect {
-        Effect::HpChanged {
-            amount,
-            dropped_to_zero,
-            ..
-        } => {
-            let was_unconscious = world.player_character.hit_points.current <= 0;
+    fn resolve_adjust_currency(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        amount: i32,
+        coin: Coin,
+        reason: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let mut purse = character.inventory.purse;
+        let count = amount.unsigned_abs();
 
-            if *amount < 0 {
-                world.player_character.hit_points.take_damage(-*amount);
-            } else {
-                world.player_character.hit_points.heal(*amount);
-            }
+        if amount >= 0 {
+            purse.add(coin, count);
+        } else if purse.pay(count as u64 * coin.value_cp()).is_err() {
+            return Resolution::new(format!(
+                "{} can't pay {} {} (has {})",
+                character.name, count, coin, character.inventory.purse
+            ));
+        }
 
-            // Add Unconscious condition if dropped to 0 (only if not already unconscious)
-            if *dropped_to_zero {
-                world
-                    .player_character
-                    .add_condition(Condition::Unconscious, "Dropped to 0 HP");
-            }
+        let action = if amount >= 0 { "gains" } else { "spends" };
+        let change_gp = (count as u64 * coin.value_cp()) as f32 / 100.0;
+        Resolution::new(format!(
+            "{} {} {} {} {} (now has {})",
+            character.name, action, count, coin, reason, purse
+        ))
+        .with_effect(Effect::GoldChanged {
+            character_id,
+            amount: if amount >= 0 { change_gp } else { -change_gp },
+            new_total: purse.total_gp() as f32,
+            reason: reason.to_string(),
+            purse,
+        })
+    }
 
-            // Remove Unconscious condition and reset death saves if healed above 0
-            if was_unconscious && world.player_character.hit_points.current > 0 {
-                world
-                    .player_character
-                    .conditions
-                    .retain(|c| c.condition != Condition::Unconscious);
-                // Reset death saves when regaining consciousness
-                world.player_character.death_saves.reset();
-            }
+    /// Resolve a death saving throw (D&D 5e rules)
+    /// - Roll d20 (no modifiers by default)
+    /// - 10+ = success, <10 = failure
+    /// - Natural 20 = regain 1 HP and become conscious
+    /// - Natural 1 = counts as 2 failures
+    /// - 3 successes = stable
+    /// - 3 failures = death
+    fn resolve_death_save(&self, world: &GameWorld, character_id: CharacterId) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
 
-            // Sync HP to combat state if in combat
-            if let Some(ref mut combat) = world.combat {
-                let player_id = world.player_character.id;
-                combat.update_combatant_hp(player_id, world.player_character.hit_points.current);
-            }
-        }
-        Effect::ConditionApplied {
-            condition,
-            source,
-            duration_rounds,
-            ..
-        } => {
-            world
-                .player_character
-                .add_condition_with_duration(*condition, source.clone(), *duration_rounds);
+        // Must be at 0 HP to make death saves
+        if character.hit_points.current > 0 {
+            return Resolution::new(format!(
+                "{} is not dying and doesn't need to make a death save.",
+                character.name
+            ));
         }
-        Effect::ConditionRemoved { condition, .. } => {
-            world
-                .player_character
-                .conditions
-                .retain(|c| c.condition != *condition);
+
+        // Roll d20
+        let roll = dice::roll("1d20").unwrap();
+        let roll_value = roll.total;
+
+        // Check for natural 20 - regain 1 HP
+        if roll.is_critical() {
+            return Resolution::new(format!(
+                "{} rolls a NATURAL 20 on their death save! They regain 1 HP and become conscious!",
+                character.name
+            ))
+            .with_effect(Effect::DeathSavesReset { target_id: character_id })
+            .with_effect(Effect::HpChanged {
+                target_id: character_id,
+                amount: 1,
+                new_current: 1,
+                new_max: character.hit_points.maximum,
+                dropped_to_zero: false,
+            })
+            .with_effect(Effect::ConditionRemoved {
+                target_id: character_id,
+                condition: Condition::Unconscious,
+            });
         }
-        Effect::CombatStarted => {
-            world.start_combat();
+
+        // Check for natural 1 - counts as 2 failures
+        if roll.is_fumble() {
+            let new_failures = character.death_saves.failures + 2;
+            if new_failures >= 3 {
+                return Resolution::new(format!(
+                    "{} rolls a NATURAL 1 on their death save! Two failures! {} has died!",
+                    character.name, character.name
+                ))
+                .with_effect(Effect::DeathSaveFailure {
+                    target_id: character_id,
+                    failures: 2,
+                    total_failures: new_failures.min(3),
+                    source: "Natural 1 on death save".to_string(),
+                })
+                .with_effect(Effect::CharacterDied {
+                    target_id: character_id,
+                    cause: "Failed death saves".to_string(),
+                });
+            } else {
+                return Resolution::new(format!(
+                    "{} rolls a NATURAL 1 on their death save! That counts as TWO failures! ({}/3)",
+                    character.name, new_failures
+                ))
+                .with_effect(Effect::DeathSaveFailure {
+                    target_id: character_id,
+                    failures: 2,
+                    total_failures: new_failures,
+                    source: "Natural 1 on death save".to_string(),
+                });
+            }
         }
-        Effect::CombatEnded => {
-            world.end_combat();
+
+        // Normal roll - 10+ is success, <10 is failure
+        if roll_value >= 10 {
+            let new_successes = character.death_saves.successes + 1;
+            if new_successes >= 3 {
+                Resolution::new(format!(
+                    "{} rolls {} on their death save - SUCCESS! With 3 successes, {} is now STABLE!",
+                    character.name, roll_value, character.name
+                ))
+                .with_effect(Effect::DeathSaveSuccess {
+                    target_id: character_id,
+                    roll: roll_value,
+                    total_successes: 3,
+                })
+                .with_effect(Effect::Stabilized { target_id: character_id })
+            } else {
+                Resolution::new(format!(
+                    "{} rolls {} on their death save - SUCCESS! ({}/3 successes)",
+                    character.name, roll_value, new_successes
+                ))
+                .with_effect(Effect::DeathSaveSuccess {
+                    target_id: character_id,
+                    roll: roll_value,
+                    total_successes: new_successes,
+                })
+            }
+        } else {
+            let new_failures = character.death_saves.failures + 1;
+            if new_failures >= 3 {
+                Resolution::new(format!(
+                    "{} rolls {} on their death save - FAILURE! With 3 failures, {} has DIED!",
+                    character.name, roll_value, character.name
+                ))
+                .with_effect(Effect::DeathSaveFailure {
+                    target_id: character_id,
+                    failures: 1,
+                    total_failures: 3,
+                    source: "Death save".to_string(),
+                })
+                .with_effect(Effect::CharacterDied {
+                    target_id: character_id,
+                    cause: "Failed death saves".to_string(),
+                })
+            } else {
+                Resolution::new(format!(
+                    "{} rolls {} on their death save - FAILURE! ({}/3 failures)",
+                    character.name, roll_value, new_failures
+                ))
+                .with_effect(Effect::DeathSaveFailure {
+                    target_id: character_id,
+                    failures: 1,
+                    total_failures: new_failures,
+                    source: "Death save".to_string(),
+                })
+            }
         }
-        Effect::CombatantAdded {
-            id,
-            name,
-            initiative,
-            is_ally,
-            current_hp,
-            max_hp,
-            armor_class,
-        } => {
-            if let Some(ref mut combat) = world.combat {
-                combat.add_combatant(Combatant {
-                    id: *id,
-                    name: name.clone(),
-                    initiative: *initiative,
-                    is_player: *id == world.player_character.id,
-                    is_ally: *is_ally,
-                    current_hp: *current_hp,
-                    max_hp: *max_hp,
-                    armor_class: *armor_class,
+    }
+
+    /// Resolve a concentration check (D&D 5e rules)
+    /// - CON saving throw
+    /// - DC = max(10, damage / 2)
+    fn resolve_concentration_check(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        damage_taken: i32,
+        spell_name: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        // Calculate DC: max(10, damage / 2)
+        let dc = (damage_taken / 2).max(10);
+
+        // Get CON modifier
+        let con_mod = character.effective_scores().modifier(Ability::Constitution);
+        let proficiency = character.proficiency_bonus();
+
+        // Check if proficient in CON saves (some classes like Sorcerer, Wizard with War Caster)
+        // For now, assume base CON save without proficiency unless they have the save proficiency
+        let save_mod = if character
+            .saving_throw_proficiencies
+            .contains(&Ability::Constitution)
+        {
+            con_mod + proficiency
+        } else {
+            con_mod
+        };
+
+        // Roll the save
+        let roll = roll_with_fallback_advantage(
+            &format!("1d20+{save_mod}"),
+            "1d20",
+            with_exhaustion(character, 3, Advantage::Normal),
+        );
+        let roll_total = roll.total;
+
+        if roll_total >= dc {
+            Resolution::new(format!(
+                "{} makes a DC {} Constitution save to maintain concentration on {}. Rolls {} - SUCCESS! Concentration maintained.",
+                character.name, dc, spell_name, roll_total
+            ))
+            .with_effect(Effect::ConcentrationMaintained {
+                character_id,
+                spell_name: spell_name.to_string(),
+                roll: roll_total,
+                dc,
+            })
+        } else {
+            Resolution::new(format!(
+                "{} makes a DC {} Constitution save to maintain concentration on {}. Rolls {} - FAILED! Concentration is broken!",
+                character.name, dc, spell_name, roll_total
+            ))
+            .with_effect(Effect::ConcentrationBroken {
+                character_id,
+                spell_name: spell_name.to_string(),
+                damage_taken,
+                roll: roll_total,
+                dc,
+            })
+        }
+    }
+
+    fn resolve_change_location(
+        &self,
+        world: &GameWorld,
+        new_location: &str,
+        location_type: Option<String>,
+        description: Option<String>,
+        travel_time_minutes: Option<u32>,
+    ) -> Resolution {
+        let here = &world.current_location;
+        if here.name.eq_ignore_ascii_case(new_location) {
+            return Resolution::new(format!("You are already at {}.", here.name));
+        }
+
+        // Going somewhere by name finds the place if it's known, or puts it
+        // on the map, and either way records the route taken
+        let mut resolution = Resolution::new(String::new());
+        let destination = match world.location_named(new_location) {
+            Some(known) => known.clone(),
+            None => {
+                let location_type = location_type
+                    .as_deref()
+                    .map(parse_location_type)
+                    .unwrap_or(LocationType::Other);
+                let location = Location::new(new_location, location_type)
+                    .with_description(description.unwrap_or_default());
+                resolution = resolution.with_effect(Effect::LocationDiscovered {
+                    location: location.clone(),
                 });
+                location
             }
-        }
-        Effect::TurnAdvanced { .. } => {
-            if let Some(ref mut combat) = world.combat {
-                combat.next_turn();
+        };
+        // A known route keeps its own travel time unless told otherwise
+        let minutes = match here.connection_to(destination.id) {
+            Some(route) => travel_time_minutes.unwrap_or(route.travel_time_minutes),
+            None => {
+                let minutes = travel_time_minutes.unwrap_or(0);
+                resolution = resolution.with_effect(Effect::LocationsConnected {
+                    from: here.id,
+                    to: destination.id,
+                    travel_time_minutes: minutes,
+                });
+                minutes
             }
+        };
+        if minutes > 0 {
+            resolution = resolution.with_effect(Effect::TimeAdvanced { minutes });
+        }
+
+        resolution.narrative = format!("You travel from {} to {}.", here.name, destination.name);
+        resolution.with_effect(Effect::LocationChanged {
+            previous_location: here.name.clone(),
+            new_location: destination.name,
+            location_id: Some(destination.id),
+        })
+    }
+
+    fn resolve_travel(&self, world: &GameWorld, to: LocationId) -> Resolution {
+        let here = &world.current_location;
+        if here.id == to {
+            return Resolution::new(format!("You are already at {}.", here.name));
+        }
+        let Some(destination) = world.location(to) else {
+            return Resolution::new("That place isn't on the map yet.");
+        };
+        let Some(route) = here.connection_to(to) else {
+            return Resolution::new(format!(
+                "There is no known way from {} to {}.",
+                here.name, destination.name
+            ));
+        };
+
+        let mut resolution = Resolution::new(format!(
+            "You travel from {} to {}.",
+            here.name, destination.name
+        ));
+        if route.travel_time_minutes > 0 {
+            resolution = resolution.with_effect(Effect::TimeAdvanced {
+                minutes: route.travel_time_minutes,
+            });
+        }
+        resolution.with_effect(Effect::LocationChanged {
+            previous_location: here.name.clone(),
+            new_location: destination.name.clone(),
+            location_id: Some(to),
+        })
+    }
+
+    fn resolve_register_consequence(
+        &self,
+        trigger_description: &str,
+        consequence_description: &str,
+        severity: &str,
+        _related_entities: &[String],
+        importance: f32,
+        expires_in_turns: Option<u32>,
+    ) -> Resolution {
+        // Generate a unique ID for this consequence
+        let consequence_id = uuid::Uuid::new_v4().to_string();
+
+        let severity_display = match severity.to_lowercase().as_str() {
+            "minor" => "minor",
+            "moderate" => "moderate",
+            "major" => "major",
+            "critical" => "critical",
+            _ => "moderate",
+        };
+
+        let expiry_note = match expires_in_turns {
+            Some(turns) => format!(" (expires in {turns} turns)"),
+            None => String::new(),
+        };
+
+        Resolution::new(format!(
+            "Consequence registered: If {trigger_description}, then {consequence_description} ({severity_display} severity, importance {importance:.1}){expiry_note}"
+        ))
+        .with_effect(Effect::ConsequenceRegistered {
+            consequence_id,
+            trigger_description: trigger_description.to_string(),
+            consequence_description: consequence_description.to_string(),
+            severity: severity_display.to_string(),
+        })
+    }
+
+    // ========================================================================
+    // Class Feature Resolution Functions
+    // ========================================================================
+
+    fn resolve_use_rage(&self, world: &GameWorld, character_id: CharacterId) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        // Check if already raging
+        if character.class_resources.rage_active {
+            return Resolution::new(format!("{} is already raging!", character.name));
+        }
+
+        // Check for rage uses remaining
+        let rage_feature = character.features.iter().find(|f| f.name == "Rage");
+        if let Some(feature) = rage_feature {
+            if let Some(ref uses) = feature.uses {
+                if uses.current == 0 {
+                    return Resolution::new(format!(
+                        "{} has no rage uses remaining! (Recovers on long rest)",
+                        character.name
+                    ));
+                }
+            }
+        }
+
+        // Determine rage damage bonus based on level
+        let barbarian_level = character
+            .classes
+            .iter()
+            .find(|c| c.class == crate::world::CharacterClass::Barbarian)
+            .map(|c| c.level)
+            .unwrap_or(1);
+
+        let rage_damage = match barbarian_level {
+            1..=8 => 2,
+            9..=15 => 3,
+            _ => 4,
+        };
+
+        Resolution::new(format!(
+            "{} enters a RAGE! Gains: advantage on STR checks/saves, +{} rage damage to melee attacks, resistance to bludgeoning/piercing/slashing damage. Cannot cast spells or concentrate while raging.",
+            character.name, rage_damage
+        ))
+        .with_effect(Effect::RageStarted {
+            character_id,
+            damage_bonus: rage_damage,
+        })
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Rage".to_string(),
+            description: format!("Entered rage (1 minute, +{rage_damage} damage)"),
+        })
+        .with_effect(Effect::FeatureUsed {
+            character_id,
+            feature_name: "Rage".to_string(),
+            uses_remaining: 0,
+        })
+    }
+
+    fn resolve_end_rage(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        reason: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        if !character.class_resources.rage_active {
+            return Resolution::new(format!("{} is not currently raging.", character.name));
+        }
+
+        let reason_text = match reason {
+            "duration_expired" => "Rage ended (1 minute duration expired).",
+            "unconscious" => "Rage ended (knocked unconscious).",
+            "no_combat_action" => "Rage ended (turn ended without attacking or taking damage).",
+            "voluntary" => "Rage ended voluntarily.",
+            _ => "Rage ended.",
+        };
+
+        Resolution::new(format!("{}'s rage ends. {}", character.name, reason_text))
+            .with_effect(Effect::RageEnded {
+                character_id,
+                reason: reason_text.to_string(),
+            })
+            .with_effect(Effect::ClassResourceUsed {
+                character_name: character.name.clone(),
+                resource_name: "Rage".to_string(),
+                description: reason_text.to_string(),
+            })
+    }
+
+    fn resolve_use_ki(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        points: u8,
+        ability: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let resources = &character.class_resources;
+
+        if resources.ki_points < points {
+            return Resolution::new(format!(
+                "{} doesn't have enough ki points! Has {} but needs {}.",
+                character.name, resources.ki_points, points
+            ));
+        }
+
+        let ability_description = match ability {
+            "flurry_of_blows" => "Flurry of Blows: Make two unarmed strikes as a bonus action.",
+            "patient_defense" => "Patient Defense: Take the Dodge action as a bonus action.",
+            "step_of_the_wind" => "Step of the Wind: Disengage or Dash as a bonus action, jump distance doubled.",
+            "stunning_strike" => "Stunning Strike: Target must make a CON save or be Stunned until the end of your next turn.",
+            _ => ability,
+        };
+
+        Resolution::new(format!(
+            "{} spends {} ki point{}. {}",
+            character.name,
+            points,
+            if points == 1 { "" } else { "s" },
+            ability_description
+        ))
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Ki Points".to_string(),
+            description: format!("Spent {points} ki for {ability}"),
+        })
+    }
+
+    fn resolve_use_lay_on_hands(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        target_name: &str,
+        hp_amount: u32,
+        cure_disease: bool,
+        neutralize_poison: bool,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let pool = character.class_resources.lay_on_hands_pool;
+
+        let total_cost = hp_amount
+            + if cure_disease { 5 } else { 0 }
+            + if neutralize_poison { 5 } else { 0 };
+
+        if pool < total_cost {
+            return Resolution::new(format!(
+                "{} doesn't have enough in their Lay on Hands pool! Has {} HP but needs {}.",
+                character.name, pool, total_cost
+            ));
+        }
+
+        let mut effects_text = Vec::new();
+        if hp_amount > 0 {
+            effects_text.push(format!("restores {hp_amount} HP"));
+        }
+        if cure_disease {
+            effects_text.push("cures one disease".to_string());
+        }
+        if neutralize_poison {
+            effects_text.push("neutralizes one poison".to_string());
+        }
+
+        Resolution::new(format!(
+            "{} uses Lay on Hands on {}: {}. ({} HP remaining in pool)",
+            character.name,
+            target_name,
+            effects_text.join(", "),
+            pool - total_cost
+        ))
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Lay on Hands".to_string(),
+            description: format!("Used {total_cost} points on {target_name}"),
+        })
+    }
+
+    fn resolve_use_divine_smite(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        spell_slot_level: u8,
+        target_is_undead_or_fiend: bool,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        // Smite rides on a hit, so it needs the paladin's attack to have just landed
+        let Some(hit) = world
+            .combat
+            .as_ref()
+            .and_then(|c| c.last_hit)
+            .filter(|hit| hit.attacker_id == character.id)
+        else {
+            return Resolution::new(format!(
+                "{} can only smite right after hitting with an attack.",
+                character.name
+            ));
+        };
+
+        // Check if they have spell slots available
+        if let Some(ref spellcasting) = character.spellcasting {
+            let slot_idx = spell_slot_level.saturating_sub(1) as usize;
+            if slot_idx < 9 {
+                let slot = &spellcasting.spell_slots.slots[slot_idx];
+                if slot.available() == 0 {
+                    return Resolution::new(format!(
+                        "{} has no level {} spell slots remaining!",
+                        character.name, spell_slot_level
+                    ));
+                }
+            }
+        }
+
+        // Calculate damage dice
+        // Base: 2d8, +1d8 per slot level above 1st, max 5d8
+        // Extra 1d8 vs undead/fiends
+        let base_dice = 2 + (spell_slot_level.saturating_sub(1)).min(3);
+        let mut total_dice = if target_is_undead_or_fiend {
+            (base_dice + 1).min(6)
+        } else {
+            base_dice.min(5)
+        };
+        // Smite dice are doubled on a critical hit like the weapon's
+        if hit.is_critical {
+            total_dice *= 2;
+        }
+
+        let damage_roll = roll_with_fallback(&format!("{total_dice}d8"), "2d8");
+
+        let extra_text = if target_is_undead_or_fiend {
+            " (extra damage vs undead/fiend)"
+        } else {
+            ""
+        };
+        let crit_text = if hit.is_critical { " critical" } else { "" };
+
+        let mut resolution = Resolution::new(format!(
+            "{} channels divine power into their{} strike! Divine Smite rolls {}d8 = {} radiant damage{}. (Level {} slot expended)",
+            character.name, crit_text, total_dice, damage_roll.total, extra_text, spell_slot_level
+        ))
+        .with_effect(Effect::DiceRolled {
+            roll: damage_roll.clone(),
+            purpose: "Divine Smite damage".to_string(),
+        })
+        .with_effect(Effect::HitSpent {
+            attacker_id: character.id,
+        })
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Divine Smite".to_string(),
+            description: format!("Used level {spell_slot_level} slot for smite"),
+        });
+
+        // The radiant damage lands on whoever was hit
+        if world.character(hit.target_id).is_some() {
+            let hurt = self.resolve_damage(
+                world,
+                hit.target_id,
+                damage_roll.total,
+                DamageType::Radiant,
+                "Divine Smite",
+            );
+            resolution.narrative = format!("{} {}", resolution.narrative, hurt.narrative);
+            return resolution.with_effects(hurt.effects);
+        }
+        let Some(target) = world
+            .combat
+            .as_ref()
+            .and_then(|c| c.combatant(hit.target_id))
+        else {
+            return resolution;
+        };
+        let (damage, hp_change) = damage_combatant(target, damage_roll.total, DamageType::Radiant);
+        resolution = resolution.with_effects(hp_change);
+        resolution.narrative.push_str(&format!(
+            " {} takes {} radiant damage.",
+            target.name, damage
+        ));
+        resolution
+    }
+
+    fn resolve_use_wild_shape(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        beast_form: &str,
+        beast_hp: i32,
+        _beast_ac: Option<u8>,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        // Check if already in Wild Shape
+        if character.class_resources.wild_shape_form.is_some() {
+            return Resolution::new(format!(
+                "{} is already in Wild Shape form!",
+                character.name
+            ));
+        }
+
+        // Find Wild Shape feature uses
+        let wild_shape_feature = character.features.iter().find(|f| f.name == "Wild Shape");
+        if let Some(feature) = wild_shape_feature {
+            if let Some(ref uses) = feature.uses {
+                if uses.current == 0 {
+                    return Resolution::new(format!(
+                        "{} has no Wild Shape uses remaining! (Recovers on short/long rest)",
+                        character.name
+                    ));
+                }
+            }
+        }
+
+        // Calculate duration based on Druid level
+        let druid_level = character
+            .classes
+            .iter()
+            .find(|c| c.class == crate::world::CharacterClass::Druid)
+            .map(|c| c.level)
+            .unwrap_or(2);
+        let duration_hours = druid_level / 2;
+
+        Resolution::new(format!(
+            "{} transforms into a {}! Beast form has {} HP. Duration: {} hour{}. Mental stats, proficiencies, and features retained. Cannot cast spells but can maintain concentration.",
+            character.name, beast_form, beast_hp, duration_hours,
+            if duration_hours == 1 { "" } else { "s" }
+        ))
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Wild Shape".to_string(),
+            description: format!("Transformed into {beast_form} ({beast_hp} HP)"),
+        })
+        .with_effect(Effect::FeatureUsed {
+            character_id,
+            feature_name: "Wild Shape".to_string(),
+            uses_remaining: 0,
+        })
+    }
+
+    fn resolve_end_wild_shape(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        reason: &str,
+        excess_damage: i32,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        if character.class_resources.wild_shape_form.is_none() {
+            return Resolution::new(format!(
+                "{} is not currently in Wild Shape form.",
+                character.name
+            ));
+        }
+
+        let reason_text = match reason {
+            "duration_expired" => "Wild Shape ended (duration expired).",
+            "hp_zero" => {
+                if excess_damage > 0 {
+                    &format!(
+                        "Wild Shape ended (beast HP dropped to 0). {} excess damage carries over to normal form!",
+                        excess_damage
+                    )
+                } else {
+                    "Wild Shape ended (beast HP dropped to 0)."
+                }
+            }
+            "voluntary" => "Wild Shape ended voluntarily as a bonus action.",
+            "incapacitated" => "Wild Shape ended (druid became incapacitated).",
+            _ => "Wild Shape ended.",
+        };
+
+        let mut resolution = Resolution::new(format!(
+            "{} reverts to their normal form. {}",
+            character.name, reason_text
+        ))
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Wild Shape".to_string(),
+            description: reason_text.to_string(),
+        });
+
+        // Apply excess damage if any
+        if excess_damage > 0 {
+            resolution = resolution.with_effect(Effect::HpChanged {
+                target_id: character_id,
+                amount: -excess_damage,
+                new_current: (character.hit_points.current - excess_damage).max(0),
+                new_max: character.hit_points.maximum,
+                dropped_to_zero: character.hit_points.current - excess_damage <= 0,
+            });
+        }
+
+        resolution
+    }
+
+    fn resolve_use_channel_divinity(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        option: &str,
+        targets: &[String],
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        // Check for Channel Divinity uses
+        let cd_feature = character
+            .features
+            .iter()
+            .find(|f| f.name == "Channel Divinity");
+        if let Some(feature) = cd_feature {
+            if let Some(ref uses) = feature.uses {
+                if uses.current == 0 {
+                    return Resolution::new(format!(
+                        "{} has no Channel Divinity uses remaining! (Recovers on short/long rest)",
+                        character.name
+                    ));
+                }
+            }
+        }
+
+        let option_description = match option.to_lowercase().as_str() {
+            "turn undead" => {
+                "Turn Undead: Each undead within 30 feet must make a WIS save. On failure, they must spend their turns moving away and cannot take reactions for 1 minute."
+            }
+            "divine spark" => {
+                "Divine Spark: Either deal 1d8 radiant damage to one creature within 30 feet (DEX save for half), or restore 1d8 HP to one creature within 30 feet."
+            }
+            "sacred weapon" => {
+                "Sacred Weapon: Your weapon becomes magical for 1 minute, +CHA to attack rolls, and sheds bright light."
+            }
+            _ => option,
+        };
+
+        let targets_text = if targets.is_empty() {
+            String::new()
+        } else {
+            format!(" Targets: {}.", targets.join(", "))
+        };
+
+        Resolution::new(format!(
+            "{} uses Channel Divinity: {}.{}",
+            character.name, option_description, targets_text
+        ))
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Channel Divinity".to_string(),
+            description: option.to_string(),
+        })
+        .with_effect(Effect::FeatureUsed {
+            character_id,
+            feature_name: "Channel Divinity".to_string(),
+            uses_remaining: 0,
+        })
+    }
+
+    fn resolve_use_bardic_inspiration(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        target_name: &str,
+        die_size: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        // Check for Bardic Inspiration uses
+        let bi_feature = character
+            .features
+            .iter()
+            .find(|f| f.name == "Bardic Inspiration");
+        if let Some(feature) = bi_feature {
+            if let Some(ref uses) = feature.uses {
+                if uses.current == 0 {
+                    return Resolution::new(format!(
+                        "{} has no Bardic Inspiration uses remaining! (Recovers on long rest, or short rest at level 5+)",
+                        character.name
+                    ));
+                }
+            }
+        }
+
+        Resolution::new(format!(
+            "{} inspires {} with a rousing performance! {} gains a {} Bardic Inspiration die they can add to one ability check, attack roll, or saving throw within the next 10 minutes.",
+            character.name, target_name, target_name, die_size
+        ))
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Bardic Inspiration".to_string(),
+            description: format!("Inspired {target_name} with a {die_size}"),
+        })
+        .with_effect(Effect::FeatureUsed {
+            character_id,
+            feature_name: "Bardic Inspiration".to_string(),
+            uses_remaining: 0,
+        })
+    }
+
+    fn resolve_use_action_surge(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        action_taken: &str,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        if character.class_resources.action_surge_used {
+            return Resolution::new(format!(
+                "{} has already used Action Surge! (Recovers on short/long rest)",
+                character.name
+            ));
+        }
+
+        Resolution::new(format!(
+            "{} surges with renewed vigor! Takes an additional action this turn: {}",
+            character.name, action_taken
+        ))
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Action Surge".to_string(),
+            description: action_taken.to_string(),
+        })
+        .with_effect(Effect::FeatureUsed {
+            character_id,
+            feature_name: "Action Surge".to_string(),
+            uses_remaining: 0,
+        })
+        .with_effects(
+            world
+                .combat
+                .as_ref()
+                .and_then(|combat| combat.combatant(character_id))
+                .map(|_| Effect::ActionRestored {
+                    combatant_id: character_id,
+                }),
+        )
+    }
+
+    fn resolve_use_second_wind(&self, world: &GameWorld, character_id: CharacterId) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+
+        if character.class_resources.second_wind_used {
+            return Resolution::new(format!(
+                "{} has already used Second Wind! (Recovers on short/long rest)",
+                character.name
+            ));
+        }
+
+        // Calculate healing: 1d10 + fighter level
+        let fighter_level = character
+            .classes
+            .iter()
+            .find(|c| c.class == crate::world::CharacterClass::Fighter)
+            .map(|c| c.level)
+            .unwrap_or(1);
+
+        let healing_roll = roll_with_fallback(&format!("1d10+{fighter_level}"), "1d10+1");
+        let healing = healing_roll.total;
+
+        let new_hp = (character.hit_points.current + healing).min(character.hit_points.maximum);
+
+        Resolution::new(format!(
+            "{} catches their breath with Second Wind! Regains 1d10+{} = {} HP. (Now at {}/{})",
+            character.name, fighter_level, healing, new_hp, character.hit_points.maximum
+        ))
+        .with_effect(Effect::DiceRolled {
+            roll: healing_roll,
+            purpose: "Second Wind healing".to_string(),
+        })
+        .with_effect(Effect::HpChanged {
+            target_id: character_id,
+            amount: healing,
+            new_current: new_hp,
+            new_max: character.hit_points.maximum,
+            dropped_to_zero: false,
+        })
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Second Wind".to_string(),
+            description: format!("Healed {healing} HP"),
+        })
+        .with_effect(Effect::FeatureUsed {
+            character_id,
+            feature_name: "Second Wind".to_string(),
+            uses_remaining: 0,
+        })
+    }
+
+    fn resolve_use_sorcery_points(
+        &self,
+        world: &GameWorld,
+        character_id: CharacterId,
+        points: u8,
+        metamagic: &str,
+        spell_name: Option<&str>,
+        slot_level: Option<u8>,
+    ) -> Resolution {
+        let Some(character) = world.character(character_id) else {
+            return unknown_character(character_id);
+        };
+        let resources = &character.class_resources;
+
+        // Handle slot conversion separately
+        if metamagic == "convert_to_slot" {
+            if let Some(level) = slot_level {
+                let cost = level; // Costs spell level points to create a slot
+                if resources.sorcery_points < cost {
+                    return Resolution::new(format!(
+                        "{} doesn't have enough sorcery points! Has {} but needs {} to create a level {} slot.",
+                        character.name, resources.sorcery_points, cost, level
+                    ));
+                }
+                return Resolution::new(format!(
+                    "{} converts {} sorcery points into a level {} spell slot.",
+                    character.name, cost, level
+                ))
+                .with_effect(Effect::ClassResourceUsed {
+                    character_name: character.name.clone(),
+                    resource_name: "Sorcery Points".to_string(),
+                    description: format!("Created level {level} spell slot"),
+                });
+            }
+        }
+
+        if metamagic == "convert_from_slot" {
+            if let Some(level) = slot_level {
+                return Resolution::new(format!(
+                    "{} converts a level {} spell slot into {} sorcery points.",
+                    character.name, level, level
+                ))
+                .with_effect(Effect::ClassResourceUsed {
+                    character_name: character.name.clone(),
+                    resource_name: "Sorcery Points".to_string(),
+                    description: format!("Gained {level} points from slot"),
+                });
+            }
+        }
+
+        // Regular Metamagic usage
+        if resources.sorcery_points < points {
+            return Resolution::new(format!(
+                "{} doesn't have enough sorcery points! Has {} but needs {}.",
+                character.name, resources.sorcery_points, points
+            ));
+        }
+
+        let metamagic_description = match metamagic.to_lowercase().as_str() {
+            "careful" => "Careful Spell: Protect allies from your spell's area effect.",
+            "distant" => "Distant Spell: Double the spell's range (or 30 ft if touch).",
+            "empowered" => "Empowered Spell: Reroll up to CHA mod damage dice.",
+            "extended" => "Extended Spell: Double the spell's duration (max 24 hours).",
+            "heightened" => "Heightened Spell: Target has disadvantage on first save.",
+            "quickened" => "Quickened Spell: Cast as a bonus action instead of an action.",
+            "subtle" => "Subtle Spell: Cast without verbal or somatic components.",
+            "twinned" => "Twinned Spell: Target a second creature with a single-target spell.",
+            _ => metamagic,
+        };
+
+        let spell_text = spell_name.map_or(String::new(), |s| format!(" on {}", s));
+
+        Resolution::new(format!(
+            "{} uses {}{} ({} sorcery point{}).",
+            character.name,
+            metamagic_description,
+            spell_text,
+            points,
+            if points == 1 { "" } else { "s" }
+        ))
+        .with_effect(Effect::ClassResourceUsed {
+            character_name: character.name.clone(),
+            resource_name: "Sorcery Points".to_string(),
+            description: format!("Used {points} for {metamagic}"),
+        })
+    }
+}
+
+/// Count down timed conditions by a round, dropping the ones that run out.
+/// Minute durations turn into rounds, ten to the minute, so they count down
+/// in combat too.
+fn tick_conditions(conditions: &mut Vec<ActiveCondition>) {
+    conditions.retain_mut(|c| {
+        if let Some(minutes) = c.duration_minutes.take() {
+            let rounds = minutes.saturating_mul(10);
+            c.duration_rounds = Some(c.duration_rounds.map_or(rounds, |r| r.min(rounds)));
+        }
+        if let Some(ref mut duration) = c.duration_rounds {
+            if *duration > 0 {
+                *duration -= 1;
+            }
+            *duration > 0 // Keep only if duration remaining
+        } else {
+            true // Keep permanent conditions
+        }
+    });
+}
+
+fn parse_location_type(s: &str) -> LocationType {
+    match s.to_lowercase().as_str() {
+        "wilderness" => LocationType::Wilderness,
+        "town" | "village" => LocationType::Town,
+        "city" => LocationType::City,
+        "dungeon" => LocationType::Dungeon,
+        "building" => LocationType::Building,
+        "room" => LocationType::Room,
+        "road" => LocationType::Road,
+        "cave" => LocationType::Cave,
+        _ => LocationType::Other,
+    }
+}
+
+fn parse_item_type(s: &str) -> ItemType {
+    match s.to_lowercase().as_str() {
+        "weapon" => ItemType::Weapon,
+        "armor" => ItemType::Armor,
+        "shield" => ItemType::Shield,
+        "potion" => ItemType::Potion,
+        "scroll" => ItemType::Scroll,
+        "wand" => ItemType::Wand,
+        "ring" => ItemType::Ring,
+        "wondrous" => ItemType::Wondrous,
+        "adventuring" => ItemType::Adventuring,
+        "tool" => ItemType::Tool,
+        _ => ItemType::Other,
+    }
+}
+
+impl Default for RulesEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Apply effects to the game world.
+pub fn apply_effects(world: &mut GameWorld, effects: &[Effect]) {
+    for effect in effects {
+        apply_effect(world, effect);
+    }
+}
+
+/// Apply a single effect to the game world.
+pub fn apply_effect(world: &mut GameWorld, effect: &Effect) {
+    match effect {
+        Effect::HpChanged {
+            target_id,
+            amount,
+            dropped_to_zero,
+            ..
+        } => {
+            // Untracked combatants only have HP in the initiative order
+            if world.character(*target_id).is_none() {
+                if let Some(combatant) = world
+                    .combat
+                    .as_mut()
+                    .and_then(|c| c.combatant_mut(*target_id))
+                {
+                    combatant.current_hp =
+                        (combatant.current_hp + amount).clamp(0, combatant.max_hp);
+                    return;
+                }
+            }
+
+            let Some(character) = world.character_mut(*target_id) else {
+                return;
+            };
+            let was_unconscious = character.hit_points.current <= 0;
+
+            if *amount < 0 {
+                character.hit_points.take_damage(-*amount);
+                // Taking damage keeps a rage going
+                if character.class_resources.rage_active {
+                    character.class_resources.rage_damage_taken = true;
+                }
+            } else {
+                character.hit_points.heal(*amount);
+            }
+
+            // Add Unconscious condition if dropped to 0 (only if not already unconscious)
+            if *dropped_to_zero {
+                character.add_condition(Condition::Unconscious, "Dropped to 0 HP");
+            }
+
+            // Remove Unconscious condition and reset death saves if healed above 0
+            if was_unconscious && character.hit_points.current > 0 {
+                character
+                    .conditions
+                    .retain(|c| c.condition != Condition::Unconscious);
+                // Reset death saves when regaining consciousness
+                character.death_saves.reset();
+            }
+
+            // Sync HP to combat state if in combat
+            let (character_id, current_hp) = (character.id, character.hit_points.current);
+            if let Some(ref mut combat) = world.combat {
+                combat.update_combatant_hp(character_id, current_hp);
+            }
+        }
+        Effect::ConditionApplied {
+            target_id,
+            condition,
+            source,
+            duration_rounds,
+            duration_minutes,
+        } => {
+            let mut active = ActiveCondition::new(*condition, source.clone());
+            active.duration_rounds = *duration_rounds;
+            active.duration_minutes = *duration_minutes;
+            if let Some(character) = world.character_mut(*target_id) {
+                character.add_active_condition(active);
+            } else if let Some(combatant) = world
+                .combat
+                .as_mut()
+                .and_then(|c| c.combatant_mut(*target_id))
+            {
+                // Creatures without a character sheet track conditions in combat
+                if !combatant.has_condition(*condition) {
+                    combatant.conditions.push(active);
+                }
+            }
+        }
+        Effect::ConditionRemoved {
+            target_id,
+            condition,
+        } => {
+            if let Some(character) = world.character_mut(*target_id) {
+                character.conditions.retain(|c| c.condition != *condition);
+            } else if let Some(combatant) = world
+                .combat
+                .as_mut()
+                .and_then(|c| c.combatant_mut(*target_id))
+            {
+                combatant.conditions.retain(|c| c.condition != *condition);
+            }
+        }
+        Effect::TempHpGranted { target_id, amount } => {
+            if let Some(character) = world.character_mut(*target_id) {
+                character.hit_points.add_temp_hp(*amount);
+            }
+        }
+        Effect::ExhaustionChanged {
+            target_id,
+            new_level,
+            source,
+            ..
+        } => {
+            let Some(character) = world.character_mut(*target_id) else {
+                return;
+            };
+            character.set_exhaustion(*new_level, source.clone());
+            // Exhaustion level 4 halves the hit point maximum
+            let effective_max = character.effective_max_hp();
+            character.hit_points.current = character.hit_points.current.min(effective_max);
+        }
+        Effect::AbilityModifierAdded {
+            character_id,
+            modifier,
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character.ability_modifiers.push(modifier.clone());
+            }
+        }
+        Effect::AbilityModifierRemoved {
+            character_id,
+            source,
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character
+                    .ability_modifiers
+                    .retain(|m| !m.source.eq_ignore_ascii_case(source));
+            }
+        }
+        Effect::InspirationGranted { character_id } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character.has_inspiration = true;
+            }
+        }
+        Effect::InspirationUsed { character_id } => {
+            let Some(character) = world.character_mut(*character_id) else {
+                return;
+            };
+            character.has_inspiration = false;
+            character.inspiration_pending = true;
+        }
+        Effect::InspirationApplied { character_id } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character.inspiration_pending = false;
+            }
+        }
+        Effect::CombatStarted => {
+            world.start_combat();
+        }
+        Effect::CombatEnded => {
+            world.end_combat();
+        }
+        Effect::CombatantAdded {
+            id,
+            name,
+            initiative,
+            is_ally,
+            current_hp,
+            max_hp,
+            armor_class,
+            attack,
+            position,
+            size,
+            speed,
+            damage_traits,
+            senses,
+        } => {
+            if let Some(ref mut combat) = world.combat {
+                combat.add_combatant(Combatant {
+                    id: *id,
+                    name: name.clone(),
+                    initiative: *initiative,
+                    is_player: *id == world.player_character.id,
+                    is_ally: *is_ally,
+                    current_hp: *current_hp,
+                    max_hp: *max_hp,
+                    armor_class: *armor_class,
+                    attack: attack.clone(),
+                    position: *position,
+                    disengaged: false,
+                    readied_reaction: None,
+                    reaction_used: false,
+                    action_used: false,
+                    attacks_made: 0,
+                    bonus_action_used: false,
+                    movement_remaining: *speed,
+                    size: *size,
+                    speed: *speed,
+                    mounted_on: None,
+                    damage_traits: damage_traits.clone(),
+                    senses: senses.clone(),
+                    conditions: Vec::new(),
+                });
+            }
+        }
+        Effect::CombatantMoved { id, position } => {
+            if let Some(combatant) = world.combat.as_mut().and_then(|c| c.combatant_mut(*id)) {
+                combatant.position = Some(*position);
+            }
+        }
+        Effect::Mounted { rider_id, mount_id } => {
+            if let Some(combat) = world.combat.as_mut() {
+                let mount_position = combat.combatant(*mount_id).and_then(|m| m.position);
+                if let Some(rider) = combat.combatant_mut(*rider_id) {
+                    rider.mounted_on = Some(*mount_id);
+                    rider.position = mount_position.or(rider.position);
+                }
+            }
+        }
+        Effect::Dismounted { rider_id } => {
+            if let Some(rider) = world
+                .combat
+                .as_mut()
+                .and_then(|c| c.combatant_mut(*rider_id))
+            {
+                rider.mounted_on = None;
+            }
+        }
+        Effect::Disengaged { id } => {
+            if let Some(combatant) = world.combat.as_mut().and_then(|c| c.combatant_mut(*id)) {
+                combatant.disengaged = true;
+            }
+        }
+        Effect::ActionUsed { combatant_id, cost } => {
+            if let Some(combatant) = world
+                .combat
+                .as_mut()
+                .and_then(|c| c.combatant_mut(*combatant_id))
+            {
+                match cost {
+                    ActionCost::Action => combatant.action_used = true,
+                    ActionCost::Attack => {
+                        combatant.action_used = true;
+                        combatant.attacks_made += 1;
+                    }
+                    ActionCost::BonusAction => combatant.bonus_action_used = true,
+                }
+            }
+        }
+        Effect::ActionRestored { combatant_id } => {
+            if let Some(combatant) = world
+                .combat
+                .as_mut()
+                .and_then(|c| c.combatant_mut(*combatant_id))
+            {
+                combatant.action_used = false;
+                combatant.attacks_made = 0;
+            }
+        }
+        Effect::MovementUsed { id, feet } => {
+            if let Some(combatant) = world.combat.as_mut().and_then(|c| c.combatant_mut(*id)) {
+                combatant.movement_remaining = combatant.movement_remaining.saturating_sub(*feet);
+            }
+        }
+        Effect::ReactionReadied {
+            reactor_id,
+            reaction,
+        } => {
+            if let Some(combatant) = world
+                .combat
+                .as_mut()
+                .and_then(|c| c.combatant_mut(*reactor_id))
+            {
+                combatant.readied_reaction = Some(*reaction);
+            }
+        }
+        Effect::ReactionUsed { reactor_id, .. } => {
+            if let Some(combatant) = world
+                .combat
+                .as_mut()
+                .and_then(|c| c.combatant_mut(*reactor_id))
+            {
+                combatant.readied_reaction = None;
+                combatant.reaction_used = true;
+            }
+        }
+        Effect::TurnAdvanced { .. } => {
+            // Whoever's turn is ending; outside combat, everyone's durations tick
+            let ending = world
+                .combat
+                .as_ref()
+                .and_then(|c| c.current_combatant())
+                .map(|c| c.id);
+            // A raging barbarian's minute counts down as their turn ends
+            if let Some(character) = ending.and_then(|id| world.character_mut(id)) {
+                let resources = &mut character.class_resources;
+                if resources.rage_active {
+                    resources.rage_damage_taken = false;
+                    if let Some(ref mut rounds) = resources.rage_rounds_remaining {
+                        *rounds = rounds.saturating_sub(1);
+                    }
+                }
+            }
+            if let Some(ref mut combat) = world.combat {
+                combat.next_turn();
+            }
+            let ticks = |id: CharacterId| ending.is_none_or(|ending| ending == id);
+
+            // Decrement condition durations and remove expired conditions at
+            // the end of each creature's own turn, so they last whole rounds
+            let party = world.party.iter_mut();
+            for character in std::iter::once(&mut world.player_character)
+                .chain(party)
+                .filter(|c| ticks(c.id))
+            {
+                tick_conditions(&mut character.conditions);
+                character
+                    .ability_modifiers
+                    .retain_mut(|m| match m.expires_in_rounds {
+                        Some(ref mut rounds) => {
+                            *rounds = rounds.saturating_sub(1);
+                            *rounds > 0
+                        }
+                        None => true,
+                    });
+            }
+            if let Some(ref mut combat) = world.combat {
+                for combatant in combat.combatants.iter_mut().filter(|c| ticks(c.id)) {
+                    tick_conditions(&mut combatant.conditions);
+                }
+            }
+        }
+        Effect::TimeAdvanced { minutes } => world.advance_time(*minutes),
+        Effect::HitDiceSpent {
+            target_id,
+            die_type,
+            count,
+        } => {
+            if let Some(character) = world.character_mut(*target_id) {
+                for _ in 0..*count {
+                    character.hit_dice.spend(*die_type);
+                }
+            }
+        }
+        Effect::RestCompleted { rest_type } => match rest_type {
+            RestType::Short => world.short_rest(),
+            RestType::Long => world.long_rest(),
+        },
+        Effect::ExperienceGained {
+            character_id,
+            amount,
+            ..
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character.experience += amount;
+            }
+        }
+        Effect::LevelUp {
+            character_id,
+            new_level,
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character.level_up(*new_level);
+            }
+        }
+        Effect::FeatTaken {
+            character_id,
+            feat_name,
+            ability,
+        } => {
+            if let (Some(feat), Some(character)) =
+                (get_feat(feat_name), world.character_mut(*character_id))
+            {
+                feat.apply(character, *ability);
+                character.pending_asi = character.pending_asi.saturating_sub(1);
+            }
+        }
+        Effect::FeatureUsed {
+            character_id,
+            feature_name,
+            uses_remaining,
+        } => {
+            if let Some(feature) = world
+                .character_mut(*character_id)
+                .and_then(|c| c.features.iter_mut().find(|f| f.name == *feature_name))
+            {
+                if let Some(ref mut uses) = feature.uses {
+                    uses.current = *uses_remaining;
+                }
+            }
+        }
+        Effect::SpellSlotUsed {
+            caster_id, level, ..
+        } => {
+            if let Some(spellcasting) = world
+                .character_mut(*caster_id)
+                .and_then(|c| c.spellcasting.as_mut())
+            {
+                spellcasting.spell_slots.use_slot(*level);
+            }
+        }
+        Effect::PactSlotUsed { caster_id, .. } => {
+            if let Some(pact) = world
+                .character_mut(*caster_id)
+                .and_then(|c| c.spellcasting.as_mut())
+                .and_then(|sc| sc.pact_slots.as_mut())
+            {
+                pact.use_slot();
+            }
+        }
+        Effect::SpellsPrepared {
+            character_id,
+            spells,
+        } => {
+            if let Some(spellcasting) = world
+                .character_mut(*character_id)
+                .and_then(|c| c.spellcasting.as_mut())
+            {
+                spellcasting.spells_prepared = spells.clone();
+                spellcasting.prepared_since_rest = true;
+            }
+        }
+        // Effects that don't modify state (informational)
+        Effect::DiceRolled { roll, purpose } => {
+            world.add_roll_narrative(roll.clone(), purpose);
+            world.log_roll(roll.clone(), purpose);
+        }
+        Effect::CheckSucceeded { .. } => {}
+        Effect::CheckFailed { .. } => {}
+        Effect::AttackHit {
+            attacker_id,
+            target_id: Some(target_id),
+            is_critical,
+            ..
+        } => {
+            if let Some(ref mut combat) = world.combat {
+                combat.last_hit = Some(LandedHit {
+                    attacker_id: *attacker_id,
+                    target_id: *target_id,
+                    is_critical: *is_critical,
+                });
+            }
+        }
+        Effect::AttackHit { .. } => {}
+        Effect::HitSpent { attacker_id } => {
+            if let Some(ref mut combat) = world.combat {
+                if combat
+                    .last_hit
+                    .is_some_and(|hit| hit.attacker_id == *attacker_id)
+                {
+                    combat.last_hit = None;
+                }
+            }
+        }
+        Effect::AttackMissed { .. } => {
+            if let Some(ref mut combat) = world.combat {
+                combat.last_hit = None;
+            }
+        }
+        Effect::InitiativeRolled { .. } => {}
+        // FactRemembered is handled by the DM agent's memory system, not world state
+        Effect::FactRemembered { .. } => {}
+
+        // Inventory effects
+        Effect::ItemAdded {
+            character_id,
+            item_name,
+            quantity,
+            item_type,
+            description,
+            magical,
+            weight,
+            value_gp,
+            ..
+        } => {
+            // Try to look up item from standard database first
+            let item = if let Some(standard_item) = world.custom_items.find_item(item_name) {
+                let mut item = standard_item.as_item();
+                item.quantity = *quantity;
+                item
+            } else {
+                // Fall back to the stats the resolution described
+                Item {
+                    name: item_name.clone(),
+                    quantity: *quantity,
+                    weight: *weight,
+                    value_gp: *value_gp,
+                    description: description.clone(),
+                    item_type: *item_type,
+                    magical: *magical,
+                    requires_attunement: false,
+                }
+            };
+            if let Some(character) = world.character_mut(*character_id) {
+                character.inventory.add_item(item);
+            }
+        }
+        Effect::ItemRemoved {
+            character_id,
+            item_name,
+            quantity,
+            ..
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character.inventory.remove_item(item_name, *quantity);
+            }
+        }
+        Effect::ItemEquipped {
+            character_id,
+            item_name,
+            slot,
+        } if world.character(*character_id).is_some_and(|c| {
+            c.equipment
+                .can_equip(slot, item_name, &world.custom_items)
+                .is_ok()
+        }) =>
+        {
+            // Look up item from database for proper stats, fall back to defaults
+            let db_armor = world.custom_items.get_armor(item_name);
+            let db_weapon = world.custom_items.get_weapon(item_name);
+            let Some(character) = world.character_mut(*character_id) else {
+                return;
+            };
+            match slot.as_str() {
+                "armor" if character.inventory.find_item(item_name).is_some() => {
+                    // Fall back to medium armor defaults
+                    let armor = db_armor.unwrap_or_else(|| {
+                        crate::world::ArmorItem::new(
+                            item_name.clone(),
+                            crate::world::ArmorType::Medium,
+                            14,
+                        )
+                    });
+                    character.equipment.armor = Some(armor);
+                    character.inventory.remove_item(item_name, 1);
+                }
+                "shield" => {
+                    if let Some(item) = character.inventory.find_item(item_name) {
+                        let shield_item = item.clone();
+                        character.equipment.shield = Some(shield_item);
+                        character.inventory.remove_item(item_name, 1);
+                    }
+                }
+                "main_hand" | "weapon" if character.inventory.find_item(item_name).is_some() => {
+                    // Fall back to generic 1d8 slashing
+                    let weapon = db_weapon.unwrap_or_else(|| {
+                        crate::world::WeaponItem::new(
+                            item_name.clone(),
+                            "1d8",
+                            crate::world::WeaponDamageType::Slashing,
+                        )
+                    });
+                    character.equipment.main_hand = Some(weapon);
+                    character.inventory.remove_item(item_name, 1);
+                }
+                "off_hand" => {
+                    if let Some(item) = character.inventory.find_item(item_name) {
+                        let off_hand_item = item.clone();
+                        character.equipment.off_hand = Some(off_hand_item);
+                        character.inventory.remove_item(item_name, 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Equipping that would overfill the character's hands is refused
+        Effect::ItemEquipped { .. } => {}
+        Effect::ItemUnequipped {
+            character_id, slot, ..
+        } => {
+            let Some(character) = world.character_mut(*character_id) else {
+                return;
+            };
+            match slot.as_str() {
+                "armor" => {
+                    if let Some(armor) = character.equipment.armor.take() {
+                        character.inventory.add_item(armor.base);
+                    }
+                }
+                "shield" => {
+                    if let Some(shield) = character.equipment.shield.take() {
+                        character.inventory.add_item(shield);
+                    }
+                }
+                "main_hand" | "weapon" => {
+                    if let Some(weapon) = character.equipment.main_hand.take() {
+                        character.inventory.add_item(weapon.base);
+                    }
+                }
+                "off_hand" => {
+                    if let Some(item) = character.equipment.off_hand.take() {
+                        character.inventory.add_item(item);
+                    }
+                }
+                _ => {}
+            }
+        }
+        // ItemUsed is informational - the actual effects (healing, etc.) are separate effects
+        Effect::ItemUsed { .. } => {}
+        Effect::ItemAttuned {
+            character_id,
+            item_name,
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                if !character.is_attuned_to(item_name)
+                    && character.attuned.len() < Character::MAX_ATTUNED_ITEMS
+                {
+                    character.attuned.push(item_name.clone());
+                }
+            }
+        }
+        Effect::AttunementEnded {
+            character_id,
+            item_name,
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character
+                    .attuned
+                    .retain(|name| !name.eq_ignore_ascii_case(item_name));
+            }
+        }
+        Effect::GoldChanged {
+            character_id,
+            purse,
+            ..
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                character.inventory.purse = *purse;
+            }
+        }
+        // AC itself is recalculated from equipment; combat keeps a copy
+        Effect::AcChanged {
+            character_id,
+            new_ac,
+            ..
+        } => {
+            if let Some(combatant) = world
+                .combat
+                .as_mut()
+                .and_then(|c| c.combatant_mut(*character_id))
+            {
+                combatant.armor_class = *new_ac;
+            }
+        }
+
+        Effect::DeathSaveFailure {
+            target_id,
+            failures,
+            ..
+        } => {
+            let Some(character) = world.character_mut(*target_id) else {
+                return;
+            };
+            for _ in 0..*failures {
+                character.death_saves.add_failure();
+            }
+        }
+
+        Effect::DeathSavesReset { target_id } => {
+            if let Some(character) = world.character_mut(*target_id) {
+                character.death_saves.reset();
+            }
+        }
+
+        Effect::CharacterDied { .. } => {
+            // Character death is tracked via the effect itself
+            // The UI/game can check for this effect and handle appropriately
+            // For now, we don't modify world state further (could add a `dead: bool` flag)
+        }
+
+        Effect::DeathSaveSuccess {
+            target_id,
+            total_successes,
+            ..
+        } => {
+            if let Some(character) = world.character_mut(*target_id) {
+                character.death_saves.successes = *total_successes;
+            }
+        }
+
+        Effect::Stabilized { target_id } => {
+            // Character is stable - still unconscious but no longer making death saves
+            let Some(character) = world.character_mut(*target_id) else {
+                return;
+            };
+            let death_saves = &mut character.death_saves;
+            death_saves.reset();
+            death_saves.stable = true;
+            // Note: Character remains Unconscious until healed
+        }
+
+        Effect::ConcentrationBroken { .. } => {
+            // Concentration tracking would be handled here if we had it
+            // For now, this is informational for the UI/narrative
+        }
+
+        Effect::ConcentrationMaintained { .. } => {
+            // Informational - concentration continues
+        }
+        Effect::LocationChanged {
+            new_location,
+            location_id,
+            ..
+        } => {
+            let entered = location_id.is_some_and(|id| world.enter_location(id));
+            if !entered {
+                world.current_location.name = new_location.clone();
+            }
+        }
+        Effect::LocationDiscovered { location } => {
+            world.known_locations.insert(location.id, location.clone());
+        }
+        Effect::LocationsConnected {
+            from,
+            to,
+            travel_time_minutes,
+        } => {
+            world.connect_locations(*from, *to, *travel_time_minutes);
+        }
+        Effect::QuestAdded { quest } => {
+            world.quests.push(quest.clone());
+        }
+        Effect::QuestStatusChanged {
+            quest_id, status, ..
+        } => {
+            if let Some(quest) = world.quests.iter_mut().find(|q| q.id == *quest_id) {
+                quest.status = *status;
+            }
+        }
+        Effect::ObjectiveCompleted {
+            quest_id,
+            objective_index,
+            ..
+        } => {
+            if let Some(objective) = world
+                .quests
+                .iter_mut()
+                .find(|q| q.id == *quest_id)
+                .and_then(|q| q.objectives.get_mut(*objective_index))
+            {
+                objective.completed = true;
+            }
+        }
+        Effect::ConsequenceRegistered { .. } => {
+            // Consequence storage is handled by the DM agent in story_memory
+            // This effect is informational for the rules layer
+        }
+        Effect::ConsequenceTriggered { .. } => {
+            // Consequence triggering is handled in story memory by the DM agent
+            // This effect is informational for the UI/narrative
+        }
+        Effect::ClassResourceUsed { .. } => {
+            // Class resource usage is tracked in ClassResources
+            // The actual state changes are handled by the DM based on the effect
+            // This effect is informational for the narrative/UI
+        }
+        Effect::RageStarted {
+            character_id,
+            damage_bonus,
+        } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                let resources = &mut character.class_resources;
+                resources.rage_active = true;
+                resources.rage_damage_bonus = *damage_bonus;
+                resources.rage_damage_taken = false;
+                resources.rage_rounds_remaining = Some(10); // 1 minute = 10 rounds
+            }
+        }
+        Effect::RageEnded { character_id, .. } => {
+            if let Some(character) = world.character_mut(*character_id) {
+                let resources = &mut character.class_resources;
+                resources.rage_active = false;
+                resources.rage_damage_taken = false;
+                resources.rage_damage_bonus = 0;
+                resources.rage_rounds_remaining = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::create_sample_fighter;
+
+    #[test]
+    fn test_skill_check() {
+        let character = create_sample_fighter("Roland");
+        let world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        let intent = Intent::SkillCheck {
+            character_id: world.player_character.id,
+            skill: Skill::Athletics,
+            dc: 15,
+            advantage: Advantage::Normal,
+            description: "Climbing a cliff".to_string(),
+            lighting: Lighting::Bright,
+        };
+
+        let resolution = engine.resolve(&world, intent);
+        assert!(!resolution.effects.is_empty());
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+    }
+
+    #[test]
+    fn test_damage() {
+        let character = create_sample_fighter("Roland");
+        let world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        let intent = Intent::Damage {
+            target_id: world.player_character.id,
+            amount: 10,
+            damage_type: DamageType::Slashing,
+            source: "Goblin".to_string(),
+        };
+
+        let resolution = engine.resolve(&world, intent);
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::HpChanged { amount, .. } if *amount == -10)));
+    }
+
+    #[test]
+    fn test_heal() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 10;
+        let world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        let intent = Intent::Heal {
+            target_id: world.player_character.id,
+            amount: 5,
+            source: "Healing Potion".to_string(),
+        };
+
+        let resolution = engine.resolve(&world, intent);
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::HpChanged { amount, .. } if *amount == 5)));
+    }
+
+    #[test]
+    fn test_apply_damage_effect() {
+        let character = create_sample_fighter("Roland");
+        let mut world = GameWorld::new("Test", character);
+        let initial_hp = world.player_character.hit_points.current;
+
+        let effect = Effect::HpChanged {
+            target_id: world.player_character.id,
+            amount: -10,
+            new_current: initial_hp - 10,
+            new_max: world.player_character.hit_points.maximum,
+            dropped_to_zero: false,
+        };
+
+        apply_effect(&mut world, &effect);
+        assert_eq!(world.player_character.hit_points.current, initial_hp - 10);
+    }
+
+    #[test]
+    fn test_healing_removes_unconscious() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 0;
+        character.conditions.push(crate::world::ActiveCondition::new(
+            Condition::Unconscious,
+            "Dropped to 0 HP",
+        ));
+        let mut world = GameWorld::new("Test", character);
+
+        // Verify character is unconscious
+        assert!(world
+            .player_character
+            .conditions
+            .iter()
+            .any(|c| c.condition == Condition::Unconscious));
+
+        // Apply healing effect
+        let effect = Effect::HpChanged {
+            target_id: world.player_character.id,
+            amount: 5,
+            new_current: 5,
+            new_max: world.player_character.hit_points.maximum,
+            dropped_to_zero: false,
+        };
+        apply_effect(&mut world, &effect);
+
+        // Verify unconscious is removed
+        assert!(!world
+            .player_character
+            .conditions
+            .iter()
+            .any(|c| c.condition == Condition::Unconscious));
+        assert_eq!(world.player_character.hit_points.current, 5);
+    }
+
+    #[test]
+    fn test_massive_damage_detection() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 10;
+        character.hit_points.maximum = 30;
+        let world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        // Damage that would cause instant death (10 current + 30 max = need 40+ damage)
+        let intent = Intent::Damage {
+            target_id: world.player_character.id,
+            amount: 50,
+            damage_type: DamageType::Slashing,
+            source: "Dragon".to_string(),
+        };
+
+        let resolution = engine.resolve(&world, intent);
+        // Should mention instant death in the narrative
+        assert!(resolution.narrative.contains("INSTANT DEATH"));
+    }
+
+    #[test]
+    fn test_start_combat() {
+        let character = create_sample_fighter("Roland");
+        let world = GameWorld::new("Test", character.clone());
+        let engine = RulesEngine::new();
+
+        let intent = Intent::StartCombat {
+            combatants: vec![CombatantInit {
+                id: character.id,
+                name: "Roland".to_string(),
+                is_player: true,
+                is_ally: true,
+                current_hp: character.hit_points.current,
+                max_hp: character.hit_points.maximum,
+                armor_class: character.current_ac(),
+                initiative_modifier: character.initiative_modifier(),
+                attack: None,
+                position: None,
+                size: Size::Medium,
+                speed: 30,
+                damage_traits: DamageTraits::default(),
+                senses: Senses::default(),
+            }],
+        };
+
+        let resolution = engine.resolve(&world, intent);
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::CombatStarted)));
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::InitiativeRolled { .. })));
+    }
+
+    /// Roland (initiative 20) against a goblin (initiative 10) with a scimitar.
+    fn goblin_fight(attack_bonus: i8) -> (GameWorld, CharacterId) {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let player = &world.player_character;
+        let roland = Combatant {
+            id: player.id,
+            name: player.name.clone(),
+            initiative: 20,
+            is_player: true,
+            is_ally: true,
+            current_hp: player.hit_points.current,
+            max_hp: player.hit_points.maximum,
+            armor_class: player.current_ac(),
+            attack: None,
+            position: None,
+            disengaged: false,
+            readied_reaction: None,
+            reaction_used: false,
+            action_used: false,
+            attacks_made: 0,
+            bonus_action_used: false,
+            movement_remaining: 30,
+            size: Size::Medium,
+            speed: 30,
+            mounted_on: None,
+            damage_traits: DamageTraits::default(),
+            senses: Senses::default(),
+            conditions: Vec::new(),
+        };
+        let goblin_id = CharacterId::new();
+        let combat = world.start_combat();
+        combat.add_combatant(roland);
+        combat.add_combatant(Combatant {
+            id: goblin_id,
+            name: "Goblin".to_string(),
+            initiative: 10,
+            is_player: false,
+            is_ally: false,
+            current_hp: 7,
+            max_hp: 7,
+            armor_class: 15,
+            attack: Some(CombatantAttack {
+                name: "Scimitar".to_string(),
+                attack_bonus,
+                damage_dice: "1d6+2".to_string(),
+                damage_type: DamageType::Slashing,
+                reach_feet: 5,
+            }),
+            position: None,
+            disengaged: false,
+            readied_reaction: None,
+            reaction_used: false,
+            action_used: false,
+            attacks_made: 0,
+            bonus_action_used: false,
+            movement_remaining: 30,
+            size: Size::Medium,
+            speed: 30,
+            mounted_on: None,
+            damage_traits: DamageTraits::default(),
+            senses: Senses::default(),
+            conditions: Vec::new(),
+        });
+        (world, goblin_id)
+    }
+
+    #[test]
+    fn test_next_turn_runs_enemy_attack() {
+        let (world, _) = goblin_fight(4);
+        let resolution = RulesEngine::new().resolve(&world, Intent::NextTurn);
+
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::TurnAdvanced { current_combatant, .. } if current_combatant == "Goblin")));
+        let attacks: Vec<_> = resolution
+            .effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::AttackHit { target_name, .. }
+                | Effect::AttackMissed { target_name, .. } => Some(target_name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(attacks, vec!["Roland"]);
+    }
+
+    #[test]
+    fn test_enemy_hit_damages_player() {
+        // Only a natural 1 misses with this bonus
+        let (mut world, goblin_id) = goblin_fight(40);
+        let resolution = (0..50)
+            .map(|_| {
+                RulesEngine::new().resolve(
+                    &world,
+                    Intent::EnemyTurn {
+                        combatant_id: goblin_id,
+                    },
+                )
+            })
+            .find(|r| {
+                r.effects
+                    .iter()
+                    .any(|e| matches!(e, Effect::AttackHit { .. }))
+            })
+            .expect("the goblin should hit at least once");
+
+        apply_effects(&mut world, &resolution.effects);
+        let hp = world.player_character.hit_points.current;
+        assert!(hp < 28, "the hit should deal damage");
+        assert!(hp >= 28 - 16, "at most a critical 2d6+2");
+    }
+
+    #[test]
+    fn test_player_turn_is_manual() {
+        let (mut world, _) = goblin_fight(4);
+        // Advance to the goblin, then past it back to Roland
+        world.combat.as_mut().unwrap().next_turn();
+        let resolution = RulesEngine::new().resolve(&world, Intent::NextTurn);
+
+        assert!(!resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::AttackHit { .. } | Effect::AttackMissed { .. })));
+
+        let player_id = world.player_character.id;
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::EnemyTurn {
+                combatant_id: player_id,
+            },
+        );
+        assert!(resolution.effects.is_empty());
+    }
+
+    /// How the d20 of every attack roll in a resolution was rolled.
+    fn attack_d20s(resolution: &Resolution) -> Vec<Advantage> {
+        resolution
+            .effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::DiceRolled { roll, purpose }
+                    if purpose.to_lowercase().contains("attack with") =>
+                {
+                    let d20 = &roll.component_results[0];
+                    let (low, high) = (d20.rolls.iter().min(), d20.rolls.iter().max());
+                    Some(match d20.rolls.len() {
+                        1 => Advantage::Normal,
+                        _ if low == high => return None,
+                        _ if d20.kept.first() == high => Advantage::Advantage,
+                        _ => Advantage::Disadvantage,
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Roland's attack on the goblin and the goblin's attack on Roland, each
+    /// repeated until the two d20s (if any) differ.
+    fn invisible_exchange(world: &GameWorld, goblin_id: CharacterId) -> (Advantage, Advantage) {
+        let roll = |intent: &dyn Fn() -> Intent| {
+            (0..100)
+                .find_map(|_| {
+                    let resolution = RulesEngine::new().resolve(world, intent());
+                    attack_d20s(&resolution).first().copied()
+                })
+                .expect("an attack roll")
+        };
+        let roland = roll(&|| Intent::Attack {
+            attacker_id: world.player_character.id,
+            target_id: goblin_id,
+            weapon_name: "Longsword".to_string(),
+            advantage: Advantage::Normal,
+            lighting: Lighting::Bright,
+        });
+        let goblin = roll(&|| Intent::EnemyTurn {
+            combatant_id: goblin_id,
+        });
+        (roland, goblin)
+    }
+
+    #[test]
+    fn test_invisibility_swings_attack_rolls() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        assert_eq!(
+            invisible_exchange(&world, goblin_id),
+            (Advantage::Normal, Advantage::Normal)
+        );
+
+        world
+            .player_character
+            .add_condition(Condition::Invisible, "Invisibility");
+        assert_eq!(
+            invisible_exchange(&world, goblin_id),
+            (Advantage::Advantage, Advantage::Disadvantage)
+        );
+    }
+
+    #[test]
+    fn test_blindsight_negates_invisibility() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        world
+            .player_character
+            .add_condition(Condition::Invisible, "Invisibility");
+        let combat = world.combat.as_mut().unwrap();
+        combat
+            .combatant_mut(goblin_id)
+            .unwrap()
+            .senses
+            .blindsight_ft = 10;
+
+        assert_eq!(
+            invisible_exchange(&world, goblin_id),
+            (Advantage::Normal, Advantage::Normal)
+        );
+    }
+
+    #[test]
+    fn test_invisible_goblin_is_hard_to_hit() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let combat = world.combat.as_mut().unwrap();
+        combat.combatant_mut(goblin_id).unwrap().conditions.push(
+            crate::world::ActiveCondition::new(Condition::Invisible, "Invisibility"),
+        );
+
+        assert_eq!(
+            invisible_exchange(&world, goblin_id),
+            (Advantage::Disadvantage, Advantage::Advantage)
+        );
+    }
+
+    #[test]
+    fn test_invisible_character_hides_automatically() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        world
+            .player_character
+            .add_condition(Condition::Invisible, "Invisibility");
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::SkillCheck {
+                character_id: world.player_character.id,
+                skill: Skill::Stealth,
+                dc: 30,
+                advantage: Advantage::Normal,
+                description: "Hide".to_string(),
+                lighting: Lighting::Bright,
+            },
+        );
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::CheckSucceeded { .. })));
+        assert!(!resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+    }
+
+    /// Go around a two-combatant fight, back to whoever's turn it is.
+    fn next_round(world: &mut GameWorld) {
+        let combat = world.combat.as_mut().unwrap();
+        combat.next_turn();
+        combat.next_turn();
+    }
+
+    fn count_attacks(resolution: &Resolution) -> usize {
+        resolution
+            .effects
+            .iter()
+            .filter(|e| matches!(e, Effect::AttackHit { .. } | Effect::AttackMissed { .. }))
+            .count()
+    }
+
+    #[test]
+    fn test_leaving_reach_provokes_opportunity_attack() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        let combat = world.combat.as_mut().unwrap();
+        combat.combatant_mut(player_id).unwrap().position = Some(GridPosition::new(0, 0));
+        combat.combatant_mut(goblin_id).unwrap().position = Some(GridPosition::new(1, 0));
+
+        let move_away = Intent::Move {
+            character_id: player_id,
+            destination: "the doorway".to_string(),
+            distance_feet: 15,
+            to_position: Some(GridPosition::new(-3, 0)),
+        };
+        let resolution = RulesEngine::new().resolve(&world, move_away.clone());
+        assert_eq!(count_attacks(&resolution), 1);
+        assert!(resolution.effects.iter().any(|e| matches!(
+            e,
+            Effect::CombatantMoved { id, position } if *id == player_id && *position == GridPosition::new(-3, 0)
+        )));
+
+        let disengage = RulesEngine::new().resolve(
+            &world,
+            Intent::Disengage {
+                combatant_id: player_id,
+                cost: ActionCost::Action,
+            },
+        );
+        for effect in &disengage.effects {
+            apply_effect(&mut world, effect);
+        }
+        let resolution = RulesEngine::new().resolve(&world, move_away);
+        assert_eq!(count_attacks(&resolution), 0);
+    }
+
+    #[test]
+    fn test_opportunity_attack_spends_the_reaction() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        let place_player_by_goblin = |world: &mut GameWorld| {
+            let combat = world.combat.as_mut().unwrap();
+            combat.combatant_mut(player_id).unwrap().position = Some(GridPosition::new(0, 0));
+            combat.combatant_mut(goblin_id).unwrap().position = Some(GridPosition::new(1, 0));
+        };
+        let move_away = Intent::Move {
+            character_id: player_id,
+            destination: "the doorway".to_string(),
+            distance_feet: 5,
+            to_position: Some(GridPosition::new(-1, 0)),
+        };
+
+        place_player_by_goblin(&mut world);
+        let first = RulesEngine::new().resolve(&world, move_away.clone());
+        assert_eq!(count_attacks(&first), 1);
+        assert!(first.effects.iter().any(|e| matches!(
+            e,
+            Effect::ReactionUsed { reactor_id, reaction: Reaction::OpportunityAttack }
+                if *reactor_id == goblin_id
+        )));
+        apply_effects(&mut world, &first.effects);
+
+        // Back into reach and out again: the goblin has no reaction left
+        place_player_by_goblin(&mut world);
+        let second = RulesEngine::new().resolve(&world, move_away.clone());
+        assert_eq!(count_attacks(&second), 0);
+
+        // The goblin's next turn gives it back
+        next_round(&mut world);
+        place_player_by_goblin(&mut world);
+        let third = RulesEngine::new().resolve(&world, move_away);
+        assert_eq!(count_attacks(&third), 1);
+    }
+
+    #[test]
+    fn test_moving_within_reach_provokes_nothing() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        let combat = world.combat.as_mut().unwrap();
+        combat.combatant_mut(player_id).unwrap().position = Some(GridPosition::new(0, 0));
+        combat.combatant_mut(goblin_id).unwrap().position = Some(GridPosition::new(1, 0));
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Move {
+                character_id: player_id,
+                destination: "the goblin's flank".to_string(),
+                distance_feet: 5,
+                to_position: Some(GridPosition::new(1, 1)),
+            },
+        );
+        assert_eq!(count_attacks(&resolution), 0);
+    }
+
+    /// Roland with Fireball and one 3rd- and 5th-level slot, in combat with
+    /// the named enemies (60 HP each).
+    fn fireball_fight(enemies: &[&str]) -> (GameWorld, Vec<CharacterId>) {
+        let mut caster = create_sample_fighter("Roland");
+        let mut slots = crate::world::SpellSlots::new();
+        slots.slots[2].total = 1;
+        slots.slots[4].total = 1;
+        caster.spellcasting = Some(crate::world::SpellcastingData {
+            ability: Ability::Intelligence,
+            spells_known: vec!["Fireball".to_string()],
+            spells_prepared: vec!["Fireball".to_string()],
+            cantrips_known: Vec::new(),
+            spell_slots: slots,
+            pact_slots: None,
+            prepared_since_rest: false,
+        });
+        let mut world = GameWorld::new("Test", caster);
+        let combat = world.start_combat();
+        let mut ids = Vec::new();
+        for name in enemies {
+            let id = CharacterId::new();
+            ids.push(id);
+            combat.add_combatant(Combatant {
+                id,
+                name: name.to_string(),
+                initiative: 10,
+                is_player: false,
+                is_ally: false,
+                current_hp: 60,
+                max_hp: 60,
+                armor_class: 13,
+                attack: None,
+                position: None,
+                disengaged: false,
+                readied_reaction: None,
+                reaction_used: false,
+                action_used: false,
+                attacks_made: 0,
+                bonus_action_used: false,
+                movement_remaining: 30,
+                size: Size::Medium,
+                speed: 30,
+                mounted_on: None,
+                damage_traits: DamageTraits::default(),
+                senses: Senses::default(),
+                conditions: Vec::new(),
+            });
+        }
+        (world, ids)
+    }
+
+    #[test]
+    fn test_area_spell_saves_per_target() {
+        let (mut world, goblin_ids) = fireball_fight(&["Goblin A", "Goblin B", "Goblin C"]);
+        let caster_id = world.player_character.id;
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::CastSpell {
+                caster_id,
+                spell_name: "Fireball".to_string(),
+                targets: goblin_ids[..1].to_vec(),
+                spell_level: 3,
+                target_names: vec!["Goblin B".to_string(), "Goblin C".to_string()],
+                ritual: false,
+            },
+        );
+
+        let full = resolution
+            .effects
+            .iter()
+            .find_map(|e| match e {
+                Effect::DiceRolled { roll, purpose } if purpose == "Fireball damage" => {
+                    Some(roll.total)
+                }
+                _ => None,
+            })
+            .expect("damage is rolled once");
+        let saves: Vec<bool> = resolution
+            .effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::CheckSucceeded { .. } => Some(true),
+                Effect::CheckFailed { .. } => Some(false),
+                _ => None,
+            })
+            .collect();
+        let damage: Vec<(CharacterId, i32)> = resolution
+            .effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::HpChanged {
+                    target_id, amount, ..
+                } => Some((*target_id, -amount)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(saves.len(), 3);
+        assert_eq!(damage.len(), 3);
+        for ((id, taken), saved) in damage.iter().zip(&saves) {
+            assert!(goblin_ids.contains(id));
+            assert_eq!(*taken, if *saved { full / 2 } else { full });
+        }
+
+        let player_hp = world.player_character.hit_points.current;
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.hit_points.current, player_hp);
+        let (hit_id, taken) = damage[0];
+        let goblin = world.combat.as_ref().unwrap().combatant(hit_id).unwrap();
+        assert_eq!(goblin.current_hp, 60 - taken);
+    }
+
+    #[test]
+    fn test_area_spell_hits_each_of_two_same_named_enemies() {
+        let (world, goblin_ids) = fireball_fight(&["Goblin", "Goblin"]);
+        let combat = world.combat.as_ref().unwrap();
+        assert_eq!(combat.combatant(goblin_ids[1]).unwrap().name, "Goblin 2");
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::CastSpell {
+                caster_id: world.player_character.id,
+                spell_name: "Fireball".to_string(),
+                targets: goblin_ids.clone(),
+                spell_level: 3,
+                target_names: Vec::new(),
+                ritual: false,
+            },
+        );
+        let mut hit: Vec<CharacterId> = resolution
+            .effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::HpChanged { target_id, .. } => Some(*target_id),
+                _ => None,
+            })
+            .collect();
+        hit.dedup();
+        assert_eq!(hit.len(), 2);
+        assert!(goblin_ids.iter().all(|id| hit.contains(id)));
+    }
+
+    fn cast_fireball(world: &GameWorld, slot: u8) -> Resolution {
+        RulesEngine::new().resolve(
+            world,
+            Intent::CastSpell {
+                caster_id: world.player_character.id,
+                spell_name: "Fireball".to_string(),
+                targets: Vec::new(),
+                spell_level: slot,
+                target_names: Vec::new(),
+                ritual: false,
+            },
+        )
+    }
+
+    fn ready_counterspell(world: &mut GameWorld, mage_id: CharacterId, slot_level: u8) {
+        let ready = RulesEngine::new().resolve(
+            world,
+            Intent::Reaction {
+                reactor_id: mage_id,
+                reaction: Reaction::Counterspell { slot_level },
+            },
+        );
+        apply_effects(world, &ready.effects);
+    }
+
+    fn fireball_damage_rolled(resolution: &Resolution) -> bool {
+        resolution.effects.iter().any(
+            |e| matches!(e, Effect::DiceRolled { purpose, .. } if purpose == "Fireball damage"),
+        )
+    }
+
+    #[test]
+    fn test_counterspell_at_equal_level_counters() {
+        let (mut world, ids) = fireball_fight(&["Cult Mage"]);
+        ready_counterspell(&mut world, ids[0], 3);
+
+        let resolution = cast_fireball(&world, 3);
+        assert!(!fireball_damage_rolled(&resolution));
+        assert!(!resolution.effects.iter().any(|e| matches!(
+            e,
+            Effect::CheckSucceeded { .. } | Effect::CheckFailed { .. }
+        )));
+        // The caster's slot is still spent
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::SpellSlotUsed { level: 3, .. })));
+
+        // One reaction per round: the mage can't ready another
+        apply_effects(&mut world, &resolution.effects);
+        let again = RulesEngine::new().resolve(
+            &world,
+            Intent::Reaction {
+                reactor_id: ids[0],
+                reaction: Reaction::Counterspell { slot_level: 3 },
+            },
+        );
+        assert!(again.effects.is_empty());
+        assert!(fireball_damage_rolled(&cast_fireball(&world, 5)));
+    }
+
+    #[test]
+    fn test_counterspell_against_higher_level_needs_check() {
+        let (mut world, ids) = fireball_fight(&["Cult Mage"]);
+        ready_counterspell(&mut world, ids[0], 3);
+
+        let resolution = cast_fireball(&world, 5);
+        let countered = resolution
+            .effects
+            .iter()
+            .find_map(|e| match e {
+                Effect::CheckSucceeded { check_type, dc, .. } if check_type == "Counterspell" => {
+                    Some((true, *dc))
+                }
+                Effect::CheckFailed { check_type, dc, .. } if check_type == "Counterspell" => {
+                    Some((false, *dc))
+                }
+                _ => None,
+            })
+            .expect("a higher-level spell requires the check");
+        assert_eq!(countered.1, 15);
+        assert_eq!(fireball_damage_rolled(&resolution), !countered.0);
+    }
+
+    #[test]
+    fn test_shield_turns_narrow_hit_into_miss() {
+        let ac = create_sample_fighter("Roland").current_ac();
+        let mut shielded = 0;
+        for _ in 0..200 {
+            // Rolls of 10-14 land in the window Shield can block
+            let (mut world, goblin_id) = goblin_fight(ac as i8 - 10);
+            let player_id = world.player_character.id;
+            world
+                .combat
+                .as_mut()
+                .unwrap()
+                .combatant_mut(player_id)
+                .unwrap()
+                .readied_reaction = Some(Reaction::Shield);
+
+            let resolution = RulesEngine::new().resolve(
+                &world,
+                Intent::EnemyTurn {
+                    combatant_id: goblin_id,
+                },
+            );
+            let used = resolution
+                .effects
+                .iter()
+                .any(|e| matches!(e, Effect::ReactionUsed { .. }));
+            for effect in &resolution.effects {
+                match effect {
+                    Effect::AttackHit {
+                        attack_roll,
+                        is_critical,
+                        ..
+                    } => assert!(!used && (*is_critical || *attack_roll >= ac as i32 + 5)),
+                    Effect::AttackMissed { target_ac, .. } if used => {
+                        assert_eq!(*target_ac, ac + 5)
+                    }
+                    _ => {}
+                }
+            }
+            shielded += used as u32;
+        }
+        assert!(shielded > 0);
+    }
+
+    /// A level 3 wizard (INT 10, so three prepared spells) with five spells
+    /// in the spellbook and four 1st-level slots.
+    fn sample_wizard() -> GameWorld {
+        let mut wizard = create_sample_fighter("Elminster");
+        wizard.classes[0].class = crate::world::CharacterClass::Wizard;
+        let mut slots = crate::world::SpellSlots::new();
+        slots.slots[0].total = 4;
+        wizard.spellcasting = Some(crate::world::SpellcastingData {
+            ability: Ability::Intelligence,
+            spells_known: [
+                "Magic Missile",
+                "Shield",
+                "Burning Hands",
+                "Sleep",
+                "Mage Armor",
+            ]
+            .map(String::from)
+            .to_vec(),
+            spells_prepared: Vec::new(),
+            cantrips_known: Vec::new(),
+            spell_slots: slots,
+            pact_slots: None,
+            prepared_since_rest: false,
+        });
+        GameWorld::new("Test", wizard)
+    }
+
+    fn prepare(world: &GameWorld, spells: &[&str]) -> Resolution {
+        RulesEngine::new().resolve(
+            world,
+            Intent::PrepareSpells {
+                character_id: world.player_character.id,
+                spells: spells.iter().map(|s| s.to_string()).collect(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_prepare_spells_respects_limit() {
+        let mut world = sample_wizard();
+        assert_eq!(world.player_character.prepared_spell_limit(), Some(3));
+
+        let too_many = prepare(&world, &["Magic Missile", "Shield", "Sleep", "Mage Armor"]);
+        assert!(too_many.effects.is_empty());
+        assert!(too_many.narrative.contains("at most 3"));
+
+        let resolution = prepare(&world, &["Magic Missile", "Shield", "Sleep"]);
+        apply_effects(&mut world, &resolution.effects);
+        let spellcasting = world.player_character.spellcasting.as_ref().unwrap();
+        assert_eq!(spellcasting.spells_prepared.len(), 3);
+
+        // The list is fixed until the next long rest
+        assert!(prepare(&world, &["Burning Hands"]).effects.is_empty());
+        world.long_rest();
+        assert!(!prepare(&world, &["Burning Hands"]).effects.is_empty());
+    }
+
+    #[test]
+    fn test_cannot_cast_unprepared_spell() {
+        let mut world = sample_wizard();
+        let resolution = prepare(&world, &["Magic Missile"]);
+        apply_effects(&mut world, &resolution.effects);
+
+        let cast = |world: &GameWorld, spell: &str| {
+            RulesEngine::new().resolve(
+                world,
+                Intent::CastSpell {
+                    caster_id: world.player_character.id,
+                    spell_name: spell.to_string(),
+                    targets: Vec::new(),
+                    spell_level: 1,
+                    target_names: Vec::new(),
+                    ritual: false,
+                },
+            )
+        };
+        let sleep = cast(&world, "Sleep");
+        assert!(sleep.effects.is_empty());
+        assert!(sleep.narrative.contains("hasn't prepared"));
+
+        let missile = cast(&world, "magic missile");
+        assert!(missile
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::SpellSlotUsed { level: 1, .. })));
+    }
+
+    fn cast_ritual(world: &GameWorld, spell: &str) -> Resolution {
+        RulesEngine::new().resolve(
+            world,
+            Intent::CastSpell {
+                caster_id: world.player_character.id,
+                spell_name: spell.to_string(),
+                targets: Vec::new(),
+                spell_level: 0,
+                target_names: Vec::new(),
+                ritual: true,
+            },
+        )
+    }
+
+    #[test]
+    fn test_ritual_casting_spends_time_not_slots() {
+        let mut world = sample_wizard();
+        world
+            .player_character
+            .spellcasting
+            .as_mut()
+            .unwrap()
+            .spells_known
+            .push("Detect Magic".to_string());
+        let start = world.game_time.clone();
+
+        // Wizards ritual-cast from the spellbook without preparing the spell
+        let resolution = cast_ritual(&world, "Detect Magic");
+        assert!(!resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::SpellSlotUsed { .. })));
+        apply_effects(&mut world, &resolution.effects);
+
+        let slots = &world
+            .player_character
+            .spellcasting
+            .as_ref()
+            .unwrap()
+            .spell_slots;
+        assert_eq!(slots.slots[0].used, 0);
+        let elapsed = (world.game_time.hour as i32 * 60 + world.game_time.minute as i32)
+            - (start.hour as i32 * 60 + start.minute as i32);
+        assert_eq!(elapsed, 10);
+    }
+
+    #[test]
+    fn test_ritual_refused_for_non_ritual_spell() {
+        let world = sample_wizard();
+        let resolution = cast_ritual(&world, "Magic Missile");
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("can't be cast as a ritual"));
+    }
+
+    #[test]
+    fn test_registered_magic_weapon_adds_bonus() {
+        use crate::items::StandardItem;
+        use crate::world::{WeaponDamageType, WeaponItem, WeaponProperty};
+
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        world.custom_items.register(StandardItem::Weapon(
+            WeaponItem::new("Longsword +1", "1d8", WeaponDamageType::Slashing)
+                .with_properties(vec![WeaponProperty::Versatile("1d10".to_string())])
+                .with_magic_bonus(1),
+        ));
+        // The registration is saved with the campaign
+        let mut world: GameWorld =
+            serde_json::from_value(serde_json::to_value(&world).unwrap()).unwrap();
+        let engine = RulesEngine::new();
+        let gift = engine.resolve(
+            &world,
+            Intent::AddItem {
+                character_id: world.player_character.id,
+                item_name: "Longsword +1".to_string(),
+                quantity: 1,
+                item_type: None,
+                description: None,
+                magical: false,
+                weight: None,
+                value_gp: None,
+            },
+        );
+        apply_effects(&mut world, &gift.effects);
+        let equip = engine.resolve(
+            &world,
+            Intent::EquipItem {
+                character_id: world.player_character.id,
+                item_name: "Longsword +1".to_string(),
+            },
+        );
+        apply_effects(&mut world, &equip.effects);
+        let weapon = world.player_character.equipment.main_hand.as_ref().unwrap();
+        assert_eq!(weapon.magic_bonus, 1);
+
+        let roland = &world.player_character;
+        let str_mod = roland.ability_scores.modifier(Ability::Strength) as i32;
+        let expected_attack = str_mod + roland.proficiency_bonus() as i32 + 1;
+        let mut damage_checked = false;
+        // Attack until one lands so the damage roll is checked too
+        for _ in 0..100 {
+            let resolution = engine.resolve(
+                &world,
+                Intent::Attack {
+                    attacker_id: roland.id,
+                    target_id: CharacterId::new(),
+                    weapon_name: "Longsword +1".to_string(),
+                    advantage: Advantage::Normal,
+                    lighting: Lighting::Bright,
+                },
+            );
+            let rolls: Vec<&RollResult> = resolution
+                .effects
+                .iter()
+                .filter_map(|e| match e {
+                    Effect::DiceRolled { roll, .. } => Some(roll),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(rolls[0].modifier, expected_attack);
+            if let Some(damage) = rolls.get(1) {
+                assert_eq!(damage.modifier, str_mod + 1);
+                damage_checked = true;
+                break;
+            }
+        }
+        assert!(damage_checked);
+    }
+
+    /// Attack with the character's weapon until a hit, returning the damage roll.
+    fn damage_roll_until_hit(world: &GameWorld, weapon_name: &str) -> RollResult {
+        for _ in 0..100 {
+            let resolution = RulesEngine::new().resolve(
+                world,
+                Intent::Attack {
+                    attacker_id: world.player_character.id,
+                    target_id: CharacterId::new(),
+                    weapon_name: weapon_name.to_string(),
+                    advantage: Advantage::Normal,
+                    lighting: Lighting::Bright,
+                },
+            );
+            let damage = resolution.effects.iter().find_map(|e| match e {
+                Effect::DiceRolled { roll, purpose } if purpose == "Damage" => Some(roll.clone()),
+                _ => None,
+            });
+            if let Some(damage) = damage {
+                return damage;
+            }
+        }
+        panic!("no attack hit in 100 tries");
+    }
+
+    #[test]
+    fn test_versatile_weapon_uses_two_handed_die() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        world.player_character.equipment.shield = None;
+        let damage = damage_roll_until_hit(&world, "Longsword");
+        assert_eq!(damage.expression.components[0].die_type, DieType::D10);
+
+        world.player_character.equipment.shield = crate::items::get_adventuring_gear("Shield");
+        let damage = damage_roll_until_hit(&world, "Longsword");
+        assert_eq!(damage.expression.components[0].die_type, DieType::D8);
+    }
+
+    #[test]
+    fn test_roll_dice() {
+        let character = create_sample_fighter("Roland");
+        let world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        let intent = Intent::RollDice {
+            notation: "2d6+3".to_string(),
+            purpose: "Damage".to_string(),
+        };
+
+        let resolution = engine.resolve(&world, intent);
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+    }
+
+    #[test]
+    fn test_damage_narrative_includes_hp_status() {
+        let character = create_sample_fighter("Roland");
+        let world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        let intent = Intent::Damage {
+            target_id: world.player_character.id,
+            amount: 5,
+            damage_type: DamageType::Slashing,
+            source: "Goblin".to_string(),
+        };
+
+        let resolution = engine.resolve(&world, intent);
+        // Narrative should include HP status
+        assert!(
+            resolution.narrative.contains("HP:"),
+            "Damage narrative should include HP status: {}",
+            resolution.narrative
+        );
+    }
+
+    #[test]
+    fn test_damage_narrative_shows_unconscious() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 5; // Low HP
+        let world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        let intent = Intent::Damage {
+            target_id: world.player_character.id,
+            amount: 10, // More than current HP
+            damage_type: DamageType::Slashing,
+            source: "Goblin".to_string(),
+        };
+
+        let resolution = engine.resolve(&world, intent);
+        // Narrative should indicate unconscious
+        assert!(
+            resolution.narrative.contains("UNCONSCIOUS"),
+            "Lethal damage narrative should indicate UNCONSCIOUS: {}",
+            resolution.narrative
+        );
+        // Effect should have dropped_to_zero
+        assert!(resolution.effects.iter().any(|e| matches!(
+            e,
+            Effect::HpChanged {
+                dropped_to_zero: true,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_short_rest_blocked_during_combat() {
+        let character = create_sample_fighter("Roland");
+        let mut world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        // Start combat (just need combat state to exist)
+        world.combat = Some(crate::world::CombatState::new());
+
+        let resolution = engine.resolve(&world, Intent::ShortRest);
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("Cannot take a short rest"));
+    }
+
+    #[test]
+    fn test_long_rest_blocked_during_combat() {
+        let character = create_sample_fighter("Roland");
+        let mut world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        // Start combat (just need combat state to exist)
+        world.combat = Some(crate::world::CombatState::new());
+
+        let resolution = engine.resolve(&world, Intent::LongRest);
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("Cannot take a long rest"));
+    }
+
+    #[test]
+    fn test_rest_allowed_outside_combat() {
+        let character = create_sample_fighter("Roland");
+        let world = GameWorld::new("Test", character);
+        let engine = RulesEngine::new();
+
+        // No combat active
+        assert!(world.combat.is_none());
+
+        let short_rest = engine.resolve(&world, Intent::ShortRest);
+        assert!(!short_rest.effects.is_empty());
+        assert!(short_rest
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::RestCompleted { rest_type: RestType::Short })));
+
+        let long_rest = engine.resolve(&world, Intent::LongRest);
+        assert!(!long_rest.effects.is_empty());
+        assert!(long_rest
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::RestCompleted { rest_type: RestType::Long })));
+    }
+
+    #[test]
+    fn test_unconscious_cannot_attack() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 0;
+        character.conditions.push(crate::world::ActiveCondition::new(
+            Condition::Unconscious,
+            "Dropped to 0 HP",
+        ));
+        let world = GameWorld::new("Test", character.clone());
+        let engine = RulesEngine::new();
+
+        let resolution = engine.resolve(
+            &world,
+            Intent::Attack {
+                attacker_id: character.id,
+                target_id: CharacterId::new(),
+                weapon_name: "Longsword".to_string(),
+                advantage: Advantage::Normal,
+                lighting: Lighting::Bright,
+            },
+        );
+
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("unconscious"));
+        assert!(resolution.narrative.contains("cannot attack"));
+    }
+
+    #[test]
+    fn test_unconscious_auto_fails_str_dex_checks() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 0;
+        character.conditions.push(crate::world::ActiveCondition::new(
+            Condition::Unconscious,
+            "Dropped to 0 HP",
+        ));
+        let world = GameWorld::new("Test", character.clone());
+        let engine = RulesEngine::new();
+
+        // Athletics is a Strength skill - should auto-fail
+        let athletics_check = engine.resolve(
+            &world,
+            Intent::SkillCheck {
+                character_id: character.id,
+                skill: Skill::Athletics,
+                dc: 10,
+                advantage: Advantage::Normal,
+                description: "Climbing".to_string(),
+                lighting: Lighting::Bright,
+            },
+        );
+        assert!(athletics_check.narrative.contains("unconscious"));
+        assert!(athletics_check.narrative.contains("automatically fails"));
+
+        // Acrobatics is a Dexterity skill - should auto-fail
+        let acrobatics_check = engine.resolve(
+            &world,
+            Intent::SkillCheck {
+                character_id: character.id,
+                skill: Skill::Acrobatics,
+                dc: 10,
+                advantage: Advantage::Normal,
+                description: "Tumbling".to_string(),
+                lighting: Lighting::Bright,
+            },
+        );
+        assert!(acrobatics_check.narrative.contains("unconscious"));
+        assert!(acrobatics_check.narrative.contains("automatically fails"));
+
+        // Perception is a Wisdom skill - should NOT auto-fail
+        let perception_check = engine.resolve(
+            &world,
+            Intent::SkillCheck {
+                character_id: character.id,
+                skill: Skill::Perception,
+                dc: 10,
+                advantage: Advantage::Normal,
+                description: "Noticing".to_string(),
+                lighting: Lighting::Bright,
+            },
+        );
+        // Should actually roll (won't auto-fail since it's Wisdom-based)
+        assert!(perception_check
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+
+        // Merely incapacitated characters still roll
+        let mut character = create_sample_fighter("Roland");
+        character
+            .conditions
+            .push(crate::world::ActiveCondition::new(
+                Condition::Incapacitated,
+                "Hideous Laughter",
+            ));
+        let world = GameWorld::new("Test", character.clone());
+        let athletics_check = engine.resolve(
+            &world,
+            Intent::SkillCheck {
+                character_id: character.id,
+                skill: Skill::Athletics,
+                dc: 10,
+                advantage: Advantage::Normal,
+                description: "Climbing".to_string(),
+                lighting: Lighting::Bright,
+            },
+        );
+        assert!(!athletics_check.narrative.contains("automatically fails"));
+    }
+
+    #[test]
+    fn test_unconscious_auto_fails_str_dex_saves() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 0;
+        character.conditions.push(crate::world::ActiveCondition::new(
+            Condition::Unconscious,
+            "Dropped to 0 HP",
+        ));
+        let world = GameWorld::new("Test", character.clone());
+        let engine = RulesEngine::new();
+
+        // Dexterity save - should auto-fail
+        let dex_save = engine.resolve(
+            &world,
+            Intent::SavingThrow {
+                character_id: character.id,
+                ability: Ability::Dexterity,
+                dc: 15,
+                advantage: Advantage::Normal,
+                source: "Fireball".to_string(),
+            },
+        );
+        assert!(dex_save.narrative.contains("unconscious"));
+        assert!(dex_save.narrative.contains("automatically fails"));
+
+        // Constitution save - should NOT auto-fail
+        let con_save = engine.resolve(
+            &world,
+            Intent::SavingThrow {
+                character_id: character.id,
+                ability: Ability::Constitution,
+                dc: 15,
+                advantage: Advantage::Normal,
+                source: "Poison".to_string(),
+            },
+        );
+        // Should actually roll
+        assert!(con_save
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+    }
+
+    #[test]
+    fn test_damage_at_zero_hp_causes_death_save_failure() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 0;
+        character.conditions.push(crate::world::ActiveCondition::new(
+            Condition::Unconscious,
+            "Dropped to 0 HP",
+        ));
+        let world = GameWorld::new("Test", character.clone());
+        let engine = RulesEngine::new();
+
+        let resolution = engine.resolve(
+            &world,
+            Intent::Damage {
+                target_id: character.id,
+                amount: 5,
+                damage_type: DamageType::Slashing,
+                source: "Goblin".to_string(),
+            },
+        );
+
+        // Should have death save failure effect
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::DeathSaveFailure { failures: 1, total_failures: 1, .. })));
+        assert!(resolution.narrative.contains("death save failure"));
+    }
+
+    #[test]
+    fn test_massive_damage_at_zero_hp_causes_instant_death() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 0;
+        character.hit_points.maximum = 30;
+        character.conditions.push(crate::world::ActiveCondition::new(
+            Condition::Unconscious,
+            "Dropped to 0 HP",
+        ));
+        let world = GameWorld::new("Test", character.clone());
+        let engine = RulesEngine::new();
+
+        // Damage >= max HP while at 0 HP = instant death
+        let resolution = engine.resolve(
+            &world,
+            Intent::Damage {
+                target_id: character.id,
+                amount: 30,
+                damage_type: DamageType::Slashing,
+                source: "Dragon".to_string(),
+            },
+        );
+
+        // Should have character died effect
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::CharacterDied { .. })));
+        assert!(resolution.narrative.contains("INSTANT DEATH"));
+    }
+
+    #[test]
+    fn test_healing_resets_death_saves() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 0;
+        character.death_saves.failures = 2; // 2 failures already
+        character.conditions.push(crate::world::ActiveCondition::new(
+            Condition::Unconscious,
+            "Dropped to 0 HP",
+        ));
+        let mut world = GameWorld::new("Test", character);
+
+        // Apply healing effect
+        let effect = Effect::HpChanged {
+            target_id: world.player_character.id,
+            amount: 5,
+            new_current: 5,
+            new_max: world.player_character.hit_points.maximum,
+            dropped_to_zero: false,
+        };
+        apply_effect(&mut world, &effect);
+
+        // Death saves should be reset
+        assert_eq!(world.player_character.death_saves.failures, 0);
+        assert_eq!(world.player_character.death_saves.successes, 0);
+        // Unconscious should be removed
+        assert!(!world
+            .player_character
+            .conditions
+            .iter()
+            .any(|c| c.condition == Condition::Unconscious));
+    }
+
+    #[test]
+    fn test_three_death_save_failures_causes_death() {
+        let mut character = create_sample_fighter("Roland");
+        character.hit_points.current = 0;
+        character.death_saves.failures = 2; // Already have 2 failures
+        character.conditions.push(crate::world::ActiveCondition::new(
+            Condition::Unconscious,
+            "Dropped to 0 HP",
+        ));
+        let world = GameWorld::new("Test", character.clone());
+        let engine = RulesEngine::new();
+
+        // Take damage at 0 HP - should cause 3rd failure and death
+        let resolution = engine.resolve(
+            &world,
+            Intent::Damage {
+                target_id: character.id,
+                amount: 5,
+                damage_type: DamageType::Slashing,
+                source: "Goblin".to_string(),
+            },
+        );
+
+        // Should have both death save failure and character died effects
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::DeathSaveFailure { total_failures: 3, .. })));
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::CharacterDied { .. })));
+        assert!(resolution.narrative.contains("DIES"));
+    }
+
+    /// Attack repeatedly until the attack roll shows the given natural d20 face.
+    fn attack_until_natural(world: &GameWorld, face: u32) -> Resolution {
+        let engine = RulesEngine::new();
+        for _ in 0..5000 {
+            let resolution = engine.resolve(
+                world,
+                Intent::Attack {
+                    attacker_id: world.player_character.id,
+                    target_id: CharacterId::new(),
+                    weapon_name: "Longsword".to_string(),
+                    advantage: Advantage::Normal,
+                    lighting: Lighting::Bright,
+                },
+            );
+            let rolled = resolution.effects.iter().any(|e| {
+                matches!(e, Effect::DiceRolled { roll, purpose }
+                    if purpose.starts_with("Attack") && roll.natural_d20() == Some(face))
+            });
+            if rolled {
+                return resolution;
+            }
+        }
+        panic!("never rolled a natural {face}");
+    }
+
+    #[test]
+    fn test_champion_crits_on_natural_19() {
+        let character = create_sample_fighter("Roland");
+        assert_eq!(character.crit_range(), 19);
+        let world = GameWorld::new("Test", character);
+
+        let resolution = attack_until_natural(&world, 19);
+        assert!(resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::AttackHit { is_critical: true, .. })));
+
+        // Longsword damage dice are doubled on the crit
+        let damage = resolution
+            .effects
+            .iter()
+            .find_map(|e| match e {
+                Effect::DiceRolled { roll, purpose } if purpose == "Damage" => Some(roll),
+                _ => None,
+            })
+            .expect("damage should be rolled");
+        assert_eq!(damage.component_results[0].rolls.len(), 2);
+    }
+
+    #[test]
+    fn test_normal_character_does_not_crit_on_natural_19() {
+        let mut character = create_sample_fighter("Roland");
+        character.classes[0].subclass = None;
+        assert_eq!(character.crit_range(), 20);
+        let world = GameWorld::new("Test", character);
+
+        let resolution = attack_until_natural(&world, 19);
+        assert!(!resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::AttackHit { is_critical: true, .. })));
+    }
+
+    fn exhausted_world(level: u8) -> GameWorld {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::AddExhaustion {
+                character_id: world.player_character.id,
+                levels: level,
+                source: "Forced march".to_string(),
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        world
+    }
+
+    /// Number of d20s rolled for the first dice roll in a resolution.
+    fn d20s_rolled(resolution: &Resolution) -> usize {
+        resolution
+            .effects
+            .iter()
+            .find_map(|e| match e {
+                Effect::DiceRolled { roll, .. } => Some(roll.component_results[0].rolls.len()),
+                _ => None,
+            })
+            .expect("a dice roll")
+    }
+
+    fn ability_check(world: &GameWorld) -> Resolution {
+        RulesEngine::new().resolve(
+            world,
+            Intent::AbilityCheck {
+                character_id: world.player_character.id,
+                ability: Ability::Strength,
+                dc: 10,
+                advantage: Advantage::Normal,
+                description: "Push".to_string(),
+            },
+        )
+    }
+
+    fn saving_throw(world: &GameWorld) -> Resolution {
+        RulesEngine::new().resolve(
+            world,
+            Intent::SavingThrow {
+                character_id: world.player_character.id,
+                ability: Ability::Constitution,
+                dc: 10,
+                advantage: Advantage::Normal,
+                source: "Poison".to_string(),
+            },
+        )
+    }
+
+    fn longsword_attack(world: &GameWorld) -> Resolution {
+        RulesEngine::new().resolve(
+            world,
+            Intent::Attack {
+                attacker_id: world.player_character.id,
+                target_id: CharacterId::new(),
+                weapon_name: "Longsword".to_string(),
+                advantage: Advantage::Normal,
+                lighting: Lighting::Bright,
+            },
+        )
+    }
+
+    #[test]
+    fn test_exhaustion_level_1_disadvantage_on_ability_checks() {
+        let world = exhausted_world(1);
+        assert_eq!(world.player_character.exhaustion_level(), 1);
+        assert_eq!(d20s_rolled(&ability_check(&world)), 2);
+        // Saves and attacks are unaffected until level 3
+        assert_eq!(d20s_rolled(&saving_throw(&world)), 1);
+        assert_eq!(d20s_rolled(&longsword_attack(&world)), 1);
+    }
+
+    #[test]
+    fn test_exhaustion_level_2_halves_speed() {
+        let world = exhausted_world(2);
+        let walk = world.player_character.speed.walk;
+        assert_eq!(world.player_character.effective_speed(), walk / 2);
+    }
+
+    #[test]
+    fn test_exhaustion_level_3_disadvantage_on_attacks_and_saves() {
+        let world = exhausted_world(3);
+        assert_eq!(d20s_rolled(&saving_throw(&world)), 2);
+        assert_eq!(d20s_rolled(&longsword_attack(&world)), 2);
+    }
+
+    #[test]
+    fn test_exhaustion_level_4_halves_hp_maximum() {
+        let mut world = exhausted_world(4);
+        assert_eq!(world.player_character.effective_max_hp(), 14);
+        assert_eq!(world.player_character.hit_points.current, 14);
+
+        // Healing cannot exceed the reduced maximum
+        world.player_character.hit_points.current = 10;
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Heal {
+                target_id: world.player_character.id,
+                amount: 20,
+                source: "Potion".to_string(),
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.hit_points.current, 14);
+    }
+
+    #[test]
+    fn test_exhaustion_level_5_reduces_speed_to_zero() {
+        let world = exhausted_world(5);
+        assert_eq!(world.player_character.effective_speed(), 0);
+    }
+
+    #[test]
+    fn test_exhaustion_level_6_causes_death() {
+        let world = exhausted_world(5);
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::AddExhaustion {
+                character_id: world.player_character.id,
+                levels: 1,
+                source: "Starvation".to_string(),
+            },
+        );
+        assert!(resolution.effects.iter().any(|e| matches!(
+            e,
+            Effect::CharacterDied { cause, .. } if cause == "Exhaustion"
+        )));
+    }
+
+    #[test]
+    fn test_remove_exhaustion_and_long_rest_recovery() {
+        let mut world = exhausted_world(3);
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::RemoveExhaustion {
+                character_id: world.player_character.id,
+                levels: 1,
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.exhaustion_level(), 2);
+
+        let resolution = RulesEngine::new().resolve(&world, Intent::LongRest);
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.exhaustion_level(), 1);
+    }
+
+    /// A character whose Athletics total always beats an untrained creature.
+    fn create_strongman() -> crate::world::Character {
+        let mut character = create_sample_fighter("Roland");
+        character.level = 20;
+        character.ability_scores.set(Ability::Strength, 30);
+        character
+            .skill_proficiencies
+            .insert(Skill::Athletics, crate::world::ProficiencyLevel::Expertise);
+        character
+    }
+
+    #[test]
+    fn test_grapple_stronger_grappler_wins() {
+        let world = GameWorld::new("Test", create_strongman());
+        let target_id = CharacterId::new();
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Grapple {
+                grappler_id: world.player_character.id,
+                target_id,
+            },
+        );
+
+        let rolls = resolution
+            .effects
+            .iter()
+            .filter(|e| matches!(e, Effect::DiceRolled { .. }))
+            .count();
+        assert_eq!(rolls, 2);
+        assert!(resolution.effects.iter().any(|e| matches!(
+            e,
+            Effect::ConditionApplied { target_id: id, condition: Condition::Grappled, .. }
+                if *id == target_id
+        )));
+        assert!(resolution.narrative.contains("Roland wins"));
+    }
+
+    #[test]
+    fn test_shove_defender_with_higher_athletics_holds_ground() {
+        let world = GameWorld::new("Test", create_strongman());
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Shove {
+                shover_id: CharacterId::new(),
+                target_id: world.player_character.id,
+                knock_prone: true,
+            },
+        );
+
+        assert!(!resolution
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::ConditionApplied { .. })));
+        assert!(resolution.narrative.contains("Roland wins"));
+    }
+
+    #[test]
+    fn test_shove_knocks_prone() {
+        let world = GameWorld::new("Test", create_strongman());
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Shove {
+                shover_id: world.player_character.id,
+                target_id: CharacterId::new(),
+                knock_prone: true,
+            },
+        );
+
+        assert!(resolution.effects.iter().any(|e| matches!(
+            e,
+            Effect::ConditionApplied {
+                condition: Condition::Prone,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_contest_tie_goes_to_defender() {
+        let contest = Contest {
+            attacker_name: "Roland".to_string(),
+            defender_name: "Goblin".to_string(),
+            attacker_total: 14,
+            defender_total: 14,
+            defender_skill: Skill::Acrobatics,
+            rolls: Vec::new(),
+        };
+        assert!(!contest.attacker_wins());
+        assert!(contest.summary().contains("Goblin wins"));
+    }
+
+    fn grant_temp_hp(world: &mut GameWorld, amount: i32) {
+        let resolution = RulesEngine::new().resolve(
+            world,
+            Intent::GrantTempHp {
+                target_id: world.player_character.id,
+                amount,
+                source: "False Life".to_string(),
+            },
+        );
+        apply_effects(world, &resolution.effects);
+    }
+
+    #[test]
+    fn test_temp_hp_keeps_higher_value() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+
+        grant_temp_hp(&mut world, 5);
+        assert_eq!(world.player_character.hit_points.temporary, 5);
+
+        // A smaller grant doesn't replace or add to the current pool
+        grant_temp_hp(&mut world, 3);
+        assert_eq!(world.player_character.hit_points.temporary, 5);
+
+        grant_temp_hp(&mut world, 8);
+        assert_eq!(world.player_character.hit_points.temporary, 8);
+    }
+
+    #[test]
+    fn test_temp_hp_depleted_before_real_hp() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        grant_temp_hp(&mut world, 5);
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Damage {
+                target_id: world.player_character.id,
+                amount: 8,
+                damage_type: DamageType::Slashing,
+                source: "Orc".to_string(),
+            },
+        );
+        assert!(resolution.narrative.contains("5 absorbed by temporary HP"));
+        apply_effects(&mut world, &resolution.effects);
+
+        assert_eq!(world.player_character.hit_points.temporary, 0);
+        assert_eq!(world.player_character.hit_points.current, 25);
+    }
+
+    #[test]
+    fn test_spend_hit_dice_heals_roll_plus_con() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        world.player_character.hit_points.current = 1;
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::SpendHitDice {
+                character_id: world.player_character.id,
+                count: 2,
+            },
+        );
+
+        let die_total: i32 = resolution
+            .effects
+            .iter()
+            .filter_map(|e| match e {
+                Effect::DiceRolled { roll, .. } => Some(roll.component_results[0].subtotal as i32),
+                _ => None,
+            })
+            .sum();
+        let rolls = resolution
+            .effects
+            .iter()
+            .filter(|e| matches!(e, Effect::DiceRolled { .. }))
+            .count();
+        assert_eq!(rolls, 2);
+
+        // Each d10 adds the +2 CON modifier
+        let expected = die_total + 2 * 2;
+        assert!(resolution.effects.iter().any(|e| matches!(
+            e,
+            Effect::HpChanged { amount, .. } if *amount == expected
+        )));
 
-            // Decrement condition durations and remove expired conditions
-            world.player_character.conditions.retain_mut(|c| {
-                if let Some(ref mut duration) = c.duration_rounds {
-                    if *duration > 0 {
-                        *duration -= 1;
-                    }
-                    *duration > 0 // Keep only if duration remaining
-                } else {
-                    true // Keep permanent conditions
-                }
-            });
-        }
-        Effect::TimeAdvanced { minutes } => {
-            world.game_time.advance_minutes(*minutes);
-        }
-        Effect::RestCompleted { rest_type } => match rest_type {
-            RestType::Short => world.short_rest(),
-            RestType::Long => world.long_rest(),
-        },
-        Effect::ExperienceGained { amount, .. } => {
-            world.player_character.experience += amount;
-        }
-        Effect::LevelUp { new_level } => {
-            world.player_character.level = *new_level;
-        }
-        Effect::FeatureUsed {
-            feature_name,
-            uses_remaining,
-        } => {
-            if let Some(feature) = world
-                .player_character
-                .features
-                .iter_mut()
-                .find(|f| f.name == *feature_name)
-            {
-                if let Some(ref mut uses) = feature.uses {
-                    uses.current = *uses_remaining;
-                }
-            }
-        }
-        Effect::SpellSlotUsed { level, .. } => {
-            if let Some(ref mut spellcasting) = world.player_character.spellcasting {
-                spellcasting.spell_slots.use_slot(*level);
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.hit_points.current, 1 + expected);
+        assert_eq!(world.player_character.hit_dice.total_remaining(), 1);
+    }
+
+    #[test]
+    fn test_spend_hit_dice_refused_when_empty() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        world
+            .player_character
+            .hit_dice
+            .remaining
+            .insert(DieType::D10, 0);
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::SpendHitDice {
+                character_id: world.player_character.id,
+                count: 1,
+            },
+        );
+
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("no hit dice remaining"));
+    }
+
+    #[test]
+    fn test_party_member_takes_damage_independently() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let companion_id = world.add_party_member(create_sample_fighter("Mira"));
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Damage {
+                target_id: companion_id,
+                amount: 10,
+                damage_type: DamageType::Piercing,
+                source: "Arrow".to_string(),
+            },
+        );
+        assert!(resolution.narrative.starts_with("Mira takes 10"));
+        apply_effects(&mut world, &resolution.effects);
+
+        assert_eq!(
+            world.character(companion_id).unwrap().hit_points.current,
+            18
+        );
+        assert_eq!(world.player_character.hit_points.current, 28);
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::ApplyCondition {
+                target_id: companion_id,
+                condition: Condition::Poisoned,
+                source: "Arrow".to_string(),
+                duration_rounds: None,
+                duration_minutes: None,
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        assert!(world
+            .character(companion_id)
+            .unwrap()
+            .has_condition(Condition::Poisoned));
+        assert!(!world.player_character.has_condition(Condition::Poisoned));
+    }
+
+    #[test]
+    fn test_party_member_keeps_their_own_gear_and_experience() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let companion_id = world.add_party_member(create_sample_fighter("Mira"));
+        let engine = RulesEngine::new();
+
+        let lantern = |character_id| Intent::AddItem {
+            character_id,
+            item_name: "Lantern".to_string(),
+            quantity: 1,
+            item_type: Some("adventuring".to_string()),
+            description: None,
+            magical: false,
+            weight: Some(2.0),
+            value_gp: Some(5.0),
+        };
+        let resolution = engine.resolve(&world, lantern(companion_id));
+        assert!(resolution.narrative.starts_with("Mira receives"));
+        apply_effects(&mut world, &resolution.effects);
+        let resolution = engine.resolve(
+            &world,
+            Intent::AdjustGold {
+                character_id: companion_id,
+                amount: 10.0,
+                reason: "for the road".to_string(),
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        let resolution = engine.resolve(
+            &world,
+            Intent::GainExperience {
+                character_id: companion_id,
+                amount: 2700,
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+
+        let mira = world.character(companion_id).unwrap();
+        assert!(mira.inventory.find_item("Lantern").is_some());
+        assert_eq!((mira.experience, mira.level), (2700, 4));
+        let roland = &world.player_character;
+        assert!(roland.inventory.find_item("Lantern").is_none());
+        assert_eq!(
+            mira.inventory.purse.total_gp(),
+            roland.inventory.purse.total_gp() + 10.0
+        );
+        assert_eq!((roland.experience, roland.level), (0, 3));
+
+        // Someone outside the party is refused rather than served as the player
+        let resolution = engine.resolve(&world, lantern(CharacterId::new()));
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("is in the party"));
+    }
+
+    #[test]
+    fn test_adjust_currency_makes_change() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        world.player_character.inventory.purse = Purse {
+            gp: 1,
+            ..Default::default()
+        };
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::AdjustCurrency {
+                character_id: world.player_character.id,
+                amount: -3,
+                coin: Coin::Silver,
+                reason: "for a pint".to_string(),
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(
+            world.player_character.inventory.purse,
+            Purse {
+                sp: 7,
+                ..Default::default()
             }
-        }
-        // Effects that don't modify state (informational)
-        Effect::DiceRolled { .. } => {}
-        Effect::CheckSucceeded { .. } => {}
-        Effect::CheckFailed { .. } => {}
-        Effect::AttackHit { .. } => {}
-        Effect::AttackMissed { .. } => {}
-        Effect::InitiativeRolled { .. } => {}
-        // FactRemembered is handled by the DM agent's memory system, not world state
-        Effect::FactRemembered { .. } => {}
+        );
 
-        // Inventory effects
-        Effect::ItemAdded {
-            item_name,
-            quantity,
-            ..
-        } => {
-            // Try to look up item from standard database first
-            let item = if let Some(standard_item) = crate::items::find_item(item_name) {
-                let mut item = standard_item.as_item();
-                item.quantity = *quantity;
-                item
-            } else {
-                // Fall back to generic item
-                Item {
-                    name: item_name.clone(),
-                    quantity: *quantity,
-                    weight: 0.0,
-                    value_gp: 0.0,
+        // Can't spend coins you don't have
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::AdjustCurrency {
+                character_id: world.player_character.id,
+                amount: -1,
+                coin: Coin::Platinum,
+                reason: "for a horse".to_string(),
+            },
+        );
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("can't pay"));
+    }
+
+    #[test]
+    fn test_add_item_warns_when_encumbered() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        world.player_character.ability_scores.strength = 10;
+        let add_rocks = |world: &GameWorld, quantity| {
+            RulesEngine::new().resolve(
+                world,
+                Intent::AddItem {
+                    character_id: world.player_character.id,
+                    item_name: "Rock".to_string(),
+                    quantity,
+                    item_type: None,
                     description: None,
-                    item_type: ItemType::Other,
                     magical: false,
-                }
-            };
-            world.player_character.inventory.add_item(item);
+                    weight: Some(10.0),
+                    value_gp: None,
+                },
+            )
+        };
+
+        let resolution = add_rocks(&world, 5);
+        assert!(!resolution.narrative.contains("encumbered"));
+
+        // The pickup still happens; the narrative just warns
+        let resolution = add_rocks(&world, 6);
+        assert!(resolution.narrative.contains("is now encumbered"));
+        assert!(!resolution.effects.is_empty());
+
+        // The rocks keep their weight once they're in the pack
+        apply_effects(&mut world, &resolution.effects);
+        let rock = world.player_character.inventory.find_item("Rock").unwrap();
+        assert_eq!(rock.weight, 10.0);
+        assert_eq!(
+            world.player_character.encumbrance_level(),
+            Encumbrance::Encumbered
+        );
+    }
+
+    fn attunement_ring(name: &str) -> Item {
+        Item {
+            name: name.to_string(),
+            quantity: 1,
+            weight: 0.0,
+            value_gp: 500.0,
+            description: None,
+            item_type: ItemType::Ring,
+            magical: true,
+            requires_attunement: true,
         }
-        Effect::ItemRemoved {
-            item_name,
-            quantity,
-            ..
-        } => {
+    }
+
+    #[test]
+    fn test_attunement_limited_to_three_items() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        for name in [
+            "Ring of Protection",
+            "Ring of Warmth",
+            "Ring of Swimming",
+            "Ring of Jumping",
+        ] {
             world
                 .player_character
                 .inventory
-                .remove_item(item_name, *quantity);
+                .add_item(attunement_ring(name));
         }
-        Effect::ItemEquipped { item_name, slot } => {
-            // Look up item from database for proper stats, fall back to defaults
-            match slot.as_str() {
-                "armor" => {
-                    if world.player_character.inventory.find_item(item_name).is_some() {
-                        // Try to get proper armor stats from database
-                        let armor = if let Some(db_armor) = crate::items::get_armor(item_name) {
-                            db_armor
-                        } else {
-                            // Fall back to medium armor defaults
-                            crate::world::ArmorItem::new(
-                                item_name.clone(),
-                                crate::world::ArmorType::Medium,
-                                14,
-                            )
-                        };
-                        world.player_character.equipment.armor = Some(armor);
-                        world.player_character.inventory.remove_item(item_name, 1);
-                    }
-                }
-                "shield" => {
-                    if let Some(item) = world.player_character.inventory.find_item(item_name) {
-                        let shield_item = item.clone();
-                        world.player_character.equipment.shield = Some(shield_item);
-                        world.player_character.inventory.remove_item(item_name, 1);
-                    }
-                }
-                "main_hand" | "weapon" => {
-                    if world.player_character.inventory.find_item(item_name).is_some() {
-                        // Try to get proper weapon stats from database
-                        let weapon = if let Some(db_weapon) = crate::items::get_weapon(item_name) {
-                            db_weapon
-                        } else {
-                            // Fall back to generic 1d8 slashing
-                            crate::world::WeaponItem::new(
-                                item_name.clone(),
-                                "1d8",
-                                crate::world::WeaponDamageType::Slashing,
-                            )
-                        };
-                        world.player_character.equipment.main_hand = Some(weapon);
-                        world.player_character.inventory.remove_item(item_name, 1);
-                    }
-                }
-                "off_hand" => {
-                    if let Some(item) = world.player_character.inventory.find_item(item_name) {
-                        let off_hand_item = item.clone();
-                        world.player_character.equipment.off_hand = Some(off_hand_item);
-                        world.player_character.inventory.remove_item(item_name, 1);
-                    }
-                }
-                _ => {}
-            }
+        let attune = |world: &GameWorld, name: &str| {
+            RulesEngine::new().resolve(
+                world,
+                Intent::Attune {
+                    character_id: world.player_character.id,
+                    item_name: name.to_string(),
+                },
+            )
+        };
+
+        for name in ["Ring of Protection", "Ring of Warmth", "Ring of Swimming"] {
+            let resolution = attune(&world, name);
+            apply_effects(&mut world, &resolution.effects);
         }
-        Effect::ItemUnequipped { slot, .. } => {
-            match slot.as_str() {
-                "armor" => {
-                    if let Some(armor) = world.player_character.equipment.armor.take() {
-                        world.player_character.inventory.add_item(armor.base);
-                    }
-                }
-                "shield" => {
-                    if let Some(shield) = world.player_character.equipment.shield.take() {
-                        world.player_character.inventory.add_item(shield);
-                    }
-                }
-                "main_hand" | "weapon" => {
-                    if let Some(weapon) = world.player_character.equipment.main_hand.take() {
-                        world.player_character.inventory.add_item(weapon.base);
-                    }
-                }
-                "off_hand" => {
-                    if let Some(item) = world.player_character.equipment.off_hand.take() {
-                        world.player_character.inventory.add_item(item);
-                    }
-                }
-                _ => {}
+        assert_eq!(world.player_character.attuned.len(), 3);
+
+        // A fourth attunement is refused
+        let resolution = attune(&world, "Ring of Jumping");
+        assert!(resolution.effects.is_empty());
+        assert!(!world.player_character.is_attuned_to("Ring of Jumping"));
+
+        // Ending one frees a slot
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::EndAttunement {
+                character_id: world.player_character.id,
+                item_name: "ring of warmth".to_string(),
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        let resolution = attune(&world, "Ring of Jumping");
+        apply_effects(&mut world, &resolution.effects);
+        assert!(world.player_character.is_attuned_to("Ring of Jumping"));
+        assert_eq!(world.player_character.attuned.len(), 3);
+
+        // Mundane items can't be attuned to
+        world.player_character.inventory.add_item(Item {
+            magical: false,
+            requires_attunement: false,
+            ..attunement_ring("Copper Ring")
+        });
+        world.player_character.attuned.clear();
+        let resolution = attune(&world, "Copper Ring");
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("not magical"));
+    }
+
+    #[test]
+    fn test_unattuned_armor_grants_no_bonus() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let plate =
+            crate::world::ArmorItem::new("Dragon Plate", crate::world::ArmorType::Heavy, 18)
+                .with_magic_bonus(2)
+                .requiring_attunement();
+        world.player_character.equipment.armor = Some(plate);
+        assert_eq!(world.player_character.current_ac(), 18);
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Attune {
+                character_id: world.player_character.id,
+                item_name: "Dragon Plate".to_string(),
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.current_ac(), 20);
+    }
+
+    fn perception_in(character: Character, lighting: Lighting) -> Resolution {
+        let world = GameWorld::new("Test", character);
+        RulesEngine::new().resolve(
+            &world,
+            Intent::SkillCheck {
+                character_id: world.player_character.id,
+                skill: Skill::Perception,
+                dc: 10,
+                advantage: Advantage::Normal,
+                description: "Scanning the cavern".to_string(),
+                lighting,
+            },
+        )
+    }
+
+    fn sample_dwarf() -> Character {
+        let mut dwarf = create_sample_fighter("Thorin");
+        dwarf.race_type = crate::world::RaceType::Dwarf;
+        dwarf.senses = crate::world::Senses::for_race(dwarf.race_type);
+        dwarf
+    }
+
+    #[test]
+    fn test_darkvision_ignores_dim_light_for_perception() {
+        let human = create_sample_fighter("Roland");
+        assert_eq!(
+            d20s_rolled(&perception_in(human.clone(), Lighting::Bright)),
+            1
+        );
+        assert_eq!(d20s_rolled(&perception_in(human, Lighting::Dim)), 2);
+
+        let dwarf = sample_dwarf();
+        assert_eq!(dwarf.senses.darkvision_ft, 60);
+        assert_eq!(d20s_rolled(&perception_in(dwarf.clone(), Lighting::Dim)), 1);
+        assert_eq!(d20s_rolled(&perception_in(dwarf, Lighting::Darkness)), 2);
+    }
+
+    #[test]
+    fn test_attacking_unseen_target_has_disadvantage() {
+        let attack_in = |character: Character| {
+            let world = GameWorld::new("Test", character);
+            RulesEngine::new().resolve(
+                &world,
+                Intent::Attack {
+                    attacker_id: world.player_character.id,
+                    target_id: CharacterId::new(),
+                    weapon_name: "Longsword".to_string(),
+                    advantage: Advantage::Normal,
+                    lighting: Lighting::Darkness,
+                },
+            )
+        };
+        assert_eq!(d20s_rolled(&attack_in(create_sample_fighter("Roland"))), 2);
+        assert_eq!(d20s_rolled(&attack_in(sample_dwarf())), 1);
+    }
+
+    fn resolve_inspiration(world: &mut GameWorld, grant: bool) -> Resolution {
+        let character_id = world.player_character.id;
+        let intent = if grant {
+            Intent::GrantInspiration { character_id }
+        } else {
+            Intent::UseInspiration { character_id }
+        };
+        let resolution = RulesEngine::new().resolve(world, intent);
+        apply_effects(world, &resolution.effects);
+        resolution
+    }
+
+    #[test]
+    fn test_granted_inspiration_gives_advantage_once() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        resolve_inspiration(&mut world, true);
+        assert!(world.player_character.has_inspiration);
+
+        resolve_inspiration(&mut world, false);
+        assert!(!world.player_character.has_inspiration);
+        assert!(world.player_character.inspiration_pending);
+
+        let check = ability_check(&world);
+        assert_eq!(d20s_rolled(&check), 2);
+        apply_effects(&mut world, &check.effects);
+        assert!(!world.player_character.inspiration_pending);
+        assert_eq!(d20s_rolled(&ability_check(&world)), 1);
+    }
+
+    #[test]
+    fn test_cannot_spend_missing_inspiration() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let resolution = resolve_inspiration(&mut world, false);
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("no Inspiration"));
+        assert!(!world.player_character.inspiration_pending);
+    }
+
+    #[test]
+    fn test_downed_player_rolls_death_saves_each_turn() {
+        let (mut world, _) = fireball_fight(&["Goblin"]);
+        world.player_character.hit_points.current = 0;
+        let player_id = world.player_character.id;
+        world.combat.as_mut().unwrap().add_combatant(Combatant {
+            id: player_id,
+            name: "Roland".to_string(),
+            initiative: 15,
+            is_player: true,
+            is_ally: false,
+            current_hp: 0,
+            max_hp: 28,
+            armor_class: 16,
+            attack: None,
+            position: None,
+            disengaged: false,
+            readied_reaction: None,
+            reaction_used: false,
+            action_used: false,
+            attacks_made: 0,
+            bonus_action_used: false,
+            movement_remaining: 30,
+            size: Size::Medium,
+            speed: 30,
+            mounted_on: None,
+            damage_traits: DamageTraits::default(),
+            senses: Senses::default(),
+            conditions: Vec::new(),
+        });
+
+        let engine = RulesEngine::new();
+        let mut saves_rolled = 0;
+        for _ in 0..20 {
+            let pc = &world.player_character;
+            let dying =
+                pc.hit_points.current == 0 && !pc.death_saves.stable && !pc.death_saves.is_dead();
+            let resolution = engine.resolve(&world, Intent::NextTurn);
+            apply_effects(&mut world, &resolution.effects);
+
+            let players_turn = world
+                .combat
+                .as_ref()
+                .unwrap()
+                .current_combatant()
+                .unwrap()
+                .id
+                == player_id;
+            let rolled = resolution.effects.iter().any(|e| {
+                matches!(
+                    e,
+                    Effect::DeathSaveSuccess { .. }
+                        | Effect::DeathSaveFailure { .. }
+                        | Effect::DeathSavesReset { .. }
+                )
+            });
+            assert_eq!(rolled, players_turn && dying);
+            if rolled {
+                saves_rolled += 1;
             }
         }
-        // ItemUsed is informational - the actual effects (healing, etc.) are separate effects
-        Effect::ItemUsed { .. } => {}
-        Effect::GoldChanged { new_total, .. } => {
-            world.player_character.inventory.gold = *new_total;
-        }
-        // AcChanged is informational - AC is recalculated from equipment
-        Effect::AcChanged { .. } => {}
 
-        Effect::DeathSaveFailure { failures, .. } => {
-            for _ in 0..*failures {
-                world.player_character.death_saves.add_failure();
-            }
-        }
+        // At most five saves settle it: stable, dead, or back on their feet
+        let pc = &world.player_character;
+        assert!((1..=5).contains(&saves_rolled));
+        assert!(pc.death_saves.stable || pc.death_saves.is_dead() || pc.hit_points.current > 0);
+    }
+
+    #[test]
+    fn test_fifty_foot_fall_rolls_five_d6() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::Fall {
+                character_id: world.player_character.id,
+                distance_feet: 50,
+            },
+        );
+        let roll = resolution
+            .effects
+            .iter()
+            .find_map(|e| match e {
+                Effect::DiceRolled { roll, .. } => Some(roll.clone()),
+                _ => None,
+            })
+            .expect("falling damage roll");
+        assert_eq!(roll.component_results[0].rolls.len(), 5);
+        assert_eq!(roll.component_results[0].die_type, DieType::D6);
+
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.hit_points.current, (28 - roll.total).max(0));
+        assert!(world.player_character.has_condition(Condition::Prone));
+    }
+
+    #[test]
+    fn test_suffocation_drops_character_after_breath_runs_out() {
+        // CON 14: hold breath 3 minutes (30 rounds), then 2 rounds of choking
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let suffocate = |world: &GameWorld, rounds| {
+            RulesEngine::new().resolve(
+                world,
+                Intent::Suffocate {
+                    character_id: world.player_character.id,
+                    rounds_without_air: rounds,
+                },
+            )
+        };
+        assert!(suffocate(&world, 30).effects.is_empty());
+        assert!(suffocate(&world, 32).effects.is_empty());
+
+        let resolution = suffocate(&world, 33);
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.hit_points.current, 0);
+        assert!(world.player_character.has_condition(Condition::Unconscious));
+    }
 
-        Effect::DeathSavesReset { .. } => {
-            world.player_character.death_saves.reset();
-        }
+    /// Roland mounted on a Large warhorse, with a Small goblin well out of reach.
+    fn cavalry_fight() -> (GameWorld, CharacterId, CharacterId) {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        let horse_id = CharacterId::new();
+        let combat = world.combat.as_mut().unwrap();
+        combat.add_combatant(Combatant {
+            id: horse_id,
+            name: "Warhorse".to_string(),
+            initiative: 5,
+            is_player: false,
+            is_ally: true,
+            current_hp: 19,
+            max_hp: 19,
+            armor_class: 11,
+            attack: None,
+            position: Some(GridPosition::new(0, 0)),
+            disengaged: false,
+            readied_reaction: None,
+            reaction_used: false,
+            action_used: false,
+            attacks_made: 0,
+            bonus_action_used: false,
+            movement_remaining: 60,
+            size: Size::Large,
+            speed: 60,
+            mounted_on: None,
+            damage_traits: DamageTraits::default(),
+            senses: Senses::default(),
+            conditions: Vec::new(),
+        });
+        combat.combatant_mut(player_id).unwrap().position = Some(GridPosition::new(0, 0));
+        let goblin = combat.combatant_mut(goblin_id).unwrap();
+        goblin.size = Size::Small;
+        goblin.position = Some(GridPosition::new(20, 0));
 
-        Effect::CharacterDied { .. } => {
-            // Character death is tracked via the effect itself
-            // The UI/game can check for this effect and handle appropriately
-            // For now, we don't modify world state further (could add a `dead: bool` flag)
-        }
+        let mount = RulesEngine::new().resolve(
+            &world,
+            Intent::Mount {
+                rider_id: player_id,
+                mount_id: horse_id,
+            },
+        );
+        apply_effects(&mut world, &mount.effects);
+        (world, horse_id, goblin_id)
+    }
 
-        Effect::DeathSaveSuccess { total_successes, .. } => {
-            world.player_character.death_saves.successes = *total_successes;
-        }
+    #[test]
+    fn test_rider_moves_at_mount_speed() {
+        let (mut world, horse_id, _) = cavalry_fight();
+        let player_id = world.player_character.id;
+        let combat = world.combat.as_ref().unwrap();
+        assert_eq!(
+            combat.combatant(player_id).unwrap().mounted_on,
+            Some(horse_id)
+        );
 
-        Effect::Stabilized { .. } => {
-            // Character is stable - still unconscious but no longer making death saves
-            world.player_character.death_saves.reset();
-            // Note: Character remains Unconscious until healed
+        let gallop = Intent::Move {
+            character_id: player_id,
+            destination: "the far hill".to_string(),
+            distance_feet: 60,
+            to_position: Some(GridPosition::new(12, 0)),
+        };
+        let resolution = RulesEngine::new().resolve(&world, gallop.clone());
+        apply_effects(&mut world, &resolution.effects);
+        let combat = world.combat.as_ref().unwrap();
+        for id in [player_id, horse_id] {
+            assert_eq!(
+                combat.combatant(id).unwrap().position,
+                Some(GridPosition::new(12, 0))
+            );
         }
 
-        Effect::ConcentrationBroken { .. } => {
-            // Concentration tracking would be handled here if we had it
-            // For now, this is informational for the UI/narrative
-        }
+        // On foot, Roland's 30 feet won't cover the same ground
+        let dismount = RulesEngine::new().resolve(
+            &world,
+            Intent::Dismount {
+                rider_id: player_id,
+            },
+        );
+        apply_effects(&mut world, &dismount.effects);
+        let resolution = RulesEngine::new().resolve(&world, gallop);
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("only move 30 feet"));
+    }
 
-        Effect::ConcentrationMaintained { .. } => {
-            // Informational - concentration continues
-        }
-        Effect::LocationChanged { new_location, .. } => {
-            world.current_location.name = new_location.clone();
-        }
-        Effect::ConsequenceRegistered { .. } => {
-            // Consequence storage is handled by the DM agent in story_memory
-            // This effect is informational for the rules layer
-        }
-        Effect::ConsequenceTriggered { .. } => {
-            // Consequence triggering is handled by the relevance checker
-            // This effect is informational for the UI/narrative
-        }
-        Effect::ClassResourceUsed { .. } => {
-            // Class resource usage is tracked in ClassResources
-            // The actual state changes are handled by the DM based on the effect
-            // This effect is informational for the narrative/UI
-        }
-        Effect::RageStarted { damage_bonus, .. } => {
-            world.player_character.class_resources.rage_active = true;
-            world.player_character.class_resources.rage_damage_bonus = *damage_bonus;
-            world.player_character.class_resources.rage_rounds_remaining = Some(10); // 1 minute = 10 rounds
-        }
-        Effect::RageEnded { .. } => {
-            world.player_character.class_resources.rage_active = false;
-            world.player_character.class_resources.rage_damage_bonus = 0;
-            world.player_character.class_resources.rage_rounds_remaining = None;
+    #[test]
+    fn test_mounted_rider_has_advantage_on_smaller_foe() {
+        let (world, _, goblin_id) = cavalry_fight();
+        let attack = RulesEngine::new().resolve(
+            &world,
+            Intent::Attack {
+                attacker_id: world.player_character.id,
+                target_id: goblin_id,
+                weapon_name: "Longsword".to_string(),
+                advantage: Advantage::Normal,
+                lighting: Lighting::Bright,
+            },
+        );
+        assert_eq!(d20s_rolled(&attack), 2);
+    }
+
+    #[test]
+    fn test_failed_save_throws_rider_from_prone_mount() {
+        for _ in 0..200 {
+            let (mut world, horse_id, _) = cavalry_fight();
+            let player_id = world.player_character.id;
+            let trip = RulesEngine::new().resolve(
+                &world,
+                Intent::ApplyCondition {
+                    target_id: horse_id,
+                    condition: Condition::Prone,
+                    source: "Tripwire".to_string(),
+                    duration_rounds: None,
+                    duration_minutes: None,
+                },
+            );
+            let failed = trip.effects.iter().any(
+                |e| matches!(e, Effect::CheckFailed { check_type, .. } if check_type == "DEX save"),
+            );
+            apply_effects(&mut world, &trip.effects);
+            let rider = world.combat.as_ref().unwrap().combatant(player_id).unwrap();
+            assert_eq!(rider.mounted_on.is_none(), failed);
+            if failed {
+                assert!(world.player_character.has_condition(Condition::Prone));
+                return;
+            }
         }
+        panic!("Roland never failed the save in 200 tries");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::world::create_sample_fighter;
+    /// Attack modifier on the d20 of a longsword attack.
+    fn attack_modifier(world: &GameWorld) -> i32 {
+        let attack = RulesEngine::new().resolve(
+            world,
+            Intent::Attack {
+                attacker_id: world.player_character.id,
+                target_id: CharacterId::new(),
+                weapon_name: "Longsword".to_string(),
+                advantage: Advantage::Normal,
+                lighting: Lighting::Bright,
+            },
+        );
+        attack
+            .effects
+            .iter()
+            .find_map(|e| match e {
+                Effect::DiceRolled { roll, .. } => Some(roll.modifier),
+                _ => None,
+            })
+            .expect("an attack roll")
+    }
 
     #[test]
-    fn test_skill_check() {
-        let character = create_sample_fighter("Roland");
-        let world = GameWorld::new("Test", character);
+    fn test_temporary_strength_buff_raises_attack_and_expires() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        assert_eq!(attack_modifier(&world), 5); // STR 16 (+3) + proficiency 2
+
+        let buff = RulesEngine::new().resolve(
+            &world,
+            Intent::ModifyAbility {
+                character_id: world.player_character.id,
+                ability: Ability::Strength,
+                delta: 2,
+                source: "Enhance Ability".to_string(),
+                duration_rounds: Some(2),
+            },
+        );
+        apply_effects(&mut world, &buff.effects);
+        assert_eq!(
+            world.player_character.effective_score(Ability::Strength),
+            18
+        );
+        assert_eq!(world.player_character.ability_scores.strength, 16);
+        assert_eq!(attack_modifier(&world), 6);
+
+        let turn = Effect::TurnAdvanced {
+            round: 1,
+            current_combatant: "Roland".to_string(),
+        };
+        apply_effect(&mut world, &turn);
+        assert_eq!(attack_modifier(&world), 6);
+        apply_effect(&mut world, &turn);
+        assert!(world.player_character.ability_modifiers.is_empty());
+        assert_eq!(attack_modifier(&world), 5);
+    }
+
+    #[test]
+    fn test_removing_ability_modifier_by_source() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let character_id = world.player_character.id;
+        let poison = RulesEngine::new().resolve(
+            &world,
+            Intent::ModifyAbility {
+                character_id,
+                ability: Ability::Strength,
+                delta: -4,
+                source: "Shadow poison".to_string(),
+                duration_rounds: None,
+            },
+        );
+        apply_effects(&mut world, &poison.effects);
+        assert_eq!(attack_modifier(&world), 3);
+
+        let cure = RulesEngine::new().resolve(
+            &world,
+            Intent::RemoveAbilityModifier {
+                character_id,
+                source: "shadow poison".to_string(),
+            },
+        );
+        apply_effects(&mut world, &cure.effects);
+        assert_eq!(attack_modifier(&world), 5);
+    }
+
+    #[test]
+    fn test_divine_smite_lands_on_the_hit_target_and_doubles_on_a_crit() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let goblin = world
+            .combat
+            .as_mut()
+            .unwrap()
+            .combatant_mut(goblin_id)
+            .unwrap();
+        goblin.current_hp = 200;
+        goblin.max_hp = 200;
         let engine = RulesEngine::new();
+        let roland = world.player_character.id;
+        let smite = Intent::UseDivineSmite {
+            character_id: roland,
+            spell_slot_level: 1,
+            target_is_undead_or_fiend: false,
+        };
 
-        let intent = Intent::SkillCheck {
-            character_id: world.player_character.id,
-            skill: Skill::Athletics,
-            dc: 15,
-            advantage: Advantage::Normal,
-            description: "Climbing a cliff".to_string(),
+        // Without a hit to ride on, nothing happens
+        assert!(engine.resolve(&world, smite.clone()).effects.is_empty());
+
+        let land_hit = |world: &mut GameWorld, want_critical: bool| {
+            for _ in 0..2000 {
+                next_round(world);
+                let attack = engine.resolve(
+                    world,
+                    Intent::Attack {
+                        attacker_id: roland,
+                        target_id: goblin_id,
+                        weapon_name: "Longsword".to_string(),
+                        advantage: Advantage::Normal,
+                        lighting: Lighting::Bright,
+                    },
+                );
+                apply_effects(world, &attack.effects);
+                let hit = world.combat.as_ref().unwrap().last_hit;
+                if hit.is_some_and(|hit| hit.is_critical == want_critical) {
+                    break;
+                }
+            }
         };
 
-        let resolution = engine.resolve(&world, intent);
-        assert!(!resolution.effects.is_empty());
+        for want_critical in [false, true] {
+            land_hit(&mut world, want_critical);
+            let hit = world.combat.as_ref().unwrap().last_hit.unwrap();
+            assert_eq!(hit.target_id, goblin_id);
+            assert_eq!(hit.is_critical, want_critical);
+
+            let before = world
+                .combat
+                .as_ref()
+                .unwrap()
+                .combatant(goblin_id)
+                .unwrap()
+                .current_hp;
+            let resolution = engine.resolve(&world, smite.clone());
+            let dice = d20s_rolled(&resolution);
+            assert_eq!(dice, if want_critical { 4 } else { 2 });
+            let rolled = resolution
+                .effects
+                .iter()
+                .find_map(|e| match e {
+                    Effect::DiceRolled { roll, .. } => Some(roll.total),
+                    _ => None,
+                })
+                .unwrap();
+            apply_effects(&mut world, &resolution.effects);
+            let after = world
+                .combat
+                .as_ref()
+                .unwrap()
+                .combatant(goblin_id)
+                .unwrap()
+                .current_hp;
+            assert_eq!(before - after, rolled);
+
+            // One hit carries one smite
+            assert!(world.combat.as_ref().unwrap().last_hit.is_none());
+            let again = engine.resolve(&world, smite.clone());
+            assert!(again.effects.is_empty());
+            assert!(again.narrative.contains("right after hitting"));
+        }
+
+        // Radiant resistance halves the smite
+        world
+            .combat
+            .as_mut()
+            .unwrap()
+            .combatant_mut(goblin_id)
+            .unwrap()
+            .damage_traits = DamageTraits {
+            resistances: vec![DamageType::Radiant],
+            ..Default::default()
+        };
+        land_hit(&mut world, false);
+        let resolution = engine.resolve(&world, smite);
+        let rolled = resolution
+            .effects
+            .iter()
+            .find_map(|e| match e {
+                Effect::DiceRolled { roll, .. } => Some(roll.total),
+                _ => None,
+            })
+            .unwrap();
         assert!(resolution
             .effects
             .iter()
-            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+            .any(|e| matches!(e, Effect::HpChanged { amount, .. } if *amount == -(rolled / 2))));
     }
 
-    #[test]
-    fn test_damage() {
-        let character = create_sample_fighter("Roland");
-        let world = GameWorld::new("Test", character);
-        let engine = RulesEngine::new();
-
-        let intent = Intent::Damage {
-            target_id: world.player_character.id,
-            amount: 10,
+    fn orc_fight() -> (GameWorld, CharacterId) {
+        let (mut world, orc_id) = goblin_fight(5);
+        let orc = world
+            .combat
+            .as_mut()
+            .unwrap()
+            .combatant_mut(orc_id)
+            .unwrap();
+        orc.name = "Orc".to_string();
+        orc.attack = Some(CombatantAttack {
+            name: "Greataxe".to_string(),
+            attack_bonus: 5,
+            damage_dice: "1d12+3".to_string(),
             damage_type: DamageType::Slashing,
-            source: "Goblin".to_string(),
+            reach_feet: 5,
+        });
+        (world, orc_id)
+    }
+
+    #[test]
+    fn test_orc_attacks_with_its_own_bonus_and_damage() {
+        let (mut world, orc_id) = orc_fight();
+        let turn = Intent::EnemyTurn {
+            combatant_id: orc_id,
         };
+        let resolution = (0..200)
+            .map(|_| RulesEngine::new().resolve(&world, turn.clone()))
+            .find(|r| {
+                r.effects.iter().any(|e| {
+                    matches!(
+                        e,
+                        Effect::AttackHit {
+                            is_critical: false,
+                            ..
+                        }
+                    )
+                })
+            })
+            .expect("the orc should land a regular hit");
 
-        let resolution = engine.resolve(&world, intent);
-        assert!(resolution
+        let rolls: Vec<&RollResult> = resolution
             .effects
             .iter()
-            .any(|e| matches!(e, Effect::HpChanged { amount, .. } if *amount == -10)));
+            .filter_map(|e| match e {
+                Effect::DiceRolled { roll, .. } => Some(roll),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rolls[0].modifier, 5);
+        assert!(resolution.narrative.contains("Greataxe"));
+        let damage = rolls[1].total;
+        assert!((4..=15).contains(&damage));
+
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.hit_points.current, 28 - damage);
     }
 
     #[test]
-    fn test_heal() {
+    fn test_combatant_conditions_shape_its_turn() {
+        let (mut world, orc_id) = orc_fight();
+        let turn = Intent::EnemyTurn {
+            combatant_id: orc_id,
+        };
+        apply_effect(
+            &mut world,
+            &Effect::ConditionApplied {
+                target_id: orc_id,
+                condition: Condition::Poisoned,
+                source: "Poisoned blade".to_string(),
+                duration_rounds: Some(1),
+                duration_minutes: None,
+            },
+        );
+        assert!(!world.player_character.has_condition(Condition::Poisoned));
+        assert_eq!(
+            d20s_rolled(&RulesEngine::new().resolve(&world, turn.clone())),
+            2
+        );
+
+        // The poison wears off as the orc's turn ends, then a stun costs
+        // the orc its next turn
+        for _ in 0..2 {
+            apply_effect(
+                &mut world,
+                &Effect::TurnAdvanced {
+                    round: 1,
+                    current_combatant: "Orc".to_string(),
+                },
+            );
+        }
+        assert_eq!(
+            d20s_rolled(&RulesEngine::new().resolve(&world, turn.clone())),
+            1
+        );
+        apply_effect(
+            &mut world,
+            &Effect::ConditionApplied {
+                target_id: orc_id,
+                condition: Condition::Stunned,
+                source: "Stunning Strike".to_string(),
+                duration_rounds: None,
+                duration_minutes: None,
+            },
+        );
+        let resolution = RulesEngine::new().resolve(&world, turn);
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("stunned"));
+    }
+
+    #[test]
+    fn test_flanking_grants_advantage_only_when_enabled_and_opposite() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let roland = world.player_character.id;
+        let combat = world.combat.as_mut().unwrap();
+        let mut lyra = combat.combatant(goblin_id).unwrap().clone();
+        lyra.id = CharacterId::new();
+        lyra.name = "Lyra".to_string();
+        lyra.is_ally = true;
+        let lyra_id = lyra.id;
+        combat.add_combatant(lyra);
+        combat.combatant_mut(roland).unwrap().position = Some(GridPosition::new(0, 0));
+        combat.combatant_mut(goblin_id).unwrap().position = Some(GridPosition::new(1, 0));
+        combat.combatant_mut(lyra_id).unwrap().position = Some(GridPosition::new(2, 0));
+        let attack = |world: &GameWorld| {
+            RulesEngine::new().resolve(
+                world,
+                Intent::Attack {
+                    attacker_id: roland,
+                    target_id: goblin_id,
+                    weapon_name: "Longsword".to_string(),
+                    advantage: Advantage::Normal,
+                    lighting: Lighting::Bright,
+                },
+            )
+        };
+
+        // Off by default, as in the core rules
+        assert_eq!(d20s_rolled(&attack(&world)), 1);
+
+        world.combat_rules.flanking = true;
+        assert_eq!(d20s_rolled(&attack(&world)), 2);
+
+        // Beside the goblin but not opposite Roland
+        let combat = world.combat.as_mut().unwrap();
+        combat.combatant_mut(lyra_id).unwrap().position = Some(GridPosition::new(1, 1));
+        assert_eq!(d20s_rolled(&attack(&world)), 1);
+    }
+
+    #[test]
+    fn test_two_handed_weapon_and_shield_conflict_both_ways() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let player_id = world.player_character.id;
+        let equip = |item_name: &str, slot: &str| Effect::ItemEquipped {
+            character_id: player_id,
+            item_name: item_name.to_string(),
+            slot: slot.to_string(),
+        };
+        let greatsword = crate::items::get_weapon("Greatsword").unwrap();
+        let shield = crate::items::get_adventuring_gear("Shield").unwrap();
+        world
+            .player_character
+            .inventory
+            .add_item(greatsword.base.clone());
+        world.player_character.equipment.shield = Some(shield.clone());
+
+        // With a shield on, resolve refuses the greatsword and so does apply
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::EquipItem {
+                character_id: world.player_character.id,
+                item_name: "Greatsword".to_string(),
+            },
+        );
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("requires two hands"));
+        apply_effect(&mut world, &equip("Greatsword", "main_hand"));
+        assert!(world.player_character.equipment.main_hand.is_none());
+        assert!(world.player_character.inventory.has_item("Greatsword"));
+
+        // With the greatsword in hand, the shield is refused the same way
+        world.player_character.equipment.shield = None;
+        world.player_character.inventory.add_item(shield);
+        apply_effect(&mut world, &equip("Greatsword", "main_hand"));
+        assert!(world.player_character.equipment.main_hand.is_some());
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::EquipItem {
+                character_id: world.player_character.id,
+                item_name: "Shield".to_string(),
+            },
+        );
+        assert!(resolution.effects.is_empty());
+        apply_effect(&mut world, &equip("Shield", "shield"));
+        assert!(world.player_character.equipment.shield.is_none());
+        assert_eq!(
+            world
+                .player_character
+                .equipment
+                .can_equip("shield", "Shield", &Default::default()),
+            Err(crate::world::EquipError::TwoHandedWeaponHeld {
+                item: "Shield".to_string(),
+                weapon: "Greatsword".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_equipping_plate_reports_the_new_ac() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        world.player_character.armor_class = crate::world::ArmorClass::unarmored();
+        let plate = crate::items::get_armor("Plate Armor").unwrap();
+        world.player_character.inventory.add_item(plate.base);
+        let ac_change = |resolution: &Resolution| {
+            resolution.effects.iter().find_map(|e| match e {
+                Effect::AcChanged { new_ac, .. } => Some(*new_ac),
+                _ => None,
+            })
+        };
+
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::EquipItem {
+                character_id: world.player_character.id,
+                item_name: "Plate Armor".to_string(),
+            },
+        );
+        assert_eq!(ac_change(&resolution), Some(18));
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.current_ac(), 18);
+
+        // Back to 10 + DEX
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::UnequipItem {
+                character_id: world.player_character.id,
+                slot: "armor".to_string(),
+            },
+        );
+        assert_eq!(ac_change(&resolution), Some(12));
+        apply_effects(&mut world, &resolution.effects);
+        assert_eq!(world.player_character.current_ac(), 12);
+    }
+
+    #[test]
+    fn test_attacks_fill_the_roll_log_in_order() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let engine = RulesEngine::new();
+        let mut expected = Vec::new();
+        for turn in 1..=3 {
+            next_round(&mut world);
+            world.add_narrative(
+                "I attack".to_string(),
+                crate::world::NarrativeType::PlayerAction,
+            );
+            let resolution = engine.resolve(
+                &world,
+                Intent::Attack {
+                    attacker_id: world.player_character.id,
+                    target_id: goblin_id,
+                    weapon_name: "Longsword".to_string(),
+                    advantage: Advantage::Normal,
+                    lighting: Lighting::Bright,
+                },
+            );
+            for effect in &resolution.effects {
+                if let Effect::DiceRolled { purpose, .. } = effect {
+                    expected.push((purpose.clone(), turn));
+                }
+            }
+            apply_effects(&mut world, &resolution.effects);
+        }
+
+        let logged: Vec<(String, usize)> = world
+            .roll_log
+            .iter()
+            .map(|r| (r.purpose.clone(), r.turn))
+            .collect();
+        assert_eq!(logged, expected);
+        let attacks = logged
+            .iter()
+            .filter(|(purpose, _)| purpose == "Attack with Longsword")
+            .count();
+        assert_eq!(attacks, 3);
+        assert_eq!(world.recent_rolls(1)[0].purpose, expected.last().unwrap().0);
+        assert_eq!(world.recent_rolls(100).len(), expected.len());
+
+        // The log drops its oldest rolls once full
+        let roll = dice::roll("1d20").unwrap();
+        for _ in 0..GameWorld::ROLL_LOG_LIMIT {
+            world.log_roll(roll.clone(), "Filler");
+        }
+        assert_eq!(world.roll_log.len(), GameWorld::ROLL_LOG_LIMIT);
+        assert!(world.roll_log.iter().all(|r| r.purpose == "Filler"));
+
+        // So does the narrative, which keeps its other entries
+        use crate::world::NarrativeType;
+        world.add_narrative("The goblin flees.".to_string(), NarrativeType::Combat);
+        for _ in 0..GameWorld::ROLL_LOG_LIMIT {
+            world.add_roll_narrative(roll.clone(), "Filler");
+        }
+        let rolls = world
+            .narrative_history
+            .iter()
+            .filter(|e| matches!(e.entry_type, NarrativeType::DiceRoll))
+            .count();
+        assert_eq!(rolls, GameWorld::ROLL_LOG_LIMIT);
+        assert_eq!(world.recent_narrative(1)[0].content, "The goblin flees.");
+    }
+
+    #[test]
+    fn test_fighter_level_5_gains_extra_attack_and_hp() {
         let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 10;
-        let world = GameWorld::new("Test", character);
+        character.level_up(4);
+        assert_eq!(character.pending_asi, 1);
+        let mut world = GameWorld::new("Test", character);
+        let max_hp = world.player_character.hit_points.maximum;
+        assert_eq!(world.player_character.proficiency_bonus(), 2);
+
+        let level_up = Effect::LevelUp {
+            character_id: world.player_character.id,
+            new_level: 5,
+        };
+        apply_effect(&mut world, &level_up);
+
+        let pc = &world.player_character;
+        assert_eq!(pc.level, 5);
+        assert_eq!(pc.classes[0].level, 5);
+        assert_eq!(pc.proficiency_bonus(), 3);
+        // d10 average (6) plus CON +2
+        assert_eq!(pc.hit_points.maximum, max_hp + 8);
+        assert!(pc.features.iter().any(|f| f.name == "Extra Attack"));
+        assert_eq!(pc.pending_asi, 1);
+    }
+
+    #[test]
+    fn test_resilient_con_adds_proficiency_to_concentration_checks() {
+        use crate::world::{AbilityScores, Background, CharacterClass, RaceType};
+
+        let mut wizard = crate::character_builder::CharacterBuilder::new()
+            .name("Elara")
+            .race(RaceType::Human)
+            .class(CharacterClass::Wizard)
+            .background(Background::Sage)
+            .ability_scores(AbilityScores::new(8, 14, 13, 15, 12, 10))
+            .skills(vec![Skill::Arcana, Skill::History])
+            .build()
+            .unwrap();
+        wizard.level_up(4);
+        let mut world = GameWorld::new("Test", wizard);
         let engine = RulesEngine::new();
+        let id = world.player_character.id;
+
+        // CON 14 (+2) after Human's +1 and no proficiency: totals go as low as 3
+        let concentration_totals = |world: &GameWorld| -> Vec<i32> {
+            (0..200)
+                .map(|_| {
+                    let resolution = engine.resolve(
+                        world,
+                        Intent::ConcentrationCheck {
+                            character_id: id,
+                            damage_taken: 4,
+                            spell_name: "Shield".to_string(),
+                        },
+                    );
+                    match &resolution.effects[0] {
+                        Effect::ConcentrationMaintained { roll, .. }
+                        | Effect::ConcentrationBroken { roll, .. } => *roll,
+                        other => panic!("unexpected effect {other:?}"),
+                    }
+                })
+                .collect()
+        };
+        assert!(concentration_totals(&world).iter().any(|&total| total < 5));
 
-        let intent = Intent::Heal {
-            target_id: world.player_character.id,
-            amount: 5,
-            source: "Healing Potion".to_string(),
+        let resolution = engine.resolve(
+            &world,
+            Intent::TakeFeat {
+                character_id: world.player_character.id,
+                feat_name: "resilient".to_string(),
+                ability: Some(Ability::Constitution),
+            },
+        );
+        apply_effects(&mut world, &resolution.effects);
+        let pc = &world.player_character;
+        assert!(pc.has_feat("Resilient"));
+        assert!(pc
+            .saving_throw_proficiencies
+            .contains(&Ability::Constitution));
+        assert_eq!(pc.pending_asi, 0);
+
+        // +2 CON and +2 proficiency
+        assert!(concentration_totals(&world).iter().all(|&total| total >= 5));
+
+        let again = engine.resolve(
+            &world,
+            Intent::TakeFeat {
+                character_id: world.player_character.id,
+                feat_name: "Lucky".to_string(),
+                ability: None,
+            },
+        );
+        assert!(again.effects.is_empty());
+    }
+
+    #[test]
+    fn test_wizard_greataxe_attack_lacks_proficiency() {
+        use crate::world::{AbilityScores, Background, CharacterClass, RaceType};
+
+        let mut wizard = crate::character_builder::CharacterBuilder::new()
+            .name("Elara")
+            .race(RaceType::Human)
+            .class(CharacterClass::Wizard)
+            .background(Background::Sage)
+            .ability_scores(AbilityScores::new(14, 14, 13, 15, 12, 10))
+            .skills(vec![Skill::Arcana, Skill::History])
+            .build()
+            .unwrap();
+        let str_mod = wizard.ability_scores.modifier(Ability::Strength) as i32;
+        let engine = RulesEngine::new();
+        let attack_modifier = |world: &GameWorld, weapon_name: &str| {
+            let resolution = engine.resolve(
+                world,
+                Intent::Attack {
+                    attacker_id: world.player_character.id,
+                    target_id: CharacterId::new(),
+                    weapon_name: weapon_name.to_string(),
+                    advantage: Advantage::Normal,
+                    lighting: Lighting::Bright,
+                },
+            );
+            resolution
+                .effects
+                .iter()
+                .find_map(|e| match e {
+                    Effect::DiceRolled { roll, .. } => Some(roll.modifier),
+                    _ => None,
+                })
+                .unwrap()
         };
 
-        let resolution = engine.resolve(&world, intent);
-        assert!(resolution
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::HpChanged { amount, .. } if *amount == 5)));
+        wizard.equipment.main_hand = crate::items::get_weapon("Greataxe");
+        let world = GameWorld::new("Test", wizard.clone());
+        assert_eq!(attack_modifier(&world, "Greataxe"), str_mod);
+
+        wizard.equipment.main_hand = crate::items::get_weapon("Quarterstaff");
+        let world = GameWorld::new("Test", wizard.clone());
+        assert_eq!(attack_modifier(&world, "Quarterstaff"), str_mod + 2);
+
+        // Chain mail without armor training hinders STR and DEX rolls
+        assert!(!wizard.wearing_untrained_armor());
+        wizard.equipment.armor = crate::items::get_armor("Chain Mail");
+        assert!(wizard.wearing_untrained_armor());
+        assert_eq!(
+            with_armor_training(&wizard, Ability::Dexterity, Advantage::Normal),
+            Advantage::Disadvantage
+        );
+        assert_eq!(
+            with_armor_training(&wizard, Ability::Intelligence, Advantage::Normal),
+            Advantage::Normal
+        );
+    }
+
+    fn attack_intent(attacker_id: CharacterId, target_id: CharacterId) -> Intent {
+        Intent::Attack {
+            attacker_id,
+            target_id,
+            weapon_name: "Longsword".to_string(),
+            advantage: Advantage::Normal,
+            lighting: Lighting::Bright,
+        }
     }
 
     #[test]
-    fn test_apply_damage_effect() {
-        let character = create_sample_fighter("Roland");
-        let mut world = GameWorld::new("Test", character);
-        let initial_hp = world.player_character.hit_points.current;
+    fn test_stunned_cannot_attack_or_cast() {
+        let mut character = create_sample_fighter("Roland");
+        character
+            .conditions
+            .push(crate::world::ActiveCondition::new(
+                Condition::Stunned,
+                "Stunning Strike",
+            ));
+        let world = GameWorld::new("Test", character.clone());
+        let engine = RulesEngine::new();
 
-        let effect = Effect::HpChanged {
-            target_id: world.player_character.id,
-            amount: -10,
-            new_current: initial_hp - 10,
-            new_max: world.player_character.hit_points.maximum,
-            dropped_to_zero: false,
-        };
+        let attack = engine.resolve(&world, attack_intent(character.id, CharacterId::new()));
+        assert!(attack.effects.is_empty());
+        assert!(attack.narrative.contains("stunned and cannot attack"));
 
-        apply_effect(&mut world, &effect);
-        assert_eq!(world.player_character.hit_points.current, initial_hp - 10);
+        let cast = engine.resolve(
+            &world,
+            Intent::CastSpell {
+                caster_id: character.id,
+                spell_name: "Fire Bolt".to_string(),
+                targets: vec![],
+                spell_level: 0,
+                target_names: vec![],
+                ritual: false,
+            },
+        );
+        assert!(cast.effects.is_empty());
+        assert!(cast.narrative.contains("cannot cast spells"));
     }
 
     #[test]
-    fn test_healing_removes_unconscious() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 0;
-        character.conditions.push(crate::world::ActiveCondition::new(
-            Condition::Unconscious,
-            "Dropped to 0 HP",
-        ));
-        let mut world = GameWorld::new("Test", character);
+    fn test_frightened_attacks_against_fear_source_have_disadvantage() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let roland = world.player_character.id;
+        let engine = RulesEngine::new();
+        assert_eq!(
+            d20s_rolled(&engine.resolve(&world, attack_intent(roland, goblin_id))),
+            1
+        );
 
-        // Verify character is unconscious
-        assert!(world
+        world
             .player_character
             .conditions
-            .iter()
-            .any(|c| c.condition == Condition::Unconscious));
+            .push(crate::world::ActiveCondition::new(
+                Condition::Frightened,
+                "The goblin's war cry",
+            ));
+        assert_eq!(
+            d20s_rolled(&engine.resolve(&world, attack_intent(roland, goblin_id))),
+            2
+        );
+        assert_eq!(
+            d20s_rolled(&engine.resolve(&world, attack_intent(roland, CharacterId::new()))),
+            1
+        );
 
-        // Apply healing effect
-        let effect = Effect::HpChanged {
-            target_id: world.player_character.id,
-            amount: 5,
-            new_current: 5,
-            new_max: world.player_character.hit_points.maximum,
-            dropped_to_zero: false,
-        };
-        apply_effect(&mut world, &effect);
+        // Charmed by the goblin: no attacking it at all
+        world.player_character.conditions = vec![crate::world::ActiveCondition::new(
+            Condition::Charmed,
+            "Goblin",
+        )];
+        let charmed = engine.resolve(&world, attack_intent(roland, goblin_id));
+        assert!(charmed.effects.is_empty());
+        assert!(charmed.narrative.contains("charmed by Goblin"));
+
+        // Only the goblin's whole name counts as the source
+        world.player_character.conditions = vec![crate::world::ActiveCondition::new(
+            Condition::Charmed,
+            "Goblinoid shaman",
+        )];
+        let free = engine.resolve(&world, attack_intent(roland, goblin_id));
+        assert!(!free.narrative.contains("charmed"));
+    }
 
-        // Verify unconscious is removed
-        assert!(!world
-            .player_character
-            .conditions
-            .iter()
-            .any(|c| c.condition == Condition::Unconscious));
-        assert_eq!(world.player_character.hit_points.current, 5);
+    fn move_intent(character_id: CharacterId, distance_feet: u32) -> Intent {
+        Intent::Move {
+            character_id,
+            destination: "the doorway".to_string(),
+            distance_feet,
+            to_position: None,
+        }
     }
 
     #[test]
-    fn test_massive_damage_detection() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 10;
-        character.hit_points.maximum = 30;
-        let world = GameWorld::new("Test", character);
-        let engine = RulesEngine::new();
-
-        // Damage that would cause instant death (10 current + 30 max = need 40+ damage)
-        let intent = Intent::Damage {
-            target_id: world.player_character.id,
-            amount: 50,
-            damage_type: DamageType::Slashing,
-            source: "Dragon".to_string(),
-        };
+    fn test_grappled_character_cannot_move() {
+        let (mut world, _) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        world
+            .player_character
+            .add_condition(Condition::Grappled, "Goblin");
 
-        let resolution = engine.resolve(&world, intent);
-        // Should mention instant death in the narrative
-        assert!(resolution.narrative.contains("INSTANT DEATH"));
+        let resolution = RulesEngine::new().resolve(&world, move_intent(player_id, 5));
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("can't move"));
     }
 
     #[test]
-    fn test_start_combat() {
-        let character = create_sample_fighter("Roland");
-        let world = GameWorld::new("Test", character.clone());
+    fn test_standing_from_prone_costs_half_movement() {
+        let (mut world, _) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        world
+            .player_character
+            .add_condition(Condition::Prone, "Tripped");
         let engine = RulesEngine::new();
 
-        let intent = Intent::StartCombat {
-            combatants: vec![CombatantInit {
-                id: character.id,
-                name: "Roland".to_string(),
-                is_player: true,
-                is_ally: true,
-                current_hp: character.hit_points.current,
-                max_hp: character.hit_points.maximum,
-                armor_class: character.current_ac(),
-                initiative_modifier: character.initiative_modifier(),
-            }],
-        };
+        let too_far = engine.resolve(&world, move_intent(player_id, 20));
+        assert!(too_far.effects.is_empty());
+        assert!(too_far.narrative.contains("15 feet"));
 
-        let resolution = engine.resolve(&world, intent);
-        assert!(resolution
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::CombatStarted)));
-        assert!(resolution
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::InitiativeRolled { .. })));
+        let resolution = engine.resolve(&world, move_intent(player_id, 15));
+        assert!(resolution.narrative.contains("stands up"));
+        apply_effects(&mut world, &resolution.effects);
+        assert!(!world.player_character.has_condition(Condition::Prone));
     }
 
     #[test]
-    fn test_roll_dice() {
-        let character = create_sample_fighter("Roland");
-        let world = GameWorld::new("Test", character);
+    fn test_one_action_per_turn() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        world.player_character.spellcasting = sample_wizard().player_character.spellcasting;
         let engine = RulesEngine::new();
-
-        let intent = Intent::RollDice {
-            notation: "2d6+3".to_string(),
-            purpose: "Damage".to_string(),
+        let magic_missile = Intent::CastSpell {
+            caster_id: player_id,
+            spell_name: "Magic Missile".to_string(),
+            targets: vec![goblin_id],
+            spell_level: 1,
+            target_names: vec!["Goblin".to_string()],
+            ritual: false,
         };
 
-        let resolution = engine.resolve(&world, intent);
-        assert!(resolution
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+        let attack = engine.resolve(&world, attack_intent(player_id, goblin_id));
+        apply_effects(&mut world, &attack.effects);
+        let second_attack = engine.resolve(&world, attack_intent(player_id, goblin_id));
+        assert!(second_attack.effects.is_empty());
+        let blocked = engine.resolve(&world, magic_missile.clone());
+        assert!(blocked.effects.is_empty());
+        assert!(blocked.narrative.contains("already used their action"));
+
+        next_round(&mut world);
+        let cast = engine.resolve(&world, magic_missile);
+        assert!(cast.effects.iter().any(|e| matches!(
+            e,
+            Effect::ActionUsed {
+                cost: ActionCost::Action,
+                ..
+            }
+        )));
     }
 
     #[test]
-    fn test_damage_narrative_includes_hp_status() {
-        let character = create_sample_fighter("Roland");
-        let world = GameWorld::new("Test", character);
+    fn test_action_surge_grants_a_fresh_attack_action() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let player_id = world.player_character.id;
         let engine = RulesEngine::new();
 
-        let intent = Intent::Damage {
-            target_id: world.player_character.id,
-            amount: 5,
-            damage_type: DamageType::Slashing,
-            source: "Goblin".to_string(),
-        };
+        let attack = engine.resolve(&world, attack_intent(player_id, goblin_id));
+        apply_effects(&mut world, &attack.effects);
+        assert!(engine
+            .resolve(&world, attack_intent(player_id, goblin_id))
+            .effects
+            .is_empty());
 
-        let resolution = engine.resolve(&world, intent);
-        // Narrative should include HP status
-        assert!(
-            resolution.narrative.contains("HP:"),
-            "Damage narrative should include HP status: {}",
-            resolution.narrative
+        let surge = engine.resolve(
+            &world,
+            Intent::UseActionSurge {
+                character_id: player_id,
+                action_taken: "Attack".to_string(),
+            },
         );
+        apply_effects(&mut world, &surge.effects);
+        let attack = engine.resolve(&world, attack_intent(player_id, goblin_id));
+        assert_eq!(count_attacks(&attack), 1);
     }
 
     #[test]
-    fn test_damage_narrative_shows_unconscious() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 5; // Low HP
-        let world = GameWorld::new("Test", character);
-        let engine = RulesEngine::new();
+    fn test_fighters_gain_more_attacks_at_11_and_20() {
+        let mut fighter = create_sample_fighter("Roland");
+        assert_eq!(fighter.attacks_per_action(), 1);
+        fighter.level_up(5);
+        assert_eq!(fighter.attacks_per_action(), 2);
+        fighter.classes[0].level = 11;
+        assert_eq!(fighter.attacks_per_action(), 3);
+        fighter.classes[0].level = 20;
+        assert_eq!(fighter.attacks_per_action(), 4);
+    }
 
-        let intent = Intent::Damage {
-            target_id: world.player_character.id,
-            amount: 10, // More than current HP
-            damage_type: DamageType::Slashing,
-            source: "Goblin".to_string(),
+    #[test]
+    fn test_bonus_action_disengage_needs_a_feature() {
+        let (mut world, _) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        let engine = RulesEngine::new();
+        let quick_disengage = Intent::Disengage {
+            combatant_id: player_id,
+            cost: ActionCost::BonusAction,
         };
 
-        let resolution = engine.resolve(&world, intent);
-        // Narrative should indicate unconscious
-        assert!(
-            resolution.narrative.contains("UNCONSCIOUS"),
-            "Lethal damage narrative should indicate UNCONSCIOUS: {}",
-            resolution.narrative
+        let refused = engine.resolve(&world, quick_disengage.clone());
+        assert!(refused.effects.is_empty());
+
+        world.player_character.features.extend(
+            crate::world::CharacterClass::Rogue
+                .features_at_level(2)
+                .into_iter()
+                .filter(|f| f.name == "Cunning Action"),
         );
-        // Effect should have dropped_to_zero
-        assert!(resolution.effects.iter().any(|e| matches!(
+        let disengage = engine.resolve(&world, quick_disengage);
+        assert!(disengage.effects.iter().any(|e| matches!(
             e,
-            Effect::HpChanged {
-                dropped_to_zero: true,
+            Effect::ActionUsed {
+                cost: ActionCost::BonusAction,
                 ..
             }
         )));
     }
 
     #[test]
-    fn test_short_rest_blocked_during_combat() {
-        let character = create_sample_fighter("Roland");
-        let mut world = GameWorld::new("Test", character);
+    fn test_cleric_with_shield_casts_through_holy_symbol() {
+        use crate::world::{AbilityScores, Background, CharacterClass, RaceType};
+
+        let mut cleric = crate::character_builder::CharacterBuilder::new()
+            .name("Brother Aldric")
+            .race(RaceType::Human)
+            .class(CharacterClass::Cleric)
+            .background(Background::Acolyte)
+            .ability_scores(AbilityScores::new(13, 10, 14, 8, 15, 12))
+            .skills(vec![Skill::Medicine, Skill::Religion])
+            .with_starting_equipment(Default::default())
+            .build()
+            .unwrap();
+        assert!(cleric.equipment.hands_full());
+        cleric.hit_points.current = 1;
+        let mut world = GameWorld::new("Test", cleric);
+        let cure_wounds = Intent::CastSpell {
+            caster_id: world.player_character.id,
+            spell_name: "Cure Wounds".to_string(),
+            targets: vec![world.player_character.id],
+            spell_level: 1,
+            target_names: vec![],
+            ritual: false,
+        };
         let engine = RulesEngine::new();
 
-        // Start combat (just need combat state to exist)
-        world.combat = Some(crate::world::CombatState::new());
+        let cast = engine.resolve(&world, cure_wounds.clone());
+        assert!(cast
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::SpellSlotUsed { level: 1, .. })));
 
-        let resolution = engine.resolve(&world, Intent::ShortRest);
-        assert!(resolution.effects.is_empty());
-        assert!(resolution.narrative.contains("Cannot take a short rest"));
+        world
+            .player_character
+            .inventory
+            .items
+            .retain(|i| i.name != "Holy Symbol");
+        let refused = engine.resolve(&world, cure_wounds);
+        assert!(refused.narrative.contains("needs a free hand"));
     }
 
     #[test]
-    fn test_long_rest_blocked_during_combat() {
-        let character = create_sample_fighter("Roland");
-        let mut world = GameWorld::new("Test", character);
+    fn test_warlock_casts_through_pact_slots() {
+        use crate::world::{AbilityScores, Background, CharacterClass, PactMagic, RaceType};
+
+        let mut warlock = crate::character_builder::CharacterBuilder::new()
+            .name("Morwen")
+            .race(RaceType::Human)
+            .class(CharacterClass::Warlock)
+            .background(Background::Charlatan)
+            .ability_scores(AbilityScores::new(8, 14, 13, 10, 12, 15))
+            .skills(vec![Skill::Arcana, Skill::Deception])
+            .build()
+            .unwrap();
+        warlock.level_up(5);
+        let mut world = GameWorld::new("Test", warlock);
+        let pact_slots = |world: &GameWorld| {
+            world
+                .player_character
+                .spellcasting
+                .as_ref()
+                .and_then(|sc| sc.pact_slots)
+                .unwrap()
+        };
+        assert_eq!(
+            pact_slots(&world),
+            PactMagic {
+                count: 2,
+                level: 3,
+                used: 0
+            }
+        );
+
+        // Hex goes off at the pact slot level, leaving standard slots alone
         let engine = RulesEngine::new();
+        let cast = engine.resolve(
+            &world,
+            Intent::CastSpell {
+                caster_id: world.player_character.id,
+                spell_name: "Hex".to_string(),
+                targets: vec![],
+                spell_level: 1,
+                target_names: vec![],
+                ritual: false,
+            },
+        );
+        assert!(cast
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::PactSlotUsed { level: 3, .. })));
+        assert!(!cast
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::SpellSlotUsed { .. })));
+        apply_effects(&mut world, &cast.effects);
+        assert_eq!(pact_slots(&world).available(), 1);
 
-        // Start combat (just need combat state to exist)
-        world.combat = Some(crate::world::CombatState::new());
+        world.short_rest();
+        assert_eq!(pact_slots(&world).available(), 2);
+    }
 
-        let resolution = engine.resolve(&world, Intent::LongRest);
-        assert!(resolution.effects.is_empty());
-        assert!(resolution.narrative.contains("Cannot take a long rest"));
+    /// A fighter/monk/barbarian who has spent ki, Action Surge, and a rage.
+    fn spent_resources_world() -> GameWorld {
+        use crate::world::{CharacterClass, ClassLevel, Feature, FeatureUses, RechargeType};
+
+        let mut character = create_sample_fighter("Kael");
+        for class in [CharacterClass::Monk, CharacterClass::Barbarian] {
+            character.classes.push(ClassLevel {
+                class,
+                level: 3,
+                subclass: None,
+            });
+        }
+        character.features.push(Feature {
+            name: "Rage".to_string(),
+            description: "Enter a rage as a bonus action.".to_string(),
+            source: "Barbarian".to_string(),
+            uses: Some(FeatureUses {
+                current: 0,
+                maximum: 2,
+                recharge: RechargeType::LongRest,
+            }),
+        });
+        let resources = &mut character.class_resources;
+        resources.max_ki_points = 3;
+        resources.action_surge_used = true;
+        GameWorld::new("Test", character)
+    }
+
+    fn rage_uses(world: &GameWorld) -> u8 {
+        world
+            .player_character
+            .features
+            .iter()
+            .find(|f| f.name == "Rage")
+            .and_then(|f| f.uses.as_ref())
+            .map_or(0, |uses| uses.current)
     }
 
     #[test]
-    fn test_rest_allowed_outside_combat() {
-        let character = create_sample_fighter("Roland");
-        let world = GameWorld::new("Test", character);
-        let engine = RulesEngine::new();
+    fn test_short_rest_restores_short_rest_resources() {
+        let mut world = spent_resources_world();
+        let rest = RulesEngine::new().resolve(&world, Intent::ShortRest);
+        apply_effects(&mut world, &rest.effects);
 
-        // No combat active
-        assert!(world.combat.is_none());
+        let resources = &world.player_character.class_resources;
+        assert_eq!(resources.ki_points, 3);
+        assert!(!resources.action_surge_used);
+        assert_eq!(rage_uses(&world), 0);
+    }
 
-        let short_rest = engine.resolve(&world, Intent::ShortRest);
-        assert!(!short_rest.effects.is_empty());
-        assert!(short_rest
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::RestCompleted { rest_type: RestType::Short })));
+    #[test]
+    fn test_long_rest_restores_all_resources() {
+        let mut world = spent_resources_world();
+        let rest = RulesEngine::new().resolve(&world, Intent::LongRest);
+        apply_effects(&mut world, &rest.effects);
 
-        let long_rest = engine.resolve(&world, Intent::LongRest);
-        assert!(!long_rest.effects.is_empty());
-        assert!(long_rest
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::RestCompleted { rest_type: RestType::Long })));
+        let resources = &world.player_character.class_resources;
+        assert_eq!(resources.ki_points, 3);
+        assert!(!resources.action_surge_used);
+        assert_eq!(rage_uses(&world), 2);
+    }
+
+    fn raging_goblin_fight() -> (GameWorld, CharacterId) {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let character_id = world.player_character.id;
+        apply_effect(
+            &mut world,
+            &Effect::RageStarted {
+                character_id,
+                damage_bonus: 2,
+            },
+        );
+        (world, goblin_id)
+    }
+
+    fn rage_ended_reason(resolution: &Resolution) -> Option<&str> {
+        resolution.effects.iter().find_map(|e| match e {
+            Effect::RageEnded { reason, .. } => Some(reason.as_str()),
+            _ => None,
+        })
     }
 
     #[test]
-    fn test_unconscious_cannot_attack() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 0;
-        character.conditions.push(crate::world::ActiveCondition::new(
-            Condition::Unconscious,
-            "Dropped to 0 HP",
-        ));
-        let world = GameWorld::new("Test", character.clone());
+    fn test_rage_ends_after_turn_without_attacking_or_damage() {
+        let (mut world, _) = raging_goblin_fight();
+        let resolution = RulesEngine::new().resolve(&world, Intent::NextTurn);
+        assert_eq!(
+            rage_ended_reason(&resolution),
+            Some("Rage ended (turn ended without attacking or taking damage).")
+        );
+        apply_effects(&mut world, &resolution.effects);
+        assert!(!world.player_character.class_resources.rage_active);
+    }
+
+    #[test]
+    fn test_rage_ends_after_ten_rounds() {
+        let (mut world, goblin_id) = raging_goblin_fight();
         let engine = RulesEngine::new();
+        let combat = world.combat.as_mut().unwrap();
+        combat.combatant_mut(goblin_id).unwrap().attack = None;
+
+        for round in 1..=10 {
+            // Taking a hit each round keeps the rage going
+            world.player_character.class_resources.rage_damage_taken = true;
+            let player_turn_ends = engine.resolve(&world, Intent::NextTurn);
+            if round < 10 {
+                assert_eq!(rage_ended_reason(&player_turn_ends), None);
+            } else {
+                assert_eq!(
+                    rage_ended_reason(&player_turn_ends),
+                    Some("Rage ended (1 minute duration expired).")
+                );
+            }
+            apply_effects(&mut world, &player_turn_ends.effects);
+            let goblin_turn_ends = engine.resolve(&world, Intent::NextTurn);
+            apply_effects(&mut world, &goblin_turn_ends.effects);
+        }
+        assert!(!world.player_character.class_resources.rage_active);
+    }
 
-        let resolution = engine.resolve(
+    #[test]
+    fn test_enemy_condition_lasts_its_rounds() {
+        let (mut world, goblin_id) = goblin_fight(4);
+        let frighten = RulesEngine::new().resolve(
             &world,
-            Intent::Attack {
-                attacker_id: character.id,
-                target_id: CharacterId::new(),
-                weapon_name: "Longsword".to_string(),
-                advantage: Advantage::Normal,
+            Intent::ApplyCondition {
+                target_id: goblin_id,
+                condition: Condition::Frightened,
+                source: "Roland".to_string(),
+                duration_rounds: Some(3),
+                duration_minutes: None,
             },
         );
+        assert!(frighten.narrative.starts_with("Goblin is now Frightened"));
+        apply_effects(&mut world, &frighten.effects);
+        assert!(!world.player_character.has_condition(Condition::Frightened));
 
-        assert!(resolution.effects.is_empty());
-        assert!(resolution.narrative.contains("unconscious"));
-        assert!(resolution.narrative.contains("cannot attack"));
+        // The fear counts down as each of the goblin's turns ends
+        let frightened = |world: &GameWorld| {
+            let combat = world.combat.as_ref().unwrap();
+            combat
+                .combatant(goblin_id)
+                .unwrap()
+                .has_condition(Condition::Frightened)
+        };
+        let turn = Effect::TurnAdvanced {
+            round: 1,
+            current_combatant: "Goblin".to_string(),
+        };
+        for _ in 0..5 {
+            apply_effect(&mut world, &turn);
+            assert!(frightened(&world));
+        }
+        apply_effect(&mut world, &turn);
+        assert!(!frightened(&world));
     }
 
     #[test]
-    fn test_unconscious_auto_fails_str_dex_checks() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 0;
-        character.conditions.push(crate::world::ActiveCondition::new(
-            Condition::Unconscious,
-            "Dropped to 0 HP",
-        ));
-        let world = GameWorld::new("Test", character.clone());
+    fn test_discovered_locations_can_be_traveled_between() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
         let engine = RulesEngine::new();
+        let village = world.current_location.id;
 
-        // Athletics is a Strength skill - should auto-fail
-        let athletics_check = engine.resolve(
+        let to_forest = engine.resolve(
             &world,
-            Intent::SkillCheck {
-                character_id: character.id,
-                skill: Skill::Athletics,
-                dc: 10,
-                advantage: Advantage::Normal,
-                description: "Climbing".to_string(),
+            Intent::ChangeLocation {
+                new_location: "Whispering Woods".to_string(),
+                location_type: Some("wilderness".to_string()),
+                description: Some("Old pines that creak in the wind".to_string()),
+                travel_time_minutes: Some(90),
             },
         );
-        assert!(athletics_check.narrative.contains("unconscious"));
-        assert!(athletics_check.narrative.contains("automatically fails"));
+        assert!(to_forest
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::TimeAdvanced { minutes: 90 })));
+        assert!(to_forest
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::LocationDiscovered { .. })));
+        apply_effects(&mut world, &to_forest.effects);
+        let forest = world.current_location.id;
+        assert_eq!(world.current_location.name, "Whispering Woods");
+        assert!(world.current_location.connection_to(village).is_some());
 
-        // Acrobatics is a Dexterity skill - should auto-fail
-        let acrobatics_check = engine.resolve(
+        let to_cave = engine.resolve(
             &world,
-            Intent::SkillCheck {
-                character_id: character.id,
-                skill: Skill::Acrobatics,
-                dc: 10,
-                advantage: Advantage::Normal,
-                description: "Tumbling".to_string(),
+            Intent::ChangeLocation {
+                new_location: "Goblin Cave".to_string(),
+                location_type: Some("cave".to_string()),
+                description: None,
+                travel_time_minutes: None,
             },
         );
-        assert!(acrobatics_check.narrative.contains("unconscious"));
-        assert!(acrobatics_check.narrative.contains("automatically fails"));
+        apply_effects(&mut world, &to_cave.effects);
+        let cave = world.current_location.id;
+        assert_eq!(world.known_locations.len(), 3);
+        assert!(world
+            .location(forest)
+            .unwrap()
+            .connection_to(cave)
+            .is_some());
+
+        // Only the forest leads back to the village
+        let shortcut = engine.resolve(&world, Intent::Travel { to: village });
+        assert!(shortcut.effects.is_empty());
+        assert!(shortcut.narrative.contains("no known way"));
+
+        let back = engine.resolve(&world, Intent::Travel { to: forest });
+        assert_eq!(
+            back.narrative,
+            "You travel from Goblin Cave to Whispering Woods."
+        );
+        apply_effects(&mut world, &back.effects);
+        assert_eq!(world.current_location.id, forest);
+        assert_eq!(
+            world.current_location.description,
+            "Old pines that creak in the wind"
+        );
 
-        // Perception is a Wisdom skill - should NOT auto-fail
-        let perception_check = engine.resolve(
+        // Going somewhere known by name follows the map instead of adding a place
+        let home = engine.resolve(
             &world,
-            Intent::SkillCheck {
-                character_id: character.id,
-                skill: Skill::Perception,
-                dc: 10,
-                advantage: Advantage::Normal,
-                description: "Noticing".to_string(),
+            Intent::ChangeLocation {
+                new_location: "starting location".to_string(),
+                location_type: None,
+                description: None,
+                travel_time_minutes: None,
             },
         );
-        // Should actually roll (won't auto-fail since it's Wisdom-based)
-        assert!(perception_check
+        // The way home takes as long as the way out did
+        assert!(home
             .effects
             .iter()
-            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+            .any(|e| matches!(e, Effect::TimeAdvanced { minutes: 90 })));
+        assert!(!home.effects.iter().any(|e| matches!(
+            e,
+            Effect::LocationDiscovered { .. } | Effect::LocationsConnected { .. }
+        )));
+        apply_effects(&mut world, &home.effects);
+        assert_eq!(world.current_location.id, village);
+        assert_eq!(world.known_locations.len(), 3);
     }
 
     #[test]
-    fn test_unconscious_auto_fails_str_dex_saves() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 0;
-        character.conditions.push(crate::world::ActiveCondition::new(
-            Condition::Unconscious,
-            "Dropped to 0 HP",
-        ));
-        let world = GameWorld::new("Test", character.clone());
+    fn test_timed_condition_wears_off_with_game_time() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
         let engine = RulesEngine::new();
-
-        // Dexterity save - should auto-fail
-        let dex_save = engine.resolve(
+        let poison = engine.resolve(
             &world,
-            Intent::SavingThrow {
-                character_id: character.id,
-                ability: Ability::Dexterity,
-                dc: 15,
-                advantage: Advantage::Normal,
-                source: "Fireball".to_string(),
+            Intent::ApplyCondition {
+                target_id: world.player_character.id,
+                condition: Condition::Poisoned,
+                source: "Tainted wine".to_string(),
+                duration_rounds: None,
+                duration_minutes: Some(10),
             },
         );
-        assert!(dex_save.narrative.contains("unconscious"));
-        assert!(dex_save.narrative.contains("automatically fails"));
+        assert!(poison.narrative.ends_with("for 10 minutes"));
+        apply_effects(&mut world, &poison.effects);
+        world
+            .player_character
+            .add_condition(Condition::Deafened, "Thunderclap");
 
-        // Constitution save - should NOT auto-fail
-        let con_save = engine.resolve(
-            &world,
-            Intent::SavingThrow {
-                character_id: character.id,
-                ability: Ability::Constitution,
-                dc: 15,
-                advantage: Advantage::Normal,
-                source: "Poison".to_string(),
-            },
+        let wait = engine.resolve(&world, Intent::AdvanceTime { minutes: 5 });
+        apply_effects(&mut world, &wait.effects);
+        assert!(world.player_character.has_condition(Condition::Poisoned));
+
+        apply_effects(&mut world, &wait.effects);
+        assert!(!world.player_character.has_condition(Condition::Poisoned));
+        // Conditions without a duration are left alone
+        assert!(world.player_character.has_condition(Condition::Deafened));
+
+        // Resting passes time the same way, once
+        world.player_character.conditions.push(
+            crate::world::ActiveCondition::new(Condition::Blinded, "Smoke")
+                .with_duration_minutes(60),
         );
-        // Should actually roll
-        assert!(con_save
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::DiceRolled { .. })));
+        let hour = world.game_time.hour;
+        let rest = engine.resolve(&world, Intent::ShortRest);
+        apply_effects(&mut world, &rest.effects);
+        assert!(!world.player_character.has_condition(Condition::Blinded));
+        assert_eq!(world.game_time.hour, (hour + 1) % 24);
     }
 
     #[test]
-    fn test_damage_at_zero_hp_causes_death_save_failure() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 0;
-        character.conditions.push(crate::world::ActiveCondition::new(
-            Condition::Unconscious,
-            "Dropped to 0 HP",
-        ));
-        let world = GameWorld::new("Test", character.clone());
-        let engine = RulesEngine::new();
+    fn test_minute_condition_counts_down_in_combat() {
+        let (mut world, _) = goblin_fight(4);
+        let player_id = world.player_character.id;
+        world.player_character.conditions.push(
+            crate::world::ActiveCondition::new(Condition::Poisoned, "Tainted wine")
+                .with_duration_minutes(1),
+        );
+        // Ten of the player's turns make a minute
+        let combat = world.combat.as_mut().unwrap();
+        while combat.current_combatant().map(|c| c.id) != Some(player_id) {
+            combat.next_turn();
+        }
+        let turn = Effect::TurnAdvanced {
+            round: 1,
+            current_combatant: "Roland".to_string(),
+        };
+        for _ in 0..9 {
+            apply_effect(&mut world, &turn);
+            apply_effect(&mut world, &turn);
+            assert!(world.player_character.has_condition(Condition::Poisoned));
+        }
+        apply_effect(&mut world, &turn);
+        apply_effect(&mut world, &turn);
+        assert!(!world.player_character.has_condition(Condition::Poisoned));
+    }
 
-        let resolution = engine.resolve(
+    /// A cleric with Revivify prepared and a 3rd-level slot to cast it with.
+    fn sample_cleric() -> GameWorld {
+        let mut cleric = create_sample_fighter("Aldric");
+        cleric.classes[0].class = crate::world::CharacterClass::Cleric;
+        let mut slots = crate::world::SpellSlots::new();
+        slots.slots[2].total = 2;
+        cleric.spellcasting = Some(crate::world::SpellcastingData {
+            ability: Ability::Wisdom,
+            spells_known: Vec::new(),
+            spells_prepared: vec!["Revivify".to_string()],
+            cantrips_known: Vec::new(),
+            spell_slots: slots,
+            pact_slots: None,
+            prepared_since_rest: true,
+        });
+        GameWorld::new("Test", cleric)
+    }
+
+    #[test]
+    fn test_party_member_spends_their_own_slots() {
+        let healer = || {
+            let mut cleric = sample_cleric().player_character;
+            let spellcasting = cleric.spellcasting.as_mut().unwrap();
+            spellcasting
+                .spells_prepared
+                .push("Healing Word".to_string());
+            spellcasting.spell_slots.slots[0].total = 1;
+            cleric
+        };
+        let mut world = GameWorld::new("Test", healer());
+        let companion_id = world.add_party_member(healer());
+        let first_level_used = |world: &GameWorld, id| {
+            let caster = world.character(id).unwrap();
+            caster.spellcasting.as_ref().unwrap().spell_slots.slots[0].used
+        };
+
+        let cast = RulesEngine::new().resolve(
             &world,
-            Intent::Damage {
-                target_id: character.id,
-                amount: 5,
-                damage_type: DamageType::Slashing,
-                source: "Goblin".to_string(),
+            Intent::CastSpell {
+                caster_id: companion_id,
+                spell_name: "Healing Word".to_string(),
+                targets: vec![],
+                spell_level: 1,
+                target_names: vec!["Aldric".to_string()],
+                ritual: false,
             },
         );
-
-        // Should have death save failure effect
-        assert!(resolution
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::DeathSaveFailure { failures: 1, total_failures: 1, .. })));
-        assert!(resolution.narrative.contains("death save failure"));
+        assert!(cast.effects.iter().any(|e| matches!(
+            e,
+            Effect::SpellSlotUsed { caster_id, level: 1, .. } if *caster_id == companion_id
+        )));
+        apply_effects(&mut world, &cast.effects);
+        assert_eq!(first_level_used(&world, companion_id), 1);
+        assert_eq!(first_level_used(&world, world.player_character.id), 0);
     }
 
     #[test]
-    fn test_massive_damage_at_zero_hp_causes_instant_death() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 0;
-        character.hit_points.maximum = 30;
-        character.conditions.push(crate::world::ActiveCondition::new(
-            Condition::Unconscious,
-            "Dropped to 0 HP",
-        ));
-        let world = GameWorld::new("Test", character.clone());
-        let engine = RulesEngine::new();
+    fn test_unknown_character_is_refused() {
+        let world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let stranger = CharacterId::new();
 
-        // Damage >= max HP while at 0 HP = instant death
-        let resolution = engine.resolve(
+        let heal = RulesEngine::new().resolve(
             &world,
-            Intent::Damage {
-                target_id: character.id,
-                amount: 30,
-                damage_type: DamageType::Slashing,
-                source: "Dragon".to_string(),
+            Intent::Heal {
+                target_id: stranger,
+                amount: 5,
+                source: "Potion".to_string(),
             },
         );
+        assert!(heal.effects.is_empty());
+        assert_eq!(
+            heal.narrative,
+            format!("No character with id {stranger} is in the party.")
+        );
+    }
 
-        // Should have character died effect
-        assert!(resolution
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::CharacterDied { .. })));
-        assert!(resolution.narrative.contains("INSTANT DEATH"));
+    fn revivify(world: &GameWorld) -> Resolution {
+        RulesEngine::new().resolve(
+            world,
+            Intent::CastSpell {
+                caster_id: world.player_character.id,
+                spell_name: "Revivify".to_string(),
+                targets: vec![],
+                spell_level: 3,
+                target_names: vec!["Bram".to_string()],
+                ritual: false,
+            },
+        )
     }
 
     #[test]
-    fn test_healing_resets_death_saves() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 0;
-        character.death_saves.failures = 2; // 2 failures already
-        character.conditions.push(crate::world::ActiveCondition::new(
-            Condition::Unconscious,
-            "Dropped to 0 HP",
-        ));
-        let mut world = GameWorld::new("Test", character);
+    fn test_revivify_needs_and_consumes_a_diamond() {
+        let mut world = sample_cleric();
+        let refused = revivify(&world);
+        assert!(refused.effects.is_empty());
+        assert!(refused
+            .narrative
+            .contains("needs a diamond worth at least 300 gp"));
+
+        world.player_character.inventory.add_item(Item {
+            name: "Diamond".to_string(),
+            quantity: 1,
+            weight: 0.0,
+            value_gp: 300.0,
+            description: None,
+            item_type: ItemType::Other,
+            magical: false,
+            requires_attunement: false,
+        });
+        let cast = revivify(&world);
+        assert!(cast.effects.iter().any(|e| matches!(
+            e,
+            Effect::ItemRemoved { item_name, remaining: 0, .. } if item_name == "Diamond"
+        )));
+        apply_effects(&mut world, &cast.effects);
+        assert!(!world.player_character.inventory.has_item("Diamond"));
 
-        // Apply healing effect
-        let effect = Effect::HpChanged {
-            target_id: world.player_character.id,
-            amount: 5,
-            new_current: 5,
-            new_max: world.player_character.hit_points.maximum,
-            dropped_to_zero: false,
-        };
-        apply_effect(&mut world, &effect);
+        // Without another diamond, the second casting is refused despite the slot
+        assert!(revivify(&world).effects.is_empty());
+    }
 
-        // Death saves should be reset
-        assert_eq!(world.player_character.death_saves.failures, 0);
-        assert_eq!(world.player_character.death_saves.successes, 0);
-        // Unconscious should be removed
-        assert!(!world
-            .player_character
-            .conditions
-            .iter()
-            .any(|c| c.condition == Condition::Unconscious));
+    fn resolve_and_apply(world: &mut GameWorld, intent: Intent) -> Resolution {
+        let resolution = RulesEngine::new().resolve(world, intent);
+        apply_effects(world, &resolution.effects);
+        resolution
     }
 
     #[test]
-    fn test_three_death_save_failures_causes_death() {
-        let mut character = create_sample_fighter("Roland");
-        character.hit_points.current = 0;
-        character.death_saves.failures = 2; // Already have 2 failures
-        character.conditions.push(crate::world::ActiveCondition::new(
-            Condition::Unconscious,
-            "Dropped to 0 HP",
-        ));
-        let world = GameWorld::new("Test", character.clone());
-        let engine = RulesEngine::new();
+    fn test_quest_objectives_and_completion() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        resolve_and_apply(
+            &mut world,
+            Intent::AddQuest {
+                name: "The Missing Miller".to_string(),
+                description: "Find the miller who vanished from Millbrook".to_string(),
+                objectives: vec![
+                    "Search the mill".to_string(),
+                    "Rescue the miller".to_string(),
+                ],
+                giver: Some("Mayor Thorne".to_string()),
+                xp_reward: 300,
+            },
+        );
+        let quest = world.quest("the missing miller").expect("quest in the log");
+        assert_eq!(quest.status, QuestStatus::Active);
+        assert_eq!(quest.objectives.len(), 2);
 
-        // Take damage at 0 HP - should cause 3rd failure and death
-        let resolution = engine.resolve(
+        // A duplicate quest is turned away
+        let duplicate = RulesEngine::new().resolve(
             &world,
-            Intent::Damage {
-                target_id: character.id,
-                amount: 5,
-                damage_type: DamageType::Slashing,
-                source: "Goblin".to_string(),
+            Intent::AddQuest {
+                name: "The Missing Miller".to_string(),
+                description: String::new(),
+                objectives: Vec::new(),
+                giver: None,
+                xp_reward: 0,
             },
         );
+        assert!(duplicate.effects.is_empty());
 
-        // Should have both death save failure and character died effects
-        assert!(resolution
-            .effects
-            .iter()
-            .any(|e| matches!(e, Effect::DeathSaveFailure { total_failures: 3, .. })));
+        let resolution = resolve_and_apply(
+            &mut world,
+            Intent::CompleteObjective {
+                quest_name: "The Missing Miller".to_string(),
+                objective: "search".to_string(),
+            },
+        );
+        assert!(resolution.narrative.contains("1 objective(s) remain"));
+        let quest = world.quest("The Missing Miller").unwrap();
+        assert!(quest.objectives[0].completed);
+        assert!(!quest.objectives[1].completed);
+        let again = RulesEngine::new().resolve(
+            &world,
+            Intent::CompleteObjective {
+                quest_name: "The Missing Miller".to_string(),
+                objective: "Search the mill".to_string(),
+            },
+        );
+        assert!(again.effects.is_empty());
+
+        let resolution = resolve_and_apply(
+            &mut world,
+            Intent::UpdateQuestStatus {
+                quest_name: "The Missing Miller".to_string(),
+                status: QuestStatus::Completed,
+            },
+        );
+        assert_eq!(
+            world.quest("The Missing Miller").unwrap().status,
+            QuestStatus::Completed
+        );
         assert!(resolution
             .effects
             .iter()
-            .any(|e| matches!(e, Effect::CharacterDied { .. })));
-        assert!(resolution.narrative.contains("DIES"));
+            .any(|e| matches!(e, Effect::ExperienceGained { amount: 300, .. })));
+        assert_eq!(world.player_character.experience, 300);
+
+        // Reopening it to complete again pays nothing more
+        let reopen = RulesEngine::new().resolve(
+            &world,
+            Intent::UpdateQuestStatus {
+                quest_name: "The Missing Miller".to_string(),
+                status: QuestStatus::Active,
+            },
+        );
+        assert!(reopen.effects.is_empty());
+
+        // An empty objective matches nothing
+        let quest = world.quest("The Missing Miller").unwrap();
+        assert_eq!(quest.objective_index("  "), None);
+
+        // The quest log survives a save and load
+        let saved = serde_json::to_string(&world).unwrap();
+        let loaded: GameWorld = serde_json::from_str(&saved).unwrap();
+        let quest = loaded.quest("The Missing Miller").unwrap();
+        assert_eq!(quest.status, QuestStatus::Completed);
+        assert!(quest.objectives[0].completed);
+    }
+
+    #[test]
+    fn test_unknown_quest_changes_nothing() {
+        let world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let resolution = RulesEngine::new().resolve(
+            &world,
+            Intent::UpdateQuestStatus {
+                quest_name: "Slay the Dragon".to_string(),
+                status: QuestStatus::Failed,
+            },
+        );
+        assert!(resolution.effects.is_empty());
+        assert!(resolution.narrative.contains("No quest named"));
     }
 }
@@ -4,10 +4,17 @@
 //! game interactions. It wraps the DungeonMaster, GameWorld, and
 //! persistence logic into a single, easy-to-use API.
 
-use crate::dm::{DmConfig, DmError, DmResponse, DungeonMaster};
+use crate::dm::{DmConfig, DmError, DmMemory, DmResponse, DungeonMaster, StoryMemory};
+use crate::persist::{self, SaveMetadata};
 use crate::rules::Effect;
-use crate::world::{create_sample_fighter, GameWorld};
-use std::path::Path;
+use crate::world::{create_sample_fighter, CombatRules, GameWorld, LoggedRoll};
+use claude::LlmProvider;
+use futures::{Stream, StreamExt};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::task::Poll;
 use thiserror::Error;
 use tokio::fs;
 
@@ -23,10 +30,22 @@ pub enum SessionError {
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
+    #[error("Save file error: {0}")]
+    Persist(#[from] crate::persist::PersistError),
+
     #[error("No API key configured - set ANTHROPIC_API_KEY environment variable")]
     NoApiKey,
+
+    #[error("Nothing to undo")]
+    NothingToUndo,
+
+    #[error("No checkpoint named '{0}'")]
+    UnknownCheckpoint(String),
 }
 
+/// How many pre-action snapshots [`GameSession::undo`] can step back through.
+pub const UNDO_LIMIT: usize = 10;
+
 /// Configuration for creating a new game session.
 #[derive(Debug, Clone)]
 pub struct SessionConfig {
@@ -50,6 +69,12 @@ pub struct SessionConfig {
 
     /// Temperature for DM generation.
     pub temperature: Option<f32>,
+
+    /// Optional combat rules in play.
+    pub combat_rules: CombatRules,
+
+    /// Token prices used to estimate what the session costs.
+    pub token_pricing: TokenPricing,
 }
 
 impl SessionConfig {
@@ -63,6 +88,8 @@ impl SessionConfig {
             model: None,
             max_tokens: 4096,
             temperature: Some(0.8),
+            combat_rules: CombatRules::default(),
+            token_pricing: TokenPricing::default(),
         }
     }
 
@@ -101,6 +128,106 @@ impl SessionConfig {
         self.temperature = Some(temp);
         self
     }
+
+    /// Set the optional combat rules in play, such as flanking.
+    pub fn with_combat_rules(mut self, rules: CombatRules) -> Self {
+        self.combat_rules = rules;
+        self
+    }
+
+    /// Set the token prices used for cost estimates.
+    pub fn with_token_pricing(mut self, pricing: TokenPricing) -> Self {
+        self.token_pricing = pricing;
+        self
+    }
+}
+
+/// Dollar prices per million tokens, for estimating what a session costs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    /// Prompt tokens read from the cache.
+    pub cache_read_per_million: f64,
+    /// Prompt tokens written to the cache.
+    pub cache_write_per_million: f64,
+}
+
+impl Default for TokenPricing {
+    /// Sonnet-class list prices.
+    fn default() -> Self {
+        Self {
+            input_per_million: 3.0,
+            output_per_million: 15.0,
+            cache_read_per_million: 0.3,
+            cache_write_per_million: 3.75,
+        }
+    }
+}
+
+/// Running totals of the tokens the DM has used this session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SessionStats {
+    /// Prompt tokens, including prompt-cache reads and writes.
+    pub total_input_tokens: u64,
+    pub total_output_tokens: u64,
+    /// The part of the prompt tokens read from the cache.
+    #[serde(default)]
+    pub total_cache_read_tokens: u64,
+    /// The part of the prompt tokens written to the cache.
+    #[serde(default)]
+    pub total_cache_write_tokens: u64,
+    /// Player actions the DM has answered.
+    pub turns: u32,
+}
+
+impl SessionStats {
+    /// Count one DM turn's token usage.
+    pub fn record(&mut self, usage: &claude::Usage) {
+        self.total_input_tokens +=
+            (usage.input_tokens + usage.cache_read_tokens + usage.cache_write_tokens) as u64;
+        self.total_output_tokens += usage.output_tokens as u64;
+        self.total_cache_read_tokens += usage.cache_read_tokens as u64;
+        self.total_cache_write_tokens += usage.cache_write_tokens as u64;
+        self.turns += 1;
+    }
+
+    /// Estimated cost in dollars at the given prices.
+    ///
+    /// Cache reads and writes are priced at their own rates; the rest of the
+    /// prompt at the input rate.
+    pub fn estimated_cost(&self, pricing: TokenPricing) -> f64 {
+        let cached = self.total_cache_read_tokens + self.total_cache_write_tokens;
+        let uncached = self.total_input_tokens.saturating_sub(cached);
+        (uncached as f64 * pricing.input_per_million
+            + self.total_cache_read_tokens as f64 * pricing.cache_read_per_million
+            + self.total_cache_write_tokens as f64 * pricing.cache_write_per_million
+            + self.total_output_tokens as f64 * pricing.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// A named snapshot of the world and DM memory that a session can rewind to.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint {
+    /// Name the player gave the checkpoint.
+    pub label: String,
+    /// When the checkpoint was taken.
+    pub created_at: String,
+    /// The world as it was.
+    pub world: GameWorld,
+    /// Conversation memory and campaign facts.
+    pub memory: DmMemory,
+    /// Tracked entities, facts and relationships.
+    pub story_memory: StoryMemory,
+}
+
+/// The world and DM memory as they were before a player action.
+#[derive(Debug, Clone)]
+struct UndoSnapshot {
+    world: GameWorld,
+    memory: DmMemory,
+    story_memory: StoryMemory,
 }
 
 /// Response from a player action.
@@ -109,7 +236,9 @@ pub struct Response {
     /// The narrative text from the DM.
     pub narrative: String,
 
-    /// Effects that were applied to the game world.
+    /// Effects that were applied to the game world this turn, in order.
+    ///
+    /// UIs can drive damage numbers and dice animations from these directly.
     pub effects: Vec<Effect>,
 
     /// Whether combat is currently active.
@@ -119,6 +248,15 @@ pub struct Response {
     pub is_player_turn: bool,
 }
 
+/// One item from [`GameSession::player_action_stream`].
+#[derive(Debug, Clone)]
+pub enum NarrativeChunk {
+    /// A piece of narrative text, as soon as the DM produces it.
+    Text(String),
+    /// The turn is over; carries the full narrative and the applied effects.
+    Done(Response),
+}
+
 impl From<DmResponse> for Response {
     fn from(dm: DmResponse) -> Self {
         Self {
@@ -139,6 +277,13 @@ impl From<DmResponse> for Response {
 pub struct GameSession {
     dm: DungeonMaster,
     world: GameWorld,
+    /// Snapshots taken before each successful player action, oldest first.
+    undo_stack: VecDeque<UndoSnapshot>,
+    /// Named snapshots, in the order they were taken.
+    checkpoints: Vec<Checkpoint>,
+    /// Tokens used so far, kept across saves.
+    stats: SessionStats,
+    token_pricing: TokenPricing,
 }
 
 impl GameSession {
@@ -146,26 +291,9 @@ impl GameSession {
     ///
     /// Requires `ANTHROPIC_API_KEY` environment variable to be set.
     pub async fn new(config: SessionConfig) -> Result<Self, SessionError> {
-        let dm_config = DmConfig {
-            model: config.model,
-            max_tokens: config.max_tokens,
-            temperature: config.temperature,
-            custom_system_prompt: config.custom_dm_prompt,
-        };
-
-        let dm = DungeonMaster::from_env()
-            .map_err(|_| SessionError::NoApiKey)?
-            .with_config(dm_config);
-
-        // Create a sample character
+        let dm = DungeonMaster::from_env().map_err(|_| SessionError::NoApiKey)?;
         let character = create_sample_fighter(&config.character_name);
-
-        let mut world = GameWorld::new(config.campaign_name, character);
-
-        // Update starting location name if custom
-        world.current_location.name = config.starting_location;
-
-        Ok(Self { dm, world })
+        Ok(Self::from_config(config, character, dm))
     }
 
     /// Create a new game session with a custom character.
@@ -175,6 +303,24 @@ impl GameSession {
         config: SessionConfig,
         character: crate::world::Character,
     ) -> Result<Self, SessionError> {
+        let dm = DungeonMaster::from_env().map_err(|_| SessionError::NoApiKey)?;
+        Ok(Self::from_config(config, character, dm))
+    }
+
+    /// Create a new game session whose DM runs on any model provider.
+    ///
+    /// Unlike [`new`](Self::new), this needs no API key, so a local model or
+    /// a scripted provider can drive the game offline.
+    pub fn with_provider(config: SessionConfig, provider: Arc<dyn LlmProvider>) -> Self {
+        let character = create_sample_fighter(&config.character_name);
+        Self::from_config(config, character, DungeonMaster::with_provider(provider))
+    }
+
+    fn from_config(
+        config: SessionConfig,
+        character: crate::world::Character,
+        dm: DungeonMaster,
+    ) -> Self {
         let dm_config = DmConfig {
             model: config.model,
             max_tokens: config.max_tokens,
@@ -182,36 +328,70 @@ impl GameSession {
             custom_system_prompt: config.custom_dm_prompt,
         };
 
-        let dm = DungeonMaster::from_env()
-            .map_err(|_| SessionError::NoApiKey)?
-            .with_config(dm_config);
-
         let mut world = GameWorld::new(config.campaign_name, character);
 
         // Update starting location name if custom
         world.current_location.name = config.starting_location;
+        world.combat_rules = config.combat_rules;
 
-        Ok(Self { dm, world })
+        Self {
+            dm: dm.with_config(dm_config),
+            world,
+            undo_stack: VecDeque::new(),
+            checkpoints: Vec::new(),
+            stats: SessionStats::default(),
+            token_pricing: config.token_pricing,
+        }
     }
 
     /// Create a session with a pre-configured world.
     ///
     /// This allows for custom character creation and world setup.
     pub fn with_world(dm: DungeonMaster, world: GameWorld) -> Self {
-        Self { dm, world }
+        Self {
+            dm,
+            world,
+            undo_stack: VecDeque::new(),
+            checkpoints: Vec::new(),
+            stats: SessionStats::default(),
+            token_pricing: TokenPricing::default(),
+        }
     }
 
-    /// Load a saved session from a file.
+    /// Load a saved session from a file, upgrading older save versions.
+    ///
+    /// Gzip-compressed saves are detected and decompressed automatically.
     pub async fn load(path: impl AsRef<Path>) -> Result<Self, SessionError> {
-        let content = fs::read_to_string(path).await?;
-        let saved: SavedSession = serde_json::from_str(&content)?;
-
+        let saved = Self::read_saved(path).await?;
         let dm = DungeonMaster::from_env().map_err(|_| SessionError::NoApiKey)?;
+        Ok(Self::restore(saved, dm))
+    }
+
+    /// Load a saved session with an already-configured Dungeon Master.
+    pub async fn load_with_dm(
+        path: impl AsRef<Path>,
+        dm: DungeonMaster,
+    ) -> Result<Self, SessionError> {
+        let saved = Self::read_saved(path).await?;
+        Ok(Self::restore(saved, dm))
+    }
 
+    async fn read_saved(path: impl AsRef<Path>) -> Result<SavedSession, SessionError> {
+        let content = persist::read_save_file(path).await?;
+        let mut value: serde_json::Value = serde_json::from_str(&content)?;
+        persist::upgrade(&mut value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    fn restore(saved: SavedSession, dm: DungeonMaster) -> Self {
         // Restore memory from saved session
         let mut session = Self {
             dm,
             world: saved.world,
+            undo_stack: VecDeque::new(),
+            checkpoints: saved.checkpoints,
+            stats: saved.stats,
+            token_pricing: TokenPricing::default(),
         };
 
         // Restore memory context
@@ -231,28 +411,57 @@ impl GameSession {
             *session.dm.story_memory_mut() = story_memory;
         }
 
-        Ok(session)
+        session
     }
 
     /// Save the current session to a file.
+    ///
+    /// Paths ending in `.gz` (e.g. `campaign.json.gz`) are gzip-compressed;
+    /// anything else is written as plain JSON.
     pub async fn save(&self, path: impl AsRef<Path>) -> Result<(), SessionError> {
         let saved = SavedSession {
+            version: persist::CURRENT_VERSION,
+            metadata: Some(SaveMetadata::from_world(&self.world, persist::chrono_now())),
             world: self.world.clone(),
             campaign_facts: self.dm.memory().campaign_facts.to_vec(),
             conversation_summary: Some(self.dm.memory().generate_summary()),
             story_memory: Some(self.dm.story_memory().clone()),
+            checkpoints: self.checkpoints.clone(),
+            stats: self.stats.clone(),
         };
 
         let content = serde_json::to_string_pretty(&saved)?;
-        fs::write(path, content).await?;
+        persist::write_save_file(path, content).await?;
         Ok(())
     }
 
+    /// Autosave into `dir`, keeping the previous autosaves up to
+    /// [`persist::AUTOSAVES_TO_KEEP`](crate::persist::AUTOSAVES_TO_KEEP).
+    ///
+    /// The newest autosave is always at [`auto_save_path`](crate::persist::auto_save_path);
+    /// older ones are archived next to it. Returns the path written.
+    pub async fn autosave(&self, dir: impl AsRef<Path>) -> Result<PathBuf, SessionError> {
+        let dir = dir.as_ref();
+        let campaign = &self.world.campaign_name;
+        fs::create_dir_all(dir).await?;
+
+        let path = persist::auto_save_path(dir, campaign);
+        if fs::try_exists(&path).await? {
+            fs::rename(&path, persist::archived_auto_save_path(dir, campaign)).await?;
+        }
+        self.save(&path).await?;
+        persist::rotate_saves(dir, campaign, persist::AUTOSAVES_TO_KEEP).await?;
+        Ok(path)
+    }
+
     /// Process a player action and get the DM's response.
     ///
     /// This is the main gameplay loop entry point.
     pub async fn player_action(&mut self, input: &str) -> Result<Response, SessionError> {
+        let snapshot = self.snapshot();
         let dm_response = self.dm.process_input(input, &mut self.world).await?;
+        self.push_undo(snapshot);
+        self.stats.record(&dm_response.usage);
 
         let in_combat = self.world.combat.is_some();
         let is_player_turn = self
@@ -284,10 +493,13 @@ impl GameSession {
     where
         F: FnMut(&str) + Send,
     {
+        let snapshot = self.snapshot();
         let dm_response = self
             .dm
             .process_input_streaming(input, &mut self.world, on_text)
             .await?;
+        self.push_undo(snapshot);
+        self.stats.record(&dm_response.usage);
 
         let in_combat = self.world.combat.is_some();
         let is_player_turn = self
@@ -306,6 +518,135 @@ impl GameSession {
         })
     }
 
+    /// Process a player action as a stream of narrative chunks.
+    ///
+    /// Yields [`NarrativeChunk::Text`] for each delta as it arrives, then a
+    /// single [`NarrativeChunk::Done`] once the turn's effects are applied.
+    /// An error ends the stream.
+    pub fn player_action_stream<'a>(
+        &'a mut self,
+        input: &'a str,
+    ) -> impl Stream<Item = Result<NarrativeChunk, SessionError>> + 'a {
+        let (tx, mut rx) = futures::channel::mpsc::unbounded();
+        // The sender lives inside the turn, so the channel closes when it ends
+        let mut turn = Some(Box::pin(self.player_action_streaming(input, move |text| {
+            let _ = tx.unbounded_send(text.to_string());
+        })));
+        let mut finished = None;
+
+        futures::stream::poll_fn(move |cx| {
+            if let Some(future) = turn.as_mut() {
+                if let Poll::Ready(result) = future.as_mut().poll(cx) {
+                    finished = Some(result);
+                    turn = None;
+                }
+            }
+            // Drain every delta before reporting the end of the turn
+            match rx.poll_next_unpin(cx) {
+                Poll::Ready(Some(text)) => {
+                    return Poll::Ready(Some(Ok(NarrativeChunk::Text(text))))
+                }
+                Poll::Ready(None) if turn.is_none() => {}
+                _ => return Poll::Pending,
+            }
+            Poll::Ready(
+                finished
+                    .take()
+                    .map(|result| result.map(NarrativeChunk::Done)),
+            )
+        })
+    }
+
+    /// Capture the current world and DM memory.
+    fn snapshot(&self) -> UndoSnapshot {
+        UndoSnapshot {
+            world: self.world.clone(),
+            memory: self.dm.memory().clone(),
+            story_memory: self.dm.story_memory().clone(),
+        }
+    }
+
+    /// Remember a snapshot so the action that followed it can be undone.
+    fn push_undo(&mut self, snapshot: UndoSnapshot) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(snapshot);
+    }
+
+    /// Restore the world and DM memory to how they were before the last
+    /// successful player action.
+    ///
+    /// This rolls back everything the action changed, including its narrative
+    /// entries and the exchange the DM remembers.
+    pub fn undo(&mut self) -> Result<(), SessionError> {
+        let snapshot = self
+            .undo_stack
+            .pop_back()
+            .ok_or(SessionError::NothingToUndo)?;
+        self.world = snapshot.world;
+        *self.dm.memory_mut() = snapshot.memory;
+        *self.dm.story_memory_mut() = snapshot.story_memory;
+        Ok(())
+    }
+
+    /// Check whether there is an action to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Save the current world and DM memory under `label`.
+    ///
+    /// Taking a checkpoint with an existing label replaces the old one.
+    pub fn checkpoint(&mut self, label: &str) {
+        self.checkpoints.retain(|c| c.label != label);
+        self.checkpoints.push(Checkpoint {
+            label: label.to_string(),
+            created_at: persist::chrono_now(),
+            world: self.world.clone(),
+            memory: self.dm.memory().clone(),
+            story_memory: self.dm.story_memory().clone(),
+        });
+    }
+
+    /// Restore the world and DM memory saved under `label`.
+    ///
+    /// The checkpoint is kept so it can be rewound to again. Undo history is
+    /// cleared, since it belongs to the abandoned timeline.
+    pub fn rewind(&mut self, label: &str) -> Result<(), SessionError> {
+        let checkpoint = self
+            .checkpoints
+            .iter()
+            .find(|c| c.label == label)
+            .ok_or_else(|| SessionError::UnknownCheckpoint(label.to_string()))?;
+
+        self.world = checkpoint.world.clone();
+        *self.dm.memory_mut() = checkpoint.memory.clone();
+        *self.dm.story_memory_mut() = checkpoint.story_memory.clone();
+        self.undo_stack.clear();
+        Ok(())
+    }
+
+    /// Get the checkpoints, oldest first.
+    pub fn list_checkpoints(&self) -> &[Checkpoint] {
+        &self.checkpoints
+    }
+
+    /// Get the tokens used so far this session.
+    pub fn stats(&self) -> &SessionStats {
+        &self.stats
+    }
+
+    /// Estimated dollar cost of the session so far.
+    pub fn estimated_cost(&self) -> f64 {
+        self.stats.estimated_cost(self.token_pricing)
+    }
+
+    /// Set the token prices used for cost estimates.
+    pub fn set_token_pricing(&mut self, pricing: TokenPricing) {
+        self.token_pricing = pricing;
+    }
+
     /// Get a reference to the game world.
     pub fn world(&self) -> &GameWorld {
         &self.world
@@ -352,6 +693,11 @@ impl GameSession {
         self.world.player_character.background.name()
     }
 
+    /// The last `count` dice rolls, oldest first.
+    pub fn recent_rolls(&self, count: usize) -> &[LoggedRoll] {
+        self.world.recent_rolls(count)
+    }
+
     /// Check if the session is in combat.
     pub fn in_combat(&self) -> bool {
         self.world.combat.is_some()
@@ -370,11 +716,19 @@ impl GameSession {
 /// Serializable session state for persistence.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct SavedSession {
+    version: u32,
+    /// Lets the save be listed with `SavedCampaign::peek_metadata`.
+    #[serde(default)]
+    metadata: Option<SaveMetadata>,
     world: GameWorld,
     campaign_facts: Vec<crate::dm::memory::CampaignFact>,
     conversation_summary: Option<String>,
     #[serde(default)]
     story_memory: Option<crate::dm::StoryMemory>,
+    #[serde(default)]
+    checkpoints: Vec<Checkpoint>,
+    #[serde(default)]
+    stats: SessionStats,
 }
 
 #[cfg(test)]
@@ -394,6 +748,22 @@ mod tests {
         assert_eq!(config.max_tokens, 2048);
     }
 
+    #[test]
+    fn test_cache_tokens_priced_at_their_own_rates() {
+        let mut stats = SessionStats::default();
+        stats.record(&claude::Usage {
+            input_tokens: 1_000,
+            output_tokens: 100,
+            cache_read_tokens: 10_000,
+            cache_write_tokens: 2_000,
+        });
+        assert_eq!(stats.total_input_tokens, 13_000);
+
+        // 1k input at $3/M, 10k reads at $0.30/M, 2k writes at $3.75/M, 100 output at $15/M
+        let expected = (1_000.0 * 3.0 + 10_000.0 * 0.3 + 2_000.0 * 3.75 + 100.0 * 15.0) / 1e6;
+        assert!((stats.estimated_cost(TokenPricing::default()) - expected).abs() < 1e-12);
+    }
+
     #[test]
     fn test_response_from_dm() {
         let dm_response = DmResponse {
@@ -401,10 +771,338 @@ mod tests {
             intents: vec![],
             effects: vec![],
             resolutions: vec![],
+            usage: claude::Usage::default(),
         };
 
         let response: Response = dm_response.into();
         assert_eq!(response.narrative, "You see a dragon!");
         assert!(!response.in_combat);
     }
+
+    /// Answers each request with the next scripted response.
+    struct ScriptedProvider {
+        responses: std::sync::Mutex<VecDeque<claude::Response>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(content: Vec<Vec<claude::ContentBlock>>) -> Self {
+            let responses = content
+                .into_iter()
+                .map(|content| claude::Response {
+                    id: "msg_local".to_string(),
+                    model: "local".to_string(),
+                    stop_reason: if content
+                        .iter()
+                        .any(|b| matches!(b, claude::ContentBlock::ToolUse { .. }))
+                    {
+                        claude::StopReason::ToolUse
+                    } else {
+                        claude::StopReason::EndTurn
+                    },
+                    content,
+                    stop_sequence: None,
+                    usage: claude::Usage {
+                        input_tokens: 50,
+                        output_tokens: 10,
+                        ..claude::Usage::default()
+                    },
+                })
+                .collect();
+            Self {
+                responses: std::sync::Mutex::new(responses),
+            }
+        }
+    }
+
+    impl LlmProvider for ScriptedProvider {
+        fn complete(
+            &self,
+            _request: claude::Request,
+        ) -> claude::BoxFuture<'_, Result<claude::Response, claude::Error>> {
+            let next = self.responses.lock().unwrap().pop_front();
+            Box::pin(async move {
+                next.ok_or_else(|| claude::Error::Config("script exhausted".to_string()))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_turn_through_custom_provider() {
+        let provider = ScriptedProvider::new(vec![
+            vec![claude::ContentBlock::ToolUse {
+                id: "toolu_1".to_string(),
+                name: "roll_dice".to_string(),
+                input: serde_json::json!({"notation": "1d20", "purpose": "luck"}),
+            }],
+            vec![claude::ContentBlock::Text {
+                text: "Fortune smiles on you.".to_string(),
+            }],
+            vec![claude::ContentBlock::Text {
+                text: "The road stretches on.".to_string(),
+            }],
+        ]);
+        let config = SessionConfig::new("Offline").with_character_name("Roland");
+        let mut session = GameSession::with_provider(config, Arc::new(provider));
+        assert_eq!(session.player_name(), "Roland");
+
+        let response = session.player_action("I test my luck").await.unwrap();
+        assert_eq!(response.narrative, "Fortune smiles on you.");
+        assert!(response
+            .effects
+            .iter()
+            .any(|e| matches!(e, Effect::DiceRolled { purpose, .. } if purpose == "luck")));
+
+        // Streaming falls back to replaying the provider's full response
+        let mut streamed = String::new();
+        let response = session
+            .player_action_streaming("I walk on", |text| streamed.push_str(text))
+            .await
+            .unwrap();
+        assert_eq!(response.narrative, "The road stretches on.");
+        assert_eq!(streamed, "The road stretches on.");
+        assert_eq!(session.stats().total_input_tokens, 150);
+    }
+
+    #[tokio::test]
+    async fn test_rolls_reach_the_narrative_with_their_dice() {
+        use crate::world::NarrativeType;
+
+        let provider = ScriptedProvider::new(vec![
+            vec![claude::ContentBlock::ToolUse {
+                id: "toolu_1".to_string(),
+                name: "roll_dice".to_string(),
+                input: serde_json::json!({"notation": "2d6+1", "purpose": "luck"}),
+            }],
+            vec![claude::ContentBlock::Text {
+                text: "Fortune smiles on you.".to_string(),
+            }],
+        ]);
+        let config = SessionConfig::new("Offline").with_character_name("Roland");
+        let mut session = GameSession::with_provider(config, Arc::new(provider));
+        session.player_action("I test my luck").await.unwrap();
+
+        let history = &session.world().narrative_history;
+        let entry = history
+            .iter()
+            .find(|e| matches!(e.entry_type, NarrativeType::DiceRoll))
+            .expect("a dice roll entry");
+        let roll = entry.roll.as_ref().expect("the roll itself");
+        assert_eq!(roll.component_results[0].rolls.len(), 2);
+        assert_eq!(entry.content, format!("luck: 2d6+1 = {}", roll.total));
+        // Plain entries stay plain
+        assert!(history
+            .iter()
+            .filter(|e| !matches!(e.entry_type, NarrativeType::DiceRoll))
+            .all(|e| e.roll.is_none()));
+    }
+
+    #[test]
+    fn test_undo_restores_world() {
+        use crate::dm::DungeonMaster;
+        use crate::rules::{apply_effects, DamageType, Intent, RulesEngine};
+        use crate::world::NarrativeType;
+
+        let world = GameWorld::new("Undo Test", create_sample_fighter("Roland"));
+        let mut session = GameSession::with_world(DungeonMaster::new("test-key"), world);
+        assert!(matches!(session.undo(), Err(SessionError::NothingToUndo)));
+
+        let snapshot = session.snapshot();
+        let resolution = RulesEngine::new().resolve(
+            session.world(),
+            Intent::Damage {
+                target_id: session.world().player_character.id,
+                amount: 8,
+                damage_type: DamageType::Slashing,
+                source: "Orc".to_string(),
+            },
+        );
+        apply_effects(session.world_mut(), &resolution.effects);
+        session
+            .world_mut()
+            .add_narrative(resolution.narrative, NarrativeType::Combat);
+        assert_eq!(session.hp_status(), (20, 28));
+        session.push_undo(snapshot);
+
+        session.undo().unwrap();
+        assert_eq!(session.hp_status(), (28, 28));
+        assert!(session.world().narrative_history.is_empty());
+        assert!(!session.can_undo());
+
+        for _ in 0..UNDO_LIMIT + 3 {
+            session.push_undo(session.snapshot());
+        }
+        assert_eq!(session.undo_stack.len(), UNDO_LIMIT);
+    }
+
+    #[tokio::test]
+    async fn test_undo_rewinds_dm_memory_and_skips_failed_actions() {
+        let provider = ScriptedProvider::new(vec![vec![claude::ContentBlock::Text {
+            text: "The door creaks open.".to_string(),
+        }]]);
+        let config = SessionConfig::new("Offline").with_character_name("Roland");
+        let mut session = GameSession::with_provider(config, Arc::new(provider));
+
+        session.player_action("I open the door").await.unwrap();
+        assert!(session.dm().memory().message_count() > 0);
+
+        // The script is exhausted, so this action fails and leaves no undo step
+        assert!(session.player_action("I step inside").await.is_err());
+        session.undo().unwrap();
+        assert_eq!(session.dm().memory().message_count(), 0);
+        assert!(!session.can_undo());
+    }
+
+    #[tokio::test]
+    async fn test_story_memory_survives_save_and_load() {
+        use crate::dm::{DungeonMaster, EntityType, FactSource, RelationshipType, StoryFactCategory};
+
+        let world = GameWorld::new("Memory Test", create_sample_fighter("Roland"));
+        let mut session = GameSession::with_world(DungeonMaster::new("test-key"), world);
+        let memory = session.dm.story_memory_mut();
+        let mira = memory.create_entity(EntityType::Npc, "Mira");
+        let guild = memory.create_entity(EntityType::Organization, "Thieves' Guild");
+        memory.record_fact(
+            mira,
+            "Mira runs the Rusty Anchor",
+            StoryFactCategory::Backstory,
+            FactSource::DmNarration,
+        );
+        memory.record_fact(
+            mira,
+            "Mira has a scar over one eye",
+            StoryFactCategory::Appearance,
+            FactSource::DmNarration,
+        );
+        memory.record_fact(
+            guild,
+            "The guild meets beneath the docks",
+            StoryFactCategory::Event,
+            FactSource::NpcDialogue,
+        );
+        memory.create_relationship(mira, guild, RelationshipType::MemberOf);
+        memory.advance_turn();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("memory.json");
+        session.save(&path).await.unwrap();
+        let loaded = GameSession::load_with_dm(&path, DungeonMaster::new("test-key"))
+            .await
+            .unwrap();
+
+        let original = session.dm.story_memory();
+        let memory = loaded.dm.story_memory();
+        assert_eq!(memory.current_turn(), original.current_turn());
+        assert_eq!(memory.find_entity_id("mira"), Some(mira));
+        assert_eq!(memory.find_entity_id("Thieves' Guild"), Some(guild));
+
+        let facts = |m: &StoryMemory, id| -> Vec<(String, StoryFactCategory)> {
+            m.facts_about(id)
+                .iter()
+                .map(|f| (f.content.clone(), f.category))
+                .collect()
+        };
+        assert_eq!(facts(memory, mira), facts(original, mira));
+        assert_eq!(facts(memory, guild), facts(original, guild));
+        assert_eq!(facts(memory, mira).len(), 2);
+
+        let relationship = memory.find_relationship(mira, guild).unwrap();
+        assert_eq!(relationship.relationship_type, RelationshipType::MemberOf);
+        assert_eq!(memory.relationships_of(guild).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_checkpoints_rewind_and_persist() {
+        use crate::dm::DungeonMaster;
+        use crate::world::NarrativeType;
+
+        let world = GameWorld::new("Rewind Test", create_sample_fighter("Roland"));
+        let mut session = GameSession::with_world(DungeonMaster::new("test-key"), world);
+
+        session.checkpoint("tavern");
+        session.world_mut().current_location.name = "Dragon's Lair".to_string();
+        session.world_mut().player_character.hit_points.current = 12;
+        session.checkpoint("before the dragon");
+        session.world_mut().player_character.hit_points.current = 1;
+        session
+            .world_mut()
+            .add_narrative("Fire everywhere!".to_string(), NarrativeType::Combat);
+
+        let labels: Vec<&str> = session
+            .list_checkpoints()
+            .iter()
+            .map(|c| c.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["tavern", "before the dragon"]);
+
+        session.rewind("before the dragon").unwrap();
+        assert_eq!(session.current_location(), "Dragon's Lair");
+        assert_eq!(session.hp_status(), (12, 28));
+        assert!(session.world().narrative_history.is_empty());
+
+        session.rewind("tavern").unwrap();
+        assert_ne!(session.current_location(), "Dragon's Lair");
+        assert_eq!(session.hp_status(), (28, 28));
+
+        assert!(matches!(
+            session.rewind("nowhere"),
+            Err(SessionError::UnknownCheckpoint(_))
+        ));
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("save.json");
+        session.save(&path).await.unwrap();
+        let saved: SavedSession =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.checkpoints.len(), 2);
+        assert_eq!(
+            saved.checkpoints[1]
+                .world
+                .player_character
+                .hit_points
+                .current,
+            12
+        );
+    }
+
+    #[tokio::test]
+    async fn test_player_action_stream_yields_deltas_before_effects() {
+        use crate::dm::DungeonMaster;
+        use claude::testing::{mock_server, sse, MockResponse};
+        use serde_json::json;
+
+        // Stream the first delta, then hold the rest until the test has seen it
+        let (release, held) = tokio::sync::oneshot::channel::<()>();
+        let opening = MockResponse::events(&[
+            json!({"type": "message_start", "message": {"id": "msg_1", "model": "claude-test", "usage": {"input_tokens": 10, "output_tokens": 1}}}),
+            json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text"}}),
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "The door "}}),
+        ]);
+        let closing = sse(&[
+            json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "creaks open."}}),
+            json!({"type": "content_block_stop", "index": 0}),
+            json!({"type": "message_delta", "delta": {"stop_reason": "end_turn"}, "usage": {"output_tokens": 5}}),
+            json!({"type": "message_stop"}),
+        ]);
+        let (url, _) = mock_server(vec![opening.then_after(held, closing)]).await;
+
+        let dm = DungeonMaster::new("test-key")
+            .with_client(claude::Claude::new("test-key").with_base_url(url));
+        let world = GameWorld::new("Stream Test", create_sample_fighter("Roland"));
+        let mut session = GameSession::with_world(dm, world);
+        let mut stream = Box::pin(session.player_action_stream("I open the door"));
+
+        let first = stream.next().await.unwrap().unwrap();
+        assert!(matches!(first, NarrativeChunk::Text(ref text) if text == "The door "));
+        release.send(()).unwrap();
+
+        let rest: Vec<NarrativeChunk> = stream.map(|chunk| chunk.unwrap()).collect().await;
+        let Some((NarrativeChunk::Done(response), deltas)) = rest.split_last() else {
+            panic!("stream should end with the turn's effects: {rest:?}");
+        };
+        assert!(matches!(deltas, [NarrativeChunk::Text(text)] if text == "creaks open."));
+        assert_eq!(response.narrative, "The door creaks open.");
+        assert_eq!(session.stats().turns, 1);
+        assert_eq!(session.stats().total_input_tokens, 10);
+        assert_eq!(session.stats().total_output_tokens, 5);
+    }
 }
@@ -88,12 +88,40 @@ impl SpellRange {
     }
 }
 
+/// A spell's material component.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaterialComponent {
+    pub description: String,
+    /// Inventory item a costly component must be, e.g. "Diamond"
+    pub item: Option<String>,
+    /// Minimum value in gold; a focus or component pouch can't stand in for it
+    pub cost_gp: Option<u32>,
+    /// The spell uses the component up
+    pub consumed: bool,
+}
+
+impl MaterialComponent {
+    pub fn new(description: &str) -> Self {
+        Self { description: description.to_string(), item: None, cost_gp: None, consumed: false }
+    }
+
+    /// A component worth at least `cost_gp`, which the caster must carry as `item`.
+    pub fn costly(description: &str, item: &str, cost_gp: u32, consumed: bool) -> Self {
+        Self {
+            description: description.to_string(),
+            item: Some(item.to_string()),
+            cost_gp: Some(cost_gp),
+            consumed,
+        }
+    }
+}
+
 /// Spell components required.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Components {
     pub verbal: bool,
     pub somatic: bool,
-    pub material: Option<String>,
+    pub material: Option<MaterialComponent>,
 }
 
 impl Components {
@@ -106,7 +134,11 @@ impl Components {
     }
 
     pub fn vsm(material: &str) -> Self {
-        Self { verbal: true, somatic: true, material: Some(material.to_string()) }
+        Self { verbal: true, somatic: true, material: Some(MaterialComponent::new(material)) }
+    }
+
+    pub fn vs_costly(material: MaterialComponent) -> Self {
+        Self { verbal: true, somatic: true, material: Some(material) }
     }
 
     pub fn s() -> Self {
@@ -114,7 +146,7 @@ impl Components {
     }
 
     pub fn sm(material: &str) -> Self {
-        Self { verbal: false, somatic: true, material: Some(material.to_string()) }
+        Self { verbal: false, somatic: true, material: Some(MaterialComponent::new(material)) }
     }
 
     pub fn description(&self) -> String {
@@ -124,7 +156,7 @@ impl Components {
         if self.material.is_some() { parts.push("M"); }
         let base = parts.join(", ");
         if let Some(ref mat) = self.material {
-            format!("{} ({})", base, mat)
+            format!("{} ({})", base, mat.description)
         } else {
             base
         }
@@ -647,6 +679,36 @@ fn build_spell_database() -> HashMap<String, SpellData> {
         classes: vec![SpellClass::Cleric],
     });
 
+    db.insert("detect magic".to_string(), SpellData {
+        name: "Detect Magic".to_string(),
+        level: 1,
+        school: SpellSchool::Divination,
+        casting_time: CastingTime::Action,
+        range: SpellRange::Self_,
+        components: Components::vs(),
+        duration: SpellDuration::Minutes(10),
+        concentration: true,
+        ritual: true,
+        description: "For the duration, you sense the presence of magic within 30 feet of you. If you sense magic in this way, you can use your action to see a faint aura around any visible creature or object in the area that bears magic, and you learn its school of magic, if any.".to_string(),
+        damage_dice: None,
+        damage_type: None,
+        damage_scaling: DamageScaling::None,
+        healing_dice: None,
+        save_type: None,
+        save_effect: None,
+        attack_type: None,
+        area_of_effect: AreaOfEffect::Sphere(30),
+        classes: vec![
+            SpellClass::Bard,
+            SpellClass::Cleric,
+            SpellClass::Druid,
+            SpellClass::Paladin,
+            SpellClass::Ranger,
+            SpellClass::Sorcerer,
+            SpellClass::Wizard,
+        ],
+    });
+
     db.insert("mage armor".to_string(), SpellData {
         name: "Mage Armor".to_string(),
         level: 1,
@@ -985,6 +1047,33 @@ fn build_spell_database() -> HashMap<String, SpellData> {
         classes: vec![SpellClass::Cleric],
     });
 
+    db.insert("revivify".to_string(), SpellData {
+        name: "Revivify".to_string(),
+        level: 3,
+        school: SpellSchool::Necromancy,
+        casting_time: CastingTime::Action,
+        range: SpellRange::Touch,
+        components: Components::vs_costly(MaterialComponent::costly(
+            "a diamond worth 300+ GP, which the spell consumes",
+            "Diamond",
+            300,
+            true,
+        )),
+        duration: SpellDuration::Instantaneous,
+        concentration: false,
+        ritual: false,
+        description: "You touch a creature that has died within the last minute. That creature revives with 1 Hit Point. This spell can't revive a creature that has died of old age, nor does it restore any missing body parts.".to_string(),
+        damage_dice: None,
+        damage_type: None,
+        damage_scaling: DamageScaling::None,
+        healing_dice: None,
+        save_type: None,
+        save_effect: None,
+        attack_type: None,
+        area_of_effect: AreaOfEffect::None,
+        classes: vec![SpellClass::Cleric, SpellClass::Druid, SpellClass::Paladin, SpellClass::Ranger],
+    });
+
     db
 }
 
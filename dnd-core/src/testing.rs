@@ -6,8 +6,31 @@
 //! - Assertion helpers for verifying game state
 
 use crate::dm::{DmResponse, StoryMemory};
-use crate::rules::{Intent, RulesEngine};
+use crate::rules::{Effect, Intent, RulesEngine};
 use crate::world::{create_sample_fighter, Character, GameWorld, NarrativeType};
+use thiserror::Error;
+
+/// What a [`MockDm`] does once every scripted response has been used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WhenExhausted {
+    /// Reply with a fixed "no more scripted responses" narrative.
+    #[default]
+    Fallback,
+    /// Keep returning the last scripted response.
+    RepeatLast,
+    /// Fail with [`MockExhausted`].
+    Error,
+}
+
+/// A [`MockDm`] ran out of scripted responses.
+#[derive(Debug, Error)]
+#[error("Mock DM has no response for turn {turn} (only {scripted} scripted)")]
+pub struct MockExhausted {
+    /// The 1-based turn that had no response.
+    pub turn: usize,
+    /// How many responses were scripted.
+    pub scripted: usize,
+}
 
 /// A mock DM that returns scripted responses.
 ///
@@ -17,6 +40,8 @@ pub struct MockDm {
     responses: Vec<MockResponse>,
     /// Index of next response to return.
     response_index: usize,
+    /// Behavior once `responses` is used up.
+    when_exhausted: WhenExhausted,
     /// Story memory (shared with real DM API).
     story_memory: StoryMemory,
     /// Rules engine for resolving intents.
@@ -30,6 +55,8 @@ pub struct MockResponse {
     pub narrative: String,
     /// Intents to execute (will be resolved by rules engine).
     pub intents: Vec<Intent>,
+    /// Effects to apply directly, skipping the rules engine.
+    pub effects: Vec<Effect>,
 }
 
 impl MockResponse {
@@ -38,6 +65,7 @@ impl MockResponse {
         Self {
             narrative: text.into(),
             intents: Vec::new(),
+            effects: Vec::new(),
         }
     }
 
@@ -46,6 +74,16 @@ impl MockResponse {
         Self {
             narrative: text.into(),
             intents,
+            effects: Vec::new(),
+        }
+    }
+
+    /// Create a response whose effects are applied as-is.
+    pub fn with_effects(text: impl Into<String>, effects: Vec<Effect>) -> Self {
+        Self {
+            narrative: text.into(),
+            intents: Vec::new(),
+            effects,
         }
     }
 }
@@ -56,27 +94,69 @@ impl MockDm {
         Self {
             responses,
             response_index: 0,
+            when_exhausted: WhenExhausted::default(),
             story_memory: StoryMemory::new(),
             rules: RulesEngine::new(),
         }
     }
 
+    /// Create a mock DM that plays `responses` in order, one per turn.
+    ///
+    /// Combine with [`with_exhausted`](Self::with_exhausted) to choose what
+    /// happens after the last one.
+    pub fn with_sequence(responses: Vec<MockResponse>) -> Self {
+        Self::new(responses)
+    }
+
+    /// Set what happens once every scripted response has been used.
+    pub fn with_exhausted(mut self, when_exhausted: WhenExhausted) -> Self {
+        self.when_exhausted = when_exhausted;
+        self
+    }
+
     /// Process input and return the next scripted response.
     ///
     /// Intents are resolved through the real rules engine.
-    pub fn process_input(&mut self, _input: &str, world: &mut GameWorld) -> DmResponse {
-        // Advance story memory turn
-        self.story_memory.advance_turn();
+    ///
+    /// # Panics
+    ///
+    /// Panics if the script is exhausted and the mock was configured with
+    /// [`WhenExhausted::Error`]; use [`try_process_input`](Self::try_process_input)
+    /// to handle that case.
+    pub fn process_input(&mut self, input: &str, world: &mut GameWorld) -> DmResponse {
+        match self.try_process_input(input, world) {
+            Ok(response) => response,
+            Err(e) => panic!("{e}"),
+        }
+    }
 
-        // Get next response or return default
+    /// Process input, failing if the script is exhausted under [`WhenExhausted::Error`].
+    pub fn try_process_input(
+        &mut self,
+        _input: &str,
+        world: &mut GameWorld,
+    ) -> Result<DmResponse, MockExhausted> {
+        // Get next response or fall back per the exhaustion behavior
         let response = if self.response_index < self.responses.len() {
             let r = self.responses[self.response_index].clone();
             self.response_index += 1;
             r
         } else {
-            MockResponse::narrative("The DM has no more scripted responses.")
+            match (self.when_exhausted, self.responses.last()) {
+                (WhenExhausted::RepeatLast, Some(last)) => last.clone(),
+                (WhenExhausted::Error, _) => {
+                    return Err(MockExhausted {
+                        turn: self.response_index + 1,
+                        scripted: self.responses.len(),
+                    })
+                }
+                _ => MockResponse::narrative("The DM has no more scripted responses."),
+            }
         };
 
+        // Advance story memory turn
+        self.story_memory.advance_turn();
+
         // Resolve intents through rules engine
         let mut all_effects = Vec::new();
         let mut all_resolutions = Vec::new();
@@ -88,15 +168,20 @@ impl MockDm {
             all_resolutions.push(resolution);
         }
 
+        // Scripted effects bypass the rules engine
+        crate::rules::apply_effects(world, &response.effects);
+        all_effects.extend(response.effects);
+
         // Add narrative to world
         world.add_narrative(response.narrative.clone(), NarrativeType::DmNarration);
 
-        DmResponse {
+        Ok(DmResponse {
             narrative: response.narrative,
             intents: response.intents,
             effects: all_effects,
             resolutions: all_resolutions,
-        }
+            usage: claude::Usage::default(),
+        })
     }
 
     /// Get the story memory.
@@ -158,6 +243,14 @@ impl TestHarness {
         self
     }
 
+    /// Create a test harness whose DM plays `responses` in order.
+    pub fn with_sequence(responses: Vec<MockResponse>) -> Self {
+        Self {
+            dm: MockDm::with_sequence(responses),
+            ..Self::new()
+        }
+    }
+
     /// Send player input and get response.
     pub fn input(&mut self, text: &str) -> DmResponse {
         // Add player input to world narrative
@@ -168,6 +261,13 @@ impl TestHarness {
         self.dm.process_input(text, &mut self.world)
     }
 
+    /// Send player input, failing if the mock DM's script is exhausted.
+    pub fn try_input(&mut self, text: &str) -> Result<DmResponse, MockExhausted> {
+        self.world
+            .add_narrative(text.to_string(), NarrativeType::PlayerAction);
+        self.dm.try_process_input(text, &mut self.world)
+    }
+
     /// Get current player HP as (current, max).
     pub fn player_hp(&self) -> (i32, i32) {
         let hp = &self.world.player_character.hit_points;
@@ -203,11 +303,11 @@ impl TestHarness {
         self.dm.story_memory().find_entity_by_name(name).is_some()
     }
 
-    /// Get the last narrative entry.
+    /// Get the last narrative entry, skipping dice rolls.
     pub fn last_narrative(&self) -> Option<&str> {
         self.world
-            .narrative_history
-            .last()
+            .recent_narrative(1)
+            .first()
             .map(|e| e.content.as_str())
     }
 }
@@ -268,8 +368,7 @@ mod tests {
     use super::*;
     use crate::dice::Advantage;
     use crate::rules::DamageType;
-    use crate::rules::Intent;
-    use crate::world::Skill;
+    use crate::world::{Lighting, Size, Skill};
 
     #[test]
     fn test_mock_dm_basic() {
@@ -282,6 +381,59 @@ mod tests {
         assert!(response.intents.is_empty());
     }
 
+    #[test]
+    fn test_mock_dm_sequence() {
+        let mut harness = TestHarness::new();
+        let player = harness.world.player_character.id;
+        harness.dm = MockDm::with_sequence(vec![
+            MockResponse::narrative("You enter the crypt."),
+            MockResponse::with_effects(
+                "A dart trap grazes you.",
+                vec![Effect::HpChanged {
+                    target_id: player,
+                    amount: -3,
+                    new_current: 25,
+                    new_max: 28,
+                    dropped_to_zero: false,
+                }],
+            ),
+            MockResponse::narrative("The crypt falls silent."),
+        ])
+        .with_exhausted(WhenExhausted::Error);
+
+        assert_eq!(harness.input("I go in").narrative, "You enter the crypt.");
+        let trap = harness.input("I step forward");
+        assert_eq!(trap.narrative, "A dart trap grazes you.");
+        assert_eq!(trap.effects.len(), 1);
+        assert_hp(&harness, 25, 28);
+        assert_eq!(harness.input("I wait").narrative, "The crypt falls silent.");
+
+        let err = harness.try_input("I wait some more").unwrap_err();
+        assert_eq!((err.turn, err.scripted), (4, 3));
+    }
+
+    #[test]
+    fn test_mock_dm_when_exhausted() {
+        let script = vec![
+            MockResponse::narrative("Rain falls."),
+            MockResponse::narrative("The rain keeps falling."),
+        ];
+
+        let mut harness = TestHarness::with_sequence(script.clone());
+        harness.input("I wait");
+        harness.input("I wait");
+        assert_eq!(
+            harness.input("I wait").narrative,
+            "The DM has no more scripted responses."
+        );
+
+        harness.dm = MockDm::with_sequence(script).with_exhausted(WhenExhausted::RepeatLast);
+        harness.input("I wait");
+        for _ in 0..3 {
+            assert_eq!(harness.input("I wait").narrative, "The rain keeps falling.");
+        }
+    }
+
     #[test]
     fn test_mock_dm_with_damage() {
         let mut harness = TestHarness::new();
@@ -315,6 +467,7 @@ mod tests {
                 dc: 12,
                 advantage: Advantage::Normal,
                 description: "Sneaking past the guard".to_string(),
+                lighting: Lighting::Bright,
             }],
         ));
 
@@ -326,8 +479,8 @@ mod tests {
 
     #[test]
     fn test_harness_combat_flow() {
-        use crate::rules::CombatantInit;
-        use crate::world::CharacterId;
+        use crate::rules::{CombatantInit, DamageTraits};
+        use crate::world::{CharacterId, Senses};
 
         let mut harness = TestHarness::new();
 
@@ -345,6 +498,12 @@ mod tests {
                         max_hp: 10,
                         armor_class: 10,
                         initiative_modifier: 0,
+                        attack: None,
+                        position: None,
+                        size: Size::Medium,
+                        speed: 30,
+                        damage_traits: DamageTraits::default(),
+                        senses: Senses::default(),
                     },
                     CombatantInit {
                         id: CharacterId::new(),
@@ -355,6 +514,12 @@ mod tests {
                         max_hp: 7,
                         armor_class: 13, // Goblin AC from SRD
                         initiative_modifier: 2, // Goblin DEX +2 from SRD
+                        attack: None,
+                        position: None,
+                        size: Size::Medium,
+                        speed: 30,
+                        damage_traits: DamageTraits::default(),
+                        senses: Senses::default(),
                     },
                 ],
             }],
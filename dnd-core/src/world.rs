@@ -3,10 +3,12 @@
 //! Contains all types for representing game state: characters, locations,
 //! NPCs, quests, combat, conditions, and the complete game world.
 
-use crate::dice::DieType;
+use crate::dice::{DieType, RollResult};
+use crate::feats::FEAT_SOURCE;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fmt;
+use thiserror::Error;
 use uuid::Uuid;
 
 // ============================================================================
@@ -164,6 +166,17 @@ impl AbilityScores {
     }
 }
 
+/// A temporary change to one ability score, such as Enhance Ability or a
+/// Strength-draining poison.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AbilityMod {
+    pub ability: Ability,
+    pub delta: i8,
+    pub source: String,
+    /// Turns until it wears off; `None` lasts until removed.
+    pub expires_in_rounds: Option<u32>,
+}
+
 impl Default for AbilityScores {
     fn default() -> Self {
         Self::new(10, 10, 10, 10, 10, 10)
@@ -341,6 +354,9 @@ pub struct ActiveCondition {
     pub condition: Condition,
     pub source: String,
     pub duration_rounds: Option<u32>,
+    /// Game minutes left, for conditions that outlast combat
+    #[serde(default)]
+    pub duration_minutes: Option<u32>,
 }
 
 impl ActiveCondition {
@@ -349,6 +365,7 @@ impl ActiveCondition {
             condition,
             source: source.into(),
             duration_rounds: None,
+            duration_minutes: None,
         }
     }
 
@@ -356,6 +373,11 @@ impl ActiveCondition {
         self.duration_rounds = Some(rounds);
         self
     }
+
+    pub fn with_duration_minutes(mut self, minutes: u32) -> Self {
+        self.duration_minutes = Some(minutes);
+        self
+    }
 }
 
 // ============================================================================
@@ -395,7 +417,7 @@ impl HitPoints {
             }
         }
 
-        self.current -= remaining;
+        self.current = (self.current - remaining).max(0);
         DamageResult {
             damage_taken: amount,
             dropped_to_zero: self.current <= 0,
@@ -455,6 +477,20 @@ impl HitDice {
         false
     }
 
+    /// Largest die type with any dice left to spend.
+    pub fn largest_remaining(&self) -> Option<DieType> {
+        self.remaining
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(die_type, _)| *die_type)
+            .max_by_key(|die_type| die_type.sides())
+    }
+
+    /// Total number of unspent hit dice across all die types.
+    pub fn total_remaining(&self) -> u8 {
+        self.remaining.values().sum()
+    }
+
     pub fn recover_half(&mut self) {
         for (die_type, total) in &self.total {
             let to_recover = (*total as f32 / 2.0).ceil() as u8;
@@ -470,6 +506,9 @@ impl HitDice {
 pub struct DeathSaves {
     pub successes: u8,
     pub failures: u8,
+    /// Stabilized at 0 HP: no more death saves until hurt again.
+    #[serde(default)]
+    pub stable: bool,
 }
 
 impl DeathSaves {
@@ -478,14 +517,22 @@ impl DeathSaves {
         self.successes >= 3
     }
 
+    /// Record a failure. Damage that causes one also ends stability.
     pub fn add_failure(&mut self) -> bool {
         self.failures += 1;
+        self.stable = false;
         self.failures >= 3
     }
 
     pub fn reset(&mut self) {
         self.successes = 0;
         self.failures = 0;
+        self.stable = false;
+    }
+
+    /// Three failures: the character is dead.
+    pub fn is_dead(&self) -> bool {
+        self.failures >= 3
     }
 }
 
@@ -531,13 +578,33 @@ impl Default for ArmorClass {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ArmorType {
     Light,
     Medium,
     Heavy,
 }
 
+/// Armor training: armor worn without it imposes disadvantage on Strength
+/// and Dexterity d20 tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArmorProficiency {
+    Light,
+    Medium,
+    Heavy,
+    Shields,
+}
+
+impl From<ArmorType> for ArmorProficiency {
+    fn from(armor_type: ArmorType) -> Self {
+        match armor_type {
+            ArmorType::Light => ArmorProficiency::Light,
+            ArmorType::Medium => ArmorProficiency::Medium,
+            ArmorType::Heavy => ArmorProficiency::Heavy,
+        }
+    }
+}
+
 /// Movement speed.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Speed {
@@ -564,6 +631,57 @@ impl Default for Speed {
     }
 }
 
+/// How well lit an area is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Lighting {
+    #[default]
+    Bright,
+    /// Lightly obscured: disadvantage on sight-based Perception.
+    Dim,
+    /// Heavily obscured: creatures can't see into it.
+    Darkness,
+}
+
+/// Special senses that let a creature perceive without light.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Senses {
+    pub darkvision_ft: u32,
+    pub blindsight_ft: u32,
+    #[serde(default)]
+    pub tremorsense_ft: u32,
+}
+
+impl Senses {
+    /// The senses a member of the given race is born with.
+    pub fn for_race(race: RaceType) -> Self {
+        Self {
+            darkvision_ft: race.darkvision_ft(),
+            blindsight_ft: 0,
+            tremorsense_ft: 0,
+        }
+    }
+
+    /// Whether this creature can pinpoint others without seeing them, so
+    /// invisibility doesn't hide them.
+    pub fn perceives_unseen(&self) -> bool {
+        self.blindsight_ft > 0 || self.tremorsense_ft > 0
+    }
+
+    /// The lighting as this creature experiences it. Darkvision treats dim
+    /// light as bright and darkness as dim; blindsight ignores light entirely.
+    pub fn perceived_lighting(&self, lighting: Lighting) -> Lighting {
+        if self.blindsight_ft > 0 {
+            return Lighting::Bright;
+        }
+        match lighting {
+            Lighting::Dim if self.darkvision_ft > 0 => Lighting::Bright,
+            Lighting::Darkness if self.darkvision_ft > 0 => Lighting::Dim,
+            other => other,
+        }
+    }
+}
+
 // ============================================================================
 // Classes and Features
 // ============================================================================
@@ -726,6 +844,10 @@ pub struct ClassResources {
     pub rage_rounds_remaining: Option<u8>,
     /// Current rage damage bonus (+2/+3/+4 based on level)
     pub rage_damage_bonus: i8,
+    /// Took damage since the end of the barbarian's last turn, which keeps
+    /// a rage going
+    #[serde(default)]
+    pub rage_damage_taken: bool,
 
     // Monk
     /// Current ki points (called "Monk's Focus" in SRD 5.2)
@@ -792,12 +914,10 @@ impl ClassResources {
                 self.bardic_inspiration_uses = 1;
                 self.max_bardic_inspiration = 1;
             }
-            CharacterClass::Monk => {
-                // Ki points equal Monk level (starting at level 2)
-                if level >= 2 {
-                    self.ki_points = level;
-                    self.max_ki_points = level;
-                }
+            // Ki points equal Monk level (starting at level 2)
+            CharacterClass::Monk if level >= 2 => {
+                self.ki_points = level;
+                self.max_ki_points = level;
             }
             CharacterClass::Cleric => {
                 // Channel Divinity starts fresh
@@ -810,12 +930,10 @@ impl ClassResources {
                 // Channel Divinity starts fresh
                 self.channel_divinity_used = false;
             }
-            CharacterClass::Sorcerer => {
-                // Sorcery points equal Sorcerer level (starting at level 2)
-                if level >= 2 {
-                    self.sorcery_points = level;
-                    self.max_sorcery_points = level;
-                }
+            // Sorcery points equal Sorcerer level (starting at level 2)
+            CharacterClass::Sorcerer if level >= 2 => {
+                self.sorcery_points = level;
+                self.max_sorcery_points = level;
             }
             CharacterClass::Fighter => {
                 self.action_surge_used = false;
@@ -828,14 +946,27 @@ impl ClassResources {
         }
     }
 
+    /// Refill what a rest restores: short-rest resources on either rest,
+    /// long-rest-only resources on a long rest.
+    pub fn restore(&mut self, rest_type: crate::rules::RestType, classes: &[ClassLevel]) {
+        for class_level in classes {
+            match rest_type {
+                crate::rules::RestType::Short => {
+                    self.short_rest_recovery(class_level.class, class_level.level)
+                }
+                crate::rules::RestType::Long => {
+                    self.long_rest_recovery(class_level.class, class_level.level)
+                }
+            }
+        }
+    }
+
     /// Reset resources on a short rest
     pub fn short_rest_recovery(&mut self, class: CharacterClass, level: u8) {
         match class {
-            CharacterClass::Bard => {
-                // Font of Inspiration (level 5+) allows recovery on short rest
-                if level >= 5 {
-                    self.bardic_inspiration_uses = self.max_bardic_inspiration;
-                }
+            // Font of Inspiration (level 5+) allows recovery on short rest
+            CharacterClass::Bard if level >= 5 => {
+                self.bardic_inspiration_uses = self.max_bardic_inspiration;
             }
             CharacterClass::Fighter => {
                 self.action_surge_used = false;
@@ -846,12 +977,11 @@ impl ClassResources {
                 self.channel_divinity_used = false;
             }
             CharacterClass::Monk => {
-                // Ki points don't recover on short rest in base rules
-                // (Uncanny Metabolism at level 2 lets them recover some)
+                // Monk's Focus points recover on a short rest
+                self.ki_points = self.max_ki_points;
             }
             _ => {}
         }
-        let _ = level; // Used for Bard Font of Inspiration check
     }
 
     /// Reset resources on a long rest
@@ -868,9 +998,6 @@ impl ClassResources {
                 // Full recovery on long rest
                 self.bardic_inspiration_uses = self.max_bardic_inspiration;
             }
-            CharacterClass::Monk => {
-                self.ki_points = self.max_ki_points;
-            }
             CharacterClass::Paladin => {
                 self.lay_on_hands_pool = self.lay_on_hands_max;
             }
@@ -897,6 +1024,13 @@ pub struct SpellcastingData {
     pub spells_prepared: Vec<String>,
     pub cantrips_known: Vec<String>,
     pub spell_slots: SpellSlots,
+    /// Warlock Pact Magic slots, kept apart from the standard table.
+    #[serde(default)]
+    pub pact_slots: Option<PactMagic>,
+    /// Spells were prepared since the last long rest, so the list is fixed
+    /// until the next one.
+    #[serde(default)]
+    pub prepared_since_rest: bool,
 }
 
 impl SpellcastingData {
@@ -947,6 +1081,50 @@ impl Default for SpellSlots {
     }
 }
 
+/// Pact Magic: a few slots, all of the warlock's highest slot level, that
+/// recharge on a short rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PactMagic {
+    pub count: u8,
+    pub level: u8,
+    #[serde(default)]
+    pub used: u8,
+}
+
+impl PactMagic {
+    /// The Pact Magic slots of a warlock of the given level.
+    pub fn for_level(warlock_level: u8) -> Self {
+        let count = match warlock_level {
+            0..=1 => 1,
+            2..=10 => 2,
+            11..=16 => 3,
+            _ => 4,
+        };
+        let level = warlock_level.div_ceil(2).clamp(1, 5);
+        Self {
+            count,
+            level,
+            used: 0,
+        }
+    }
+
+    pub fn available(&self) -> u8 {
+        self.count.saturating_sub(self.used)
+    }
+
+    pub fn use_slot(&mut self) -> bool {
+        if self.available() > 0 {
+            self.used += 1;
+            return true;
+        }
+        false
+    }
+
+    pub fn recover_all(&mut self) {
+        self.used = 0;
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct SlotInfo {
     pub total: u8,
@@ -973,6 +1151,9 @@ pub struct Item {
     pub description: Option<String>,
     pub item_type: ItemType,
     pub magical: bool,
+    /// Magic items whose properties only work for an attuned wearer.
+    #[serde(default)]
+    pub requires_attunement: bool,
 }
 
 impl Item {
@@ -988,7 +1169,7 @@ impl Item {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ItemType {
     Weapon,
     Armor,
@@ -1000,14 +1181,237 @@ pub enum ItemType {
     Wondrous,
     Adventuring,
     Tool,
+    #[default]
     Other,
 }
 
+/// Coin denominations, from least to most valuable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Coin {
+    Copper,
+    Silver,
+    Electrum,
+    Gold,
+    Platinum,
+}
+
+impl Coin {
+    pub const ALL: [Coin; 5] = [
+        Coin::Copper,
+        Coin::Silver,
+        Coin::Electrum,
+        Coin::Gold,
+        Coin::Platinum,
+    ];
+
+    /// Value of one coin in copper pieces.
+    pub fn value_cp(&self) -> u64 {
+        match self {
+            Coin::Copper => 1,
+            Coin::Silver => 10,
+            Coin::Electrum => 50,
+            Coin::Gold => 100,
+            Coin::Platinum => 1000,
+        }
+    }
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Coin::Copper => "cp",
+            Coin::Silver => "sp",
+            Coin::Electrum => "ep",
+            Coin::Gold => "gp",
+            Coin::Platinum => "pp",
+        }
+    }
+
+    /// Parse an abbreviation ("gp") or name ("gold").
+    pub fn parse(s: &str) -> Option<Coin> {
+        match s.to_lowercase().as_str() {
+            "cp" | "copper" => Some(Coin::Copper),
+            "sp" | "silver" => Some(Coin::Silver),
+            "ep" | "electrum" => Some(Coin::Electrum),
+            "gp" | "gold" => Some(Coin::Gold),
+            "pp" | "platinum" => Some(Coin::Platinum),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Coin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+/// Coins carried by a character, tracked per denomination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Purse {
+    pub cp: u32,
+    pub sp: u32,
+    pub ep: u32,
+    pub gp: u32,
+    pub pp: u32,
+}
+
+impl Purse {
+    /// A purse holding the given value in the fewest copper, silver, and gold coins.
+    pub fn from_cp(total_cp: u64) -> Self {
+        let mut purse = Purse::default();
+        purse.add_value(total_cp);
+        purse
+    }
+
+    /// A purse holding the given gold value (rounded to the nearest copper).
+    pub fn from_gp(gold: f64) -> Self {
+        Self::from_cp((gold.max(0.0) * 100.0).round() as u64)
+    }
+
+    pub fn count(&self, coin: Coin) -> u32 {
+        match coin {
+            Coin::Copper => self.cp,
+            Coin::Silver => self.sp,
+            Coin::Electrum => self.ep,
+            Coin::Gold => self.gp,
+            Coin::Platinum => self.pp,
+        }
+    }
+
+    fn count_mut(&mut self, coin: Coin) -> &mut u32 {
+        match coin {
+            Coin::Copper => &mut self.cp,
+            Coin::Silver => &mut self.sp,
+            Coin::Electrum => &mut self.ep,
+            Coin::Gold => &mut self.gp,
+            Coin::Platinum => &mut self.pp,
+        }
+    }
+
+    /// Add coins of a single denomination.
+    pub fn add(&mut self, coin: Coin, count: u32) {
+        let slot = self.count_mut(coin);
+        *slot = slot.saturating_add(count);
+    }
+
+    /// Add a value in the fewest copper, silver, and gold coins, leaving the
+    /// coins already in the purse as they are.
+    pub fn add_value(&mut self, value_cp: u64) {
+        self.add_change(value_cp, Coin::Platinum);
+    }
+
+    /// Total value of all coins in copper pieces.
+    pub fn total_cp(&self) -> u64 {
+        Coin::ALL
+            .iter()
+            .map(|coin| self.count(*coin) as u64 * coin.value_cp())
+            .sum()
+    }
+
+    /// Total value of all coins in gold pieces.
+    pub fn total_gp(&self) -> f64 {
+        self.total_cp() as f64 / 100.0
+    }
+
+    /// Consolidate loose coins: 10 cp become 1 sp, 10 sp become 1 gp, and
+    /// 2 ep become 1 gp. Platinum is never minted from gold.
+    pub fn normalize(&mut self) {
+        self.sp += self.cp / 10;
+        self.cp %= 10;
+        self.gp += self.sp / 10;
+        self.sp %= 10;
+        self.gp += self.ep / 2;
+        self.ep %= 2;
+    }
+
+    /// Pay a cost, spending the least valuable coins first and breaking a
+    /// larger coin for change only when the small coins run out.
+    pub fn pay(&mut self, cost_cp: u64) -> Result<(), &'static str> {
+        if cost_cp > self.total_cp() {
+            return Err("Insufficient funds");
+        }
+
+        let mut remaining = cost_cp;
+        for coin in Coin::ALL {
+            let used = (remaining / coin.value_cp()).min(self.count(coin) as u64);
+            *self.count_mut(coin) -= used as u32;
+            remaining -= used * coin.value_cp();
+        }
+
+        if remaining > 0 {
+            // Every denomination still holding coins is worth more than what's left,
+            // so break the smallest one and take change.
+            let coin = Coin::ALL
+                .into_iter()
+                .find(|coin| self.count(*coin) > 0)
+                .ok_or("Insufficient funds")?;
+            *self.count_mut(coin) -= 1;
+            self.add_change(coin.value_cp() - remaining, coin);
+        }
+        Ok(())
+    }
+
+    /// Add a value as change, in the largest copper/silver/gold coins below `below`.
+    fn add_change(&mut self, mut value_cp: u64, below: Coin) {
+        for coin in [Coin::Gold, Coin::Silver, Coin::Copper] {
+            if coin.value_cp() < below.value_cp() {
+                let count = value_cp / coin.value_cp();
+                self.add(coin, count as u32);
+                value_cp -= count * coin.value_cp();
+            }
+        }
+    }
+}
+
+impl fmt::Display for Purse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = Coin::ALL
+            .iter()
+            .rev()
+            .filter(|coin| self.count(**coin) > 0)
+            .map(|coin| format!("{} {}", self.count(*coin), coin))
+            .collect();
+        if parts.is_empty() {
+            write!(f, "0 gp")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Purse {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Older saves stored a single `gold: f32`; accept that as well as coins.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Gold(f64),
+            Coins {
+                #[serde(default)]
+                cp: u32,
+                #[serde(default)]
+                sp: u32,
+                #[serde(default)]
+                ep: u32,
+                #[serde(default)]
+                gp: u32,
+                #[serde(default)]
+                pp: u32,
+            },
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Gold(gold) => Purse::from_gp(gold),
+            Repr::Coins { cp, sp, ep, gp, pp } => Purse { cp, sp, ep, gp, pp },
+        })
+    }
+}
+
 /// Character inventory.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Inventory {
     pub items: Vec<Item>,
-    pub gold: f32,
+    #[serde(alias = "gold")]
+    pub purse: Purse,
 }
 
 // ============================================================================
@@ -1027,6 +1431,117 @@ impl Equipment {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Combined weight of everything equipped.
+    pub fn total_weight(&self) -> f32 {
+        self.armor.as_ref().map_or(0.0, |a| a.base.weight)
+            + self.shield.as_ref().map_or(0.0, |s| s.weight)
+            + self.main_hand.as_ref().map_or(0.0, |w| w.base.weight)
+            + self.off_hand.as_ref().map_or(0.0, |i| i.weight)
+    }
+
+    /// Both hands are holding something, leaving none free for somatic
+    /// spell components.
+    pub fn hands_full(&self) -> bool {
+        self.main_hand.is_some() && (self.shield.is_some() || self.off_hand.is_some())
+    }
+
+    /// Check that an item can go into a slot without overfilling the
+    /// character's hands: a two-handed weapon leaves no room for a shield or
+    /// off-hand item, and the reverse.
+    pub fn can_equip(
+        &self,
+        slot: &str,
+        item_name: &str,
+        items: &crate::items::ItemRegistry,
+    ) -> Result<(), EquipError> {
+        match slot {
+            "shield" | "off_hand" => match self.main_hand {
+                Some(ref weapon) if weapon.is_two_handed() => {
+                    Err(EquipError::TwoHandedWeaponHeld {
+                        item: item_name.to_string(),
+                        weapon: weapon.base.name.clone(),
+                    })
+                }
+                _ => Ok(()),
+            },
+            "main_hand" | "weapon" => {
+                let two_handed = items
+                    .get_weapon(item_name)
+                    .is_some_and(|w| w.is_two_handed());
+                match self.shield.as_ref().or(self.off_hand.as_ref()) {
+                    Some(held) if two_handed => Err(EquipError::HandOccupied {
+                        weapon: item_name.to_string(),
+                        held: held.name.clone(),
+                    }),
+                    _ => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Why an item can't be equipped.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EquipError {
+    #[error("Cannot equip {item} - {weapon} requires two hands")]
+    TwoHandedWeaponHeld { item: String, weapon: String },
+
+    #[error("Cannot equip {weapon} - it requires two hands but {held} is equipped. Unequip the {held} first.")]
+    HandOccupied { weapon: String, held: String },
+}
+
+/// How weighed down a character is, using the variant encumbrance rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Encumbrance {
+    /// Carrying up to 5 x STR pounds.
+    Unencumbered,
+    /// Carrying more than 5 x STR pounds: speed drops by 10 feet.
+    Encumbered,
+    /// Carrying more than 10 x STR pounds: speed drops by 20 feet, and
+    /// disadvantage on STR, DEX, and CON checks, attacks, and saves.
+    HeavilyEncumbered,
+    /// Carrying more than the 15 x STR carrying capacity: can barely move.
+    OverCapacity,
+}
+
+impl Encumbrance {
+    /// Encumbrance for a load in pounds carried with the given Strength score.
+    pub fn for_load(weight: f32, strength: u8) -> Self {
+        let strength = strength as f32;
+        if weight > strength * 15.0 {
+            Encumbrance::OverCapacity
+        } else if weight > strength * 10.0 {
+            Encumbrance::HeavilyEncumbered
+        } else if weight > strength * 5.0 {
+            Encumbrance::Encumbered
+        } else {
+            Encumbrance::Unencumbered
+        }
+    }
+
+    /// Feet subtracted from walking speed.
+    pub fn speed_penalty(&self) -> u32 {
+        match self {
+            Encumbrance::Unencumbered => 0,
+            Encumbrance::Encumbered => 10,
+            Encumbrance::HeavilyEncumbered => 20,
+            // Beyond capacity a creature can only push or drag at 5 feet
+            Encumbrance::OverCapacity => u32::MAX,
+        }
+    }
+}
+
+impl fmt::Display for Encumbrance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encumbrance::Unencumbered => write!(f, "unencumbered"),
+            Encumbrance::Encumbered => write!(f, "encumbered"),
+            Encumbrance::HeavilyEncumbered => write!(f, "heavily encumbered"),
+            Encumbrance::OverCapacity => write!(f, "over carrying capacity"),
+        }
+    }
 }
 
 /// Armor with D&D 5e properties.
@@ -1037,6 +1552,9 @@ pub struct ArmorItem {
     pub base_ac: u8,
     pub strength_requirement: Option<u8>,
     pub stealth_disadvantage: bool,
+    /// Enchantment bonus to AC (e.g. +1 armor).
+    #[serde(default)]
+    pub magic_bonus: i8,
 }
 
 impl ArmorItem {
@@ -1050,11 +1568,13 @@ impl ArmorItem {
                 description: None,
                 item_type: ItemType::Armor,
                 magical: false,
+                requires_attunement: false,
             },
             armor_type,
             base_ac,
             strength_requirement: None,
             stealth_disadvantage: false,
+            magic_bonus: 0,
         }
     }
 
@@ -1082,6 +1602,18 @@ impl ArmorItem {
         self.base.magical = true;
         self
     }
+
+    pub fn with_magic_bonus(mut self, bonus: i8) -> Self {
+        self.base.magical = true;
+        self.magic_bonus = bonus;
+        self
+    }
+
+    pub fn requiring_attunement(mut self) -> Self {
+        self.base.magical = true;
+        self.base.requires_attunement = true;
+        self
+    }
 }
 
 /// Weapons with D&D 5e properties.
@@ -1092,6 +1624,11 @@ pub struct WeaponItem {
     pub damage_type: WeaponDamageType,
     pub properties: Vec<WeaponProperty>,
     pub range: Option<(u32, u32)>,
+    /// Enchantment bonus to attack and damage rolls (e.g. +1 weapon).
+    #[serde(default)]
+    pub magic_bonus: i8,
+    #[serde(default)]
+    pub category: WeaponCategory,
 }
 
 impl WeaponItem {
@@ -1105,11 +1642,14 @@ impl WeaponItem {
                 description: None,
                 item_type: ItemType::Weapon,
                 magical: false,
+                requires_attunement: false,
             },
             damage_dice: damage_dice.into(),
             damage_type,
             properties: Vec::new(),
             range: None,
+            magic_bonus: 0,
+            category: WeaponCategory::Simple,
         }
     }
 
@@ -1123,6 +1663,11 @@ impl WeaponItem {
         self
     }
 
+    pub fn martial(mut self) -> Self {
+        self.category = WeaponCategory::Martial;
+        self
+    }
+
     pub fn with_properties(mut self, properties: Vec<WeaponProperty>) -> Self {
         self.properties = properties;
         self
@@ -1138,6 +1683,18 @@ impl WeaponItem {
         self
     }
 
+    pub fn with_magic_bonus(mut self, bonus: i8) -> Self {
+        self.base.magical = true;
+        self.magic_bonus = bonus;
+        self
+    }
+
+    pub fn requiring_attunement(mut self) -> Self {
+        self.base.magical = true;
+        self.base.requires_attunement = true;
+        self
+    }
+
     pub fn is_finesse(&self) -> bool {
         self.properties.contains(&WeaponProperty::Finesse)
     }
@@ -1178,6 +1735,25 @@ impl WeaponDamageType {
     }
 }
 
+/// Simple or martial weapon, for proficiency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeaponCategory {
+    #[default]
+    Simple,
+    Martial,
+}
+
+/// Weapons a character adds their proficiency bonus to attacks with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WeaponProficiency {
+    Simple,
+    Martial,
+    /// Martial weapons with the Light property (Monk).
+    MartialLight,
+    /// Martial weapons with the Finesse or Light property (Rogue).
+    MartialFinesseOrLight,
+}
+
 /// Weapon properties per D&D 5e.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WeaponProperty {
@@ -1229,6 +1805,7 @@ impl ConsumableItem {
                 description: Some("A magical potion that restores health when consumed.".to_string()),
                 item_type: ItemType::Potion,
                 magical: true,
+                requires_attunement: false,
             },
             effect: ConsumableEffect::Healing { dice: dice.into(), bonus },
         }
@@ -1246,6 +1823,7 @@ impl ConsumableItem {
                 description: Some("A magical scroll containing a spell.".to_string()),
                 item_type: ItemType::Scroll,
                 magical: true,
+                requires_attunement: false,
             },
             effect: ConsumableEffect::CastSpell {
                 spell_name: spell_name_str,
@@ -1310,13 +1888,15 @@ impl Inventory {
 
     /// Adjust gold amount. Returns new total or error if insufficient funds.
     pub fn adjust_gold(&mut self, amount: f32) -> Result<f32, &'static str> {
-        let new_total = self.gold + amount;
-        if new_total < 0.0 {
-            Err("Insufficient gold")
+        let cp = (amount.abs() as f64 * 100.0).round() as u64;
+        if amount >= 0.0 {
+            self.purse.add_value(cp);
         } else {
-            self.gold = new_total;
-            Ok(self.gold)
+            let mut purse = self.purse;
+            purse.pay(cp).map_err(|_| "Insufficient gold")?;
+            self.purse = purse;
         }
+        Ok(self.purse.total_gp() as f32)
     }
 }
 
@@ -1379,6 +1959,19 @@ impl RaceType {
         }
     }
 
+    /// Range of the race's darkvision in feet, or 0 without it.
+    pub fn darkvision_ft(&self) -> u32 {
+        match self {
+            RaceType::Elf
+            | RaceType::Dwarf
+            | RaceType::HalfOrc
+            | RaceType::HalfElf
+            | RaceType::Tiefling
+            | RaceType::Gnome => 60,
+            RaceType::Human | RaceType::Halfling | RaceType::Dragonborn => 0,
+        }
+    }
+
     /// Apply racial ability score bonuses to base scores.
     pub fn apply_ability_bonuses(&self, scores: &mut AbilityScores) {
         match self {
@@ -1444,6 +2037,47 @@ impl RaceType {
         }
     }
 
+    pub fn size(&self) -> Size {
+        match self {
+            RaceType::Halfling | RaceType::Gnome => Size::Small,
+            _ => Size::Medium,
+        }
+    }
+
+    /// Languages every member of the race speaks.
+    pub fn languages(&self) -> Vec<Language> {
+        let racial = match self {
+            RaceType::Human => return vec![Language::Common],
+            RaceType::Elf | RaceType::HalfElf => Language::Elvish,
+            RaceType::Dwarf => Language::Dwarvish,
+            RaceType::Halfling => Language::Halfling,
+            RaceType::HalfOrc => Language::Orc,
+            RaceType::Tiefling => Language::Infernal,
+            RaceType::Gnome => Language::Gnomish,
+            RaceType::Dragonborn => Language::Draconic,
+        };
+        vec![Language::Common, racial]
+    }
+
+    /// Skills every member of the race is proficient in.
+    pub fn skill_proficiencies(&self) -> Vec<Skill> {
+        match self {
+            // Keen Senses
+            RaceType::Elf => vec![Skill::Perception],
+            // Menacing
+            RaceType::HalfOrc => vec![Skill::Intimidation],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Number of extra languages of the player's choice.
+    pub fn bonus_languages(&self) -> usize {
+        match self {
+            RaceType::Human | RaceType::HalfElf => 1,
+            _ => 0,
+        }
+    }
+
     pub fn all() -> &'static [RaceType] {
         &[
             RaceType::Human,
@@ -1544,6 +2178,37 @@ impl Background {
         }
     }
 
+    /// Gold pieces the background starts with.
+    pub fn starting_gold(&self) -> u32 {
+        match self {
+            Background::Noble => 25,
+            Background::Acolyte
+            | Background::Charlatan
+            | Background::Criminal
+            | Background::Entertainer
+            | Background::GuildArtisan => 15,
+            Background::Hermit => 5,
+            Background::FolkHero
+            | Background::Outlander
+            | Background::Sage
+            | Background::Sailor
+            | Background::Soldier
+            | Background::Urchin => 10,
+        }
+    }
+
+    /// Number of extra languages of the player's choice.
+    pub fn bonus_languages(&self) -> usize {
+        match self {
+            Background::Acolyte | Background::Sage => 2,
+            Background::GuildArtisan
+            | Background::Hermit
+            | Background::Noble
+            | Background::Outlander => 1,
+            _ => 0,
+        }
+    }
+
     pub fn all() -> &'static [Background] {
         &[
             Background::Acolyte,
@@ -1569,13 +2234,93 @@ impl fmt::Display for Background {
     }
 }
 
-// ============================================================================
-// Character
-// ============================================================================
+/// A language a creature can speak and understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Language {
+    Common,
+    Dwarvish,
+    Elvish,
+    Giant,
+    Gnomish,
+    Goblin,
+    Halfling,
+    Orc,
+    Abyssal,
+    Celestial,
+    Draconic,
+    #[serde(rename = "Deep Speech")]
+    DeepSpeech,
+    Infernal,
+    Primordial,
+    Sylvan,
+    Undercommon,
+}
 
-/// D&D race (legacy struct for compatibility).
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Race {
+impl Language {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Common => "Common",
+            Language::Dwarvish => "Dwarvish",
+            Language::Elvish => "Elvish",
+            Language::Giant => "Giant",
+            Language::Gnomish => "Gnomish",
+            Language::Goblin => "Goblin",
+            Language::Halfling => "Halfling",
+            Language::Orc => "Orc",
+            Language::Abyssal => "Abyssal",
+            Language::Celestial => "Celestial",
+            Language::Draconic => "Draconic",
+            Language::DeepSpeech => "Deep Speech",
+            Language::Infernal => "Infernal",
+            Language::Primordial => "Primordial",
+            Language::Sylvan => "Sylvan",
+            Language::Undercommon => "Undercommon",
+        }
+    }
+
+    /// Look up a language by name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Language> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|l| l.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn all() -> &'static [Language] {
+        &[
+            Language::Common,
+            Language::Dwarvish,
+            Language::Elvish,
+            Language::Giant,
+            Language::Gnomish,
+            Language::Goblin,
+            Language::Halfling,
+            Language::Orc,
+            Language::Abyssal,
+            Language::Celestial,
+            Language::Draconic,
+            Language::DeepSpeech,
+            Language::Infernal,
+            Language::Primordial,
+            Language::Sylvan,
+            Language::Undercommon,
+        ]
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// ============================================================================
+// Character
+// ============================================================================
+
+/// D&D race (legacy struct for compatibility).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Race {
     pub name: String,
     pub subrace: Option<String>,
     #[serde(default)]
@@ -1602,6 +2347,8 @@ pub struct Character {
     // Combat
     pub armor_class: ArmorClass,
     pub speed: Speed,
+    #[serde(default)]
+    pub senses: Senses,
     pub conditions: Vec<ActiveCondition>,
 
     // Class features
@@ -1615,7 +2362,14 @@ pub struct Character {
     // Skills & proficiencies
     pub skill_proficiencies: HashMap<Skill, ProficiencyLevel>,
     pub saving_throw_proficiencies: HashSet<Ability>,
-    pub languages: Vec<String>,
+    pub languages: HashSet<Language>,
+    /// Tools and vehicles the character is proficient with, e.g. "Thieves' Tools".
+    #[serde(default)]
+    pub tool_proficiencies: Vec<String>,
+    #[serde(default)]
+    pub weapon_proficiencies: HashSet<WeaponProficiency>,
+    #[serde(default)]
+    pub armor_proficiencies: HashSet<ArmorProficiency>,
 
     // Equipment
     pub inventory: Inventory,
@@ -1629,9 +2383,32 @@ pub struct Character {
 
     // Player backstory
     pub backstory: Option<String>,
+
+    /// Names of magic items the character is attuned to (at most three).
+    #[serde(default)]
+    pub attuned: Vec<String>,
+
+    /// Temporary ability score changes layered over `ability_scores`.
+    #[serde(default)]
+    pub ability_modifiers: Vec<AbilityMod>,
+
+    /// Inspiration awarded by the DM, not yet spent.
+    #[serde(default)]
+    pub has_inspiration: bool,
+
+    /// Inspiration has been spent and grants advantage on the next d20 roll.
+    #[serde(default)]
+    pub inspiration_pending: bool,
+
+    /// Ability Score Improvements (or feats) earned by leveling, not yet chosen.
+    #[serde(default)]
+    pub pending_asi: u8,
 }
 
 impl Character {
+    /// Maximum number of magic items a character can be attuned to at once.
+    pub const MAX_ATTUNED_ITEMS: usize = 3;
+
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             id: CharacterId::new(),
@@ -1645,6 +2422,7 @@ impl Character {
             death_saves: DeathSaves::default(),
             armor_class: ArmorClass::default(),
             speed: Speed::default(),
+            senses: Senses::default(),
             conditions: Vec::new(),
             classes: Vec::new(),
             features: Vec::new(),
@@ -1652,10 +2430,16 @@ impl Character {
             spellcasting: None,
             skill_proficiencies: HashMap::new(),
             saving_throw_proficiencies: HashSet::new(),
-            languages: vec!["Common".to_string()],
+            languages: HashSet::from([Language::Common]),
+            tool_proficiencies: Vec::new(),
+            weapon_proficiencies: HashSet::new(),
+            armor_proficiencies: HashSet::new(),
             inventory: Inventory {
                 items: Vec::new(),
-                gold: 15.0, // Starting gold
+                purse: Purse {
+                    gp: 15, // Starting gold
+                    ..Default::default()
+                },
             },
             equipment: Equipment::default(),
             race: Race {
@@ -1667,6 +2451,51 @@ impl Character {
             background: Background::Soldier,
             background_name: "Soldier".to_string(),
             backstory: None,
+            attuned: Vec::new(),
+            ability_modifiers: Vec::new(),
+            has_inspiration: false,
+            inspiration_pending: false,
+            pending_asi: 0,
+        }
+    }
+
+    /// Advance to `new_level`, applying every level gained along the way:
+    /// average hit points for the class hit die plus CON, one more hit die,
+    /// the class features for that level, and any Ability Score Improvement.
+    pub fn level_up(&mut self, new_level: u8) {
+        let Some(class) = self.classes.first().map(|c| c.class) else {
+            self.level = new_level;
+            return;
+        };
+        let die = class.hit_die();
+        while self.level < new_level.min(20) {
+            self.level += 1;
+            let con = self.ability_scores.modifier(Ability::Constitution) as i32;
+            let hp_gain = (die.sides() as i32 / 2 + 1 + con).max(1);
+            self.hit_points.maximum += hp_gain;
+            self.hit_points.current += hp_gain;
+            self.hit_dice.add(die, 1);
+            self.features.extend(class.features_at_level(self.level));
+            if class.grants_asi(self.level) {
+                self.pending_asi += 1;
+            }
+        }
+        self.classes[0].level = self.level;
+        // Ki, sorcery points, and Lay on Hands scale with class level
+        if matches!(
+            class,
+            CharacterClass::Monk | CharacterClass::Sorcerer | CharacterClass::Paladin
+        ) {
+            self.class_resources.initialize_for_class(class, self.level);
+        }
+        // Pact Magic slots grow in number and level, keeping those spent
+        if class == CharacterClass::Warlock {
+            if let Some(ref mut spellcasting) = self.spellcasting {
+                let used = spellcasting.pact_slots.map_or(0, |p| p.used);
+                let mut pact = PactMagic::for_level(self.level);
+                pact.used = used.min(pact.count);
+                spellcasting.pact_slots = Some(pact);
+            }
         }
     }
 
@@ -1682,12 +2511,32 @@ impl Character {
         }
     }
 
+    /// An ability score with temporary modifiers folded in (1 to 30).
+    pub fn effective_score(&self, ability: Ability) -> u8 {
+        let delta: i32 = self
+            .ability_modifiers
+            .iter()
+            .filter(|m| m.ability == ability)
+            .map(|m| m.delta as i32)
+            .sum();
+        (self.ability_scores.get(ability) as i32 + delta).clamp(1, 30) as u8
+    }
+
+    /// All ability scores with temporary modifiers folded in.
+    pub fn effective_scores(&self) -> AbilityScores {
+        let mut scores = self.ability_scores.clone();
+        for ability in Ability::all() {
+            scores.set(ability, self.effective_score(ability));
+        }
+        scores
+    }
+
     pub fn initiative_modifier(&self) -> i8 {
-        self.ability_scores.modifier(Ability::Dexterity)
+        self.effective_scores().modifier(Ability::Dexterity)
     }
 
     pub fn skill_modifier(&self, skill: Skill) -> i8 {
-        let ability_mod = self.ability_scores.modifier(skill.ability());
+        let ability_mod = self.effective_scores().modifier(skill.ability());
         let proficiency = self
             .skill_proficiencies
             .get(&skill)
@@ -1697,7 +2546,7 @@ impl Character {
     }
 
     pub fn saving_throw_modifier(&self, ability: Ability) -> i8 {
-        let ability_mod = self.ability_scores.modifier(ability);
+        let ability_mod = self.effective_scores().modifier(ability);
         if self.saving_throw_proficiencies.contains(&ability) {
             ability_mod + self.proficiency_bonus()
         } else {
@@ -1705,12 +2554,111 @@ impl Character {
         }
     }
 
+    /// How many spells a prepared caster (cleric, druid or wizard) can have
+    /// prepared: spellcasting modifier + class level, minimum 1.
+    ///
+    /// `None` for characters who know their spells instead of preparing them.
+    pub fn prepared_spell_limit(&self) -> Option<usize> {
+        let spellcasting = self.spellcasting.as_ref()?;
+        let class_level = self
+            .classes
+            .iter()
+            .find(|c| {
+                matches!(
+                    c.class,
+                    CharacterClass::Cleric | CharacterClass::Druid | CharacterClass::Wizard
+                )
+            })?
+            .level;
+        let modifier = self.effective_scores().modifier(spellcasting.ability) as i32;
+        Some((modifier + class_level as i32).max(1) as usize)
+    }
+
+    /// Whether the character carries a spellcasting focus. A cleric's holy
+    /// symbol on their shield, or a focus in the hand already holding
+    /// something, leaves a hand free for somatic components.
+    pub fn has_spellcasting_focus(&self) -> bool {
+        self.inventory.items.iter().any(|item| {
+            SPELLCASTING_FOCI
+                .iter()
+                .any(|focus| item.name.eq_ignore_ascii_case(focus))
+        })
+    }
+
+    /// Whether this character can cast `spell_name` as a ritual.
+    ///
+    /// Wizards ritual-cast from their spellbook without preparing the spell,
+    /// clerics and druids need it prepared, and bards need to know it.
+    pub fn can_ritual_cast(&self, spell_name: &str) -> bool {
+        let Some(spellcasting) = self.spellcasting.as_ref() else {
+            return false;
+        };
+        let has = |list: &[String]| list.iter().any(|s| s.eq_ignore_ascii_case(spell_name));
+        self.classes.iter().any(|c| match c.class {
+            CharacterClass::Wizard | CharacterClass::Bard => has(&spellcasting.spells_known),
+            CharacterClass::Cleric | CharacterClass::Druid => has(&spellcasting.spells_prepared),
+            _ => false,
+        })
+    }
+
+    /// Lowest natural d20 roll that scores a critical hit for this character.
+    ///
+    /// Champion fighters crit on 19-20 from level 3 (Improved Critical) and on
+    /// 18-20 from level 15 (Superior Critical). Features with those names widen
+    /// the range for any character.
+    pub fn crit_range(&self) -> u8 {
+        let champion_level = self
+            .classes
+            .iter()
+            .filter(|c| {
+                c.class == CharacterClass::Fighter
+                    && c.subclass
+                        .as_deref()
+                        .is_some_and(|s| s.eq_ignore_ascii_case("champion"))
+            })
+            .map(|c| c.level)
+            .max()
+            .unwrap_or(0);
+        let has_feature = |name: &str| {
+            self.features
+                .iter()
+                .any(|f| f.name.eq_ignore_ascii_case(name))
+        };
+
+        if champion_level >= 15 || has_feature("Superior Critical") {
+            18
+        } else if champion_level >= 3 || has_feature("Improved Critical") {
+            19
+        } else {
+            20
+        }
+    }
+
+    /// Attacks this character makes with one Attack action.
+    ///
+    /// Extra Attack allows two; fighters make three from level 11 and four
+    /// from level 20.
+    pub fn attacks_per_action(&self) -> u8 {
+        let fighter_level = self
+            .classes
+            .iter()
+            .find(|c| c.class == CharacterClass::Fighter)
+            .map(|c| c.level)
+            .unwrap_or(0);
+        match fighter_level {
+            20.. => 4,
+            11.. => 3,
+            _ if self.features.iter().any(|f| f.name == "Extra Attack") => 2,
+            _ => 1,
+        }
+    }
+
     /// Calculate current AC from equipped armor and shield.
     ///
     /// If equipment is set, AC is calculated from equipped armor.
     /// Otherwise, falls back to the armor_class field for backwards compatibility.
     pub fn current_ac(&self) -> u8 {
-        let dex_mod = self.ability_scores.modifier(Ability::Dexterity);
+        let dex_mod = self.effective_scores().modifier(Ability::Dexterity);
 
         // Calculate base AC from equipped armor or unarmored
         let base_ac = if let Some(ref armor) = self.equipment.armor {
@@ -1729,19 +2677,118 @@ impl Character {
             // No equipment set - use legacy armor_class field
             return self
                 .armor_class
-                .calculate(self.ability_scores.modifier(Ability::Dexterity));
+                .calculate(self.effective_scores().modifier(Ability::Dexterity));
         };
 
         // Add shield bonus if equipped
         let shield_bonus: i8 = if self.equipment.shield.is_some() { 2 } else { 0 };
 
-        (base_ac + shield_bonus).max(1) as u8
+        // Enchanted armor only helps once any required attunement is in place
+        let magic_bonus = match self.equipment.armor {
+            Some(ref armor) if self.can_use_magic(&armor.base) => armor.magic_bonus,
+            _ => 0,
+        };
+
+        (base_ac + shield_bonus + magic_bonus).max(1) as u8
+    }
+
+    /// Grant the weapon and armor proficiencies of `class`.
+    pub fn grant_class_proficiencies(&mut self, class: CharacterClass) {
+        let data = class.data();
+        self.weapon_proficiencies.extend(data.weapon_proficiencies);
+        self.armor_proficiencies.extend(data.armor_proficiencies);
+    }
+
+    /// Whether the character adds their proficiency bonus to attacks with `weapon`.
+    pub fn is_proficient_with(&self, weapon: &WeaponItem) -> bool {
+        let light = weapon.properties.contains(&WeaponProperty::Light);
+        self.weapon_proficiencies
+            .iter()
+            .any(|proficiency| match proficiency {
+                WeaponProficiency::Simple => weapon.category == WeaponCategory::Simple,
+                WeaponProficiency::Martial => true,
+                WeaponProficiency::MartialLight => light,
+                WeaponProficiency::MartialFinesseOrLight => light || weapon.is_finesse(),
+            })
+    }
+
+    /// Whether the character wears armor or a shield they aren't trained with.
+    pub fn wearing_untrained_armor(&self) -> bool {
+        let armor = self
+            .equipment
+            .armor
+            .as_ref()
+            .is_some_and(|a| !self.armor_proficiencies.contains(&a.armor_type.into()));
+        let shield = self.equipment.shield.is_some()
+            && !self
+                .armor_proficiencies
+                .contains(&ArmorProficiency::Shields);
+        armor || shield
+    }
+
+    /// Whether the character has taken a feat, e.g. "Resilient".
+    pub fn has_feat(&self, feat_name: &str) -> bool {
+        self.features.iter().any(|feature| {
+            // Feats with a choice are recorded as e.g. "Resilient (CON)"
+            feature.source == FEAT_SOURCE
+                && feature
+                    .name
+                    .split(" (")
+                    .next()
+                    .is_some_and(|base| base.eq_ignore_ascii_case(feat_name))
+        })
+    }
+
+    pub fn is_attuned_to(&self, item_name: &str) -> bool {
+        self.attuned
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(item_name))
+    }
+
+    /// Whether an item's magical properties work for this character: either it
+    /// doesn't need attunement or the character is attuned to it.
+    pub fn can_use_magic(&self, item: &Item) -> bool {
+        !item.requires_attunement || self.is_attuned_to(&item.name)
+    }
+
+    /// Find an item the character owns, whether carried or equipped.
+    pub fn find_owned_item(&self, name: &str) -> Option<&Item> {
+        let equipped = [
+            self.equipment.armor.as_ref().map(|a| &a.base),
+            self.equipment.shield.as_ref(),
+            self.equipment.main_hand.as_ref().map(|w| &w.base),
+            self.equipment.off_hand.as_ref(),
+        ];
+        equipped
+            .into_iter()
+            .flatten()
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+            .or_else(|| self.inventory.find_item(name))
     }
 
     pub fn is_conscious(&self) -> bool {
         self.hit_points.current > 0
     }
 
+    /// Rounds the character can hold their breath: 1 + CON modifier minutes,
+    /// never less than 30 seconds.
+    pub fn breath_rounds(&self) -> u32 {
+        let minutes = 1 + self.effective_scores().modifier(Ability::Constitution);
+        (minutes.max(0) as u32 * 10).max(5)
+    }
+
+    /// Rounds the character survives once out of breath: CON modifier, at least 1.
+    pub fn suffocation_rounds(&self) -> u32 {
+        self.effective_scores()
+            .modifier(Ability::Constitution)
+            .max(1) as u32
+    }
+
+    /// Whether the character can follow speech in the given language.
+    pub fn understands(&self, language: Language) -> bool {
+        self.languages.contains(&language)
+    }
+
     /// Check if the character has a specific condition.
     pub fn has_condition(&self, condition: Condition) -> bool {
         self.conditions
@@ -1749,6 +2796,187 @@ impl Character {
             .any(|c| std::mem::discriminant(&c.condition) == std::mem::discriminant(&condition))
     }
 
+    /// The first condition that stops the character from taking actions, if any.
+    pub fn incapacitating_condition(&self) -> Option<Condition> {
+        self.conditions
+            .iter()
+            .map(|c| c.condition)
+            .find(Condition::is_incapacitating)
+    }
+
+    /// The condition that makes the character automatically fail Strength and
+    /// Dexterity rolls: paralyzed, petrified, stunned or unconscious, but not
+    /// merely incapacitated.
+    pub fn helpless_condition(&self) -> Option<Condition> {
+        self.conditions
+            .iter()
+            .map(|c| c.condition)
+            .filter(|c| *c != Condition::Incapacitated)
+            .find(Condition::is_incapacitating)
+    }
+
+    /// Whether the character has `condition` caused by the named creature,
+    /// e.g. Frightened by "The Dragon's roar" for "Dragon".
+    ///
+    /// The creature's whole name must appear in the source as words, so
+    /// "Gob" doesn't match a condition from "Goblin".
+    pub fn has_condition_from(&self, condition: Condition, creature: &str) -> bool {
+        let name = words(creature);
+        !name.is_empty()
+            && self.conditions.iter().any(|c| {
+                c.condition == condition
+                    && words(&c.source)
+                        .windows(name.len())
+                        .any(|window| window == name.as_slice())
+            })
+    }
+
+    /// Current exhaustion level (0 if not exhausted).
+    pub fn exhaustion_level(&self) -> u8 {
+        self.conditions
+            .iter()
+            .find_map(|c| match c.condition {
+                Condition::Exhaustion(level) => Some(level),
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Set the exhaustion level, removing the condition entirely at 0.
+    pub fn set_exhaustion(&mut self, level: u8, source: impl Into<String>) {
+        if let Some(active) = self
+            .conditions
+            .iter_mut()
+            .find(|c| matches!(c.condition, Condition::Exhaustion(_)))
+        {
+            active.condition = Condition::Exhaustion(level);
+        } else if level > 0 {
+            self.conditions
+                .push(ActiveCondition::new(Condition::Exhaustion(level), source));
+        }
+        self.conditions
+            .retain(|c| !matches!(c.condition, Condition::Exhaustion(0)));
+    }
+
+    /// Total weight of inventory and equipped items, in pounds.
+    pub fn carried_weight(&self) -> f32 {
+        self.inventory.total_weight() + self.equipment.total_weight()
+    }
+
+    /// Maximum weight the character can carry (STR x 15).
+    pub fn carrying_capacity(&self) -> f32 {
+        self.effective_score(Ability::Strength) as f32 * 15.0
+    }
+
+    pub fn encumbrance_level(&self) -> Encumbrance {
+        Encumbrance::for_load(
+            self.carried_weight(),
+            self.effective_score(Ability::Strength),
+        )
+    }
+
+    /// Recover what a short rest restores: Pact Magic slots, short-rest
+    /// features and class resources.
+    pub fn recover_from_short_rest(&mut self) {
+        // Warlocks recover their Pact Magic slots on a short rest
+        if let Some(pact) = self
+            .spellcasting
+            .as_mut()
+            .and_then(|sc| sc.pact_slots.as_mut())
+        {
+            pact.recover_all();
+        }
+
+        // Reset feature uses that recharge on short rest
+        for feature in &mut self.features {
+            if let Some(ref mut uses) = feature.uses {
+                if matches!(uses.recharge, RechargeType::ShortRest) {
+                    uses.current = uses.maximum;
+                }
+            }
+        }
+
+        // Reset class-specific resources
+        self.class_resources
+            .restore(crate::rules::RestType::Short, &self.classes);
+    }
+
+    /// Recover what a long rest restores: hit points, half the hit dice, a
+    /// level of exhaustion, spell slots, features and class resources.
+    pub fn recover_from_long_rest(&mut self) {
+        // Full HP recovery
+        self.hit_points.current = self.hit_points.maximum;
+
+        // Remove Unconscious condition if present (they're now healed)
+        self.conditions
+            .retain(|c| c.condition != Condition::Unconscious);
+
+        // Reduce exhaustion by 1 level (if any)
+        let exhaustion = self.exhaustion_level();
+        if exhaustion > 0 {
+            self.set_exhaustion(exhaustion - 1, "Long rest");
+        }
+        // Remaining exhaustion may still cap the hit point maximum
+        let effective_max = self.effective_max_hp();
+        self.hit_points.current = self.hit_points.current.min(effective_max);
+
+        // Recover half hit dice
+        self.hit_dice.recover_half();
+
+        // Recover spell slots; prepared casters may choose new spells
+        if let Some(ref mut spellcasting) = self.spellcasting {
+            spellcasting.spell_slots.recover_all();
+            if let Some(ref mut pact) = spellcasting.pact_slots {
+                pact.recover_all();
+            }
+            spellcasting.prepared_since_rest = false;
+        }
+
+        // Reset feature uses (both short rest and long rest features)
+        for feature in &mut self.features {
+            if let Some(ref mut uses) = feature.uses {
+                if matches!(
+                    uses.recharge,
+                    RechargeType::LongRest | RechargeType::ShortRest
+                ) {
+                    uses.current = uses.maximum;
+                }
+            }
+        }
+
+        // Reset class-specific resources
+        self.class_resources
+            .restore(crate::rules::RestType::Long, &self.classes);
+    }
+
+    /// Walking speed after encumbrance, with a 5 foot floor once over capacity.
+    pub fn encumbered_speed(&self) -> u32 {
+        match self.encumbrance_level() {
+            Encumbrance::OverCapacity => self.speed.walk.min(5),
+            level => self.speed.walk.saturating_sub(level.speed_penalty()),
+        }
+    }
+
+    /// Walking speed after exhaustion penalties.
+    ///
+    /// Speed is halved at exhaustion level 2 and reduced to 0 at level 5.
+    pub fn effective_speed(&self) -> u32 {
+        match self.exhaustion_level() {
+            0..=1 => self.speed.walk,
+            2..=4 => self.speed.walk / 2,
+            _ => 0,
+        }
+    }
+
+    /// Hit point maximum after exhaustion penalties (halved at level 4).
+    pub fn effective_max_hp(&self) -> i32 {
+        if self.exhaustion_level() >= 4 {
+            self.hit_points.maximum / 2
+        } else {
+            self.hit_points.maximum
+        }
+    }
+
     /// Add a condition if not already present. Returns true if the condition was added.
     pub fn add_condition(&mut self, condition: Condition, source: impl Into<String>) -> bool {
         self.add_condition_with_duration(condition, source, None)
@@ -1761,13 +2989,18 @@ impl Character {
         source: impl Into<String>,
         duration_rounds: Option<u32>,
     ) -> bool {
-        if self.has_condition(condition) {
+        let mut active = ActiveCondition::new(condition, source);
+        if let Some(duration) = duration_rounds {
+            active = active.with_duration(duration);
+        }
+        self.add_active_condition(active)
+    }
+
+    /// Add a fully described condition if not already present. Returns true if it was added.
+    pub fn add_active_condition(&mut self, active: ActiveCondition) -> bool {
+        if self.has_condition(active.condition) {
             false
         } else {
-            let mut active = ActiveCondition::new(condition, source);
-            if let Some(duration) = duration_rounds {
-                active = active.with_duration(duration);
-            }
             self.conditions.push(active);
             true
         }
@@ -1811,6 +3044,11 @@ impl Location {
         self.description = description.into();
         self
     }
+
+    /// The known way from here to another location, if there is one.
+    pub fn connection_to(&self, id: LocationId) -> Option<&LocationConnection> {
+        self.connections.iter().find(|c| c.destination_id == id)
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -1889,6 +3127,9 @@ pub struct Quest {
     pub objectives: Vec<QuestObjective>,
     pub rewards: Vec<String>,
     pub giver: Option<String>,
+    /// Experience awarded when the quest is completed.
+    #[serde(default)]
+    pub xp_reward: u32,
 }
 
 impl Quest {
@@ -1901,9 +3142,36 @@ impl Quest {
             objectives: Vec::new(),
             rewards: Vec::new(),
             giver: None,
+            xp_reward: 0,
         }
     }
 
+    /// Add a required objective.
+    pub fn with_objective(mut self, description: impl Into<String>) -> Self {
+        self.objectives.push(QuestObjective {
+            description: description.into(),
+            completed: false,
+            optional: false,
+        });
+        self
+    }
+
+    /// Find an objective by its description, or failing that by part of it.
+    pub fn objective_index(&self, description: &str) -> Option<usize> {
+        let wanted = description.trim().to_lowercase();
+        if wanted.is_empty() {
+            return None;
+        }
+        self.objectives
+            .iter()
+            .position(|o| o.description.to_lowercase() == wanted)
+            .or_else(|| {
+                self.objectives
+                    .iter()
+                    .position(|o| o.description.to_lowercase().contains(&wanted))
+            })
+    }
+
     pub fn is_complete(&self) -> bool {
         !self.objectives.is_empty() && self.objectives.iter().all(|o| o.completed)
     }
@@ -1917,6 +3185,17 @@ pub enum QuestStatus {
     Abandoned,
 }
 
+impl QuestStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            QuestStatus::Active => "active",
+            QuestStatus::Completed => "completed",
+            QuestStatus::Failed => "failed",
+            QuestStatus::Abandoned => "abandoned",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuestObjective {
     pub description: String,
@@ -1928,6 +3207,122 @@ pub struct QuestObjective {
 // Combat
 // ============================================================================
 
+/// A combatant's basic attack, used to run its turns automatically.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CombatantAttack {
+    /// Attack name, e.g. "Scimitar".
+    pub name: String,
+    /// Total bonus to the attack roll.
+    pub attack_bonus: i8,
+    /// Damage expression including modifiers, e.g. "1d6+2".
+    pub damage_dice: String,
+    pub damage_type: crate::rules::DamageType,
+    /// How far the attack reaches, for opportunity attacks.
+    #[serde(default = "default_reach_feet")]
+    pub reach_feet: u32,
+}
+
+fn default_reach_feet() -> u32 {
+    5
+}
+
+pub(crate) fn default_speed_feet() -> u32 {
+    30
+}
+
+/// Let game time pass for timed conditions, dropping the ones that run out.
+/// Round-based durations count down too, at ten rounds to the minute.
+fn expire_conditions(conditions: &mut Vec<ActiveCondition>, minutes: u32) {
+    conditions.retain_mut(|c| {
+        if let Some(ref mut left) = c.duration_minutes {
+            *left = left.saturating_sub(minutes);
+            if *left == 0 {
+                return false;
+            }
+        }
+        if let Some(ref mut rounds) = c.duration_rounds {
+            *rounds = rounds.saturating_sub(minutes.saturating_mul(10));
+            if *rounds == 0 {
+                return false;
+            }
+        }
+        true
+    });
+}
+
+/// Items a caster can use as a spellcasting focus.
+const SPELLCASTING_FOCI: &[&str] = &["Holy Symbol", "Arcane Focus", "Druidic Focus"];
+
+/// Lowercase words of a name or description, ignoring punctuation.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Creature size category.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Size {
+    Tiny,
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Huge,
+    Gargantuan,
+}
+
+impl Size {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Size::Tiny => "Tiny",
+            Size::Small => "Small",
+            Size::Medium => "Medium",
+            Size::Large => "Large",
+            Size::Huge => "Huge",
+            Size::Gargantuan => "Gargantuan",
+        }
+    }
+
+    /// Look up a size by name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Size> {
+        [
+            Size::Tiny,
+            Size::Small,
+            Size::Medium,
+            Size::Large,
+            Size::Huge,
+            Size::Gargantuan,
+        ]
+        .into_iter()
+        .find(|s| s.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// A square on the combat grid; each square is 5 feet across.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridPosition {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl GridPosition {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// Distance in feet, counting diagonal steps as 5 feet.
+    pub fn distance_feet(&self, other: GridPosition) -> u32 {
+        let dx = self.x.abs_diff(other.x);
+        let dy = self.y.abs_diff(other.y);
+        dx.max(dy) * 5
+    }
+}
+
 /// Combat participant.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Combatant {
@@ -1939,6 +3334,62 @@ pub struct Combatant {
     pub current_hp: i32,
     pub max_hp: i32,
     pub armor_class: u8,
+    /// Attack used on this combatant's turns; without one the DM narrates them.
+    #[serde(default)]
+    pub attack: Option<CombatantAttack>,
+    /// Where the combatant stands, if the fight is tracked on a grid.
+    #[serde(default)]
+    pub position: Option<GridPosition>,
+    /// Took the Disengage action this turn, so movement provokes nothing.
+    #[serde(default)]
+    pub disengaged: bool,
+    /// Reaction waiting for its trigger, such as a spell to counter.
+    #[serde(default)]
+    pub readied_reaction: Option<crate::rules::Reaction>,
+    /// Already reacted this round.
+    #[serde(default)]
+    pub reaction_used: bool,
+    /// Already took an action this turn.
+    #[serde(default)]
+    pub action_used: bool,
+    /// Attacks made with this turn's Attack action, for Extra Attack.
+    #[serde(default)]
+    pub attacks_made: u8,
+    /// Already took a bonus action this turn.
+    #[serde(default)]
+    pub bonus_action_used: bool,
+    /// Feet of movement left this turn.
+    #[serde(default = "default_speed_feet")]
+    pub movement_remaining: u32,
+    #[serde(default)]
+    pub size: Size,
+    /// Walking speed in feet per turn.
+    #[serde(default = "default_speed_feet")]
+    pub speed: u32,
+    /// The combatant this one is riding, if any.
+    #[serde(default)]
+    pub mounted_on: Option<CharacterId>,
+    #[serde(default)]
+    pub damage_traits: crate::rules::DamageTraits,
+    /// Blindsight and the like on creatures without a character sheet.
+    #[serde(default)]
+    pub senses: Senses,
+    /// Conditions on creatures without a character sheet.
+    #[serde(default)]
+    pub conditions: Vec<ActiveCondition>,
+}
+
+impl Combatant {
+    /// Melee reach in feet: the stored attack's, or the usual 5 feet.
+    pub fn reach_feet(&self) -> u32 {
+        self.attack.as_ref().map_or(5, |a| a.reach_feet)
+    }
+
+    pub fn has_condition(&self, condition: Condition) -> bool {
+        self.conditions
+            .iter()
+            .any(|c| std::mem::discriminant(&c.condition) == std::mem::discriminant(&condition))
+    }
 }
 
 /// Combat state tracking.
@@ -1948,6 +3399,28 @@ pub struct CombatState {
     pub round: u32,
     pub turn_index: usize,
     pub combatants: Vec<Combatant>,
+    /// The most recent attack that hit this turn, for riders like Divine Smite.
+    #[serde(default)]
+    pub last_hit: Option<LandedHit>,
+}
+
+/// An attack that connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LandedHit {
+    pub attacker_id: CharacterId,
+    pub target_id: CharacterId,
+    pub is_critical: bool,
+}
+
+/// `name`, or the first of "name 2", "name 3", ... that isn't taken.
+pub(crate) fn numbered_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{name} {n}"))
+        .find(|numbered| !taken(numbered))
+        .unwrap()
 }
 
 impl CombatState {
@@ -1957,13 +3430,21 @@ impl CombatState {
             round: 1,
             turn_index: 0,
             combatants: Vec::new(),
+            last_hit: None,
         }
     }
 
-    pub fn add_combatant(&mut self, combatant: Combatant) {
+    /// Add a combatant, numbering it when its name is taken ("Goblin 2") so
+    /// every combatant can be picked out by name.
+    pub fn add_combatant(&mut self, mut combatant: Combatant) {
+        combatant.name = numbered_name(&combatant.name, |name| {
+            self.combatants
+                .iter()
+                .any(|c| c.name.eq_ignore_ascii_case(name))
+        });
         self.combatants.push(combatant);
         self.combatants
-            .sort_by(|a, b| b.initiative.cmp(&a.initiative));
+            .sort_by_key(|c| std::cmp::Reverse(c.initiative));
     }
 
     pub fn current_combatant(&self) -> Option<&Combatant> {
@@ -1976,6 +3457,32 @@ impl CombatState {
             self.turn_index = 0;
             self.round += 1;
         }
+        self.last_hit = None;
+        // Disengage lasts until the start of the combatant's next turn, and
+        // reactions, actions, and movement come back then too
+        if let Some(current) = self.combatants.get_mut(self.turn_index) {
+            current.disengaged = false;
+            current.reaction_used = false;
+            current.action_used = false;
+            current.attacks_made = 0;
+            current.bonus_action_used = false;
+            current.movement_remaining = current.speed;
+        }
+    }
+
+    pub fn combatant(&self, id: CharacterId) -> Option<&Combatant> {
+        self.combatants.iter().find(|c| c.id == id)
+    }
+
+    pub fn combatant_mut(&mut self, id: CharacterId) -> Option<&mut Combatant> {
+        self.combatants.iter_mut().find(|c| c.id == id)
+    }
+
+    /// Combatants currently riding the given mount.
+    pub fn riders_of(&self, mount_id: CharacterId) -> impl Iterator<Item = &Combatant> {
+        self.combatants
+            .iter()
+            .filter(move |c| c.mounted_on == Some(mount_id))
     }
 
     pub fn end_combat(&mut self) {
@@ -2090,12 +3597,27 @@ pub enum GameMode {
     Rest,
 }
 
+/// A dice roll kept in the world's roll log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggedRoll {
+    pub roll: RollResult,
+    pub purpose: String,
+    /// How many player actions had been taken when the roll was made.
+    pub turn: usize,
+}
+
 /// Entry in the narrative history.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NarrativeEntry {
     pub content: String,
     pub entry_type: NarrativeType,
     pub game_time: GameTime,
+    /// Name of the location where the entry happened.
+    #[serde(default)]
+    pub location: String,
+    /// The roll behind a `DiceRoll` entry, so UIs can show the actual dice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roll: Option<RollResult>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -2105,6 +3627,17 @@ pub enum NarrativeType {
     NpcDialogue,
     Combat,
     System,
+    /// A dice roll; `content` is a plain-text summary of it.
+    DiceRoll,
+}
+
+/// Optional combat rules a table can switch on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CombatRules {
+    /// Melee attackers on opposite sides of a target have advantage (DMG
+    /// optional rule). Off by default, as in the core rules.
+    #[serde(default)]
+    pub flanking: bool,
 }
 
 /// The complete game world state.
@@ -2116,6 +3649,11 @@ pub struct GameWorld {
     // Player character
     pub player_character: Character,
 
+    // Companions whose HP and conditions the rules engine tracks.
+    // Saves from before parties existed load with an empty party.
+    #[serde(default)]
+    pub party: Vec<Character>,
+
     // NPCs
     pub npcs: HashMap<CharacterId, NPC>,
 
@@ -2131,9 +3669,24 @@ pub struct GameWorld {
     // Campaign progress
     pub quests: Vec<Quest>,
     pub narrative_history: Vec<NarrativeEntry>,
+
+    // Table rules
+    #[serde(default)]
+    pub combat_rules: CombatRules,
+
+    // Every dice roll, oldest first, capped at ROLL_LOG_LIMIT
+    #[serde(default)]
+    pub roll_log: Vec<LoggedRoll>,
+
+    // Homebrew items this campaign registered
+    #[serde(default)]
+    pub custom_items: crate::items::ItemRegistry,
 }
 
 impl GameWorld {
+    /// How many rolls the roll log keeps before dropping the oldest.
+    pub const ROLL_LOG_LIMIT: usize = 500;
+
     pub fn new(campaign_name: impl Into<String>, player_character: Character) -> Self {
         let starting_location = Location::new("Starting Location", LocationType::Town)
             .with_description("A quiet place where your adventure begins.");
@@ -2145,6 +3698,7 @@ impl GameWorld {
             session_id: Uuid::new_v4(),
             campaign_name: campaign_name.into(),
             player_character,
+            party: Vec::new(),
             npcs: HashMap::new(),
             mode: GameMode::Exploration,
             combat: None,
@@ -2153,108 +3707,137 @@ impl GameWorld {
             known_locations,
             quests: Vec::new(),
             narrative_history: Vec::new(),
+            combat_rules: CombatRules::default(),
+            roll_log: Vec::new(),
+            custom_items: crate::items::ItemRegistry::default(),
         }
     }
 
-    pub fn start_combat(&mut self) -> &mut CombatState {
-        self.mode = GameMode::Combat;
-        self.combat = Some(CombatState::new());
-        self.combat.as_mut().unwrap()
+    /// Look up a known location by id, the current one included.
+    pub fn location(&self, id: LocationId) -> Option<&Location> {
+        if self.current_location.id == id {
+            Some(&self.current_location)
+        } else {
+            self.known_locations.get(&id)
+        }
     }
 
-    pub fn end_combat(&mut self) {
-        self.combat = None;
-        self.mode = GameMode::Exploration;
+    /// Look up a known location by name, ignoring case.
+    pub fn location_named(&self, name: &str) -> Option<&Location> {
+        if self.current_location.name.eq_ignore_ascii_case(name) {
+            return Some(&self.current_location);
+        }
+        // The map's copy of the current location may be stale, so skip it
+        self.known_locations
+            .values()
+            .find(|l| l.id != self.current_location.id && l.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Link two known locations in both directions, unless they already are.
+    pub fn connect_locations(&mut self, a: LocationId, b: LocationId, travel_time_minutes: u32) {
+        let (Some(a_name), Some(b_name)) = (
+            self.location(a).map(|l| l.name.clone()),
+            self.location(b).map(|l| l.name.clone()),
+        ) else {
+            return;
+        };
+        for (from, to, to_name) in [(a, b, b_name), (b, a, a_name)] {
+            let copies = std::iter::once(&mut self.current_location)
+                .chain(self.known_locations.values_mut())
+                .filter(|l| l.id == from);
+            for location in copies {
+                if location.connection_to(to).is_none() {
+                    location.connections.push(LocationConnection {
+                        destination_id: to,
+                        destination_name: to_name.clone(),
+                        direction: None,
+                        travel_time_minutes,
+                    });
+                }
+            }
+        }
     }
 
-    pub fn short_rest(&mut self) {
-        self.game_time.advance_hours(1);
-
-        // Warlocks recover all spell slots on short rest (Pact Magic)
-        let is_warlock = self
-            .player_character
-            .classes
-            .iter()
-            .any(|c| c.class == CharacterClass::Warlock);
-        if is_warlock {
-            if let Some(ref mut spellcasting) = self.player_character.spellcasting {
-                spellcasting.spell_slots.recover_all();
-            }
+    /// Make a known location the current one, filing the one left behind
+    /// back into the map. Returns false if the location isn't known.
+    pub fn enter_location(&mut self, id: LocationId) -> bool {
+        if self.current_location.id == id {
+            return true;
         }
+        let Some(next) = self.known_locations.get(&id).cloned() else {
+            return false;
+        };
+        let previous = std::mem::replace(&mut self.current_location, next);
+        self.known_locations.insert(previous.id, previous);
+        true
+    }
 
-        // Reset feature uses that recharge on short rest
-        for feature in &mut self.player_character.features {
-            if let Some(ref mut uses) = feature.uses {
-                if matches!(uses.recharge, RechargeType::ShortRest) {
-                    uses.current = uses.maximum;
-                }
-            }
+    /// Look up the player or a party member by id.
+    pub fn character(&self, id: CharacterId) -> Option<&Character> {
+        if self.player_character.id == id {
+            Some(&self.player_character)
+        } else {
+            self.party.iter().find(|c| c.id == id)
         }
+    }
 
-        // Reset class-specific resources
-        for class_level in &self.player_character.classes {
-            self.player_character
-                .class_resources
-                .short_rest_recovery(class_level.class, class_level.level);
+    /// Look up the player or a party member by id, mutably.
+    pub fn character_mut(&mut self, id: CharacterId) -> Option<&mut Character> {
+        if self.player_character.id == id {
+            Some(&mut self.player_character)
+        } else {
+            self.party.iter_mut().find(|c| c.id == id)
         }
     }
 
-    pub fn long_rest(&mut self) {
-        self.game_time.advance_hours(8);
+    /// Look up the player or a party member by name (case-insensitive).
+    pub fn character_by_name(&self, name: &str) -> Option<&Character> {
+        self.characters()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
 
-        // Full HP recovery
-        let max_hp = self.player_character.hit_points.maximum;
-        self.player_character.hit_points.current = max_hp;
+    /// The player followed by every party member.
+    pub fn characters(&self) -> impl Iterator<Item = &Character> {
+        std::iter::once(&self.player_character).chain(self.party.iter())
+    }
 
-        // Remove Unconscious condition if present (they're now healed)
-        self.player_character
-            .conditions
-            .retain(|c| c.condition != Condition::Unconscious);
+    /// Add a companion to the party, returning their id.
+    pub fn add_party_member(&mut self, character: Character) -> CharacterId {
+        let id = character.id;
+        self.party.push(character);
+        id
+    }
 
-        // Reduce exhaustion by 1 level (if any)
-        for condition in &mut self.player_character.conditions {
-            if let Condition::Exhaustion(level) = &mut condition.condition {
-                if *level > 0 {
-                    *level -= 1;
-                }
-            }
-        }
-        // Remove exhaustion if reduced to 0
-        self.player_character
-            .conditions
-            .retain(|c| !matches!(c.condition, Condition::Exhaustion(0)));
+    pub fn start_combat(&mut self) -> &mut CombatState {
+        self.mode = GameMode::Combat;
+        self.combat = Some(CombatState::new());
+        self.combat.as_mut().unwrap()
+    }
 
-        // Recover half hit dice
-        self.player_character.hit_dice.recover_half();
+    pub fn end_combat(&mut self) {
+        self.combat = None;
+        self.mode = GameMode::Exploration;
+    }
 
-        // Recover spell slots
-        if let Some(ref mut spellcasting) = self.player_character.spellcasting {
-            spellcasting.spell_slots.recover_all();
+    /// Let game time pass, expiring timed conditions on the party.
+    pub fn advance_time(&mut self, minutes: u32) {
+        self.game_time.advance_minutes(minutes);
+        for character in std::iter::once(&mut self.player_character).chain(&mut self.party) {
+            expire_conditions(&mut character.conditions, minutes);
         }
+    }
 
-        // Reset feature uses (both short rest and long rest features)
-        for feature in &mut self.player_character.features {
-            if let Some(ref mut uses) = feature.uses {
-                if matches!(
-                    uses.recharge,
-                    RechargeType::LongRest | RechargeType::ShortRest
-                ) {
-                    uses.current = uses.maximum;
-                }
-            }
+    pub fn short_rest(&mut self) {
+        self.advance_time(60);
+        for character in std::iter::once(&mut self.player_character).chain(&mut self.party) {
+            character.recover_from_short_rest();
         }
+    }
 
-        // Reset class-specific resources
-        let classes: Vec<_> = self
-            .player_character
-            .classes
-            .iter()
-            .map(|c| (c.class, c.level))
-            .collect();
-        for (class, level) in classes {
-            self.player_character
-                .class_resources
-                .long_rest_recovery(class, level);
+    pub fn long_rest(&mut self) {
+        self.advance_time(480);
+        for character in std::iter::once(&mut self.player_character).chain(&mut self.party) {
+            character.recover_from_long_rest();
         }
     }
 
@@ -2263,11 +3846,68 @@ impl GameWorld {
             content,
             entry_type,
             game_time: self.game_time.clone(),
+            location: self.current_location.name.clone(),
+            roll: None,
         });
     }
 
+    /// Add a dice roll to the narrative, keeping the roll for display.
+    ///
+    /// Like the roll log, only the last [`Self::ROLL_LOG_LIMIT`] rolls are
+    /// kept; the oldest roll entry goes once the narrative holds more.
+    pub fn add_roll_narrative(&mut self, roll: RollResult, purpose: &str) {
+        self.narrative_history.push(NarrativeEntry {
+            content: format!("{purpose}: {} = {}", roll.expression, roll.total),
+            entry_type: NarrativeType::DiceRoll,
+            game_time: self.game_time.clone(),
+            location: self.current_location.name.clone(),
+            roll: Some(roll),
+        });
+        let is_roll = |e: &NarrativeEntry| matches!(e.entry_type, NarrativeType::DiceRoll);
+        if self.narrative_history.iter().filter(|e| is_roll(e)).count() > Self::ROLL_LOG_LIMIT {
+            if let Some(oldest) = self.narrative_history.iter().position(is_roll) {
+                self.narrative_history.remove(oldest);
+            }
+        }
+    }
+
+    /// The last `count` narrative entries, newest first, leaving out dice rolls.
     pub fn recent_narrative(&self, count: usize) -> Vec<&NarrativeEntry> {
-        self.narrative_history.iter().rev().take(count).collect()
+        self.narrative_history
+            .iter()
+            .rev()
+            .filter(|e| !matches!(e.entry_type, NarrativeType::DiceRoll))
+            .take(count)
+            .collect()
+    }
+
+    /// A quest in the quest log, by name (ignoring case).
+    pub fn quest(&self, name: &str) -> Option<&Quest> {
+        let name = name.trim();
+        self.quests
+            .iter()
+            .find(|q| q.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Record a roll in the roll log, dropping the oldest past the limit.
+    pub fn log_roll(&mut self, roll: RollResult, purpose: impl Into<String>) {
+        let turn = self
+            .narrative_history
+            .iter()
+            .filter(|e| matches!(e.entry_type, NarrativeType::PlayerAction))
+            .count();
+        self.roll_log.push(LoggedRoll {
+            roll,
+            purpose: purpose.into(),
+            turn,
+        });
+        let excess = self.roll_log.len().saturating_sub(Self::ROLL_LOG_LIMIT);
+        self.roll_log.drain(..excess);
+    }
+
+    /// The last `count` rolls, oldest first.
+    pub fn recent_rolls(&self, count: usize) -> &[LoggedRoll] {
+        &self.roll_log[self.roll_log.len().saturating_sub(count)..]
     }
 }
 
@@ -2292,6 +3932,7 @@ pub fn create_sample_fighter(name: &str) -> Character {
     character
         .saving_throw_proficiencies
         .insert(Ability::Constitution);
+    character.grant_class_proficiencies(CharacterClass::Fighter);
 
     character
         .skill_proficiencies
@@ -2417,6 +4058,7 @@ mod tests {
             description: None,
             item_type: ItemType::Weapon,
             magical: false,
+            requires_attunement: false,
         };
         inventory.add_item(sword);
 
@@ -2432,6 +4074,7 @@ mod tests {
             description: None,
             item_type: ItemType::Weapon,
             magical: false,
+            requires_attunement: false,
         };
         inventory.add_item(sword2);
 
@@ -2446,6 +4089,7 @@ mod tests {
             description: None,
             item_type: ItemType::Potion,
             magical: true,
+            requires_attunement: false,
         };
         inventory.add_item(potion1);
         assert_eq!(inventory.items.len(), 3);
@@ -2458,6 +4102,7 @@ mod tests {
             description: None,
             item_type: ItemType::Potion,
             magical: true,
+            requires_attunement: false,
         };
         inventory.add_item(potion2);
         assert_eq!(inventory.items.len(), 3); // Still 3 - potions stacked
@@ -2475,6 +4120,7 @@ mod tests {
             description: None,
             item_type: ItemType::Potion,
             magical: true,
+            requires_attunement: false,
         };
         inventory.add_item(potion);
 
@@ -2491,19 +4137,195 @@ mod tests {
     #[test]
     fn test_inventory_gold() {
         let mut inventory = Inventory {
-            gold: 100.0,
+            purse: Purse::from_gp(100.0),
             ..Default::default()
         };
 
         assert!(inventory.adjust_gold(50.0).is_ok());
-        assert_eq!(inventory.gold, 150.0);
+        assert_eq!(inventory.purse.total_gp(), 150.0);
 
         assert!(inventory.adjust_gold(-100.0).is_ok());
-        assert_eq!(inventory.gold, 50.0);
+        assert_eq!(inventory.purse.total_gp(), 50.0);
 
         // Can't go negative
         assert!(inventory.adjust_gold(-100.0).is_err());
-        assert_eq!(inventory.gold, 50.0);
+        assert_eq!(inventory.purse.total_gp(), 50.0);
+    }
+
+    #[test]
+    fn test_gaining_gold_keeps_existing_coins() {
+        let mut inventory = Inventory {
+            purse: Purse {
+                pp: 2,
+                ep: 3,
+                sp: 4,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert!(inventory.adjust_gold(1.5).is_ok());
+        assert_eq!(
+            inventory.purse,
+            Purse {
+                pp: 2,
+                gp: 1,
+                ep: 3,
+                sp: 9,
+                cp: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_encumbrance_thresholds_str_10() {
+        assert_eq!(Encumbrance::for_load(50.0, 10), Encumbrance::Unencumbered);
+        assert_eq!(Encumbrance::for_load(50.5, 10), Encumbrance::Encumbered);
+        assert_eq!(Encumbrance::for_load(100.0, 10), Encumbrance::Encumbered);
+        assert_eq!(
+            Encumbrance::for_load(101.0, 10),
+            Encumbrance::HeavilyEncumbered
+        );
+        assert_eq!(
+            Encumbrance::for_load(150.0, 10),
+            Encumbrance::HeavilyEncumbered
+        );
+        assert_eq!(Encumbrance::for_load(151.0, 10), Encumbrance::OverCapacity);
+    }
+
+    #[test]
+    fn test_encumbrance_thresholds_str_20() {
+        let mut character = Character::new("Test");
+        character.ability_scores.strength = 20;
+        assert_eq!(character.carrying_capacity(), 300.0);
+
+        // Plate armor plus 35 lb of gear: exactly at the 100 lb threshold
+        character.equipment.armor =
+            Some(ArmorItem::new("Plate", ArmorType::Heavy, 18).with_weight(65.0));
+        character.inventory.add_item(Item {
+            name: "Iron Ingot".to_string(),
+            quantity: 35,
+            weight: 1.0,
+            value_gp: 0.1,
+            description: None,
+            item_type: ItemType::Adventuring,
+            magical: false,
+            requires_attunement: false,
+        });
+        assert_eq!(character.carried_weight(), 100.0);
+        assert_eq!(character.encumbrance_level(), Encumbrance::Unencumbered);
+        assert_eq!(character.encumbered_speed(), 30);
+
+        character
+            .inventory
+            .find_item_mut("Iron Ingot")
+            .unwrap()
+            .quantity = 36;
+        assert_eq!(character.encumbrance_level(), Encumbrance::Encumbered);
+        assert_eq!(character.encumbered_speed(), 20);
+
+        character
+            .inventory
+            .find_item_mut("Iron Ingot")
+            .unwrap()
+            .quantity = 136;
+        assert_eq!(
+            character.encumbrance_level(),
+            Encumbrance::HeavilyEncumbered
+        );
+        assert_eq!(character.encumbered_speed(), 10);
+
+        character
+            .inventory
+            .find_item_mut("Iron Ingot")
+            .unwrap()
+            .quantity = 236;
+        assert_eq!(character.encumbrance_level(), Encumbrance::OverCapacity);
+        assert_eq!(character.encumbered_speed(), 5);
+    }
+
+    #[test]
+    fn test_purse_makes_change() {
+        let mut purse = Purse {
+            cp: 3,
+            gp: 2,
+            ..Default::default()
+        };
+
+        // 15 cp: spend the 3 copper, then break a gold piece for the other 12
+        assert!(purse.pay(15).is_ok());
+        assert_eq!(
+            purse,
+            Purse {
+                cp: 8,
+                sp: 8,
+                gp: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(purse.total_cp(), 188);
+
+        // Breaking platinum gives gold back as change
+        let mut purse = Purse {
+            pp: 1,
+            ..Default::default()
+        };
+        assert!(purse.pay(250).is_ok());
+        assert_eq!(
+            purse,
+            Purse {
+                sp: 5,
+                gp: 7,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_purse_refuses_when_short() {
+        let mut purse = Purse {
+            sp: 9,
+            cp: 9,
+            ..Default::default()
+        };
+        assert!(purse.pay(100).is_err());
+        // A failed payment leaves the coins untouched
+        assert_eq!(purse.total_cp(), 99);
+        assert_eq!(purse.sp, 9);
+        assert_eq!(purse.cp, 9);
+    }
+
+    #[test]
+    fn test_purse_normalize() {
+        let mut purse = Purse {
+            cp: 125,
+            sp: 7,
+            ep: 3,
+            ..Default::default()
+        };
+        let total = purse.total_cp();
+        purse.normalize();
+
+        assert_eq!(
+            purse,
+            Purse {
+                cp: 5,
+                sp: 9,
+                ep: 1,
+                gp: 2,
+                pp: 0,
+            }
+        );
+        assert_eq!(purse.total_cp(), total);
+        assert_eq!(purse.to_string(), "2 gp, 1 ep, 9 sp, 5 cp");
+    }
+
+    #[test]
+    fn test_inventory_reads_legacy_gold() {
+        let inventory: Inventory = serde_json::from_str(r#"{"items": [], "gold": 12.5}"#).unwrap();
+        assert_eq!(inventory.purse.gp, 12);
+        assert_eq!(inventory.purse.sp, 5);
+        assert_eq!(inventory.purse.total_gp(), 12.5);
     }
 
     #[test]
@@ -2520,6 +4342,7 @@ mod tests {
             description: None,
             item_type: ItemType::Shield,
             magical: false,
+            requires_attunement: false,
         });
         // With shield but no armor: 10 + 3 + 2 = 15
         assert_eq!(character.current_ac(), 15);
@@ -2575,6 +4398,7 @@ mod tests {
             description: None,
             item_type: ItemType::Weapon,
             magical: false,
+            requires_attunement: false,
         };
         assert!(!sword.is_stackable());
 
@@ -2587,6 +4411,7 @@ mod tests {
             description: None,
             item_type: ItemType::Armor,
             magical: false,
+            requires_attunement: false,
         };
         assert!(!armor.is_stackable());
 
@@ -2599,6 +4424,7 @@ mod tests {
             description: None,
             item_type: ItemType::Potion,
             magical: true,
+            requires_attunement: false,
         };
         assert!(potion.is_stackable());
 
@@ -2611,6 +4437,7 @@ mod tests {
             description: None,
             item_type: ItemType::Adventuring,
             magical: false,
+            requires_attunement: false,
         };
         assert!(rope.is_stackable());
     }
@@ -2630,4 +4457,47 @@ mod tests {
             "A wandering adventurer seeking glory."
         );
     }
+
+    #[test]
+    fn test_party_member_lookup() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let companion_id = world.add_party_member(Character::new("Mira"));
+
+        assert_eq!(world.character(companion_id).unwrap().name, "Mira");
+        assert_eq!(world.character_by_name("mira").unwrap().id, companion_id);
+        assert_eq!(world.characters().count(), 2);
+        assert!(world.character(CharacterId::new()).is_none());
+    }
+
+    #[test]
+    fn test_rests_restore_party_members() {
+        let mut world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let companion_id = world.add_party_member(create_sample_fighter("Mira"));
+        let mira = world.character_mut(companion_id).unwrap();
+        mira.hit_points.current = 5;
+        mira.hit_dice.spend(DieType::D10);
+        mira.hit_dice.spend(DieType::D10);
+        mira.class_resources.second_wind_used = true;
+
+        world.short_rest();
+        let mira = world.character(companion_id).unwrap();
+        assert!(!mira.class_resources.second_wind_used);
+        assert_eq!(mira.hit_points.current, 5);
+
+        world.long_rest();
+        let mira = world.character(companion_id).unwrap();
+        assert_eq!(mira.hit_points.current, mira.hit_points.maximum);
+        assert_eq!(mira.hit_dice.total_remaining(), 3);
+    }
+
+    #[test]
+    fn test_world_without_party_deserializes() {
+        let world = GameWorld::new("Test", create_sample_fighter("Roland"));
+        let mut json = serde_json::to_value(&world).unwrap();
+        json.as_object_mut().unwrap().remove("party");
+
+        let loaded: GameWorld = serde_json::from_value(json).unwrap();
+        assert!(loaded.party.is_empty());
+        assert_eq!(loaded.player_character.name, "Roland");
+    }
 }
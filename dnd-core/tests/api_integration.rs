@@ -67,6 +67,8 @@ fn create_sample_wizard(name: &str) -> Character {
                 SlotInfo { total: 0, used: 0 },
             ],
         },
+        pact_slots: None,
+        prepared_since_rest: false,
     });
 
     // Set hit points
@@ -127,6 +129,8 @@ fn create_sample_cleric(name: &str) -> Character {
                 SlotInfo { total: 0, used: 0 },
             ],
         },
+        pact_slots: None,
+        prepared_since_rest: false,
     });
 
     // Set hit points
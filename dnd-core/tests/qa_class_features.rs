@@ -488,7 +488,7 @@ async fn test_cleric_healing_spell() {
 
         // Cure Wounds heals 1d8 + spellcasting modifier (minimum 1)
         // For a typical cleric, this should be 1-13 range
-        if amount_healed >= 1 && amount_healed <= 15 {
+        if (1..=15).contains(&amount_healed) {
             println!("  Healing amount {} is in expected range for Cure Wounds", amount_healed);
         } else {
             println!("  WARNING: Healing amount {} seems unusual for 1st level Cure Wounds", amount_healed);
@@ -0,0 +1,58 @@
+//! A seeded attack turn reports the effects it applied.
+//!
+//! The dice seed is process-wide, so this lives in its own test binary where
+//! no other test can roll between seeding and attacking.
+
+use dnd_core::dice::Advantage;
+use dnd_core::monsters::stats_for_cr;
+use dnd_core::rules::{Effect, Intent};
+use dnd_core::world::{create_sample_fighter, GameWorld, Lighting};
+use dnd_core::{MockDm, MockResponse, Response};
+
+#[test]
+fn test_attack_turn_response_carries_applied_effects() {
+    dnd_core::dice::set_seed(Some(42));
+
+    let mut world = GameWorld::new("Effects Test", create_sample_fighter("Roland"));
+    let mut rat = stats_for_cr(0.0).combatant("Rat");
+    rat.armor_class = 1;
+    rat.max_hp = 500;
+    rat.current_hp = 500;
+    let rat_id = rat.id;
+    let mut dm = MockDm::new(vec![
+        MockResponse::with_intents(
+            "The fight begins.",
+            vec![Intent::StartCombat {
+                combatants: vec![rat],
+            }],
+        ),
+        MockResponse::with_intents(
+            "You swing.",
+            vec![Intent::Attack {
+                attacker_id: world.player_character.id,
+                target_id: rat_id,
+                weapon_name: "Longsword".to_string(),
+                advantage: Advantage::Normal,
+                lighting: Lighting::Bright,
+            }],
+        ),
+    ]);
+    dm.process_input("I draw my sword", &mut world);
+    let response = Response::from(dm.process_input("I attack the rat", &mut world));
+
+    assert!(response.effects.iter().any(|e| matches!(
+        e,
+        Effect::AttackHit { target_id: Some(id), .. } if *id == rat_id
+    )));
+    let rolls: Vec<&str> = response
+        .effects
+        .iter()
+        .filter_map(|e| match e {
+            Effect::DiceRolled { purpose, .. } => Some(purpose.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert!(rolls.len() >= 2, "attack and damage rolls: {rolls:?}");
+
+    dnd_core::dice::set_seed(None);
+}
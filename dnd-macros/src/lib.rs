@@ -15,13 +15,26 @@
 //!     /// Optional purpose for the roll
 //!     #[tool(optional)]
 //!     purpose: Option<String>,
+//!     /// Roll twice and keep one
+//!     advantage: RollMode,
+//! }
+//!
+//! /// C-like enums become string schemas listing their variants
+//! #[derive(Tool)]
+//! enum RollMode {
+//!     Normal,
+//!     Advantage,
+//!     Disadvantage,
 //! }
 //! ```
 
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Field, Lit, Meta, Type};
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, DeriveInput, Lit, Meta, Token, Type};
 
 /// Derive macro for generating Tool implementations.
 ///
@@ -29,7 +42,29 @@ use syn::{parse_macro_input, DeriveInput, Field, Lit, Meta, Type};
 ///
 /// - `#[tool(name = "...")]` - Override the tool name (defaults to snake_case struct name)
 /// - `#[tool(optional)]` on fields - Mark field as optional in JSON schema
-/// - `#[tool(rename = "...")]` on fields - Override field name in schema
+/// - `#[tool(rename_all = "...")]` - Convert every field name with a serde rule such as `camelCase`, `PascalCase`, or `kebab-case`
+/// - `#[tool(rename = "...")]` on fields - Override field name in schema (takes precedence over `rename_all`)
+/// - `#[tool(enum("a", "b"))]` on fields - Restrict a string field to the listed values
+/// - `#[tool(min = 1, max = 9)]` on fields - Emit `minimum`/`maximum` for numbers
+/// - `#[tool(min_length = 1, max_length = 64)]` on fields - Emit `minLength`/`maxLength` for strings
+/// - `#[tool(rename = "...")]` on enum variants - Override the variant's name in schema
+/// - `#[tool(rename_all = "...")]` on enums - Convert every variant name with a serde rule
+///
+/// The type must also implement `serde::Deserialize`: the derive generates a
+/// `from_input(serde_json::Value)` that parses a tool call's input into it.
+/// Schema names follow `#[serde(rename)]` and `#[serde(rename_all)]`, and a
+/// `#[tool]` rename that disagrees with serde is a compile error, so the
+/// schema never advertises a name `from_input` would reject.
+///
+/// On a C-like enum, the derive generates an `input_schema()` returning a
+/// string schema with the variant names, so fields of that type get an `enum`
+/// constraint. Fields of other non-primitive types (including `Vec` and
+/// `Option` of them) inline the type's own `input_schema()`, so nested structs
+/// appear with their `properties` and `required`. Those types must also derive `Tool`
+/// (which implements `dnd_core::ToolSchema`); any other type is a compile error
+/// pointing at the field.
+/// `HashMap<String, T>` and `BTreeMap<String, T>` become objects whose
+/// `additionalProperties` is the schema of `T`.
 #[proc_macro_derive(Tool, attributes(tool))]
 pub fn derive_tool(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -42,13 +77,21 @@ fn expand_tool(input: DeriveInput) -> syn::Result<TokenStream2> {
     let struct_name = &input.ident;
 
     // Get tool name from attribute or default to snake_case
-    let tool_name = get_tool_name(&input)?;
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let serde_container = SerdeAttrs::parse(&input.attrs)?;
+    let tool_name = container
+        .name
+        .clone()
+        .unwrap_or_else(|| to_snake_case(&struct_name.to_string()));
 
     // Get description from doc comments
     let description = get_doc_comment(&input.attrs);
 
     // Parse fields
     let fields = match &input.data {
+        syn::Data::Enum(data) => {
+            return expand_enum(struct_name, data, &container, &serde_container)
+        }
         syn::Data::Struct(data) => match &data.fields {
             syn::Fields::Named(named) => &named.named,
             _ => {
@@ -61,7 +104,7 @@ fn expand_tool(input: DeriveInput) -> syn::Result<TokenStream2> {
         _ => {
             return Err(syn::Error::new_spanned(
                 input,
-                "Tool derive only supports structs",
+                "Tool derive only supports structs and C-like enums",
             ))
         }
     };
@@ -71,13 +114,46 @@ fn expand_tool(input: DeriveInput) -> syn::Result<TokenStream2> {
     let mut required_fields = Vec::new();
 
     for field in fields {
-        let _field_ident = field.ident.as_ref().unwrap();
-        let field_name_str = get_field_name(field)?;
+        let field_ident = field.ident.as_ref().unwrap();
+        let attrs = FieldAttrs::parse(&field.attrs)?;
+        let serde_attrs = SerdeAttrs::parse(&field.attrs)?;
+        let name = field_ident.unraw().to_string();
+
+        // The name serde deserializes, and the one the schema advertises;
+        // they have to agree or from_input rejects the schema's own input
+        let serde_name =
+            serde_attrs
+                .rename
+                .clone()
+                .unwrap_or_else(|| match serde_container.rename_all {
+                    Some(rule) => rule.apply_to_field(&name),
+                    None => name.clone(),
+                });
+        let field_name_str = attrs
+            .rename
+            .clone()
+            .or_else(|| serde_attrs.rename.clone())
+            .unwrap_or_else(|| match container.rename_all {
+                Some(rule) => rule.apply_to_field(&name),
+                None => serde_name.clone(),
+            });
+        if field_name_str != serde_name {
+            return Err(rename_mismatch(field_ident, &field_name_str, &serde_name));
+        }
         let field_desc = get_doc_comment(&field.attrs);
-        let is_optional = is_field_optional(field)?;
+        let is_optional = attrs.optional;
         let field_type = &field.ty;
 
-        let type_schema = type_to_schema(field_type)?;
+        let type_schema = match &attrs.enum_values {
+            Some(values) => quote! {
+                serde_json::json!({"type": "string", "enum": [#(#values),*]})
+            },
+            None => type_to_schema(field_type)?,
+        };
+
+        let constraint_tokens = attrs.constraints.iter().map(|(keyword, value)| {
+            quote! { property[#keyword] = serde_json::json!(#value); }
+        });
 
         let desc_token = if field_desc.is_empty() {
             quote! {}
@@ -89,6 +165,7 @@ fn expand_tool(input: DeriveInput) -> syn::Result<TokenStream2> {
             {
                 let mut property = #type_schema;
                 #desc_token
+                #(#constraint_tokens)*
                 properties.insert(#field_name_str.to_string(), property);
             }
         });
@@ -126,6 +203,11 @@ fn expand_tool(input: DeriveInput) -> syn::Result<TokenStream2> {
                 })
             }
 
+            /// Parse a tool call's input into this struct.
+            pub fn from_input(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+                serde_json::from_value(value)
+            }
+
             /// Create a Tool definition for use with the Claude API.
             pub fn as_tool() -> claude::Tool {
                 claude::Tool {
@@ -135,59 +217,309 @@ fn expand_tool(input: DeriveInput) -> syn::Result<TokenStream2> {
                 }
             }
         }
+
+        impl ::dnd_core::ToolSchema for #struct_name {
+            fn input_schema() -> serde_json::Value {
+                <#struct_name>::input_schema()
+            }
+        }
     })
 }
 
-fn get_tool_name(input: &DeriveInput) -> syn::Result<String> {
-    for attr in &input.attrs {
-        if attr.path().is_ident("tool") {
-            let meta = attr.parse_args::<Meta>()?;
-            if let Meta::NameValue(nv) = meta {
-                if nv.path.is_ident("name") {
-                    if let syn::Expr::Lit(expr_lit) = &nv.value {
-                        if let Lit::Str(s) = &expr_lit.lit {
-                            return Ok(s.value());
-                        }
+/// Generate a string schema listing the variants of a C-like enum.
+fn expand_enum(
+    enum_name: &syn::Ident,
+    data: &syn::DataEnum,
+    container: &ContainerAttrs,
+    serde_container: &SerdeAttrs,
+) -> syn::Result<TokenStream2> {
+    let mut variant_names = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "Tool derive only supports enums whose variants have no fields",
+            ));
+        }
+        let attrs = FieldAttrs::parse(&variant.attrs)?;
+        let serde_attrs = SerdeAttrs::parse(&variant.attrs)?;
+        let name = variant.ident.unraw().to_string();
+
+        // As with fields, the schema may only list names serde accepts
+        let serde_name =
+            serde_attrs
+                .rename
+                .clone()
+                .unwrap_or_else(|| match serde_container.rename_all {
+                    Some(rule) => rule.apply_to_variant(&name),
+                    None => name.clone(),
+                });
+        let variant_name =
+            attrs
+                .rename
+                .or(serde_attrs.rename)
+                .unwrap_or_else(|| match container.rename_all {
+                    Some(rule) => rule.apply_to_variant(&name),
+                    None => serde_name.clone(),
+                });
+        if variant_name != serde_name {
+            return Err(rename_mismatch(&variant.ident, &variant_name, &serde_name));
+        }
+        variant_names.push(variant_name);
+    }
+
+    Ok(quote! {
+        impl #enum_name {
+            /// Names of the variants, as they appear in the schema.
+            pub fn variant_names() -> &'static [&'static str] {
+                &[#(#variant_names),*]
+            }
+
+            /// Generate the JSON schema for this enum (a string restricted to the variant names).
+            pub fn input_schema() -> serde_json::Value {
+                serde_json::json!({
+                    "type": "string",
+                    "enum": Self::variant_names()
+                })
+            }
+
+            /// Parse a JSON string into a variant.
+            pub fn from_input(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+                serde_json::from_value(value)
+            }
+        }
+
+        impl ::dnd_core::ToolSchema for #enum_name {
+            fn input_schema() -> serde_json::Value {
+                <#enum_name>::input_schema()
+            }
+        }
+    })
+}
+
+/// Attributes from `#[tool(...)]` on the struct itself.
+#[derive(Default)]
+struct ContainerAttrs {
+    name: Option<String>,
+    rename_all: Option<RenameRule>,
+}
+
+impl ContainerAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs {
+            if !attr.path().is_ident("tool") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    result.name = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.path.is_ident("rename_all") {
+                    let rule = meta.value()?.parse::<syn::LitStr>()?;
+                    result.rename_all = Some(RenameRule::parse(&rule)?);
+                } else {
+                    return Err(meta.error("unknown tool attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+/// Case conversion applied by `rename_all`, with serde's rule names and semantics.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(lit: &syn::LitStr) -> syn::Result<Self> {
+        match lit.value().as_str() {
+            "lowercase" => Ok(Self::Lower),
+            "UPPERCASE" => Ok(Self::Upper),
+            "PascalCase" => Ok(Self::Pascal),
+            "camelCase" => Ok(Self::Camel),
+            "snake_case" => Ok(Self::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnake),
+            "kebab-case" => Ok(Self::Kebab),
+            "SCREAMING-KEBAB-CASE" => Ok(Self::ScreamingKebab),
+            _ => Err(syn::Error::new_spanned(
+                lit,
+                "rename_all must be one of serde's rules, such as \"camelCase\", \"PascalCase\", or \"kebab-case\"",
+            )),
+        }
+    }
+
+    /// Rename a PascalCase enum variant.
+    fn apply_to_variant(self, name: &str) -> String {
+        match self {
+            Self::Pascal => name.to_string(),
+            Self::Lower => name.to_ascii_lowercase(),
+            Self::Upper => name.to_ascii_uppercase(),
+            Self::Camel => {
+                let mut chars = name.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => String::new(),
+                }
+            }
+            Self::Snake | Self::ScreamingSnake | Self::Kebab | Self::ScreamingKebab => {
+                let snake = to_snake_case(name);
+                let snake = match self {
+                    Self::ScreamingSnake | Self::ScreamingKebab => snake.to_ascii_uppercase(),
+                    _ => snake,
+                };
+                match self {
+                    Self::Kebab | Self::ScreamingKebab => snake.replace('_', "-"),
+                    _ => snake,
+                }
+            }
+        }
+    }
+
+    /// Rename a snake_case field.
+    fn apply_to_field(self, name: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => name.to_string(),
+            Self::Upper | Self::ScreamingSnake => name.to_ascii_uppercase(),
+            Self::Kebab => name.replace('_', "-"),
+            Self::ScreamingKebab => name.to_ascii_uppercase().replace('_', "-"),
+            Self::Camel | Self::Pascal => {
+                let mut result = String::new();
+                let mut capitalize = matches!(self, Self::Pascal);
+                for c in name.chars() {
+                    if c == '_' {
+                        capitalize = !result.is_empty();
+                    } else if capitalize {
+                        result.push(c.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        result.push(c);
                     }
                 }
+                result
             }
         }
     }
+}
 
-    // Default: convert struct name to snake_case
-    let name = input.ident.to_string();
-    Ok(to_snake_case(&name))
+/// Names serde deserializes with, read from `#[serde(...)]` so the schema can
+/// be checked against them.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    rename_all: Option<RenameRule>,
 }
 
-fn get_field_name(field: &Field) -> syn::Result<String> {
-    for attr in &field.attrs {
-        if attr.path().is_ident("tool") {
-            if let Ok(Meta::NameValue(nv)) = attr.parse_args::<Meta>() {
-                if nv.path.is_ident("rename") {
-                    if let syn::Expr::Lit(expr_lit) = &nv.value {
-                        if let Lit::Str(s) = &expr_lit.lit {
-                            return Ok(s.value());
-                        }
+impl SerdeAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            let metas = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+            for meta in metas {
+                if meta.path().is_ident("rename") {
+                    if let Some(name) = deserialize_name(&meta)? {
+                        result.rename = Some(name.value());
+                    }
+                } else if meta.path().is_ident("rename_all") {
+                    if let Some(rule) = deserialize_name(&meta)? {
+                        result.rename_all = Some(RenameRule::parse(&rule)?);
                     }
                 }
             }
         }
+        Ok(result)
     }
+}
 
-    Ok(field.ident.as_ref().unwrap().to_string())
+/// The deserializing half of `key = "..."` or `key(deserialize = "...")`.
+fn deserialize_name(meta: &Meta) -> syn::Result<Option<syn::LitStr>> {
+    match meta {
+        Meta::NameValue(nv) => match &nv.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(s), ..
+            }) => Ok(Some(s.clone())),
+            other => Err(syn::Error::new_spanned(other, "expected a string literal")),
+        },
+        Meta::List(list) => {
+            let nested = list.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+            match nested.iter().find(|m| m.path().is_ident("deserialize")) {
+                Some(inner) => deserialize_name(inner),
+                None => Ok(None),
+            }
+        }
+        Meta::Path(_) => Ok(None),
+    }
 }
 
-fn is_field_optional(field: &Field) -> syn::Result<bool> {
-    for attr in &field.attrs {
-        if attr.path().is_ident("tool") {
-            if let Ok(Meta::Path(path)) = attr.parse_args::<Meta>() {
-                if path.is_ident("optional") {
-                    return Ok(true);
-                }
+/// The error for a schema name serde wouldn't accept.
+fn rename_mismatch(span: impl quote::ToTokens, schema: &str, serde: &str) -> syn::Error {
+    syn::Error::new_spanned(
+        span,
+        format!(
+            "the tool schema calls this `{schema}` but serde expects `{serde}`; \
+             add #[serde(rename = \"{schema}\")] or a matching #[serde(rename_all)] \
+             so from_input accepts the schema's names"
+        ),
+    )
+}
+
+/// Field attributes that map directly onto JSON Schema keywords.
+const CONSTRAINTS: &[(&str, &str)] = &[
+    ("min", "minimum"),
+    ("max", "maximum"),
+    ("min_length", "minLength"),
+    ("max_length", "maxLength"),
+];
+
+/// Attributes from `#[tool(...)]` on a field or enum variant.
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    optional: bool,
+    enum_values: Option<Vec<String>>,
+    /// JSON Schema keyword/value pairs such as `minimum` or `minLength`
+    constraints: Vec<(&'static str, syn::Expr)>,
+}
+
+impl FieldAttrs {
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs {
+            if !attr.path().is_ident("tool") {
+                continue;
             }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("optional") {
+                    result.optional = true;
+                } else if meta.path.is_ident("rename") {
+                    result.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.path.is_ident("enum") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let list = Punctuated::<syn::LitStr, Token![,]>::parse_terminated(&content)?;
+                    result.enum_values = Some(list.iter().map(|v| v.value()).collect());
+                } else {
+                    let keyword = CONSTRAINTS
+                        .iter()
+                        .find(|(name, _)| meta.path.is_ident(name))
+                        .map(|(_, keyword)| *keyword)
+                        .ok_or_else(|| meta.error("unknown tool attribute"))?;
+                    result.constraints.push((keyword, meta.value()?.parse()?));
+                }
+                Ok(())
+            })?;
         }
+        Ok(result)
     }
-    Ok(false)
 }
 
 fn get_doc_comment(attrs: &[syn::Attribute]) -> String {
@@ -215,6 +547,15 @@ fn is_option_type(ty: &Type) -> bool {
     false
 }
 
+fn is_string_type(ty: &Type) -> bool {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            return segment.ident == "String";
+        }
+    }
+    false
+}
+
 fn type_to_schema(ty: &Type) -> syn::Result<TokenStream2> {
     Ok(match ty {
         Type::Path(type_path) => {
@@ -254,10 +595,40 @@ fn type_to_schema(ty: &Type) -> syn::Result<TokenStream2> {
                         }
                         quote! { serde_json::json!({"type": "array"}) }
                     }
-                    _ => {
-                        // Unknown type - use object
+                    "HashMap" | "BTreeMap" => {
+                        // String-keyed maps are open objects; the value type
+                        // constrains every property
+                        if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                            let mut types = args.args.iter().filter_map(|arg| match arg {
+                                syn::GenericArgument::Type(ty) => Some(ty),
+                                _ => None,
+                            });
+                            if let (Some(key), Some(value)) = (types.next(), types.next()) {
+                                if !is_string_type(key) {
+                                    return Err(syn::Error::new_spanned(
+                                        key,
+                                        "Tool derive only supports maps with String keys",
+                                    ));
+                                }
+                                let value_schema = type_to_schema(value)?;
+                                return Ok(quote! {
+                                    serde_json::json!({
+                                        "type": "object",
+                                        "additionalProperties": #value_schema
+                                    })
+                                });
+                            }
+                        }
                         quote! { serde_json::json!({"type": "object"}) }
                     }
+                    _ => {
+                        // Other types must derive Tool themselves, so nested structs
+                        // and C-like enums inline their own schema; the trait bound
+                        // names the field's type if it doesn't
+                        quote_spanned! {ty.span()=>
+                            <#type_path as ::dnd_core::ToolSchema>::input_schema()
+                        }
+                    }
                 }
             } else {
                 quote! { serde_json::json!({}) }